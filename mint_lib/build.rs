@@ -1,3 +1,4 @@
 fn main() {
+    println!("cargo:rerun-if-env-changed=MINT_UPDATE_REPO");
     built::write_built_file().expect("Failed to acquire build-time information");
 }
@@ -1,5 +1,6 @@
 pub mod error;
 pub mod mod_info;
+pub mod paths;
 pub mod update;
 
 use std::{
@@ -20,9 +21,11 @@ pub mod built_info {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DRGInstallationType {
     Steam,
+    /// Microsoft Store/Game Pass.
     Xbox,
 }
 
@@ -74,6 +77,13 @@ impl DRGInstallationType {
             Self::Xbox => "d3d9.dll",
         }
     }
+    /// Name of the store the installation was bought from.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Steam => "Steam",
+            Self::Xbox => "Microsoft Store",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -83,23 +93,68 @@ pub struct DRGInstallation {
 }
 
 impl DRGInstallation {
-    /// Returns first DRG installation found. Only supports Steam version
-    /// TODO locate Xbox version
+    /// Returns first DRG installation found, see [`Self::find_all`].
     pub fn find() -> Option<Self> {
-        steamlocate::SteamDir::locate()
-            .ok()
-            .and_then(|steamdir| {
-                steamdir
-                    .find_app(548430)
-                    .ok()
-                    .flatten()
-                    .map(|(app, library)| {
+        Self::find_all().into_iter().next()
+    }
+    /// Every DRG installation found: the Steam version in each Steam library, on any drive, then
+    /// the Xbox app version in its default install locations.
+    pub fn find_all() -> Vec<Self> {
+        let mut paks = vec![];
+        if let Ok(libraries) = steamlocate::SteamDir::locate().and_then(|s| s.libraries()) {
+            for library in libraries.flatten() {
+                if let Some(Ok(app)) = library.app(548430) {
+                    paks.push(
                         library
                             .resolve_app_dir(&app)
-                            .join("FSD/Content/Paks/FSD-WindowsNoEditor.pak")
-                    })
-            })
-            .and_then(|path| Self::from_pak_path(path).ok())
+                            .join("FSD/Content/Paks")
+                            .join(DRGInstallationType::Steam.main_pak_name()),
+                    );
+                }
+            }
+        }
+        paks.extend(xbox_game_dirs().into_iter().map(|dir| {
+            dir.join("FSD/Content/Paks")
+                .join(DRGInstallationType::Xbox.main_pak_name())
+        }));
+        paks.into_iter()
+            .filter(|pak| pak.is_file())
+            .filter_map(|pak| Self::from_pak_path(pak).ok())
+            .collect()
+    }
+    /// Main paks likely meant when `near` is not one: those of installations in the directories
+    /// around it, in every Steam library and in the Xbox app's default install locations. Only
+    /// paks that exist are returned, each once.
+    pub fn candidate_paks(near: &Path) -> Vec<PathBuf> {
+        const GAME_DIR: &str = "Deep Rock Galactic";
+
+        let mut game_dirs = vec![];
+        for dir in near.ancestors().filter(|d| d.is_dir()) {
+            game_dirs.push(dir.to_path_buf());
+            game_dirs.push(dir.join(GAME_DIR));
+            // the Xbox app installs into `Deep Rock Galactic/Content`
+            game_dirs.push(dir.join("Content"));
+            game_dirs.push(dir.join(GAME_DIR).join("Content"));
+        }
+        if let Ok(libraries) = steamlocate::SteamDir::locate().and_then(|s| s.libraries()) {
+            for library in libraries.flatten() {
+                game_dirs.push(library.path().join("steamapps/common").join(GAME_DIR));
+            }
+        }
+        game_dirs.extend(xbox_game_dirs());
+
+        let mut paks: Vec<PathBuf> = vec![];
+        for game_dir in game_dirs {
+            for installation_type in [DRGInstallationType::Steam, DRGInstallationType::Xbox] {
+                let pak = game_dir
+                    .join("FSD/Content/Paks")
+                    .join(installation_type.main_pak_name());
+                if pak.is_file() && pak != near && !paks.contains(&pak) {
+                    paks.push(pak);
+                }
+            }
+        }
+        paks
     }
     pub fn from_pak_path<P: AsRef<Path>>(pak: P) -> Result<Self> {
         let root = pak
@@ -155,11 +210,34 @@ impl DRGInstallation {
     }
 }
 
+/// Where the Xbox app installs the game by default, in `XboxGames` on each drive.
+fn xbox_game_dirs() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        ('C'..='Z')
+            .map(|drive| PathBuf::from(format!("{drive}:\\XboxGames\\Deep Rock Galactic\\Content")))
+            .collect()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        vec![]
+    }
+}
+
 pub fn setup_logging<P: AsRef<Path>>(
     log_path: P,
     target: &str,
 ) -> Result<tracing_appender::non_blocking::WorkerGuard> {
-    use tracing::metadata::LevelFilter;
+    setup_logging_with_level(log_path, target, tracing::metadata::LevelFilter::INFO)
+}
+
+/// Like [`setup_logging`], but with a custom default level for the stderr log. `RUST_LOG` still
+/// takes precedence. The log file always receives debug output.
+pub fn setup_logging_with_level<P: AsRef<Path>>(
+    log_path: P,
+    target: &str,
+    stderr_level: tracing::metadata::LevelFilter,
+) -> Result<tracing_appender::non_blocking::WorkerGuard> {
     use tracing_subscriber::prelude::*;
     use tracing_subscriber::{
         EnvFilter,
@@ -198,7 +276,7 @@ pub fn setup_logging<P: AsRef<Path>>(
         .with_span_events(FmtSpan::CLOSE)
         .with_filter(
             EnvFilter::builder()
-                .with_default_directive(LevelFilter::INFO.into())
+                .with_default_directive(stderr_level.into())
                 .from_env_lossy(),
         );
     let subscriber = tracing_subscriber::registry()
@@ -213,3 +291,28 @@ pub fn setup_logging<P: AsRef<Path>>(
 
     Ok(guard)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_paks_near() {
+        let dir = tempfile::tempdir().unwrap();
+        let paks = dir.path().join("Deep Rock Galactic/FSD/Content/Paks");
+        fs::create_dir_all(&paks).unwrap();
+        fs::write(paks.join("FSD-WindowsNoEditor.pak"), b"").unwrap();
+
+        // the game directory instead of the pak
+        let candidates = DRGInstallation::candidate_paks(&dir.path().join("Deep Rock Galactic"));
+        assert!(candidates.contains(&paks.join("FSD-WindowsNoEditor.pak")));
+
+        // another pak next to it
+        let candidates = DRGInstallation::candidate_paks(&paks.join("pakchunk1.pak"));
+        assert!(candidates.contains(&paks.join("FSD-WindowsNoEditor.pak")));
+
+        // the pak itself is no suggestion
+        let candidates = DRGInstallation::candidate_paks(&paks.join("FSD-WindowsNoEditor.pak"));
+        assert!(!candidates.contains(&paks.join("FSD-WindowsNoEditor.pak")));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -45,8 +45,25 @@ pub struct ModInfo {
     pub resolution: ModResolution,
     pub suggested_require: bool,
     pub suggested_dependencies: Vec<ModSpecification>, // ModResponse
-    pub modio_tags: Option<ModioTags>,                 // only available for mods from mod.io
-    pub modio_id: Option<u32>,                         // only available for mods from mod.io
+    /// Versions of `suggested_dependencies` the mod requires, if the provider states them.
+    pub dependency_constraints: BTreeMap<ModSpecification, VersionConstraint>,
+    pub modio_tags: Option<ModioTags>, // only available for mods from mod.io
+    pub modio_id: Option<u32>,         // only available for mods from mod.io
+    /// Tags set by the user for mods from providers without tags.
+    pub local_tags: BTreeSet<String>,
+}
+
+impl ModInfo {
+    /// The version this mod resolves to, see [`ModSpecification::version_id`].
+    pub fn version_id(&self) -> Option<u32> {
+        modio_ids(&self.resolution.url.0).2
+    }
+
+    /// The spec pinned to the version this mod resolves to, among [`Self::versions`].
+    pub fn resolved_version(&self) -> Option<&ModSpecification> {
+        let id = self.version_id()?;
+        self.versions.iter().find(|v| v.version_id() == Some(id))
+    }
 }
 
 /// Returned from ModProvider
@@ -56,21 +73,154 @@ pub enum ModResponse {
     Resolve(ModInfo),
 }
 
-/// Points to a mod, optionally a specific version
+/// Points to a mod, optionally a specific version. The URL is kept in its canonical form so the
+/// same mod written differently compares equal; see [`canonical_url`].
 #[derive(
     Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
 )]
+#[serde(from = "RawModSpecification")]
 pub struct ModSpecification {
     pub url: String,
 }
 
+/// Specs as stored by earlier versions, canonicalized on load.
+#[derive(Deserialize)]
+struct RawModSpecification {
+    url: String,
+}
+
+impl From<RawModSpecification> for ModSpecification {
+    fn from(raw: RawModSpecification) -> Self {
+        Self::new(raw.url)
+    }
+}
+
 impl ModSpecification {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url: canonical_url(&url),
+        }
+    }
+    /// Whether both point to the same mod, regardless of version.
+    pub fn same_mod(&self, other: &ModSpecification) -> bool {
+        match (modio_ids(&self.url), modio_ids(&other.url)) {
+            // mod IDs stay the same when a mod is renamed, so names only decide without them
+            ((_, Some(a), _), (_, Some(b), _)) => a == b,
+            ((Some(a), _, _), (Some(b), _, _)) => a == b,
+            // TODO this hack works surprisingly well but is still a complete hack and should be
+            // replaced
+            _ => self.url.starts_with(&other.url) || other.url.starts_with(&self.url),
+        }
     }
+    /// Whether `self` can stand in for the dependency `other`: the same mod, at the exact version
+    /// if `other` is pinned to one.
     pub fn satisfies_dependency(&self, other: &ModSpecification) -> bool {
-        // TODO this hack works surprisingly well but is still a complete hack and should be replaced
-        self.url.starts_with(&other.url) || other.url.starts_with(&self.url)
+        self.same_mod(other)
+            && other
+                .version_id()
+                .is_none_or(|v| self.satisfies_version(VersionConstraint::Exact(v)))
+    }
+    /// Whether the version `self` is pinned to is allowed by `constraint`. Unpinned specs follow
+    /// the latest version, which is assumed to be allowed.
+    pub fn satisfies_version(&self, constraint: VersionConstraint) -> bool {
+        self.version_id().is_none_or(|v| constraint.allows(v))
+    }
+    /// The version `self` is pinned to, for providers with numbered versions. For mod.io this is
+    /// the modfile ID, which grows with each upload.
+    pub fn version_id(&self) -> Option<u32> {
+        modio_ids(&self.url).2
+    }
+}
+
+/// Versions of a dependency a mod works with, as stated by providers which support it. Versions
+/// are compared by [`ModSpecification::version_id`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionConstraint {
+    /// This version or a newer one.
+    AtLeast(u32),
+    /// Only this version.
+    Exact(u32),
+}
+
+impl VersionConstraint {
+    pub fn allows(self, version: u32) -> bool {
+        match self {
+            VersionConstraint::AtLeast(min) => version >= min,
+            VersionConstraint::Exact(v) => version == v,
+        }
+    }
+}
+
+impl std::str::FromStr for VersionConstraint {
+    type Err = std::num::ParseIntError;
+
+    /// Parses `>=123` and `=123`, or a plain `123` meaning exactly that version.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(v) = s.strip_prefix(">=") {
+            Ok(VersionConstraint::AtLeast(v.trim().parse()?))
+        } else {
+            Ok(VersionConstraint::Exact(
+                s.strip_prefix('=').unwrap_or(s).trim().parse()?,
+            ))
+        }
+    }
+}
+
+impl std::fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionConstraint::AtLeast(v) => write!(f, ">={v}"),
+            VersionConstraint::Exact(v) => write!(f, "={v}"),
+        }
+    }
+}
+
+const MODIO_MOD_URL: &str = "https://mod.io/g/drg/m/";
+
+/// Rewrite the equivalent forms of a mod.io mod URL to the one mint itself produces: `https`, no
+/// `www.`, no trailing slash, no `@version` suffix on the name and no page anchors like
+/// `#comments`, keeping only the `#mod_id/modfile_id` mint uses to pin versions. Other URLs and
+/// paths only have surrounding whitespace removed.
+pub fn canonical_url(url: &str) -> String {
+    let url = url.trim();
+    let Some(path) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .map(|rest| rest.strip_prefix("www.").unwrap_or(rest))
+        .and_then(|rest| rest.strip_prefix(&MODIO_MOD_URL["https://".len()..]))
+    else {
+        return url.to_string();
+    };
+    let (name, anchor) = match path.split_once('#') {
+        Some((name, anchor)) => (name, Some(anchor)),
+        None => (path, None),
+    };
+    let name = name.trim_end_matches('/');
+    let name = name.split_once('@').map_or(name, |(name, _version)| name);
+    // preview links and anything else unexpected are left for the provider to reject
+    if name.is_empty() || name.contains(['/', '?']) {
+        return url.to_string();
+    }
+    let is_id = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match anchor.filter(|a| a.split('/').count() <= 2 && a.split('/').all(is_id)) {
+        Some(ids) => format!("{MODIO_MOD_URL}{}#{ids}", name.to_ascii_lowercase()),
+        None => format!("{MODIO_MOD_URL}{}", name.to_ascii_lowercase()),
+    }
+}
+
+/// Name ID, mod ID and modfile ID of a canonical mod.io URL, all `None` for other URLs.
+fn modio_ids(url: &str) -> (Option<&str>, Option<u32>, Option<u32>) {
+    let Some(path) = url.strip_prefix(MODIO_MOD_URL) else {
+        return (None, None, None);
+    };
+    match path.split_once('#') {
+        Some((name, ids)) => {
+            let mut ids = ids.split('/').map(|id| id.parse().ok());
+            (Some(name), ids.next().flatten(), ids.next().flatten())
+        }
+        None => (Some(path), None, None),
     }
 }
 
@@ -166,3 +316,80 @@ impl Meta {
             .join(";")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_url() {
+        for url in [
+            "https://mod.io/g/drg/m/build-inspector",
+            "https://mod.io/g/drg/m/build-inspector/",
+            "http://mod.io/g/drg/m/build-inspector",
+            "https://www.mod.io/g/drg/m/Build-Inspector",
+            "https://mod.io/g/drg/m/build-inspector@1.2",
+            "https://mod.io/g/drg/m/build-inspector/#comments",
+            " https://mod.io/g/drg/m/build-inspector\n",
+        ] {
+            assert_eq!(
+                canonical_url(url),
+                "https://mod.io/g/drg/m/build-inspector",
+                "{url}"
+            );
+        }
+        assert_eq!(
+            canonical_url("https://mod.io/g/drg/m/build-inspector/#2101319/3169221"),
+            "https://mod.io/g/drg/m/build-inspector#2101319/3169221"
+        );
+        for url in [
+            "https://mod.io/g/drg/m/build-inspector?preview=abc",
+            "http://example.com/mod.pak",
+            "C:\\mods\\mod.pak",
+        ] {
+            assert_eq!(canonical_url(url), url);
+        }
+    }
+
+    #[test]
+    fn test_satisfies_dependency() {
+        let spec = |url: &str| ModSpecification::new(url.to_string());
+        assert!(
+            spec("https://mod.io/g/drg/m/old-name#123/456")
+                .satisfies_dependency(&spec("https://mod.io/g/drg/m/new-name#123"))
+        );
+        assert!(
+            spec("http://mod.io/g/drg/m/foo/")
+                .satisfies_dependency(&spec("https://mod.io/g/drg/m/foo#123"))
+        );
+        assert!(
+            !spec("https://mod.io/g/drg/m/foo")
+                .satisfies_dependency(&spec("https://mod.io/g/drg/m/foo-bar"))
+        );
+        assert!(
+            !spec("https://mod.io/g/drg/m/foo#1")
+                .satisfies_dependency(&spec("https://mod.io/g/drg/m/foo#2"))
+        );
+    }
+
+    #[test]
+    fn test_version_constraints() {
+        let spec = |url: &str| ModSpecification::new(url.to_string());
+        let pinned = spec("https://mod.io/g/drg/m/foo#1/20");
+        assert!(pinned.satisfies_dependency(&spec("https://mod.io/g/drg/m/foo#1")));
+        assert!(pinned.satisfies_dependency(&spec("https://mod.io/g/drg/m/foo#1/20")));
+        assert!(!pinned.satisfies_dependency(&spec("https://mod.io/g/drg/m/foo#1/21")));
+        assert!(pinned.same_mod(&spec("https://mod.io/g/drg/m/foo#1/21")));
+
+        assert!(pinned.satisfies_version(VersionConstraint::AtLeast(20)));
+        assert!(!pinned.satisfies_version(VersionConstraint::AtLeast(21)));
+        assert!(!pinned.satisfies_version(VersionConstraint::Exact(19)));
+        assert!(spec("https://mod.io/g/drg/m/foo").satisfies_version(VersionConstraint::Exact(19)));
+
+        for constraint in [VersionConstraint::AtLeast(20), VersionConstraint::Exact(20)] {
+            assert_eq!(constraint.to_string().parse(), Ok(constraint));
+        }
+        assert_eq!(" 20 ".parse(), Ok(VersionConstraint::Exact(20)));
+        assert!("> 20".parse::<VersionConstraint>().is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! Paths which keep working past Windows' `MAX_PATH` limit. Game directories inside deep library
+//! folders, long or non-ASCII user names and nested mod archives can push the files mint writes
+//! beyond 260 characters, where most Windows APIs fail unless the path uses the extended-length
+//! `\\?\` prefix.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// `path` in a form which works regardless of `MAX_PATH`: long absolute paths get the
+/// extended-length prefix. Short paths, and all paths on other platforms, are returned unchanged.
+///
+/// Extended-length paths are not normalized by Windows, so the path is made absolute first, which
+/// also turns `/` into `\` and resolves `..`. Use it right where a file is opened or created
+/// rather than on a directory paths are later joined to.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStrExt;
+
+        /// Directory creation is limited to `MAX_PATH` minus room for an 8.3 file name.
+        const THRESHOLD: usize = 248;
+
+        if path.as_os_str().encode_wide().count() >= THRESHOLD
+            && let Some(extended) = std::path::absolute(path)
+                .ok()
+                .and_then(|p| p.to_str().and_then(extended_length))
+        {
+            return Cow::Owned(PathBuf::from(extended));
+        }
+    }
+    Cow::Borrowed(path)
+}
+
+/// The extended-length form of an absolute Windows path, or `None` if it already is one or is not
+/// absolute.
+fn extended_length(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        None
+    } else if let Some(unc) = path.strip_prefix(r"\\") {
+        Some(format!(r"\\?\UNC\{unc}"))
+    } else if let [drive, b':', b'\\', ..] = path.as_bytes()
+        && drive.is_ascii_alphabetic()
+    {
+        Some(format!(r"\\?\{path}"))
+    } else {
+        None
+    }
+}
+
+/// Like [`PathBuf::from`] but through [`long_path`].
+pub fn long_path_buf<P: AsRef<Path>>(path: P) -> PathBuf {
+    long_path(path.as_ref()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_length() {
+        assert_eq!(
+            extended_length(r"C:\Users\Пользователь\mint").as_deref(),
+            Some(r"\\?\C:\Users\Пользователь\mint")
+        );
+        assert_eq!(
+            extended_length(r"\\server\share\DRG").as_deref(),
+            Some(r"\\?\UNC\server\share\DRG")
+        );
+        assert_eq!(extended_length(r"\\?\C:\already"), None);
+        assert_eq!(extended_length(r"relative\path"), None);
+        assert_eq!(extended_length("/home/user"), None);
+    }
+
+    #[test]
+    fn test_short_paths_unchanged() {
+        let path = Path::new("Пользователь/模组/mod.pak");
+        assert_eq!(long_path(path), Cow::Borrowed(path));
+    }
+
+    #[test]
+    fn test_long_non_ascii_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut path = dir.path().to_path_buf();
+        while path.as_os_str().len() < 300 {
+            path.push("Пользователь_模组");
+        }
+        let file = path.join("mods_P.pak");
+
+        fs_err::create_dir_all(long_path(&path)).unwrap();
+        fs_err::write(long_path(&file), b"pak").unwrap();
+        assert_eq!(fs_err::read(long_path(&file)).unwrap(), b"pak");
+    }
+}
@@ -1,22 +1,103 @@
+use crate::built_info;
 use crate::error::GenericError;
 use crate::error::ResultExt;
 
-pub const GITHUB_RELEASE_URL: &str = "https://api.github.com/repos/trumank/mint/releases/latest";
-pub const GITHUB_REQ_USER_AGENT: &str = "trumank/mint";
+/// `owner/repo` of the GitHub repository releases are published to. Taken from the package
+/// `repository` metadata at build time so forks only need to change it in Cargo.toml, and can be
+/// overridden by setting `MINT_UPDATE_REPO` when building.
+pub fn update_repo() -> &'static str {
+    option_env!("MINT_UPDATE_REPO").unwrap_or_else(|| {
+        built_info::PKG_REPOSITORY
+            .trim_start_matches("https://github.com/")
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+    })
+}
+
+/// URL of the latest release document of [`update_repo`].
+pub fn default_release_url() -> String {
+    format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        update_repo()
+    )
+}
+
+/// URL to download a release asset from the latest release of [`update_repo`].
+pub fn default_asset_url(asset_name: &str) -> String {
+    format!(
+        "https://github.com/{}/releases/latest/download/{asset_name}",
+        update_repo()
+    )
+}
 
 #[derive(Debug, serde::Deserialize)]
 pub struct GitHubRelease {
     pub html_url: String,
     pub tag_name: String,
     pub body: String,
+    #[serde(default)]
+    pub assets: Vec<GitHubReleaseAsset>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct GitHubReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+impl GitHubRelease {
+    pub fn asset_url(&self, name: &str) -> Option<&str> {
+        self.assets
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.browser_download_url.as_str())
+    }
+
+    /// URL of the SHA-256 checksum published next to the asset `name` as `<name>.sha256`.
+    pub fn checksum_url(&self, name: &str) -> Option<&str> {
+        self.asset_url(&format!("{name}.sha256"))
+    }
+}
+
+/// Whether `url` uses https, which release documents and the assets they point to must.
+pub fn is_https(url: &str) -> bool {
+    url.get(..8)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"))
+}
+
+/// Resolve a user supplied update endpoint, which is either the `owner/repo` of a GitHub
+/// repository or the full https URL of a GitHub-style "latest release" JSON document.
+pub fn release_url(endpoint: Option<&str>) -> Result<String, GenericError> {
+    match endpoint.map(str::trim).filter(|e| !e.is_empty()) {
+        Some(url) if is_https(url) => Ok(url.to_string()),
+        Some(url) if url.contains("://") => Err(GenericError {
+            msg: format!("update source {url} does not use https"),
+        }),
+        Some(repo) => Ok(format!(
+            "https://api.github.com/repos/{repo}/releases/latest"
+        )),
+        None => Ok(default_release_url()),
+    }
+}
+
+/// The hex SHA-256 digest in a checksum file, which holds either just the digest or a line in the
+/// `<digest>  <file name>` format of `sha256sum`.
+pub fn parse_checksum(text: &str) -> Option<String> {
+    let digest = text.split_whitespace().next()?;
+    (digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| digest.to_ascii_lowercase())
 }
 
 pub async fn get_latest_release() -> Result<GitHubRelease, GenericError> {
+    get_latest_release_from(&default_release_url()).await
+}
+
+pub async fn get_latest_release_from(url: &str) -> Result<GitHubRelease, GenericError> {
     reqwest::Client::builder()
-        .user_agent(GITHUB_REQ_USER_AGENT)
+        .user_agent(update_repo())
         .build()
         .generic("failed to construct reqwest client".to_string())?
-        .get(GITHUB_RELEASE_URL)
+        .get(url)
         .send()
         .await
         .generic("check self update request failed".to_string())?
@@ -24,3 +105,34 @@ pub async fn get_latest_release() -> Result<GitHubRelease, GenericError> {
         .await
         .generic("check self update response is error".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_url() {
+        assert_eq!(
+            release_url(Some("someone/mint")).unwrap(),
+            "https://api.github.com/repos/someone/mint/releases/latest"
+        );
+        assert_eq!(
+            release_url(Some(" HTTPS://example.com/latest.json ")).unwrap(),
+            "HTTPS://example.com/latest.json"
+        );
+        assert!(release_url(Some("http://example.com/latest.json")).is_err());
+        assert_eq!(release_url(Some("")).unwrap(), default_release_url());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let digest = "ab".repeat(32);
+        assert_eq!(parse_checksum(&digest), Some(digest.clone()));
+        assert_eq!(
+            parse_checksum(&format!("{}  mint.zip\n", digest.to_uppercase())),
+            Some(digest)
+        );
+        assert_eq!(parse_checksum("not a checksum"), None);
+        assert_eq!(parse_checksum(""), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Opt-in usage analytics which never leave the machine: how often each feature of the GUI is used
+//! and how often each error occurs, by its code from [`crate::error_info`]. Nothing identifying
+//! is recorded, no mod names, paths or error messages. Users can look at the counts and export them
+//! to share with the maintainers if they choose to.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// File in the data directory the counts are recorded in.
+pub const ANALYTICS_FILE: &str = "analytics.json";
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Analytics {
+    /// When the first count was recorded, RFC 3339.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// How often each feature was used.
+    #[serde(default)]
+    pub features: BTreeMap<String, u64>,
+    /// How often each error code occurred.
+    #[serde(default)]
+    pub errors: BTreeMap<String, u64>,
+}
+
+/// What is exported for sharing: the counts and what they were recorded with.
+#[derive(Debug, Serialize)]
+struct AnalyticsExport<'a> {
+    mint_version: &'static str,
+    os: &'static str,
+    exported: String,
+    #[serde(flatten)]
+    analytics: &'a Analytics,
+}
+
+impl Analytics {
+    pub fn load<P: AsRef<Path>>(data_dir: P) -> Self {
+        fs::read(data_dir.as_ref().join(ANALYTICS_FILE))
+            .ok()
+            .and_then(|buf| serde_json::from_slice(&buf).ok())
+            .unwrap_or_default()
+    }
+
+    /// Best-effort save; failing to record analytics must not get in the way of anything else.
+    pub fn save<P: AsRef<Path>>(&self, data_dir: P) {
+        let res = serde_json::to_vec_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|buf| fs::write(data_dir.as_ref().join(ANALYTICS_FILE), buf));
+        if let Err(e) = res {
+            warn!("failed to write usage analytics: {e}");
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.errors.is_empty()
+    }
+
+    pub fn record_feature(&mut self, feature: &str) {
+        self.record_since();
+        *self.features.entry(feature.to_string()).or_default() += 1;
+    }
+
+    pub fn record_error(&mut self, code: &str) {
+        self.record_since();
+        *self.errors.entry(code.to_string()).or_default() += 1;
+    }
+
+    fn record_since(&mut self) {
+        if self.since.is_none() {
+            self.since = Some(chrono::Local::now().to_rfc3339());
+        }
+    }
+
+    /// The counts of `features` or `errors`, the most frequent first.
+    pub fn most_frequent(counts: &BTreeMap<String, u64>) -> Vec<(&str, u64)> {
+        let mut counts = counts
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect::<Vec<_>>();
+        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        counts
+    }
+
+    /// The counts along with mint's version and the OS as pretty JSON, to share with the
+    /// maintainers.
+    pub fn export(&self) -> String {
+        serde_json::to_string_pretty(&AnalyticsExport {
+            mint_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            exported: chrono::Local::now().to_rfc3339(),
+            analytics: self,
+        })
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut analytics = Analytics::default();
+        assert!(analytics.is_empty());
+        analytics.record_feature("install");
+        analytics.record_feature("lint");
+        analytics.record_feature("lint");
+        analytics.record_error("P002");
+        assert!(analytics.since.is_some());
+        assert_eq!(
+            Analytics::most_frequent(&analytics.features),
+            [("lint", 2), ("install", 1)]
+        );
+        assert_eq!(Analytics::most_frequent(&analytics.errors), [("P002", 1)]);
+
+        let export = serde_json::from_str::<serde_json::Value>(&analytics.export()).unwrap();
+        assert_eq!(export["mint_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(export["features"]["lint"], 2);
+        assert_eq!(export["errors"]["P002"], 1);
+    }
+}
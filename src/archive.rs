@@ -0,0 +1,445 @@
+//! Opening mod files which may be packed as 7z or rar instead of zip, as many older mods on
+//! third-party hosts are. Everything reading mod archives understands zip, so other formats are
+//! repacked into an uncompressed zip in memory when opened.
+//!
+//! Large mods on hosts with file size limits also come split into several parts, which providers
+//! join into a single file with [`MultiPart`] before handing it out.
+//!
+//! Paks extracted from archives are kept in memory only up to a budget, see [`buffer`]; texture
+//! packs of several GB would otherwise take that much memory while they are installed or linted.
+
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use mint_lib::paths::long_path;
+
+use crate::providers::ReadSeek;
+
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const RAR_MAGIC: &[u8] = b"Rar!\x1A\x07";
+
+/// Marks the first part of a split zip.
+const SPLIT_ZIP_SIGNATURE: &[u8] = b"PK\x07\x08";
+const CENTRAL_HEADER_SIGNATURE: &[u8] = b"PK\x01\x02";
+const END_OF_CENTRAL_DIR_SIGNATURE: &[u8] = b"PK\x05\x06";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    SevenZip,
+    Rar,
+}
+
+impl ArchiveFormat {
+    /// Format of an archive starting with `header`, if it needs repacking.
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(SEVEN_ZIP_MAGIC) {
+            Some(Self::SevenZip)
+        } else if header.starts_with(RAR_MAGIC) {
+            Some(Self::Rar)
+        } else {
+            None
+        }
+    }
+}
+
+/// Default for how many MiB of an extracted pak are kept in memory.
+pub const DEFAULT_MEMORY_BUDGET: u64 = 512;
+
+/// Copy `reader` to a buffer which is kept in memory up to `budget` MiB and moves to a temporary
+/// file once it grows past that.
+pub fn buffer(reader: &mut impl Read, budget: u64) -> std::io::Result<Box<dyn ReadSeek>> {
+    let max_size = budget.saturating_mul(1024 * 1024);
+    let mut buf = tempfile::SpooledTempFile::new(max_size.try_into().unwrap_or(usize::MAX));
+    std::io::copy(reader, &mut buf)?;
+    buf.rewind()?;
+    Ok(Box::new(buf))
+}
+
+/// Open the mod file at `path` for reading. 7z and rar archives are returned as an equivalent zip
+/// archive, any other file as is. The repacked zip is kept in memory up to `budget` MiB and moves
+/// to a temporary file once it grows past that.
+pub fn open(path: &Path, budget: u64) -> std::io::Result<Box<dyn ReadSeek>> {
+    let mut file = BufReader::new(fs::File::open(long_path(path))?);
+    let mut header = [0; 8];
+    let read = read_up_to(&mut file, &mut header)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    Ok(match ArchiveFormat::detect(&header[..read]) {
+        None => Box::new(file),
+        Some(ArchiveFormat::SevenZip) => {
+            let len = file.get_ref().metadata()?.len();
+            Box::new(repack_7z(file, len, budget)?)
+        }
+        // unrar only reads from paths
+        Some(ArchiveFormat::Rar) => Box::new(repack_rar(path, budget)?),
+    })
+}
+
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..])? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read)
+}
+
+/// Writes extracted files into an uncompressed zip, kept in memory up to `budget` MiB.
+struct Repacker {
+    zip: zip::ZipWriter<tempfile::SpooledTempFile>,
+    budget: u64,
+}
+
+impl Repacker {
+    fn new(budget: u64) -> Self {
+        let max_size = budget.saturating_mul(1024 * 1024);
+        Self {
+            zip: zip::ZipWriter::new(tempfile::SpooledTempFile::new(
+                max_size.try_into().unwrap_or(usize::MAX),
+            )),
+            budget,
+        }
+    }
+
+    fn add(&mut self, name: &str, data: &mut dyn Read) -> std::io::Result<()> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(true);
+        // archives made on Windows may use backslashes
+        self.zip
+            .start_file(name.replace('\\', "/"), options)
+            .map_err(std::io::Error::other)?;
+        std::io::copy(data, &mut self.zip)?;
+        Ok(())
+    }
+
+    fn finish(self) -> std::io::Result<tempfile::SpooledTempFile> {
+        let mut buf = self.zip.finish().map_err(std::io::Error::other)?;
+        buf.rewind()?;
+        Ok(buf)
+    }
+}
+
+fn repack_7z(
+    reader: impl Read + Seek,
+    len: u64,
+    budget: u64,
+) -> std::io::Result<tempfile::SpooledTempFile> {
+    let mut repacker = Repacker::new(budget);
+    let mut archive = sevenz_rust::SevenZReader::new(reader, len, sevenz_rust::Password::empty())
+        .map_err(std::io::Error::other)?;
+    archive
+        .for_each_entries(|entry, data| {
+            if !entry.is_directory() {
+                repacker.add(entry.name(), data)?;
+            }
+            Ok(true)
+        })
+        .map_err(std::io::Error::other)?;
+    repacker.finish()
+}
+
+fn repack_rar(path: &Path, budget: u64) -> std::io::Result<tempfile::SpooledTempFile> {
+    let mut repacker = Repacker::new(budget);
+    // entries over budget are extracted to disk instead of being read into memory whole
+    let temp_dir = tempfile::tempdir()?;
+    let mut archive = unrar::Archive::new(path)
+        .open_for_processing()
+        .map_err(std::io::Error::other)?;
+    while let Some(header) = archive.read_header().map_err(std::io::Error::other)? {
+        archive = if header.entry().is_file() {
+            let name = header.entry().filename.to_string_lossy().to_string();
+            if header.entry().unpacked_size > repacker.budget.saturating_mul(1024 * 1024) {
+                let extracted = temp_dir.path().join("entry");
+                let rest = header
+                    .extract_to(&extracted)
+                    .map_err(std::io::Error::other)?;
+                repacker.add(&name, &mut BufReader::new(fs::File::open(&extracted)?))?;
+                fs::remove_file(&extracted)?;
+                rest
+            } else {
+                let (data, rest) = header.read().map_err(std::io::Error::other)?;
+                repacker.add(&name, &mut data.as_slice())?;
+                rest
+            }
+        } else {
+            header.skip().map_err(std::io::Error::other)?
+        };
+    }
+    repacker.finish()
+}
+
+/// How the parts of a multi-part archive fit together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultiPart {
+    /// A zip split into `.z01`, `.z02`, ... and a final `.zip`, as made by WinZip and 7-Zip.
+    SplitZip,
+    /// A file cut into `.001`, `.002`, ... pieces which only need to be joined.
+    Concatenated,
+}
+
+impl MultiPart {
+    /// The kind of multi-part archive `name` is the first part of, and `name` without its part
+    /// extension.
+    pub fn first_part(name: &str) -> Option<(Self, &str)> {
+        if let Some(base) = name.strip_suffix(".z01") {
+            Some((Self::SplitZip, base))
+        } else {
+            name.strip_suffix(".001")
+                .map(|base| (Self::Concatenated, base))
+        }
+    }
+
+    /// Name of the `n`th numbered part, counting from 1.
+    pub fn part(self, base: &str, n: usize) -> String {
+        match self {
+            Self::SplitZip => format!("{base}.z{n:02}"),
+            Self::Concatenated => format!("{base}.{n:03}"),
+        }
+    }
+
+    /// Name of the part following the numbered ones, if the format has one.
+    pub fn last_part(self, base: &str) -> Option<String> {
+        match self {
+            Self::SplitZip => Some(format!("{base}.zip")),
+            Self::Concatenated => None,
+        }
+    }
+
+    /// Join the contents of all parts, in order, into a single archive.
+    pub fn join(self, parts: Vec<Vec<u8>>) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::SplitZip => join_split_zip(parts),
+            Self::Concatenated => Ok(parts.concat()),
+        }
+    }
+}
+
+/// The parts of the local multi-part archive `path` belongs to, in order, if it is either the first
+/// part of one or the `.zip` of a split zip.
+pub fn local_parts(path: &Path) -> Option<(MultiPart, Vec<PathBuf>)> {
+    let name = path.to_str()?;
+    let (kind, base) = MultiPart::first_part(name).or_else(|| {
+        let base = name.strip_suffix(".zip")?;
+        Path::new(&MultiPart::SplitZip.part(base, 1))
+            .exists()
+            .then_some((MultiPart::SplitZip, base))
+    })?;
+    let mut parts = (1..)
+        .map(|n| PathBuf::from(kind.part(base, n)))
+        .take_while(|p| p.exists())
+        .collect::<Vec<_>>();
+    parts.extend(kind.last_part(base).map(PathBuf::from));
+    Some((kind, parts))
+}
+
+/// Join the parts of a split zip into a regular zip. The parts are concatenated and the offsets in
+/// the central directory, which are relative to the part they point into, made absolute.
+///
+/// Zip64 split zips, and those which would need zip64 once joined because an entry starts beyond
+/// 4 GiB, are not supported and fail with [`ErrorKind::Unsupported`].
+fn join_split_zip(parts: Vec<Vec<u8>>) -> std::io::Result<Vec<u8>> {
+    let invalid =
+        |msg: &str| std::io::Error::new(ErrorKind::InvalidData, format!("split zip {msg}"));
+    let zip64 = || {
+        std::io::Error::new(
+            ErrorKind::Unsupported,
+            "split zips larger than 4 GiB (zip64) are not supported, extract the archive first",
+        )
+    };
+    let to_u32 = |offset: usize| u32::try_from(offset).map_err(|_| zip64());
+
+    let mut starts = vec![];
+    let mut joined = vec![];
+    for part in parts {
+        starts.push(joined.len());
+        joined.extend(part);
+    }
+    let skip = if joined.starts_with(SPLIT_ZIP_SIGNATURE) {
+        SPLIT_ZIP_SIGNATURE.len()
+    } else {
+        0
+    };
+    // where a position in `disk` ends up in the result
+    let absolute = |disk: u16, offset: u32| -> std::io::Result<usize> {
+        let start = starts
+            .get(disk as usize)
+            .ok_or_else(|| invalid("refers to a missing part"))?;
+        (start + offset as usize)
+            .checked_sub(skip)
+            .ok_or_else(|| invalid("has an invalid offset"))
+    };
+
+    let eocd = joined
+        .windows(END_OF_CENTRAL_DIR_SIGNATURE.len())
+        .rposition(|w| w == END_OF_CENTRAL_DIR_SIGNATURE)
+        .ok_or_else(|| invalid("has no central directory"))?;
+    let entries = read_u16(&joined, eocd + 10)?;
+    let (cd_disk, cd_offset) = (read_u16(&joined, eocd + 6)?, read_u32(&joined, eocd + 16)?);
+    // fields set to their maximum value are stored in the zip64 records instead
+    if entries == u16::MAX || cd_disk == u16::MAX || cd_offset == u32::MAX {
+        return Err(zip64());
+    }
+    let cd_offset = absolute(cd_disk, cd_offset)?;
+
+    let mut pos = cd_offset + skip;
+    for _ in 0..entries {
+        if joined.get(pos..pos + 4) != Some(CENTRAL_HEADER_SIGNATURE) {
+            return Err(invalid("has a corrupt central directory"));
+        }
+        let (disk, offset) = (read_u16(&joined, pos + 34)?, read_u32(&joined, pos + 42)?);
+        if disk == u16::MAX || offset == u32::MAX {
+            return Err(zip64());
+        }
+        let offset = to_u32(absolute(disk, offset)?)?;
+        write_u16(&mut joined, pos + 34, 0);
+        write_u32(&mut joined, pos + 42, offset);
+        pos += 46
+            + read_u16(&joined, pos + 28)? as usize
+            + read_u16(&joined, pos + 30)? as usize
+            + read_u16(&joined, pos + 32)? as usize;
+    }
+
+    // everything is on the first and only disk now
+    write_u16(&mut joined, eocd + 4, 0);
+    write_u16(&mut joined, eocd + 6, 0);
+    write_u16(&mut joined, eocd + 8, entries);
+    write_u32(&mut joined, eocd + 16, to_u32(cd_offset)?);
+    joined.drain(..skip);
+    Ok(joined)
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> std::io::Result<u16> {
+    buf.get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| ErrorKind::UnexpectedEof.into())
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> std::io::Result<u32> {
+    buf.get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| ErrorKind::UnexpectedEof.into())
+}
+
+fn write_u16(buf: &mut [u8], pos: usize, value: u16) {
+    buf[pos..pos + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(buf: &mut [u8], pos: usize, value: u32) {
+    buf[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            ArchiveFormat::detect(b"7z\xBC\xAF\x27\x1C\x00\x04"),
+            Some(ArchiveFormat::SevenZip)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(b"Rar!\x1A\x07\x01\x00"),
+            Some(ArchiveFormat::Rar)
+        );
+        assert_eq!(ArchiveFormat::detect(b"PK\x03\x04"), None);
+        assert_eq!(ArchiveFormat::detect(b"7z"), None);
+    }
+
+    #[test]
+    fn test_plain_files_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mod.pak");
+        fs::write(&path, b"not an archive").unwrap();
+        let mut buf = vec![];
+        open(&path).unwrap().read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, b"not an archive");
+    }
+
+    #[test]
+    fn test_buffer() {
+        let data = vec![7; 4096];
+        // a budget of 0 MiB spills into a file right away
+        for budget in [0, DEFAULT_MEMORY_BUDGET] {
+            let mut buf = vec![];
+            let mut reader = buffer(&mut data.as_slice(), budget).unwrap();
+            reader.read_to_end(&mut buf).unwrap();
+            assert_eq!(buf, data);
+            reader.seek(SeekFrom::Start(4095)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_repacker() {
+        // a budget of 0 spills to disk right away
+        for budget in [0, DEFAULT_MEMORY_BUDGET] {
+            let mut repacker = Repacker::new(budget);
+            repacker
+                .add(r"Content\mod_P.pak", &mut b"pak".as_slice())
+                .unwrap();
+            let mut archive = zip::ZipArchive::new(repacker.finish().unwrap()).unwrap();
+            let mut file = archive.by_name("Content/mod_P.pak").unwrap();
+            let mut buf = vec![];
+            file.read_to_end(&mut buf).unwrap();
+            assert_eq!(buf, b"pak");
+        }
+    }
+
+    #[test]
+    fn test_multi_part_names() {
+        let (kind, base) = MultiPart::first_part("mod.z01").unwrap();
+        assert_eq!((kind, base), (MultiPart::SplitZip, "mod"));
+        assert_eq!(kind.part(base, 2), "mod.z02");
+        assert_eq!(kind.last_part(base).as_deref(), Some("mod.zip"));
+
+        let (kind, base) = MultiPart::first_part("mod.7z.001").unwrap();
+        assert_eq!((kind, base), (MultiPart::Concatenated, "mod.7z"));
+        assert_eq!(kind.part(base, 12), "mod.7z.012");
+        assert_eq!(kind.last_part(base), None);
+
+        assert_eq!(MultiPart::first_part("mod.zip"), None);
+        assert_eq!(MultiPart::first_part("mod.002"), None);
+    }
+
+    #[test]
+    fn test_join_split_zip() {
+        let mut repacker = Repacker::new(DEFAULT_MEMORY_BUDGET);
+        repacker.add("a_P.pak", &mut b"aaaa".as_slice()).unwrap();
+        repacker.add("b_P.pak", &mut b"bbbb".as_slice()).unwrap();
+        let mut zip = vec![];
+        repacker.finish().unwrap().read_to_end(&mut zip).unwrap();
+
+        // split in front of the central directory, with offsets relative to their part
+        let eocd = zip.len() - 22;
+        let cd_offset = read_u32(&zip, eocd + 16).unwrap() as usize;
+        let mut first = SPLIT_ZIP_SIGNATURE.to_vec();
+        first.extend(&zip[..cd_offset]);
+        let mut last = zip[cd_offset..].to_vec();
+        let mut pos = 0;
+        for _ in 0..2 {
+            let offset = read_u32(&last, pos + 42).unwrap();
+            write_u32(&mut last, pos + 42, offset + 4);
+            pos += 46
+                + read_u16(&last, pos + 28).unwrap() as usize
+                + read_u16(&last, pos + 30).unwrap() as usize;
+        }
+        let eocd = last.len() - 22;
+        write_u16(&mut last, eocd + 4, 1);
+        write_u16(&mut last, eocd + 6, 1);
+        write_u32(&mut last, eocd + 16, 0);
+
+        let joined = MultiPart::SplitZip
+            .join(vec![first.clone(), last.clone()])
+            .unwrap();
+        assert_eq!(joined, zip);
+
+        // an entry stored in the zip64 extra field
+        write_u32(&mut last, 42, u32::MAX);
+        let err = MultiPart::SplitZip.join(vec![first, last]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+}
@@ -0,0 +1,327 @@
+//! Backups of mint's config and data directories, either copied into a directory or packed into a
+//! single `.zip` which is easier to move between machines. A backup is made by hand from the
+//! settings, or automatically on launch, before installing or daily as configured in
+//! [`AutoBackup`]. Only the newest [`AutoBackup::keep`] backups are kept.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use fs_err as fs;
+use mint_lib::paths::long_path;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::Dirs;
+use crate::state::history;
+
+const BACKUP_PREFIX: &str = "backup_";
+
+const ZIP_EXTENSION: &str = ".zip";
+
+const TIME_FORMAT: &str = "%Y-%m-%d-%H-%M-%S";
+
+/// How long after the newest backup a daily backup is due.
+pub const DAILY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// When backups are made automatically and how many are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoBackup {
+    pub on_launch: bool,
+    pub before_install: bool,
+    /// Once a day while mint is open, if the newest backup is older than that.
+    pub daily: bool,
+    /// How many backups are kept, older ones are deleted. 0 keeps all of them.
+    pub keep: usize,
+}
+
+impl Default for AutoBackup {
+    fn default() -> Self {
+        Self {
+            on_launch: false,
+            before_install: false,
+            daily: false,
+            keep: 10,
+        }
+    }
+}
+
+/// Why a backup was made.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    Manual,
+    Launch,
+    Install,
+    Daily,
+}
+
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Trigger::Manual => "manual",
+            Trigger::Launch => "on launch",
+            Trigger::Install => "before install",
+            Trigger::Daily => "daily",
+        })
+    }
+}
+
+/// `mint_backups` in the user's documents, where backups go unless configured otherwise.
+pub fn default_dir() -> Option<PathBuf> {
+    directories::UserDirs::new()
+        .and_then(|dirs| dirs.document_dir().map(|d| d.join("mint_backups")))
+}
+
+/// All backups in `dir`, directories and `.zip`s, with the time they were made, newest first.
+/// Other entries are skipped.
+pub fn list(dir: &Path) -> std::io::Result<Vec<(PathBuf, NaiveDateTime)>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut backups = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str()?.strip_prefix(BACKUP_PREFIX))
+        else {
+            continue;
+        };
+        let time = match name.strip_suffix(ZIP_EXTENSION) {
+            Some(time) if path.is_file() => time,
+            None if path.is_dir() => name,
+            _ => continue,
+        };
+        if let Ok(time) = NaiveDateTime::parse_from_str(time, TIME_FORMAT) {
+            backups.push((path, time));
+        }
+    }
+    backups.sort_by(|a, b| b.1.cmp(&a.1));
+    Ok(backups)
+}
+
+/// Whether a daily backup is due in `dir`, i.e. it has none made within the last day.
+pub fn daily_due(dir: &Path) -> bool {
+    let newest = list(dir)
+        .ok()
+        .and_then(|b| b.first().map(|(_, time)| *time));
+    newest.is_none_or(|time| {
+        (chrono::Local::now().naive_local() - time)
+            .to_std()
+            .is_ok_and(|age| age >= DAILY)
+    })
+}
+
+/// Directories left out of backups: the download cache, in case it was moved into the config or
+/// data directory, and the history of the mod data, see [`history`].
+fn skipped_dirs(dirs: &Dirs) -> [PathBuf; 2] {
+    [
+        dirs.cache_dir.clone(),
+        dirs.data_dir.join(history::HISTORY_DIR),
+    ]
+}
+
+fn is_skipped(path: &Path, skip: &[PathBuf]) -> bool {
+    skip.iter().any(|s| long_path(s) == long_path(path))
+}
+
+/// Copy the config and data directories into a new backup in `dir`, packed into a `.zip` if
+/// `zip`, then delete all but the newest `keep` backups unless `keep` is 0.
+pub fn create(dirs: &Dirs, dir: &Path, zip: bool, keep: usize) -> std::io::Result<PathBuf> {
+    let time = chrono::Local::now().format(TIME_FORMAT);
+    let skip = skipped_dirs(dirs);
+    let path = if zip {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{BACKUP_PREFIX}{time}{ZIP_EXTENSION}"));
+        // not listed as a backup until complete
+        let partial = path.with_extension("zip.part");
+        zip_dirs(dirs, &partial, &skip)?;
+        fs::rename(&partial, &path)?;
+        path
+    } else {
+        let path = dir.join(format!("{BACKUP_PREFIX}{time}"));
+        fs::create_dir_all(&path)?;
+        if dirs.config_dir.exists() {
+            copy_dir_contents(&dirs.config_dir, &path.join("config"), &skip)?;
+        }
+        if dirs.data_dir.exists() {
+            copy_dir_contents(&dirs.data_dir, &path.join("data"), &skip)?;
+        }
+        path
+    };
+    info!("backup created at {}", path.display());
+
+    if keep > 0 {
+        for (old, _) in list(dir)?.into_iter().skip(keep) {
+            if old.is_dir() {
+                fs::remove_dir_all(long_path(&old))?;
+            } else {
+                fs::remove_file(&old)?;
+            }
+            info!("old backup {} deleted", old.display());
+        }
+    }
+    Ok(path)
+}
+
+/// Pack the config and data directories into a new `.zip` at `path`, under `config/` and `data/`.
+fn zip_dirs(dirs: &Dirs, path: &Path, skip: &[PathBuf]) -> std::io::Result<()> {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    for (src, prefix) in [(&dirs.config_dir, "config"), (&dirs.data_dir, "data")] {
+        if src.exists() {
+            zip_dir_contents(&mut zip, options, src, prefix, skip)?;
+        }
+    }
+    zip.finish().map_err(std::io::Error::other)?;
+    Ok(())
+}
+
+fn zip_dir_contents<W: std::io::Write + std::io::Seek>(
+    zip: &mut zip::ZipWriter<W>,
+    options: zip::write::SimpleFileOptions,
+    src: &Path,
+    prefix: &str,
+    skip: &[PathBuf],
+) -> std::io::Result<()> {
+    zip.add_directory(prefix, options)
+        .map_err(std::io::Error::other)?;
+
+    for entry in fs::read_dir(long_path(src))? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = format!("{prefix}/{}", entry.file_name().to_string_lossy());
+
+        if path.is_dir() {
+            if is_skipped(&path, skip) {
+                continue;
+            }
+            zip_dir_contents(zip, options, &path, &name, skip)?;
+        } else {
+            zip.start_file(name, options)
+                .map_err(std::io::Error::other)?;
+            std::io::copy(&mut fs::File::open(long_path(&path))?, zip)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn copy_dir_contents(src: &Path, dst: &Path, skip: &[PathBuf]) -> std::io::Result<()> {
+    fs::create_dir_all(long_path(dst))?;
+
+    for entry in fs::read_dir(long_path(src))? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest_path = dst.join(entry.file_name());
+
+        if path.is_dir() {
+            if is_skipped(&path, skip) {
+                continue;
+            }
+            copy_dir_contents(&path, &dest_path, skip)?;
+        } else {
+            fs::copy(long_path(&path), long_path(&dest_path))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "backup_2024-01-02-03-04-05",
+            "backup_2024-01-03-00-00-00",
+            "backup_garbage",
+            "other",
+        ] {
+            fs::create_dir(dir.path().join(name)).unwrap();
+        }
+        fs::write(dir.path().join("backup_2024-01-04-00-00-00"), b"").unwrap();
+        fs::write(dir.path().join("backup_2024-01-02-00-00-00.zip"), b"").unwrap();
+
+        let backups = list(dir.path()).unwrap();
+        let names = backups
+            .iter()
+            .map(|(p, _)| p.file_name().unwrap().to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "backup_2024-01-03-00-00-00",
+                "backup_2024-01-02-03-04-05",
+                "backup_2024-01-02-00-00-00.zip"
+            ]
+        );
+        assert!(daily_due(dir.path()));
+        assert!(daily_due(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn test_create_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let dirs = Dirs {
+            config_dir: dir.path().join("config"),
+            cache_dir: dir.path().join("cache"),
+            data_dir: dir.path().join("data"),
+            workspace: None,
+        };
+        fs::create_dir_all(dirs.config_dir.join("nested")).unwrap();
+        fs::write(dirs.config_dir.join("nested/config.json"), b"{}").unwrap();
+        fs::create_dir_all(&dirs.cache_dir).unwrap();
+        fs::write(dirs.cache_dir.join("huge.pak"), b"").unwrap();
+
+        let backups = dir.path().join("backups");
+        let path = create(&dirs, &backups, true, 1).unwrap();
+        let mut archive = zip::ZipArchive::new(fs::File::open(&path).unwrap()).unwrap();
+        let mut names = archive.file_names().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            ["config/", "config/nested/", "config/nested/config.json"]
+        );
+        let mut contents = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("config/nested/config.json").unwrap(),
+            &mut contents,
+        )
+        .unwrap();
+        assert_eq!(contents, "{}");
+    }
+
+    #[test]
+    fn test_create_skips_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = dir.path().join("data");
+        let dirs = Dirs {
+            config_dir: dir.path().join("config"),
+            // moved into the data directory
+            cache_dir: data_dir.join("cache"),
+            data_dir: data_dir.clone(),
+            workspace: None,
+        };
+        for path in [
+            data_dir.join("mod_data.json"),
+            dirs.cache_dir.join("blobs/abc"),
+            data_dir.join(history::HISTORY_DIR).join("old.json"),
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"{}").unwrap();
+        }
+
+        let backups = dir.path().join("backups");
+        let path = create(&dirs, &backups, false, 0).unwrap();
+        assert!(path.join("data/mod_data.json").is_file());
+        assert!(!path.join("data/cache").exists());
+        assert!(!path.join("data").join(history::HISTORY_DIR).exists());
+    }
+}
@@ -0,0 +1,277 @@
+//! `mint://` links, so "Add to mint" links on mod pages add a mod straight to a profile. The OS
+//! opens them with `mint open-link <link>`, which hands the link to the running mint if there is
+//! one, see [`Instance`], and starts mint with it otherwise.
+//!
+//! The running mint listens on a local port written to [`INSTANCE_FILE`] in the data directory,
+//! along with a token later launches have to send with each link.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use fs_err as fs;
+use snafu::prelude::*;
+use tracing::{info, warn};
+
+pub const SCHEME: &str = "mint";
+
+/// File in the data directory the running mint writes its port and token to.
+const INSTANCE_FILE: &str = "instance";
+
+/// Longest to wait for the running mint to take a link.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Snafu)]
+pub enum DeepLinkError {
+    #[snafu(display("invalid link <{link}>"))]
+    InvalidLink {
+        source: url::ParseError,
+        link: String,
+    },
+    #[snafu(display("<{link}> is not a mint:// link"))]
+    WrongScheme { link: String },
+    #[snafu(display("unknown action {action:?} in <{link}>"))]
+    UnknownAction { action: String, link: String },
+    #[snafu(display("<{link}> does not name the http(s) URL of a mod"))]
+    MissingModUrl { link: String },
+    #[snafu(display("registering mint:// links is not supported on this platform"))]
+    Unsupported,
+    #[snafu(display("{command} failed while registering mint:// links"))]
+    RegisterFailed { command: String },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+}
+
+/// What a `mint://` link asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// Add the mod at `url` to `profile`, or to the active profile.
+    Add {
+        url: String,
+        profile: Option<String>,
+    },
+}
+
+impl DeepLink {
+    /// Parse `mint://add?url=<mod URL>[&profile=<name>]`. The mod URL has to be a web URL, so
+    /// links cannot add local files.
+    pub fn parse(link: &str) -> Result<Self, DeepLinkError> {
+        let parsed = url::Url::parse(link).context(InvalidLinkSnafu { link })?;
+        ensure!(parsed.scheme() == SCHEME, WrongSchemeSnafu { link });
+        let action = parsed.host_str().unwrap_or(parsed.path()).trim_matches('/');
+        match action {
+            "add" => {
+                let mut url = None;
+                let mut profile = None;
+                for (key, value) in parsed.query_pairs() {
+                    match &*key {
+                        "url" => url = Some(value.into_owned()),
+                        "profile" => profile = Some(value.into_owned()).filter(|p| !p.is_empty()),
+                        _ => {}
+                    }
+                }
+                let url = url
+                    .filter(|u| u.starts_with("https://") || u.starts_with("http://"))
+                    .context(MissingModUrlSnafu { link })?;
+                Ok(Self::Add { url, profile })
+            }
+            _ => UnknownActionSnafu { action, link }.fail(),
+        }
+    }
+}
+
+/// The running mint, taking links from later launches.
+pub struct Instance {
+    file: PathBuf,
+    links: mpsc::Receiver<DeepLink>,
+}
+
+impl Instance {
+    /// Listen for links forwarded by [`forward`], calling `wake` after each so the GUI picks it
+    /// up, see [`Self::links`].
+    pub fn listen(data_dir: &Path, wake: impl Fn() + Send + 'static) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let token = format!("{:016x}", rand::random::<u64>());
+        let file = data_dir.join(INSTANCE_FILE);
+        fs::write(&file, format!("{} {token}", listener.local_addr()?.port()))?;
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                match receive(stream, &token) {
+                    Ok(link) => {
+                        info!("received link {link:?} from another launch");
+                        if tx.send(link).is_err() {
+                            break;
+                        }
+                        wake();
+                    }
+                    Err(e) => warn!("rejected link from another launch: {e}"),
+                }
+            }
+        });
+        Ok(Self { file, links: rx })
+    }
+
+    /// Links forwarded since the last call.
+    pub fn links(&self) -> impl Iterator<Item = DeepLink> + '_ {
+        self.links.try_iter()
+    }
+}
+
+impl Drop for Instance {
+    fn drop(&mut self) {
+        fs::remove_file(&self.file).ok();
+    }
+}
+
+/// Read the token and link sent by [`forward`], answering whether the link was taken.
+fn receive(stream: TcpStream, token: &str) -> Result<DeepLink, DeepLinkError> {
+    stream.set_read_timeout(Some(FORWARD_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut sent_token = String::new();
+    reader.read_line(&mut sent_token)?;
+    let mut link = String::new();
+    reader.read_line(&mut link)?;
+    let result = if sent_token.trim() == token {
+        DeepLink::parse(link.trim())
+    } else {
+        Err(std::io::Error::other("wrong token").into())
+    };
+    writeln!(
+        &stream,
+        "{}",
+        if result.is_ok() { "ok" } else { "rejected" }
+    )?;
+    result
+}
+
+/// Hand `link` to the running mint. `false` if there is none, e.g. as the file was left behind by
+/// one which crashed.
+pub fn forward(data_dir: &Path, link: &str) -> bool {
+    let send = || -> std::io::Result<bool> {
+        let contents = fs::read_to_string(data_dir.join(INSTANCE_FILE))?;
+        let Some((port, token)) = contents.trim().split_once(' ') else {
+            return Ok(false);
+        };
+        let Ok(port) = port.parse::<u16>() else {
+            return Ok(false);
+        };
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let stream = TcpStream::connect_timeout(&address, FORWARD_TIMEOUT)?;
+        stream.set_read_timeout(Some(FORWARD_TIMEOUT))?;
+        writeln!(&stream, "{token}\n{link}")?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply)?;
+        Ok(reply.trim() == "ok")
+    };
+    match send() {
+        Ok(forwarded) => forwarded,
+        Err(e) => {
+            info!("no running mint to hand the link to: {e}");
+            false
+        }
+    }
+}
+
+/// Have the OS open `mint://` links with this executable, for the current user.
+pub fn register() -> Result<(), DeepLinkError> {
+    register_exe(&std::env::current_exe()?)
+}
+
+#[cfg(target_os = "windows")]
+fn register_exe(exe: &Path) -> Result<(), DeepLinkError> {
+    let key = format!(r"HKCU\Software\Classes\{SCHEME}");
+    let command_key = format!(r"{key}\shell\open\command");
+    let command = format!("\"{}\" open-link \"%1\"", exe.display());
+    let entries: [&[&str]; 3] = [
+        &[key.as_str(), "/ve", "/d", "URL:mint mod link"],
+        &[key.as_str(), "/v", "URL Protocol", "/d", ""],
+        &[command_key.as_str(), "/ve", "/d", command.as_str()],
+    ];
+    for args in entries {
+        let status = std::process::Command::new("reg")
+            .arg("add")
+            .args(args)
+            .arg("/f")
+            .status()?;
+        ensure!(
+            status.success(),
+            RegisterFailedSnafu {
+                command: format!("reg add {}", args[0])
+            }
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn register_exe(exe: &Path) -> Result<(), DeepLinkError> {
+    const DESKTOP_FILE: &str = "mint-link.desktop";
+
+    let dirs = directories::BaseDirs::new().context(UnsupportedSnafu)?;
+    let applications = dirs.data_dir().join("applications");
+    fs::create_dir_all(&applications)?;
+    fs::write(
+        applications.join(DESKTOP_FILE),
+        format!(
+            "[Desktop Entry]\nType=Application\nName=mint\nExec=\"{}\" open-link %u\n\
+             NoDisplay=true\nMimeType=x-scheme-handler/{SCHEME};\n",
+            exe.display()
+        ),
+    )?;
+    let status = std::process::Command::new("xdg-mime")
+        .args(["default", DESKTOP_FILE])
+        .arg(format!("x-scheme-handler/{SCHEME}"))
+        .status()?;
+    ensure!(
+        status.success(),
+        RegisterFailedSnafu {
+            command: "xdg-mime default"
+        }
+    );
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn register_exe(_exe: &Path) -> Result<(), DeepLinkError> {
+    UnsupportedSnafu.fail()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            DeepLink::parse("mint://add?url=https%3A%2F%2Fmod.io%2Fg%2Fdrg%2Fm%2Fmod&profile=Main")
+                .unwrap(),
+            DeepLink::Add {
+                url: "https://mod.io/g/drg/m/mod".to_string(),
+                profile: Some("Main".to_string()),
+            }
+        );
+        assert_eq!(
+            DeepLink::parse("mint://add/?url=https://example.com/mod.zip").unwrap(),
+            DeepLink::Add {
+                url: "https://example.com/mod.zip".to_string(),
+                profile: None,
+            }
+        );
+        assert!(matches!(
+            DeepLink::parse("mint://add?url=C:%5Cmods%5Cmod.pak"),
+            Err(DeepLinkError::MissingModUrl { .. })
+        ));
+        assert!(matches!(
+            DeepLink::parse("mint://remove?url=https://example.com/mod.zip"),
+            Err(DeepLinkError::UnknownAction { .. })
+        ));
+        assert!(matches!(
+            DeepLink::parse("https://mod.io/g/drg/m/mod"),
+            Err(DeepLinkError::WrongScheme { .. })
+        ));
+    }
+}
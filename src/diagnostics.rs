@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use fs_err as fs;
+use mint_lib::DRGInstallation;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+use tracing::warn;
+
+use crate::integrate::IntegrationWarning;
+use crate::providers::ModSpecification;
+use crate::state::State;
+
+/// Placeholder written in place of provider parameters (API keys, OAuth tokens).
+pub const REDACTED: &str = "<redacted>";
+
+static RE_SECRET_QUERY_PARAM: OnceLock<regex::Regex> = OnceLock::new();
+
+/// Matches the value of URL query parameters carrying credentials, e.g. mod.io's `api_key`.
+fn re_secret_query_param() -> &'static regex::Regex {
+    RE_SECRET_QUERY_PARAM.get_or_init(|| {
+        regex::Regex::new(r#"(?i)([?&](?:api_key|access_token|token|key)=)[^&#\s"']*"#).unwrap()
+    })
+}
+
+/// Replace the values of credential query parameters in URLs within `s` by [`REDACTED`].
+fn redact_query_params(s: &str) -> String {
+    re_secret_query_param()
+        .replace_all(s, format!("${{1}}{REDACTED}"))
+        .into_owned()
+}
+
+#[derive(Debug, Snafu)]
+pub enum DiagnosticsError {
+    #[snafu(display("failed to serialize {name} for diagnostics"))]
+    SerializeFailed {
+        source: serde_json::Error,
+        name: &'static str,
+    },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+    #[snafu(display("failed to write support bundle: {source}"))]
+    ZipFailed { source: zip::result::ZipError },
+}
+
+/// File in the data directory recording the outcome of the most recent integration.
+pub const LAST_INTEGRATION_FILE: &str = "last_integration.json";
+
+/// Outcome of the most recent integration, kept so it can be attached to support bundles.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrationReport {
+    pub time: String,
+    pub version: String,
+    pub mods: Vec<String>,
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<IntegrationWarning>,
+}
+
+impl IntegrationReport {
+    pub fn new<'a, E: std::fmt::Display>(
+        mods: impl IntoIterator<Item = &'a ModSpecification>,
+        error: Option<E>,
+    ) -> Self {
+        Self {
+            time: chrono::Local::now().to_rfc3339(),
+            version: mint_lib::built_info::version().to_string(),
+            mods: mods.into_iter().map(|m| m.url.clone()).collect(),
+            error: error.map(|e| e.to_string()),
+            warnings: vec![],
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: &[IntegrationWarning]) -> Self {
+        self.warnings = warnings.to_vec();
+        self
+    }
+
+    /// Report for removing the installed mods, so the previous install is no longer expected to
+    /// be in place.
+    pub fn uninstalled() -> Self {
+        Self::new([], None::<std::convert::Infallible>)
+    }
+
+    pub fn load<P: AsRef<Path>>(data_dir: P) -> Option<Self> {
+        let buf = fs::read(data_dir.as_ref().join(LAST_INTEGRATION_FILE)).ok()?;
+        serde_json::from_slice(&buf).ok()
+    }
+
+    /// Whether this integration succeeded and left mods installed.
+    pub fn installed_mods(&self) -> bool {
+        self.error.is_none() && !self.mods.is_empty()
+    }
+
+    /// Best-effort save; failing to record the report must not fail the integration itself.
+    pub fn save<P: AsRef<Path>>(&self, data_dir: P) {
+        let path = data_dir.as_ref().join(LAST_INTEGRATION_FILE);
+        let res = serde_json::to_vec_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|buf| fs::write(&path, buf));
+        if let Err(e) = res {
+            warn!("failed to write integration report: {e}");
+        }
+    }
+}
+
+/// Rewrites paths in exported files so they do not reveal user names or directory layouts, and
+/// redacts credentials passed in URLs. Longer prefixes are tried first so e.g. the config
+/// directory wins over the home directory.
+struct PathNormalizer {
+    replacements: Vec<(String, &'static str)>,
+}
+
+impl PathNormalizer {
+    fn new(state: &State) -> Self {
+        let mut replacements = vec![
+            (&state.dirs.config_dir, "<config_dir>"),
+            (&state.dirs.cache_dir, "<cache_dir>"),
+            (&state.dirs.data_dir, "<data_dir>"),
+        ]
+        .into_iter()
+        .map(|(p, r)| (p.to_string_lossy().to_string(), r))
+        .collect::<Vec<_>>();
+        if let Some(base) = directories::BaseDirs::new() {
+            replacements.push((base.home_dir().to_string_lossy().to_string(), "~"));
+        }
+        replacements.retain(|(p, _)| !p.is_empty());
+        replacements.sort_by_key(|(p, _)| std::cmp::Reverse(p.len()));
+        Self { replacements }
+    }
+
+    fn normalize(&self, s: &str) -> String {
+        let mut s = s.to_string();
+        for (prefix, replacement) in &self.replacements {
+            s = s.replace(prefix.as_str(), replacement);
+        }
+        redact_query_params(&s)
+    }
+
+    fn normalize_value(&self, value: &mut serde_json::Value) {
+        use serde_json::Value;
+        match value {
+            Value::String(s) => *s = self.normalize(s),
+            Value::Array(a) => a.iter_mut().for_each(|v| self.normalize_value(v)),
+            Value::Object(o) => o.values_mut().for_each(|v| self.normalize_value(v)),
+            _ => {}
+        }
+    }
+}
+
+fn to_value<T: Serialize>(
+    value: &T,
+    name: &'static str,
+) -> Result<serde_json::Value, DiagnosticsError> {
+    serde_json::to_value(value).context(SerializeFailedSnafu { name })
+}
+
+/// config.json with all provider parameters replaced by [`REDACTED`] and paths normalized.
+pub fn redacted_config(state: &State) -> Result<serde_json::Value, DiagnosticsError> {
+    let mut config = to_value(&*state.config, "config")?;
+    if let Some(providers) = config
+        .get_mut("provider_parameters")
+        .and_then(|p| p.as_object_mut())
+    {
+        for params in providers.values_mut().filter_map(|p| p.as_object_mut()) {
+            for value in params.values_mut() {
+                *value = REDACTED.into();
+            }
+        }
+    }
+    PathNormalizer::new(state).normalize_value(&mut config);
+    Ok(config)
+}
+
+/// mod_data.json with paths (e.g. of local mods) normalized.
+pub fn redacted_mod_data(state: &State) -> Result<serde_json::Value, DiagnosticsError> {
+    let mut mod_data = to_value(&*state.mod_data, "mod data")?;
+    PathNormalizer::new(state).normalize_value(&mut mod_data);
+    Ok(mod_data)
+}
+
+/// Redacted config and mod data as `(file name, contents)` pairs, ready to be written to disk or
+/// added to an archive.
+pub fn redacted_files(state: &State) -> Result<Vec<(&'static str, Vec<u8>)>, DiagnosticsError> {
+    let config = redacted_config(state)?;
+    let mod_data = redacted_mod_data(state)?;
+    Ok(vec![
+        (
+            "config.json",
+            serde_json::to_vec_pretty(&config).context(SerializeFailedSnafu { name: "config" })?,
+        ),
+        (
+            "mod_data.json",
+            serde_json::to_vec_pretty(&mod_data)
+                .context(SerializeFailedSnafu { name: "mod data" })?,
+        ),
+    ])
+}
+
+/// Write redacted copies of config.json and mod_data.json into a new timestamped directory
+/// inside `dir`, returning its path.
+pub fn export_diagnostics_config<P: AsRef<Path>>(
+    state: &State,
+    dir: P,
+) -> Result<PathBuf, DiagnosticsError> {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S");
+    let out = dir.as_ref().join(format!("mint_diagnostics_{timestamp}"));
+    fs::create_dir_all(&out)?;
+    for (name, data) in redacted_files(state)? {
+        fs::write(out.join(name), data)?;
+    }
+    Ok(out)
+}
+
+/// Write a zip containing redacted config, logs, the last integration report, a summary of the
+/// cache and basic system information to `dir`, returning the path of the zip.
+pub fn write_support_bundle<P: AsRef<Path>>(
+    state: &State,
+    dir: P,
+) -> Result<PathBuf, DiagnosticsError> {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S");
+    let path = dir
+        .as_ref()
+        .join(format!("mint_support_bundle_{timestamp}.zip"));
+    fs::create_dir_all(dir.as_ref())?;
+
+    let normalizer = PathNormalizer::new(state);
+    let mut zip = zip::ZipWriter::new(fs::File::create(&path)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut add = |name: &str, data: &[u8]| -> Result<(), DiagnosticsError> {
+        zip.start_file(name, options).context(ZipFailedSnafu)?;
+        zip.write_all(data)?;
+        Ok(())
+    };
+
+    for (name, data) in redacted_files(state)? {
+        add(name, &data)?;
+    }
+
+    let mut logs = vec![state.dirs.data_dir.join("mint.log")];
+    if let Some(pak) = &state.config.drg_pak_path
+        && let Ok(installation) = DRGInstallation::from_pak_path(pak)
+    {
+        logs.push(installation.binaries_directory().join("mint_hook.log"));
+    }
+    for log in logs {
+        match fs::read_to_string(&log) {
+            Ok(contents) => add(
+                &format!("logs/{}", log.file_name().unwrap().to_string_lossy()),
+                normalizer.normalize(&contents).as_bytes(),
+            )?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("failed to read {}: {e}", log.display()),
+        }
+    }
+
+    match fs::read_to_string(state.dirs.data_dir.join(LAST_INTEGRATION_FILE)) {
+        Ok(report) => add(
+            LAST_INTEGRATION_FILE,
+            normalizer.normalize(&report).as_bytes(),
+        )?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!("failed to read integration report: {e}"),
+    }
+
+    let cache = serde_json::to_vec_pretty(&cache_summary(state)).context(SerializeFailedSnafu {
+        name: "cache summary",
+    })?;
+    add("cache_summary.json", &cache)?;
+
+    let system = serde_json::to_vec_pretty(&system_info(state)).context(SerializeFailedSnafu {
+        name: "system info",
+    })?;
+    add("system_info.json", &system)?;
+
+    zip.finish().context(ZipFailedSnafu)?;
+    Ok(path)
+}
+
+fn cache_summary(state: &State) -> serde_json::Value {
+    let blobs_dir = state.dirs.cache_dir.join("blobs");
+    let (mut count, mut bytes) = (0u64, 0u64);
+    if let Ok(entries) = fs::read_dir(&blobs_dir) {
+        for entry in entries.flatten() {
+            if let Ok(meta) = entry.metadata()
+                && meta.is_file()
+            {
+                count += 1;
+                bytes += meta.len();
+            }
+        }
+    }
+    serde_json::json!({
+        "blob_count": count,
+        "blob_bytes": bytes,
+        "providers": state.store.cache_summary(),
+    })
+}
+
+fn system_info(state: &State) -> BTreeMap<&'static str, String> {
+    let pak = state.config.drg_pak_path.as_ref();
+    BTreeMap::from([
+        ("version", mint_lib::built_info::version().to_string()),
+        ("os", std::env::consts::OS.to_string()),
+        ("arch", std::env::consts::ARCH.to_string()),
+        ("family", std::env::consts::FAMILY.to_string()),
+        (
+            "installation",
+            pak.and_then(|p| DRGInstallation::from_pak_path(p).ok())
+                .map(|i| format!("{:?}", i.installation_type))
+                .unwrap_or_else(|| "unknown".to_string()),
+        ),
+        (
+            "drg_pak_path",
+            pak.map(|p| PathNormalizer::new(state).normalize(&p.to_string_lossy()))
+                .unwrap_or_else(|| "not set".to_string()),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_query_params() {
+        assert_eq!(
+            redact_query_params(
+                "GET https://api.mod.io/v1/games/2475/mods?api_key=abc123&_limit=100 failed"
+            ),
+            "GET https://api.mod.io/v1/games/2475/mods?api_key=<redacted>&_limit=100 failed"
+        );
+        assert_eq!(
+            redact_query_params("\"https://example.com/a.pak?v=2&ACCESS_TOKEN=x.y-z#frag\""),
+            "\"https://example.com/a.pak?v=2&ACCESS_TOKEN=<redacted>#frag\""
+        );
+        // parameters merely ending in a secret name are kept
+        assert_eq!(
+            redact_query_params("https://example.com/?monkey=1&sort_key=2"),
+            "https://example.com/?monkey=1&sort_key=2"
+        );
+        assert_eq!(redact_query_params("api_key=abc"), "api_key=abc");
+    }
+
+    #[test]
+    fn test_normalize() {
+        let normalizer = PathNormalizer {
+            replacements: vec![
+                ("/home/user/.config/mint".to_string(), "<config_dir>"),
+                ("/home/user".to_string(), "~"),
+            ],
+        };
+        let mut value = serde_json::json!({
+            "path": "/home/user/.config/mint/config.json",
+            "mods": ["/home/user/mods/a.pak", "https://x.com/b.pak?token=secret"],
+        });
+        normalizer.normalize_value(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "path": "<config_dir>/config.json",
+                "mods": ["~/mods/a.pak", "https://x.com/b.pak?token=<redacted>"],
+            })
+        );
+    }
+}
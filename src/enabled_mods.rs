@@ -0,0 +1,126 @@
+//! The mods a profile installs, exactly as [`ModData_v0_3_0::get_enabled_mods_with_priority`]
+//! resolves them, for reproducing a pack or debugging load order outside of mint. Written as JSON,
+//! or as CSV for spreadsheets.
+//!
+//! [`ModData_v0_3_0::get_enabled_mods_with_priority`]: crate::state::ModData_v0_3_0::get_enabled_mods_with_priority
+
+use std::path::Path;
+
+use fs_err as fs;
+use serde::Serialize;
+
+use crate::state::State;
+
+/// Columns of the CSV export, in the order of the fields of [`EnabledMod`].
+const CSV_HEADER: &str = "name,url,version,priority,folder,priority_folder";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnabledMod {
+    /// Name of the mod, `None` if its info is not cached.
+    pub name: Option<String>,
+    pub url: String,
+    /// Version the mod is pinned to, or resolved to if it follows the latest version. `None` for
+    /// providers without numbered versions.
+    pub version: Option<u32>,
+    /// Priority the mod is installed with, after folder overrides.
+    pub priority: i32,
+    /// Innermost folder containing the mod, `None` at the top level.
+    pub folder: Option<String>,
+    /// Folder whose priority override applies, `None` if the mod's own priority is used.
+    pub priority_folder: Option<String>,
+}
+
+/// Enabled mods of `profile` in profile order. The profile has to exist.
+pub fn collect(state: &State, profile: &str) -> Vec<EnabledMod> {
+    state
+        .mod_data
+        .effective_mods(profile)
+        .into_iter()
+        .map(|m| {
+            let info = state.store.get_mod_info(&m.config.spec);
+            EnabledMod {
+                name: info.as_ref().map(|info| info.name.clone()),
+                version: m
+                    .config
+                    .spec
+                    .version_id()
+                    .or_else(|| info.as_ref()?.version_id()),
+                url: m.config.spec.url,
+                priority: m.priority,
+                folder: m.folder,
+                priority_folder: m.priority_folder,
+            }
+        })
+        .collect()
+}
+
+pub fn to_json(mods: &[EnabledMod]) -> String {
+    serde_json::to_string_pretty(mods).unwrap()
+}
+
+pub fn to_csv(mods: &[EnabledMod]) -> String {
+    let mut csv = format!("{CSV_HEADER}\n");
+    for m in mods {
+        let fields = [
+            csv_field(m.name.as_deref().unwrap_or_default()),
+            csv_field(&m.url),
+            m.version.map(|v| v.to_string()).unwrap_or_default(),
+            m.priority.to_string(),
+            csv_field(m.folder.as_deref().unwrap_or_default()),
+            csv_field(m.priority_folder.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote `value` if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write `mods` to `path`, as CSV if it ends in `.csv` and as JSON otherwise.
+pub fn write(path: &Path, mods: &[EnabledMod]) -> std::io::Result<()> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    fs::write(path, if is_csv { to_csv(mods) } else { to_json(mods) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let mods = [
+            EnabledMod {
+                name: Some("Better \"Mod\", v2".to_string()),
+                url: "https://mod.io/g/drg/m/better-mod#1/2".to_string(),
+                version: Some(2),
+                priority: 50,
+                folder: Some("QoL".to_string()),
+                priority_folder: None,
+            },
+            EnabledMod {
+                name: None,
+                url: "C:\\mods\\local.pak".to_string(),
+                version: None,
+                priority: -1,
+                folder: None,
+                priority_folder: None,
+            },
+        ];
+        assert_eq!(
+            to_csv(&mods),
+            "name,url,version,priority,folder,priority_folder\n\
+             \"Better \"\"Mod\"\", v2\",https://mod.io/g/drg/m/better-mod#1/2,2,50,QoL,\n\
+             ,C:\\mods\\local.pak,,-1,,\n"
+        );
+    }
+}
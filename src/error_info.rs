@@ -0,0 +1,316 @@
+//! User-facing descriptions of errors. Each failure gets a stable code to quote in bug reports and
+//! search the README for, its cause in one line and what to do about it. The errors' `Display`
+//! stays as is for logs; the full chain is kept for "copy details".
+//!
+//! Codes are grouped by where the error comes from: `M` for mint itself, `P` for mod providers and
+//! `I` for integration. Codes are never reused for a different failure.
+
+use std::error::Error;
+use std::fmt::Write as _;
+
+use crate::MintError;
+use crate::integrate::IntegrationError;
+use crate::providers::ProviderError;
+use crate::providers::modio::DrgModioError;
+use crate::state::StateError;
+
+const REPORT_BUG: &str = "This is likely a bug in mint, please report it with the details.";
+const CHECK_CONNECTION: &str = "Check your internet connection and try again. If it keeps \
+                                failing, run the health check in the settings.";
+const CHECK_URL: &str = "Check that the mod URL or path is correct.";
+const MODIO_UNAVAILABLE: &str =
+    "mod.io could not be reached or refused the request. Try again in a few minutes.";
+const CLEAR_CACHE: &str = "Check that the cache directory is writable and has free space. \
+                           Deleting cache.json in it makes mint rebuild the cache.";
+
+#[derive(Debug, Clone)]
+pub struct ErrorInfo {
+    pub code: &'static str,
+    /// What went wrong, in one line.
+    pub cause: String,
+    /// What the user can do about it.
+    pub action: &'static str,
+    /// The error and all its sources.
+    pub details: String,
+}
+
+impl ErrorInfo {
+    fn new(code: &'static str, error: &(dyn Error + 'static), action: &'static str) -> Self {
+        Self {
+            code,
+            cause: error.to_string(),
+            action,
+            details: chain(error),
+        }
+    }
+
+    /// Code and cause on one line, for status bars.
+    pub fn summary(&self) -> String {
+        format!("[{}] {}", self.code, self.cause)
+    }
+
+    /// Everything known about the error, for pasting into bug reports.
+    pub fn report(&self) -> String {
+        format!(
+            "error {}: {}\nsuggested action: {}\n\n{}",
+            self.code, self.cause, self.action, self.details
+        )
+    }
+}
+
+/// The error followed by its sources, one per line.
+fn chain(error: &(dyn Error + 'static)) -> String {
+    let mut details = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        write!(details, "\ncaused by: {e}").unwrap();
+        source = e.source();
+    }
+    details
+}
+
+impl MintError {
+    pub fn info(&self) -> ErrorInfo {
+        let (code, action) = match self {
+            MintError::ProviderError { source } => return source.info(),
+            MintError::IntegrationError { source } => return source.info(),
+            MintError::StateError {
+                source: StateError::ProviderError { source },
+            } => return source.info(),
+            MintError::StateError {
+                source:
+                    StateError::NoSuchProfile { .. }
+                    | StateError::ProfileSerializationFailed { .. }
+                    | StateError::ProfileDeserializationFailed { .. },
+            } => (
+                "M008",
+                "Check that the file is a .mintprofile exported by mint.",
+            ),
+            MintError::StateError {
+                source: StateError::SettingsDeserializationFailed { .. },
+            } => (
+                "M009",
+                "Check that the file is settings exported by mint with \"Export settings\".",
+            ),
+            MintError::StateError { .. } => (
+                "M003",
+                "config.json or mod_data.json in the config directory could not be read or \
+                 written. Restore them from a backup, or move them away to start fresh.",
+            ),
+            MintError::IoError { .. } => (
+                "M001",
+                "Check that mint's directories are writable and the drive has free space.",
+            ),
+            MintError::RepakError { .. } => (
+                "M002",
+                "The pak could not be read. Verify the game files, or remove the mod if it is a \
+                 mod's pak.",
+            ),
+            MintError::InvalidDrgPak { .. } => (
+                "M004",
+                "Select FSD-WindowsNoEditor.pak (FSD-WinGDK.pak for the Microsoft Store version) \
+                 in the settings.",
+            ),
+            MintError::RelocateDir { .. } => (
+                "M005",
+                "Choose an empty directory outside of mint's other directories.",
+            ),
+            MintError::Workspace { .. } => (
+                "M010",
+                "Pick a workspace listed in the workspace selector, or add it there first.",
+            ),
+            MintError::DiagnosticsError { .. } => (
+                "M006",
+                "Check that the output directory is writable and try again.",
+            ),
+            MintError::ImportError { .. } => (
+                "M007",
+                "Check that the file is an export of a supported mod manager, or pick the \
+                 importer by hand.",
+            ),
+            MintError::GenericError { .. } => ("M000", REPORT_BUG),
+        };
+        ErrorInfo::new(code, self, action)
+    }
+}
+
+impl ProviderError {
+    pub fn info(&self) -> ErrorInfo {
+        let (code, action) = match self {
+            ProviderError::InitProviderFailed { .. } => (
+                "P001",
+                "Check the provider's settings, such as the mod.io OAuth token, under \"Mod \
+                 providers\" in the settings.",
+            ),
+            ProviderError::NoProvider { .. } => (
+                "P002",
+                "Configure the provider for this mod, e.g. add a mod.io OAuth token.",
+            ),
+            ProviderError::ProviderNotFound { .. } | ProviderError::InvalidUrl { .. } => {
+                ("P003", CHECK_URL)
+            }
+            ProviderError::PreviewLink { .. } => (
+                "P004",
+                "Subscribe to the mod on mod.io and add it by its normal link.",
+            ),
+            ProviderError::NoAssociatedModfile { .. } => (
+                "P005",
+                "The mod has no file uploaded. Remove it or try again once the author uploads one.",
+            ),
+            ProviderError::AmbiguousModNameId { .. } => (
+                "P006",
+                "Add the mod by its full mod.io URL instead of its name.",
+            ),
+            ProviderError::NoModsForNameId { .. } => (
+                "P007",
+                "The mod was removed from mod.io or renamed. Search for it on mod.io and add it \
+                 again.",
+            ),
+            ProviderError::RequestFailed { .. }
+            | ProviderError::ResponseError { .. }
+            | ProviderError::FetchError { .. } => ("P010", CHECK_CONNECTION),
+            #[cfg(feature = "torrent")]
+            ProviderError::TorrentError { .. } => (
+                "P013",
+                "The torrent could not be downloaded. Check that it has seeders and that your \
+                 network allows BitTorrent traffic.",
+            ),
+            ProviderError::InvalidMime { .. } | ProviderError::UnexpectedContentType { .. } => (
+                "P011",
+                "The link does not lead to a mod file. Use a direct download link to a .pak, \
+                 .zip, .7z or .rar.",
+            ),
+            ProviderError::LinkExpired { .. } => (
+                "P012",
+                "Replace the link with a new one, or add a refresh rule for its host in the http \
+                 provider settings.",
+            ),
+            ProviderError::Unauthorized { .. } => (
+                "P016",
+                "The server does not allow downloading the file. If it needs a login token, add \
+                 it as a request header for its host in the http provider settings.",
+            ),
+            ProviderError::CaptivePortal { .. } => (
+                "P015",
+                "Open any website in your browser to log in to the network, e.g. a hotel's \
+                 Wi-Fi, then try again.",
+            ),
+            ProviderError::SignatureMismatch { .. } => (
+                "P017",
+                "The archive was changed after it was signed, possibly by someone other than its \
+                 author. The download was not kept; tell the mod's author.",
+            ),
+            ProviderError::ContentMismatch { .. } => (
+                "P018",
+                "The IPFS gateways did not serve the content the link names. Add a gateway you \
+                 trust, such as your own node, in the ipfs provider settings.",
+            ),
+            ProviderError::ScanFailed { .. } => (
+                "P014",
+                "The scan command in the settings flagged the mod or could not run. Remove the \
+                 mod, or check the command and its output in the log.",
+            ),
+            ProviderError::CacheError { .. }
+            | ProviderError::BlobCacheError { .. }
+            | ProviderError::BufferIoError { .. }
+            | ProviderError::ModCtxtIoError { .. } => ("P020", CLEAR_CACHE),
+            ProviderError::ModCtxtModioError { .. } => ("P030", MODIO_UNAVAILABLE),
+            ProviderError::DrgModioError { source } => match source {
+                DrgModioError::MissingOauthToken => (
+                    "P031",
+                    "Add a mod.io OAuth token under \"Mod providers\" in the settings.",
+                ),
+                DrgModioError::CheckFailed { .. } => (
+                    "P032",
+                    "The mod.io OAuth token was rejected. Create a new one on mod.io and enter \
+                     it under \"Mod providers\" in the settings.",
+                ),
+                DrgModioError::LoginRefused { .. } => (
+                    "P033",
+                    "Check the email address, or enter the code from the newest email. Codes \
+                     expire after 15 minutes.",
+                ),
+                DrgModioError::LoginRequestFailed { .. } => ("P010", CHECK_CONNECTION),
+                _ => ("P030", MODIO_UNAVAILABLE),
+            },
+        };
+        ErrorInfo::new(code, self, action)
+    }
+}
+
+impl IntegrationError {
+    pub fn info(&self) -> ErrorInfo {
+        let (code, action) = match self {
+            IntegrationError::ProviderError { source } => return source.info(),
+            IntegrationError::DrgInstallationNotFound { .. } => (
+                "I001",
+                "Select the pak inside the \"Deep Rock Galactic\" directory under \
+                 FSD/Content/Paks in the settings.",
+            ),
+            IntegrationError::FileLocked { .. } => (
+                "I002",
+                "Close the game and the programs named above, then install again.",
+            ),
+            IntegrationError::IoError { .. } => (
+                "I003",
+                "Check that the game directory is writable and its drive has free space. The \
+                 health check in the settings tests both.",
+            ),
+            IntegrationError::RepakError { .. } | IntegrationError::UnrealAssetError { .. } => (
+                "I004",
+                "The game's own files could not be read. Verify the game files, then install \
+                 again.",
+            ),
+            IntegrationError::CtxtIoError { .. }
+            | IntegrationError::CtxtRepakError { .. }
+            | IntegrationError::ModfileInvalidPrefix { .. }
+            | IntegrationError::CtxtGenericError { .. } => (
+                "I005",
+                "The mod's file is damaged or not a valid mod. Disable it, or update the cache \
+                 to download it again, and let its author know if it persists.",
+            ),
+            IntegrationError::LintError { .. } => (
+                "I006",
+                "A mod could not be checked. Disable the lint or the mod it names.",
+            ),
+            IntegrationError::SelfUpdateFailed { .. } => (
+                "I007",
+                "Download the latest release from GitHub and replace mint by hand.",
+            ),
+            IntegrationError::GenericError { .. } | IntegrationError::JoinError { .. } => {
+                ("I000", REPORT_BUG)
+            }
+        };
+        ErrorInfo::new(code, self, action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain() {
+        let error = IntegrationError::FileLocked {
+            path: "mods_P.pak".into(),
+            lockers: vec!["OneDrive"],
+            source: std::io::Error::other("sharing violation"),
+        };
+        let info = error.info();
+        assert_eq!(info.code, "I002");
+        assert!(info.summary().starts_with("[I002] mods_P.pak is in use"));
+        assert!(info.details.ends_with("\ncaused by: sharing violation"));
+    }
+
+    #[test]
+    fn test_transparent_errors_keep_their_code() {
+        let error = MintError::IntegrationError {
+            source: IntegrationError::ProviderError {
+                source: ProviderError::DrgModioError {
+                    source: DrgModioError::MissingOauthToken,
+                },
+            },
+        };
+        assert_eq!(error.info().code, "P031");
+    }
+}
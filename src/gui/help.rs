@@ -0,0 +1,143 @@
+//! Built-in help topics. The content is compiled into the binary and rendered with the
+//! CommonMark viewer.
+
+use crate::mod_lints::LintId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    Overview,
+    Profiles,
+    Priorities,
+    Folders,
+    ApprovalCategories,
+    RequiredTags,
+    Lints,
+    /// What the findings of a lint mean and how to fix them.
+    Lint(LintId),
+}
+
+impl HelpTopic {
+    /// All topics, each lint's page following the lints overview.
+    pub fn iter() -> impl Iterator<Item = HelpTopic> {
+        [
+            HelpTopic::Overview,
+            HelpTopic::Profiles,
+            HelpTopic::Priorities,
+            HelpTopic::Folders,
+            HelpTopic::ApprovalCategories,
+            HelpTopic::RequiredTags,
+            HelpTopic::Lints,
+        ]
+        .into_iter()
+        .chain(LintId::ALL.map(HelpTopic::Lint))
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            HelpTopic::Overview => "Getting started",
+            HelpTopic::Profiles => "Profiles and loadouts",
+            HelpTopic::Priorities => "Load priority",
+            HelpTopic::Folders => "Folders",
+            HelpTopic::ApprovalCategories => "Approval categories",
+            HelpTopic::RequiredTags => "Required tags",
+            HelpTopic::Lints => "Lints",
+            // the page's heading
+            HelpTopic::Lint(_) => self
+                .content()
+                .lines()
+                .next()
+                .and_then(|heading| heading.strip_prefix("# "))
+                .unwrap_or("Lint"),
+        }
+    }
+
+    pub fn content(self) -> &'static str {
+        match self {
+            HelpTopic::Overview => include_str!("help/overview.md"),
+            HelpTopic::Profiles => include_str!("help/profiles.md"),
+            HelpTopic::Priorities => include_str!("help/priorities.md"),
+            HelpTopic::Folders => include_str!("help/folders.md"),
+            HelpTopic::ApprovalCategories => include_str!("help/approval.md"),
+            HelpTopic::RequiredTags => include_str!("help/required.md"),
+            HelpTopic::Lints => include_str!("help/lints.md"),
+            HelpTopic::Lint(id) => lint_page(id),
+        }
+    }
+
+    fn matches(self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.title().to_lowercase().contains(&query)
+            || self.content().to_lowercase().contains(&query)
+    }
+}
+
+pub struct WindowHelp {
+    pub topic: HelpTopic,
+    pub search: String,
+}
+
+impl WindowHelp {
+    pub fn new(topic: HelpTopic) -> Self {
+        Self {
+            topic,
+            search: String::new(),
+        }
+    }
+
+    pub fn matching_topics(&self) -> impl Iterator<Item = HelpTopic> + '_ {
+        HelpTopic::iter().filter(|t| t.matches(&self.search))
+    }
+}
+
+fn lint_page(id: LintId) -> &'static str {
+    match id {
+        LintId::CONFLICTING => include_str!("help/lints/conflicting.md"),
+        LintId::ASSET_REGISTRY_BIN => include_str!("help/lints/asset_registry_bin.md"),
+        LintId::SHADER_FILES => include_str!("help/lints/shader_files.md"),
+        LintId::OUTDATED_PAK_VERSION => include_str!("help/lints/outdated_pak_version.md"),
+        LintId::EMPTY_ARCHIVE => include_str!("help/lints/empty_archive.md"),
+        LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
+            include_str!("help/lints/archive_only_non_pak_files.md")
+        }
+        LintId::ARCHIVE_WITH_MULTIPLE_PAKS => {
+            include_str!("help/lints/archive_with_multiple_paks.md")
+        }
+        LintId::NON_ASSET_FILES => include_str!("help/lints/non_asset_files.md"),
+        LintId::SPLIT_ASSET_PAIRS => include_str!("help/lints/split_asset_pairs.md"),
+        LintId::UNMODIFIED_GAME_ASSETS => include_str!("help/lints/unmodified_game_assets.md"),
+        LintId::CONFIG_OVERRIDES => include_str!("help/lints/config_overrides.md"),
+        LintId::UNSUPPORTED_COMPRESSION => include_str!("help/lints/unsupported_compression.md"),
+        LintId::MOUNT_POINT => include_str!("help/lints/mount_point.md"),
+        LintId::LOCALIZATION_CONFLICTS => include_str!("help/lints/localization_conflicts.md"),
+        _ => "# Lint\n\nThere is no page for this lint yet.",
+    }
+}
+
+fn request_id() -> egui::Id {
+    egui::Id::new("help-requested-topic")
+}
+
+/// Small "?" button which opens the help window at `topic`. Usable from any nested UI closure
+/// since the request goes through egui memory rather than the `App`.
+pub fn button(ui: &mut egui::Ui, topic: HelpTopic) -> egui::Response {
+    let res = ui
+        .small_button("?")
+        .on_hover_text(format!("Help: {}", topic.title()));
+    if res.clicked() {
+        open(ui.ctx(), topic);
+    }
+    res
+}
+
+/// Open the help window at `topic`, like a [`button`] does.
+pub fn open(ctx: &egui::Context, topic: HelpTopic) {
+    ctx.data_mut(|d| d.insert_temp(request_id(), topic));
+    // the help window may already have been drawn this frame
+    ctx.request_repaint();
+}
+
+/// Take the topic requested by a [`button`] this frame, if any.
+pub fn take_request(ctx: &egui::Context) -> Option<HelpTopic> {
+    ctx.data_mut(|d| d.remove_temp::<HelpTopic>(request_id()))
+}
@@ -1,29 +1,40 @@
-use std::collections::BTreeSet;
-use std::ops::DerefMut;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Deref, DerefMut};
 use std::time::SystemTime;
 use std::{collections::HashMap, sync::Arc};
 
 use snafu::prelude::*;
 use tokio::{
-    sync::mpsc::{self, Sender},
+    sync::{
+        mpsc::{self, Sender},
+        oneshot,
+    },
     task::JoinHandle,
 };
 use tracing::*;
 
 use super::SelfUpdateProgress;
 use super::{
-    App, SpecFetchProgress, WindowProviderParameters,
+    App, SpecFetchProgress, Thumbnail, WindowDownloadEstimate, WindowHealthCheck,
+    WindowIntegrationWarnings, WindowLintReport, WindowPreview, WindowProviderParameters,
+    WindowRefreshLink, WindowUnlistedMods,
     request_counter::{RequestCounter, RequestID},
 };
 use crate::gui::LastAction;
+use crate::health::CheckResult;
+use crate::integrate::payload::ModPayload;
 use crate::integrate::*;
-use crate::mod_lints::{LintId, LintReport};
-use crate::state::{ModData_v0_2_0 as ModData, ModOrGroup};
+use crate::mod_lints::{LINT_CACHE_FILE, LintCache, LintId, LintProgress, LintReport};
+use crate::mod_list::ListedMod;
+use crate::preview::ModPreview;
+use crate::saves::SnapshotBeforeInstall;
+use crate::state::{ModData_v0_3_0 as ModData, ModOrGroup, PayloadRule, UpdatePolicy};
 use crate::*;
 use crate::{
-    providers::{FetchProgress, ModInfo, ModStore},
+    providers::{DownloadEstimate, FetchProgress, ModInfo, ModStore, Pause},
     state::ModConfig,
 };
+use mint_lib::DRGInstallation;
 use mint_lib::error::GenericError;
 use mint_lib::mod_info::MetaConfig;
 use mint_lib::update::GitHubRelease;
@@ -43,8 +54,14 @@ pub enum Message {
     UpdateCache(UpdateCache),
     CheckUpdates(CheckUpdates),
     LintMods(Box<LintMods>),
+    LintModsProgress(LintModsProgress),
     SelfUpdate(SelfUpdate),
     FetchSelfUpdateProgress(FetchSelfUpdateProgress),
+    PreviewMod(PreviewMod),
+    HealthCheck(HealthCheck),
+    EstimateDownload(EstimateDownload),
+    Backup(Backup),
+    FetchThumbnail(FetchThumbnail),
 }
 
 impl Message {
@@ -56,18 +73,119 @@ impl Message {
             Self::UpdateCache(msg) => msg.receive(app),
             Self::CheckUpdates(msg) => msg.receive(app),
             Self::LintMods(msg) => msg.receive(app),
+            Self::LintModsProgress(msg) => msg.receive(app),
             Self::SelfUpdate(msg) => msg.receive(app),
             Self::FetchSelfUpdateProgress(msg) => msg.receive(app),
+            Self::PreviewMod(msg) => msg.receive(app),
+            Self::HealthCheck(msg) => msg.receive(app),
+            Self::EstimateDownload(msg) => msg.receive(app),
+            Self::Backup(msg) => msg.receive(app),
+            Self::FetchThumbnail(msg) => msg.receive(app),
         }
     }
 }
 
+/// An install, cache update or lint with its parameters, kept until it completes so it can be
+/// retried as it was if it fails. These never run at the same time.
+#[derive(Debug, Clone)]
+pub enum Operation {
+    Integrate {
+        mods: Vec<ModSpecification>,
+        payload_rules: BTreeMap<String, Vec<PayloadRule>>,
+        asset_overrides: BTreeMap<String, String>,
+        save_snapshot: Option<SnapshotBeforeInstall>,
+        /// `None` to only download the mods.
+        fsd_pak: Option<PathBuf>,
+    },
+    UpdateCache,
+    LintMods {
+        mods: Vec<ModSpecification>,
+        enabled_lints: BTreeSet<LintId>,
+        game_pak_path: Option<PathBuf>,
+    },
+}
+
+impl Operation {
+    pub fn dispatch(self, app: &mut App, ctx: &egui::Context) {
+        app.last_action = None;
+        app.pause = Pause::default();
+        match &self {
+            Operation::Integrate {
+                mods,
+                payload_rules,
+                asset_overrides,
+                save_snapshot,
+                fsd_pak,
+            } => {
+                app.record_feature(if fsd_pak.is_some() {
+                    "install_mods"
+                } else {
+                    "download_mods"
+                });
+                app.integration_warnings_window = None;
+                let backup = if fsd_pak.is_some() && app.state.config.auto_backup.before_install {
+                    let dir = app.backup_dir();
+                    Backup::send(app, ctx, dir, backup::Trigger::Install)
+                } else {
+                    None
+                };
+                app.integrate_rid = Some(Integrate::send(
+                    &mut app.request_counter,
+                    app.state.store.clone(),
+                    mods.clone(),
+                    payload_rules.clone(),
+                    asset_overrides.clone(),
+                    save_snapshot.clone(),
+                    fsd_pak.clone(),
+                    app.state.config.deref().into(),
+                    app.state.config.memory_budget,
+                    app.pause.clone(),
+                    backup,
+                    app.tx.clone(),
+                    ctx.clone(),
+                ));
+            }
+            Operation::UpdateCache => {
+                app.record_feature("update_cache");
+                UpdateCache::send(app);
+            }
+            Operation::LintMods {
+                mods,
+                enabled_lints,
+                game_pak_path,
+            } => {
+                app.record_feature("lint_mods");
+                app.lint_report = None;
+                app.lint_profile = Some(app.state.mod_data.active_profile.clone());
+                app.lint_rid = Some(LintMods::send(
+                    &mut app.request_counter,
+                    app.state.store.clone(),
+                    mods.clone(),
+                    enabled_lints.clone(),
+                    game_pak_path.clone(),
+                    app.state.config.memory_budget,
+                    app.state.dirs.cache_dir.join(LINT_CACHE_FILE),
+                    app.tx.clone(),
+                    ctx.clone(),
+                ));
+                app.lint_report_window = Some(WindowLintReport::default());
+            }
+        }
+        app.problematic_mod_id = None;
+        app.last_operation = Some(self);
+    }
+}
+
 #[derive(Debug)]
 pub struct ResolveMods {
     rid: RequestID,
     specs: Vec<ModSpecification>,
     result: Result<HashMap<ModSpecification, ModInfo>, ProviderError>,
+    /// Readable names of the resolved local files, by URL.
+    derived_names: HashMap<String, String>,
     is_dependency: bool,
+    /// Version, enabled state and priority of the mods added from a mod list.
+    listed: HashMap<ModSpecification, ListedMod>,
 }
 
 impl ResolveMods {
@@ -76,6 +194,31 @@ impl ResolveMods {
         ctx: &egui::Context,
         specs: Vec<ModSpecification>,
         is_dependency: bool,
+    ) {
+        app.record_feature(if is_dependency {
+            "add_dependencies"
+        } else {
+            "add_mods"
+        });
+        Self::spawn(app, ctx, specs, is_dependency, HashMap::new());
+    }
+
+    /// Add the mods of a [`crate::mod_list`] with the versions, enabled states and priorities it
+    /// lists. Mods the active profile already has are changed to match, and enabled mods it has
+    /// which the list does not are offered to be disabled, see [`super::WindowUnlistedMods`].
+    pub fn send_list(app: &mut App, ctx: &egui::Context, mods: Vec<ListedMod>) {
+        app.record_feature("import_mod_list");
+        let specs = mods.iter().map(|m| m.spec.clone()).collect();
+        let listed = mods.into_iter().map(|m| (m.spec.clone(), m)).collect();
+        Self::spawn(app, ctx, specs, false, listed);
+    }
+
+    fn spawn(
+        app: &mut App,
+        ctx: &egui::Context,
+        specs: Vec<ModSpecification>,
+        is_dependency: bool,
+        listed: HashMap<ModSpecification, ListedMod>,
     ) {
         let rid = app.request_counter.next();
         let store = app.state.store.clone();
@@ -83,11 +226,30 @@ impl ResolveMods {
         let tx = app.tx.clone();
         let handle = tokio::spawn(async move {
             let result = store.resolve_mods(&specs, false).await;
+            let files = result
+                .iter()
+                .flat_map(|mods| mods.values())
+                .filter(|info| info.provider == "file")
+                .map(|info| info.spec.url.clone())
+                .collect::<Vec<_>>();
+            let derived_names = tokio::task::spawn_blocking(move || {
+                files
+                    .into_iter()
+                    .filter_map(|url| {
+                        let name = providers::file::derive_name(Path::new(&url))?;
+                        Some((url, name))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .await
+            .unwrap_or_default();
             tx.send(Message::ResolveMods(Self {
                 rid,
                 specs,
                 result,
+                derived_names,
                 is_dependency,
+                listed,
             }))
             .await
             .unwrap();
@@ -105,13 +267,43 @@ impl ResolveMods {
         if Some(self.rid) == app.resolve_mod_rid.as_ref().map(|r| r.rid) {
             match self.result {
                 Ok(resolved_mods) => {
+                    let mut named_local = false;
                     let primary_mods = self
                         .specs
                         .into_iter()
                         .collect::<HashSet<ModSpecification>>();
+                    let resolved_specs = resolved_mods.keys().cloned().collect::<Vec<_>>();
                     for (resolved_spec, info) in resolved_mods {
                         let is_dep = self.is_dependency || !primary_mods.contains(&resolved_spec);
-                        let add = if is_dep {
+                        let listed = self.listed.get(&resolved_spec);
+                        let config = listed.map(|listed| ModConfig {
+                            spec: listed.spec.clone(),
+                            required: info.suggested_require,
+                            enabled: listed.enabled,
+                            priority: listed.priority,
+                            update_policy: if listed.spec.version_id().is_some() {
+                                UpdatePolicy::Pinned
+                            } else {
+                                UpdatePolicy::Latest
+                            },
+                            tags: BTreeSet::new(),
+                        });
+                        let add = if let Some(config) = &config {
+                            // a listed mod the profile has already is changed to match the list
+                            let active_profile = app.state.mod_data.active_profile.clone();
+                            !app.state.mod_data.any_mod_mut(&active_profile, |mc, _| {
+                                if mc.spec.same_mod(&config.spec) {
+                                    *mc = ModConfig {
+                                        required: mc.required,
+                                        tags: std::mem::take(&mut mc.tags),
+                                        ..config.clone()
+                                    };
+                                    true
+                                } else {
+                                    false
+                                }
+                            })
+                        } else if is_dep {
                             // if mod is a dependency then check if there is a disabled
                             // mod that satisfies the dependency and enable it. if it
                             // is not a dependency then assume the user explicitly
@@ -144,20 +336,57 @@ impl ResolveMods {
 
                             profiles.get_mut(active_profile).unwrap().mods.insert(
                                 0,
-                                ModOrGroup::Individual(ModConfig {
+                                ModOrGroup::Individual(config.unwrap_or_else(|| ModConfig {
                                     spec: info.spec.clone(),
                                     required: info.suggested_require,
                                     enabled: true,
                                     priority: 0,
-                                }),
+                                    update_policy: UpdatePolicy::Latest,
+                                    tags: BTreeSet::new(),
+                                })),
                             );
+
+                            // name local files after their content rather than their path, unless
+                            // the user already named them
+                            if let Some(name) = self.derived_names.get(&info.spec.url) {
+                                let metadata = app
+                                    .state
+                                    .mod_data
+                                    .local_metadata
+                                    .entry(info.spec.url.clone())
+                                    .or_default();
+                                if metadata.name.is_none() {
+                                    metadata.name = Some(name.clone());
+                                    named_local = true;
+                                }
+                            }
                         }
                     }
+                    if named_local {
+                        app.state
+                            .store
+                            .set_local_metadata(app.state.mod_data.local_metadata.clone());
+                    }
                     app.resolve_mod.clear();
                     app.state.mod_data.save().unwrap();
                     app.last_action = Some(LastAction::success(
                         "mods successfully resolved".to_string(),
                     ));
+                    if !self.listed.is_empty() {
+                        let profile = app.state.mod_data.active_profile.clone();
+                        let urls = app
+                            .state
+                            .mod_data
+                            .effective_mods(&profile)
+                            .into_iter()
+                            .map(|m| m.config.spec)
+                            .filter(|spec| !resolved_specs.iter().any(|r| r.same_mod(spec)))
+                            .map(|spec| spec.url)
+                            .collect::<Vec<_>>();
+                        if !urls.is_empty() {
+                            app.unlisted_mods_window = Some(WindowUnlistedMods { profile, urls });
+                        }
+                    }
                 }
                 Err(ProviderError::NoProvider { url: _, factory }) => {
                     app.window_provider_parameters =
@@ -167,7 +396,7 @@ impl ResolveMods {
                 Err(e) => {
                     error!("{}", e);
                     app.problematic_mod_id = e.opt_mod_id();
-                    app.last_action = Some(LastAction::failure(e.to_string()));
+                    app.last_action = Some(app.error_action(e.info()));
                 }
             }
             app.resolve_mod_rid = None;
@@ -178,29 +407,63 @@ impl ResolveMods {
 #[derive(Debug)]
 pub struct Integrate {
     rid: RequestID,
-    result: Result<(), IntegrationError>,
+    mods: Vec<ModSpecification>,
+    /// Only fetch the mods into the cache without installing them.
+    download_only: bool,
+    result: Result<Vec<IntegrationWarning>, IntegrationError>,
 }
 
 impl Integrate {
+    /// Fetch `mods` and integrate them into the game at `fsd_pak`, or only fetch them into the
+    /// cache if it is `None`. While `pause` is set, the mods not being downloaded yet are held and
+    /// integrating waits. If `backup` is given, nothing happens until that backup is done.
     pub fn send(
         rc: &mut RequestCounter,
         store: Arc<ModStore>,
         mods: Vec<ModSpecification>,
-        fsd_pak: PathBuf,
+        payload_rules: BTreeMap<String, Vec<PayloadRule>>,
+        asset_overrides: BTreeMap<String, String>,
+        save_snapshot: Option<SnapshotBeforeInstall>,
+        fsd_pak: Option<PathBuf>,
         config: MetaConfig,
+        memory_budget: u64,
+        pause: Pause,
+        backup: Option<oneshot::Receiver<()>>,
         tx: Sender<Message>,
         ctx: egui::Context,
     ) -> MessageHandle<HashMap<ModSpecification, SpecFetchProgress>> {
         let rid = rc.next();
+        let download_only = fsd_pak.is_none();
         MessageHandle {
             rid,
             handle: tokio::task::spawn(async move {
-                let res =
-                    integrate_async(store, ctx.clone(), mods, fsd_pak, config, rid, tx.clone())
-                        .await;
-                tx.send(Message::Integrate(Integrate { rid, result: res }))
-                    .await
-                    .unwrap();
+                if let Some(backup) = backup {
+                    // failed backups are reported on their own
+                    backup.await.ok();
+                }
+                let res = integrate_async(
+                    store,
+                    ctx.clone(),
+                    mods.clone(),
+                    payload_rules,
+                    asset_overrides,
+                    save_snapshot,
+                    fsd_pak,
+                    config,
+                    memory_budget,
+                    pause,
+                    rid,
+                    tx.clone(),
+                )
+                .await;
+                tx.send(Message::Integrate(Integrate {
+                    rid,
+                    mods,
+                    download_only,
+                    result: res,
+                }))
+                .await
+                .unwrap();
                 ctx.request_repaint();
             }),
             state: Default::default(),
@@ -209,10 +472,41 @@ impl Integrate {
 
     fn receive(self, app: &mut App) {
         if Some(self.rid) == app.integrate_rid.as_ref().map(|r| r.rid) {
+            // only launch after a startup install if it actually succeeded
+            let launch = std::mem::take(&mut app.startup.launch) && !self.download_only;
+            if !self.download_only {
+                crate::diagnostics::IntegrationReport::new(&self.mods, self.result.as_ref().err())
+                    .with_warnings(self.result.as_deref().unwrap_or_default())
+                    .save(&app.state.dirs.data_dir);
+            }
             match self.result {
-                Ok(()) => {
+                Ok(_) if self.download_only => {
+                    info!("download complete");
+                    app.last_operation = None;
+                    app.last_action = Some(LastAction::success(format!(
+                        "{} mods downloaded, ready to install offline",
+                        self.mods.len()
+                    )));
+                }
+                Ok(warnings) => {
                     info!("integration complete");
-                    app.last_action = Some(LastAction::success("integration complete".to_string()));
+                    app.last_operation = None;
+                    app.missing_install = None;
+                    app.install_changes = None;
+                    if warnings.is_empty() {
+                        app.last_action =
+                            Some(LastAction::success("integration complete".to_string()));
+                    } else {
+                        app.last_action = Some(LastAction::success(format!(
+                            "integration complete with {} warnings",
+                            warnings.len()
+                        )));
+                        app.integration_warnings_window =
+                            Some(WindowIntegrationWarnings { warnings });
+                    }
+                    if launch {
+                        app.launch_game();
+                    }
                 }
                 Err(ref e)
                     if let IntegrationError::ProviderError { source } = e
@@ -220,12 +514,32 @@ impl Integrate {
                 {
                     app.window_provider_parameters =
                         Some(WindowProviderParameters::new(factory, &app.state));
-                    app.last_action = Some(LastAction::failure("no provider".to_string()));
+                    app.last_action = Some(
+                        LastAction::failure("no provider".to_string())
+                            .with_retry(app.last_operation.take()),
+                    );
+                }
+                // retrying would only fail again, so ask for a new link instead
+                Err(ref e)
+                    if let IntegrationError::ProviderError { source } = e
+                        && let ProviderError::LinkExpired { url } = source =>
+                {
+                    error!("{}", e);
+                    app.refresh_link_window = Some(WindowRefreshLink::new(
+                        &app.state,
+                        url.clone(),
+                        self.download_only,
+                    ));
+                    app.last_operation = None;
+                    app.last_action = Some(app.error_action(e.info()));
                 }
                 Err(e) => {
                     error!("{}", e);
                     app.problematic_mod_id = e.opt_mod_id();
-                    app.last_action = Some(LastAction::failure(e.to_string()));
+                    app.last_action = Some(
+                        app.error_action(e.info())
+                            .with_retry(app.last_operation.take()),
+                    );
                 }
             }
             app.integrate_rid = None;
@@ -253,6 +567,8 @@ impl FetchModProgress {
 #[derive(Debug)]
 pub struct UpdateCache {
     rid: RequestID,
+    /// Versions from before the update of the mods whose update policy may keep them on it.
+    held: HashMap<ModSpecification, ModSpecification>,
     result: Result<(), ProviderError>,
 }
 
@@ -261,11 +577,17 @@ impl UpdateCache {
         let rid = app.request_counter.next();
         let tx = app.tx.clone();
         let store = app.state.store.clone();
+        let held = app.state.held_versions();
+        let pause = app.pause.clone();
         let handle = tokio::spawn(async move {
-            let res = store.update_cache().await;
-            tx.send(Message::UpdateCache(UpdateCache { rid, result: res }))
-                .await
-                .unwrap();
+            let res = store.update_cache(&pause).await;
+            tx.send(Message::UpdateCache(UpdateCache {
+                rid,
+                held,
+                result: res,
+            }))
+            .await
+            .unwrap();
         });
         app.last_action = None;
         app.update_rid = Some(MessageHandle {
@@ -280,19 +602,34 @@ impl UpdateCache {
             match self.result {
                 Ok(()) => {
                     info!("cache update complete");
-                    app.last_action = Some(LastAction::success(
-                        "successfully updated cache".to_string(),
-                    ));
+                    app.state.config.last_cache_update = Some(chrono::Local::now().to_rfc3339());
+                    app.state.config.save().unwrap();
+                    let held_back = app.state.apply_update_policies(&self.held);
+                    app.last_operation = None;
+                    app.last_action = Some(LastAction::success(if held_back.is_empty() {
+                        "successfully updated cache".to_string()
+                    } else {
+                        format!(
+                            "successfully updated cache, held back sandboxed updates of {}",
+                            held_back.join(", ")
+                        )
+                    }));
                 }
                 Err(ProviderError::NoProvider { url: _, factory }) => {
                     app.window_provider_parameters =
                         Some(WindowProviderParameters::new(factory, &app.state));
-                    app.last_action = Some(LastAction::failure("no provider".to_string()));
+                    app.last_action = Some(
+                        LastAction::failure("no provider".to_string())
+                            .with_retry(app.last_operation.take()),
+                    );
                 }
                 Err(e) => {
                     error!("{}", e);
                     app.problematic_mod_id = e.opt_mod_id();
-                    app.last_action = Some(LastAction::failure(e.to_string()));
+                    app.last_action = Some(
+                        app.error_action(e.info())
+                            .with_retry(app.last_operation.take()),
+                    );
                 }
             }
             app.update_rid = None;
@@ -311,14 +648,16 @@ impl CheckUpdates {
         let rid = app.request_counter.next();
         let tx = app.tx.clone();
         let ctx = ctx.clone();
+        let url = mint_lib::update::release_url(app.state.config.update_endpoint.as_deref());
 
         let handle = tokio::spawn(async move {
-            tx.send(Message::CheckUpdates(Self {
-                rid,
-                result: mint_lib::update::get_latest_release().await,
-            }))
-            .await
-            .unwrap();
+            let result = match url {
+                Ok(url) => mint_lib::update::get_latest_release_from(&url).await,
+                Err(e) => Err(e),
+            };
+            tx.send(Message::CheckUpdates(Self { rid, result }))
+                .await
+                .unwrap();
             ctx.request_repaint();
         });
         app.check_updates_rid = Some(MessageHandle {
@@ -355,11 +694,16 @@ async fn integrate_async(
     store: Arc<ModStore>,
     ctx: egui::Context,
     mod_specs: Vec<ModSpecification>,
-    fsd_pak: PathBuf,
+    payload_rules: BTreeMap<String, Vec<PayloadRule>>,
+    asset_overrides: BTreeMap<String, String>,
+    save_snapshot: Option<SnapshotBeforeInstall>,
+    fsd_pak: Option<PathBuf>,
     config: MetaConfig,
+    memory_budget: u64,
+    pause: Pause,
     rid: RequestID,
     message_tx: Sender<Message>,
-) -> Result<(), IntegrationError> {
+) -> Result<Vec<IntegrationWarning>, IntegrationError> {
     let update = false;
 
     let mods = store.resolve_mods(&mod_specs, update).await?;
@@ -395,18 +739,39 @@ async fn integrate_async(
         }
     });
 
-    let paths = store.fetch_mods_ordered(&urls, update, Some(tx)).await?;
+    let paths = store
+        .fetch_mods_ordered(&urls, update, Some(tx), Some(&pause))
+        .await?;
+    let mut warnings = crate::signing::integration_warnings(&store, &to_integrate);
+    let Some(fsd_pak) = fsd_pak else {
+        return Ok(warnings);
+    };
+    pause.wait().await;
 
-    tokio::task::spawn_blocking(|| {
-        crate::integrate::integrate(
+    tokio::task::spawn_blocking(move || {
+        let mods = to_integrate.into_iter().zip(paths).collect::<Vec<_>>();
+        if let Some(save_snapshot) = save_snapshot
+            && let Ok(installation) = DRGInstallation::from_pak_path(&fsd_pak)
+        {
+            save_snapshot.take(&installation, &mods).map_err(|e| {
+                IntegrationError::GenericError {
+                    msg: format!("failed to snapshot save files before installing: {e}"),
+                }
+            })?;
+        }
+        let payloads = ModPayload::collect(&mod_specs, &mods, &payload_rules);
+        let asset_winners = crate::integrate::asset_winners(&mod_specs, &asset_overrides);
+        warnings.extend(crate::integrate::integrate(
             fsd_pak,
             config,
-            to_integrate.into_iter().zip(paths).collect(),
-        )
+            mods,
+            &payloads,
+            &asset_winners,
+            memory_budget,
+        )?);
+        Ok(warnings)
     })
-    .await??;
-
-    Ok(())
+    .await?
 }
 
 #[derive(Debug)]
@@ -422,9 +787,11 @@ impl LintMods {
         mods: Vec<ModSpecification>,
         enabled_lints: BTreeSet<LintId>,
         game_pak_path: Option<PathBuf>,
+        memory_budget: u64,
+        cache_path: PathBuf,
         tx: Sender<Message>,
         ctx: egui::Context,
-    ) -> MessageHandle<()> {
+    ) -> MessageHandle<Option<LintProgress>> {
         let rid = rc.next();
 
         let handle = tokio::task::spawn(async move {
@@ -433,13 +800,40 @@ impl LintMods {
             let mod_path_pairs_res =
                 paths_res.map(|paths| mods.into_iter().zip(paths).collect::<Vec<_>>());
 
+            let (progress_tx, mut progress_rx) = mpsc::channel::<LintProgress>(10);
+            {
+                let tx = tx.clone();
+                let ctx = ctx.clone();
+                tokio::spawn(async move {
+                    while let Some(progress) = progress_rx.recv().await {
+                        tx.send(Message::LintModsProgress(LintModsProgress {
+                            rid,
+                            progress,
+                        }))
+                        .await
+                        .unwrap();
+                        ctx.request_repaint();
+                    }
+                });
+            }
+
             let report_res = match mod_path_pairs_res {
                 Ok(pairs) => tokio::task::spawn_blocking(move || {
-                    crate::mod_lints::run_lints(
+                    let mut cache = LintCache::load(&cache_path);
+                    let report = crate::mod_lints::run_lints_cached(
                         &enabled_lints,
                         pairs.into_iter().collect(),
                         game_pak_path,
-                    )
+                        memory_budget,
+                        &mut cache,
+                        |progress| {
+                            progress_tx.blocking_send(progress).ok();
+                        },
+                    );
+                    if let Err(e) = cache.save(&cache_path) {
+                        warn!("failed to save lint cache: {e}");
+                    }
+                    report
                 })
                 .await
                 .unwrap()
@@ -471,6 +865,7 @@ impl LintMods {
             match self.result {
                 Ok(report) => {
                     info!("lint mod report complete");
+                    app.last_operation = None;
                     app.lint_report = Some(report);
                     app.last_action =
                         Some(LastAction::success("lint mod report complete".to_string()));
@@ -481,12 +876,18 @@ impl LintMods {
                 {
                     app.window_provider_parameters =
                         Some(WindowProviderParameters::new(factory, &app.state));
-                    app.last_action = Some(LastAction::failure("no provider".to_string()));
+                    app.last_action = Some(
+                        LastAction::failure("no provider".to_string())
+                            .with_retry(app.last_operation.take()),
+                    );
                 }
                 Err(e) => {
                     error!("{}", e);
                     app.problematic_mod_id = e.opt_mod_id();
-                    app.last_action = Some(LastAction::failure(e.to_string()));
+                    app.last_action = Some(
+                        app.error_action(e.info())
+                            .with_retry(app.last_operation.take()),
+                    );
                 }
             }
             app.integrate_rid = None;
@@ -494,6 +895,22 @@ impl LintMods {
     }
 }
 
+#[derive(Debug)]
+pub struct LintModsProgress {
+    rid: RequestID,
+    progress: LintProgress,
+}
+
+impl LintModsProgress {
+    fn receive(self, app: &mut App) {
+        if let Some(MessageHandle { rid, state, .. }) = &mut app.lint_rid
+            && *rid == self.rid
+        {
+            *state = Some(self.progress);
+        }
+    }
+}
+
 async fn resolve_async_ordered(
     store: Arc<ModStore>,
     ctx: egui::Context,
@@ -536,7 +953,9 @@ async fn resolve_async_ordered(
         }
     });
 
-    Ok(store.fetch_mods_ordered(&urls, update, Some(tx)).await?)
+    Ok(store
+        .fetch_mods_ordered(&urls, update, Some(tx), None)
+        .await?)
 }
 
 #[derive(Debug)]
@@ -550,12 +969,21 @@ impl SelfUpdate {
         rc: &mut RequestCounter,
         tx: Sender<Message>,
         ctx: egui::Context,
+        release: &GitHubRelease,
     ) -> MessageHandle<SelfUpdateProgress> {
         let rid = rc.next();
+        let download_url = release
+            .asset_url(self_update_asset_name())
+            .map(str::to_owned);
+        let checksum_url = release
+            .checksum_url(self_update_asset_name())
+            .map(str::to_owned);
         MessageHandle {
             rid,
             handle: tokio::task::spawn(async move {
-                let result = self_update_async(ctx.clone(), rid, tx.clone()).await;
+                let result =
+                    self_update_async(ctx.clone(), rid, tx.clone(), download_url, checksum_url)
+                        .await;
                 tx.send(Message::SelfUpdate(SelfUpdate { rid, result }))
                     .await
                     .unwrap();
@@ -577,7 +1005,7 @@ impl SelfUpdate {
                     error!("self update failed");
                     error!("{:#?}", e);
                     app.self_update_rid = None;
-                    app.last_action = Some(LastAction::failure("self update failed".to_string()));
+                    app.last_action = Some(app.error_action(e.info()));
                 }
             }
             app.integrate_rid = None;
@@ -601,14 +1029,29 @@ impl FetchSelfUpdateProgress {
     }
 }
 
+fn self_update_asset_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "mint-x86_64-pc-windows-msvc.zip"
+    } else if cfg!(target_os = "linux") {
+        "mint-x86_64-unknown-linux-gnu.zip"
+    } else {
+        unimplemented!("unsupported platform");
+    }
+}
+
 async fn self_update_async(
     ctx: egui::Context,
     rid: RequestID,
     message_tx: Sender<Message>,
+    download_url: Option<String>,
+    checksum_url: Option<String>,
 ) -> Result<PathBuf, IntegrationError> {
     use futures::stream::TryStreamExt;
+    use sha2::{Digest, Sha256};
     use tokio::io::AsyncWriteExt;
 
+    let failed = |msg: String| IntegrationError::SelfUpdateFailed { source: msg.into() };
+
     let (tx, mut rx) = mpsc::channel::<SelfUpdateProgress>(1);
 
     tokio::spawn(async move {
@@ -626,20 +1069,37 @@ async fn self_update_async(
 
     let client = reqwest::Client::new();
 
-    let asset_name = if cfg!(target_os = "windows") {
-        "mint-x86_64-pc-windows-msvc.zip"
-    } else if cfg!(target_os = "linux") {
-        "mint-x86_64-unknown-linux-gnu.zip"
-    } else {
-        unimplemented!("unsupported platform");
-    };
+    let asset_name = self_update_asset_name();
+    // releases from custom endpoints list their assets, fall back to the build's repo otherwise
+    let download_url =
+        download_url.unwrap_or_else(|| mint_lib::update::default_asset_url(asset_name));
+    let checksum_url = checksum_url
+        .unwrap_or_else(|| mint_lib::update::default_asset_url(&format!("{asset_name}.sha256")));
+    for url in [&download_url, &checksum_url] {
+        if !mint_lib::update::is_https(url) {
+            return Err(failed(format!("refusing to download {url} without https")));
+        }
+    }
+
+    // a release without a checksum is refused before its archive is downloaded
+    let checksum = client
+        .get(&checksum_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(Into::into)
+        .with_context(|_| SelfUpdateFailedSnafu)?
+        .text()
+        .await
+        .map_err(Into::into)
+        .with_context(|_| SelfUpdateFailedSnafu)?;
+    let expected = mint_lib::update::parse_checksum(&checksum)
+        .ok_or_else(|| failed(format!("{checksum_url} does not hold a SHA-256 checksum")))?;
 
-    info!("downloading update");
+    info!("downloading update from {download_url}");
 
     let response = client
-        .get(format!(
-            "https://github.com/trumank/mint/releases/latest/download/{asset_name}"
-        ))
+        .get(download_url)
         .send()
         .await
         .map_err(Into::into)
@@ -661,6 +1121,7 @@ async fn self_update_async(
         .with_context(|_| SelfUpdateFailedSnafu)?;
     let mut stream = response.bytes_stream();
 
+    let mut hasher = Sha256::new();
     let mut total_bytes_written = 0;
     while let Some(bytes) = stream
         .try_next()
@@ -668,8 +1129,9 @@ async fn self_update_async(
         .map_err(Into::into)
         .with_context(|_| SelfUpdateFailedSnafu)?
     {
-        let bytes_written = tmp_archive.write(&bytes).await?;
-        total_bytes_written += bytes_written;
+        tmp_archive.write_all(&bytes).await?;
+        hasher.update(&bytes);
+        total_bytes_written += bytes.len();
         if let Some(size) = size {
             tx.send(SelfUpdateProgress::Progress {
                 progress: total_bytes_written as u64,
@@ -680,10 +1142,19 @@ async fn self_update_async(
         }
     }
 
+    tmp_archive.flush().await?;
+
     debug!(?tmp_dir);
     debug!(?tmp_archive_path);
     debug!(?tmp_archive);
 
+    let actual = hex::encode(hasher.finalize());
+    if actual != expected {
+        return Err(failed(format!(
+            "downloaded archive has SHA-256 {actual}, but the release publishes {expected}"
+        )));
+    }
+
     let original_exe_path =
         tokio::task::spawn_blocking(move || -> Result<PathBuf, IntegrationError> {
             let bin_name = if cfg!(target_os = "windows") {
@@ -731,3 +1202,267 @@ async fn self_update_async(
 
     Ok(original_exe_path)
 }
+
+#[derive(Debug)]
+pub struct PreviewMod {
+    rid: RequestID,
+    result: Result<(ModInfo, ModPreview), IntegrationError>,
+}
+
+impl PreviewMod {
+    pub fn send(app: &mut App, ctx: &egui::Context, spec: ModSpecification) {
+        app.record_feature("preview_mod");
+        let rid = app.request_counter.next();
+        let store = app.state.store.clone();
+        let ctx = ctx.clone();
+        let tx = app.tx.clone();
+        let handle = tokio::spawn(async move {
+            let result = preview_async(store, spec).await;
+            tx.send(Message::PreviewMod(Self { rid, result }))
+                .await
+                .unwrap();
+            ctx.request_repaint();
+        });
+        app.last_action = None;
+        app.preview_rid = Some(MessageHandle {
+            rid,
+            handle,
+            state: (),
+        });
+    }
+
+    fn receive(self, app: &mut App) {
+        if Some(self.rid) == app.preview_rid.as_ref().map(|r| r.rid) {
+            match self.result {
+                Ok((info, preview)) => {
+                    app.preview_window = Some(WindowPreview::new(info.name, preview));
+                }
+                Err(ref e)
+                    if let IntegrationError::ProviderError { source } = e
+                        && let ProviderError::NoProvider { url: _, factory } = source =>
+                {
+                    app.window_provider_parameters =
+                        Some(WindowProviderParameters::new(factory, &app.state));
+                    app.last_action = Some(LastAction::failure("no provider".to_string()));
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    app.problematic_mod_id = e.opt_mod_id();
+                    app.last_action = Some(app.error_action(e.info()));
+                }
+            }
+            app.preview_rid = None;
+        }
+    }
+}
+
+async fn preview_async(
+    store: Arc<ModStore>,
+    spec: ModSpecification,
+) -> Result<(ModInfo, ModPreview), IntegrationError> {
+    let (_, info) = store.resolve_mod(spec, false).await?;
+    let path = store.fetch_mod(&info.resolution, false, None).await?;
+    let preview =
+        tokio::task::spawn_blocking(move || crate::preview::extract_preview(path)).await??;
+    Ok((info, preview))
+}
+
+#[derive(Debug)]
+pub struct HealthCheck {
+    rid: RequestID,
+    results: Vec<CheckResult>,
+}
+
+impl HealthCheck {
+    pub fn send(app: &mut App, ctx: &egui::Context) {
+        app.record_feature("health_check");
+        let rid = app.request_counter.next();
+        let tx = app.tx.clone();
+        let ctx = ctx.clone();
+        let pak_path = app.state.config.drg_pak_path.clone();
+        let cache_dir = app.state.dirs.cache_dir.clone();
+
+        let handle = tokio::spawn(async move {
+            let results = crate::health::run(pak_path, cache_dir).await;
+            tx.send(Message::HealthCheck(Self { rid, results }))
+                .await
+                .unwrap();
+            ctx.request_repaint();
+        });
+        app.health_check_window = Some(WindowHealthCheck {
+            rid: Some(MessageHandle {
+                rid,
+                handle,
+                state: (),
+            }),
+            results: vec![],
+        });
+    }
+
+    fn receive(self, app: &mut App) {
+        if let Some(window) = &mut app.health_check_window
+            && Some(self.rid) == window.rid.as_ref().map(|r| r.rid)
+        {
+            window.rid = None;
+            window.results = self.results;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EstimateDownload {
+    rid: RequestID,
+    result: Result<DownloadEstimate, ProviderError>,
+}
+
+impl EstimateDownload {
+    pub fn send(app: &mut App, ctx: &egui::Context, mods: Vec<ModSpecification>) {
+        let rid = app.request_counter.next();
+        let tx = app.tx.clone();
+        let ctx = ctx.clone();
+        let store = app.state.store.clone();
+
+        let handle = tokio::spawn(async move {
+            let result = async {
+                let resolved = store.resolve_mods(&mods, false).await?;
+                let infos = mods.iter().map(|s| &resolved[s]).collect::<Vec<_>>();
+                store.estimate_download(&infos).await
+            }
+            .await;
+            tx.send(Message::EstimateDownload(Self { rid, result }))
+                .await
+                .unwrap();
+            ctx.request_repaint();
+        });
+        app.last_action = None;
+        app.download_estimate_window = Some(WindowDownloadEstimate {
+            rid: Some(MessageHandle {
+                rid,
+                handle,
+                state: (),
+            }),
+            result: None,
+        });
+    }
+
+    fn receive(self, app: &mut App) {
+        if let Some(window) = &mut app.download_estimate_window
+            && Some(self.rid) == window.rid.as_ref().map(|r| r.rid)
+        {
+            window.rid = None;
+            window.result = Some(self.result.map_err(|e| e.info().summary()));
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Backup {
+    rid: RequestID,
+    result: Result<PathBuf, String>,
+}
+
+impl Backup {
+    /// Back up to `dir` in the background, unless a backup is already running. The returned
+    /// receiver resolves once the backup is done.
+    pub fn send(
+        app: &mut App,
+        ctx: &egui::Context,
+        dir: PathBuf,
+        trigger: backup::Trigger,
+    ) -> Option<oneshot::Receiver<()>> {
+        if app.backup_rid.is_some() {
+            return None;
+        }
+        let (done_tx, done) = oneshot::channel();
+        let rid = app.request_counter.next();
+        let tx = app.tx.clone();
+        let ctx = ctx.clone();
+        let dirs = app.state.dirs.clone();
+        let zip = app.state.config.zip_backups;
+        let keep = app.state.config.auto_backup.keep;
+
+        let handle = tokio::spawn(async move {
+            let result =
+                tokio::task::spawn_blocking(move || backup::create(&dirs, &dir, zip, keep))
+                    .await
+                    .unwrap()
+                    .map_err(|e| e.to_string());
+            done_tx.send(()).ok();
+            tx.send(Message::Backup(Self { rid, result }))
+                .await
+                .unwrap();
+            ctx.request_repaint();
+        });
+        app.backup_rid = Some(MessageHandle {
+            rid,
+            handle,
+            state: trigger,
+        });
+        Some(done)
+    }
+
+    fn receive(self, app: &mut App) {
+        let Some(rid) = app.backup_rid.take_if(|r| r.rid == self.rid) else {
+            return;
+        };
+        let status = match self.result {
+            Ok(path) => {
+                info!("{} backup created at {}", rid.state, path.display());
+                (true, format!("Backup created: {}", path.display()))
+            }
+            Err(e) => {
+                warn!("{} backup failed: {e}", rid.state);
+                (false, format!("Backup failed: {e}"))
+            }
+        };
+        if let Some(window) = &mut app.settings_window {
+            window.backup_status = Some(status);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FetchThumbnail {
+    url: String,
+    result: Result<image::RgbaImage, String>,
+}
+
+impl FetchThumbnail {
+    /// Download and decode the thumbnail at `url` in the background, once one of
+    /// [`App::thumbnail_fetches`] is free. Thumbnails are kept by URL rather than by request, see
+    /// [`App::thumbnails`].
+    pub fn send(app: &mut App, ctx: &egui::Context, url: String) {
+        let store = app.state.store.clone();
+        let permits = app.thumbnail_fetches.clone();
+        let tx = app.tx.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await.unwrap();
+            let result = match store.fetch_thumbnail(&url).await {
+                Ok(data) => tokio::task::spawn_blocking(move || {
+                    image::load_from_memory(&data)
+                        .map(|image| image.to_rgba8())
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .unwrap(),
+                Err(e) => Err(e.to_string()),
+            };
+            tx.send(Message::FetchThumbnail(Self { url, result }))
+                .await
+                .unwrap();
+            ctx.request_repaint();
+        });
+    }
+
+    fn receive(self, app: &mut App) {
+        let thumbnail = match self.result {
+            Ok(image) => Thumbnail::Image(image),
+            Err(e) => {
+                debug!("failed to fetch thumbnail {}: {e}", self.url);
+                Thumbnail::Failed(std::time::Instant::now())
+            }
+        };
+        app.thumbnails.insert(self.url, thumbnail);
+    }
+}
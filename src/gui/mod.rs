@@ -1,22 +1,27 @@
 mod find_string;
+mod help;
 mod message;
 mod named_combobox;
+mod qr;
 mod request_counter;
 mod toggle_switch;
+mod tutorial;
 
 //#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
-use std::ops::{Deref, RangeInclusive};
+use std::io::Cursor;
+use std::ops::RangeInclusive;
 use std::time::{Duration, Instant, SystemTime};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     ops::DerefMut,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use eframe::egui::{Button, CollapsingHeader, RichText};
+use eframe::egui::{Button, CollapsingHeader, RichText, collapsing_header::CollapsingState};
 use eframe::epaint::{Pos2, Vec2};
 use eframe::{
     egui::{FontSelection, Layout, TextFormat, Ui},
@@ -24,46 +29,100 @@ use eframe::{
     epaint::{Color32, Stroke, text::LayoutJob},
 };
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
+use futures::{FutureExt as _, TryFutureExt as _};
 use mint_lib::error::ResultExt as _;
 use mint_lib::mod_info::{ModioTags, RequiredStatus};
 use mint_lib::update::GitHubRelease;
+use mint_lib::{DRGInstallation, DRGInstallationType};
 use strum::{EnumIter, IntoEnumIterator};
 use tokio::{
     sync::mpsc::{self, Receiver, Sender},
     task::JoinHandle,
 };
-use tracing::{debug, trace};
-
-use crate::Dirs;
+use tracing::{debug, info, trace, warn};
+
+use crate::analytics::Analytics;
+use crate::backup;
+use crate::deep_link::{self, DeepLink};
+use crate::diagnostics::IntegrationReport;
+use crate::enabled_mods;
+use crate::error_info::ErrorInfo;
 use crate::gui::find_string::searchable_text;
-use crate::mod_lints::{LintId, LintReport, SplitAssetPair};
+use crate::gui::help::{HelpTopic, WindowHelp};
+use crate::gui::tutorial::{Tour, TourStatus, TourTarget};
+use crate::health::{CheckResult, CheckStatus};
+use crate::lobby::{self, InstallChange, LobbyReport};
+use crate::mod_lints::{LintId, LintProgress, LintReport};
+use crate::mod_list;
+use crate::preview::audio::{self, AudioFormat};
+use crate::preview::{ModPreview, PreviewFile};
+use crate::profile_health::{Grade, HealthInputs, ProfileHealth};
 use crate::providers::ProviderError;
+use crate::saves::{self, SnapshotBeforeInstall};
+use crate::sessions::{self, Sessions};
+use crate::signing::{self, SignatureStatus};
 use crate::state::SortingConfig;
+use crate::{DEFAULT_WORKSPACE, DirKind, Dirs, Workspaces};
 use crate::{
     MintError,
-    integrate::uninstall,
+    integrate::{
+        IntegrationWarning, MissingInstall, WarningKind, asset_key, check_install, ue4ss, uninstall,
+    },
     is_drg_pak,
     providers::{
-        ApprovalStatus, FetchProgress, ModInfo, ModSpecification, ModStore, ProviderFactory,
+        ApprovalStatus, DownloadEstimate, EmailLogin, FetchProgress, LocalDependency,
+        LocalMetadata, ModInfo, ModSpecification, ModStore, Pause, ProviderFactory,
+    },
+    state::{
+        BrokenDependency, LaunchMethod, MergeConflict, ModConfig, ModData_v0_3_0 as ModData,
+        ModGroup_v0_3_0 as ModGroup, ModOrGroup, ModProfile_v0_3_0 as ModProfile,
+        PROFILE_EXTENSION, PayloadRule, State, UniqueContents, UpdatePolicy,
+        dependencies::{DependencyGraph, NodeStatus},
+        history,
+        import::{self, Importer},
+        load_order::PriorityChange,
+        secrets,
     },
-    state::{ModConfig, ModData_v0_2_0 as ModData, ModOrGroup, ModProfile_v0_2_0 as ModProfile, State},
 };
 use message::MessageHandle;
 use request_counter::{RequestCounter, RequestID};
 
 use self::toggle_switch::toggle_switch;
 
-pub fn gui(dirs: Dirs, args: Option<Vec<String>>) -> Result<(), MintError> {
+/// Actions to perform once the GUI has started, as requested on the command line.
+#[derive(Debug, Default)]
+pub struct StartupActions {
+    /// Profile to select.
+    pub profile: Option<String>,
+    /// Install the selected profile.
+    pub install: bool,
+    /// Launch the game, after installing if `install` is also set.
+    pub launch: bool,
+    /// `mint://` link mint was started with, see [`crate::deep_link`].
+    pub link: Option<DeepLink>,
+    /// Start with [`State::init_safe_mode`].
+    pub safe_mode: bool,
+}
+
+pub fn gui(
+    dirs: Dirs,
+    args: Option<Vec<String>>,
+    startup: StartupActions,
+) -> Result<(), MintError> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([900.0, 500.0])
             .with_drag_and_drop(true),
         ..Default::default()
     };
+    let mut title = format!("mint {}", mint_lib::built_info::version());
+    if let Some(workspace) = &dirs.workspace {
+        title.push_str(&format!(" - {workspace}"));
+    }
     eframe::run_native(
-        &format!("mint {}", mint_lib::built_info::version()),
+        &title,
         options,
-        Box::new(|cc| Ok(Box::new(App::new(cc, dirs, args)?))),
+        Box::new(|cc| Ok(Box::new(App::new(cc, dirs, args, startup)?))),
     )
     .with_generic(|e| format!("{e}"))?;
     Ok(())
@@ -127,8 +186,28 @@ impl SortBy {
 
 const MODIO_LOGO_PNG: &[u8] = include_bytes!("../../assets/modio-cog-blue.png");
 
+/// How often to check whether a daily backup is due.
+const BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Size of a thumbnail in the mod list, the 16:9 of mod.io logos.
+const THUMBNAIL_SIZE: egui::Vec2 = egui::vec2(32.0, 18.0);
+/// Thumbnails fetched at once, the others wait for one of them to finish.
+const MAX_THUMBNAIL_FETCHES: usize = 5;
+/// How long after failing a thumbnail is fetched again when drawn.
+const THUMBNAIL_RETRY: Duration = Duration::from_secs(60);
+
+enum Thumbnail {
+    Loading,
+    /// Decoded, uploaded as a texture when next drawn.
+    Image(image::RgbaImage),
+    Texture(egui::TextureHandle),
+    /// When it failed, see [`THUMBNAIL_RETRY`].
+    Failed(Instant),
+}
+
 pub struct App {
     args: Option<Vec<String>>,
+    startup: StartupActions,
     tx: Sender<message::Message>,
     rx: Receiver<message::Message>,
     state: State,
@@ -136,7 +215,13 @@ pub struct App {
     resolve_mod_rid: Option<MessageHandle<()>>,
     integrate_rid: Option<MessageHandle<HashMap<ModSpecification, SpecFetchProgress>>>,
     update_rid: Option<MessageHandle<()>>,
+    /// Pause of the running install or cache update.
+    pause: Pause,
     check_updates_rid: Option<MessageHandle<()>>,
+    /// The running backup, with what triggered it.
+    backup_rid: Option<MessageHandle<backup::Trigger>>,
+    /// When to check again whether a daily backup is due.
+    next_backup_check: Instant,
     has_run_init: bool,
     request_counter: RequestCounter,
     window_provider_parameters: Option<WindowProviderParameters>,
@@ -145,25 +230,81 @@ pub struct App {
     focus_search: bool,
     settings_window: Option<WindowSettings>,
     modio_texture_handle: Option<egui::TextureHandle>,
+    /// Thumbnails of the mods in the mod list by URL, fetched when first drawn.
+    thumbnails: HashMap<String, Thumbnail>,
+    /// Limits the thumbnails fetched at once to [`MAX_THUMBNAIL_FETCHES`].
+    thumbnail_fetches: Arc<tokio::sync::Semaphore>,
     last_action: Option<LastAction>,
+    /// The running integrate, cache update or lint, offered for retrying if it fails.
+    last_operation: Option<message::Operation>,
     available_update: Option<GitHubRelease>,
     show_update_time: Option<SystemTime>,
     open_profiles: HashSet<String>,
-    lint_rid: Option<MessageHandle<()>>,
+    lint_rid: Option<MessageHandle<Option<LintProgress>>>,
     lint_report_window: Option<WindowLintReport>,
     lint_report: Option<LintReport>,
+    /// Profile [`Self::lint_report`] was made for.
+    lint_profile: Option<String>,
     lints_toggle_window: Option<WindowLintsToggle>,
     lint_options: LintOptions,
+    preview_rid: Option<MessageHandle<()>>,
+    preview_window: Option<WindowPreview>,
+    help_window: Option<WindowHelp>,
+    merge_profiles_window: Option<WindowMergeProfiles>,
+    payload_rules_window: Option<WindowPayloadRules>,
+    local_metadata_window: Option<WindowLocalMetadata>,
+    refresh_link_window: Option<WindowRefreshLink>,
+    save_snapshots_window: Option<WindowSaveSnapshots>,
+    history_window: Option<WindowHistory>,
+    health_check_window: Option<WindowHealthCheck>,
+    download_estimate_window: Option<WindowDownloadEstimate>,
+    integration_warnings_window: Option<WindowIntegrationWarnings>,
+    lobby_window: Option<WindowLobby>,
+    stats_window: Option<WindowStats>,
+    analytics_window: Option<WindowAnalytics>,
+    qr_window: Option<WindowQr>,
+    pin_to_date_window: Option<WindowPinToDate>,
+    archive_window: Option<WindowArchive>,
+    profile_order_window: Option<WindowProfileOrder>,
+    profile_info_window: Option<WindowProfileInfo>,
+    profile_readme_window: Option<WindowProfileReadme>,
+    folder_dependencies_window: Option<WindowFolderDependencies>,
+    dependency_graph_window: Option<WindowDependencyGraph>,
+    /// Takes `mint://` links from later launches, `None` if it could not listen.
+    instance: Option<deep_link::Instance>,
+    deep_link_window: Option<WindowDeepLink>,
+    unlisted_mods_window: Option<WindowUnlistedMods>,
+    suggest_priorities_window: Option<WindowSuggestPriorities>,
+    /// The game if mint started it itself.
+    game: Option<RunningGame>,
+    /// Set on startup when the last install is no longer in place.
+    missing_install: Option<MissingInstall>,
+    /// Set on startup when the active profile changed since the last install.
+    install_changes: Option<WindowInstallChanges>,
+    tour: Option<Tour>,
+    /// Usage counts, only recorded if enabled in the settings.
+    analytics: Analytics,
     cache: CommonMarkCache,
     needs_restart: bool,
     self_update_rid: Option<MessageHandle<SelfUpdateProgress>>,
     original_exe_path: Option<PathBuf>,
+    /// Arguments to restart with instead of the current ones, see [`args_in_workspace`].
+    restart_args: Option<Vec<std::ffi::OsString>>,
+    /// Whether the window explaining `--safe-mode` is shown.
+    safe_mode_open: bool,
     problematic_mod_id: Option<u32>,
     pending_deletion: Option<PendingDeletion>,
+    /// Mods checked for bulk editing.
+    selection: Option<ModSelection>,
+    /// Url of the mod highlighted in the list, e.g. after clicking it in the lint report. Only
+    /// kept while it stays selected.
+    focused_mod: Option<String>,
+    scroll_to_focused: bool,
     // Folder management
     create_folder_popup: Option<String>, // Some(buffer) when popup is open
     rename_folder_popup: Option<(String, String)>, // Some((old_name, buffer))
-    expand_folder: Option<String>, // Folder to expand on next frame
+    expand_folder: Option<String>,       // Folder to expand on next frame
+    save_loadout_popup: Option<String>,  // Some(buffer) when popup is open
 }
 
 #[derive(Default)]
@@ -178,25 +319,44 @@ struct LintOptions {
     non_asset_files: bool,
     split_asset_pairs: bool,
     unmodified_game_assets: bool,
+    config_overrides: bool,
+    unsupported_compression: bool,
+    mount_point: bool,
+    localization_conflicts: bool,
 }
 
 struct LastAction {
     timestamp: Instant,
     status: LastActionStatus,
+    /// The failed operation, to offer retrying it.
+    retry: Option<message::Operation>,
 }
 impl LastAction {
     fn success(msg: String) -> Self {
         Self {
             timestamp: Instant::now(),
             status: LastActionStatus::Success(msg),
+            retry: None,
         }
     }
     fn failure(msg: String) -> Self {
         Self {
             timestamp: Instant::now(),
             status: LastActionStatus::Failure(msg),
+            retry: None,
+        }
+    }
+    fn error(info: ErrorInfo) -> Self {
+        Self {
+            timestamp: Instant::now(),
+            status: LastActionStatus::Error(info),
+            retry: None,
         }
     }
+    fn with_retry(mut self, retry: Option<message::Operation>) -> Self {
+        self.retry = retry;
+        self
+    }
     fn timeago(&self) -> String {
         let duration = Instant::now().duration_since(self.timestamp);
         let seconds = duration.as_secs();
@@ -213,19 +373,62 @@ impl LastAction {
 enum LastActionStatus {
     Success(String),
     Failure(String),
+    /// A failure with a code, suggested action and details to copy.
+    Error(ErrorInfo),
 }
 
 impl App {
     fn new(
-        _cc: &eframe::CreationContext,
+        cc: &eframe::CreationContext,
         dirs: Dirs,
         args: Option<Vec<String>>,
+        startup: StartupActions,
     ) -> Result<Self, MintError> {
         let (tx, rx) = mpsc::channel(10);
-        let state = State::init(dirs)?;
+        let state = if startup.safe_mode {
+            State::init_safe_mode(dirs)
+        } else {
+            State::init_previewing_migration(dirs)?
+        };
+        let safe_mode_open = state.safe_mode.is_some();
+        let missing_install = IntegrationReport::load(&state.dirs.data_dir)
+            .filter(IntegrationReport::installed_mods)
+            .and(state.config.drg_pak_path.as_ref())
+            .and_then(|p| DRGInstallation::from_pak_path(p).ok())
+            .map(|installation| check_install(&installation))
+            .filter(MissingInstall::any);
+        let install_changes = state
+            .config
+            .drg_pak_path
+            .as_ref()
+            .and_then(|p| DRGInstallation::from_pak_path(p).ok())
+            .and_then(|installation| lobby::read_installed(&installation).ok())
+            .map(|installed| {
+                let mut enabled = vec![];
+                state
+                    .mod_data
+                    .for_each_enabled_mod(&state.mod_data.active_profile, |mc| {
+                        enabled.push(mc.spec.clone())
+                    });
+                lobby::changes_since_install(&installed, &enabled, |spec| {
+                    state.store.get_mod_info(spec).and_then(|i| i.version_id())
+                })
+            })
+            .filter(|changes| !changes.is_empty())
+            .map(|changes| WindowInstallChanges {
+                changes,
+                open: false,
+            });
+        let analytics = Analytics::load(&state.dirs.data_dir);
+        let ctx = cc.egui_ctx.clone();
+        let instance =
+            deep_link::Instance::listen(&state.dirs.data_dir, move || ctx.request_repaint())
+                .inspect_err(|e| warn!("not listening for links from other launches: {e}"))
+                .ok();
 
         Ok(Self {
             args,
+            startup,
             tx,
             rx,
             request_counter: Default::default(),
@@ -234,7 +437,10 @@ impl App {
             resolve_mod_rid: None,
             integrate_rid: None,
             update_rid: None,
+            pause: Pause::default(),
             check_updates_rid: None,
+            backup_rid: None,
+            next_backup_check: Instant::now(),
             has_run_init: false,
             window_provider_parameters: None,
             search_string: Default::default(),
@@ -242,36 +448,100 @@ impl App {
             focus_search: false,
             settings_window: None,
             modio_texture_handle: None,
+            thumbnails: Default::default(),
+            thumbnail_fetches: Arc::new(tokio::sync::Semaphore::new(MAX_THUMBNAIL_FETCHES)),
             last_action: None,
+            last_operation: None,
             available_update: None,
             show_update_time: None,
             open_profiles: Default::default(),
             lint_rid: None,
             lint_report_window: None,
             lint_report: None,
+            lint_profile: None,
             lints_toggle_window: None,
             lint_options: LintOptions::default(),
+            preview_rid: None,
+            preview_window: None,
+            help_window: None,
+            merge_profiles_window: None,
+            payload_rules_window: None,
+            local_metadata_window: None,
+            refresh_link_window: None,
+            save_snapshots_window: None,
+            history_window: None,
+            health_check_window: None,
+            download_estimate_window: None,
+            integration_warnings_window: None,
+            lobby_window: None,
+            stats_window: None,
+            analytics_window: None,
+            qr_window: None,
+            pin_to_date_window: None,
+            archive_window: None,
+            profile_order_window: None,
+            profile_info_window: None,
+            profile_readme_window: None,
+            folder_dependencies_window: None,
+            dependency_graph_window: None,
+            instance,
+            deep_link_window: None,
+            unlisted_mods_window: None,
+            suggest_priorities_window: None,
+            game: None,
+            missing_install,
+            install_changes,
+            tour: (!state.config.tutorial_completed).then(Tour::default),
+            analytics,
             cache: Default::default(),
             needs_restart: false,
             self_update_rid: None,
             original_exe_path: None,
+            restart_args: None,
+            safe_mode_open,
             problematic_mod_id: None,
             pending_deletion: None,
+            selection: None,
+            focused_mod: None,
+            scroll_to_focused: false,
             create_folder_popup: None,
             rename_folder_popup: None,
             expand_folder: None,
+            save_loadout_popup: None,
         })
     }
 
     fn ui_profile(&mut self, ui: &mut Ui, profile: &str) {
+        self.ui_bulk_actions(ui, profile);
+
         let sorting_config = self.get_sorting_config();
+        let selected_urls = self
+            .selection
+            .as_ref()
+            .filter(|s| s.profile == profile)
+            .map(|s| &s.urls);
+        if self
+            .focused_mod
+            .as_ref()
+            .is_some_and(|url| selected_urls.is_none_or(|urls| !urls.contains(url)))
+        {
+            self.focused_mod = None;
+        }
+        let focused_url = self.focused_mod.as_ref();
 
         let mod_data = self.state.mod_data.deref_mut().deref_mut();
         let active_profile_name = mod_data.active_profile.clone();
-        
+        let copy_targets = mod_data
+            .ordered_profiles()
+            .into_iter()
+            .filter(|p| p != profile)
+            .collect::<Vec<_>>();
+        let mod_tags = mod_data.mod_tags(profile);
+
         // Get mutable reference to profiles map
         let profiles = &mut mod_data.profiles;
-        
+        let payload_rules = &mod_data.payload_rules;
+
         // Get folder names from the active profile
         let folder_names: Vec<String> = profiles
             .get(&active_profile_name)
@@ -281,31 +551,58 @@ impl App {
         struct Ctx {
             needs_save: bool,
             scroll_to_match: bool,
+            scroll_to_focused: bool,
             btn_remove: Option<usize>,
             pending_delete: Option<(String, usize)>, // (mod_name, row_index)
             pending_folder_delete: Option<String>, // folder_name
             pending_folder_mod_delete: Option<(String, usize)>, // (folder_name, mod_index) - delete mod inside folder
             add_deps: Option<Vec<ModSpecification>>,
+            preview: Option<ModSpecification>,
+            payload_rules: Option<(String, ModSpecification)>, // (mod_name, spec)
+            local_metadata: Option<(String, ModSpecification)>, // (mod_name, spec)
+            replace_file: Option<(ModSpecification, PathBuf)>,
             // Folder operations
             move_mod_to_folder: Option<(usize, String)>, // (mod_index, folder_name)
             move_mod_from_folder: Option<(String, usize)>, // (folder_name, mod_index_in_folder) -> to root
             move_mod_between_folders: Option<(String, usize, String)>, // (from_folder, mod_index, to_folder)
-            rename_folder: Option<String>, // folder name to rename
+            rename_folder: Option<String>,                             // folder name to rename
+            folder_toggled: Option<(String, bool)>,                    // (folder_name, enabled)
+            move_folder: Option<(String, Option<String>)>, // (folder_name, target folder or root)
+            toggle_selected: Option<String>,               // url of the mod checked or unchecked
+            copy_to_profile: Option<(CopyItem, String)>,   // (what to copy, target profile)
+            fetch_thumbnails: Vec<String>, // thumbnail urls drawn for the first time
         }
         let mut ctx = Ctx {
             needs_save: false,
             scroll_to_match: self.scroll_to_match,
+            scroll_to_focused: self.scroll_to_focused,
             btn_remove: None,
             pending_delete: None,
             pending_folder_delete: None,
             pending_folder_mod_delete: None,
             add_deps: None,
+            preview: None,
+            payload_rules: None,
+            local_metadata: None,
+            replace_file: None,
             move_mod_to_folder: None,
             move_mod_from_folder: None,
             move_mod_between_folders: None,
             rename_folder: None,
+            folder_toggled: None,
+            move_folder: None,
+            toggle_selected: None,
+            copy_to_profile: None,
+            fetch_thumbnails: vec![],
         };
 
+        /// What "Copy to profile…" was chosen on.
+        enum CopyItem {
+            /// The mod with this URL, or all checked mods if it is one of them.
+            Mod(String),
+            Folder(String),
+        }
+
         // Unique identifier for a mod's location (for duplicate detection)
         #[derive(Clone, PartialEq, Eq)]
         enum ModLocation {
@@ -313,42 +610,188 @@ impl App {
             InFolder(String, usize),        // (folder_name, index within folder)
         }
 
+        /// A row inside an open folder, whose contents are shown as an indented list.
+        enum FolderRow {
+            /// Priority settings of an open nested folder.
+            Settings { folder: String, depth: usize },
+            /// A mod or nested folder at `index` of `folder`.
+            Entry {
+                folder: String,
+                index: usize,
+                depth: usize,
+                override_priority: Option<i32>,
+                priority_offset: i32,
+            },
+        }
+
+        /// Rows of the folder at the end of `path`, descending into the open nested folders.
+        fn folder_rows(
+            groups: &BTreeMap<String, ModGroup>,
+            path: &mut Vec<String>,
+            override_priority: Option<i32>,
+            priority_offset: i32,
+            order: &dyn Fn(&[ModOrGroup]) -> Vec<usize>,
+            is_open: &dyn Fn(&str) -> bool,
+            rows: &mut Vec<FolderRow>,
+        ) {
+            let folder = path.last().unwrap().clone();
+            let Some(group) = groups.get(&folder) else {
+                return;
+            };
+            let depth = path.len() - 1;
+            for index in order(&group.mods) {
+                rows.push(FolderRow::Entry {
+                    folder: folder.clone(),
+                    index,
+                    depth,
+                    override_priority,
+                    priority_offset,
+                });
+                if let ModOrGroup::Group { group_name, .. } = &group.mods[index]
+                    && is_open(group_name.as_str())
+                    && !path.contains(group_name)
+                    && let Some(nested) = groups.get(group_name)
+                {
+                    rows.push(FolderRow::Settings {
+                        folder: group_name.clone(),
+                        depth: depth + 1,
+                    });
+                    path.push(group_name.clone());
+                    let override_priority = nested.priority_override.or(override_priority);
+                    let priority_offset = priority_offset + nested.priority_offset.unwrap_or(0);
+                    folder_rows(
+                        groups,
+                        path,
+                        override_priority,
+                        priority_offset,
+                        order,
+                        is_open,
+                        rows,
+                    );
+                    path.pop();
+                }
+            }
+        }
+
+        const FOLDER_INDENT: f32 = 16.0;
+
+        fn folder_state_id(folder: &str) -> egui::Id {
+            egui::Id::new(("folder-state", folder))
+        }
+
         let ui_profile = |ui: &mut Ui, profile: &mut ModProfile| {
+            let folders_enabled = profile.folder_states();
             let enabled_specs = profile
                 .mods
                 .iter()
                 .enumerate()
-                .flat_map(|(i, m)| -> Box<dyn Iterator<Item = _>> {
-                    match m {
-                        ModOrGroup::Individual(mc) => {
-                            Box::new(mc.enabled.then_some((ModLocation::Root(i), mc.spec.clone())).into_iter())
-                        }
-                        ModOrGroup::Group {
-                            group_name,
-                            enabled,
-                        } => {
-                            let folder_name = group_name.clone();
-                            Box::new(
-                                enabled
-                                    .then(|| profile.groups.get(group_name))
-                                    .flatten()
-                                    .into_iter()
-                                    .flat_map(move |g| {
-                                        let folder_name = folder_name.clone();
-                                        g.mods
-                                            .iter()
-                                            .enumerate()
-                                            .filter_map(move |(idx, m)| {
-                                                m.enabled.then_some((ModLocation::InFolder(folder_name.clone(), idx), m.spec.clone()))
-                                            })
-                                    }),
-                            )
-                        }
-                    }
-                })
+                .filter_map(|(i, m)| Some((ModLocation::Root(i), m.as_mod()?)))
+                .chain(
+                    profile
+                        .groups
+                        .iter()
+                        .filter(|(name, _)| folders_enabled.get(*name) == Some(&true))
+                        .flat_map(|(name, g)| {
+                            g.mods.iter().enumerate().filter_map(|(i, m)| {
+                                Some((ModLocation::InFolder(name.clone(), i), m.as_mod()?))
+                            })
+                        }),
+                )
+                .filter(|(_, mc)| mc.enabled)
+                .map(|(location, mc)| (location, mc.spec.clone()))
                 .collect::<Vec<_>>();
 
+            // the folder to expand and the folders containing it
+            let mut expanded_folders = HashSet::new();
+            let mut expand = self.expand_folder.clone();
+            while let Some(folder) = expand.filter(|f| expanded_folders.insert(f.clone())) {
+                expand = profile.parent_folder(&folder).map(str::to_string);
+            }
+            let search_lower = self.search_string.to_lowercase();
+            let folder_forced_open = |folder: &str| {
+                expanded_folders.contains(folder)
+                    || (!search_lower.is_empty() && folder.to_lowercase().contains(&search_lower))
+            };
+
+            // entries inside a folder follow the same sort as those outside, folders first
+            let order_entries = |entries: &[ModOrGroup]| {
+                let mut order = (0..entries.len()).collect::<Vec<_>>();
+                if let Some(config) = sorting_config.clone() {
+                    let comp = sort_mods(config.clone());
+                    let folder_comp = sort_folders(config);
+                    let infos = entries
+                        .iter()
+                        .map(|m| {
+                            m.as_mod()
+                                .and_then(|mc| self.state.store.get_mod_info(&mc.spec))
+                        })
+                        .collect::<Vec<_>>();
+                    order.sort_by(|&a, &b| match (&entries[a], &entries[b]) {
+                        (
+                            ModOrGroup::Group {
+                                group_name: name_a,
+                                enabled: enabled_a,
+                            },
+                            ModOrGroup::Group {
+                                group_name: name_b,
+                                enabled: enabled_b,
+                            },
+                        ) => folder_comp(
+                            (name_a.as_str(), *enabled_a),
+                            (name_b.as_str(), *enabled_b),
+                        ),
+                        (ModOrGroup::Group { .. }, _) => Ordering::Less,
+                        (_, ModOrGroup::Group { .. }) => Ordering::Greater,
+                        (ModOrGroup::Individual(mc_a), ModOrGroup::Individual(mc_b)) => {
+                            comp((mc_a, infos[a].as_ref()), (mc_b, infos[b].as_ref()))
+                        }
+                    });
+                }
+                order
+            };
+
             let ui_mod_tags = |ctx: &mut Ctx, ui: &mut Ui, info: &ModInfo| {
+                let mut mk_searchable_tag =
+                    |tag_str: &str,
+                     ui: &mut Ui,
+                     color: Option<egui::Color32>,
+                     hover_str: Option<&str>| {
+                        let search = searchable_text(tag_str, &self.search_string, {
+                            TextFormat {
+                                color: if color.is_some() {
+                                    Color32::BLACK
+                                } else {
+                                    Color32::GRAY
+                                },
+
+                                ..Default::default()
+                            }
+                        });
+
+                        let button = if let Some(color) = color {
+                            egui::Button::new(search.job)
+                                .small()
+                                .fill(color)
+                                .stroke(egui::Stroke::NONE)
+                        } else {
+                            egui::Button::new(search.job)
+                                .small()
+                                .stroke(egui::Stroke::NONE)
+                        };
+
+                        let res = if let Some(hover_str) = hover_str {
+                            ui.add_enabled(false, button)
+                                .on_disabled_hover_text(hover_str)
+                        } else {
+                            ui.add_enabled(false, button)
+                        };
+
+                        if search.is_match && self.scroll_to_match {
+                            res.scroll_to_me(None);
+                            ctx.scroll_to_match = false;
+                        }
+                    };
+
                 if let Some(ModioTags {
                     qol,
                     gameplay,
@@ -360,50 +803,9 @@ impl App {
                     versions: _,
                 }) = info.modio_tags.as_ref()
                 {
-                    let mut mk_searchable_modio_tag =
-                        |tag_str: &str,
-                         ui: &mut Ui,
-                         color: Option<egui::Color32>,
-                         hover_str: Option<&str>| {
-                            let search = searchable_text(tag_str, &self.search_string, {
-                                TextFormat {
-                                    color: if color.is_some() {
-                                        Color32::BLACK
-                                    } else {
-                                        Color32::GRAY
-                                    },
-
-                                    ..Default::default()
-                                }
-                            });
-
-                            let button = if let Some(color) = color {
-                                egui::Button::new(search.job)
-                                    .small()
-                                    .fill(color)
-                                    .stroke(egui::Stroke::NONE)
-                            } else {
-                                egui::Button::new(search.job)
-                                    .small()
-                                    .stroke(egui::Stroke::NONE)
-                            };
-
-                            let res = if let Some(hover_str) = hover_str {
-                                ui.add_enabled(false, button)
-                                    .on_disabled_hover_text(hover_str)
-                            } else {
-                                ui.add_enabled(false, button)
-                            };
-
-                            if search.is_match && self.scroll_to_match {
-                                res.scroll_to_me(None);
-                                ctx.scroll_to_match = false;
-                            }
-                        };
-
                     match approval_status {
                         ApprovalStatus::Verified => {
-                            mk_searchable_modio_tag(
+                            mk_searchable_tag(
                                 "Verified",
                                 ui,
                                 Some(egui::Color32::LIGHT_GREEN),
@@ -411,7 +813,7 @@ impl App {
                             );
                         }
                         ApprovalStatus::Approved => {
-                            mk_searchable_modio_tag(
+                            mk_searchable_tag(
                                 "Approved",
                                 ui,
                                 Some(egui::Color32::LIGHT_BLUE),
@@ -419,7 +821,7 @@ impl App {
                             );
                         }
                         ApprovalStatus::Sandbox => {
-                            mk_searchable_modio_tag(
+                            mk_searchable_tag(
                                 "Sandbox",
                                 ui,
                                 Some(egui::Color32::LIGHT_YELLOW),
@@ -432,7 +834,7 @@ impl App {
 
                     match required_status {
                         RequiredStatus::RequiredByAll => {
-                            mk_searchable_modio_tag(
+                            mk_searchable_tag(
                                 "RequiredByAll",
                                 ui,
                                 Some(egui::Color32::LIGHT_RED),
@@ -442,7 +844,7 @@ impl App {
                             );
                         }
                         RequiredStatus::Optional => {
-                            mk_searchable_modio_tag(
+                            mk_searchable_tag(
                                 "Optional",
                                 ui,
                                 None,
@@ -452,28 +854,117 @@ impl App {
                     }
 
                     if *qol {
-                        mk_searchable_modio_tag("QoL", ui, None, None);
+                        mk_searchable_tag("QoL", ui, None, None);
                     }
                     if *gameplay {
-                        mk_searchable_modio_tag("Gameplay", ui, None, None);
+                        mk_searchable_tag("Gameplay", ui, None, None);
                     }
                     if *audio {
-                        mk_searchable_modio_tag("Audio", ui, None, None);
+                        mk_searchable_tag("Audio", ui, None, None);
                     }
                     if *visual {
-                        mk_searchable_modio_tag("Visual", ui, None, None);
+                        mk_searchable_tag("Visual", ui, None, None);
                     }
                     if *framework {
-                        mk_searchable_modio_tag("Framework", ui, None, None);
+                        mk_searchable_tag("Framework", ui, None, None);
+                    }
+                }
+                for tag in &info.local_tags {
+                    mk_searchable_tag(tag, ui, None, None);
+                }
+            };
+
+            let ui_replace_file = |ctx: &mut Ctx, ui: &mut Ui, spec: &ModSpecification| {
+                if ui
+                    .button("🔁")
+                    .on_hover_text_at_pointer(
+                        "Replace file\nPoint this mod at another file, keeping its place, priority and enabled state.\nDropping a file onto the row does the same.",
+                    )
+                    .clicked()
+                {
+                    let mut dialog = rfd::FileDialog::new();
+                    if let Some(dir) = Path::new(&spec.url).parent().filter(|d| d.is_dir()) {
+                        dialog = dialog.set_directory(dir);
+                    }
+                    if let Some(path) = dialog.pick_file() {
+                        ctx.replace_file = Some((spec.clone(), path));
+                    }
+                }
+            };
+
+            let ui_copy_menu = |ctx: &mut Ctx, ui: &mut Ui, item: CopyItem| {
+                ui.menu_button("Copy to profile…", |ui| {
+                    for target in &copy_targets {
+                        if ui.button(target).clicked() {
+                            ctx.copy_to_profile = Some((item, target.clone()));
+                            ui.close_menu();
+                            return;
+                        }
                     }
+                });
+            };
+
+            let ui_copy_to_profile = |ctx: &mut Ctx, res: &egui::Response, item: CopyItem| {
+                if copy_targets.is_empty() {
+                    return;
                 }
+                res.context_menu(|ui| ui_copy_menu(ctx, ui, item));
             };
 
+            // tags of the mod after its name, and a context menu to change them
+            let ui_mod_menu =
+                |ctx: &mut Ctx, ui: &mut Ui, res: &egui::Response, mc: &mut ModConfig| {
+                    if !mc.tags.is_empty() {
+                        let tags = mc.tags.iter().cloned().collect::<Vec<_>>();
+                        ui.weak(format!("🏷 {}", tags.join(", ")));
+                    }
+                    res.context_menu(|ui| {
+                        ui.menu_button("Tags…", |ui| {
+                            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                            for tag in &mod_tags {
+                                let mut tagged = mc.tags.contains(tag);
+                                if ui.checkbox(&mut tagged, tag).changed() {
+                                    if tagged {
+                                        mc.tags.insert(tag.clone());
+                                    } else {
+                                        mc.tags.remove(tag);
+                                    }
+                                    ctx.needs_save = true;
+                                }
+                            }
+                            if !mod_tags.is_empty() {
+                                ui.separator();
+                            }
+                            let id = egui::Id::new("new-mod-tag");
+                            let mut new_tag =
+                                ui.data(|d| d.get_temp::<String>(id)).unwrap_or_default();
+                            let res = ui.add(
+                                egui::TextEdit::singleline(&mut new_tag)
+                                    .hint_text("New tag")
+                                    .desired_width(120.),
+                            );
+                            if res.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                && !new_tag.trim().is_empty()
+                            {
+                                mc.tags.insert(new_tag.trim().to_string());
+                                new_tag.clear();
+                                ctx.needs_save = true;
+                            }
+                            ui.data_mut(|d| d.insert_temp(id, new_tag));
+                        });
+                        if !copy_targets.is_empty() {
+                            ui_copy_menu(ctx, ui, CopyItem::Mod(mc.spec.url.clone()));
+                        }
+                    });
+                };
+
             let mut ui_mod = |ctx: &mut Ctx,
                               ui: &mut Ui,
                               mod_location: ModLocation,
                               mc: &mut ModConfig,
-                              override_priority: Option<i32>| {
+                              override_priority: Option<i32>,
+                              priority_offset: i32| {
                 // Extract row_index for move operations (only valid for root mods)
                 let root_index = match &mod_location {
                     ModLocation::Root(idx) => Some(*idx),
@@ -486,6 +977,17 @@ impl App {
                     vis.hyperlink_color = vis.text_color();
                 }
 
+                let mut selected = selected_urls.is_some_and(|urls| urls.contains(&mc.spec.url));
+                if ui
+                    .add(egui::Checkbox::without_text(&mut selected))
+                    .on_hover_text_at_pointer(
+                        "Select for bulk editing\nCtrl-clicking the row does the same",
+                    )
+                    .changed()
+                {
+                    ctx.toggle_selected = Some(mc.spec.url.clone());
+                }
+
                 if ui
                     .add(toggle_switch(&mut mc.enabled))
                     .on_hover_text_at_pointer("Enabled?")
@@ -532,6 +1034,16 @@ impl App {
                     ui.add_enabled(false, icon);
                 }
 
+                if focused_url == Some(&mc.spec.url) {
+                    let icon = egui::Button::new(RichText::new("👉").color(Color32::WHITE))
+                        .fill(ui.visuals().selection.bg_fill);
+                    let res = ui.add_enabled(false, icon);
+                    if ctx.scroll_to_focused {
+                        res.scroll_to_me(Some(egui::Align::Center));
+                        ctx.scroll_to_focused = false;
+                    }
+                }
+
                 if mc.enabled
                     && let Some(req) = &self.integrate_rid
                 {
@@ -558,13 +1070,17 @@ impl App {
                         ModLocation::Root(idx) => format!("version-root-{}", idx),
                         ModLocation::InFolder(folder, idx) => format!("version-{}-{}", folder, idx),
                     };
-                    egui::ComboBox::from_id_salt(combo_id)
-                        .selected_text(
-                            self.state
-                                .store
-                                .get_version_name(&mc.spec)
-                                .unwrap_or_default(),
-                        )
+                    let version_name = self
+                        .state
+                        .store
+                        .get_version_name(&mc.spec)
+                        .unwrap_or_default();
+                    let before = (mc.spec.clone(), mc.update_policy);
+                    let response = egui::ComboBox::from_id_salt(combo_id)
+                        .selected_text(match mc.update_policy {
+                            UpdatePolicy::Pinned => format!("📌 {version_name}"),
+                            _ => version_name,
+                        })
                         .show_ui(ui, |ui| {
                             ui.selectable_value(
                                 &mut mc.spec.url,
@@ -584,14 +1100,62 @@ impl App {
                                         .unwrap_or_default(),
                                 );
                             }
+                            ui.separator();
+                            for (policy, label, hover) in [
+                                (
+                                    UpdatePolicy::Latest,
+                                    "Always latest",
+                                    "Update cache moves this mod to its newest version",
+                                ),
+                                (
+                                    UpdatePolicy::LatestApproved,
+                                    "Latest approved",
+                                    "Update cache holds this mod at its current version while the newest one is only in mod.io's sandbox",
+                                ),
+                                (
+                                    UpdatePolicy::Pinned,
+                                    "Pinned",
+                                    "Update cache keeps this mod at its current version",
+                                ),
+                            ] {
+                                ui.selectable_value(&mut mc.update_policy, policy, label)
+                                    .on_hover_text(hover);
+                            }
+                        })
+                        .response;
+                    if mc.update_policy != UpdatePolicy::Latest {
+                        response.on_hover_text(match mc.update_policy {
+                            UpdatePolicy::Pinned => "Pinned, Update cache keeps this version",
+                            _ => "Update cache skips sandboxed versions of this mod",
                         });
+                    }
+
+                    if mc.spec != before.0 {
+                        // picking a specific version pins it, picking the latest unpins it
+                        if mc.spec.version_id().is_some() {
+                            mc.update_policy = UpdatePolicy::Pinned;
+                        } else if mc.update_policy == UpdatePolicy::Pinned {
+                            mc.update_policy = UpdatePolicy::Latest;
+                        }
+                        ctx.needs_save = true;
+                    } else if mc.update_policy != before.1 {
+                        if mc.update_policy == UpdatePolicy::Pinned {
+                            if let Some(version) = info.resolved_version() {
+                                mc.spec = version.clone();
+                            }
+                        } else if mc.spec.version_id().is_some() {
+                            mc.spec = info.spec.clone();
+                        }
+                        ctx.needs_save = true;
+                    }
 
                     ui.scope(|ui| {
                         ui.style_mut().spacing.interact_size.x = 30.;
                         let dark = ui.visuals().dark_mode;
                         
                         // Use override priority if set, otherwise use mod's own priority
-                        let display_priority = override_priority.unwrap_or(mc.priority);
+                        let display_priority =
+                            override_priority.unwrap_or(mc.priority) + priority_offset;
                         let is_overridden = override_priority.is_some();
                         
                         match display_priority.cmp(&0) {
@@ -645,6 +1209,13 @@ impl App {
                             ).changed() {
                                 ctx.needs_save = true;
                             }
+                            if priority_offset != 0 {
+                                ui.label(format!("{priority_offset:+}")).on_hover_text_at_pointer(
+                                    format!(
+                                        "Offset by the folders containing it\nInstalled with priority {display_priority}"
+                                    ),
+                                );
+                            }
                         }
                     });
 
@@ -656,10 +1227,42 @@ impl App {
                         ui.ctx().copy_text(mc.spec.url.to_string());
                     }
 
+                    if ui
+                        .add_enabled(self.preview_rid.is_none(), egui::Button::new("👁"))
+                        .on_hover_text_at_pointer("Preview contents")
+                        .clicked()
+                    {
+                        ctx.preview = Some(mc.spec.clone());
+                    }
+
+                    let has_payloads = payload_rules.contains_key(&mc.spec.url);
+                    if ui
+                        .selectable_label(has_payloads, "📦")
+                        .on_hover_text_at_pointer(
+                            "Install destinations\nInstall config files or scripts from this mod outside of the mod bundle",
+                        )
+                        .clicked()
+                    {
+                        ctx.payload_rules = Some((info.name.clone(), mc.spec.clone()));
+                    }
+
+                    if LocalMetadata::PROVIDERS.contains(&info.provider)
+                        && ui
+                            .button("✏")
+                            .on_hover_text_at_pointer("Edit name, tags and dependencies")
+                            .clicked()
+                    {
+                        ctx.local_metadata = Some((info.name.clone(), mc.spec.clone()));
+                    }
+
+                    if info.provider == "file" {
+                        ui_replace_file(ctx, ui, &mc.spec);
+                    }
+
                     if mc.enabled {
-                        let is_duplicate = enabled_specs.iter().any(|(loc, spec)| {
-                            *loc != mod_location && info.spec.satisfies_dependency(spec)
-                        });
+                        let is_duplicate = enabled_specs
+                            .iter()
+                            .any(|(loc, spec)| *loc != mod_location && info.spec.same_mod(spec));
                         if is_duplicate
                             && ui
                                 .button(
@@ -700,6 +1303,31 @@ impl App {
                                 ctx.add_deps = Some(missing_deps.into_iter().cloned().collect());
                             }
                         }
+
+                        let violated = info
+                            .dependency_constraints
+                            .iter()
+                            .filter_map(|(dep, constraint)| {
+                                let version = enabled_specs
+                                    .iter()
+                                    .find(|(_, s)| s.same_mod(dep))
+                                    .and_then(|(_, s)| self.state.store.get_mod_info(s))?
+                                    .version_id()?;
+                                (!constraint.allows(version)).then(|| {
+                                    format!("{} {constraint}, enabled: {version}", dep.url)
+                                })
+                            })
+                            .collect::<Vec<_>>();
+                        if !violated.is_empty() {
+                            ui.label(
+                                egui::RichText::new("\u{26A0}")
+                                    .color(ui.visuals().error_fg_color),
+                            )
+                            .on_hover_text(format!(
+                                "Enabled dependency versions do not match what this mod requires:\n{}",
+                                violated.join("\n")
+                            ));
+                        }
                     }
 
                     match info.provider {
@@ -731,9 +1359,57 @@ impl App {
                         "file" => {
                             ui.label("📁");
                         }
+                        "torrent" => {
+                            ui.label("🧲");
+                        }
+                        "ipfs" => {
+                            ui.label("🪐");
+                        }
                         _ => unimplemented!("unimplemented provider kind"),
                     }
 
+                    if self.state.config.show_thumbnails
+                        && let Some(url) = self.state.store.thumbnail_url(&mc.spec)
+                    {
+                        let thumbnail =
+                            self.thumbnails
+                                .entry(url.clone())
+                                .or_insert_with_key(|url| {
+                                    ctx.fetch_thumbnails.push(url.clone());
+                                    Thumbnail::Loading
+                                });
+                        if let Thumbnail::Failed(at) = thumbnail
+                            && at.elapsed() >= THUMBNAIL_RETRY
+                        {
+                            ctx.fetch_thumbnails.push(url);
+                            *thumbnail = Thumbnail::Loading;
+                        }
+                        if let Thumbnail::Image(image) = thumbnail {
+                            let size = [image.width() as _, image.height() as _];
+                            let image = egui::ColorImage::from_rgba_unmultiplied(
+                                size,
+                                image.as_flat_samples().as_slice(),
+                            );
+                            *thumbnail = Thumbnail::Texture(ui.ctx().load_texture(
+                                format!("thumbnail-{}", mc.spec.url),
+                                image,
+                                Default::default(),
+                            ));
+                        }
+                        match thumbnail {
+                            Thumbnail::Texture(texture) => {
+                                let image =
+                                    egui::Image::new(&*texture).fit_to_exact_size(THUMBNAIL_SIZE);
+                                ui.add(image).on_hover_ui(|ui| {
+                                    ui.add(egui::Image::new(&*texture).max_width(320.0));
+                                });
+                            }
+                            _ => {
+                                ui.allocate_space(THUMBNAIL_SIZE);
+                            }
+                        }
+                    }
+
                     let search = searchable_text(&info.name, &self.search_string, {
                         TextFormat {
                             color: ui.visuals().hyperlink_color,
@@ -772,6 +1448,14 @@ impl App {
                         res.scroll_to_me(None);
                         ctx.scroll_to_match = false;
                     }
+                    ui_mod_menu(ctx, ui, &res, mc);
+
+                    if info.provider != "modio"
+                        && let Some(status) =
+                            self.state.store.signature_status(&info.resolution.url.0)
+                    {
+                        ui_signature(ui, &status);
+                    }
 
                     ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
                         ui_mod_tags(ctx, ui, info);
@@ -785,6 +1469,11 @@ impl App {
                         ui.ctx().copy_text(mc.spec.url.to_string());
                     }
 
+                    // a local file which can't be resolved was most likely moved or renamed
+                    if is_local_path(&mc.spec.url) {
+                        ui_replace_file(ctx, ui, &mc.spec);
+                    }
+
                     let search = searchable_text(&mc.spec.url, &self.search_string, {
                         TextFormat {
                             color: ui.visuals().hyperlink_color,
@@ -825,183 +1514,442 @@ impl App {
                         res.scroll_to_me(None);
                         ctx.scroll_to_match = false;
                     }
+                    ui_mod_menu(ctx, ui, &res, mc);
                 }
-            };
 
-            let mut ui_item =
-                |ctx: &mut Ctx, ui: &mut Ui, mc: &mut ModOrGroup, row_index: usize| {
-                    ui.scope(|ui| {
-                        ui.visuals_mut().widgets.hovered.weak_bg_fill = colors::DARK_RED;
-                        ui.visuals_mut().widgets.active.weak_bg_fill = colors::DARKER_RED;
-                        if ui
-                            .add(Button::new(" 🗑 "))
-                            .on_hover_text_at_pointer(match mc {
-                                ModOrGroup::Individual(_) => "Delete mod",
-                                ModOrGroup::Group { .. } => "Delete folder (mods move to root)",
-                            })
-                            .clicked()
-                        {
-                            match mc {
-                                ModOrGroup::Individual(mod_config) => {
-                                    ctx.pending_delete = Some((mod_config.spec.url.clone(), row_index));
-                                }
-                                ModOrGroup::Group { group_name, .. } => {
-                                    ctx.pending_folder_delete = Some(group_name.clone());
-                                }
-                            }
+                let is_file = info
+                    .as_ref()
+                    .map_or_else(|| is_local_path(&mc.spec.url), |i| i.provider == "file");
+                if is_file {
+                    // a single file dropped onto the row replaces the mod's file
+                    let row = ui.min_rect();
+                    let (hovered, dropped) = ui.input(|i| {
+                        let over = i.pointer.latest_pos().is_some_and(|p| row.contains(p));
+                        let dropped = match i.raw.dropped_files.as_slice() {
+                            [file] if over => file.path.clone(),
+                            _ => None,
                         };
+                        (over && i.raw.hovered_files.len() == 1, dropped)
                     });
+                    if hovered {
+                        ui.painter().rect_stroke(
+                            row,
+                            2.0,
+                            ui.visuals().selection.stroke,
+                            egui::StrokeKind::Outside,
+                        );
+                    }
+                    if let Some(path) = dropped {
+                        ctx.replace_file = Some((mc.spec.clone(), path));
+                    }
+                }
 
-                    match mc {
-                        ModOrGroup::Individual(mc) => {
-                            ui_mod(ctx, ui, ModLocation::Root(row_index), mc, None);
-                        }
-                        ModOrGroup::Group {
-                            group_name,
-                            enabled,
-                        } => {
+                // ctrl-clicking the row anywhere but on its buttons toggles its selection
+                let row = ui.min_rect();
+                if ui.input(|i| {
+                    i.modifiers.command
+                        && i.pointer.primary_clicked()
+                        && i.pointer.interact_pos().is_some_and(|p| row.contains(p))
+                }) && ui.ctx().interaction_snapshot(|s| s.clicked.is_none())
+                {
+                    ctx.toggle_selected = Some(mc.spec.url.clone());
+                }
+            };
+
+            let ui_folder_priority = |ctx: &mut Ctx,
+                                      ui: &mut Ui,
+                                      folder: &str,
+                                      group: &mut ModGroup| {
+                const OVERRIDE_HINT: &str = "All mods in this folder and the folders nested in it use the folder's priority";
+                const OFFSET_HINT: &str = "The folder's priority is added to that of each mod in this folder and the folders nested in it, keeping their order";
+                ui.label("Folder priority:");
+                let selected = if group.priority_override.is_some() {
+                    "Override"
+                } else if group.priority_offset.is_some() {
+                    "Offset"
+                } else {
+                    "None"
+                };
+                egui::ComboBox::from_id_salt(("folder-priority", folder))
+                    .selected_text(selected)
+                    .width(70.0)
+                    .show_ui(ui, |ui| {
+                        let modes = [
+                            ("None", "Mods use their own priority", None, None),
+                            ("Override", OVERRIDE_HINT, Some(0), None),
+                            ("Offset", OFFSET_HINT, None, Some(0)),
+                        ];
+                        for (mode, hint, priority_override, priority_offset) in modes {
                             if ui
-                                .add(toggle_switch(enabled))
-                                .on_hover_text_at_pointer("Enabled?")
-                                .changed()
+                                .selectable_label(selected == mode, mode)
+                                .on_hover_text(hint)
+                                .clicked()
+                                && selected != mode
                             {
+                                group.priority_override = priority_override;
+                                group.priority_offset = priority_offset;
                                 ctx.needs_save = true;
                             }
-                            
-                            // Rename button for folder
-                            if ui.button("✏").on_hover_text("Rename folder").clicked() {
-                                ctx.rename_folder = Some(group_name.clone());
-                            }
-                            
-                            let group_name_clone = group_name.clone();
-                            let folder_id = ui.make_persistent_id(format!("folder-{}", group_name));
-                            
-                            // Check if this folder should be opened (e.g., after moving a mod into it)
-                            let should_open = self.expand_folder.as_ref() == Some(group_name);
-                            
-                            // Check if folder name matches search - if so, also expand it
-                            let folder_matches_search = !self.search_string.is_empty() 
-                                && group_name.to_lowercase().contains(&self.search_string.to_lowercase());
-                            
-                            // Create searchable folder name for highlighting
-                            let folder_label = searchable_text(group_name.as_str(), &self.search_string, {
-                                TextFormat {
-                                    color: ui.visuals().hyperlink_color,
-                                    ..Default::default()
-                                }
-                            });
-                            
-                            // Use open() to force-open when a mod was just moved in or when folder name matches search
-                            let mut header = egui::CollapsingHeader::new(folder_label.job)
-                                .id_salt(folder_id)
-                                .default_open(false);
-                            
-                            if should_open || folder_matches_search {
-                                header = header.open(Some(true));
-                            }
-                            
-                            let header_response = header.show(ui, |ui| {
-                                    if let Some(group) = profile.groups.get_mut(&group_name_clone) {
-                                        // Folder priority override controls
-                                        ui.horizontal(|ui| {
-                                            let has_override = group.priority_override.is_some();
-                                            let mut override_enabled = has_override;
-                                            
-                                            if ui.checkbox(&mut override_enabled, "Priority override:")
-                                                .on_hover_text("When enabled, all mods in this folder use the folder's priority")
-                                                .changed()
-                                            {
-                                                if override_enabled {
-                                                    group.priority_override = Some(0);
-                                                } else {
-                                                    group.priority_override = None;
-                                                }
-                                                ctx.needs_save = true;
-                                            }
-                                            
-                                            if let Some(ref mut priority) = group.priority_override {
-                                                if ui.add(egui::DragValue::new(priority)).changed() {
-                                                    ctx.needs_save = true;
-                                                }
-                                            }
-                                        });
-                                        
-                                        ui.separator();
-                                        
-                                        let override_priority = group.priority_override;
-                                        let mut move_out_index: Option<usize> = None;
-                                        let mut move_to_other_folder: Option<(usize, String)> = None;
-                                        let mut delete_mod_index: Option<usize> = None;
-                                        
-                                        for (index, m) in group.mods.iter_mut().enumerate() {
-                                            ui.horizontal(|ui| {
-                                                // Delete button (red styling)
-                                                ui.scope(|ui| {
-                                                    ui.visuals_mut().widgets.hovered.weak_bg_fill = colors::DARK_RED;
-                                                    ui.visuals_mut().widgets.active.weak_bg_fill = colors::DARKER_RED;
-                                                    if ui.button(" 🗑 ").on_hover_text("Delete mod").clicked() {
-                                                        delete_mod_index = Some(index);
-                                                    }
+                        }
+                    });
+                if let Some(priority) = &mut group.priority_override
+                    && ui
+                        .add(egui::DragValue::new(priority))
+                        .on_hover_text(OVERRIDE_HINT)
+                        .changed()
+                {
+                    ctx.needs_save = true;
+                }
+                if let Some(offset) = &mut group.priority_offset
+                    && ui
+                        .add(egui::DragValue::new(offset).custom_formatter(|n, _| format!("{n:+}")))
+                        .on_hover_text(OFFSET_HINT)
+                        .changed()
+                {
+                    ctx.needs_save = true;
+                }
+                help::button(ui, HelpTopic::Priorities);
+            };
+
+            let ui_move_folder = |ctx: &mut Ctx, ui: &mut Ui, folder: &str, nested: bool| {
+                if !nested && folder_names.len() < 2 {
+                    return;
+                }
+                egui::ComboBox::from_id_salt(format!("move-folder-{folder}"))
+                    .selected_text("📁")
+                    .width(40.0)
+                    .show_ui(ui, |ui| {
+                        if nested && ui.selectable_label(false, "📤 (root)").clicked() {
+                            ctx.move_folder = Some((folder.to_string(), None));
+                        }
+                        for other_folder in folder_names.iter().filter(|f| *f != folder) {
+                            if ui
+                                .selectable_label(false, format!("📁 {other_folder}"))
+                                .clicked()
+                            {
+                                ctx.move_folder =
+                                    Some((folder.to_string(), Some(other_folder.clone())));
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Move folder into...");
+            };
+
+            let mut ui_item = |ctx: &mut Ctx,
+                               ui: &mut Ui,
+                               mc: &mut ModOrGroup,
+                               row_index: usize| {
+                ui.scope(|ui| {
+                    ui.visuals_mut().widgets.hovered.weak_bg_fill = colors::DARK_RED;
+                    ui.visuals_mut().widgets.active.weak_bg_fill = colors::DARKER_RED;
+                    if ui
+                        .add(Button::new(" 🗑 "))
+                        .on_hover_text_at_pointer(match mc {
+                            ModOrGroup::Individual(_) => "Delete mod",
+                            ModOrGroup::Group { .. } => "Delete folder (mods move to root)",
+                        })
+                        .clicked()
+                    {
+                        match mc {
+                            ModOrGroup::Individual(mod_config) => {
+                                ctx.pending_delete = Some((mod_config.spec.url.clone(), row_index));
+                            }
+                            ModOrGroup::Group { group_name, .. } => {
+                                ctx.pending_folder_delete = Some(group_name.clone());
+                            }
+                        }
+                    };
+                });
+
+                match mc {
+                    ModOrGroup::Individual(mc) => {
+                        ui_mod(ctx, ui, ModLocation::Root(row_index), mc, None, 0);
+                    }
+                    ModOrGroup::Group {
+                        group_name,
+                        enabled,
+                    } => {
+                        if ui
+                            .add(toggle_switch(enabled))
+                            .on_hover_text_at_pointer("Enabled?")
+                            .changed()
+                        {
+                            ctx.folder_toggled = Some((group_name.clone(), *enabled));
+                            ctx.needs_save = true;
+                        }
+
+                        // Rename button for folder
+                        if ui.button("✏").on_hover_text("Rename folder").clicked() {
+                            ctx.rename_folder = Some(group_name.clone());
+                        }
+                        ui_move_folder(ctx, ui, group_name.as_str(), false);
+
+                        let group_name_clone = group_name.clone();
+                        let folder_id = ui.make_persistent_id(format!("folder-{}", group_name));
+
+                        // Check if this folder should be opened (e.g., after moving a mod into it or
+                        // into a folder nested in it)
+                        let should_open = expanded_folders.contains(group_name);
+
+                        // Check if folder name matches search - if so, also expand it
+                        let folder_matches_search = !self.search_string.is_empty()
+                            && group_name
+                                .to_lowercase()
+                                .contains(&self.search_string.to_lowercase());
+
+                        // Create searchable folder name for highlighting
+                        let folder_label =
+                            searchable_text(group_name.as_str(), &self.search_string, {
+                                TextFormat {
+                                    color: ui.visuals().hyperlink_color,
+                                    ..Default::default()
+                                }
+                            });
+
+                        // Use open() to force-open when a mod was just moved in or when folder name matches search
+                        let mut header = egui::CollapsingHeader::new(folder_label.job)
+                            .id_salt(folder_id)
+                            .default_open(false);
+
+                        if should_open || folder_matches_search {
+                            header = header.open(Some(true));
+                        }
+
+                        let header_response = header.show(ui, |ui| {
+                            let egui_ctx = ui.ctx().clone();
+                            let is_open = |folder: &str| {
+                                folder_forced_open(folder)
+                                    || CollapsingState::load(&egui_ctx, folder_state_id(folder))
+                                        .is_some_and(|s| s.is_open())
+                            };
+                            let Some(group) = profile.groups.get_mut(&group_name_clone) else {
+                                return;
+                            };
+                            ui.horizontal(|ui| {
+                                ui_folder_priority(ctx, ui, &group_name_clone, group)
+                            });
+                            ui.separator();
+                            let override_priority = group.priority_override;
+                            let priority_offset = group.priority_offset.unwrap_or(0);
+
+                            let mut rows = vec![];
+                            folder_rows(
+                                &profile.groups,
+                                &mut vec![group_name_clone.clone()],
+                                override_priority,
+                                priority_offset,
+                                &order_entries,
+                                &is_open,
+                                &mut rows,
+                            );
+                            for row in rows {
+                                let (folder, index, depth, override_priority, priority_offset) =
+                                    match row {
+                                        FolderRow::Settings { folder, depth } => {
+                                            if let Some(group) = profile.groups.get_mut(&folder) {
+                                                ui.horizontal(|ui| {
+                                                    ui.add_space(FOLDER_INDENT * depth as f32);
+                                                    ui_folder_priority(ctx, ui, &folder, group);
                                                 });
-                                                
-                                                // Move dropdown - shows root + other folders
-                                                egui::ComboBox::from_id_salt(format!("move-in-folder-{}-{}", group_name_clone, index))
-                                                    .selected_text("📁")
-                                                    .width(40.0)
-                                                    .show_ui(ui, |ui| {
-                                                        // Option to move to root
-                                                        if ui.selectable_label(false, "📤 (root)").clicked() {
-                                                            move_out_index = Some(index);
-                                                        }
-                                                        ui.separator();
-                                                        // Options for other folders
-                                                        for other_folder in &folder_names {
-                                                            if other_folder != &group_name_clone {
-                                                                if ui.selectable_label(false, format!("📁 {}", other_folder)).clicked() {
-                                                                    move_to_other_folder = Some((index, other_folder.clone()));
-                                                                }
-                                                            }
-                                                        }
-                                                    })
-                                                    .response
-                                                    .on_hover_text("Move to...");
-                                                
-                                                ui_mod(ctx, ui, ModLocation::InFolder(group_name_clone.clone(), index), m, override_priority);
-                                            });
+                                            }
+                                            continue;
                                         }
-                                        if let Some(idx) = move_out_index {
-                                            ctx.move_mod_from_folder = Some((group_name_clone.clone(), idx));
+                                        FolderRow::Entry {
+                                            folder,
+                                            index,
+                                            depth,
+                                            override_priority,
+                                            priority_offset,
+                                        } => (
+                                            folder,
+                                            index,
+                                            depth,
+                                            override_priority,
+                                            priority_offset,
+                                        ),
+                                    };
+                                let Some(entry) = profile
+                                    .groups
+                                    .get_mut(&folder)
+                                    .and_then(|g| g.mods.get_mut(index))
+                                else {
+                                    continue;
+                                };
+                                ui.horizontal(|ui| {
+                                    ui.add_space(FOLDER_INDENT * depth as f32);
+                                    // Delete button (red styling)
+                                    ui.scope(|ui| {
+                                        ui.visuals_mut().widgets.hovered.weak_bg_fill =
+                                            colors::DARK_RED;
+                                        ui.visuals_mut().widgets.active.weak_bg_fill =
+                                            colors::DARKER_RED;
+                                        let hover_text = match &*entry {
+                                            ModOrGroup::Individual(_) => "Delete mod",
+                                            ModOrGroup::Group { .. } => {
+                                                "Delete folder (contents move up a level)"
+                                            }
+                                        };
+                                        if ui.button(" 🗑 ").on_hover_text(hover_text).clicked() {
+                                            match &*entry {
+                                                ModOrGroup::Individual(_) => {
+                                                    ctx.pending_folder_mod_delete =
+                                                        Some((folder.clone(), index));
+                                                }
+                                                ModOrGroup::Group { group_name, .. } => {
+                                                    ctx.pending_folder_delete =
+                                                        Some(group_name.clone());
+                                                }
+                                            }
                                         }
-                                        if let Some((idx, target_folder)) = move_to_other_folder {
-                                            ctx.move_mod_between_folders = Some((group_name_clone.clone(), idx, target_folder));
+                                    });
+
+                                    match entry {
+                                        ModOrGroup::Individual(m) => {
+                                            // Move dropdown - shows root + other folders
+                                            egui::ComboBox::from_id_salt(format!(
+                                                "move-in-folder-{}-{}",
+                                                folder, index
+                                            ))
+                                            .selected_text("📁")
+                                            .width(40.0)
+                                            .show_ui(ui, |ui| {
+                                                // Option to move to root
+                                                if ui.selectable_label(false, "📤 (root)").clicked()
+                                                {
+                                                    ctx.move_mod_from_folder =
+                                                        Some((folder.clone(), index));
+                                                }
+                                                ui.separator();
+                                                // Options for other folders
+                                                for other_folder in
+                                                    folder_names.iter().filter(|f| **f != folder)
+                                                {
+                                                    if ui
+                                                        .selectable_label(
+                                                            false,
+                                                            format!("📁 {}", other_folder),
+                                                        )
+                                                        .clicked()
+                                                    {
+                                                        ctx.move_mod_between_folders = Some((
+                                                            folder.clone(),
+                                                            index,
+                                                            other_folder.clone(),
+                                                        ));
+                                                    }
+                                                }
+                                            })
+                                            .response
+                                            .on_hover_text("Move to...");
+
+                                            ui_mod(
+                                                ctx,
+                                                ui,
+                                                ModLocation::InFolder(folder.clone(), index),
+                                                m,
+                                                override_priority,
+                                                priority_offset,
+                                            );
                                         }
-                                        if let Some(idx) = delete_mod_index {
-                                            // Get mod name for confirmation
-                                            if group.mods.get(idx).is_some() {
-                                                ctx.pending_folder_mod_delete = Some((group_name_clone.clone(), idx));
+                                        ModOrGroup::Group {
+                                            group_name,
+                                            enabled,
+                                        } => {
+                                            let mut state = CollapsingState::load_with_default_open(
+                                                ui.ctx(),
+                                                folder_state_id(group_name),
+                                                false,
+                                            );
+                                            if folder_forced_open(group_name.as_str()) {
+                                                state.set_open(true);
+                                            }
+                                            state.show_toggle_button(
+                                                ui,
+                                                egui::collapsing_header::paint_default_icon,
+                                            );
+                                            state.store(ui.ctx());
+
+                                            if ui
+                                                .add(toggle_switch(enabled))
+                                                .on_hover_text_at_pointer("Enabled?")
+                                                .changed()
+                                            {
+                                                ctx.folder_toggled =
+                                                    Some((group_name.clone(), *enabled));
+                                                ctx.needs_save = true;
+                                            }
+                                            ui_move_folder(ctx, ui, group_name.as_str(), true);
+                                            if ui
+                                                .button("✏")
+                                                .on_hover_text("Rename folder")
+                                                .clicked()
+                                            {
+                                                ctx.rename_folder = Some(group_name.clone());
+                                            }
+
+                                            let label = searchable_text(
+                                                group_name.as_str(),
+                                                &self.search_string,
+                                                {
+                                                    TextFormat {
+                                                        color: ui.visuals().hyperlink_color,
+                                                        ..Default::default()
+                                                    }
+                                                },
+                                            );
+                                            let res = ui.label(label.job);
+                                            if label.is_match && self.scroll_to_match {
+                                                res.scroll_to_me(None);
+                                                ctx.scroll_to_match = false;
                                             }
+                                            ui_copy_to_profile(
+                                                ctx,
+                                                &res,
+                                                CopyItem::Folder(group_name.clone()),
+                                            );
                                         }
                                     }
                                 });
-                            
-                            // Scroll to folder if it matches the search
-                            if folder_matches_search && self.scroll_to_match {
-                                header_response.header_response.scroll_to_me(None);
-                                ctx.scroll_to_match = false;
                             }
+                        });
+
+                        // Scroll to folder if it matches the search
+                        if folder_matches_search && self.scroll_to_match {
+                            header_response.header_response.scroll_to_me(None);
+                            ctx.scroll_to_match = false;
                         }
+                        ui_copy_to_profile(
+                            ctx,
+                            &header_response.header_response,
+                            CopyItem::Folder(group_name_clone),
+                        );
                     }
-                };
+                }
+            };
+
+            if let Some(sorting_config) = sorting_config.clone() {
+                let comp = sort_mods(sorting_config.clone());
+                let folder_comp = sort_folders(sorting_config);
 
-            if let Some(sorting_config) = sorting_config {
-                let comp = sort_mods(sorting_config);
-                
                 // Collect indices and info for folders and individuals separately
-                let folder_indices: Vec<usize> = profile.mods.iter()
+                let mut folder_indices: Vec<usize> = profile
+                    .mods
+                    .iter()
                     .enumerate()
                     .filter(|(_, m)| matches!(m, ModOrGroup::Group { .. }))
                     .map(|(i, _)| i)
                     .collect();
+                folder_indices.sort_by(|&a, &b| {
+                    let folder = |i: usize| match &profile.mods[i] {
+                        ModOrGroup::Group {
+                            group_name,
+                            enabled,
+                        } => (group_name.as_str(), *enabled),
+                        ModOrGroup::Individual(_) => unreachable!(),
+                    };
+                    folder_comp(folder(a), folder(b))
+                });
                 
                 let mut individual_data: Vec<(usize, Option<ModInfo>)> = profile.mods.iter()
                     .enumerate()
@@ -1017,12 +1965,15 @@ impl App {
                 
                 // Sort the individual mods by comparing their data
                 individual_data.sort_by(|(idx_a, info_a), (idx_b, info_b)| {
-                    let a = &profile.mods[*idx_a];
-                    let b = &profile.mods[*idx_b];
+                    let (ModOrGroup::Individual(a), ModOrGroup::Individual(b)) =
+                        (&profile.mods[*idx_a], &profile.mods[*idx_b])
+                    else {
+                        unreachable!()
+                    };
                     comp((a, info_a.as_ref()), (b, info_b.as_ref()))
                 });
                 
-                // Display folders first (unsorted, original order)
+                // Display folders first, sorted as units
                 let mut visual_index = 0;
                 for store_index in &folder_indices {
                     let mut frame = egui::Frame::NONE;
@@ -1093,6 +2044,48 @@ impl App {
             }
         });
 
+        if let Some(url) = ctx.toggle_selected {
+            if self.selection.as_ref().is_none_or(|s| s.profile != profile) {
+                self.selection = Some(ModSelection {
+                    profile: profile.to_string(),
+                    ..Default::default()
+                });
+            }
+            let urls = &mut self.selection.as_mut().unwrap().urls;
+            if !urls.remove(&url) {
+                urls.insert(url);
+            }
+        }
+
+        if let Some((item, target)) = ctx.copy_to_profile {
+            let mut urls = HashSet::new();
+            let mut folders = HashSet::new();
+            match item {
+                CopyItem::Mod(url) => match self.selection.as_ref() {
+                    Some(s) if s.profile == profile && s.urls.contains(&url) => {
+                        urls = s.urls.clone();
+                    }
+                    _ => {
+                        urls.insert(url);
+                    }
+                },
+                CopyItem::Folder(folder) => {
+                    folders.insert(folder);
+                }
+            }
+            let (copied, skipped) = self
+                .state
+                .mod_data
+                .copy_to_profile(profile, &target, &urls, &folders);
+            let mut message = format!("Copied {copied} mods to {target:?}");
+            if skipped > 0 {
+                message.push_str(&format!(", skipped {skipped} already there"));
+            }
+            self.last_action = Some(LastAction::success(message));
+            self.record_feature("copy_to_profile");
+            ctx.needs_save = true;
+        }
+
         // Transfer pending deletion to App for confirmation dialog
         if let Some((mod_name, row_index)) = ctx.pending_delete {
             self.pending_deletion = Some(PendingDeletion::Mod {
@@ -1119,7 +2112,7 @@ impl App {
                         profile.mods.remove(mod_index);
                         // Add to folder (we know it exists)
                         if let Some(group) = profile.groups.get_mut(&folder_name) {
-                            group.mods.push(mod_config);
+                            group.mods.push(ModOrGroup::Individual(mod_config));
                         }
                         // Expand the folder so user can see where mod went
                         self.expand_folder = Some(folder_name);
@@ -1143,7 +2136,7 @@ impl App {
                     if mod_index < group.mods.len() {
                         let mod_config = group.mods.remove(mod_index);
                         // Add to root of profile
-                        profile.mods.push(ModOrGroup::Individual(mod_config));
+                        profile.mods.push(mod_config);
                         ctx.needs_save = true;
                     }
                 }
@@ -1176,6 +2169,42 @@ impl App {
             }
         }
 
+        // Handle move folder into another folder or back to the root
+        if let Some((folder_name, into)) = ctx.move_folder {
+            let active_profile = self.state.mod_data.active_profile.clone();
+            if let Some(profile) = self.state.mod_data.profiles.get_mut(&active_profile)
+                && profile.move_folder(&folder_name, into.as_deref())
+            {
+                self.expand_folder = Some(folder_name);
+                ctx.needs_save = true;
+            }
+        }
+
+        // Warn about mods left without their dependencies by the folder
+        if let Some((folder, enabled)) = ctx.folder_toggled {
+            let store = &self.state.store;
+            let dependencies = |spec: &ModSpecification| {
+                store
+                    .get_mod_info(spec)
+                    .map(|info| info.suggested_dependencies)
+                    .unwrap_or_default()
+            };
+            let broken = self.state.mod_data.folder_broken_dependencies(
+                profile,
+                &folder,
+                enabled,
+                dependencies,
+            );
+            if !broken.is_empty() {
+                self.folder_dependencies_window = Some(WindowFolderDependencies {
+                    profile: profile.to_string(),
+                    folder,
+                    enabled,
+                    broken,
+                });
+            }
+        }
+
         // Handle folder deletion request
         if let Some(folder_name) = ctx.pending_folder_delete {
             self.pending_deletion = Some(PendingDeletion::Folder { folder_name });
@@ -1186,7 +2215,7 @@ impl App {
             let active_profile = self.state.mod_data.active_profile.clone();
             if let Some(profile) = self.state.mod_data.profiles.get(&active_profile) {
                 if let Some(group) = profile.groups.get(&folder_name) {
-                    if let Some(m) = group.mods.get(mod_index) {
+                    if let Some(m) = group.mods.get(mod_index).and_then(ModOrGroup::as_mod) {
                         self.pending_deletion = Some(PendingDeletion::FolderMod { 
                             folder_name, 
                             mod_index,
@@ -1202,90 +2231,659 @@ impl App {
             self.problematic_mod_id = None;
         }
 
+        if let Some(spec) = ctx.preview {
+            message::PreviewMod::send(self, ui.ctx(), spec);
+        }
+
+        for url in ctx.fetch_thumbnails {
+            message::FetchThumbnail::send(self, ui.ctx(), url);
+        }
+
+        if let Some((name, spec)) = ctx.payload_rules {
+            let rules = self.state.mod_data.payload_rules.get(&spec.url);
+            self.payload_rules_window = Some(WindowPayloadRules::new(name, spec, rules));
+        }
+
+        if let Some((name, spec)) = ctx.local_metadata {
+            let metadata = self.state.mod_data.local_metadata.get(&spec.url);
+            self.local_metadata_window = Some(WindowLocalMetadata::new(name, spec, metadata));
+        }
+
+        if let Some((spec, path)) = ctx.replace_file {
+            // keep the drop from also adding the file as a new mod
+            ui.ctx().input_mut(|i| i.raw.dropped_files.clear());
+            self.replace_mod_url(
+                &spec.url,
+                ModSpecification::new(path.to_string_lossy().into()),
+            );
+        }
+
         self.scroll_to_match = ctx.scroll_to_match;
+        self.scroll_to_focused = ctx.scroll_to_focused;
 
         if ctx.needs_save {
             self.state.mod_data.save().unwrap();
         }
     }
 
-    fn parse_mods(&self) -> Vec<ModSpecification> {
-        self.resolve_mod
-            .lines()
-            .map(|l| l.trim())
-            .filter(|l| !l.is_empty())
-            .map(|l| {
-                // Strip URL fragment (e.g., "#description" from mod.io URLs)
-                let url = l.split('#').next().unwrap_or(l);
-                ModSpecification::new(url.to_string())
-            })
-            .collect()
+    /// Select the mod with `url` in the active profile, open the folders containing it and scroll
+    /// to it. Does nothing if the profile does not have it.
+    fn focus_mod(&mut self, url: String) {
+        let mut folder = None;
+        self.state
+            .mod_data
+            .get_active_profile()
+            .visit(|entry, path| {
+                if folder.is_none() && entry.as_mod().is_some_and(|mc| mc.spec.url == url) {
+                    folder = Some(path.last().map(|(name, _)| name.to_string()));
+                }
+            });
+        let Some(folder) = folder else {
+            return;
+        };
+        self.expand_folder = folder;
+        self.selection = Some(ModSelection {
+            profile: self.state.mod_data.active_profile.clone(),
+            urls: HashSet::from([url.clone()]),
+            ..Default::default()
+        });
+        self.focused_mod = Some(url);
+        self.scroll_to_focused = true;
     }
 
-    fn build_mod_string(mods: &Vec<ModConfig>) -> String {
-        let mut string = String::new();
-        for m in mods {
-            if m.enabled {
-                string.push_str(&m.spec.url);
-                string.push('\n');
-            }
+    /// Actions on the mods checked in `profile`, shown while any are.
+    fn ui_bulk_actions(&mut self, ui: &mut Ui, profile: &str) {
+        let Some(selection) = self
+            .selection
+            .as_mut()
+            .filter(|s| s.profile == profile && !s.urls.is_empty())
+        else {
+            return;
+        };
+        let Some(mod_profile) = self.state.mod_data.profiles.get_mut(profile) else {
+            return;
+        };
+        // drop mods deleted since they were checked
+        let all_urls = mod_profile
+            .all_entries()
+            .filter_map(ModOrGroup::as_mod)
+            .map(|mc| mc.spec.url.clone())
+            .collect::<HashSet<_>>();
+        selection.urls.retain(|url| all_urls.contains(url));
+        if selection.urls.is_empty() {
+            return;
         }
-        string
-    }
 
-    fn create_backup(dirs: &Dirs, backup_base_path: &str) -> Result<String, String> {
-        use std::fs;
-        use chrono::Local;
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.label(format!("{} selected:", selection.urls.len()));
+            if ui.button("Enable").clicked() {
+                action = Some(BulkAction::Enable(true));
+            }
+            if ui.button("Disable").clicked() {
+                action = Some(BulkAction::Enable(false));
+            }
+            ui.add(egui::DragValue::new(&mut selection.priority));
+            if ui.button("Set priority").clicked() {
+                action = Some(BulkAction::SetPriority(selection.priority));
+            }
+            egui::ComboBox::from_id_salt(("bulk-move", profile))
+                .selected_text("📁 Move to")
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(false, "📤 (root)").clicked() {
+                        action = Some(BulkAction::MoveTo(None));
+                    }
+                    for folder in mod_profile.groups.keys() {
+                        if ui.selectable_label(false, format!("📁 {folder}")).clicked() {
+                            action = Some(BulkAction::MoveTo(Some(folder.clone())));
+                        }
+                    }
+                });
+            ui.scope(|ui| {
+                ui.visuals_mut().widgets.hovered.weak_bg_fill = colors::DARK_RED;
+                ui.visuals_mut().widgets.active.weak_bg_fill = colors::DARKER_RED;
+                if ui.button(" 🗑 ").on_hover_text("Delete selected").clicked() {
+                    action = Some(BulkAction::Delete);
+                }
+            });
+            if ui.button("Select all").clicked() {
+                selection.urls = all_urls;
+            }
+            if ui.button("Clear").clicked() {
+                selection.urls.clear();
+            }
+        });
+        ui.separator();
 
-        // Create timestamp for backup folder name
-        let timestamp = Local::now().format("%Y-%m-%d-%H-%M-%S").to_string();
-        let backup_folder_name = format!("backup_{}", timestamp);
-        let backup_path = PathBuf::from(backup_base_path).join(&backup_folder_name);
+        let Some(action) = action else {
+            return;
+        };
+        match action {
+            BulkAction::Enable(enabled) => {
+                for mc in mod_profile.mods_with_urls_mut(&selection.urls) {
+                    mc.enabled = enabled;
+                }
+            }
+            BulkAction::SetPriority(priority) => {
+                for mc in mod_profile.mods_with_urls_mut(&selection.urls) {
+                    mc.priority = priority;
+                }
+            }
+            BulkAction::MoveTo(into) => {
+                mod_profile.move_mods(&selection.urls, into.as_deref());
+                self.expand_folder = into;
+            }
+            BulkAction::Delete => {
+                self.pending_deletion = Some(PendingDeletion::Mods {
+                    profile: profile.to_string(),
+                    urls: selection.urls.clone(),
+                });
+                return;
+            }
+        }
+        self.state.mod_data.save().unwrap();
+        self.record_feature("bulk_edit");
+    }
 
-        // Create backup directory
-        fs::create_dir_all(&backup_path)
-            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    /// Pause or resume the running install or cache update, with a spinner while it runs.
+    fn ui_pause(&self, ui: &mut Ui) {
+        if self.pause.is_paused() {
+            if ui.button("▶").on_hover_text("Resume").clicked() {
+                self.pause.set(false);
+            }
+            ui.label("Paused");
+        } else {
+            if ui
+                .button("⏸")
+                .on_hover_text("Pause\nThe mods in progress finish, the rest wait until resumed")
+                .clicked()
+            {
+                self.pause.set(true);
+            }
+            ui.spinner();
+        }
+    }
 
-        // Copy config directory contents
-        let config_dir = &dirs.config_dir;
-        if config_dir.exists() {
-            Self::copy_dir_contents(config_dir, &backup_path.join("config"))
-                .map_err(|e| format!("Failed to backup config: {}", e))?;
+    /// Point the mod at `old` to `new` in all profiles, keeping everything else about it.
+    /// Returns whether any mod was changed.
+    fn replace_mod_url(&mut self, old: &str, new: ModSpecification) -> bool {
+        if new.url == old {
+            return false;
         }
+        let mod_data = &mut self.state.mod_data;
+        let replaced = mod_data.replace_mod_url(old, &new);
+        if replaced {
+            self.state
+                .store
+                .set_local_metadata(mod_data.local_metadata.clone());
+            mod_data.save().unwrap();
+            self.last_action = Some(LastAction::success(format!(
+                "{old} now points to {}",
+                new.url
+            )));
+        }
+        replaced
+    }
 
-        // Copy data directory contents
-        let data_dir = &dirs.data_dir;
-        if data_dir.exists() {
-            Self::copy_dir_contents(data_dir, &backup_path.join("data"))
-                .map_err(|e| format!("Failed to backup data: {}", e))?;
+    /// Integrate the enabled mods of the active profile, ordered by their effective priority.
+    /// If enabled, how much needs downloading is shown for confirmation first.
+    fn install_mods(&mut self, ctx: &egui::Context) {
+        if self.state.config.confirm_downloads {
+            let active_profile = &self.state.mod_data.active_profile;
+            let mods = self.state.mod_data.enabled_mods_by_priority(active_profile);
+            message::EstimateDownload::send(self, ctx, mods);
+        } else {
+            self.integrate_mods(ctx, false);
         }
+    }
 
-        Ok(backup_path.to_string_lossy().to_string())
+    /// Fetch the enabled mods of the active profile and integrate them unless `download_only`,
+    /// which also works without a DRG install.
+    fn integrate_mods(&mut self, ctx: &egui::Context, download_only: bool) {
+        let fsd_pak = match self.state.config.drg_pak_path.clone() {
+            _ if download_only => None,
+            Some(fsd_pak) => Some(fsd_pak),
+            None => {
+                self.last_action = Some(LastAction::failure(
+                    "DRG install not found. Configure it in the settings menu.".to_string(),
+                ));
+                return;
+            }
+        };
+        if !download_only
+            && let Err(e) = self.state.mod_data.record_history(history::Reason::Install)
+        {
+            warn!("failed to snapshot mod data before installing: {e}");
+        }
+        let active_profile = self.state.mod_data.active_profile.clone();
+        let mods = self
+            .state
+            .mod_data
+            .enabled_mods_by_priority(&active_profile);
+
+        message::Operation::Integrate {
+            mods,
+            payload_rules: self.state.mod_data.payload_rules.clone(),
+            asset_overrides: self
+                .state
+                .mod_data
+                .get_active_profile()
+                .asset_overrides
+                .clone(),
+            save_snapshot: self
+                .state
+                .config
+                .snapshot_saves
+                .then(|| SnapshotBeforeInstall {
+                    data_dir: self.state.dirs.data_dir.clone(),
+                    profile: active_profile,
+                }),
+            fsd_pak,
+        }
+        .dispatch(self, ctx);
     }
 
-    fn copy_dir_contents(src: &PathBuf, dst: &PathBuf) -> std::io::Result<()> {
-        use std::fs;
+    fn launch_game(&mut self) {
+        self.record_feature("launch_game");
+        match crate::launch_game(
+            self.args.as_deref(),
+            &self.state.config.launch_args,
+            self.state.config.launch_method(),
+        ) {
+            Ok(child) => {
+                let profile = self.state.mod_data.active_profile.clone();
+                let session = Sessions::record_launch(&self.state.dirs.data_dir, &profile);
+                self.game = child.map(|child| RunningGame {
+                    child,
+                    profile,
+                    session,
+                    launched: Instant::now(),
+                });
+            }
+            Err(e) => {
+                self.last_action = Some(LastAction::failure(format!("failed to launch game: {e}")));
+            }
+        }
+    }
 
-        fs::create_dir_all(dst)?;
+    /// Once the game exits, record how long it ran and run the post-game actions of the profile it
+    /// was launched with.
+    fn watch_game(&mut self, ctx: &egui::Context) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        match game.child.try_wait() {
+            Ok(None) => {
+                ctx.request_repaint_after(Duration::from_secs(1));
+                return;
+            }
+            Ok(Some(status)) => info!("game exited with {status}"),
+            Err(e) => {
+                warn!("failed to watch the game: {e}");
+                self.game = None;
+                return;
+            }
+        }
 
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let path = entry.path();
-            let dest_path = dst.join(entry.file_name());
+        let game = self.game.take().unwrap();
+        Sessions::record_exit(
+            &self.state.dirs.data_dir,
+            &game.session,
+            game.launched.elapsed(),
+        );
+        match crate::run_post_game_actions(&mut self.state, &game.profile) {
+            Ok(done) if done.is_empty() => {}
+            Ok(done) => {
+                self.missing_install = None;
+                self.last_action = Some(LastAction::success(format!(
+                    "game closed: {}",
+                    done.join(", ")
+                )));
+            }
+            Err(e) => {
+                self.last_action = Some(LastAction::failure(format!(
+                    "post-game actions failed: {e}"
+                )));
+            }
+        }
+    }
 
-            if path.is_dir() {
-                Self::copy_dir_contents(&path, &dest_path)?;
+    fn run_startup_actions(&mut self, ctx: &egui::Context) {
+        if let Some(profile) = self.startup.profile.take() {
+            if self.state.mod_data.profiles.contains_key(&profile) {
+                self.state.mod_data.active_profile = profile;
+                self.state.mod_data.save().unwrap();
             } else {
-                fs::copy(&path, &dest_path)?;
+                self.last_action =
+                    Some(LastAction::failure(format!("no such profile: {profile:?}")));
+                // don't install or launch something the user didn't ask for
+                self.startup = Default::default();
+                return;
             }
         }
+        if self.startup.install {
+            self.startup.install = false;
+            self.install_mods(ctx);
+            // launching is deferred until the integration completes
+        } else if self.startup.launch {
+            self.startup.launch = false;
+            self.launch_game();
+        }
+    }
 
-        Ok(())
+    /// Ask to confirm the link mint was started with and those handed over by later launches.
+    fn receive_links(&mut self, ctx: &egui::Context) {
+        let links = self.startup.link.take().into_iter().chain(
+            self.instance
+                .iter()
+                .flat_map(|instance| instance.links())
+                .collect::<Vec<_>>(),
+        );
+        for link in links {
+            self.deep_link_window
+                .get_or_insert_with(|| WindowDeepLink {
+                    links: VecDeque::new(),
+                })
+                .links
+                .push_back(link);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+        }
     }
 
-    fn show_update_window(&mut self, ctx: &egui::Context) {
-        if let (Some(update), Some(update_time)) =
-            (self.available_update.as_ref(), self.show_update_time)
+    fn show_deep_link(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.deep_link_window else {
+            return;
+        };
+        let Some(DeepLink::Add { url, profile }) = window.links.front().cloned() else {
+            self.deep_link_window = None;
+            return;
+        };
+        let waiting = window.links.len() - 1;
+        let active = &self.state.mod_data.active_profile;
+        // links naming a profile this machine does not have add to the active one
+        let target = profile
+            .as_ref()
+            .filter(|p| self.state.mod_data.profiles.contains_key(p.as_str()))
+            .unwrap_or(active)
+            .clone();
+
+        let mut add = false;
+        let mut open = true;
+        egui::Window::new("Add mod from link")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "A link asks to add this mod to the profile {target:?}:"
+                ));
+                ui.monospace(&url);
+                if let Some(profile) = profile.as_ref().filter(|p| **p != target) {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("There is no profile {profile:?}, so it is added to {target:?}."),
+                    );
+                }
+                if waiting > 0 {
+                    ui.weak(format!("Links waiting after this one: {waiting}"));
+                }
+                ui.horizontal(|ui| {
+                    add = ui.button("Add").clicked();
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                });
+            });
+
+        if add {
+            let spec = ModSpecification::new(url);
+            self.record_feature("deep_link");
+            if self.state.mod_data.active_profile != target {
+                self.state.mod_data.active_profile = target;
+                self.state.mod_data.save().unwrap();
+            }
+            message::ResolveMods::send(self, ctx, vec![spec], false);
+        }
+        if (add || !open)
+            && let Some(window) = &mut self.deep_link_window
+        {
+            window.links.pop_front();
+            if window.links.is_empty() {
+                self.deep_link_window = None;
+            }
+        }
+    }
+
+    fn show_safe_mode(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.state.safe_mode else {
+            return;
+        };
+        let mut restart = false;
+        egui::Window::new("Safe mode")
+            .open(&mut self.safe_mode_open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "mint was started with --safe-mode. Changes are not saved and mods are not \
+                     resolved or downloaded. Export profiles or create a backup in the settings, \
+                     or fix the files below in the config directory, then restart normally.",
+                );
+                if !report.unreadable.is_empty() {
+                    ui.heading("Could not be read");
+                    for (path, reason) in &report.unreadable {
+                        ui.label(path.display().to_string());
+                        ui.colored_label(ui.visuals().error_fg_color, reason);
+                    }
+                }
+                if !report.migrations.is_empty() {
+                    ui.heading("Would be migrated");
+                    for migration in &report.migrations {
+                        ui.label(migration);
+                    }
+                }
+                if report.unreadable.is_empty() && report.migrations.is_empty() {
+                    ui.label("All files could be read and none need to be migrated.");
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Open config directory").clicked() {
+                        opener::open(&self.state.dirs.config_dir).ok();
+                    }
+                    restart = ui.button("Restart normally").clicked();
+                });
+            });
+        if restart {
+            self.restart_args = Some(
+                std::env::args_os()
+                    .skip(1)
+                    .filter(|arg| arg != "--safe-mode")
+                    .collect(),
+            );
+            self.original_exe_path = std::env::current_exe().ok();
+            self.needs_restart = true;
+        }
+    }
+
+    /// Ask before saving mod data read in an older format in the current one, see
+    /// [`State::apply_migration`]. Nothing can be changed until then.
+    fn show_migration(&mut self, ctx: &egui::Context) {
+        let Some(migration) = &self.state.migration else {
+            return;
+        };
+        let mut upgrade = false;
+        let mut quit = false;
+        egui::Modal::new(egui::Id::new("migration")).show(ctx, |ui| {
+            ui.set_max_width(500.0);
+            ui.heading("Upgrade mod data");
+            ui.label(format!(
+                "{} was saved by an older version of mint (format {}). Upgrading saves it in the \
+                 current format ({}), which older versions of mint cannot read.",
+                migration.file.display(),
+                migration.from,
+                crate::state::migration::CURRENT_VERSION
+            ));
+            if migration.file.file_name() != Some("mod_data.json".as_ref()) {
+                ui.label("It is saved as mod_data.json and the old file is removed.");
+            }
+            ui.horizontal_wrapped(|ui| {
+                ui.label("A copy of the file as it is now will be kept at");
+                if ui.link(migration.backup.display().to_string()).clicked()
+                    && let Some(dir) = migration.backup.parent()
+                {
+                    opener::open(dir).ok();
+                }
+            });
+            ui.separator();
+            ui.heading("Profiles");
+            for profile in &migration.profiles {
+                ui.label(format!("{}: {} mods", profile.name, profile.mods));
+                if !profile.copied_folders.is_empty() {
+                    ui.indent(&profile.name, |ui| {
+                        ui.label(format!(
+                            "Gets its own copy of the folders {}, which were shared by all \
+                             profiles.",
+                            profile
+                                .copied_folders
+                                .iter()
+                                .map(|f| format!("{f:?}"))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    });
+                }
+            }
+            for folder in &migration.dropped_folders {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "The folder {folder:?} is not used by any profile and is dropped. It is \
+                         still in the copy."
+                    ),
+                );
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                upgrade = ui.button("Upgrade").clicked();
+                quit = ui
+                    .button("Quit")
+                    .on_hover_text("Leave the file as it is")
+                    .clicked();
+            });
+        });
+        if upgrade && let Err(e) = self.state.apply_migration() {
+            self.last_action = Some(LastAction::failure(format!(
+                "failed to upgrade mod data: {e}"
+            )));
+        }
+        if quit {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    fn show_unlisted_mods(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.unlisted_mods_window else {
+            return;
+        };
+        let mut disable = false;
+        let mut open = true;
+        egui::Window::new("Mods not in the list")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "These mods are enabled in the profile {:?} but not in the pasted list. \
+                     Disable them to get the exact setup of the list:",
+                    window.profile
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for url in &window.urls {
+                            let name = self
+                                .state
+                                .store
+                                .get_mod_info(&ModSpecification::new(url.clone()))
+                                .map_or_else(|| url.clone(), |info| info.name);
+                            ui.label(name).on_hover_text(url);
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    disable = ui.button("Disable them").clicked();
+                    if ui.button("Keep them").clicked() {
+                        open = false;
+                    }
+                });
+            });
+
+        if disable {
+            let urls = window.urls.iter().collect::<HashSet<_>>();
+            self.state.mod_data.any_mod_mut(&window.profile, |mc, _| {
+                if urls.contains(&mc.spec.url) {
+                    mc.enabled = false;
+                }
+                false
+            });
+            self.state.mod_data.save().unwrap();
+            self.last_action = Some(LastAction::success(format!(
+                "Disabled {} mods not in the list",
+                urls.len()
+            )));
+        }
+        if disable || !open {
+            self.unlisted_mods_window = None;
+        }
+    }
+
+    /// Add the mods in [`Self::resolve_mod`], a list of URLs or a [`mod_list`] with the version,
+    /// enabled state and priority of each mod.
+    fn resolve_mods(&mut self, ctx: &egui::Context) {
+        if !mod_list::is_mod_list(&self.resolve_mod) {
+            message::ResolveMods::send(self, ctx, self.parse_mods(), false);
+            return;
+        }
+        match mod_list::parse(&self.resolve_mod) {
+            Ok(mods) => message::ResolveMods::send_list(self, ctx, mods),
+            Err(e) => self.last_action = Some(LastAction::failure(e.to_string())),
+        }
+    }
+
+    fn parse_mods(&self) -> Vec<ModSpecification> {
+        self.resolve_mod
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty())
+            .map(|l| {
+                // Strip URL fragment (e.g., "#description" from mod.io URLs)
+                let url = l.split('#').next().unwrap_or(l);
+                ModSpecification::new(url.to_string())
+            })
+            .collect()
+    }
+
+    fn build_mod_string(mods: &Vec<ModConfig>) -> String {
+        let mut string = String::new();
+        for m in mods {
+            if m.enabled {
+                string.push_str(&m.spec.url);
+                string.push('\n');
+            }
+        }
+        string
+    }
+
+    /// Directory backups go to, see [`crate::backup`].
+    fn backup_dir(&self) -> PathBuf {
+        self.state
+            .config
+            .backup_path
+            .clone()
+            .or_else(backup::default_dir)
+            .unwrap_or_default()
+    }
+
+    fn show_update_window(&mut self, ctx: &egui::Context) {
+        if let (Some(update), Some(update_time)) =
+            (self.available_update.as_ref(), self.show_update_time)
         {
             let now = SystemTime::now();
             let wait_time = Duration::from_secs(10);
@@ -1352,6 +2950,7 @@ impl App {
                                     &mut self.request_counter,
                                     self.tx.clone(),
                                     ctx.clone(),
+                                    update,
                                 ));
                             }
 
@@ -1374,6 +2973,27 @@ impl App {
             return;
         };
 
+        let mut check = false;
+        while let Ok(res) = window.login_rx.try_recv() {
+            let Some(login) = &mut window.login else {
+                continue;
+            };
+            login.pending = None;
+            match res {
+                Ok(None) => {
+                    login.code_sent = true;
+                    login.error = None;
+                }
+                Ok(Some(value)) => {
+                    let parameter = login.parameter;
+                    window.parameters.insert(parameter.to_string(), value);
+                    window.login = None;
+                    check = true;
+                }
+                Err(e) => login.error = Some(e.to_string()),
+            }
+        }
+
         while let Ok((rid, res)) = window.rx.try_recv() {
             if window.check_rid.as_ref().is_some_and(|r| rid == r.0) {
                 match res {
@@ -1395,7 +3015,7 @@ impl App {
         }
 
         let mut open = true;
-        let mut check = false;
+        let mut login_request = None;
         egui::Window::new(format!("Configure {} provider", window.factory.id))
             .open(&mut open)
             .resizable(false)
@@ -1408,20 +3028,104 @@ impl App {
                             } else {
                                 ui.label(p.name).on_hover_text(p.description);
                             }
-                            let res = ui.add(
-                                egui::TextEdit::singleline(
-                                    window.parameters.entry(p.id.to_string()).or_default(),
-                                )
-                                .password(true)
-                                .desired_width(200.0),
-                            );
-                            if is_committed(&res) {
-                                check = true;
+                            if window.external.contains(p.id) {
+                                ui.label("set externally").on_hover_text(format!(
+                                    "Supplied via the {} environment variable or the secrets file",
+                                    secrets::env_var_name(window.factory.id, p.id)
+                                ));
+                                ui.end_row();
+                                continue;
+                            }
+                            let value = window.parameters.entry(p.id.to_string()).or_default();
+                            if p.optional {
+                                ui.add(
+                                    egui::TextEdit::multiline(value)
+                                        .code_editor()
+                                        .desired_rows(4)
+                                        .desired_width(400.0),
+                                );
+                            } else {
+                                ui.horizontal(|ui| {
+                                    let res = ui.add(
+                                        egui::TextEdit::singleline(value)
+                                            .password(true)
+                                            .desired_width(200.0),
+                                    );
+                                    if is_committed(&res) {
+                                        check = true;
+                                    }
+                                    if let Some(login) = p.email_login
+                                        && ui
+                                            .button("Log in...")
+                                            .on_hover_text(format!(
+                                                "Get the {} by logging in with your email",
+                                                p.name
+                                            ))
+                                            .clicked()
+                                    {
+                                        window.login = Some(EmailLoginState::new(p.id, login));
+                                    }
+                                });
                             }
                             ui.end_row();
                         }
                     });
 
+                    if let Some(login) = &mut window.login {
+                        ui.separator();
+                        ui.add_enabled_ui(login.pending.is_none(), |ui| {
+                            if login.code_sent {
+                                ui.label(format!(
+                                    "Enter the security code sent to {}:",
+                                    login.email
+                                ));
+                                ui.horizontal(|ui| {
+                                    let res = ui.add(
+                                        egui::TextEdit::singleline(&mut login.code)
+                                            .desired_width(100.0),
+                                    );
+                                    if (ui.button("Log in").clicked() || is_committed(&res))
+                                        && !login.code.trim().is_empty()
+                                    {
+                                        login_request = Some(
+                                            (login.login.exchange_code)(login.code.clone())
+                                                .map_ok(Some)
+                                                .boxed(),
+                                        );
+                                    }
+                                    if ui.button("Change email").clicked() {
+                                        login.code_sent = false;
+                                    }
+                                });
+                            } else {
+                                ui.label("Email address of your account:");
+                                ui.horizontal(|ui| {
+                                    let res = ui.add(
+                                        egui::TextEdit::singleline(&mut login.email)
+                                            .desired_width(200.0),
+                                    );
+                                    if (ui.button("Send code").clicked() || is_committed(&res))
+                                        && !login.email.trim().is_empty()
+                                    {
+                                        login_request = Some(
+                                            (login.login.request_code)(
+                                                login.email.trim().to_string(),
+                                            )
+                                            .map_ok(|()| None)
+                                            .boxed(),
+                                        );
+                                    }
+                                });
+                            }
+                        });
+                        if login.pending.is_some() {
+                            ui.spinner();
+                        }
+                        if let Some(error) = &login.error {
+                            ui.colored_label(ui.visuals().error_fg_color, error);
+                        }
+                    }
+
                     ui.with_layout(Layout::right_to_left(Align::TOP), |ui| {
                         if ui.button("Save").clicked() {
                             check = true;
@@ -1435,6 +3139,17 @@ impl App {
                     });
                 });
             });
+        if let Some(request) = login_request
+            && let Some(login) = &mut window.login
+        {
+            login.error = None;
+            let tx = window.login_tx.clone();
+            let ctx = ctx.clone();
+            login.pending = Some(tokio::task::spawn(async move {
+                tx.send(request.await).await.ok();
+                ctx.request_repaint();
+            }));
+        }
         if !open {
             self.window_provider_parameters = None;
         } else if check {
@@ -1443,7 +3158,12 @@ impl App {
             let ctx = ctx.clone();
             let rid = self.request_counter.next();
             let store = self.state.store.clone();
-            let params = window.parameters.clone();
+            let params = secrets::merge_parameters(
+                &HashMap::from([(window.factory.id.to_string(), window.parameters.clone())]),
+                &self.state.external_provider_parameters,
+            )
+            .remove(window.factory.id)
+            .unwrap_or_default();
             let factory = window.factory;
             let handle = tokio::task::spawn(async move {
                 let res = store.add_provider_checked(factory, &params).await;
@@ -1473,6 +3193,12 @@ impl App {
     }
 
     fn show_settings(&mut self, ctx: &egui::Context) {
+        let mut run_health_check = false;
+        // directory to back up to
+        let mut create_backup = None;
+        // recorded once the window is no longer borrowed
+        let mut features = vec![];
+        let mut error = None;
         if let Some(window) = &mut self.settings_window {
             let mut open = true;
             let mut try_save = false;
@@ -1513,6 +3239,158 @@ impl App {
                                     window.drg_pak_path = fsd_pak.to_string_lossy().to_string();
                                     window.drg_pak_path_err = None;
                                 }
+                            if !window.detected_paks.is_empty() {
+                                ui.menu_button("detected", |ui| {
+                                    for pak in &window.detected_paks {
+                                        if ui.button(pak.display().to_string()).clicked() {
+                                            window.drg_pak_path = pak.to_string_lossy().to_string();
+                                            window.drg_pak_path_err = None;
+                                            ui.close_menu();
+                                        }
+                                    }
+                                })
+                                .response
+                                .on_hover_text("DRG installs found in the Steam libraries and Xbox app folders");
+                            }
+                        });
+                        ui.end_row();
+
+                        if window.drg_pak_path_err.is_some() && !window.pak_suggestions.is_empty() {
+                            ui.label("Did you mean:");
+                            ui.vertical(|ui| {
+                                let mut picked = None;
+                                for pak in &window.pak_suggestions {
+                                    if ui.link(pak.display().to_string()).clicked() {
+                                        picked = Some(pak.clone());
+                                    }
+                                }
+                                if let Some(pak) = picked {
+                                    window.drg_pak_path = pak.to_string_lossy().to_string();
+                                    window.drg_pak_path_err = None;
+                                    window.pak_suggestions.clear();
+                                }
+                            });
+                            ui.end_row();
+                        }
+
+                        ui.label("Game installs:").on_hover_text("Installations to switch between in the bottom panel. Saving a DRG pak above adds its installation.");
+                        ui.vertical(|ui| {
+                            let mut changed = false;
+                            let mut remove = None;
+                            for (i, install) in self.state.config.game_installs.iter_mut().enumerate() {
+                                ui.horizontal(|ui| {
+                                    changed |= ui
+                                        .add(egui::TextEdit::singleline(&mut install.name).desired_width(120.0))
+                                        .lost_focus();
+                                    ui.weak(install.kind.name())
+                                        .on_hover_text(install.pak_path.display().to_string());
+                                    if ui.button("🗑").on_hover_text("Remove from the list").clicked() {
+                                        remove = Some(i);
+                                    }
+                                    if ui.button("select").on_hover_text("Use this installation's DRG pak").clicked() {
+                                        window.drg_pak_path = install.pak_path.to_string_lossy().to_string();
+                                        window.drg_pak_path_err = None;
+                                    }
+                                });
+                            }
+                            if self.state.config.game_installs.is_empty() {
+                                ui.weak("None");
+                            }
+                            if let Some(i) = remove {
+                                self.state.config.game_installs.remove(i);
+                                changed = true;
+                            }
+                            if changed {
+                                self.state.config.save().unwrap();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Game files:");
+                        let steam = !matches!(
+                            DRGInstallationType::from_pak_path(&window.drg_pak_path),
+                            Ok(DRGInstallationType::Xbox)
+                        );
+                        let verify = ui
+                            .add_enabled(steam, egui::Button::new("Verify game files..."))
+                            .on_hover_text(
+                                "Ask Steam to verify the game files. Use this if the game crashes on \
+                                 start or the DRG pak above is reported as invalid. Verifying \
+                                 removes installed mods, so install them again afterwards.",
+                            )
+                            .on_disabled_hover_text(
+                                "Use \"Repair\" in the Xbox app's settings for Deep Rock Galactic",
+                            );
+                        if verify.clicked()
+                            && let Err(e) = crate::verify_game_files()
+                        {
+                            self.last_action =
+                                Some(LastAction::failure(format!("failed to open Steam: {e}")));
+                        }
+                        ui.end_row();
+
+                        ui.label("Workspace:").on_hover_text("Independent sets of profiles, settings and downloaded mods, e.g. to maintain a community pack apart from your own mods. Switching restarts mint.");
+                        ui.vertical(|ui| {
+                            let current = self.state.dirs.workspace.as_deref().unwrap_or(DEFAULT_WORKSPACE);
+                            let mut selected = current.to_string();
+                            let mut remove = None;
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_salt("workspace")
+                                    .selected_text(current)
+                                    .show_ui(ui, |ui| {
+                                        for name in window.workspaces.names() {
+                                            let hover = window.workspaces.get(name).map(|w| w.root.display().to_string());
+                                            let response = ui.selectable_value(&mut selected, name.to_string(), name);
+                                            if let Some(hover) = hover {
+                                                response.on_hover_text(hover);
+                                            }
+                                        }
+                                    });
+                                if current != DEFAULT_WORKSPACE
+                                    && ui.button("🗑").on_hover_text("Remove from the list, leaving its files in place. Switches to the default workspace.").clicked()
+                                {
+                                    remove = Some(current.to_string());
+                                    selected = DEFAULT_WORKSPACE.to_string();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut window.new_workspace).hint_text("name").desired_width(120.0));
+                                if ui
+                                    .add_enabled(!window.new_workspace.trim().is_empty(), egui::Button::new("Add..."))
+                                    .on_hover_text("Choose a directory for the new workspace's files")
+                                    .clicked()
+                                    && let Some(root) = rfd::FileDialog::new().pick_folder()
+                                {
+                                    let name = window.new_workspace.trim().to_string();
+                                    match window.workspaces.add(&name, root).and_then(|()| window.workspaces.write()) {
+                                        Ok(()) => {
+                                            window.new_workspace.clear();
+                                            window.workspace_err = None;
+                                            features.push("add_workspace");
+                                        }
+                                        Err(e) => window.workspace_err = Some(e.to_string()),
+                                    }
+                                }
+                            });
+                            if let Some(e) = &window.workspace_err {
+                                ui.colored_label(ui.visuals().error_fg_color, e);
+                            }
+                            if selected != current {
+                                if let Some(name) = &remove {
+                                    window.workspaces.remove(name);
+                                }
+                                window.workspaces.current = Some(selected.clone()).filter(|n| n != DEFAULT_WORKSPACE);
+                                match window.workspaces.write() {
+                                    Ok(()) => {
+                                        info!("switching to workspace {selected:?}");
+                                        features.push("switch_workspace");
+                                        self.restart_args = Some(args_in_workspace(&selected));
+                                        self.original_exe_path = std::env::current_exe().ok();
+                                        self.needs_restart = true;
+                                    }
+                                    Err(e) => window.workspace_err = Some(e.to_string()),
+                                }
+                            }
                         });
                         ui.end_row();
 
@@ -1523,19 +3401,77 @@ impl App {
                         }
                         ui.end_row();
 
-                        let cache_dir = &self.state.dirs.cache_dir;
-                        ui.label("Cache directory:");
-                        if ui.link(cache_dir.display().to_string()).clicked() {
-                            opener::open(cache_dir).ok();
+                        for (kind, label, hover) in [
+                            (
+                                DirKind::Cache,
+                                "Cache directory:",
+                                "Downloaded mods. Move it to a drive with more free space if it grows too large.",
+                            ),
+                            (
+                                DirKind::Data,
+                                "Data directory:",
+                                "Logs, reports and save snapshots",
+                            ),
+                        ] {
+                            let dir = self.state.dirs.get(kind);
+                            ui.label(label).on_hover_text(hover);
+                            ui.horizontal(|ui| {
+                                if ui.link(dir.display().to_string()).clicked() {
+                                    opener::open(dir).ok();
+                                }
+                                if ui
+                                    .button("Move...")
+                                    .on_hover_text("Choose an empty directory to move this one to when mint is restarted")
+                                    .clicked()
+                                    && let Some(target) = rfd::FileDialog::new().pick_folder()
+                                    && let Err(e) = self.state.dirs.relocate(kind, target)
+                                {
+                                    window.relocate_err = Some(e.to_string());
+                                }
+                                let pending = self.state.dirs.pending_relocation(kind);
+                                if kind == DirKind::Cache
+                                    && pending.is_none()
+                                    && let Some(game_drive) = &window.cache_elsewhere
+                                    && ui
+                                        .button("Move to game drive")
+                                        .on_hover_text(format!(
+                                            "The cache is on a different drive than the game, so installing copies every mod across drives. Move it to {} when mint is restarted.",
+                                            game_drive.join("mint_cache").display()
+                                        ))
+                                        .clicked()
+                                    && let Err(e) = self.state.dirs.relocate(kind, game_drive.join("mint_cache"))
+                                {
+                                    window.relocate_err = Some(e.to_string());
+                                }
+                                if let Some((target, error)) = pending {
+                                    match error {
+                                        Some(e) => ui.colored_label(
+                                            ui.visuals().error_fg_color,
+                                            format!("Moving to {} failed, retrying on restart: {e}", target.display()),
+                                        ),
+                                        None => ui.colored_label(
+                                            ui.visuals().warn_fg_color,
+                                            format!("Moves to {} on restart", target.display()),
+                                        ),
+                                    };
+                                    if ui.button("Restart now").clicked() {
+                                        self.original_exe_path = std::env::current_exe().ok();
+                                        self.needs_restart = true;
+                                    }
+                                    if ui.button("Cancel").clicked()
+                                        && let Err(e) = self.state.dirs.relocate(kind, dir.to_path_buf())
+                                    {
+                                        window.relocate_err = Some(e.to_string());
+                                    }
+                                }
+                            });
+                            ui.end_row();
                         }
-                        ui.end_row();
-
-                        let data_dir = &self.state.dirs.data_dir;
-                        ui.label("Data directory:");
-                        if ui.link(data_dir.display().to_string()).clicked() {
-                            opener::open(data_dir).ok();
+                        if let Some(e) = &window.relocate_err {
+                            ui.label("");
+                            ui.colored_label(ui.visuals().error_fg_color, e);
+                            ui.end_row();
                         }
-                        ui.end_row();
 
                         ui.label("GUI theme:");
                         ui.horizontal(|ui| {
@@ -1554,6 +3490,15 @@ impl App {
                         });
                         ui.end_row();
 
+                        ui.label("Show thumbnails:");
+                        if ui.checkbox(&mut self.state.config.show_thumbnails, "")
+                            .on_hover_text("Show the thumbnails of mods which have one, such as mod.io mods, in the mod list. They are downloaded once and cached.")
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                        }
+                        ui.end_row();
+
                         ui.label("Confirm mod deletion:");
                         if ui.checkbox(&mut self.state.config.confirm_mod_deletion, "")
                             .on_hover_text("Show confirmation dialog before deleting mods")
@@ -1563,6 +3508,15 @@ impl App {
                         }
                         ui.end_row();
 
+                        ui.label("Confirm downloads:");
+                        if ui.checkbox(&mut self.state.config.confirm_downloads, "")
+                            .on_hover_text("Show how much needs downloading before installing, for metered connections")
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                        }
+                        ui.end_row();
+
                         ui.label("Confirm profile deletion:");
                         if ui.checkbox(&mut self.state.config.confirm_profile_deletion, "")
                             .on_hover_text("Show confirmation dialog before deleting profiles")
@@ -1592,15 +3546,13 @@ impl App {
 
                         ui.label("");
                         ui.horizontal(|ui| {
-                            if ui.button("Create Backup Now").clicked() {
-                                let backup_result = Self::create_backup(
-                                    &self.state.dirs,
-                                    &window.backup_path,
-                                );
-                                window.backup_status = Some(match backup_result {
-                                    Ok(path) => (true, format!("Backup created: {}", path)),
-                                    Err(e) => (false, format!("Backup failed: {}", e)),
-                                });
+                            if ui.button("History...").on_hover_text("Roll the profiles back to how they were before a change or install").clicked() {
+                                self.history_window = Some(WindowHistory::new(&self.state));
+                            }
+                            if self.backup_rid.is_some() {
+                                ui.spinner();
+                            } else if ui.button("Create Backup Now").clicked() {
+                                create_backup = Some(PathBuf::from(&window.backup_path));
                             }
                             if let Some((success, msg)) = &window.backup_status {
                                 if *success {
@@ -1612,21 +3564,447 @@ impl App {
                         });
                         ui.end_row();
 
-                        ui.label("Mod providers:");
+                        ui.label("Zip backups:");
+                        if ui.checkbox(&mut self.state.config.zip_backups, "")
+                            .on_hover_text("Pack backups into a single .zip instead of copying the directories, easier to move between machines")
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                        }
                         ui.end_row();
 
-                        for provider_factory in ModStore::get_provider_factories() {
-                            ui.label(provider_factory.id);
-                            if ui.add_enabled(!provider_factory.parameters.is_empty(), egui::Button::new("⚙"))
-                                    .on_hover_text(format!("Open \"{}\" settings", provider_factory.id))
-                                    .clicked() {
-                                self.window_provider_parameters = Some(
-                                    WindowProviderParameters::new(provider_factory, &self.state),
-                                );
+                        ui.label("Automatic backups:");
+                        ui.horizontal(|ui| {
+                            let auto_backup = &mut self.state.config.auto_backup;
+                            let mut changed = false;
+                            changed |= ui.checkbox(&mut auto_backup.on_launch, "on launch").changed();
+                            changed |= ui.checkbox(&mut auto_backup.before_install, "before install").changed();
+                            changed |= ui.checkbox(&mut auto_backup.daily, "daily").changed();
+                            ui.label("keep:");
+                            changed |= ui
+                                .add(egui::DragValue::new(&mut auto_backup.keep).range(0..=1000))
+                                .on_hover_text("How many backups are kept, older ones are deleted after each backup\n0 keeps all of them")
+                                .changed();
+                            if changed {
+                                self.state.config.save().unwrap();
                             }
-                            ui.end_row();
-                        }
-                    });
+                        });
+                        ui.end_row();
+
+                        ui.label("Snapshot saves:");
+                        ui.horizontal(|ui| {
+                            if ui.checkbox(&mut self.state.config.snapshot_saves, "")
+                                .on_hover_text("Snapshot the save files before the first install of a profile with Approved or Sandbox mods")
+                                .changed()
+                            {
+                                self.state.config.save().unwrap();
+                            }
+                            if ui.button("Snapshots...").clicked() {
+                                self.save_snapshots_window =
+                                    Some(WindowSaveSnapshots::new(&self.state));
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Trusted signers:");
+                        ui.horizontal(|ui| {
+                            let signers = &self.state.config.trusted_signers;
+                            ui.label(signers.len().to_string()).on_hover_text(
+                                signers
+                                    .iter()
+                                    .map(|s| format!("{} ({})", s.name, s.key))
+                                    .collect::<Vec<_>>()
+                                    .join("\n"),
+                            );
+                            if ui
+                                .button("Import list...")
+                                .on_hover_text("Trust the signers in a list shared by the community, e.g. made with `mint trust export`")
+                                .clicked()
+                                && let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("Trust list", &["json"])
+                                    .pick_file()
+                            {
+                                let result = signing::read_trust_list(&path)
+                                    .map(|signers| self.state.trust_signers(signers));
+                                self.last_action = Some(match result {
+                                    Ok(added) => LastAction::success(format!("trusted {added} new signers")),
+                                    Err(e) => LastAction::failure(format!("failed to import trust list: {e}")),
+                                });
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Usage analytics:");
+                        ui.horizontal(|ui| {
+                            if ui.checkbox(&mut self.state.config.usage_analytics, "")
+                                .on_hover_text("Count which features are used and which errors occur, on this computer only")
+                                .changed()
+                            {
+                                self.state.config.save().unwrap();
+                            }
+                            if ui.button("View...").clicked() {
+                                self.analytics_window = Some(WindowAnalytics { status: None });
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Check for updates:");
+                        if ui.checkbox(&mut self.state.config.check_for_updates, "")
+                            .on_hover_text("Check for a new release on startup")
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                        }
+                        ui.end_row();
+
+                        ui.label("Update source:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("GitHub repository (owner/repo) or https URL of a GitHub-style latest release JSON document.\nUpdates are only installed if their archive matches the <archive>.sha256 checksum published with the release.\nLeave empty to use the default.");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut window.update_endpoint)
+                                    .hint_text(mint_lib::update::update_repo())
+                                    .desired_width(200.0),
+                            );
+                            let endpoint = window.update_endpoint.trim();
+                            let valid = mint_lib::update::release_url(Some(endpoint)).is_ok();
+                            if ui
+                                .add_enabled(valid, egui::Button::new("Save source"))
+                                .on_disabled_hover_text("URLs must use https")
+                                .clicked()
+                            {
+                                self.state.config.update_endpoint =
+                                    (!endpoint.is_empty()).then(|| endpoint.to_string());
+                                self.state.config.save().unwrap();
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Scan downloads:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("Command run on each downloaded archive before it can be installed, e.g. an antivirus' command line scanner.\n{file} is replaced by the archive's path, or the path is appended.\nArchives it exits with an error for are moved to the quarantine directory in the cache.\nLeave empty to not scan.");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut window.scan_command)
+                                    .hint_text("clamscan --no-summary {file}")
+                                    .desired_width(200.0),
+                            );
+                            if ui.button("Save command").clicked() {
+                                let command = window.scan_command.trim();
+                                self.state.config.scan_command =
+                                    (!command.is_empty()).then(|| command.to_string());
+                                self.state.config.save().unwrap();
+                                self.state
+                                    .store
+                                    .set_scan_command(self.state.config.scan_command.as_deref());
+                            }
+                        });
+                        ui.end_row();
+
+                        if let Some(pak_path) = self.state.config.drg_pak_path.clone()
+                            && let Some(current) = self.state.config.launch_method()
+                        {
+                            ui.label("Launcher:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("How \"Launch game\" starts this installation.\nThe Xbox app launches the Microsoft Store/Game Pass version through the shell, without launch options.");
+                            let mut method = current;
+                            egui::ComboBox::from_id_salt("launch-method")
+                                .selected_text(match method {
+                                    LaunchMethod::Steam => "Steam",
+                                    LaunchMethod::Xbox => "Xbox app",
+                                })
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut method, LaunchMethod::Steam, "Steam");
+                                    ui.selectable_value(&mut method, LaunchMethod::Xbox, "Xbox app");
+                                });
+                            if method != current {
+                                self.state.config.launch_methods.insert(pak_path, method);
+                                self.state.config.save().unwrap();
+                            }
+                            ui.end_row();
+                        }
+
+                        ui.label("Launch options:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("Added to the game's command line by \"Launch game\", one per line.\nApplies to launches through Steam as well as `mint launch %command%`.");
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut window.launch_args)
+                                    .hint_text("-nosplash")
+                                    .desired_rows(2)
+                                    .desired_width(200.0),
+                            );
+                            ui.vertical(|ui| {
+                                ui.menu_button("Presets", |ui| {
+                                    let mut preset = None;
+                                    if ui
+                                        .button("-nosplash")
+                                        .on_hover_text("Skip the splash screen")
+                                        .clicked()
+                                    {
+                                        preset = Some("-nosplash".to_string());
+                                    }
+                                    if ui
+                                        .button("-windowed")
+                                        .on_hover_text("Start in a window instead of fullscreen")
+                                        .clicked()
+                                    {
+                                        preset = Some("-windowed".to_string());
+                                    }
+                                    if ui
+                                        .button("Save directory...")
+                                        .on_hover_text("Keep the Saved folder, with the save files and game settings, in another folder.\nmint's save snapshots still use the game's own Saved folder.")
+                                        .clicked()
+                                        && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                                    {
+                                        preset = Some(format!("-userdir={}", folder.display()));
+                                    }
+                                    if let Some(preset) = preset {
+                                        if !window.launch_args.trim().is_empty()
+                                            && !window.launch_args.ends_with('\n')
+                                        {
+                                            window.launch_args.push('\n');
+                                        }
+                                        window.launch_args.push_str(&preset);
+                                        ui.close_menu();
+                                    }
+                                });
+                                if ui.button("Save options").clicked() {
+                                    self.state.config.launch_args = window
+                                        .launch_args
+                                        .lines()
+                                        .map(str::trim)
+                                        .filter(|a| !a.is_empty())
+                                        .map(str::to_string)
+                                        .collect();
+                                    self.state.config.save().unwrap();
+                                }
+                            });
+                        });
+                        ui.end_row();
+
+                        ui.label("Memory budget:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("How much of a mod's pak is kept in memory while installing or linting.\nLarger paks, e.g. texture packs, are extracted to a temporary file instead.");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.state.config.memory_budget)
+                                    .range(64..=16384)
+                                    .speed(16)
+                                    .suffix(" MiB"),
+                            )
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                        }
+                        ui.end_row();
+
+                        ui.label("Network timeouts:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("How long to wait for a connection, and for more of a response, before giving up.\nRaise them on slow or high-latency connections.");
+                        let timeouts_changed = ui
+                            .horizontal(|ui| {
+                                let timeouts = &mut self.state.config.http_timeouts;
+                                let connect = ui.add(
+                                    egui::DragValue::new(&mut timeouts.connect)
+                                        .range(1..=600)
+                                        .prefix("connect ")
+                                        .suffix(" s"),
+                                );
+                                let read = ui.add(
+                                    egui::DragValue::new(&mut timeouts.read)
+                                        .range(1..=600)
+                                        .prefix("read ")
+                                        .suffix(" s"),
+                                );
+                                connect.changed() || read.changed()
+                            })
+                            .inner;
+                        if timeouts_changed {
+                            self.state.config.save().unwrap();
+                            if let Err(e) = self.state.apply_http_timeouts() {
+                                warn!("failed to apply network timeouts: {e}");
+                            }
+                        }
+                        ui.end_row();
+
+                        ui.label("Parallel downloads:").on_hover_cursor(egui::CursorIcon::Help).on_hover_text("How many mods are downloaded at once.\nInterrupted downloads are resumed where they left off.");
+                        if ui
+                            .add(
+                                egui::DragValue::new(&mut self.state.config.download_concurrency)
+                                    .range(1..=16),
+                            )
+                            .changed()
+                        {
+                            self.state.config.save().unwrap();
+                            self.state
+                                .store
+                                .set_download_concurrency(self.state.config.download_concurrency);
+                        }
+                        ui.end_row();
+
+                        ui.label("Links:");
+                        if ui
+                            .button("Open mint:// links with mint")
+                            .on_hover_text("Have \"Add to mint\" links on mod pages add the mod to a profile, after asking")
+                            .clicked()
+                        {
+                            features.push("register_links");
+                            self.last_action = Some(match deep_link::register() {
+                                Ok(()) => LastAction::success("mint now opens mint:// links".to_string()),
+                                Err(e) => LastAction::failure(format!("Registering mint:// links failed: {e}")),
+                            });
+                        }
+                        ui.end_row();
+
+                        ui.label("Settings:");
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("Export settings...")
+                                .on_hover_text("Save these settings, without provider tokens and settings, the game's location and what mint trusts, to set up mint the same way on another PC")
+                                .clicked()
+                                && let Some(path) = rfd::FileDialog::new()
+                                    .set_file_name("mint_settings.json")
+                                    .add_filter("JSON", &["json"])
+                                    .save_file()
+                            {
+                                match self.state.config.export_settings(&path) {
+                                    Ok(()) => self.last_action = Some(LastAction::success(format!("Exported settings to {}", path.display()))),
+                                    Err(e) => error = Some(MintError::from(e).info()),
+                                }
+                            }
+                            if ui
+                                .button("Import settings...")
+                                .on_hover_text("Replace these settings by exported ones, keeping the provider tokens and settings, the game's location, the scan command, the update source and the trusted signers of this PC")
+                                .clicked()
+                                && let Some(path) = rfd::FileDialog::new()
+                                    .add_filter("JSON", &["json"])
+                                    .pick_file()
+                            {
+                                features.push("import_settings");
+                                match self.state.import_settings(&path) {
+                                    Ok(()) => {
+                                        let theme = GuiTheme::into_egui_theme(self.state.config.gui_theme);
+                                        ui.ctx().memory_mut(|m| m.options.theme_preference = theme);
+                                        self.last_action = Some(LastAction::success(format!("Imported settings from {}", path.display())));
+                                    }
+                                    Err(e) => error = Some(MintError::from(e).info()),
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Diagnostics:");
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("Health check")
+                                .on_hover_text("Check the pak path, permissions, disk space, file locks, whether the game is running and connectivity")
+                                .clicked()
+                            {
+                                run_health_check = true;
+                            }
+                            if ui
+                                .button("Export diagnostics config")
+                                .on_hover_text("Write config.json and mod_data.json with secrets removed and paths normalized, for attaching to bug reports")
+                                .clicked()
+                                && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                            {
+                                window.diagnostics_status = Some(
+                                    match crate::diagnostics::export_diagnostics_config(&self.state, folder) {
+                                        Ok(path) => {
+                                            opener::open(&path).ok();
+                                            (true, format!("Exported to {}", path.display()))
+                                        }
+                                        Err(e) => (false, format!("Export failed: {e}")),
+                                    },
+                                );
+                            }
+                            if ui
+                                .button("Create support bundle")
+                                .on_hover_text("Zip redacted config, recent logs, the last integration report, a cache summary and system info")
+                                .clicked()
+                            {
+                                window.diagnostics_status = Some(
+                                    match crate::diagnostics::write_support_bundle(&self.state, &self.state.dirs.data_dir) {
+                                        Ok(path) => {
+                                            opener::open(&self.state.dirs.data_dir).ok();
+                                            (true, format!("Support bundle written to {}", path.display()))
+                                        }
+                                        Err(e) => (false, format!("Support bundle failed: {e}")),
+                                    },
+                                );
+                            }
+                            if let Some((success, msg)) = &window.diagnostics_status {
+                                if *success {
+                                    ui.colored_label(Color32::LIGHT_GREEN, msg);
+                                } else {
+                                    ui.colored_label(ui.visuals().error_fg_color, msg);
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Import:");
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("Import profiles...")
+                                .on_hover_text(format!(
+                                    "Add profiles from another mod manager's data. Supported: {}",
+                                    Importer::all().map(|i| i.name).collect::<Vec<_>>().join(", ")
+                                ))
+                                .clicked()
+                                && let Some(path) = rfd::FileDialog::new().pick_file()
+                            {
+                                let res = Importer::detect(&path)
+                                    .and_then(|importer| importer.import(&path));
+                                window.import_status = Some(match res {
+                                    Ok(profiles) => {
+                                        let names = import::add_profiles(
+                                            &mut self.state.mod_data,
+                                            profiles,
+                                        );
+                                        self.state.mod_data.save().unwrap();
+                                        (true, format!("Imported {}", names.join(", ")))
+                                    }
+                                    Err(e) => (false, format!("Import failed: {e}")),
+                                });
+                            }
+                            if let Some((success, msg)) = &window.import_status {
+                                if *success {
+                                    ui.colored_label(Color32::LIGHT_GREEN, msg);
+                                } else {
+                                    ui.colored_label(ui.visuals().error_fg_color, msg);
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("UE4SS:");
+                        ui.vertical(|ui| {
+                            let manifest = window.ue4ss.as_ref();
+                            match manifest.and_then(|m| m.loader.as_ref()) {
+                                Some(loader) => ui.label(format!("installed by mint from {}", loader.name)),
+                                None => ui.label("not installed by mint"),
+                            };
+                            let mods = manifest.map(|m| m.mods.as_slice()).unwrap_or_default();
+                            if mods.is_empty() {
+                                ui.label("No script mods installed");
+                            }
+                            for package in mods {
+                                let version = self
+                                    .state
+                                    .store
+                                    .get_version_name(&ModSpecification::new(package.version.clone()))
+                                    .unwrap_or_default();
+                                ui.label(format!("{} {version}", package.name))
+                                    .on_hover_text(package.url.as_str());
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Mod providers:");
+                        ui.end_row();
+
+                        for provider_factory in ModStore::get_provider_factories() {
+                            ui.label(provider_factory.id);
+                            if ui.add_enabled(!provider_factory.parameters.is_empty(), egui::Button::new("⚙"))
+                                    .on_hover_text(format!("Open \"{}\" settings", provider_factory.id))
+                                    .clicked() {
+                                self.window_provider_parameters = Some(
+                                    WindowProviderParameters::new(provider_factory, &self.state),
+                                );
+                            }
+                            ui.end_row();
+                        }
+                    });
 
                     ui.with_layout(egui::Layout::right_to_left(Align::TOP), |ui| {
                         if ui.add_enabled(window.drg_pak_path_err.is_none(), egui::Button::new("save")).clicked() {
@@ -1641,16 +4019,118 @@ impl App {
             if try_save {
                 if let Err(e) = is_drg_pak(&window.drg_pak_path) {
                     window.drg_pak_path_err = Some(e.to_string());
+                    window.pak_suggestions =
+                        DRGInstallation::candidate_paks(Path::new(&window.drg_pak_path))
+                            .into_iter()
+                            .filter(|p| is_drg_pak(p).is_ok())
+                            .collect();
                 } else {
                     self.state.config.drg_pak_path = Some(PathBuf::from(
                         self.settings_window.take().unwrap().drg_pak_path,
                     ));
+                    self.state.config.remember_game_install();
                     self.state.config.save().unwrap();
                 }
             } else if !open {
                 self.settings_window = None;
             }
         }
+        if run_health_check {
+            message::HealthCheck::send(self, ctx);
+        }
+        if let Some(dir) = create_backup {
+            message::Backup::send(self, ctx, dir, backup::Trigger::Manual);
+        }
+        for feature in features {
+            self.record_feature(feature);
+        }
+        if let Some(info) = error {
+            self.last_action = Some(self.error_action(info));
+        }
+    }
+
+    fn show_missing_install(&mut self, ctx: &egui::Context) {
+        let Some(missing) = self.missing_install else {
+            return;
+        };
+
+        let mut open = true;
+        let mut reinstall = false;
+        egui::Window::new("Installed mods are missing")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Files from the last install are no longer in place. This usually happens \
+                     after Steam verified the game files.",
+                );
+                if missing.mod_pak {
+                    ui.label("• the mod bundle (mods_P.pak) was removed");
+                }
+                if missing.hook {
+                    ui.label("• the mint hook DLL was replaced or removed");
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Reinstall mods")
+                        .on_hover_text("Install the active profile again")
+                        .clicked()
+                    {
+                        reinstall = true;
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        self.missing_install = None;
+                    }
+                });
+            });
+
+        if reinstall {
+            self.missing_install = None;
+            self.install_mods(ctx);
+        } else if !open {
+            self.missing_install = None;
+        }
+    }
+
+    fn show_install_changes(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.install_changes else {
+            return;
+        };
+        if !window.open {
+            return;
+        }
+
+        let mut install = false;
+        egui::Window::new("Changes since the last install")
+            .open(&mut window.open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for change in &window.changes {
+                            let icon = match change {
+                                InstallChange::Added(_) => "➕",
+                                InstallChange::Removed(_) => "➖",
+                                InstallChange::Updated { .. } => "🔄",
+                            };
+                            ui.label(format!("{icon} {change}"));
+                        }
+                    });
+                ui.add_space(4.0);
+                install = ui
+                    .button("Install")
+                    .on_hover_text("Install the active profile to bring the game up to date")
+                    .clicked();
+            });
+
+        if install {
+            self.install_changes = None;
+            self.install_mods(ctx);
+        }
     }
 
     fn show_delete_confirmation(&mut self, ctx: &egui::Context) {
@@ -1661,9 +4141,13 @@ impl App {
         // Check if confirmation is enabled for this type
         let confirmation_enabled = match pending {
             PendingDeletion::Mod { .. } => self.state.config.confirm_mod_deletion,
-            PendingDeletion::Profile { .. } => self.state.config.confirm_profile_deletion,
+            // always warn when deleting the profile would lose folders or mods for good
+            PendingDeletion::Profile { unique, .. } => {
+                self.state.config.confirm_profile_deletion || !unique.is_empty()
+            }
             PendingDeletion::Folder { .. } => self.state.config.confirm_mod_deletion,
             PendingDeletion::FolderMod { .. } => self.state.config.confirm_mod_deletion,
+            PendingDeletion::Mods { .. } => self.state.config.confirm_mod_deletion,
         };
 
         // If confirmation is disabled, perform deletion immediately
@@ -1675,9 +4159,10 @@ impl App {
         // Extract info based on deletion type
         let (item_type, item_name) = match pending {
             PendingDeletion::Mod { mod_name, .. } => ("mod", mod_name.clone()),
-            PendingDeletion::Profile { profile_name } => ("profile", profile_name.clone()),
+            PendingDeletion::Profile { profile_name, .. } => ("profile", profile_name.clone()),
             PendingDeletion::Folder { folder_name } => ("folder", folder_name.clone()),
             PendingDeletion::FolderMod { mod_name, .. } => ("mod", mod_name.clone()),
+            PendingDeletion::Mods { urls, .. } => ("selection", format!("{} mods", urls.len())),
         };
 
         let mut confirmed = false;
@@ -1702,23 +4187,118 @@ impl App {
                             ui.label(egui::RichText::new(&item_name).strong());
                         });
 
-                    ui.add_space(16.0);
+                    if matches!(self.pending_deletion, Some(PendingDeletion::Profile { .. })) {
+                        ui.add_space(4.0);
+                        ui.label(format!(
+                            "It can be restored from 🗄 for {} days.",
+                            crate::state::TRASH_DAYS
+                        ));
+                    }
 
-                    ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
-                            cancelled = true;
-                        }
-                        ui.add_space(16.0);
-                        if ui
-                            .add(egui::Button::new(
-                                egui::RichText::new("Delete").color(egui::Color32::WHITE),
-                            ).fill(egui::Color32::DARK_RED))
-                            .clicked()
-                        {
-                            confirmed = true;
-                        }
-                    });
-                    ui.add_space(8.0);
+                    let mut delete_label = "Delete";
+                    if let Some(PendingDeletion::Profile {
+                        profile_name,
+                        unique,
+                        move_to,
+                        export_status,
+                    }) = &mut self.pending_deletion
+                        && !unique.is_empty()
+                    {
+                        ui.add_space(8.0);
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "This is the only profile containing {} folder(s) and {} mod(s).",
+                                unique.folders.len(),
+                                unique.mods.len()
+                            ),
+                        );
+                        CollapsingHeader::new("Show")
+                            .id_salt("delete-profile-unique")
+                            .show(ui, |ui| {
+                                egui::ScrollArea::vertical()
+                                    .max_height(150.0)
+                                    .show(ui, |ui| {
+                                        for folder in &unique.folders {
+                                            ui.label(format!("📁 {folder}"));
+                                        }
+                                        for spec in &unique.mods {
+                                            let name = self
+                                                .state
+                                                .store
+                                                .get_mod_info(spec)
+                                                .map(|i| i.name)
+                                                .unwrap_or_else(|| spec.url.clone());
+                                            ui.label(name);
+                                        }
+                                    });
+                            });
+
+                        ui.horizontal(|ui| {
+                            ui.label("Move them to:");
+                            egui::ComboBox::from_id_salt("delete-profile-move-to")
+                                .selected_text(move_to.as_deref().unwrap_or("(don't move)"))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(move_to, None, "(don't move)");
+                                    for name in self.state.mod_data.profiles.keys() {
+                                        if *name != *profile_name {
+                                            ui.selectable_value(move_to, Some(name.clone()), name);
+                                        }
+                                    }
+                                });
+                        });
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button("Export profile...")
+                                .on_hover_text("Save this profile to a file before deleting it")
+                                .clicked()
+                                && let Some(path) = rfd::FileDialog::new()
+                                    .set_file_name(format!("{profile_name}.{PROFILE_EXTENSION}"))
+                                    .add_filter("mint profile", &[PROFILE_EXTENSION])
+                                    .save_file()
+                            {
+                                *export_status = Some(
+                                    match self.state.mod_data.export_profile(profile_name, &path) {
+                                        Ok(()) => (true, format!("Exported to {}", path.display())),
+                                        Err(e) => (false, format!("Export failed: {e}")),
+                                    },
+                                );
+                            }
+                            if let Some((success, msg)) = export_status {
+                                if *success {
+                                    ui.colored_label(Color32::LIGHT_GREEN, msg.as_str());
+                                } else {
+                                    ui.colored_label(ui.visuals().error_fg_color, msg.as_str());
+                                }
+                            }
+                        });
+
+                        if move_to.is_some() {
+                            delete_label = "Move and delete";
+                        }
+                    }
+
+                    ui.add_space(16.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            cancelled = true;
+                        }
+                        ui.add_space(16.0);
+                        if ui
+                            .add(
+                                egui::Button::new(
+                                    egui::RichText::new(delete_label).color(egui::Color32::WHITE),
+                                )
+                                .fill(egui::Color32::DARK_RED),
+                            )
+                            .clicked()
+                        {
+                            confirmed = true;
+                        }
+                    });
+                    ui.add_space(8.0);
                 });
             });
 
@@ -1739,16 +4319,18 @@ impl App {
                     self.state.mod_data.save().unwrap();
                 }
             }
-            Some(PendingDeletion::Profile { profile_name }) => {
+            Some(PendingDeletion::Profile {
+                profile_name,
+                move_to,
+                ..
+            }) => {
                 let profile_name = profile_name.clone();
-                self.state.mod_data.profiles.remove(&profile_name);
-                // Select a different profile if we deleted the active one
-                if self.state.mod_data.active_profile == profile_name {
-                    if let Some(first_profile) = self.state.mod_data.profiles.keys().next() {
-                        self.state.mod_data.active_profile = first_profile.clone();
-                    }
+                if let Some(move_to) = move_to.clone() {
+                    self.state
+                        .mod_data
+                        .move_unique_contents(&profile_name, &move_to);
                 }
-                
+                self.state.mod_data.trash_profile(&profile_name);
                 self.state.mod_data.save().unwrap();
             }
             Some(PendingDeletion::Folder { folder_name }) => {
@@ -1756,16 +4338,8 @@ impl App {
                 let active_profile = self.state.mod_data.active_profile.clone();
                 
                 if let Some(profile) = self.state.mod_data.profiles.get_mut(&active_profile) {
-                    // Move all mods from folder back to root
-                    if let Some(group) = profile.groups.remove(&folder_name) {
-                        for mod_config in group.mods {
-                            profile.mods.push(ModOrGroup::Individual(mod_config));
-                        }
-                    }
-                    // Remove the group reference from profile's mods list
-                    profile.mods.retain(|item| {
-                        !matches!(item, ModOrGroup::Group { group_name, .. } if group_name == &folder_name)
-                    });
+                    // Move the folder's contents to where the folder was
+                    profile.remove_folder(&folder_name);
                 }
                 
                 self.state.mod_data.save().unwrap();
@@ -1785,6 +4359,12 @@ impl App {
                 
                 self.state.mod_data.save().unwrap();
             }
+            Some(PendingDeletion::Mods { profile, urls }) => {
+                if let Some(profile) = self.state.mod_data.profiles.get_mut(profile) {
+                    profile.remove_mods(urls);
+                }
+                self.state.mod_data.save().unwrap();
+            }
             None => {}
         }
         self.pending_deletion = None;
@@ -1850,16 +4430,27 @@ impl App {
         if should_close {
             self.create_folder_popup = None;
         } else if should_create {
+            self.record_feature("create_folder");
             let folder_name = self.create_folder_popup.take().unwrap().trim().to_string();
             // Add group to active profile
             if let Some(profile) = self.state.mod_data.profiles.get_mut(&active_profile) {
                 // Create the group in profile's groups map
-                profile.groups.insert(folder_name.clone(), crate::state::ModGroup { 
-                    mods: vec![],
-                    priority_override: None,
-                });
+                profile.groups.insert(
+                    folder_name.clone(),
+                    ModGroup {
+                        mods: vec![],
+                        priority_override: None,
+                        priority_offset: None,
+                    },
+                );
                 // Add group reference to profile's mods list (at the top)
-                profile.mods.insert(0, ModOrGroup::Group { group_name: folder_name.clone(), enabled: true });
+                profile.mods.insert(
+                    0,
+                    ModOrGroup::Group {
+                        group_name: folder_name.clone(),
+                        enabled: true,
+                    },
+                );
                 // Expand the newly created folder so user can see it
                 self.expand_folder = Some(folder_name);
             }
@@ -1867,517 +4458,2570 @@ impl App {
         }
     }
 
-    fn show_rename_folder_popup(&mut self, ctx: &egui::Context) {
-        if self.rename_folder_popup.is_none() {
+    fn show_history(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.history_window else {
             return;
-        }
-
-        let mut should_close = false;
-        let mut should_rename = false;
-        
-        // Get active profile for checking existing folders
-        let active_profile = self.state.mod_data.active_profile.clone();
+        };
+        let history_dir = self.state.dirs.data_dir.join(history::HISTORY_DIR);
 
-        egui::Window::new("Rename Folder")
-            .collapsible(false)
+        let mut open = true;
+        let mut restore = None;
+        egui::Window::new("History")
+            .open(&mut open)
             .resizable(false)
-            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
             .show(ctx, |ui| {
-                ui.vertical_centered(|ui| {
-                    ui.add_space(8.0);
-                    ui.label("Enter new folder name:");
-                    ui.add_space(8.0);
-
-                    let (old_name, buffer) = self.rename_folder_popup.as_mut().unwrap();
-                    let response = ui.text_edit_singleline(buffer);
-
-                    // Check if name already exists (and is different from current)
-                    let name_exists = buffer != old_name && self.state.mod_data.profiles
-                        .get(&active_profile)
-                        .map(|p| p.groups.contains_key(buffer.as_str()))
-                        .unwrap_or(false);
-                    let name_valid = !buffer.trim().is_empty() && !name_exists;
-
-                    if name_exists {
-                        ui.colored_label(ui.visuals().error_fg_color, "Folder name already exists");
+                ui.label(format!(
+                    "The profiles as they were before changes and installs, the newest {} of each are kept.",
+                    history::KEEP_SNAPSHOTS
+                ));
+                if ui.button("Open folder").clicked() {
+                    opener::open(&history_dir).ok();
+                }
+                if let Some((success, msg)) = &window.status {
+                    if *success {
+                        ui.colored_label(Color32::LIGHT_GREEN, msg);
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, msg);
                     }
+                }
+                ui.separator();
 
-                    ui.add_space(16.0);
-
-                    ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
-                            should_close = true;
-                        }
-                        ui.add_space(16.0);
-                        if ui.add_enabled(name_valid, egui::Button::new("Rename")).clicked()
-                            || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && name_valid)
-                        {
-                            should_rename = true;
+                if window.snapshots.is_empty() {
+                    ui.label("No snapshots yet");
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("history").num_columns(3).striped(true).show(ui, |ui| {
+                        for (i, snapshot) in window.snapshots.iter().enumerate() {
+                            ui.label(snapshot.time.format("%Y-%m-%d %H:%M:%S").to_string());
+                            ui.label(snapshot.reason.to_string());
+                            ui.horizontal(|ui| {
+                                if window.confirm_restore == Some(i) {
+                                    if ui
+                                        .button(RichText::new("Confirm restore").color(ui.visuals().warn_fg_color))
+                                        .on_hover_text("Replace all profiles and folders. They are kept in the history first.")
+                                        .clicked()
+                                    {
+                                        restore = Some(snapshot.clone());
+                                        window.confirm_restore = None;
+                                    }
+                                    if ui.button("Cancel").clicked() {
+                                        window.confirm_restore = None;
+                                    }
+                                } else if ui.button("Restore").clicked() {
+                                    window.confirm_restore = Some(i);
+                                }
+                            });
+                            ui.end_row();
                         }
                     });
-                    ui.add_space(8.0);
                 });
             });
 
-        if should_close {
-            self.rename_folder_popup = None;
-        } else if should_rename {
-            let (old_name, new_name) = self.rename_folder_popup.take().unwrap();
-            let new_name = new_name.trim().to_string();
-            
-            // Rename in active profile only
-            if let Some(profile) = self.state.mod_data.profiles.get_mut(&active_profile) {
-                // Move the group data to new key
-                if let Some(group) = profile.groups.remove(&old_name) {
-                    profile.groups.insert(new_name.clone(), group);
-                }
-                
-                // Update references in mods list
-                for item in &mut profile.mods {
-                    if let ModOrGroup::Group { group_name, .. } = item {
-                        if group_name == &old_name {
-                            *group_name = new_name.clone();
-                        }
-                    }
-                }
-            }
-            
-            self.state.mod_data.save().unwrap();
+        if let Some(snapshot) = restore {
+            let time = snapshot.time.format("%Y-%m-%d %H:%M:%S");
+            window.status = Some(match self.state.restore_snapshot(&snapshot) {
+                Ok(()) => (true, format!("Profiles restored from {time}")),
+                Err(e) => (false, format!("Restore failed: {e}")),
+            });
+            window.snapshots = history::list(&history_dir).unwrap_or_default();
+        }
+        if !open {
+            self.history_window = None;
         }
     }
 
-    fn show_lints_toggle(&mut self, ctx: &egui::Context) {
-        if let Some(_lints_toggle) = &self.lints_toggle_window {
-            let mut open = true;
-
-            egui::Window::new("Toggle lints")
-                .open(&mut open)
-                .resizable(false)
-                .show(ctx, |ui| {
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        egui::Grid::new("lints-toggle-grid").show(ui, |ui| {
-                            ui.heading("Lint");
-                            ui.heading("Enabled?");
-                            ui.end_row();
-
-                            ui.label("Archive with multiple paks");
-                            ui.add(toggle_switch(
-                                &mut self.lint_options.archive_with_multiple_paks,
-                            ));
-                            ui.end_row();
-
-                            ui.label("Archive with only non-pak files");
-                            ui.add(toggle_switch(
-                                &mut self.lint_options.archive_with_only_non_pak_files,
-                            ));
-                            ui.end_row();
-
-                            ui.label("Mods containing AssetRegister.bin");
-                            ui.add(toggle_switch(&mut self.lint_options.asset_register_bin));
-                            ui.end_row();
+    fn show_save_snapshots(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.save_snapshots_window else {
+            return;
+        };
+        let installation = self
+            .state
+            .config
+            .drg_pak_path
+            .as_ref()
+            .and_then(|p| DRGInstallation::from_pak_path(p).ok());
+        let data_dir = &self.state.dirs.data_dir;
 
-                            ui.label("Mods containing conflicting files");
-                            ui.add(toggle_switch(&mut self.lint_options.conflicting));
-                            ui.end_row();
+        let mut open = true;
+        let mut reload = false;
+        egui::Window::new("Save snapshots")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let Some(installation) = &installation else {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        "DRG install not found. Configure it in the settings.",
+                    );
+                    return;
+                };
+                match saves::save_dir(installation) {
+                    Ok(dir) => ui.label(format!("Save files: {}", dir.display())),
+                    Err(e) => ui.colored_label(ui.visuals().error_fg_color, e.to_string()),
+                };
 
-                            ui.label("Mods containing empty archives");
-                            ui.add(toggle_switch(&mut self.lint_options.empty_archive));
-                            ui.end_row();
+                ui.horizontal(|ui| {
+                    if ui.button("Snapshot now").clicked() {
+                        window.status = Some(
+                            match saves::create(
+                                installation,
+                                data_dir,
+                                &self.state.mod_data.active_profile,
+                                "manual",
+                            ) {
+                                Ok(_) => (true, "Snapshot created".to_string()),
+                                Err(e) => (false, format!("Snapshot failed: {e}")),
+                            },
+                        );
+                        reload = true;
+                    }
+                    if ui.button("Open folder").clicked() {
+                        opener::open(data_dir.join(saves::SNAPSHOT_DIR)).ok();
+                    }
+                });
+                if let Some((success, msg)) = &window.status {
+                    if *success {
+                        ui.colored_label(Color32::LIGHT_GREEN, msg);
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, msg);
+                    }
+                }
+                ui.separator();
 
-                            ui.label("Mods containing oudated pak version");
-                            ui.add(toggle_switch(&mut self.lint_options.outdated_pak_version));
+                if window.snapshots.is_empty() {
+                    ui.label("No snapshots yet");
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("save-snapshots").num_columns(4).striped(true).show(ui, |ui| {
+                        for (i, snapshot) in window.snapshots.iter().enumerate() {
+                            let time = chrono::DateTime::parse_from_rfc3339(&snapshot.time)
+                                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_else(|_| snapshot.time.clone());
+                            ui.label(time);
+                            ui.label(&snapshot.profile);
+                            ui.label(&snapshot.reason);
+                            ui.horizontal(|ui| {
+                                if window.confirm_restore == Some(i) {
+                                    if ui
+                                        .button(RichText::new("Confirm restore").color(ui.visuals().warn_fg_color))
+                                        .on_hover_text("Overwrite the current save files. The game must be closed. The current saves are snapshotted first.")
+                                        .clicked()
+                                    {
+                                        window.status = Some(match saves::restore(installation, data_dir, snapshot) {
+                                            Ok(_) => (true, format!("Save files restored from {time}")),
+                                            Err(e) => (false, format!("Restore failed: {e}")),
+                                        });
+                                        window.confirm_restore = None;
+                                        reload = true;
+                                    }
+                                    if ui.button("Cancel").clicked() {
+                                        window.confirm_restore = None;
+                                    }
+                                } else {
+                                    if ui.button("Restore").clicked() {
+                                        window.confirm_restore = Some(i);
+                                    }
+                                    if ui.button("🗑").on_hover_text("Delete snapshot").clicked() {
+                                        if let Err(e) = saves::delete(snapshot) {
+                                            window.status = Some((false, format!("Delete failed: {e}")));
+                                        }
+                                        reload = true;
+                                    }
+                                }
+                            });
                             ui.end_row();
+                        }
+                    });
+                });
+            });
 
-                            ui.label("Mods containing shader files");
-                            ui.add(toggle_switch(&mut self.lint_options.shader_files));
-                            ui.end_row();
+        if !open {
+            self.save_snapshots_window = None;
+        } else if reload {
+            window.snapshots = saves::list(data_dir).unwrap_or_default();
+        }
+    }
 
-                            ui.label("Mods containing non-asset files");
-                            ui.add(toggle_switch(&mut self.lint_options.non_asset_files));
-                            ui.end_row();
+    fn show_payload_rules(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.payload_rules_window else {
+            return;
+        };
 
-                            ui.label("Mods containing split {uexp, uasset} pairs");
-                            ui.add(toggle_switch(&mut self.lint_options.split_asset_pairs));
-                            ui.end_row();
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new(format!("Install destinations: {}", window.name))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Files of this mod's zip archive to install outside of the mod bundle, e.g. \
+                     config files or UE4SS scripts. Archive folders are relative to the archive, \
+                     game folders to the game directory containing FSD.",
+                );
+                ui.add_space(8.0);
 
-                            ui.label("Mods containing unmodified game assets");
-                            ui.add_enabled(
-                                self.state.config.drg_pak_path.is_some(),
-                                toggle_switch(&mut self.lint_options.unmodified_game_assets),
-                            )
-                            .on_disabled_hover_text(
-                                "This lint requires DRG pak path to be specified",
+                let mut remove = None;
+                egui::Grid::new("payload-rules")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.strong("Archive folder");
+                        ui.strong("Game folder");
+                        ui.end_row();
+                        for (i, (source, target)) in window.rows.iter_mut().enumerate() {
+                            ui.add(egui::TextEdit::singleline(source).hint_text("(whole archive)"));
+                            ui.add(
+                                egui::TextEdit::singleline(target)
+                                    .hint_text("FSD/Binaries/Win64/Mods"),
                             );
+                            if ui.button("🗑").clicked() {
+                                remove = Some(i);
+                            }
                             ui.end_row();
-                        });
+                        }
                     });
+                if let Some(i) = remove {
+                    window.rows.remove(i);
+                }
+                if ui.button("➕ Add destination").clicked() {
+                    window.rows.push(Default::default());
+                }
 
-                    ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
-                            self.lints_toggle_window = None;
-                        }
+                ui.add_space(8.0);
+                let invalid = window.rows.iter().any(|(_, target)| {
+                    target.trim().is_empty()
+                        || !crate::integrate::payload::is_contained(Path::new(target.trim()))
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!invalid, egui::Button::new("Save"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                    if invalid {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            "Game folders must be relative paths inside the game directory",
+                        );
+                    } else {
+                        ui.weak("Takes effect on the next install");
+                    }
+                });
+            });
 
-                        if ui
-                            .add_enabled(
-                                self.check_updates_rid.is_none()
-                                    && self.integrate_rid.is_none()
-                                    && self.lint_rid.is_none(),
-                                egui::Button::new("Generate report"),
-                            )
-                            .clicked()
-                        {
-                            let lint_options = BTreeMap::from([
-                                (
-                                    LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
-                                    self.lint_options.archive_with_multiple_paks,
-                                ),
-                                (
-                                    LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
-                                    self.lint_options.archive_with_only_non_pak_files,
-                                ),
-                                (
-                                    LintId::ASSET_REGISTRY_BIN,
-                                    self.lint_options.asset_register_bin,
-                                ),
-                                (LintId::CONFLICTING, self.lint_options.conflicting),
-                                (LintId::EMPTY_ARCHIVE, self.lint_options.empty_archive),
-                                (
-                                    LintId::OUTDATED_PAK_VERSION,
-                                    self.lint_options.outdated_pak_version,
-                                ),
-                                (LintId::SHADER_FILES, self.lint_options.shader_files),
-                                (LintId::NON_ASSET_FILES, self.lint_options.non_asset_files),
-                                (
-                                    LintId::SPLIT_ASSET_PAIRS,
-                                    self.lint_options.split_asset_pairs,
-                                ),
-                                (
-                                    LintId::UNMODIFIED_GAME_ASSETS,
-                                    self.lint_options.unmodified_game_assets,
-                                ),
-                            ]);
+        if save && let Some(window) = self.payload_rules_window.take() {
+            let rules = window
+                .rows
+                .into_iter()
+                .map(|(source, target)| PayloadRule {
+                    source: source.trim().trim_matches(['/', '\\']).to_string(),
+                    target: PathBuf::from(target.trim()),
+                })
+                .collect::<Vec<_>>();
+            if rules.is_empty() {
+                self.state.mod_data.payload_rules.remove(&window.spec.url);
+            } else {
+                self.state
+                    .mod_data
+                    .payload_rules
+                    .insert(window.spec.url, rules);
+            }
+            self.state.mod_data.save().unwrap();
+        } else if !open {
+            self.payload_rules_window = None;
+        }
+    }
 
-                            trace!(?lint_options);
+    fn show_local_metadata(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.local_metadata_window else {
+            return;
+        };
 
-                            let mut mods = Vec::new();
-                            self.state.mod_data.for_each_enabled_mod(
-                                &self.state.mod_data.active_profile,
-                                |mc| {
-                                    mods.push(mc.spec.clone());
-                                },
-                            );
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new(format!("Mod details: {}", window.name))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "This mod has no metadata from its provider. Set it here to sort, search and \
+                     check dependencies like for mods from mod.io.",
+                );
+                ui.add_space(8.0);
 
-                            self.lint_report = None;
-                            self.lint_rid = Some(message::LintMods::send(
-                                &mut self.request_counter,
-                                self.state.store.clone(),
-                                mods,
-                                BTreeSet::from_iter(
-                                    lint_options
-                                        .into_iter()
-                                        .filter_map(|(lint, enabled)| enabled.then_some(lint)),
-                                ),
-                                self.state.config.drg_pak_path.clone(),
-                                self.tx.clone(),
-                                ctx.clone(),
-                            ));
-                            self.problematic_mod_id = None;
-                            self.lint_report_window = Some(WindowLintReport);
+                egui::Grid::new("local-metadata")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut window.display_name)
+                                .hint_text(window.spec.url.as_str()),
+                        );
+                        ui.end_row();
+                        ui.label("Tags:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut window.tags).hint_text("QoL, Visual"),
+                        );
+                        ui.end_row();
+                    });
+
+                ui.add_space(8.0);
+                ui.strong("Dependencies");
+                let mut remove = None;
+                egui::Grid::new("local-metadata-dependencies")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for (i, (url, constraint)) in window.dependencies.iter_mut().enumerate() {
+                            ui.add(
+                                egui::TextEdit::singleline(url)
+                                    .hint_text("https://mod.io/g/drg/m/..."),
+                            );
+                            ui.add(
+                                egui::TextEdit::singleline(constraint)
+                                    .hint_text("any version")
+                                    .desired_width(100.0),
+                            )
+                            .on_hover_text(
+                                "Optional version: >=123 for this mod.io file ID or newer, =123 \
+                                 for exactly this one",
+                            );
+                            if ui.button("🗑").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
                         }
                     });
+                if let Some(i) = remove {
+                    window.dependencies.remove(i);
+                }
+                if ui.button("➕ Add dependency").clicked() {
+                    window.dependencies.push(Default::default());
+                }
+
+                ui.add_space(8.0);
+                let metadata = window.metadata();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(metadata.is_some(), egui::Button::new("Save"))
+                        .clicked()
+                    {
+                        save = true;
+                    }
+                    if metadata.is_none() {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            "Dependencies need a URL and a version like >=123, =123 or none",
+                        );
+                    }
                 });
+            });
 
-            if !open {
-                self.lints_toggle_window = None;
+        if save
+            && let Some(window) = self.local_metadata_window.take()
+            && let Some(metadata) = window.metadata()
+        {
+            let local_metadata = &mut self.state.mod_data.local_metadata;
+            if metadata.is_empty() {
+                local_metadata.remove(&window.spec.url);
+            } else {
+                local_metadata.insert(window.spec.url, metadata);
             }
+            self.state.store.set_local_metadata(local_metadata.clone());
+            self.state.mod_data.save().unwrap();
+        } else if !open {
+            self.local_metadata_window = None;
         }
     }
 
-    fn show_lint_report(&mut self, ctx: &egui::Context) {
-        if self.lint_report_window.is_some() {
-            let mut open = true;
+    fn show_refresh_link(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.refresh_link_window else {
+            return;
+        };
 
-            egui::Window::new("Lint results")
-                .open(&mut open)
-                .resizable(true)
-                .show(ctx, |ui| {
-                    if let Some(report) = &self.lint_report {
-                        let scroll_height =
-                            (ui.available_height() - 30.0).clamp(0.0, f32::INFINITY);
-                        egui::ScrollArea::vertical()
-                            .max_height(scroll_height)
-                            .show(ui, |ui| {
-                                const AMBER: Color32 = Color32::from_rgb(255, 191, 0);
+        let mut open = true;
+        let mut replace = false;
+        egui::Window::new("Link expired")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "The download link of {} has expired or is no longer authorized. Paste a new \
+                     link for the mod to keep its place and settings, then install again.",
+                    window.name
+                ));
+                ui.add_space(8.0);
+                let res = ui.add(
+                    egui::TextEdit::singleline(&mut window.new_url)
+                        .hint_text("https://...")
+                        .desired_width(f32::INFINITY),
+                );
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let valid = window.new_url.trim().starts_with("http");
+                    if ui
+                        .add_enabled(valid, egui::Button::new("Replace and install"))
+                        .clicked()
+                        || (valid && is_committed(&res))
+                    {
+                        replace = true;
+                    }
+                    ui.label(
+                        "Refresh rules in the http provider settings can do this by themselves.",
+                    );
+                });
+            });
 
-                                if let Some(conflicting_mods) = &report.conflicting_mods
-                                    && !conflicting_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new("⚠ Mods(s) with conflicting asset modifications detected")
-                                                .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            conflicting_mods.iter().for_each(|(path, mods)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "⚠ Conflicting modification of asset `{path}`"
-                                                    ))
-                                                    .color(AMBER),
-                                                )
-                                                .show(
-                                                    ui,
-                                                    |ui| {
-                                                        mods.iter().for_each(|mod_spec| {
-                                                            ui.label(&mod_spec.url);
-                                                        });
-                                                    },
-                                                );
-                                            });
-                                        });
-                                    }
+        if replace && let Some(window) = self.refresh_link_window.take() {
+            let new = ModSpecification::new(window.new_url.trim().to_string());
+            if self.replace_mod_url(&window.url, new)
+                && self.integrate_rid.is_none()
+                && self.update_rid.is_none()
+            {
+                self.integrate_mods(ctx, window.download_only);
+            }
+        } else if !open {
+            self.refresh_link_window = None;
+        }
+    }
 
-                                if let Some(asset_register_bin_mods) = &report.asset_register_bin_mods
-                                    && !asset_register_bin_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new("ℹ Mod(s) with `AssetRegistry.bin` included detected")
-                                                .color(Color32::LIGHT_BLUE),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            asset_register_bin_mods.iter().for_each(
-                                                |(r#mod, paths)| {
-                                                    CollapsingHeader::new(
-                                                        RichText::new(format!(
-                                                        "ℹ {} includes one or more `AssetRegistry.bin`",
-                                                        r#mod.url
-                                                    ))
-                                                        .color(Color32::LIGHT_BLUE),
-                                                    )
-                                                    .show(ui, |ui| {
-                                                        paths.iter().for_each(|path| {
-                                                            ui.label(path);
-                                                        });
-                                                    });
-                                                },
-                                            );
-                                        });
-                                    }
+    fn show_merge_profiles(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.merge_profiles_window else {
+            return;
+        };
 
-                                if let Some(shader_file_mods) = &report.shader_file_mods
-                                    && !shader_file_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mods(s) with shader files included detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            shader_file_mods.iter().for_each(
-                                                |(r#mod, shader_files)| {
-                                                    CollapsingHeader::new(
-                                                        RichText::new(format!(
-                                                            "⚠ {} includes one or more shader files",
-                                                            r#mod.url
-                                                        ))
-                                                        .color(AMBER),
-                                                    )
-                                                    .show(ui, |ui| {
-                                                        shader_files.iter().for_each(|shader_file| {
-                                                            ui.label(shader_file);
-                                                        });
-                                                    });
-                                                },
-                                            );
-                                        });
-                                    }
+        let store = &self.state.store;
+        let describe = |mc: &ModConfig| {
+            let version = store
+                .get_version_name(&mc.spec)
+                .unwrap_or_else(|| mc.spec.url.clone());
+            format!("{version} (priority {})", mc.priority)
+        };
 
-                                if let Some(outdated_pak_version_mods) = &report.outdated_pak_version_mods
-                                    && !outdated_pak_version_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with outdated pak version detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            outdated_pak_version_mods.iter().for_each(
-                                                |(r#mod, version)| {
-                                                    ui.label(
-                                                        RichText::new(format!(
-                                                            "⚠ {} includes outdated pak version {}",
-                                                            r#mod.url, version
-                                                        ))
-                                                        .color(AMBER),
-                                                    );
-                                                },
-                                            );
-                                        });
-                                    }
+        let mut open = true;
+        let mut merge = false;
+        egui::Window::new("Merge profiles")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Merge into {:?} from:", window.into));
+                    let mut from = window.from.clone();
+                    egui::ComboBox::from_id_salt("merge-profiles-from")
+                        .selected_text(from.as_deref().unwrap_or("(select profile)"))
+                        .show_ui(ui, |ui| {
+                            for name in self.state.mod_data.profiles.keys() {
+                                if *name != window.into {
+                                    ui.selectable_value(&mut from, Some(name.clone()), name);
+                                }
+                            }
+                        });
+                    if from != window.from {
+                        window.conflicts = from
+                            .as_deref()
+                            .map(|from| {
+                                self.state
+                                    .mod_data
+                                    .merge_conflicts(&window.into, from, |s| mod_key(store, s))
+                            })
+                            .unwrap_or_default();
+                        window.take_source.clear();
+                        window.from = from;
+                    }
+                });
 
-                                if let Some(empty_archive_mods) = &report.empty_archive_mods
-                                    && !empty_archive_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with empty archives detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            empty_archive_mods.iter().for_each(|r#mod| {
-                                                ui.label(
-                                                    RichText::new(format!(
-                                                        "⚠ {} contains an empty archive",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(AMBER),
-                                                );
-                                            });
-                                        });
+                let Some(from) = &window.from else {
+                    return;
+                };
+                ui.add_space(8.0);
+                if window.conflicts.is_empty() {
+                    ui.label("Both profiles agree on the versions and priorities of shared mods.");
+                } else {
+                    ui.label("These mods differ between the profiles. Choose which to keep:");
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("merge-profiles-conflicts")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    ui.strong("Mod");
+                                    ui.strong(&window.into);
+                                    ui.strong(from);
+                                    ui.end_row();
+                                    for conflict in &window.conflicts {
+                                        let name = store
+                                            .get_mod_info(&conflict.target.spec)
+                                            .map(|info| info.name)
+                                            .unwrap_or_else(|| conflict.key.clone());
+                                        ui.label(name);
+                                        let mut take = window.take_source.contains(&conflict.key);
+                                        ui.radio_value(
+                                            &mut take,
+                                            false,
+                                            describe(&conflict.target),
+                                        );
+                                        ui.radio_value(&mut take, true, describe(&conflict.source));
+                                        if take {
+                                            window.take_source.insert(conflict.key.clone());
+                                        } else {
+                                            window.take_source.remove(&conflict.key);
+                                        }
+                                        ui.end_row();
                                     }
+                                });
+                        });
+                }
+                ui.add_space(8.0);
+                if ui
+                    .button("Merge")
+                    .on_hover_text(format!(
+                        "Add the mods and folders of {from:?} missing from {:?}",
+                        window.into
+                    ))
+                    .clicked()
+                {
+                    merge = true;
+                }
+            });
 
-                                if let Some(archive_with_only_non_pak_files_mods) = &report.archive_with_only_non_pak_files_mods
-                                    && !archive_with_only_non_pak_files_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with only non-`.pak` files detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            archive_with_only_non_pak_files_mods.iter().for_each(|r#mod| {
-                                                ui.label(
-                                                    RichText::new(format!(
-                                                        "⚠ {} contains only non-`.pak` files, perhaps the author forgot to pack it?",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(AMBER),
-                                                );
-                                            });
-                                        });
-                                    }
+        if merge
+            && let Some(window) = self.merge_profiles_window.take()
+            && let Some(from) = window.from
+        {
+            let store = &self.state.store;
+            self.state.mod_data.merge_profiles(
+                &window.into,
+                &from,
+                |s| mod_key(store, s),
+                &window.take_source,
+            );
+            self.state.mod_data.save().unwrap();
+            self.last_action = Some(LastAction::success(format!(
+                "merged profile {from:?} into {:?}",
+                window.into
+            )));
+        } else if !open {
+            self.merge_profiles_window = None;
+        }
+    }
 
-                                if let Some(archive_with_multiple_paks_mods) = &report.archive_with_multiple_paks_mods
-                                    && !archive_with_multiple_paks_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with multiple `.pak`s detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            archive_with_multiple_paks_mods.iter().for_each(|r#mod| {
-                                                ui.label(RichText::new(format!(
-                                                    "⚠ {} contains multiple `.pak`s, only the first encountered `.pak` will be loaded",
-                                                    r#mod.url
-                                                ))
-                                                .color(AMBER));
-                                            });
-                                        });
-                                    }
+    fn show_save_loadout_popup(&mut self, ctx: &egui::Context) {
+        let Some(buffer) = &mut self.save_loadout_popup else {
+            return;
+        };
 
-                                if let Some(non_asset_file_mods) = &report.non_asset_file_mods
-                                    && !non_asset_file_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with non-asset files detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            non_asset_file_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "⚠ {} includes non-asset files",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|file| {
-                                                        ui.label(file);
-                                                    });
-                                                });
-                                            });
-                                        });
-                                    }
+        let mut should_close = false;
+        let mut should_save = false;
 
-                                if let Some(split_asset_pairs_mods) = &report.split_asset_pairs_mods
-                                    && !split_asset_pairs_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with split {uexp, uasset} pairs detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            split_asset_pairs_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "⚠ {} includes split {{uexp, uasset}} pairs",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|(file, kind)| {
-                                                        match kind {
-                                                            SplitAssetPair::MissingUasset => {
-                                                                ui.label(format!("`{file}` missing matching .uasset file"));
-                                                            },
-                                                            SplitAssetPair::MissingUexp => {
-                                                                ui.label(format!("`{file}` missing matching .uexp file"));
-                                                            }
-                                                        }
-                                                    });
-                                                });
-                                            });
-                                        });
-                                    }
+        let active_profile = self.state.mod_data.active_profile.clone();
+
+        egui::Window::new("Save Loadout")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(8.0);
+                    ui.label("Save which mods and folders are enabled as:");
+                    ui.add_space(8.0);
+
+                    let response = ui.text_edit_singleline(buffer);
+                    if response.gained_focus() || buffer.is_empty() {
+                        response.request_focus();
+                    }
+
+                    let name_exists = self
+                        .state
+                        .mod_data
+                        .loadouts
+                        .get(&active_profile)
+                        .is_some_and(|l| l.contains_key(buffer.trim()));
+                    let name_valid = !buffer.trim().is_empty();
+
+                    if name_exists {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            "Existing loadout will be overwritten",
+                        );
+                    }
+
+                    ui.add_space(16.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                        ui.add_space(16.0);
+                        if ui
+                            .add_enabled(name_valid, egui::Button::new("Save"))
+                            .clicked()
+                            || (response.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                && name_valid)
+                        {
+                            should_save = true;
+                        }
+                    });
+                    ui.add_space(8.0);
+                });
+            });
+
+        if should_close {
+            self.save_loadout_popup = None;
+        } else if should_save {
+            self.record_feature("save_loadout");
+            let name = self.save_loadout_popup.take().unwrap().trim().to_string();
+            self.state
+                .mod_data
+                .save_loadout(&active_profile, name.clone());
+            self.state.mod_data.save().unwrap();
+            self.last_action = Some(LastAction::success(format!("saved loadout {name:?}")));
+        }
+    }
+
+    fn show_rename_folder_popup(&mut self, ctx: &egui::Context) {
+        if self.rename_folder_popup.is_none() {
+            return;
+        }
+
+        let mut should_close = false;
+        let mut should_rename = false;
+        
+        // Get active profile for checking existing folders
+        let active_profile = self.state.mod_data.active_profile.clone();
+
+        egui::Window::new("Rename Folder")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(8.0);
+                    ui.label("Enter new folder name:");
+                    ui.add_space(8.0);
+
+                    let (old_name, buffer) = self.rename_folder_popup.as_mut().unwrap();
+                    let response = ui.text_edit_singleline(buffer);
+
+                    // Check if name already exists (and is different from current)
+                    let name_exists = buffer != old_name && self.state.mod_data.profiles
+                        .get(&active_profile)
+                        .map(|p| p.groups.contains_key(buffer.as_str()))
+                        .unwrap_or(false);
+                    let name_valid = !buffer.trim().is_empty() && !name_exists;
+
+                    if name_exists {
+                        ui.colored_label(ui.visuals().error_fg_color, "Folder name already exists");
+                    }
+
+                    ui.add_space(16.0);
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                        ui.add_space(16.0);
+                        if ui.add_enabled(name_valid, egui::Button::new("Rename")).clicked()
+                            || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && name_valid)
+                        {
+                            should_rename = true;
+                        }
+                    });
+                    ui.add_space(8.0);
+                });
+            });
+
+        if should_close {
+            self.rename_folder_popup = None;
+        } else if should_rename {
+            let (old_name, new_name) = self.rename_folder_popup.take().unwrap();
+            let new_name = new_name.trim().to_string();
+            
+            // Rename in active profile only
+            if let Some(profile) = self.state.mod_data.profiles.get_mut(&active_profile) {
+                // Move the group data to new key and update references at any depth
+                profile.rename_folder(&old_name, &new_name);
+            }
+            
+            self.state.mod_data.save().unwrap();
+        }
+    }
+
+    fn show_lints_toggle(&mut self, ctx: &egui::Context) {
+        if let Some(_lints_toggle) = &self.lints_toggle_window {
+            let mut open = true;
+            let mut lint = None;
+
+            egui::Window::new("Toggle lints")
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        egui::Grid::new("lints-toggle-grid").show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.heading("Lint");
+                                help::button(ui, HelpTopic::Lints);
+                            });
+                            ui.heading("Enabled?");
+                            ui.end_row();
+
+                            ui.label("Archive with multiple paks");
+                            ui.add(toggle_switch(
+                                &mut self.lint_options.archive_with_multiple_paks,
+                            ));
+                            ui.end_row();
+
+                            ui.label("Archive with only non-pak files");
+                            ui.add(toggle_switch(
+                                &mut self.lint_options.archive_with_only_non_pak_files,
+                            ));
+                            ui.end_row();
+
+                            ui.label("Mods containing AssetRegister.bin");
+                            ui.add(toggle_switch(&mut self.lint_options.asset_register_bin));
+                            ui.end_row();
+
+                            ui.label("Mods containing conflicting files");
+                            ui.add(toggle_switch(&mut self.lint_options.conflicting));
+                            ui.end_row();
+
+                            ui.label("Mods containing empty archives");
+                            ui.add(toggle_switch(&mut self.lint_options.empty_archive));
+                            ui.end_row();
+
+                            ui.label("Mods containing oudated pak version");
+                            ui.add(toggle_switch(&mut self.lint_options.outdated_pak_version));
+                            ui.end_row();
+
+                            ui.label("Mods containing shader files");
+                            ui.add(toggle_switch(&mut self.lint_options.shader_files));
+                            ui.end_row();
+
+                            ui.label("Mods containing non-asset files");
+                            ui.add(toggle_switch(&mut self.lint_options.non_asset_files));
+                            ui.end_row();
+
+                            ui.label("Mods containing split {uexp, uasset} pairs");
+                            ui.add(toggle_switch(&mut self.lint_options.split_asset_pairs));
+                            ui.end_row();
+
+                            ui.label("Mods containing unmodified game assets");
+                            ui.add_enabled(
+                                self.state.config.drg_pak_path.is_some(),
+                                toggle_switch(&mut self.lint_options.unmodified_game_assets),
+                            )
+                            .on_disabled_hover_text(
+                                "This lint requires DRG pak path to be specified",
+                            );
+                            ui.end_row();
+
+                            ui.label("Mods overriding game config or settings");
+                            ui.add(toggle_switch(&mut self.lint_options.config_overrides));
+                            ui.end_row();
+
+                            ui.label("Mods using unsupported compression");
+                            ui.add(toggle_switch(
+                                &mut self.lint_options.unsupported_compression,
+                            ));
+                            ui.end_row();
+
+                            ui.label("Mods with files outside the game's directories");
+                            ui.add(toggle_switch(&mut self.lint_options.mount_point));
+                            ui.end_row();
+
+                            ui.label("Mods changing the same localization files");
+                            ui.add(toggle_switch(&mut self.lint_options.localization_conflicts));
+                            ui.end_row();
+                        });
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            self.lints_toggle_window = None;
+                        }
+
+                        if ui
+                            .add_enabled(
+                                self.check_updates_rid.is_none()
+                                    && self.integrate_rid.is_none()
+                                    && self.lint_rid.is_none(),
+                                egui::Button::new("Generate report"),
+                            )
+                            .clicked()
+                        {
+                            let lint_options = BTreeMap::from([
+                                (
+                                    LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
+                                    self.lint_options.archive_with_multiple_paks,
+                                ),
+                                (
+                                    LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
+                                    self.lint_options.archive_with_only_non_pak_files,
+                                ),
+                                (
+                                    LintId::ASSET_REGISTRY_BIN,
+                                    self.lint_options.asset_register_bin,
+                                ),
+                                (LintId::CONFLICTING, self.lint_options.conflicting),
+                                (LintId::EMPTY_ARCHIVE, self.lint_options.empty_archive),
+                                (
+                                    LintId::OUTDATED_PAK_VERSION,
+                                    self.lint_options.outdated_pak_version,
+                                ),
+                                (LintId::SHADER_FILES, self.lint_options.shader_files),
+                                (LintId::NON_ASSET_FILES, self.lint_options.non_asset_files),
+                                (
+                                    LintId::SPLIT_ASSET_PAIRS,
+                                    self.lint_options.split_asset_pairs,
+                                ),
+                                (
+                                    LintId::UNMODIFIED_GAME_ASSETS,
+                                    self.lint_options.unmodified_game_assets,
+                                ),
+                                (LintId::CONFIG_OVERRIDES, self.lint_options.config_overrides),
+                                (
+                                    LintId::UNSUPPORTED_COMPRESSION,
+                                    self.lint_options.unsupported_compression,
+                                ),
+                                (LintId::MOUNT_POINT, self.lint_options.mount_point),
+                                (
+                                    LintId::LOCALIZATION_CONFLICTS,
+                                    self.lint_options.localization_conflicts,
+                                ),
+                            ]);
+
+                            trace!(?lint_options);
+
+                            let mut mods = Vec::new();
+                            self.state.mod_data.for_each_enabled_mod(
+                                &self.state.mod_data.active_profile,
+                                |mc| {
+                                    mods.push(mc.spec.clone());
+                                },
+                            );
+
+                            lint = Some(message::Operation::LintMods {
+                                mods,
+                                enabled_lints: BTreeSet::from_iter(
+                                    lint_options
+                                        .into_iter()
+                                        .filter_map(|(lint, enabled)| enabled.then_some(lint)),
+                                ),
+                                game_pak_path: self.state.config.drg_pak_path.clone(),
+                            });
+                        }
+                    });
+                });
+
+            if let Some(lint) = lint {
+                lint.dispatch(self, ctx);
+            }
+            if !open {
+                self.lints_toggle_window = None;
+            }
+        }
+    }
+
+    fn show_lint_report(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.lint_report_window else {
+            return;
+        };
+        let mut open = true;
+        // asset and its new winner, `None` to go by priority again
+        let mut set_override: Option<(String, Option<String>)> = None;
+        let mut go_to_mod: Option<ModSpecification> = None;
+        let mut suggest_priorities = false;
+
+        egui::Window::new("Lint results")
+            .open(&mut open)
+            .resizable(true)
+            .default_size([720.0, 400.0])
+            .show(ctx, |ui| {
+                let Some(report) = &self.lint_report else {
+                    match self.lint_rid.as_ref().and_then(|r| r.state.as_ref()) {
+                        Some(LintProgress {
+                            done,
+                            total,
+                            mod_spec,
+                        }) => {
+                            ui.add(
+                                egui::ProgressBar::new(*done as f32 / (*total).max(1) as f32)
+                                    .show_percentage(),
+                            );
+                            ui.label(format!("{done} of {total} mods analyzed"));
+                            let name = self
+                                .state
+                                .store
+                                .get_mod_info(mod_spec)
+                                .map(|info| info.name)
+                                .unwrap_or_else(|| mod_spec.url.clone());
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label(format!("Analyzing {name}"));
+                            });
+                        }
+                        None => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label("Lint report generating...");
+                            });
+                        }
+                    }
+                    return;
+                };
+                let counts = report.problem_counts();
+                let found = counts
+                    .iter()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<_>>();
+                let Some(first) = found.first() else {
+                    ui.colored_label(Color32::LIGHT_GREEN, "No problems found");
+                    return;
+                };
+                let selected = window
+                    .selected
+                    .filter(|id| found.contains(id))
+                    .unwrap_or(*first);
+
+                let mod_name = |spec: &ModSpecification| {
+                    self.state
+                        .store
+                        .get_mod_info(spec)
+                        .map(|info| info.name)
+                        .unwrap_or_else(|| spec.url.clone())
+                };
+                let height = (ui.available_height() - 8.0).max(0.0);
+
+                ui.horizontal_top(|ui| {
+                    // issue categories
+                    ui.vertical(|ui| {
+                        ui.set_width(240.0);
+                        egui::ScrollArea::vertical()
+                            .id_salt("lint-categories")
+                            .max_height(height)
+                            .show(ui, |ui| {
+                                for id in &found {
+                                    let (title, color) = lint_category(*id);
+                                    if ui
+                                        .selectable_label(
+                                            *id == selected,
+                                            RichText::new(format!("{title} ({})", counts[id]))
+                                                .color(color),
+                                        )
+                                        .clicked()
+                                    {
+                                        window.selected = Some(*id);
+                                    }
+                                }
+                            });
+                    });
+                    ui.separator();
+
+                    // affected mods and files
+                    ui.vertical(|ui| {
+                        ui.label(lint_description(selected));
+                        if ui
+                            .button("What does this mean / how to fix?")
+                            .on_hover_text("Open the help page of this lint")
+                            .clicked()
+                        {
+                            help::open(ui.ctx(), HelpTopic::Lint(selected));
+                        }
+                        ui.add_space(4.0);
+                        egui::ScrollArea::vertical()
+                            .id_salt("lint-findings")
+                            .max_height(height)
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                let row_actions =
+                                    |ui: &mut Ui,
+                                     go_to_mod: &mut Option<ModSpecification>,
+                                     spec: &ModSpecification,
+                                     path: &str| {
+                                        if ui
+                                            .small_button("🔍")
+                                            .on_hover_text("Go to mod in list")
+                                            .clicked()
+                                        {
+                                            *go_to_mod = Some(spec.clone());
+                                        }
+                                        if ui
+                                            .small_button("📋")
+                                            .on_hover_text("Copy path")
+                                            .clicked()
+                                        {
+                                            ui.ctx().copy_text(path.to_string());
+                                        }
+                                    };
+
+                                if selected == LintId::CONFLICTING {
+                                    // the files of an asset, e.g. .uasset and .uexp, share a winner
+                                    let mut conflicting_assets =
+                                        BTreeMap::<String, Vec<ModSpecification>>::new();
+                                    for finding in report.findings(selected) {
+                                        let path = finding.path.as_deref().unwrap_or_default();
+                                        let asset_mods =
+                                            conflicting_assets.entry(asset_key(path)).or_default();
+                                        if !asset_mods.contains(&finding.mod_spec) {
+                                            asset_mods.push(finding.mod_spec);
+                                        }
+                                    }
+                                    let overrides =
+                                        &self.state.mod_data.get_active_profile().asset_overrides;
+                                    if ui
+                                        .button("Suggest priorities")
+                                        .on_hover_text(
+                                            "Suggest priorities so the winner of each asset does \
+                                             not depend on the order of the mods in the list",
+                                        )
+                                        .clicked()
+                                    {
+                                        suggest_priorities = true;
+                                    }
+                                    for (asset, mods) in &conflicting_assets {
+                                        let winner = overrides.get(asset);
+                                        ui.horizontal(|ui| {
+                                            ui.label(RichText::new(asset).monospace().color(AMBER));
+                                            if ui
+                                                .small_button("📋")
+                                                .on_hover_text("Copy path")
+                                                .clicked()
+                                            {
+                                                ui.ctx().copy_text(asset.clone());
+                                            }
+                                        });
+                                        ui.indent(asset, |ui| {
+                                            if ui.radio(winner.is_none(), "By priority").clicked() {
+                                                set_override = Some((asset.clone(), None));
+                                            }
+                                            for spec in mods {
+                                                ui.horizontal(|ui| {
+                                                    if ui
+                                                        .radio(
+                                                            winner == Some(&spec.url),
+                                                            mod_name(spec),
+                                                        )
+                                                        .on_hover_text(&spec.url)
+                                                        .clicked()
+                                                    {
+                                                        set_override = Some((
+                                                            asset.clone(),
+                                                            Some(spec.url.clone()),
+                                                        ));
+                                                    }
+                                                    row_actions(
+                                                        ui,
+                                                        &mut go_to_mod,
+                                                        spec,
+                                                        &spec.url,
+                                                    );
+                                                });
+                                            }
+                                        });
+                                    }
+                                } else {
+                                    egui::Grid::new("lint-findings-grid")
+                                        .num_columns(3)
+                                        .striped(true)
+                                        .show(ui, |ui| {
+                                            for finding in report.findings(selected) {
+                                                if ui
+                                                    .link(mod_name(&finding.mod_spec))
+                                                    .on_hover_text(&finding.mod_spec.url)
+                                                    .clicked()
+                                                {
+                                                    go_to_mod = Some(finding.mod_spec.clone());
+                                                }
+                                                ui.horizontal(|ui| {
+                                                    if let Some(path) = &finding.path {
+                                                        ui.label(RichText::new(path).monospace());
+                                                    }
+                                                    if let Some(detail) = &finding.detail {
+                                                        ui.label(detail);
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    row_actions(
+                                                        ui,
+                                                        &mut go_to_mod,
+                                                        &finding.mod_spec,
+                                                        finding
+                                                            .path
+                                                            .as_deref()
+                                                            .unwrap_or(&finding.mod_spec.url),
+                                                    );
+                                                });
+                                                ui.end_row();
+                                            }
+                                        });
+                                }
+                            });
+                    });
+                });
+            });
+
+        if let Some((asset, winner)) = set_override {
+            let overrides = &mut self.state.mod_data.get_active_profile_mut().asset_overrides;
+            match winner {
+                Some(url) => overrides.insert(asset, url),
+                None => overrides.remove(&asset),
+            };
+            self.state.mod_data.save().unwrap();
+        }
+        if let Some(spec) = go_to_mod {
+            self.focus_mod(spec.url);
+        }
+        if suggest_priorities && let Some(report) = &self.lint_report {
+            let profile = self.state.mod_data.active_profile.clone();
+            let overrides = &self.state.mod_data.get_active_profile().asset_overrides;
+            // assets with a chosen winner are resolved already
+            let conflicts = report
+                .conflicting_mods
+                .iter()
+                .flatten()
+                .filter(|(path, _)| !overrides.contains_key(&asset_key(path)))
+                .map(|(_, mods)| mods);
+            let changes = self.state.mod_data.suggest_priorities(&profile, conflicts);
+            self.record_feature("suggest_priorities");
+            self.suggest_priorities_window = Some(WindowSuggestPriorities {
+                profile,
+                changes: changes.into_iter().map(|c| (c, true)).collect(),
+            });
+        }
+        if !open {
+            self.lint_report_window = None;
+            self.lint_rid = None;
+        }
+    }
+
+    fn show_suggest_priorities(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.suggest_priorities_window else {
+            return;
+        };
+
+        let store = &self.state.store;
+        let mut apply = false;
+        let mut open = true;
+        egui::Window::new("Suggested priorities")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if window.changes.is_empty() {
+                    ui.label(
+                        "Each conflicting asset is already won by the mod with the highest \
+                         priority.",
+                    );
+                    return;
+                }
+                ui.label(
+                    "With these priorities the winner of each conflicting asset no longer depends \
+                     on the order of the mods in the list. Of mods sharing a priority, the one \
+                     changing fewer of the assets wins.",
+                );
+                egui::Grid::new("suggested-priorities")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (change, accepted) in &mut window.changes {
+                            let name = store
+                                .get_mod_info(&change.spec)
+                                .map_or_else(|| change.spec.url.clone(), |info| info.name);
+                            ui.checkbox(accepted, name).on_hover_text(&change.spec.url);
+                            ui.label(format!("{} → {}", change.from, change.to));
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            window.changes.iter().any(|(_, accepted)| *accepted),
+                            egui::Button::new("Apply selected"),
+                        )
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        open = false;
+                    }
+                });
+            });
+
+        if apply
+            && let Some(window) = self.suggest_priorities_window.take()
+            && self.state.mod_data.profiles.contains_key(&window.profile)
+        {
+            for (change, _) in window.changes.iter().filter(|(_, accepted)| *accepted) {
+                self.state.mod_data.any_mod_mut(&window.profile, |mc, _| {
+                    if mc.spec != change.spec {
+                        return false;
+                    }
+                    mc.priority = change.to;
+                    true
+                });
+            }
+            self.state.mod_data.save().unwrap();
+        }
+        if !open {
+            self.suggest_priorities_window = None;
+        }
+    }
+
+    fn show_preview(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.preview_window else {
+            return;
+        };
+        let mut open = true;
+
+        egui::Window::new(format!("Preview: {}", window.name))
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if window.preview.is_empty() {
+                    ui.label("This mod does not contain any previewable files.");
+                    return;
+                }
+                let mut play = None;
+                let mut stop = false;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    if !window.preview.audio.is_empty() {
+                        CollapsingHeader::new(format!(
+                            "🔊 Sounds ({})",
+                            window.preview.audio.len()
+                        ))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if window.player.is_some() && ui.button("⏹ Stop").clicked() {
+                                stop = true;
+                            }
+                            for (i, file) in window.preview.audio.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui.button("▶").on_hover_text_at_pointer("Play").clicked() {
+                                        play = Some(i);
+                                    }
+                                    ui.label(&file.path);
+                                });
+                            }
+                        });
+                    }
+
+                    if !window.preview.images.is_empty() {
+                        CollapsingHeader::new(format!(
+                            "🖼 Images ({})",
+                            window.preview.images.len()
+                        ))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for (i, file) in window.preview.images.iter().enumerate() {
+                                ui.label(&file.path);
+                                let texture = window.images.entry(i).or_insert_with(|| {
+                                    let image = image::load_from_memory(&file.data).ok()?;
+                                    let size = [image.width() as _, image.height() as _];
+                                    let image_buffer = image.to_rgba8();
+                                    let pixels = image_buffer.as_flat_samples();
+                                    let image = egui::ColorImage::from_rgba_unmultiplied(
+                                        size,
+                                        pixels.as_slice(),
+                                    );
+                                    Some(ui.ctx().load_texture(
+                                        format!("preview-{}", file.path),
+                                        image,
+                                        Default::default(),
+                                    ))
+                                });
+                                match texture {
+                                    Some(texture) => {
+                                        ui.add(egui::Image::new(&*texture).max_width(384.0));
+                                    }
+                                    None => {
+                                        ui.colored_label(
+                                            ui.visuals().warn_fg_color,
+                                            "unsupported image format",
+                                        );
+                                    }
+                                }
+                            }
+                        });
+                    }
+
+                    if !window.preview.textures.is_empty() {
+                        CollapsingHeader::new(format!(
+                            "🎨 Textures ({})",
+                            window.preview.textures.len()
+                        ))
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            for (i, texture) in window.preview.textures.iter().enumerate() {
+                                ui.label(&texture.path);
+                                match &texture.image {
+                                    Ok(image) => {
+                                        let handle =
+                                            window.textures.entry(i).or_insert_with(|| {
+                                                let pixels =
+                                                    egui::ColorImage::from_rgba_unmultiplied(
+                                                        [image.width as _, image.height as _],
+                                                        &image.rgba,
+                                                    );
+                                                ui.ctx().load_texture(
+                                                    format!("preview-{}", texture.path),
+                                                    pixels,
+                                                    Default::default(),
+                                                )
+                                            });
+                                        ui.add(egui::Image::new(&*handle).max_width(256.0));
+                                    }
+                                    Err(e) => {
+                                        ui.colored_label(ui.visuals().warn_fg_color, e);
+                                    }
+                                }
+                            }
+                        });
+                    }
+                });
+                if let Some(i) = play {
+                    let file = window.preview.audio[i].clone();
+                    if let Err(e) = window.play(&file) {
+                        window.error = Some(e.to_string());
+                    }
+                }
+                if stop {
+                    window.stop();
+                }
+                if let Some(error) = &window.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+
+        if !open {
+            self.preview_window = None;
+        }
+    }
+
+    fn show_health_check(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.health_check_window else {
+            return;
+        };
+
+        let mut open = true;
+        let mut run_again = false;
+        egui::Window::new("Health check")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if window.rid.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Running checks...");
+                    });
+                    return;
+                }
+
+                egui::Grid::new("health-check")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for result in &window.results {
+                            let (icon, color) = match result.status {
+                                CheckStatus::Pass => ("✔", Color32::LIGHT_GREEN),
+                                CheckStatus::Warn => ("⚠", ui.visuals().warn_fg_color),
+                                CheckStatus::Fail => ("✖", ui.visuals().error_fg_color),
+                                CheckStatus::Skipped => ("–", ui.visuals().weak_text_color()),
+                            };
+                            ui.colored_label(color, icon);
+                            ui.strong(result.name);
+                            ui.vertical(|ui| {
+                                ui.label(&result.detail);
+                                if let Some(fix) = result.fix {
+                                    ui.label(RichText::new(fix).italics());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Run again").clicked() {
+                        run_again = true;
+                    }
+                    if ui
+                        .button("Copy report")
+                        .on_hover_text(
+                            "Copy the results as text, e.g. to paste into a support chat",
+                        )
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(window.report());
+                    }
+                });
+            });
+
+        if run_again {
+            message::HealthCheck::send(self, ctx);
+        } else if !open {
+            self.health_check_window = None;
+        }
+    }
+
+    fn show_download_estimate(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.download_estimate_window else {
+            return;
+        };
+
+        let mut open = true;
+        let mut start = None;
+        egui::Window::new("Download size")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                let estimate = match &window.result {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Checking which mods need downloading...");
+                        });
+                        return;
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                        return;
+                    }
+                    Some(Ok(estimate)) => estimate,
+                };
+
+                ui.label(format!(
+                    "{} mods already cached, {} to download",
+                    estimate.cached,
+                    estimate.to_download()
+                ));
+                if estimate.to_download() > 0 {
+                    egui::Grid::new("download-estimate")
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for (provider, download) in &estimate.providers {
+                                ui.strong(*provider);
+                                ui.label(format!("{} mods", download.mods));
+                                ui.label(download_size_text(
+                                    download.bytes,
+                                    download.unknown_size,
+                                ));
+                                ui.end_row();
+                            }
+                        });
+                    ui.label(format!(
+                        "Total: {}",
+                        download_size_text(estimate.bytes(), estimate.unknown_size())
+                    ));
+                }
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Install").clicked() {
+                        start = Some(false);
+                    }
+                    if ui
+                        .button("Download only")
+                        .on_hover_text("Download the mods into the cache without installing them, to install later without a connection")
+                        .clicked()
+                    {
+                        start = Some(true);
+                    }
+                });
+            });
+
+        if let Some(download_only) = start {
+            self.download_estimate_window = None;
+            self.integrate_mods(ctx, download_only);
+        } else if !open {
+            self.download_estimate_window = None;
+            // don't launch a startup install which was cancelled later
+            self.startup.launch = false;
+        }
+    }
+
+    fn show_integration_warnings(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.integration_warnings_window else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Installed with warnings")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.label("The mods were installed, but some things did not go as intended:");
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("integration-warnings")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for warning in &window.warnings {
+                                    ui.label(match warning.kind {
+                                        WarningKind::SkippedFile => "Skipped",
+                                        WarningKind::Fallback => "Fallback",
+                                        WarningKind::Deprecated => "Deprecated",
+                                        WarningKind::Untrusted => "Untrusted",
+                                    });
+                                    ui.strong(warning.mod_name.as_deref().unwrap_or("(all mods)"));
+                                    ui.label(&warning.message);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.add_space(4.0);
+                if ui.button("Copy").clicked() {
+                    ui.ctx().copy_text(
+                        window
+                            .warnings
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    );
+                }
+            });
+
+        if !open {
+            self.integration_warnings_window = None;
+        }
+    }
+
+    fn show_lobby(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.lobby_window else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new("Lobby report")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let installed = match &window.installed {
+                    Ok(report) => report,
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e);
+                        return;
+                    }
+                };
+                ui.label(
+                    "Players in a lobby can check that they installed exactly the same mods as the \
+                     host by comparing codes, or with the host's report file.",
+                );
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Your {} installed mods:", installed.mods.len()));
+                    ui.strong(RichText::new(installed.code()).monospace());
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(installed.code());
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Sign with key:")
+                        .on_hover_cursor(egui::CursorIcon::Help)
+                        .on_hover_text("Secret key file created by `mint sign --generate-key`, so players can tell the report really comes from you.\nLeave empty to not sign.");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut window.key_path)
+                            .hint_text("optional")
+                            .desired_width(200.0),
+                    );
+                    if ui.button("browse").clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_file()
+                    {
+                        window.key_path = path.to_string_lossy().to_string();
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Save report...").clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Lobby report", &["json"])
+                            .set_file_name("lobby_report.json")
+                            .save_file()
+                    {
+                        window.status = Some(match WindowLobby::save(
+                            installed,
+                            &window.key_path,
+                            &path,
+                        ) {
+                            Ok(()) => (true, format!("Report saved: {}", path.display())),
+                            Err(e) => (false, format!("Failed to save report: {e}")),
+                        });
+                    }
+                    if ui.button("Check host's report...").clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Lobby report", &["json"])
+                            .pick_file()
+                    {
+                        match LobbyReport::read(&path) {
+                            Ok(report) => {
+                                let signature =
+                                    report.signature_status(&self.state.config.trusted_signers);
+                                let differences = report.compare(&installed.mods);
+                                window.status = None;
+                                window.checked = Some((report, signature, differences));
+                            }
+                            Err(e) => {
+                                window.status = Some((false, format!("Failed to read report: {e}")))
+                            }
+                        }
+                    }
+                });
+                if let Some((success, msg)) = &window.status {
+                    if *success {
+                        ui.colored_label(Color32::LIGHT_GREEN, msg);
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, msg);
+                    }
+                }
+
+                if let Some((report, signature, differences)) = &window.checked {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Host's {} installed mods:", report.mods.len()));
+                        ui.strong(RichText::new(report.code()).monospace());
+                    });
+                    match signature {
+                        SignatureStatus::Trusted { signer } => {
+                            ui.colored_label(Color32::LIGHT_GREEN, format!("✔ Signed by {signer}"));
+                        }
+                        SignatureStatus::Mismatch => {
+                            ui.colored_label(
+                                ui.visuals().error_fg_color,
+                                "✖ The report was changed after it was made, don't rely on it",
+                            );
+                        }
+                        status => {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                format!("⚠ The report {}", status.problem().unwrap_or_default()),
+                            );
+                        }
+                    }
+                    if differences.is_empty() {
+                        ui.colored_label(Color32::LIGHT_GREEN, "✔ Your mods match the host's");
+                    } else {
+                        for difference in differences {
+                            ui.label(format!("• {difference}"));
+                        }
+                    }
+                }
+            });
+
+        if !open {
+            self.lobby_window = None;
+        }
+    }
+
+    fn show_archive(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.archive_window else {
+            return;
+        };
+
+        let mod_data = &mut self.state.mod_data;
+        let active = mod_data.active_profile.clone();
+        let mut modified = false;
+        let mut open = true;
+        egui::Window::new("Seasons, archived and deleted profiles")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Season of {active:?}:"));
+                    let season = ui.add(
+                        egui::TextEdit::singleline(&mut window.season)
+                            .hint_text("e.g. Season 5")
+                            .desired_width(120.0),
+                    );
+                    if (ui.button("Set").clicked() || is_committed(&season))
+                        && let Some(profile) = mod_data.profiles.get_mut(&active)
+                    {
+                        let season = window.season.trim();
+                        profile.season = (!season.is_empty()).then(|| season.to_string());
+                        modified = true;
+                    }
+                });
+                if ui
+                    .button("Archive this profile")
+                    .on_hover_text("Hide it from the profile list until it is restored")
+                    .clicked()
+                {
+                    modified |= mod_data.set_archived(&active, true);
+                }
+
+                let seasons = mod_data.seasons();
+                if !seasons.is_empty() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label("Archive all profiles of");
+                        egui::ComboBox::from_id_salt("archive-season")
+                            .selected_text(window.archive_season.as_deref().unwrap_or("(season)"))
+                            .show_ui(ui, |ui| {
+                                for season in &seasons {
+                                    ui.selectable_value(
+                                        &mut window.archive_season,
+                                        Some(season.clone()),
+                                        season,
+                                    );
+                                }
+                            });
+                        if ui
+                            .add_enabled(
+                                window.archive_season.is_some(),
+                                egui::Button::new("Archive"),
+                            )
+                            .clicked()
+                            && let Some(season) = &window.archive_season
+                        {
+                            modified |= mod_data.archive_season(season) > 0;
+                        }
+                    });
+                }
+
+                ui.separator();
+                let archived = mod_data
+                    .profiles
+                    .iter()
+                    .filter(|(_, p)| p.archived)
+                    .map(|(name, p)| (name.clone(), p.season.clone()))
+                    .collect::<Vec<_>>();
+                if archived.is_empty() {
+                    ui.label("No archived profiles");
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("archived-profiles")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (name, season) in archived {
+                                    ui.label(&name);
+                                    ui.label(season.unwrap_or_default());
+                                    if ui.button("Restore").clicked() {
+                                        modified |= mod_data.set_archived(&name, false);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                if !mod_data.trash.is_empty() {
+                    ui.separator();
+                    ui.label(format!(
+                        "Deleted profiles, kept for {} days:",
+                        crate::state::TRASH_DAYS
+                    ));
+                    let mut restore = None;
+                    egui::ScrollArea::vertical()
+                        .id_salt("trash")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("trashed-profiles")
+                                .num_columns(3)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (i, trashed) in mod_data.trash.iter().enumerate().rev() {
+                                        ui.label(&trashed.name);
+                                        ui.label(
+                                            chrono::DateTime::parse_from_rfc3339(&trashed.deleted)
+                                                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                                                .unwrap_or_else(|_| trashed.deleted.clone()),
+                                        );
+                                        if ui.button("Restore").clicked() {
+                                            restore = Some(i);
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    if let Some(i) = restore {
+                        modified |= mod_data.restore_trashed(i).is_some();
+                    }
+                }
+            });
+
+        if modified {
+            mod_data.save().unwrap();
+        }
+        if !open {
+            self.archive_window = None;
+        }
+    }
+
+    fn show_profile_order(&mut self, ctx: &egui::Context) {
+        if self.profile_order_window.is_none() {
+            return;
+        }
+
+        let mod_data = &mut self.state.mod_data;
+        let mut order = mod_data.ordered_profiles();
+        let mut modified = false;
+        let mut open = true;
+        egui::Window::new("Profile order")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("Drag profiles to order them. Favorites (★) are always listed first.");
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        let res = egui_dnd::dnd(ui, "profile-order")
+                            .with_mouse_config(egui_dnd::DragDropConfig::mouse())
+                            .show(order.iter(), |ui, name, handle, state| {
+                                let mut frame = egui::Frame::NONE;
+                                if state.dragged {
+                                    frame.fill = ui.visuals().extreme_bg_color
+                                } else if state.index % 2 == 1 {
+                                    frame.fill = ui.visuals().faint_bg_color
+                                }
+                                frame.show(ui, |ui| {
+                                    ui.horizontal(|ui| {
+                                        handle.ui(ui, |ui| {
+                                            ui.label("   ☰  ");
+                                        });
+                                        let Some(profile) = mod_data.profiles.get_mut(name) else {
+                                            return;
+                                        };
+                                        let star = if profile.favorite { "★" } else { "☆" };
+                                        if ui
+                                            .selectable_label(profile.favorite, star)
+                                            .on_hover_text("Favorite")
+                                            .clicked()
+                                        {
+                                            profile.favorite = !profile.favorite;
+                                            modified = true;
+                                        }
+                                        if profile.archived {
+                                            ui.weak(format!("{name} (archived)"));
+                                        } else {
+                                            ui.label(name);
+                                        }
+                                    });
+                                });
+                            });
+                        if res.final_update().is_some() {
+                            res.update_vec(&mut order);
+                            modified = true;
+                        }
+                    });
+            });
+
+        if modified {
+            mod_data.profile_order = order;
+            mod_data.save().unwrap();
+        }
+        if !open {
+            self.profile_order_window = None;
+        }
+    }
+
+    fn show_profile_info(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.profile_info_window else {
+            return;
+        };
+
+        let mut save = false;
+        let mut open = true;
+        egui::Window::new(format!("About {:?}", window.profile))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("profile-info")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Icon:");
+                        ui.add(
+                            egui::TextEdit::singleline(&mut window.icon)
+                                .hint_text("e.g. 🔥")
+                                .char_limit(8)
+                                .desired_width(40.0),
+                        );
+                        ui.end_row();
+
+                        ui.label("Description:");
+                        ui.add(
+                            egui::TextEdit::multiline(&mut window.description)
+                                .hint_text("What the profile is for")
+                                .desired_rows(3),
+                        );
+                        ui.end_row();
+                    });
+                save = ui.button("Save").clicked();
+            });
+
+        if save {
+            let some = |s: &str| {
+                let s = s.trim();
+                (!s.is_empty()).then(|| s.to_string())
+            };
+            if let Some(profile) = self.state.mod_data.profiles.get_mut(&window.profile) {
+                profile.icon = some(&window.icon);
+                profile.description = some(&window.description);
+                self.state.mod_data.save().unwrap();
+            }
+            open = false;
+        }
+        if !open {
+            self.profile_info_window = None;
+        }
+    }
+
+    fn show_profile_readme(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.profile_readme_window else {
+            return;
+        };
+
+        let mut save = false;
+        let mut open = true;
+        egui::Window::new(format!("Readme of {:?}", window.profile))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut window.preview, false, "Edit");
+                    ui.selectable_value(&mut window.preview, true, "Preview");
+                });
+                egui::ScrollArea::vertical()
+                    .id_salt("profile-readme-edit")
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        if window.preview {
+                            CommonMarkViewer::new().show(ui, &mut self.cache, &window.text);
+                        } else {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut window.text)
+                                    .hint_text("Install notes, in Markdown")
+                                    .code_editor()
+                                    .desired_rows(16)
+                                    .desired_width(480.0),
+                            );
+                        }
+                    });
+                save = ui.button("Save").clicked();
+            });
+
+        if save {
+            if let Some(profile) = self.state.mod_data.profiles.get_mut(&window.profile) {
+                let text = window.text.trim();
+                profile.readme = (!text.is_empty()).then(|| text.to_string());
+                self.state.mod_data.save().unwrap();
+            }
+            open = false;
+        }
+        if !open {
+            self.profile_readme_window = None;
+        }
+    }
+
+    fn show_folder_dependencies(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.folder_dependencies_window else {
+            return;
+        };
+
+        let name = |spec: &ModSpecification| {
+            self.state
+                .store
+                .get_mod_info(spec)
+                .map_or_else(|| spec.url.clone(), |info| info.name)
+        };
+        let mut undo = false;
+        let mut add_dependencies = false;
+        let mut open = true;
+        egui::Window::new("Dependencies")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if window.enabled {
+                    ui.label(format!(
+                        "Mods in {:?} depend on mods which are not enabled:",
+                        window.folder
+                    ));
+                } else {
+                    ui.label(format!(
+                        "Mods outside {:?} depend on mods in it, which are now disabled:",
+                        window.folder
+                    ));
+                }
+                egui::Grid::new("folder-broken-dependencies")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for broken in &window.broken {
+                            ui.label(name(&broken.dependent));
+                            ui.label(format!("needs {}", name(&broken.dependency)));
+                            ui.end_row();
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button("Keep").clicked() {
+                        open = false;
+                    }
+                    let action = if window.enabled {
+                        "Disable the folder again"
+                    } else {
+                        "Enable the folder again"
+                    };
+                    if ui.button(action).clicked() {
+                        undo = true;
+                    }
+                    if window.enabled && ui.button("Add missing dependencies").clicked() {
+                        add_dependencies = true;
+                    }
+                });
+            });
+
+        if undo && let Some(profile) = self.state.mod_data.profiles.get_mut(&window.profile) {
+            profile.set_folder_enabled(&window.folder, !window.enabled);
+            self.state.mod_data.save().unwrap();
+        }
+        if add_dependencies {
+            let deps = window.broken.iter().map(|b| b.dependency.clone()).collect();
+            message::ResolveMods::send(self, ctx, deps, true);
+            self.problematic_mod_id = None;
+        }
+        if undo || add_dependencies || !open {
+            self.folder_dependencies_window = None;
+        }
+    }
+
+    fn show_dependency_graph(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.dependency_graph_window else {
+            return;
+        };
+
+        let profile = self.state.mod_data.active_profile.clone();
+        let store = &self.state.store;
+        let mods = self.state.mod_data.mod_states(&profile);
+        let cached = match window.cached.take() {
+            Some((p, m, cached)) if p == profile && m == mods => (p, m, cached),
+            _ => {
+                let graph = DependencyGraph::build(mods.clone(), |spec| {
+                    store
+                        .get_mod_info(spec)
+                        .map(|info| info.suggested_dependencies)
+                        .unwrap_or_default()
+                });
+                (profile.clone(), mods, CachedDependencyGraph::new(graph))
+            }
+        };
+        let CachedDependencyGraph {
+            graph,
+            roots,
+            incomplete,
+        } = &window.cached.insert(cached).2;
+        let name = |spec: &ModSpecification| {
+            store
+                .get_mod_info(spec)
+                .map_or_else(|| spec.url.clone(), |info| info.name)
+        };
+        let mut enable_chain = None;
+        let mut open = true;
+        egui::Window::new(format!("Dependencies of {profile:?}"))
+            .open(&mut open)
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                if roots.is_empty() {
+                    ui.label("No enabled mod depends on another mod.");
+                    return;
+                }
+                let count = |status| graph.nodes.iter().filter(|n| n.status == status).count();
+                let missing = count(NodeStatus::Missing);
+                if missing > 0 {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{missing} dependencies are not in the profile"),
+                    );
+                }
+                let disabled = count(NodeStatus::Disabled);
+                if disabled > 0 {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("{disabled} dependencies are disabled"),
+                    );
+                }
+                if graph.nodes.iter().any(|n| n.in_cycle) {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        "Some mods depend on each other in a circle (\u{21BB})",
+                    );
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(500.0)
+                    .show(ui, |ui| {
+                        for &root in roots {
+                            ui_dependency_node(
+                                ui,
+                                graph,
+                                incomplete,
+                                &name,
+                                root,
+                                &mut vec![],
+                                &mut enable_chain,
+                            );
+                        }
+                    });
+            });
+
+        if let Some(root) = enable_chain {
+            let mut disabled = HashSet::new();
+            let mut missing = vec![];
+            for node in graph.chain(root).into_iter().map(|i| &graph.nodes[i]) {
+                match node.status {
+                    NodeStatus::Enabled => {}
+                    NodeStatus::Disabled => {
+                        disabled.insert(node.spec.url.clone());
+                    }
+                    NodeStatus::Missing => missing.push(node.spec.clone()),
+                }
+            }
+            self.state.mod_data.enable_only(&profile, &disabled);
+            self.state.mod_data.save().unwrap();
+            if !missing.is_empty() {
+                message::ResolveMods::send(self, ctx, missing, true);
+            }
+        }
+        if !open {
+            self.dependency_graph_window = None;
+        }
+    }
+
+    fn show_pin_to_date(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.pin_to_date_window else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(format!("Pin {:?} to date", window.profile))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Pins every mod to the newest version released before the date, e.g. to \
+                     recreate a pack that worked before a game update. Mods without a version \
+                     history, such as local files, stay as they are.",
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Date:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut window.date)
+                            .hint_text("YYYY-MM-DD")
+                            .desired_width(100.0),
+                    );
+                    let date = chrono::NaiveDate::parse_from_str(window.date.trim(), "%Y-%m-%d");
+                    if ui
+                        .add_enabled(date.is_ok(), egui::Button::new("Pin all mods"))
+                        .clicked()
+                        && let Ok(date) = date
+                    {
+                        let before = date.and_time(chrono::NaiveTime::MIN).and_utc().timestamp();
+                        let before = u64::try_from(before).unwrap_or_default();
+                        let pinned = self.state.pin_to_date(&window.profile, before);
+                        window.status = Some((pinned.too_new.is_empty(), pinned.to_string()));
+                    }
+                });
+                if let Some((success, msg)) = &window.status {
+                    if *success {
+                        ui.colored_label(Color32::LIGHT_GREEN, msg);
+                    } else {
+                        ui.colored_label(ui.visuals().warn_fg_color, msg);
+                    }
+                }
+            });
+
+        if !open {
+            self.pin_to_date_window = None;
+        }
+    }
+
+    fn show_qr(&mut self, ctx: &egui::Context) {
+        let Some(window) = &self.qr_window else {
+            return;
+        };
+
+        let mut open = true;
+        egui::Window::new(format!("Mods of {}", window.title))
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| match &window.code {
+                Ok(code) => {
+                    if let Some(description) = &window.description {
+                        ui.label(description);
+                    }
+                    ui.label(format!(
+                        "{} enabled mods. Scan with a phone, or take a screenshot and add it with 📷 \
+                         next to \"Add mod...\".",
+                        window.mods
+                    ));
+                    ui.add_space(4.0);
+                    qr::show(ui, code, 320.0);
+                }
+                Err(e) => {
+                    ui.colored_label(ui.visuals().error_fg_color, e);
+                }
+            });
+
+        if !open {
+            self.qr_window = None;
+        }
+    }
+
+    fn show_stats(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.stats_window else {
+            return;
+        };
+
+        let format_time = |time: &str| {
+            chrono::DateTime::parse_from_rfc3339(time)
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|_| time.to_string())
+        };
+        let mut open = true;
+        egui::Window::new("Play statistics")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let sessions = match &mut window.sessions {
+                    Ok(sessions) => sessions,
+                    Err(e) => {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            format!("Failed to read {}: {e}", sessions::SESSIONS_FILE),
+                        );
+                        ui.label(
+                            "No sessions are recorded until the file in mint's data directory is \
+                             fixed or removed.",
+                        );
+                        return;
+                    }
+                };
+                if sessions.sessions.is_empty() {
+                    ui.label("No games launched through mint yet");
+                    return;
+                }
+                ui.label(
+                    "Play time is only known for sessions mint started from Steam's launch \
+                     options (`mint launch %command%`).",
+                );
+                ui.add_space(4.0);
+                egui::Grid::new("stats-profiles")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Profile");
+                        ui.strong("Launches");
+                        ui.strong("Played");
+                        ui.strong("Last played");
+                        ui.end_row();
+                        for stats in sessions.by_profile() {
+                            ui.label(&stats.profile);
+                            ui.label(stats.launches.to_string());
+                            ui.label(sessions::format_duration(stats.played));
+                            ui.label(format_time(&stats.last_started));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.label("Recent sessions:");
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("stats-sessions")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for session in sessions.sessions.iter().rev() {
+                                    ui.label(format_time(&session.started));
+                                    ui.label(&session.profile);
+                                    ui.label(
+                                        session
+                                            .duration
+                                            .map(sessions::format_duration)
+                                            .unwrap_or_else(|| "unknown".to_string()),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.add_space(4.0);
+                if ui.button("Clear statistics").clicked() {
+                    *sessions = Sessions::default();
+                    sessions.save(&self.state.dirs.data_dir);
+                }
+            });
+
+        if !open {
+            self.stats_window = None;
+        }
+    }
+
+    fn show_analytics(&mut self, ctx: &egui::Context) {
+        let Some(window) = &mut self.analytics_window else {
+            return;
+        };
+
+        let ui_counts = |ui: &mut Ui, id: &str, header: &str, counts: &BTreeMap<String, u64>| {
+            egui::Grid::new(id)
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(header);
+                    ui.strong("Count");
+                    ui.end_row();
+                    for (name, count) in Analytics::most_frequent(counts) {
+                        ui.label(name);
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+        };
+        let mut open = true;
+        egui::Window::new("Usage analytics")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    "Counts of the features used and the errors that occurred, kept on this \
+                     computer only. Nothing is ever sent anywhere; export them to share with the \
+                     maintainers if you like.",
+                );
+                if ui
+                    .checkbox(&mut self.state.config.usage_analytics, "Record usage")
+                    .changed()
+                {
+                    self.state.config.save().unwrap();
+                }
+                ui.separator();
+                if self.analytics.is_empty() {
+                    ui.label("Nothing recorded yet");
+                    return;
+                }
+                if let Some(since) = &self.analytics.since
+                    && let Ok(since) = chrono::DateTime::parse_from_rfc3339(since)
+                {
+                    ui.label(format!("Recorded since {}", since.format("%Y-%m-%d")));
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.horizontal_top(|ui| {
+                            ui_counts(
+                                ui,
+                                "analytics-features",
+                                "Feature",
+                                &self.analytics.features,
+                            );
+                            ui.add_space(16.0);
+                            ui_counts(ui, "analytics-errors", "Error", &self.analytics.errors);
+                        });
+                    });
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Export...").clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("Usage analytics", &["json"])
+                            .set_file_name("mint_analytics.json")
+                            .save_file()
+                    {
+                        window.status =
+                            Some(match std::fs::write(&path, self.analytics.export()) {
+                                Ok(()) => (true, format!("Exported to {}", path.display())),
+                                Err(e) => (false, format!("Export failed: {e}")),
+                            });
+                    }
+                    if ui.button("Copy").clicked() {
+                        ui.ctx().copy_text(self.analytics.export());
+                        window.status = Some((true, "Copied to the clipboard".to_string()));
+                    }
+                    if ui.button("Clear").clicked() {
+                        self.analytics = Analytics::default();
+                        self.analytics.save(&self.state.dirs.data_dir);
+                        window.status = None;
+                    }
+                });
+                if let Some((success, msg)) = &window.status {
+                    if *success {
+                        ui.colored_label(Color32::LIGHT_GREEN, msg);
+                    } else {
+                        ui.colored_label(ui.visuals().error_fg_color, msg);
+                    }
+                }
+            });
+
+        if !open {
+            self.analytics_window = None;
+        }
+    }
+
+    /// Switch between the configured game installations in the bottom panel.
+    fn ui_game_install_selector(&mut self, ui: &mut Ui) {
+        let config = &mut self.state.config;
+        let active = config.active_game_install();
+        let mut selected = active;
+        ui.add_enabled_ui(
+            self.integrate_rid.is_none() && self.update_rid.is_none() && self.lint_rid.is_none(),
+            |ui| {
+                egui::ComboBox::from_id_salt("game-install")
+                    .selected_text(match active {
+                        Some(i) => config.game_installs[i].name.as_str(),
+                        None => "Other install",
+                    })
+                    .show_ui(ui, |ui| {
+                        for (i, install) in config.game_installs.iter().enumerate() {
+                            ui.selectable_value(&mut selected, Some(i), &install.name)
+                                .on_hover_text(format!(
+                                    "{}\n{}",
+                                    install.kind.name(),
+                                    install.pak_path.display()
+                                ));
+                        }
+                    })
+                    .response
+                    .on_hover_text("Game installation mods are installed to");
+            },
+        );
+        if selected != active
+            && let Some(i) = selected
+        {
+            let install = &config.game_installs[i];
+            info!("switching to game install {:?}", install.name);
+            config.drg_pak_path = Some(install.pak_path.clone());
+            config.save().unwrap();
+            // both describe the previous installation
+            self.missing_install = None;
+            self.install_changes = None;
+            self.record_feature("switch_game_install");
+        }
+    }
+
+    /// Health of the active profile, from the last lint report if it was made for the profile.
+    fn profile_health(&self) -> ProfileHealth {
+        let mod_data = &self.state.mod_data;
+        let mut dead_mods = 0;
+        mod_data.for_each_enabled_mod(&mod_data.active_profile, |mc| {
+            if self.state.store.get_mod_info(&mc.spec).is_none() {
+                dead_mods += 1;
+            }
+        });
+        let report = self
+            .lint_report
+            .as_ref()
+            .filter(|_| self.lint_profile.as_ref() == Some(&mod_data.active_profile));
+        let overrides = &mod_data.get_active_profile().asset_overrides;
+        let conflicts = report.and_then(|r| r.conflicting_mods.as_ref()).map(|c| {
+            c.keys()
+                .filter(|path| !overrides.contains_key(&asset_key(path)))
+                .count()
+        });
+        let since_update = self
+            .state
+            .config
+            .last_cache_update
+            .as_deref()
+            .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+            .and_then(|time| (chrono::Local::now().fixed_offset() - time).to_std().ok());
+        ProfileHealth::new(&HealthInputs {
+            dead_mods,
+            lint_problems: report.map(|r| {
+                let counts = r.problem_counts();
+                r.problem_count() - counts.get(&LintId::CONFLICTING).copied().unwrap_or(0)
+            }),
+            conflicts,
+            since_update,
+        })
+    }
+
+    /// Count a use of `feature` if usage analytics are enabled.
+    fn record_feature(&mut self, feature: &str) {
+        if self.state.config.usage_analytics {
+            self.analytics.record_feature(feature);
+            self.analytics.save(&self.state.dirs.data_dir);
+        }
+    }
+
+    /// The action showing `info`, counting the error if usage analytics are enabled.
+    fn error_action(&mut self, info: ErrorInfo) -> LastAction {
+        if self.state.config.usage_analytics {
+            self.analytics.record_error(info.code);
+            self.analytics.save(&self.state.dirs.data_dir);
+        }
+        LastAction::error(info)
+    }
 
-                                if let Some(unmodified_game_assets_mods) = &report.unmodified_game_assets_mods
-                                    && !unmodified_game_assets_mods.is_empty() {
-                                        CollapsingHeader::new(
-                                            RichText::new(
-                                                "⚠ Mod(s) with unmodified game assets detected",
-                                            )
-                                            .color(AMBER),
-                                        )
-                                        .default_open(true)
-                                        .show(ui, |ui| {
-                                            unmodified_game_assets_mods.iter().for_each(|(r#mod, files)| {
-                                                CollapsingHeader::new(
-                                                    RichText::new(format!(
-                                                        "⚠ {} includes unmodified game assets",
-                                                        r#mod.url
-                                                    ))
-                                                    .color(AMBER),
-                                                )
-                                                .show(ui, |ui| {
-                                                    files.iter().for_each(|file| {
-                                                        ui.label(file);
-                                                    });
-                                                });
-                                            });
-                                        });
-                                    }
-                            });
-                    } else {
-                        ui.spinner();
-                        ui.label("Lint report generating...");
+    fn show_help(&mut self, ctx: &egui::Context) {
+        if let Some(topic) = help::take_request(ctx) {
+            self.help_window = Some(WindowHelp::new(topic));
+        }
+        let Some(window) = &mut self.help_window else {
+            return;
+        };
+
+        let mut open = true;
+        let mut start_tour = false;
+        egui::Window::new("Help")
+            .open(&mut open)
+            .default_size([640.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("Take the tour")
+                        .on_hover_text("Show the guided tour of the main window again")
+                        .clicked()
+                    {
+                        start_tour = true;
                     }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut window.search)
+                            .hint_text("Search help...")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+                ui.separator();
+
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(160.0);
+                        let topics = window.matching_topics().collect::<Vec<_>>();
+                        if topics.is_empty() {
+                            ui.weak("No matching topics");
+                        }
+                        for topic in topics {
+                            ui.horizontal(|ui| {
+                                // lint pages go below the lints overview
+                                if let HelpTopic::Lint(_) = topic {
+                                    ui.add_space(ui.spacing().indent);
+                                }
+                                if ui
+                                    .selectable_label(window.topic == topic, topic.title())
+                                    .clicked()
+                                {
+                                    window.topic = topic;
+                                }
+                            });
+                        }
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .id_salt("help-content")
+                        .show(ui, |ui| {
+                            CommonMarkViewer::new().show(
+                                ui,
+                                &mut self.cache,
+                                window.topic.content(),
+                            );
+                        });
                 });
+            });
 
-            if !open {
-                self.lint_report_window = None;
-                self.lint_rid = None;
-            }
+        if start_tour {
+            self.tour = Some(Tour::default());
+            open = false;
+        }
+        if !open {
+            self.help_window = None;
         }
     }
 
@@ -2394,23 +7038,9 @@ impl App {
     }
 }
 
-type ModListEntry<'a> = (&'a ModOrGroup, Option<&'a ModInfo>);
+type ModListEntry<'a> = (&'a ModConfig, Option<&'a ModInfo>);
 fn sort_mods(config: SortingConfig) -> impl Fn(ModListEntry, ModListEntry) -> Ordering {
-    move |(a, info_a), (b, info_b)| {
-        // Groups should not reach here, but handle gracefully if they do
-        if matches!(a, ModOrGroup::Group { .. }) || matches!(b, ModOrGroup::Group { .. }) {
-            return Ordering::Equal;
-        }
-
-        let ModOrGroup::Individual(mc_a) = a else {
-            debug!("Item is not Individual \n{:?}", a);
-            return Ordering::Equal;
-        };
-        let ModOrGroup::Individual(mc_b) = b else {
-            debug!("Item is not Individual \n{:?}", b);
-            return Ordering::Equal;
-        };
-
+    move |(mc_a, info_a), (mc_b, info_b)| {
         fn map_cmp<V, M, F>(a: &V, b: &V, map: F) -> Ordering
         where
             M: Ord,
@@ -2450,6 +7080,22 @@ fn sort_mods(config: SortingConfig) -> impl Fn(ModListEntry, ModListEntry) -> Or
     }
 }
 
+/// Folders are sorted as units: by whether they are enabled when sorting by that, otherwise by
+/// name.
+fn sort_folders(config: SortingConfig) -> impl Fn((&str, bool), (&str, bool)) -> Ordering {
+    move |(name_a, enabled_a), (name_b, enabled_b)| {
+        let name_order = name_a.to_lowercase().cmp(&name_b.to_lowercase());
+        let mut order = match config.sort_category {
+            SortBy::Enabled => enabled_b.cmp(&enabled_a),
+            _ => name_order,
+        };
+        if config.is_ascending {
+            order = order.reverse();
+        }
+        order.then(name_order)
+    }
+}
+
 struct WindowProviderParameters {
     tx: Sender<(RequestID, Result<(), ProviderError>)>,
     rx: Receiver<(RequestID, Result<(), ProviderError>)>,
@@ -2457,11 +7103,45 @@ struct WindowProviderParameters {
     check_error: Option<String>,
     factory: &'static ProviderFactory,
     parameters: HashMap<String, String>,
+    /// Parameters supplied via environment variables or the secrets file, which are not editable
+    /// here and never saved to config.
+    external: HashSet<String>,
+    login: Option<EmailLoginState>,
+    /// Results of [`EmailLoginState`] requests: `None` once the code was sent, then the value.
+    login_tx: Sender<Result<Option<String>, ProviderError>>,
+    login_rx: Receiver<Result<Option<String>, ProviderError>>,
+}
+
+/// Logging in by email to get the value of a parameter, see [`EmailLogin`].
+struct EmailLoginState {
+    parameter: &'static str,
+    login: EmailLogin,
+    email: String,
+    code: String,
+    /// The code was sent, so it is asked for next.
+    code_sent: bool,
+    pending: Option<JoinHandle<()>>,
+    error: Option<String>,
+}
+
+impl EmailLoginState {
+    fn new(parameter: &'static str, login: EmailLogin) -> Self {
+        Self {
+            parameter,
+            login,
+            email: String::new(),
+            code: String::new(),
+            code_sent: false,
+            pending: None,
+            error: None,
+        }
+    }
 }
 
 impl WindowProviderParameters {
     fn new(factory: &'static ProviderFactory, state: &State) -> Self {
         let (tx, rx) = mpsc::channel(10);
+        let (login_tx, login_rx) = mpsc::channel(1);
         Self {
             tx,
             rx,
@@ -2473,7 +7153,16 @@ impl WindowProviderParameters {
                 .get(factory.id)
                 .cloned()
                 .unwrap_or_default(),
+            external: factory
+                .parameters
+                .iter()
+                .filter(|p| state.is_external_provider_parameter(factory.id, p.id))
+                .map(|p| p.id.to_string())
+                .collect(),
             factory,
+            login: None,
+            login_tx,
+            login_rx,
         }
     }
 }
@@ -2481,8 +7170,26 @@ impl WindowProviderParameters {
 struct WindowSettings {
     drg_pak_path: String,
     drg_pak_path_err: Option<String>,
+    /// Valid paks found around [`Self::drg_pak_path`] after it failed to save.
+    pak_suggestions: Vec<PathBuf>,
+    /// Main paks of all installs found, see [`DRGInstallation::find_all`].
+    detected_paks: Vec<PathBuf>,
     backup_path: String,
     backup_status: Option<(bool, String)>, // (success, message)
+    update_endpoint: String,
+    scan_command: String,
+    /// [`crate::state::Config::launch_args`] being edited, one per line.
+    launch_args: String,
+    diagnostics_status: Option<(bool, String)>, // (success, message)
+    import_status: Option<(bool, String)>,      // (success, message)
+    ue4ss: Option<ue4ss::Manifest>,
+    relocate_err: Option<String>,
+    /// Mount point of the game's drive if the cache is on another one.
+    cache_elsewhere: Option<PathBuf>,
+    workspaces: Workspaces,
+    /// Name for the next workspace added.
+    new_workspace: String,
+    workspace_err: Option<String>,
 }
 
 impl WindowSettings {
@@ -2496,36 +7203,752 @@ impl WindowSettings {
         let backup_path = state
             .config
             .backup_path
-            .as_ref()
+            .clone()
+            .or_else(backup::default_dir)
             .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|| Self::default_backup_path());
+            .unwrap_or_default();
+        let installation = state
+            .config
+            .drg_pak_path
+            .as_ref()
+            .and_then(|p| DRGInstallation::from_pak_path(p).ok());
         Self {
             drg_pak_path: path,
             drg_pak_path_err: None,
+            pak_suggestions: vec![],
+            detected_paks: DRGInstallation::find_all()
+                .iter()
+                .map(DRGInstallation::main_pak)
+                .collect(),
             backup_path,
             backup_status: None,
+            update_endpoint: state.config.update_endpoint.clone().unwrap_or_default(),
+            scan_command: state.config.scan_command.clone().unwrap_or_default(),
+            launch_args: state.config.launch_args.join("\n"),
+            diagnostics_status: None,
+            import_status: None,
+            ue4ss: installation.as_ref().and_then(|i| ue4ss::installed(i).ok()),
+            relocate_err: None,
+            cache_elsewhere: installation
+                .as_ref()
+                .and_then(|i| crate::health::game_drive_if_elsewhere(i, &state.dirs.cache_dir)),
+            workspaces: Workspaces::read().unwrap_or_default(),
+            new_workspace: String::new(),
+            workspace_err: None,
+        }
+    }
+}
+
+/// The arguments mint was started with, switched to the workspace `name`.
+fn args_in_workspace(name: &str) -> Vec<std::ffi::OsString> {
+    let mut args = vec!["--workspace".into(), name.into()];
+    let mut current = std::env::args_os().skip(1);
+    while let Some(arg) = current.next() {
+        let s = arg.to_string_lossy();
+        if s == "--workspace" || s == "--appdata" {
+            current.next();
+        } else if !s.starts_with("--workspace=") && !s.starts_with("--appdata=") {
+            args.push(arg);
+        }
+    }
+    args
+}
+
+#[derive(Default)]
+struct WindowLintReport {
+    /// Lint whose findings are shown, the first with any if `None`.
+    selected: Option<LintId>,
+}
+
+const AMBER: Color32 = Color32::from_rgb(255, 191, 0);
+
+/// Title and color of lint `id` in the lint report.
+fn lint_category(id: LintId) -> (&'static str, Color32) {
+    match id {
+        LintId::CONFLICTING => ("⚠ Conflicting asset modifications", AMBER),
+        LintId::ASSET_REGISTRY_BIN => ("ℹ `AssetRegistry.bin` included", Color32::LIGHT_BLUE),
+        LintId::SHADER_FILES => ("⚠ Shader files included", AMBER),
+        LintId::OUTDATED_PAK_VERSION => ("⚠ Outdated pak version", AMBER),
+        LintId::EMPTY_ARCHIVE => ("⚠ Empty archives", AMBER),
+        LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => ("⚠ Only non-`.pak` files", AMBER),
+        LintId::ARCHIVE_WITH_MULTIPLE_PAKS => ("⚠ Multiple `.pak`s", AMBER),
+        LintId::NON_ASSET_FILES => ("⚠ Non-asset files", AMBER),
+        LintId::SPLIT_ASSET_PAIRS => ("⚠ Split {uexp, uasset} pairs", AMBER),
+        LintId::UNMODIFIED_GAME_ASSETS => ("⚠ Unmodified game assets", AMBER),
+        LintId::CONFIG_OVERRIDES => ("⚠ Game config overrides", AMBER),
+        LintId::UNSUPPORTED_COMPRESSION => ("⚠ Unsupported compression", Color32::LIGHT_RED),
+        LintId::MOUNT_POINT => ("⚠ Wrong mount point", AMBER),
+        LintId::LOCALIZATION_CONFLICTS => ("⚠ Localization conflicts", AMBER),
+        _ => ("⚠ Other", AMBER),
+    }
+}
+
+/// What the findings of lint `id` mean, shown above them in the lint report.
+fn lint_description(id: LintId) -> &'static str {
+    match id {
+        LintId::CONFLICTING => {
+            "More than one mod changes these assets. Pick the mod whose version is installed, \
+             otherwise the mod with the highest priority wins."
+        }
+        LintId::ASSET_REGISTRY_BIN => {
+            "These mods include one or more `AssetRegistry.bin`, which is left out in favor of \
+             the game's own."
+        }
+        LintId::SHADER_FILES => "These mods include one or more shader files.",
+        LintId::OUTDATED_PAK_VERSION => {
+            "These mods use an outdated pak version and should be repacked for the current game."
+        }
+        LintId::EMPTY_ARCHIVE => "These mods contain an empty archive.",
+        LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
+            "These mods contain only non-`.pak` files, perhaps the author forgot to pack them?"
+        }
+        LintId::ARCHIVE_WITH_MULTIPLE_PAKS => {
+            "These mods contain multiple `.pak`s, only the first encountered `.pak` will be loaded."
+        }
+        LintId::NON_ASSET_FILES => "These mods include non-asset files.",
+        LintId::SPLIT_ASSET_PAIRS => "These mods include split {uexp, uasset} pairs.",
+        LintId::UNMODIFIED_GAME_ASSETS => "These mods include unmodified game assets.",
+        LintId::CONFIG_OVERRIDES => {
+            "These mods include ini files or settings assets which change the game's input, \
+             engine or user settings. Changed key bindings or graphics settings may come from them."
+        }
+        LintId::UNSUPPORTED_COMPRESSION => {
+            "These mods' paks are compressed with methods the game cannot decompress, and crash \
+             it when their files are loaded. They need to be repacked with Zlib or Oodle."
+        }
+        LintId::MOUNT_POINT => {
+            "These mods' paks are not mounted at `../../../` with their files under `FSD/`, so the \
+             game does not find them and the mods do nothing. They need to be repacked."
+        }
+        LintId::LOCALIZATION_CONFLICTS => {
+            "More than one mod changes these localization files or string tables. Only one mod's \
+             version is loaded, so the texts of the others are lost or the UI ends up in mixed \
+             languages. Overlapping entries are listed as `namespace/key` where they can be read."
+        }
+        _ => "",
+    }
+}
+
+struct WindowPreview {
+    name: String,
+    preview: ModPreview,
+    /// Opened on the first sound played, stopping it once the window is closed.
+    player: Option<(rodio::OutputStream, rodio::Sink)>,
+    /// Decoded bundled images keyed by index into `preview.images`, `None` if decoding failed.
+    images: HashMap<usize, Option<egui::TextureHandle>>,
+    /// Uploaded textures keyed by index into `preview.textures`.
+    textures: HashMap<usize, egui::TextureHandle>,
+    error: Option<String>,
+}
+
+impl WindowPreview {
+    fn new(name: String, preview: ModPreview) -> Self {
+        Self {
+            name,
+            preview,
+            player: None,
+            images: Default::default(),
+            textures: Default::default(),
+            error: None,
+        }
+    }
+
+    /// Play a sound file, replacing the one playing.
+    fn play(&mut self, file: &PreviewFile) -> Result<(), Box<dyn std::error::Error>> {
+        let source: Box<dyn rodio::Source<Item = i16> + Send> = match audio::detect(&file.data) {
+            AudioFormat::Ogg => Box::new(rodio::Decoder::new(Cursor::new(file.data.clone()))?),
+            _ => {
+                let pcm = audio::decode(&file.data)?;
+                Box::new(rodio::buffer::SamplesBuffer::new(
+                    pcm.channels,
+                    pcm.sample_rate,
+                    pcm.samples,
+                ))
+            }
+        };
+        let (_, sink) = match &mut self.player {
+            Some(player) => player,
+            None => {
+                let (stream, handle) = rodio::OutputStream::try_default()?;
+                let sink = rodio::Sink::try_new(&handle)?;
+                self.player.insert((stream, sink))
+            }
+        };
+        sink.clear();
+        sink.append(source);
+        sink.play();
+        self.error = None;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some((_, sink)) = &self.player {
+            sink.clear();
+        }
+    }
+}
+
+struct WindowLintsToggle;
+
+struct WindowMergeProfiles {
+    /// Profile the other one is merged into.
+    into: String,
+    from: Option<String>,
+    conflicts: Vec<MergeConflict>,
+    /// Keys of the conflicting mods for which the version and priority of `from` is used.
+    take_source: HashSet<String>,
+}
+
+impl WindowMergeProfiles {
+    fn new(into: String) -> Self {
+        Self {
+            into,
+            from: None,
+            conflicts: vec![],
+            take_source: Default::default(),
+        }
+    }
+}
+
+struct WindowPayloadRules {
+    name: String,
+    spec: ModSpecification,
+    /// (archive folder, game folder) being edited
+    rows: Vec<(String, String)>,
+}
+
+impl WindowPayloadRules {
+    fn new(name: String, spec: ModSpecification, rules: Option<&Vec<PayloadRule>>) -> Self {
+        Self {
+            name,
+            spec,
+            rows: rules
+                .into_iter()
+                .flatten()
+                .map(|r| (r.source.clone(), r.target.to_string_lossy().to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Asks for a new link for a mod whose download link expired.
+struct WindowRefreshLink {
+    name: String,
+    /// The expired link, which is the mod's URL.
+    url: String,
+    new_url: String,
+    /// Whether the failed operation only downloaded the mods.
+    download_only: bool,
+}
+
+impl WindowRefreshLink {
+    fn new(state: &State, url: String, download_only: bool) -> Self {
+        let name = state
+            .store
+            .get_mod_info(&ModSpecification::new(url.clone()))
+            .map(|info| info.name)
+            .unwrap_or_else(|| url.clone());
+        Self {
+            name,
+            url,
+            new_url: String::new(),
+            download_only,
+        }
+    }
+}
+
+struct WindowLocalMetadata {
+    name: String,
+    spec: ModSpecification,
+    display_name: String,
+    /// Comma separated.
+    tags: String,
+    /// (mod URL, version constraint) being edited
+    dependencies: Vec<(String, String)>,
+}
+
+impl WindowLocalMetadata {
+    fn new(name: String, spec: ModSpecification, metadata: Option<&LocalMetadata>) -> Self {
+        let metadata = metadata.cloned().unwrap_or_default();
+        Self {
+            name,
+            spec,
+            display_name: metadata.name.unwrap_or_default(),
+            tags: metadata.tags.into_iter().collect::<Vec<_>>().join(", "),
+            dependencies: metadata
+                .dependencies
+                .into_iter()
+                .map(|d| {
+                    let constraint = d.constraint.map(|c| c.to_string()).unwrap_or_default();
+                    (d.spec.url, constraint)
+                })
+                .collect(),
+        }
+    }
+
+    /// The edited metadata, or `None` if a dependency is invalid.
+    fn metadata(&self) -> Option<LocalMetadata> {
+        let name = self.display_name.trim();
+        Some(LocalMetadata {
+            name: (!name.is_empty()).then(|| name.to_string()),
+            tags: self
+                .tags
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(ToString::to_string)
+                .collect(),
+            dependencies: self
+                .dependencies
+                .iter()
+                .map(|(url, constraint)| {
+                    let url = url.trim();
+                    let constraint = constraint.trim();
+                    Some(LocalDependency {
+                        spec: ModSpecification::new((!url.is_empty()).then_some(url)?.to_string()),
+                        constraint: if constraint.is_empty() {
+                            None
+                        } else {
+                            Some(constraint.parse().ok()?)
+                        },
+                    })
+                })
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+struct WindowHealthCheck {
+    /// Set while the checks are running.
+    rid: Option<MessageHandle<()>>,
+    results: Vec<CheckResult>,
+}
+
+impl WindowHealthCheck {
+    /// Plain text version of the results.
+    fn report(&self) -> String {
+        self.results
+            .iter()
+            .map(|r| {
+                let status = match r.status {
+                    CheckStatus::Pass => "PASS",
+                    CheckStatus::Warn => "WARN",
+                    CheckStatus::Fail => "FAIL",
+                    CheckStatus::Skipped => "SKIP",
+                };
+                let mut line = format!("[{status}] {}: {}", r.name, r.detail);
+                if let Some(fix) = r.fix {
+                    line.push_str(&format!(" ({fix})"));
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct WindowDownloadEstimate {
+    /// Set while the mods are being resolved.
+    rid: Option<MessageHandle<()>>,
+    result: Option<Result<DownloadEstimate, String>>,
+}
+
+/// Non-fatal problems of the last install.
+struct WindowIntegrationWarnings {
+    warnings: Vec<IntegrationWarning>,
+}
+
+struct RunningGame {
+    child: std::process::Child,
+    /// Profile which was active when the game was launched.
+    profile: String,
+    /// Start time of the recorded session.
+    session: String,
+    launched: Instant,
+}
+
+struct WindowArchive {
+    /// Season of the active profile being edited.
+    season: String,
+    archive_season: Option<String>,
+}
+
+impl WindowArchive {
+    fn new(state: &State) -> Self {
+        Self {
+            season: state
+                .mod_data
+                .get_active_profile()
+                .season
+                .clone()
+                .unwrap_or_default(),
+            archive_season: None,
+        }
+    }
+}
+
+struct WindowProfileOrder;
+
+struct WindowInstallChanges {
+    changes: Vec<InstallChange>,
+    /// Whether the list is shown, not only the banner.
+    open: bool,
+}
+
+struct WindowFolderDependencies {
+    profile: String,
+    folder: String,
+    /// Whether the folder was enabled or disabled.
+    enabled: bool,
+    broken: Vec<BrokenDependency>,
+}
+
+#[derive(Default)]
+struct WindowDependencyGraph {
+    /// The graph with the mods it was built from, rebuilt once they change.
+    cached: Option<(String, Vec<(ModSpecification, bool)>, CachedDependencyGraph)>,
+}
+
+struct CachedDependencyGraph {
+    graph: DependencyGraph,
+    roots: Vec<usize>,
+    /// Whether any mod in the chain of each node is not enabled.
+    incomplete: Vec<bool>,
+}
+
+impl CachedDependencyGraph {
+    fn new(graph: DependencyGraph) -> Self {
+        let incomplete = (0..graph.nodes.len())
+            .map(|i| {
+                graph
+                    .chain(i)
+                    .iter()
+                    .any(|&c| graph.nodes[c].status != NodeStatus::Enabled)
+            })
+            .collect();
+        Self {
+            roots: graph.roots(),
+            incomplete,
+            graph,
+        }
+    }
+}
+
+/// `mint://` links waiting for the user to confirm them, one at a time in the order they arrived.
+struct WindowDeepLink {
+    links: VecDeque<DeepLink>,
+}
+
+/// Mods enabled in `profile` which a pasted [`mod_list`] did not list, offered to be disabled so
+/// the profile matches the list.
+struct WindowUnlistedMods {
+    profile: String,
+    urls: Vec<String>,
+}
+
+struct WindowSuggestPriorities {
+    profile: String,
+    /// Suggested changes and whether each is to be applied.
+    changes: Vec<(PriorityChange, bool)>,
+}
+
+struct WindowProfileReadme {
+    profile: String,
+    text: String,
+    /// Whether the rendered readme is shown instead of the editor.
+    preview: bool,
+}
+
+struct WindowProfileInfo {
+    profile: String,
+    icon: String,
+    description: String,
+}
+
+impl WindowProfileInfo {
+    fn new(state: &State) -> Self {
+        let profile = state.mod_data.get_active_profile();
+        Self {
+            profile: state.mod_data.active_profile.clone(),
+            icon: profile.icon.clone().unwrap_or_default(),
+            description: profile.description.clone().unwrap_or_default(),
+        }
+    }
+}
+
+struct WindowPinToDate {
+    profile: String,
+    date: String,
+    status: Option<(bool, String)>,
+}
+
+struct WindowQr {
+    /// Name of the profile with its icon.
+    title: String,
+    description: Option<String>,
+    mods: usize,
+    code: Result<qrcode::QrCode, String>,
+}
+
+impl WindowQr {
+    fn new(state: &State) -> Self {
+        let profile = state.mod_data.active_profile.clone();
+        let mut mods = Vec::new();
+        state.mod_data.for_each_enabled_mod(&profile, |mc| {
+            mods.push(mc.clone());
+        });
+        let info = state.mod_data.get_active_profile();
+        Self {
+            code: qr::encode(&App::build_mod_string(&mods)),
+            mods: mods.len(),
+            title: match &info.icon {
+                Some(icon) => format!("{icon} {profile}"),
+                None => profile,
+            },
+            description: info.description.clone(),
+        }
+    }
+}
+
+struct WindowStats {
+    /// Error if the sessions file could not be read, which is then left alone.
+    sessions: Result<Sessions, String>,
+}
+
+struct WindowAnalytics {
+    /// Whether exporting succeeded and what happened.
+    status: Option<(bool, String)>,
+}
+
+struct WindowLobby {
+    /// Report of the current install, or why there is none.
+    installed: Result<LobbyReport, String>,
+    key_path: String,
+    status: Option<(bool, String)>, // (success, message)
+    /// A host's report with its signature and how the install differs from it.
+    checked: Option<(LobbyReport, SignatureStatus, Vec<lobby::Difference>)>,
+}
+
+impl WindowLobby {
+    fn new(state: &State) -> Self {
+        let installed = state
+            .config
+            .drg_pak_path
+            .as_ref()
+            .ok_or_else(|| "DRG install not found. Configure it in the settings menu.".to_string())
+            .and_then(|p| DRGInstallation::from_pak_path(p).map_err(|e| e.to_string()))
+            .and_then(|i| LobbyReport::from_install(&i).map_err(|e| e.to_string()));
+        Self {
+            installed,
+            key_path: String::new(),
+            status: None,
+            checked: None,
+        }
+    }
+
+    /// Save `report`, signed with the key at `key_path` unless it is empty.
+    fn save(report: &LobbyReport, key_path: &str, path: &Path) -> Result<(), lobby::LobbyError> {
+        let mut report = report.clone();
+        let key_path = key_path.trim();
+        if !key_path.is_empty() {
+            report.sign(&fs_err::read_to_string(key_path)?)?;
+        }
+        report.write(path)
+    }
+}
+
+/// `bytes` in MiB, noting mods whose size is unknown.
+/// One mod of the dependency graph and, below it, the mods it depends on. `path` holds the mods
+/// above it, so circular dependencies end instead of being followed forever.
+fn ui_dependency_node(
+    ui: &mut Ui,
+    graph: &DependencyGraph,
+    incomplete: &[bool],
+    name: &dyn Fn(&ModSpecification) -> String,
+    index: usize,
+    path: &mut Vec<usize>,
+    enable_chain: &mut Option<usize>,
+) {
+    let node = &graph.nodes[index];
+    let circular = path.contains(&index);
+    let header = |ui: &mut Ui, enable_chain: &mut Option<usize>| {
+        let text = RichText::new(name(&node.spec));
+        match node.status {
+            NodeStatus::Enabled => ui.label(text),
+            NodeStatus::Disabled => ui
+                .label(text.color(ui.visuals().warn_fg_color))
+                .on_hover_text("Disabled"),
+            NodeStatus::Missing => ui
+                .label(text.color(ui.visuals().error_fg_color))
+                .on_hover_text(format!("Not in the profile: {}", node.spec.url)),
+        };
+        if node.in_cycle {
+            ui.colored_label(ui.visuals().warn_fg_color, "\u{21BB}")
+                .on_hover_text("Depends on itself through its dependencies");
+        }
+        if circular {
+            ui.weak("(see above)");
+        } else if incomplete[index]
+            && ui
+                .small_button("Enable chain")
+                .on_hover_text(
+                    "Enable this mod and everything it depends on, adding the missing mods",
+                )
+                .clicked()
+        {
+            *enable_chain = Some(index);
+        }
+    };
+
+    if circular || node.dependencies.is_empty() {
+        ui.horizontal(|ui| {
+            ui.add_space(ui.spacing().indent);
+            header(ui, enable_chain);
+        });
+        return;
+    }
+    path.push(index);
+    let id = ui.make_persistent_id(("dependency-graph", path.as_slice()));
+    CollapsingState::load_with_default_open(ui.ctx(), id, true)
+        .show_header(ui, |ui| header(ui, enable_chain))
+        .body(|ui| {
+            for &dependency in &node.dependencies {
+                ui_dependency_node(ui, graph, incomplete, name, dependency, path, enable_chain);
+            }
+        });
+    path.pop();
+}
+
+fn download_size_text(bytes: u64, unknown_size: usize) -> String {
+    let size = format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0));
+    if unknown_size == 0 {
+        size
+    } else {
+        format!("{size} + {unknown_size} of unknown size")
+    }
+}
+
+struct WindowHistory {
+    snapshots: Vec<history::Snapshot>,
+    status: Option<(bool, String)>, // (success, message)
+    /// Index of the snapshot waiting for restore confirmation.
+    confirm_restore: Option<usize>,
+}
+
+impl WindowHistory {
+    fn new(state: &State) -> Self {
+        Self {
+            snapshots: history::list(&state.dirs.data_dir.join(history::HISTORY_DIR))
+                .unwrap_or_default(),
+            status: None,
+            confirm_restore: None,
         }
     }
+}
 
-    fn default_backup_path() -> String {
-        directories::UserDirs::new()
-            .and_then(|dirs| dirs.document_dir().map(|d| d.to_path_buf()))
-            .map(|d| d.join("mint_backups"))
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default()
+struct WindowSaveSnapshots {
+    snapshots: Vec<saves::Snapshot>,
+    status: Option<(bool, String)>, // (success, message)
+    /// Index of the snapshot waiting for restore confirmation.
+    confirm_restore: Option<usize>,
+}
+
+impl WindowSaveSnapshots {
+    fn new(state: &State) -> Self {
+        Self {
+            snapshots: saves::list(&state.dirs.data_dir).unwrap_or_default(),
+            status: None,
+            confirm_restore: None,
+        }
     }
 }
 
-struct WindowLintReport;
+/// Identifies a mod regardless of the version it is pinned to.
+fn mod_key(store: &ModStore, spec: &ModSpecification) -> String {
+    store
+        .get_mod_info(spec)
+        .map(|info| info.spec.url)
+        .unwrap_or_else(|| spec.url.clone())
+}
 
-struct WindowLintsToggle;
+/// Whether the mod URL is a filesystem path rather than a link, even if nothing is there anymore.
+fn is_local_path(url: &str) -> bool {
+    !url.starts_with("http://") && !url.starts_with("https://")
+}
 
 /// Holds information about a pending deletion confirmation
+/// Badge showing who signed a mod from outside mod.io, or why it can't be trusted.
+fn ui_signature(ui: &mut Ui, status: &SignatureStatus) {
+    match status {
+        SignatureStatus::Trusted { signer } => {
+            ui.colored_label(Color32::LIGHT_GREEN, "✔")
+                .on_hover_text(format!("Signed by {signer}"));
+        }
+        SignatureStatus::UnknownSigner { key } => {
+            ui.colored_label(ui.visuals().warn_fg_color, "⚠")
+                .on_hover_text(format!(
+                    "Signed by a key you don't trust:\n{key}\nAdd it with `mint trust add` if \
+                     you know who it belongs to."
+                ));
+        }
+        SignatureStatus::Mismatch => {
+            ui.colored_label(ui.visuals().error_fg_color, "⚠")
+                .on_hover_text(
+                    "The file does not match its signature. It was changed after signing, \
+                     possibly by someone other than its author.",
+                );
+        }
+        SignatureStatus::Unsigned => {
+            ui.colored_label(ui.visuals().weak_text_color(), "?")
+                .on_hover_text("Not signed, nothing vouches for who made this file");
+        }
+    }
+}
+
 enum PendingDeletion {
-    Mod { mod_name: String, row_index: usize },
-    Profile { profile_name: String },
-    Folder { folder_name: String },
-    FolderMod { folder_name: String, mod_index: usize, mod_name: String },
+    Mod {
+        mod_name: String,
+        row_index: usize,
+    },
+    Profile {
+        profile_name: String,
+        /// Folders and mods only this profile has, which would be lost with it.
+        unique: UniqueContents,
+        /// Profile to move the unique folders and mods into before deleting.
+        move_to: Option<String>,
+        export_status: Option<(bool, String)>, // (success, message)
+    },
+    Folder {
+        folder_name: String,
+    },
+    FolderMod {
+        folder_name: String,
+        mod_index: usize,
+        mod_name: String,
+    },
+    /// The mods checked for bulk editing.
+    Mods {
+        profile: String,
+        urls: HashSet<String>,
+    },
+}
+
+/// Mods checked in a profile for bulk editing, by URL.
+#[derive(Default)]
+struct ModSelection {
+    profile: String,
+    urls: HashSet<String>,
+    /// Priority for "Set priority".
+    priority: i32,
+}
+
+/// An edit to all mods in the [`ModSelection`].
+enum BulkAction {
+    Enable(bool),
+    SetPriority(i32),
+    /// Into a folder, or to the top level for `None`.
+    MoveTo(Option<String>),
+    Delete,
 }
 
 impl eframe::App for App {
@@ -2537,7 +7960,12 @@ impl eframe::App for App {
             self.needs_restart = false;
 
             debug!("restarting...");
+            let args = self
+                .restart_args
+                .take()
+                .unwrap_or_else(|| std::env::args_os().skip(1).collect());
             let _child = std::process::Command::new(original_exe_path)
+                .args(args)
                 .spawn()
                 .unwrap();
             debug!("created child process");
@@ -2552,7 +7980,30 @@ impl eframe::App for App {
             let theme = GuiTheme::into_egui_theme(self.state.config.gui_theme);
             ctx.memory_mut(|m| m.options.theme_preference = theme);
 
-            message::CheckUpdates::send(self, ctx);
+            // safe mode is for broken state, which should not replace good backups
+            let safe_mode = self.state.safe_mode.is_some();
+            if self.state.config.check_for_updates && !safe_mode {
+                message::CheckUpdates::send(self, ctx);
+            }
+            if self.state.config.auto_backup.on_launch && !safe_mode {
+                let dir = self.backup_dir();
+                message::Backup::send(self, ctx, dir, backup::Trigger::Launch);
+            }
+
+            self.run_startup_actions(ctx);
+        }
+        self.receive_links(ctx);
+
+        if self.state.config.auto_backup.daily && self.backup_rid.is_none() {
+            let now = Instant::now();
+            if now >= self.next_backup_check {
+                self.next_backup_check = now + BACKUP_CHECK_INTERVAL;
+                let dir = self.backup_dir();
+                if backup::daily_due(&dir) {
+                    message::Backup::send(self, ctx, dir, backup::Trigger::Daily);
+                }
+            }
+            ctx.request_repaint_after(self.next_backup_check.saturating_duration_since(now));
         }
 
         // message handling
@@ -2568,10 +8019,42 @@ impl eframe::App for App {
         self.show_settings(ctx);
         self.show_lints_toggle(ctx);
         self.show_lint_report(ctx);
+        self.show_preview(ctx);
         self.show_delete_confirmation(ctx);
         self.show_create_folder_popup(ctx);
         self.show_rename_folder_popup(ctx);
-
+        self.show_save_loadout_popup(ctx);
+        self.show_help(ctx);
+        self.show_merge_profiles(ctx);
+        self.show_payload_rules(ctx);
+        self.show_local_metadata(ctx);
+        self.show_refresh_link(ctx);
+        self.show_save_snapshots(ctx);
+        self.show_history(ctx);
+        self.show_missing_install(ctx);
+        self.show_install_changes(ctx);
+        self.show_health_check(ctx);
+        self.show_download_estimate(ctx);
+        self.show_integration_warnings(ctx);
+        self.show_lobby(ctx);
+        self.show_stats(ctx);
+        self.show_analytics(ctx);
+        self.show_qr(ctx);
+        self.show_pin_to_date(ctx);
+        self.show_archive(ctx);
+        self.show_profile_order(ctx);
+        self.show_profile_info(ctx);
+        self.show_profile_readme(ctx);
+        self.show_folder_dependencies(ctx);
+        self.show_dependency_graph(ctx);
+        self.show_suggest_priorities(ctx);
+        self.show_deep_link(ctx);
+        self.show_unlisted_mods(ctx);
+        self.show_safe_mode(ctx);
+        self.show_migration(ctx);
+        self.watch_game(ctx);
+
+        let mut retry = None;
         egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
             ui.with_layout(egui::Layout::right_to_left(Align::TOP), |ui| {
                 ui.add_enabled_ui(
@@ -2588,23 +8071,18 @@ impl eframe::App for App {
                                     for arg in args {
                                         ui.label(arg);
                                     }
+                                    for arg in &self.state.config.launch_args {
+                                        ui.label(arg);
+                                    }
                                 })
                                 .clicked()
                         {
-                            let args = args.clone();
-                            std::thread::spawn(move || {
-                                let mut iter = args.iter();
-                                std::process::Command::new(iter.next().unwrap())
-                                    .args(iter)
-                                    .spawn()
-                                    .unwrap()
-                                    .wait()
-                                    .unwrap();
-                            });
+                            self.launch_game();
                         }
 
                         ui.add_enabled_ui(self.state.config.drg_pak_path.is_some(), |ui| {
                             let mut button = ui.button("Install mods");
+                            tutorial::register(ctx, TourTarget::Install, button.rect);
                             if self.state.config.drg_pak_path.is_none() {
                                 button = button.on_disabled_hover_text(
                                     "DRG install not found. Configure it in the settings menu.",
@@ -2612,32 +8090,7 @@ impl eframe::App for App {
                             }
 
                             if button.clicked() {
-                                let mut mods = Vec::new();
-                                let active_profile = self.state.mod_data.active_profile.clone();
-                                
-                                // Get mods with effective priority (respecting folder overrides)
-                                let mut mods_with_priority = self.state
-                                    .mod_data
-                                    .get_enabled_mods_with_priority(&active_profile);
-
-                                // Sort by effective priority (descending)
-                                mods_with_priority.sort_by_key(|(_, priority)| -priority);
-
-                                for (config, _) in mods_with_priority {
-                                    mods.push(config.spec.clone());
-                                }
-
-                                self.last_action = None;
-                                self.integrate_rid = Some(message::Integrate::send(
-                                    &mut self.request_counter,
-                                    self.state.store.clone(),
-                                    mods,
-                                    self.state.config.drg_pak_path.as_ref().unwrap().clone(),
-                                    self.state.config.deref().into(),
-                                    self.tx.clone(),
-                                    ctx.clone(),
-                                ));
-                                self.problematic_mod_id = None;
+                                self.install_mods(ctx);
                             }
                         });
 
@@ -2649,6 +8102,7 @@ impl eframe::App for App {
                                 );
                             }
                             if button.clicked() {
+                                self.record_feature("uninstall_mods");
                                 self.last_action = None;
                                 if let Some(pak_path) = &self.state.config.drg_pak_path {
                                     let mut mods = HashSet::default();
@@ -2669,9 +8123,14 @@ impl eframe::App for App {
 
                                     debug!("uninstalling mods: pak_path = {}", pak_path.display());
                                     self.last_action = Some(match uninstall(pak_path, mods) {
-                                        Ok(()) => LastAction::success(
-                                            "Successfully uninstalled mods".to_string(),
-                                        ),
+                                        Ok(()) => {
+                                            IntegrationReport::uninstalled()
+                                                .save(&self.state.dirs.data_dir);
+                                            self.missing_install = None;
+                                            LastAction::success(
+                                                "Successfully uninstalled mods".to_string(),
+                                            )
+                                        }
                                         Err(e) => LastAction::failure(format!(
                                             "Failed to uninstall mods: {e}"
                                         )),
@@ -2687,33 +8146,73 @@ impl eframe::App for App {
                             )
                             .clicked()
                         {
-                            message::UpdateCache::send(self);
-                            self.problematic_mod_id = None;
+                            message::Operation::UpdateCache.dispatch(self, ctx);
+                        }
+
+                        if ui
+                            .button("Download all")
+                            .on_hover_text(
+                                "Downloads the enabled mods into the cache without installing them, to install later without a connection",
+                            )
+                            .clicked()
+                        {
+                            self.integrate_mods(ctx, true);
                         }
                     },
                 );
+                if !self.state.config.game_installs.is_empty() {
+                    self.ui_game_install_selector(ui);
+                }
                 if self.integrate_rid.is_some() {
                     if ui.button("Cancel").clicked() {
                         self.integrate_rid.take().unwrap().handle.abort();
                     }
-                    ui.spinner();
+                    self.ui_pause(ui);
                 }
                 if self.update_rid.is_some() {
                     if ui.button("Cancel").clicked() {
                         self.update_rid.take().unwrap().handle.abort();
                     }
-                    ui.spinner();
+                    self.ui_pause(ui);
                 }
-                if ui
+                let lint = ui
                     .button("Lint mods")
-                    .on_hover_text("Lint mods in the current profile")
+                    .on_hover_text("Lint mods in the current profile");
+                tutorial::register(ctx, TourTarget::Lint, lint.rect);
+                if lint.clicked() {
+                    self.lints_toggle_window = Some(WindowLintsToggle);
+                }
+                if ui
+                    .button("Dependencies")
+                    .on_hover_text("Show the dependencies between the enabled mods")
                     .clicked()
                 {
-                    self.lints_toggle_window = Some(WindowLintsToggle);
+                    self.record_feature("dependency_graph");
+                    self.dependency_graph_window = Some(Default::default());
+                }
+                if ui
+                    .button("Lobby report")
+                    .on_hover_text("Compare the installed mods with a lobby host's")
+                    .clicked()
+                {
+                    self.record_feature("lobby_report");
+                    self.lobby_window = Some(WindowLobby::new(&self.state));
+                }
+                if ui.button("📊").on_hover_text("Play statistics").clicked() {
+                    self.record_feature("play_statistics");
+                    self.stats_window = Some(WindowStats {
+                        sessions: Sessions::load(&self.state.dirs.data_dir)
+                            .map_err(|e| e.to_string()),
+                    });
                 }
-                if ui.button("⚙").on_hover_text("Open settings").clicked() {
+                let settings = ui.button("⚙").on_hover_text("Open settings");
+                tutorial::register(ctx, TourTarget::Settings, settings.rect);
+                if settings.clicked() {
                     self.settings_window = Some(WindowSettings::new(&self.state));
                 }
+                if ui.button("?").on_hover_text("Open help").clicked() {
+                    self.help_window = Some(WindowHelp::new(HelpTopic::Overview));
+                }
                 if let Some(available_update) = &self.available_update
                     && ui
                         .button(egui::RichText::new("\u{26A0}").color(ui.visuals().warn_fg_color))
@@ -2728,38 +8227,181 @@ impl eframe::App for App {
                 }
                 ui.with_layout(egui::Layout::left_to_right(Align::TOP), |ui| {
                     if let Some(last_action) = &self.last_action {
-                        let msg = match &last_action.status {
-                            LastActionStatus::Success(msg) => {
-                                ui.label(
-                                    egui::RichText::new("STATUS")
-                                        .color(Color32::BLACK)
-                                        .background_color(Color32::LIGHT_GREEN),
-                                );
-                                msg
-                            }
-                            LastActionStatus::Failure(msg) => {
-                                ui.label(
-                                    egui::RichText::new("STATUS")
-                                        .color(Color32::BLACK)
-                                        .background_color(Color32::LIGHT_RED),
-                                );
-                                msg
+                        let color = match &last_action.status {
+                            LastActionStatus::Success(_) => Color32::LIGHT_GREEN,
+                            LastActionStatus::Failure(_) | LastActionStatus::Error(_) => {
+                                Color32::LIGHT_RED
                             }
                         };
+                        ui.label(
+                            egui::RichText::new("STATUS")
+                                .color(Color32::BLACK)
+                                .background_color(color),
+                        );
                         ui.ctx().request_repaint_after(std::time::Duration::from_secs(1)); // throttle timeago updates
-                        ui.label(format!("({}): {}", last_action.timeago(), msg));
+                        match &last_action.status {
+                            LastActionStatus::Success(msg) | LastActionStatus::Failure(msg) => {
+                                ui.label(format!("({}): {}", last_action.timeago(), msg));
+                            }
+                            LastActionStatus::Error(info) => {
+                                ui.label(format!("({}): {}", last_action.timeago(), info.summary()));
+                                ui.label(RichText::new(info.action).italics());
+                                if ui
+                                    .small_button("Copy details")
+                                    .on_hover_text("Copy the error code, cause, suggested action and full error, e.g. for a bug report")
+                                    .clicked()
+                                {
+                                    ui.ctx().copy_text(info.report());
+                                }
+                            }
+                        }
+                        if let Some(operation) = &last_action.retry
+                            && self.integrate_rid.is_none()
+                            && self.update_rid.is_none()
+                            && ui
+                                .small_button("Retry")
+                                .on_hover_text("Run the failed operation again with the same mods and settings")
+                                .clicked()
+                        {
+                            retry = Some(operation.clone());
+                        }
                     }
                 });
             });
         });
+        if let Some(operation) = retry {
+            operation.dispatch(self, ctx);
+        }
         egui::CentralPanel::default().show(ctx, |ui| {
             if self.integrate_rid.is_some() || self.update_rid.is_some() || self.lint_rid.is_some()
             {
                 ui.disable();
             }
+            if let Some(window) = &mut self.install_changes {
+                let mut install = false;
+                let mut dismiss = false;
+                ui.horizontal(|ui| {
+                    let count = window.changes.len();
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!(
+                            "⚠ {count} mod{} changed since the last install",
+                            if count == 1 { "" } else { "s" }
+                        ),
+                    );
+                    if ui.small_button("Show").clicked() {
+                        window.open = true;
+                    }
+                    install = ui.small_button("Install").clicked();
+                    dismiss = ui.small_button("✖").on_hover_text("Dismiss").clicked();
+                });
+                ui.separator();
+                if install || dismiss {
+                    self.install_changes = None;
+                }
+                if install {
+                    self.install_mods(ctx);
+                }
+            }
+
             // profile selection
 
+            let mut open_merge = false;
+            let mut open_qr = false;
+            let mut open_pin_to_date = false;
+            let mut open_archive = false;
+            let mut open_profile_order = false;
+            let mut open_profile_info = false;
+            let mut open_profile_readme = false;
+            let mut export_profile = false;
+            let mut import_profile = false;
+            let mut export_enabled = false;
+            let mut copy_mod_list = false;
+            let health = self.profile_health();
             let buttons = |ui: &mut Ui, mod_data: &mut ModData| {
+                let color = match health.grade() {
+                    Grade::Good => Color32::LIGHT_GREEN,
+                    Grade::Fair => ui.visuals().warn_fg_color,
+                    Grade::Poor => ui.visuals().error_fg_color,
+                };
+                let issues = health
+                    .issues
+                    .iter()
+                    .map(|(issue, points)| format!("\n• {issue} (-{points})"))
+                    .collect::<String>();
+                ui.label(RichText::new(format!("♥ {}", health.score)).color(color))
+                    .on_hover_text(format!(
+                        "Profile health: {}/{}{issues}",
+                        health.score,
+                        crate::profile_health::MAX_SCORE
+                    ));
+
+                if ui
+                    .button("Merge")
+                    .on_hover_text_at_pointer("Merge another profile into this one")
+                    .clicked()
+                {
+                    open_merge = true;
+                }
+
+                if ui
+                    .button("📅")
+                    .on_hover_text_at_pointer("Pin all mods to their versions as of a date")
+                    .clicked()
+                {
+                    open_pin_to_date = true;
+                }
+
+                if ui
+                    .button("🗄")
+                    .on_hover_text_at_pointer("Seasons, archived and deleted profiles")
+                    .clicked()
+                {
+                    open_archive = true;
+                }
+
+                if ui
+                    .button("⇅")
+                    .on_hover_text_at_pointer("Order and favorite profiles")
+                    .clicked()
+                {
+                    open_profile_order = true;
+                }
+
+                if ui
+                    .button("✏")
+                    .on_hover_text_at_pointer("Icon and description of the profile")
+                    .clicked()
+                {
+                    open_profile_info = true;
+                }
+
+                if ui
+                    .button("📖")
+                    .on_hover_text_at_pointer("Write the profile's readme")
+                    .clicked()
+                {
+                    open_profile_readme = true;
+                }
+
+                if ui
+                    .button("📤")
+                    .on_hover_text_at_pointer(
+                        "Export the profile with its folders and loadouts to a .mintprofile file",
+                    )
+                    .clicked()
+                {
+                    export_profile = true;
+                }
+
+                if ui
+                    .button("📥")
+                    .on_hover_text_at_pointer("Import a profile from a .mintprofile file")
+                    .clicked()
+                {
+                    import_profile = true;
+                }
+
                 if ui
                     .button("📋")
                     .on_hover_text_at_pointer("Copy profile mods")
@@ -2774,6 +8416,37 @@ impl eframe::App for App {
                     ui.ctx().copy_text(mods);
                 }
 
+                if ui
+                    .button("📑")
+                    .on_hover_text_at_pointer(
+                        "Copy profile mods with versions\nWith their versions, priorities and \
+                         enabled states, so pasting them into mint sets up the same mods. Older \
+                         versions of mint only take plain lists from \"Copy profile mods\"",
+                    )
+                    .clicked()
+                {
+                    copy_mod_list = true;
+                }
+
+                if ui
+                    .button("📊")
+                    .on_hover_text_at_pointer(
+                        "Export the enabled mods with their versions, priorities after folder \
+                         overrides and folders as JSON or CSV",
+                    )
+                    .clicked()
+                {
+                    export_enabled = true;
+                }
+
+                if ui
+                    .button("QR")
+                    .on_hover_text_at_pointer("Show profile mods as a QR code")
+                    .clicked()
+                {
+                    open_qr = true;
+                }
+
                 // TODO find better icon, flesh out multiple-view usage, fix GUI locking
                 /*
                 if ui
@@ -2786,6 +8459,10 @@ impl eframe::App for App {
                 */
             };
 
+            let (previous, profile_count) = (
+                self.state.mod_data.active_profile.clone(),
+                self.state.mod_data.profiles.len(),
+            );
             let (modified, pending_profile_delete) = named_combobox::ui(
                 ui,
                 "profile",
@@ -2794,9 +8471,146 @@ impl eframe::App for App {
             );
             if modified {
                 self.state.mod_data.save().unwrap();
+                let mod_data = &self.state.mod_data;
+                // renamed, as opposed to added, duplicated or deleted
+                if mod_data.profiles.len() == profile_count
+                    && !mod_data.profiles.contains_key(&previous)
+                {
+                    Sessions::rename_profile(
+                        &self.state.dirs.data_dir,
+                        &previous,
+                        &mod_data.active_profile,
+                    );
+                }
+            }
+            if open_merge {
+                self.record_feature("merge_profiles");
+                self.merge_profiles_window = Some(WindowMergeProfiles::new(
+                    self.state.mod_data.active_profile.clone(),
+                ));
+            }
+            if open_qr {
+                self.record_feature("profile_qr_code");
+                self.qr_window = Some(WindowQr::new(&self.state));
+            }
+            if open_archive {
+                self.record_feature("profile_archive");
+                self.archive_window = Some(WindowArchive::new(&self.state));
+            }
+            if open_profile_order {
+                self.record_feature("profile_order");
+                self.profile_order_window = Some(WindowProfileOrder);
+            }
+            if open_profile_info {
+                self.record_feature("profile_info");
+                self.profile_info_window = Some(WindowProfileInfo::new(&self.state));
+            }
+            if export_profile {
+                self.record_feature("export_profile");
+                let profile = self.state.mod_data.active_profile.clone();
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_file_name(format!("{profile}.{PROFILE_EXTENSION}"))
+                    .add_filter("mint profile", &[PROFILE_EXTENSION])
+                    .save_file()
+                {
+                    let res = self.state.mod_data.export_profile(&profile, &path);
+                    self.last_action = Some(match res {
+                        Ok(()) => LastAction::success(format!("Exported to {}", path.display())),
+                        Err(e) => self.error_action(MintError::from(e).info()),
+                    });
+                }
+            }
+            if copy_mod_list {
+                self.record_feature("copy_mod_list");
+                let profile = self.state.mod_data.active_profile.clone();
+                let mods = mod_list::from_profile(&self.state, &profile);
+                ui.ctx().copy_text(mod_list::to_string(&mods));
+                self.last_action = Some(LastAction::success(format!(
+                    "Copied {} mods with their versions",
+                    mods.len()
+                )));
+            }
+            if export_enabled {
+                self.record_feature("export_enabled_mods");
+                let profile = self.state.mod_data.active_profile.clone();
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_file_name(format!("{profile} enabled mods.json"))
+                    .add_filter("JSON", &["json"])
+                    .add_filter("CSV", &["csv"])
+                    .save_file()
+                {
+                    let mods = enabled_mods::collect(&self.state, &profile);
+                    self.last_action = Some(match enabled_mods::write(&path, &mods) {
+                        Ok(()) => LastAction::success(format!(
+                            "Exported {} enabled mods to {}",
+                            mods.len(),
+                            path.display()
+                        )),
+                        Err(e) => {
+                            LastAction::failure(format!("failed to export enabled mods: {e}"))
+                        }
+                    });
+                }
+            }
+            if import_profile
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("mint profile", &[PROFILE_EXTENSION])
+                    .pick_file()
+            {
+                self.record_feature("import_profile");
+                self.last_action = Some(match self.state.mod_data.import_profile(&path) {
+                    Ok(name) => {
+                        self.state.mod_data.active_profile = name.clone();
+                        self.state.mod_data.save().unwrap();
+                        LastAction::success(format!("Imported profile {name:?}"))
+                    }
+                    Err(e) => self.error_action(MintError::from(e).info()),
+                });
+            }
+            if open_profile_readme {
+                self.record_feature("profile_readme");
+                self.profile_readme_window = Some(WindowProfileReadme {
+                    profile: self.state.mod_data.active_profile.clone(),
+                    text: self
+                        .state
+                        .mod_data
+                        .get_active_profile()
+                        .readme
+                        .clone()
+                        .unwrap_or_default(),
+                    preview: false,
+                });
+            }
+            if open_pin_to_date {
+                self.record_feature("pin_to_date");
+                self.pin_to_date_window = Some(WindowPinToDate {
+                    profile: self.state.mod_data.active_profile.clone(),
+                    date: String::new(),
+                    status: None,
+                });
             }
             if let Some(profile_name) = pending_profile_delete {
-                self.pending_deletion = Some(PendingDeletion::Profile { profile_name });
+                let unique = self.state.mod_data.unique_contents(&profile_name);
+                self.pending_deletion = Some(PendingDeletion::Profile {
+                    profile_name,
+                    unique,
+                    move_to: None,
+                    export_status: None,
+                });
+            }
+
+            if let Some(readme) = &self.state.mod_data.get_active_profile().readme {
+                egui::CollapsingHeader::new("📖 Readme")
+                    .id_salt(("profile-readme", &self.state.mod_data.active_profile))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_salt("profile-readme")
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                CommonMarkViewer::new().show(ui, &mut self.cache, readme);
+                            });
+                    });
             }
 
             ui.separator();
@@ -2805,6 +8619,23 @@ impl eframe::App for App {
                 if self.resolve_mod_rid.is_some() {
                     ui.spinner();
                 }
+                if ui
+                    .add_enabled(self.resolve_mod_rid.is_none(), egui::Button::new("📷"))
+                    .on_hover_text("Add the mods of a QR code from a screenshot or photo")
+                    .clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .add_filter("Image", &["png", "jpg", "jpeg"])
+                        .pick_file()
+                {
+                    match qr::decode_image(&path) {
+                        Ok(text) => {
+                            self.resolve_mod = text;
+                            self.resolve_mods(ctx);
+                            self.problematic_mod_id = None;
+                        }
+                        Err(e) => self.last_action = Some(LastAction::failure(e)),
+                    }
+                }
                 ui.with_layout(ui.layout().with_main_justify(true), |ui| {
                     // define multiline layouter to be able to show multiple lines in a single line widget
                     let font_id = FontSelection::default().resolve(ui.style());
@@ -2825,8 +8656,9 @@ impl eframe::App for App {
                             .layouter(&mut multiline_layouter)
                             .hint_text("Add mod..."),
                     );
+                    tutorial::register(ctx, TourTarget::AddMod, resolve.rect);
                     if is_committed(&resolve) {
-                        message::ResolveMods::send(self, ctx, self.parse_mods(), false);
+                        self.resolve_mods(ctx);
                         self.problematic_mod_id = None;
                     }
                 });
@@ -2883,12 +8715,169 @@ impl eframe::App for App {
                         }
                     });
 
+                help::button(
+                    ui,
+                    match sort_category {
+                        Some(SortBy::Priority) => HelpTopic::Priorities,
+                        Some(SortBy::ApprovalCategory) => HelpTopic::ApprovalCategories,
+                        Some(SortBy::RequiredStatus) => HelpTopic::RequiredTags,
+                        _ => HelpTopic::Overview,
+                    },
+                );
+
                 ui.add_space(8.);
 
                 // Create folder button
                 if ui.button("📁+").on_hover_text("Create new folder").clicked() {
                     self.create_folder_popup = Some(String::new());
                 }
+                help::button(ui, HelpTopic::Folders);
+
+                ui.add_space(8.);
+
+                // Loadouts: named enable/disable snapshots of the active profile
+                let loadout_names = self
+                    .state
+                    .mod_data
+                    .loadouts
+                    .get(&profile)
+                    .map(|l| l.keys().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let mut apply_loadout = None;
+                let mut remove_loadout = None;
+                egui::ComboBox::from_id_salt("loadout-dropdown")
+                    .selected_text("Loadout")
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        if ui.button("💾 Save current...").clicked() {
+                            self.save_loadout_popup = Some(String::new());
+                        }
+                        if !loadout_names.is_empty() {
+                            ui.separator();
+                        }
+                        for name in &loadout_names {
+                            ui.horizontal(|ui| {
+                                if ui.button("🗑").on_hover_text("Delete loadout").clicked() {
+                                    remove_loadout = Some(name.clone());
+                                }
+                                if ui.button(name).on_hover_text("Apply loadout").clicked() {
+                                    apply_loadout = Some(name.clone());
+                                }
+                            });
+                        }
+                    })
+                    .response
+                    .on_hover_text("Saved sets of enabled mods and folders for this profile");
+                if let Some(name) = apply_loadout
+                    && self.state.mod_data.apply_loadout(&profile, &name)
+                {
+                    self.record_feature("apply_loadout");
+                    self.state.mod_data.save().unwrap();
+                    self.last_action =
+                        Some(LastAction::success(format!("applied loadout {name:?}")));
+                }
+                if let Some(name) = remove_loadout {
+                    self.state.mod_data.remove_loadout(&profile, &name);
+                    self.state.mod_data.save().unwrap();
+                }
+
+                ui.add_space(8.);
+
+                // Tags: turn every mod given a tag on or off, e.g. when switching activities
+                let mod_tags = self.state.mod_data.mod_tags(&profile);
+                let mut set_tag = None;
+                egui::ComboBox::from_id_salt("tag-dropdown")
+                    .selected_text("Tags")
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        if mod_tags.is_empty() {
+                            ui.weak("Tag mods from their right-click menu");
+                        }
+                        for tag in &mod_tags {
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button("✔")
+                                    .on_hover_text("Enable mods with this tag")
+                                    .clicked()
+                                {
+                                    set_tag = Some((tag.clone(), true));
+                                }
+                                if ui
+                                    .button("❌")
+                                    .on_hover_text("Disable mods with this tag")
+                                    .clicked()
+                                {
+                                    set_tag = Some((tag.clone(), false));
+                                }
+                                ui.label(tag);
+                            });
+                        }
+                    })
+                    .response
+                    .on_hover_text("Enable or disable all mods with a tag at once");
+                if let Some((tag, enabled)) = set_tag {
+                    let changed = self.state.mod_data.set_tag_enabled(&profile, &tag, enabled);
+                    self.record_feature(if enabled { "enable_tag" } else { "disable_tag" });
+                    self.state.mod_data.save().unwrap();
+                    self.last_action = Some(LastAction::success(format!(
+                        "{} {changed} mods tagged {tag:?}",
+                        if enabled { "enabled" } else { "disabled" }
+                    )));
+                }
+
+                ui.add_space(8.);
+
+                // What to do once the game exits, if mint launched it
+                let mut actions = self
+                    .state
+                    .mod_data
+                    .post_game
+                    .get(&profile)
+                    .cloned()
+                    .unwrap_or_default();
+                let other_profiles = self
+                    .state
+                    .mod_data
+                    .profiles
+                    .keys()
+                    .filter(|p| **p != profile)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let before = actions.clone();
+                egui::ComboBox::from_id_salt("post-game-dropdown")
+                    .selected_text(if actions.is_empty() {
+                        "After game"
+                    } else {
+                        "After game ✔"
+                    })
+                    .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                    .show_ui(ui, |ui| {
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        ui.checkbox(&mut actions.snapshot_saves, "Snapshot save files");
+                        ui.checkbox(&mut actions.uninstall, "Uninstall mods");
+                        ui.separator();
+                        ui.label("Switch to profile:");
+                        ui.radio_value(&mut actions.switch_profile, None, "(keep this one)");
+                        for name in &other_profiles {
+                            ui.radio_value(&mut actions.switch_profile, Some(name.clone()), name);
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "What to do when the game closes. Only works if mint launched the game, \
+                         via `mint launch %command%` in Steam's launch options.",
+                    );
+                if actions != before {
+                    if actions.is_empty() {
+                        self.state.mod_data.post_game.remove(&profile);
+                    } else {
+                        self.state
+                            .mod_data
+                            .post_game
+                            .insert(profile.clone(), actions);
+                    }
+                    self.state.mod_data.save().unwrap();
+                }
 
                 ui.add_space(8.);
 
@@ -2966,7 +8955,7 @@ impl eframe::App for App {
                     }
 
                     self.resolve_mod = mods.trim().to_string();
-                    message::ResolveMods::send(self, ctx, self.parse_mods(), false);
+                    self.resolve_mods(ctx);
                     self.problematic_mod_id = None;
                 }
                 for e in &i.events {
@@ -2978,7 +8967,7 @@ impl eframe::App for App {
                                 && !is_anything_focused
                             {
                                 self.resolve_mod = s.trim().to_string();
-                                message::ResolveMods::send(self, ctx, self.parse_mods(), false);
+                                self.resolve_mods(ctx);
                             }
                         }
                         egui::Event::Text(text) => {
@@ -2993,6 +8982,14 @@ impl eframe::App for App {
                 }
             });
         });
+
+        if let Some(tour) = &mut self.tour
+            && tour.show(ctx) == TourStatus::Done
+        {
+            self.tour = None;
+            self.state.config.tutorial_completed = true;
+            self.state.config.save().unwrap();
+        }
     }
 }
 
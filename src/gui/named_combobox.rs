@@ -1,6 +1,6 @@
 use super::{colors, custom_popup_above_or_below_widget, is_committed};
 
-use crate::state::{ModData_v0_2_0 as ModData, ModProfile_v0_2_0 as ModProfile};
+use crate::state::{ModData_v0_3_0 as ModData, ModProfile_v0_3_0 as ModProfile};
 
 #[derive(Debug, Clone)]
 struct NamePopup {
@@ -26,7 +26,22 @@ pub trait NamedEntries<E> {
     fn remove_selected(&mut self);
     fn rename_selected(&mut self, new_name: String);
     fn duplicate_selected(&mut self, new_name: String);
+    /// Entries offered in the dropdown.
     fn entries<'s>(&'s mut self) -> Box<dyn Iterator<Item = (&'s String, &'s E)> + 's>;
+    /// How the entry is shown in the dropdown.
+    fn label(name: &str, _entry: &E) -> String
+    where
+        Self: Sized,
+    {
+        name.to_owned()
+    }
+    /// Shown when hovering the entry in the dropdown.
+    fn hover_text(_entry: &E) -> Option<&str>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 impl NamedEntries<ModProfile> for ModData {
@@ -52,15 +67,51 @@ impl NamedEntries<ModProfile> for ModData {
     fn rename_selected(&mut self, new_name: String) {
         let tmp = self.profiles.remove(&self.active_profile).unwrap();
         self.profiles.insert(new_name.clone(), tmp);
+        if let Some(loadouts) = self.loadouts.remove(&self.active_profile) {
+            self.loadouts.insert(new_name.clone(), loadouts);
+        }
+        if let Some(actions) = self.post_game.remove(&self.active_profile) {
+            self.post_game.insert(new_name.clone(), actions);
+        }
+        for actions in self.post_game.values_mut() {
+            if actions.switch_profile.as_ref() == Some(&self.active_profile) {
+                actions.switch_profile = Some(new_name.clone());
+            }
+        }
+        for name in &mut self.profile_order {
+            if *name == self.active_profile {
+                *name = new_name.clone();
+            }
+        }
         self.active_profile = new_name;
     }
     fn duplicate_selected(&mut self, new_name: String) {
-        let new = self.get_active_profile().clone();
-        self.profiles.insert(new_name.clone(), new);
-        self.active_profile = new_name;
+        self.duplicate_profile(&self.active_profile.clone(), new_name);
     }
     fn entries<'s>(&'s mut self) -> Box<dyn Iterator<Item = (&'s String, &'s ModProfile)> + 's> {
-        Box::new(self.profiles.iter())
+        let this = &*self;
+        Box::new(
+            this.ordered_profiles()
+                .into_iter()
+                .filter_map(move |name| this.profiles.get_key_value(&name))
+                .filter(move |(name, p)| !p.archived || **name == this.active_profile),
+        )
+    }
+    fn label(name: &str, profile: &ModProfile) -> String {
+        let mut label = name.to_owned();
+        if let Some(icon) = &profile.icon {
+            label.insert_str(0, &format!("{icon} "));
+        }
+        if profile.favorite {
+            label.insert_str(0, "★ ");
+        }
+        if let Some(season) = &profile.season {
+            label.push_str(&format!(" ({season})"));
+        }
+        label
+    }
+    fn hover_text(profile: &ModProfile) -> Option<&str> {
+        profile.description.as_deref()
     }
 }
 
@@ -182,9 +233,9 @@ fn mk_duplicate<E, N>(ui: &mut egui::Ui, name: &str, entries: &mut N, modified:
 where
     N: NamedEntries<E>,
 {
-    let response = ui
-        .button("🗐")
-        .on_hover_text_at_pointer(format!("Duplicate {name}"));
+    let response = ui.button("Duplicate").on_hover_text_at_pointer(format!(
+        "Copy the selected {name} with everything in it under a new name"
+    ));
     let popup_id = ui.make_persistent_id(format!("duplicate-{name}"));
     if response.clicked() {
         ui.memory_mut(|mem| mem.open_popup(popup_id));
@@ -208,15 +259,27 @@ where
     N: NamedEntries<E>,
 {
     let mut selected = entries.selected_name().to_owned();
+    let (selected_text, selected_hover) = entries
+        .entries()
+        .find(|(k, _)| **k == selected)
+        .map(|(k, e)| (N::label(k, e), N::hover_text(e).map(str::to_owned)))
+        .unwrap_or_else(|| (selected.clone(), None));
 
-    egui::ComboBox::from_id_salt(format!("dropdown-{name}"))
+    let response = egui::ComboBox::from_id_salt(format!("dropdown-{name}"))
         .width(ui.available_width())
-        .selected_text(selected.clone())
+        .selected_text(selected_text)
         .show_ui(ui, |ui| {
-            entries.entries().for_each(|(k, _)| {
-                ui.selectable_value(&mut selected, k.to_owned(), k);
+            entries.entries().for_each(|(k, e)| {
+                let response = ui.selectable_value(&mut selected, k.to_owned(), N::label(k, e));
+                if let Some(text) = N::hover_text(e) {
+                    response.on_hover_text(text);
+                }
             })
-        });
+        })
+        .response;
+    if let Some(text) = selected_hover {
+        response.on_hover_text(text);
+    }
 
     if selected != entries.selected_name() {
         entries.select(selected);
@@ -0,0 +1,63 @@
+//! QR codes of mod lists, for players in the same room to grab a profile's mods with a phone or
+//! scan them from a screenshot instead of passing the list around by chat.
+
+use std::path::Path;
+
+use eframe::egui;
+use qrcode::{Color, EcLevel, QrCode};
+
+/// Encode `text` as a QR code. Shown on a screen it doesn't need much error correction, which
+/// leaves room for longer lists.
+pub fn encode(text: &str) -> Result<QrCode, String> {
+    QrCode::with_error_correction_level(text.as_bytes(), EcLevel::L).map_err(|e| match e {
+        qrcode::types::QrError::DataTooLong => format!(
+            "the mod list is too long for a QR code ({} bytes), copy it instead",
+            text.len()
+        ),
+        e => e.to_string(),
+    })
+}
+
+/// Draw `code` as a square of `size` points, with the quiet zone scanners need around it.
+pub fn show(ui: &mut egui::Ui, code: &QrCode, size: f32) -> egui::Response {
+    let (response, painter) = ui.allocate_painter(egui::vec2(size, size), egui::Sense::hover());
+    let rect = response.rect;
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+
+    let width = code.width();
+    let quiet_zone = 4;
+    let module = size / (width + 2 * quiet_zone) as f32;
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color == Color::Dark {
+            let (x, y) = (i % width + quiet_zone, i / width + quiet_zone);
+            let min = rect.min + egui::vec2(x as f32, y as f32) * module;
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::vec2(module, module)),
+                0.0,
+                egui::Color32::BLACK,
+            );
+        }
+    }
+    response
+}
+
+/// Read the text of the first QR code found in the image at `path`.
+pub fn decode_image(path: &Path) -> Result<String, String> {
+    let image = image::open(path)
+        .map_err(|e| format!("failed to open image: {e}"))?
+        .to_luma8();
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        |x, y| image.get_pixel(x as u32, y as u32).0[0],
+    );
+    let grid = prepared
+        .detect_grids()
+        .into_iter()
+        .next()
+        .ok_or_else(|| "no QR code found in the image".to_string())?;
+    let (_, text) = grid
+        .decode()
+        .map_err(|e| format!("failed to read QR code: {e}"))?;
+    Ok(text)
+}
@@ -0,0 +1,129 @@
+//! Guided tour for first-time users. Widgets register their rect each frame with [`register`] and
+//! the tour highlights them one step at a time.
+
+use egui::{Align2, Color32, Id, Rect, Stroke, Vec2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TourTarget {
+    AddMod,
+    Install,
+    Settings,
+    Lint,
+}
+
+struct Step {
+    target: TourTarget,
+    title: &'static str,
+    text: &'static str,
+}
+
+const STEPS: &[Step] = &[
+    Step {
+        target: TourTarget::AddMod,
+        title: "Add mods",
+        text: "Paste mod.io URLs, .pak/.zip/.7z/.rar URLs or local file paths here and press \
+               Enter. You can also drop files onto the window.",
+    },
+    Step {
+        target: TourTarget::Install,
+        title: "Install",
+        text: "Builds a bundle from the enabled mods of the selected profile and installs it into \
+               the game. Start the game normally afterwards.",
+    },
+    Step {
+        target: TourTarget::Settings,
+        title: "Settings",
+        text: "Set the path to the game if it was not found automatically, and configure mod.io \
+               access, backups and more.",
+    },
+    Step {
+        target: TourTarget::Lint,
+        title: "Lint mods",
+        text: "Checks the current profile for conflicts and other common problems before you \
+               install it.",
+    },
+];
+
+fn rect_id(target: TourTarget) -> Id {
+    Id::new(("tour-target", target))
+}
+
+/// Record where `target` was drawn this frame so the tour can point at it.
+pub fn register(ctx: &egui::Context, target: TourTarget, rect: Rect) {
+    ctx.data_mut(|d| d.insert_temp(rect_id(target), rect));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourStatus {
+    Running,
+    /// The tour was completed or skipped and should not be shown again.
+    Done,
+}
+
+#[derive(Debug, Default)]
+pub struct Tour {
+    step: usize,
+}
+
+impl Tour {
+    pub fn show(&mut self, ctx: &egui::Context) -> TourStatus {
+        let Some(step) = STEPS.get(self.step) else {
+            return TourStatus::Done;
+        };
+        let rect = ctx.data(|d| d.get_temp::<Rect>(rect_id(step.target)));
+
+        if let Some(rect) = rect {
+            ctx.layer_painter(egui::LayerId::new(
+                egui::Order::Foreground,
+                Id::new("tour-highlight"),
+            ))
+            .rect_stroke(
+                rect.expand(4.0),
+                4.0,
+                Stroke::new(2.0, Color32::LIGHT_BLUE),
+                egui::StrokeKind::Outside,
+            );
+        }
+
+        let mut status = TourStatus::Running;
+        let window = egui::Window::new(step.title)
+            .id(Id::new("tour-window"))
+            .collapsible(false)
+            .resizable(false)
+            .order(egui::Order::Foreground);
+        // place the window next to the highlighted widget, on whichever side has more room
+        let window = match rect {
+            Some(rect) if rect.center().y > ctx.screen_rect().center().y => window
+                .pivot(Align2::CENTER_BOTTOM)
+                .fixed_pos(rect.center_top() - Vec2::new(0.0, 12.0)),
+            Some(rect) => window
+                .pivot(Align2::CENTER_TOP)
+                .fixed_pos(rect.center_bottom() + Vec2::new(0.0, 12.0)),
+            None => window.anchor(Align2::CENTER_CENTER, Vec2::ZERO),
+        };
+        window.show(ctx, |ui| {
+            ui.set_max_width(320.0);
+            ui.label(step.text);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.weak(format!("{} of {}", self.step + 1, STEPS.len()));
+                if ui.button("Skip tour").clicked() {
+                    status = TourStatus::Done;
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let last = self.step + 1 == STEPS.len();
+                    if ui.button(if last { "Finish" } else { "Next" }).clicked() {
+                        self.step += 1;
+                        if last {
+                            status = TourStatus::Done;
+                        }
+                    }
+                    if self.step > 0 && ui.button("Back").clicked() {
+                        self.step -= 1;
+                    }
+                });
+            });
+        });
+        status
+    }
+}
@@ -0,0 +1,314 @@
+//! Installation health check: the common reasons an install fails, checked in the order support
+//! volunteers go through them by hand. Each check reports what it found and how to fix a failure.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use fs_err as fs;
+use mint_lib::DRGInstallation;
+use serde::Serialize;
+
+use crate::is_drg_pak;
+
+/// Free space below which installs and mod downloads are likely to fail.
+const MIN_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+/// Check names and URLs of the hosts mods are usually downloaded from.
+const PROVIDER_HOSTS: &[(&str, &str)] = &[("Connection to mod.io", "https://api.mod.io/v1")];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not run because an earlier check it depends on failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a failure or warning.
+    pub fix: Option<&'static str>,
+}
+
+impl CheckResult {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: &'static str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: &'static str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix),
+        }
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skipped,
+            detail: "requires a valid DRG pak path".to_string(),
+            fix: None,
+        }
+    }
+}
+
+/// Run all checks. `pak_path` is the configured DRG pak, `cache_dir` where mods are downloaded to.
+pub async fn run(pak_path: Option<PathBuf>, cache_dir: PathBuf) -> Vec<CheckResult> {
+    let mut results = tokio::task::spawn_blocking(move || local_checks(pak_path, &cache_dir))
+        .await
+        .unwrap_or_default();
+    for &(name, url) in PROVIDER_HOSTS {
+        results.push(check_connectivity(name, url).await);
+    }
+    results
+}
+
+fn local_checks(pak_path: Option<PathBuf>, cache_dir: &Path) -> Vec<CheckResult> {
+    let (pak, installation) = check_pak_path(pak_path.as_deref());
+    let mut results = vec![pak];
+    match installation {
+        Some(installation) => {
+            results.push(check_write_permissions(&installation));
+            results.push(check_disk_space(&[
+                installation.paks_path().as_path(),
+                cache_dir,
+            ]));
+            results.push(check_cache_drive(&installation, cache_dir));
+            results.push(check_file_locks(&installation));
+            results.push(check_game_running());
+        }
+        None => {
+            results.push(CheckResult::skipped("Write permissions"));
+            results.push(check_disk_space(&[cache_dir]));
+            results.push(CheckResult::skipped("Cache drive"));
+            results.push(CheckResult::skipped("File locks"));
+            results.push(check_game_running());
+        }
+    }
+    results
+}
+
+fn check_pak_path(pak_path: Option<&Path>) -> (CheckResult, Option<DRGInstallation>) {
+    const NAME: &str = "DRG pak path";
+    let Some(pak_path) = pak_path else {
+        return (
+            CheckResult::fail(
+                NAME,
+                "not configured",
+                "Select FSD-WindowsNoEditor.pak (FSD-WinGDK.pak for the Microsoft Store version) \
+                 in the settings.",
+            ),
+            None,
+        );
+    };
+    if !pak_path.is_file() {
+        return (
+            CheckResult::fail(
+                NAME,
+                format!("{} does not exist", pak_path.display()),
+                "The game was moved or uninstalled. Select the pak again in the settings.",
+            ),
+            None,
+        );
+    }
+    if let Err(e) = is_drg_pak(pak_path) {
+        return (
+            CheckResult::fail(
+                NAME,
+                format!("{} is not a valid DRG pak: {e}", pak_path.display()),
+                "Make sure the right file is selected, otherwise verify the game files.",
+            ),
+            None,
+        );
+    }
+    match DRGInstallation::from_pak_path(pak_path) {
+        Ok(installation) => (
+            CheckResult::pass(
+                NAME,
+                format!(
+                    "{:?} install at {}",
+                    installation.installation_type,
+                    pak_path.display()
+                ),
+            ),
+            Some(installation),
+        ),
+        Err(e) => (
+            CheckResult::fail(
+                NAME,
+                format!("unrecognized install: {e}"),
+                "Select the pak inside the \"Deep Rock Galactic\" directory under FSD/Content/Paks.",
+            ),
+            None,
+        ),
+    }
+}
+
+fn check_write_permissions(installation: &DRGInstallation) -> CheckResult {
+    const NAME: &str = "Write permissions";
+    for dir in [installation.paks_path(), installation.binaries_directory()] {
+        if let Err(e) = tempfile::tempfile_in(&dir) {
+            return CheckResult::fail(
+                NAME,
+                format!("cannot write to {}: {e}", dir.display()),
+                "Run mint as a user allowed to modify the game, or move the game out of protected \
+                 folders such as Program Files.",
+            );
+        }
+    }
+    CheckResult::pass(NAME, "game directories are writable")
+}
+
+fn check_disk_space(paths: &[&Path]) -> CheckResult {
+    const NAME: &str = "Disk space";
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let mut details = vec![];
+    let mut low = false;
+    for path in paths {
+        let Some(disk) = find_disk(&disks, path) else {
+            continue;
+        };
+        let free = disk.available_space();
+        low |= free < MIN_FREE_SPACE;
+        details.push(format!(
+            "{:.1} GiB free on {}",
+            free as f64 / (1024.0 * 1024.0 * 1024.0),
+            disk.mount_point().display()
+        ));
+    }
+    details.dedup();
+    if details.is_empty() {
+        CheckResult::warn(
+            NAME,
+            "could not determine free space",
+            "Make sure the game and mint's cache have at least 1 GiB of free space.",
+        )
+    } else if low {
+        CheckResult::fail(
+            NAME,
+            details.join(", "),
+            "Free up at least 1 GiB on the drives of the game and mint's cache.",
+        )
+    } else {
+        CheckResult::pass(NAME, details.join(", "))
+    }
+}
+
+/// The disk with the longest mount point containing `path`.
+fn find_disk<'a>(disks: &'a sysinfo::Disks, path: &Path) -> Option<&'a sysinfo::Disk> {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    disks
+        .list()
+        .iter()
+        .filter(|d| path.starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+}
+
+/// The mount point of the game's drive if mint's cache is on a different one, so every install
+/// reads all mods across drives.
+pub fn game_drive_if_elsewhere(
+    installation: &DRGInstallation,
+    cache_dir: &Path,
+) -> Option<PathBuf> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let game = find_disk(&disks, &installation.paks_path())?;
+    let cache = find_disk(&disks, cache_dir)?;
+    (game.mount_point() != cache.mount_point()).then(|| game.mount_point().to_path_buf())
+}
+
+fn check_cache_drive(installation: &DRGInstallation, cache_dir: &Path) -> CheckResult {
+    const NAME: &str = "Cache drive";
+    match game_drive_if_elsewhere(installation, cache_dir) {
+        Some(game_drive) => CheckResult::warn(
+            NAME,
+            format!(
+                "the cache at {} is not on the game's drive {}",
+                cache_dir.display(),
+                game_drive.display()
+            ),
+            "Installs copy every mod across drives, which is slow on hard drives and USB drives. \
+             Use \"Move to game drive\" next to the cache directory in the settings.",
+        ),
+        None => CheckResult::pass(NAME, "the cache is on the game's drive"),
+    }
+}
+
+fn check_file_locks(installation: &DRGInstallation) -> CheckResult {
+    const NAME: &str = "File locks";
+    let files = [
+        installation.paks_path().join("mods_P.pak"),
+        installation
+            .binaries_directory()
+            .join(installation.installation_type.hook_dll_name()),
+    ];
+    for file in files.iter().filter(|f| f.exists()) {
+        if let Err(e) = fs::OpenOptions::new().append(true).open(file) {
+            return CheckResult::fail(
+                NAME,
+                format!("{} cannot be opened for writing: {e}", file.display()),
+                "Another program is using the file, usually an antivirus scanning it. Close the \
+                 game, then add an exception for the game directory to your antivirus.",
+            );
+        }
+    }
+    CheckResult::pass(NAME, "installed files are not locked")
+}
+
+fn check_game_running() -> CheckResult {
+    const NAME: &str = "Game running";
+    let mut system = sysinfo::System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    let running = system.processes().values().any(|p| {
+        let name = p.name().to_string_lossy().to_lowercase();
+        name == "fsd-win64-shipping.exe" || name == "fsd-wingdk-shipping.exe"
+    });
+    if running {
+        CheckResult::fail(
+            NAME,
+            "Deep Rock Galactic is running",
+            "Close the game before installing or uninstalling mods.",
+        )
+    } else {
+        CheckResult::pass(NAME, "the game is not running")
+    }
+}
+
+async fn check_connectivity(name: &'static str, url: &str) -> CheckResult {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build();
+    let res = match client {
+        Ok(client) => client.get(url).send().await,
+        Err(e) => Err(e),
+    };
+    // any response at all means the host is reachable
+    match res {
+        Ok(response) => CheckResult::pass(name, format!("reachable ({})", response.status())),
+        Err(e) => CheckResult::fail(
+            name,
+            format!("{url} is unreachable: {e}"),
+            "Check your internet connection, and whether a proxy or firewall blocks mint.",
+        ),
+    }
+}
@@ -1,13 +1,17 @@
-use std::collections::{HashMap, HashSet};
+pub mod locks;
+pub mod payload;
+pub mod ue4ss;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{BufReader, BufWriter, Cursor, ErrorKind, Read, Seek, Write};
 use std::path::{Path, PathBuf};
 
 use fs_err as fs;
 
 use repak::PakWriter;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use snafu::{Whatever, prelude::*};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use uasset_utils::asset_registry::{AssetRegistry, Readable as _, Writable as _};
 use uasset_utils::paths::{PakPath, PakPathBuf, PakPathComponentTrait};
 use uasset_utils::splice::{
@@ -16,10 +20,13 @@ use uasset_utils::splice::{
 use unreal_asset::AssetBuilder;
 use unreal_asset::engine_version::EngineVersion;
 
+use crate::lobby;
 use crate::mod_lints::LintError;
-use crate::providers::{ModInfo, ProviderError, ReadSeek};
+use crate::providers::{ModInfo, ModSpecification, ProviderError, ReadSeek};
 use mint_lib::DRGInstallation;
 use mint_lib::mod_info::{ApprovalStatus, Meta, MetaConfig, MetaMod};
+use mint_lib::paths::long_path;
+use payload::ModPayload;
 
 use unreal_asset::{
     Asset,
@@ -34,6 +41,53 @@ use unreal_asset::{
     types::vector::Vector,
 };
 
+#[cfg(feature = "hook")]
+const HOOK_DLL: &[u8] = include_bytes!(env!("CARGO_CDYLIB_FILE_HOOK_hook"));
+
+/// File next to the hook DLL recording the size of the hook as installed, so a later check can
+/// tell it apart from the game's original DLL of the same name even after mint was updated.
+#[cfg(feature = "hook")]
+const HOOK_SIZE_FILE: &str = "mint_hook_size.txt";
+
+#[cfg(feature = "hook")]
+fn hook_missing(binaries: &Path, hook_dll_name: &str) -> bool {
+    let Ok(meta) = binaries.join(hook_dll_name).metadata() else {
+        return true;
+    };
+    // installs from before the size was recorded only tell whether the file is there
+    fs::read_to_string(binaries.join(HOOK_SIZE_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse::<u64>().ok())
+        .is_some_and(|size| size != meta.len())
+}
+
+/// Parts of a previous install which are no longer in place, typically because Steam verified the
+/// game files, which restores the hook DLL's original and can remove the mod bundle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MissingInstall {
+    pub mod_pak: bool,
+    pub hook: bool,
+}
+
+impl MissingInstall {
+    pub fn any(&self) -> bool {
+        self.mod_pak || self.hook
+    }
+}
+
+/// Check whether the files written by [`integrate`] are still in place.
+pub fn check_install(installation: &DRGInstallation) -> MissingInstall {
+    let mod_pak = !installation.paks_path().join("mods_P.pak").is_file();
+    #[cfg(feature = "hook")]
+    let hook = hook_missing(
+        &installation.binaries_directory(),
+        installation.installation_type.hook_dll_name(),
+    );
+    #[cfg(not(feature = "hook"))]
+    let hook = false;
+    MissingInstall { mod_pak, hook }
+}
+
 /// Why does the uninstall function require a list of Modio mod IDs?
 /// Glad you ask. The official integration enables *every mod the user has installed* once it gets
 /// re-enabled. We do the user a favor and collect all the installed mods and explicitly add them
@@ -45,12 +99,12 @@ pub fn uninstall<P: AsRef<Path>>(path_pak: P, modio_mods: HashSet<u32>) -> Resul
     let installation = DRGInstallation::from_pak_path(path_pak)
         .whatever_context("failed to get DRG installation")?;
     let path_mods_pak = installation.paks_path().join("mods_P.pak");
-    match fs::remove_file(&path_mods_pak) {
+    locks::retry(&path_mods_pak, || match fs::remove_file(&path_mods_pak) {
         Ok(()) => Ok(()),
         Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
         Err(e) => Err(e),
-    }
-    .with_whatever_context(|_| format!("failed to remove {}", path_mods_pak.display()))?;
+    })
+    .with_whatever_context(|e| format!("failed to remove {}: {e}", path_mods_pak.display()))?;
     #[cfg(feature = "hook")]
     {
         let path_hook_dll = installation
@@ -62,7 +116,13 @@ pub fn uninstall<P: AsRef<Path>>(path_pak: P, modio_mods: HashSet<u32>) -> Resul
             Err(e) => Err(e),
         }
         .with_whatever_context(|_| format!("failed to remove {}", path_hook_dll.display()))?;
+        fs::remove_file(installation.binaries_directory().join(HOOK_SIZE_FILE)).ok();
     }
+    payload::uninstall(&installation)
+        .whatever_context("failed to remove installed payload files")?;
+    ue4ss::uninstall(&installation).whatever_context("failed to remove UE4SS script mods")?;
+    lobby::remove_installed(&installation)
+        .whatever_context("failed to remove the record of installed mods")?;
     uninstall_modio(&installation, modio_mods).ok();
     Ok(())
 }
@@ -189,6 +249,16 @@ pub enum IntegrationError {
         source: Box<dyn std::error::Error + Send + Sync>,
         mod_info: Box<ModInfo>,
     },
+    #[snafu(display(
+        "{} is in use by another program: {}",
+        path.display(),
+        locks::lockers_hint(lockers)
+    ))]
+    FileLocked {
+        path: PathBuf,
+        lockers: Vec<&'static str>,
+        source: std::io::Error,
+    },
     #[snafu(transparent)]
     ProviderError { source: ProviderError },
     #[snafu(display("integration error: {msg}"))]
@@ -216,12 +286,146 @@ impl IntegrationError {
     }
 }
 
+/// A problem which did not stop the install but which the user should know about, such as a file
+/// being left out of the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrationWarning {
+    pub kind: WarningKind,
+    /// Name of the mod it concerns, if any.
+    pub mod_name: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// A file of a mod was not installed.
+    SkippedFile,
+    /// Something could not be done as intended and a fallback was used instead.
+    Fallback,
+    /// The mod uses a format which still works but should be updated by its author.
+    Deprecated,
+    /// The mod's archive is not signed by a trusted signer, see [`crate::signing`].
+    Untrusted,
+}
+
+impl IntegrationWarning {
+    /// Create the warning and log it. Skipped files are routine, e.g. most mods ship an
+    /// AssetRegistry.bin, so they are only logged at debug level and counted in the summary
+    /// [`integrate`] logs once done.
+    pub fn new(kind: WarningKind, mod_info: Option<&ModInfo>, message: impl Into<String>) -> Self {
+        let warning = Self {
+            kind,
+            mod_name: mod_info.map(|m| m.name.clone()),
+            message: message.into(),
+        };
+        match kind {
+            WarningKind::SkippedFile => debug!("{warning}"),
+            _ => warn!("{warning}"),
+        }
+        warning
+    }
+}
+
+impl std::fmt::Display for IntegrationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.mod_name {
+            Some(name) => write!(f, "mod {name:?}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Identifies an asset by a file path in a pak, so its `.uasset`, `.uexp` and `.ubulk` go to the
+/// same mod.
+pub fn asset_key(path: &str) -> String {
+    let path = path.to_ascii_lowercase();
+    match path.rsplit_once('.') {
+        Some((stem, extension)) if !extension.contains('/') && !stem.ends_with('/') => {
+            stem.to_string()
+        }
+        _ => path,
+    }
+}
+
+/// Position in `specs` of the mod that wins each asset of `asset_overrides`, see
+/// [`crate::state::ModProfile::asset_overrides`]. Winners that are not being installed are left
+/// out, their assets go by priority again.
+pub fn asset_winners(
+    specs: &[ModSpecification],
+    asset_overrides: &BTreeMap<String, String>,
+) -> HashMap<String, usize> {
+    asset_overrides
+        .iter()
+        .filter_map(|(asset, url)| {
+            let winner = specs.iter().position(|s| &s.url == url)?;
+            Some((asset.clone(), winner))
+        })
+        .collect()
+}
+
+/// Whether a mod's file is left out of the bundle in favor of the game's own.
+fn is_left_out(path: &str) -> bool {
+    path.rsplit('/').next() == Some("AssetRegistry.bin") || path.ends_with(".ushaderbytecode")
+}
+
+/// The entries of `asset_winners` whose winner installs a file of the asset, as listed by
+/// `files`. The others go by priority again, so the next mod containing the asset gets it.
+fn installed_winners<E>(
+    asset_winners: &HashMap<String, usize>,
+    mut files: impl FnMut(usize) -> Result<Vec<String>, E>,
+) -> Result<HashMap<String, usize>, E> {
+    let mut installed: HashMap<usize, HashSet<String>> = HashMap::new();
+    let mut winners = HashMap::new();
+    for (asset, &winner) in asset_winners {
+        if !installed.contains_key(&winner) {
+            let keys = files(winner)?
+                .iter()
+                .filter(|path| !is_left_out(path))
+                .map(|path| asset_key(path))
+                .collect();
+            installed.insert(winner, keys);
+        }
+        if installed[&winner].contains(asset) {
+            winners.insert(asset.clone(), winner);
+        }
+    }
+    Ok(winners)
+}
+
+/// Paths of the files the mod at `path` installs into the bundle.
+fn bundled_files(path: &Path, memory_budget: u64) -> Result<Vec<String>, IntegrationError> {
+    if ue4ss::is_script_only(path) {
+        return Ok(vec![]);
+    }
+    let raw_mod_file = crate::archive::open(path, memory_budget)?;
+    let mut buf = get_pak_from_data(raw_mod_file, memory_budget)?;
+    let pak = repak::PakBuilder::new().reader(&mut buf)?;
+    let mount = PakPath::new(pak.mount_point());
+    Ok(pak
+        .files()
+        .into_iter()
+        .filter_map(|p| {
+            let path = mount.join(&p);
+            let path = path.strip_prefix("../../../").ok()?.to_path_buf();
+            Some(path.as_str().to_string())
+        })
+        .collect())
+}
+
 #[tracing::instrument(skip_all)]
+/// Install `mods` into the game at `path_pak`. Assets in `asset_winners`, see [`asset_winners`],
+/// are taken from that mod only, all others from the first mod containing them. If the winner
+/// doesn't install a file of the asset, it goes to the next mod containing it instead. At most
+/// `memory_budget` MiB of each mod's pak are kept in memory, see [`crate::archive::buffer`].
 pub fn integrate<P: AsRef<Path>>(
     path_pak: P,
     config: MetaConfig,
     mods: Vec<(ModInfo, PathBuf)>,
-) -> Result<(), IntegrationError> {
+    payloads: &[ModPayload],
+    asset_winners: &HashMap<String, usize>,
+    memory_budget: u64,
+) -> Result<Vec<IntegrationWarning>, IntegrationError> {
     let Ok(installation) = DRGInstallation::from_pak_path(&path_pak) else {
         return Err(IntegrationError::DrgInstallationNotFound {
             path: path_pak.as_ref().to_path_buf(),
@@ -296,42 +500,53 @@ pub fn integrate<P: AsRef<Path>>(
         }?;
     }
 
-    let mut bundle = ModBundleWriter::new(
-        BufWriter::new(
-            fs::OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .open(&path_mod_pak)?,
-        ),
-        &fsd_pak.files(),
-    )?;
+    // Written next to the game pak and moved over mods_P.pak once complete, so the rename stays
+    // on one drive and a failed install leaves the previous bundle intact.
+    let mod_pak = tempfile::Builder::new()
+        .prefix("mods_P")
+        .suffix(".pak.tmp")
+        .tempfile_in(long_path(&installation.paks_path()))?;
+    let (mod_pak, mod_pak_tmp) = mod_pak.into_parts();
+    let mut bundle = ModBundleWriter::new(BufWriter::new(mod_pak), &fsd_pak.files())?;
 
     #[cfg(feature = "hook")]
     {
         let path_hook_dll = installation
             .binaries_directory()
             .join(installation.installation_type.hook_dll_name());
-        let hook_dll = include_bytes!(env!("CARGO_CDYLIB_FILE_HOOK_hook"));
         if path_hook_dll
             .metadata()
-            .map(|m| m.len() != hook_dll.len() as u64)
+            .map(|m| m.len() != HOOK_DLL.len() as u64)
             .unwrap_or(true)
         {
-            fs::write(&path_hook_dll, hook_dll)?;
+            locks::retry(&path_hook_dll, || fs::write(&path_hook_dll, HOOK_DLL))?;
         }
+        fs::write(
+            installation.binaries_directory().join(HOOK_SIZE_FILE),
+            HOOK_DLL.len().to_string(),
+        )?;
     }
 
     let mut init_spacerig_assets = HashSet::new();
     let mut init_cave_assets = HashSet::new();
 
     let mut added_paths = HashSet::new();
+    let mut warnings = vec![];
 
-    for (mod_info, path) in &mods {
-        let raw_mod_file = fs::File::open(path).with_context(|_| CtxtIoSnafu {
-            mod_info: mod_info.clone(),
-        })?;
-        let mut buf = get_pak_from_data(Box::new(BufReader::new(raw_mod_file))).map_err(|e| {
+    let asset_winners = installed_winners(asset_winners, |index| {
+        bundled_files(&mods[index].1, memory_budget)
+    })?;
+
+    for (index, (mod_info, path)) in mods.iter().enumerate() {
+        // UE4SS and script mods are installed next to the game instead
+        if ue4ss::is_script_only(path) {
+            continue;
+        }
+        let raw_mod_file =
+            crate::archive::open(path, memory_budget).with_context(|_| CtxtIoSnafu {
+                mod_info: mod_info.clone(),
+            })?;
+        let mut buf = get_pak_from_data(raw_mod_file, memory_budget).map_err(|e| {
             if let IntegrationError::IoError { source } = e {
                 IntegrationError::CtxtIoError {
                     source,
@@ -346,6 +561,16 @@ pub fn integrate<P: AsRef<Path>>(
             .with_context(|_| CtxtRepakSnafu {
                 mod_info: mod_info.clone(),
             })?;
+        if pak.version() < repak::Version::V11 {
+            warnings.push(IntegrationWarning::new(
+                WarningKind::Deprecated,
+                Some(mod_info),
+                format!(
+                    "pak version {:?} is outdated, the mod should be repacked for the current game",
+                    pak.version()
+                ),
+            ));
+        }
 
         let mount = PakPath::new(pak.mount_point());
 
@@ -365,8 +590,11 @@ pub fn integrate<P: AsRef<Path>>(
                 ))
             })
             .collect::<Result<HashMap<_, _>, _>>()?;
+        // in a fixed order, so the same mods always make the same bundle
+        let mut sorted_files = pak_files.iter().collect::<Vec<_>>();
+        sorted_files.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
 
-        for (normalized, pak_path) in &pak_files {
+        for &(normalized, pak_path) in &sorted_files {
             match normalized.extension() {
                 Some("uasset" | "umap")
                     if pak_files.contains_key(&normalized.with_extension("uexp")) =>
@@ -394,7 +622,14 @@ pub fn integrate<P: AsRef<Path>>(
                     let asset = match asset {
                         Ok(asset) => asset,
                         Err(err) => {
-                            warn!("failed to parse asset {normalized}: {err}");
+                            warnings.push(IntegrationWarning::new(
+                                WarningKind::Fallback,
+                                Some(mod_info),
+                                format!(
+                                    "failed to parse asset {normalized}, it is installed without \
+                                     an asset registry entry: {err}"
+                                ),
+                            ));
                             continue;
                         }
                     };
@@ -410,17 +645,25 @@ pub fn integrate<P: AsRef<Path>>(
             }
         }
 
-        for (normalized, pak_path) in pak_files {
+        for &(normalized, pak_path) in &sorted_files {
             let lowercase = normalized.as_str().to_ascii_lowercase();
             if added_paths.contains(&lowercase) {
                 continue;
             }
+            if asset_winners
+                .get(&asset_key(&lowercase))
+                .is_some_and(|winner| *winner != index)
+            {
+                continue;
+            }
 
             if let Some(filename) = normalized.file_name() {
-                if filename == "AssetRegistry.bin" {
-                    continue;
-                }
-                if normalized.extension() == Some("ushaderbytecode") {
+                if is_left_out(normalized.as_str()) {
+                    warnings.push(IntegrationWarning::new(
+                        WarningKind::SkippedFile,
+                        Some(mod_info),
+                        format!("{normalized} is left out in favor of the game's own"),
+                    ));
                     continue;
                 }
                 let lower = filename.to_lowercase();
@@ -480,6 +723,8 @@ pub fn integrate<P: AsRef<Path>>(
 
     let mut int_files = HashMap::new();
     collect_dir_files(&INTEGRATION_DIR, &mut int_files);
+    let mut int_files = int_files.into_iter().collect::<Vec<_>>();
+    int_files.sort_by(|(a, _), (b, _)| a.cmp(b));
 
     for (path, data) in &int_files {
         bundle.write_file(data, path)?;
@@ -493,15 +738,39 @@ pub fn integrate<P: AsRef<Path>>(
         .map_err(|e| IntegrationError::GenericError { msg: e.to_string() })?;
     bundle.write_file(&buf, ar_path)?;
 
-    bundle.finish()?;
+    let mod_pak = bundle.finish()?.into_inner().map_err(|e| e.into_error())?;
+    mod_pak.sync_all()?;
+    drop(mod_pak);
+    locks::retry(&path_mod_pak, || {
+        std::fs::rename(&mod_pak_tmp, long_path(&path_mod_pak))
+    })?;
+    mod_pak_tmp.keep().ok();
+
+    payload::install(&installation, payloads)?;
+    let payload_urls = payloads
+        .iter()
+        .map(|p| p.mod_info.spec.url.as_str())
+        .collect();
+    ue4ss::install(&installation, &mods, &payload_urls, &mut warnings)?;
+
+    let installed = mods
+        .iter()
+        .map(|(info, path)| lobby::InstalledMod::new(info, path))
+        .collect::<Result<Vec<_>, _>>()?;
+    lobby::write_installed(&installation, &installed)?;
 
+    let skipped = warnings
+        .iter()
+        .filter(|w| w.kind == WarningKind::SkippedFile)
+        .count();
     info!(
-        "{} mods installed to {}",
+        "{} mods installed to {} with {} warnings, {skipped} files skipped",
         mods.len(),
-        path_mod_pak.display()
+        path_mod_pak.display(),
+        warnings.len() - skipped
     );
 
-    Ok(())
+    Ok(warnings)
 }
 
 fn collect_dir_files(dir: &'static include_dir::Dir, collect: &mut HashMap<String, &[u8]>) {
@@ -629,9 +898,8 @@ impl<W: Write + Seek> ModBundleWriter<W> {
         Ok(())
     }
 
-    fn finish(self) -> Result<(), IntegrationError> {
-        self.pak_writer.write_index()?;
-        Ok(())
+    fn finish(self) -> Result<W, IntegrationError> {
+        Ok(self.pak_writer.write_index()?)
     }
 }
 
@@ -641,8 +909,11 @@ struct Dir {
     children: HashMap<String, Dir>,
 }
 
+/// The pak in `data`, extracted if `data` is a zip archive. Extracted paks are kept in memory up to
+/// `memory_budget` MiB.
 pub(crate) fn get_pak_from_data(
     mut data: Box<dyn ReadSeek>,
+    memory_budget: u64,
 ) -> Result<Box<dyn ReadSeek>, IntegrationError> {
     if let Ok(mut archive) = zip::ZipArchive::new(&mut data) {
         (0..archive.len())
@@ -655,9 +926,7 @@ pub(crate) fn get_pak_from_data(
                 match file.enclosed_name() {
                     Some(p) => {
                         if file.is_file() && p.extension() == Some(std::ffi::OsStr::new("pak")) {
-                            let mut buf = vec![];
-                            file.read_to_end(&mut buf)?;
-                            Ok(Some(Box::new(Cursor::new(buf))))
+                            Ok(Some(crate::archive::buffer(&mut file, memory_budget)?))
                         } else {
                             Ok(None)
                         }
@@ -1266,3 +1535,60 @@ fn patch_server_list_entry<C: Seek + Read>(asset: &mut Asset<C>) -> Result<(), I
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_key() {
+        assert_eq!(asset_key("FSD/Content/A/Gun.uasset"), "fsd/content/a/gun");
+        assert_eq!(asset_key("FSD/Content/A/gun.uexp"), "fsd/content/a/gun");
+        assert_eq!(asset_key("FSD/Content/A/Gun.ubulk"), "fsd/content/a/gun");
+        assert_eq!(asset_key("FSD/Content/A.b/Gun"), "fsd/content/a.b/gun");
+        assert_eq!(asset_key("FSD/Content/A/.hidden"), "fsd/content/a/.hidden");
+    }
+
+    #[test]
+    fn test_asset_winners() {
+        let specs = ["https://a", "https://b", "https://c"]
+            .map(|url| ModSpecification::new(url.to_string()));
+        let asset_overrides = BTreeMap::from([
+            ("fsd/content/gun".to_string(), "https://b".to_string()),
+            ("fsd/content/drill".to_string(), "https://c".to_string()),
+            // not being installed
+            ("fsd/content/pickaxe".to_string(), "https://d".to_string()),
+        ]);
+        assert_eq!(
+            asset_winners(&specs, &asset_overrides),
+            HashMap::from([
+                ("fsd/content/gun".to_string(), 1),
+                ("fsd/content/drill".to_string(), 2),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_installed_winners() {
+        let asset_winners = HashMap::from([
+            ("fsd/content/gun".to_string(), 1),
+            ("fsd/content/drill".to_string(), 2),
+            ("fsd/assetregistry".to_string(), 1),
+        ]);
+        let files = |index| -> Result<Vec<String>, ()> {
+            Ok(match index {
+                1 => vec![
+                    "FSD/Content/Gun.uasset".to_string(),
+                    "FSD/Content/Gun.uexp".to_string(),
+                    "FSD/AssetRegistry.bin".to_string(),
+                ],
+                // mod 2 doesn't contain the drill, so it goes by priority again
+                _ => vec!["FSD/Content/Pickaxe.uasset".to_string()],
+            })
+        };
+        assert_eq!(
+            installed_winners(&asset_winners, files),
+            Ok(HashMap::from([("fsd/content/gun".to_string(), 1)]))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Retrying writes to game files which another program briefly holds open, as antivirus scanners
+//! and cloud sync clients do right after a file changes, and naming the likely culprit once
+//! retrying gives up.
+
+use std::error::Error as _;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::Duration;
+
+use super::IntegrationError;
+
+/// Delays between attempts; the total stays short enough not to look like a hang.
+const BACKOFF: &[Duration] = &[
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_millis(1000),
+    Duration::from_millis(2000),
+];
+
+/// Running programs known to hold files open, by lowercase executable name.
+const KNOWN_LOCKERS: &[(&str, &str)] = &[
+    ("fsd-win64-shipping.exe", "Deep Rock Galactic"),
+    ("fsd-wingdk-shipping.exe", "Deep Rock Galactic"),
+    ("msmpeng.exe", "Microsoft Defender"),
+    ("onedrive.exe", "OneDrive"),
+    ("dropbox.exe", "Dropbox"),
+    ("googledrivefs.exe", "Google Drive"),
+    ("avastsvc.exe", "Avast"),
+    ("avgsvc.exe", "AVG"),
+    ("avp.exe", "Kaspersky"),
+    ("bdservicehost.exe", "Bitdefender"),
+    ("mcshield.exe", "McAfee"),
+    ("ekrn.exe", "ESET"),
+];
+
+/// Whether the error means another process has the file open. Windows reports this as a sharing
+/// or lock violation; scanners sometimes cause a plain access denied as well.
+pub fn is_lock_error(error: &std::io::Error) -> bool {
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    const ERROR_LOCK_VIOLATION: i32 = 33;
+    cfg!(windows)
+        && matches!(
+            raw_os_error(error),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+}
+
+/// The OS error code, also from errors `fs_err` wrapped to add the path.
+fn raw_os_error(error: &std::io::Error) -> Option<i32> {
+    error.raw_os_error().or_else(|| {
+        error
+            .get_ref()
+            .and_then(|e| e.source())
+            .and_then(|e| e.downcast_ref::<std::io::Error>())
+            .and_then(std::io::Error::raw_os_error)
+    })
+}
+
+fn is_transient(error: &std::io::Error) -> bool {
+    is_lock_error(error) || (cfg!(windows) && error.kind() == ErrorKind::PermissionDenied)
+}
+
+/// Names of running programs which commonly lock game files.
+pub fn likely_lockers() -> Vec<&'static str> {
+    let mut system = sysinfo::System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    let mut names = system
+        .processes()
+        .values()
+        .filter_map(|p| {
+            let exe = p.name().to_string_lossy().to_lowercase();
+            KNOWN_LOCKERS
+                .iter()
+                .find(|(name, _)| *name == exe)
+                .map(|(_, program)| *program)
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Run `f`, retrying with backoff while it fails because `path` is locked. If it stays locked, the
+/// error names the programs likely holding it.
+pub fn retry<T>(
+    path: &Path,
+    mut f: impl FnMut() -> std::io::Result<T>,
+) -> Result<T, IntegrationError> {
+    let mut delays = BACKOFF.iter();
+    loop {
+        let error = match f() {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        if is_transient(&error)
+            && let Some(delay) = delays.next()
+        {
+            tracing::debug!(
+                "{} is locked, retrying in {delay:?}: {error}",
+                path.display()
+            );
+            std::thread::sleep(*delay);
+            continue;
+        }
+        return Err(if is_lock_error(&error) {
+            IntegrationError::FileLocked {
+                path: path.to_path_buf(),
+                lockers: likely_lockers(),
+                source: error,
+            }
+        } else {
+            error.into()
+        });
+    }
+}
+
+/// Hint appended to [`IntegrationError::FileLocked`].
+pub(super) fn lockers_hint(lockers: &[&str]) -> String {
+    if lockers.is_empty() {
+        "close programs which may be using it, such as antivirus or cloud sync, and try again"
+            .to_string()
+    } else {
+        format!(
+            "it is likely used by {}; close it or add an exception for the game directory and try again",
+            lockers.join(", ")
+        )
+    }
+}
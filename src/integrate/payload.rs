@@ -0,0 +1,242 @@
+//! Files from mod archives which are installed next to the game instead of into the mod bundle,
+//! as configured by [`PayloadRule`]s. Every installed file is recorded in a manifest inside the
+//! game's `FSD` directory so it can be removed again on the next install or uninstall. Files
+//! which existed before are moved aside and restored on removal.
+
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+use std::io::{ErrorKind, Read};
+use std::path::{Component, Path, PathBuf};
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+use tracing::info;
+
+use super::{CtxtIoSnafu, IntegrationError};
+use crate::providers::{ModInfo, ModSpecification};
+use crate::state::PayloadRule;
+use mint_lib::DRGInstallation;
+use mint_lib::paths::long_path;
+
+/// Name of the manifest of installed payload files, stored in the `FSD` directory.
+pub const PAYLOAD_MANIFEST: &str = "mint_payloads.json";
+
+const BACKUP_SUFFIX: &str = ".mint_backup";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: Vec<InstalledFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstalledFile {
+    /// Relative to the game directory.
+    path: PathBuf,
+    /// Where the file previously at `path` was moved to, relative to the game directory.
+    backup: Option<PathBuf>,
+}
+
+/// Payload rules of one mod together with its fetched archive.
+#[derive(Debug, Clone)]
+pub struct ModPayload {
+    pub mod_info: ModInfo,
+    pub archive: PathBuf,
+    pub rules: Vec<PayloadRule>,
+}
+
+impl ModPayload {
+    /// Pair the rules configured for `specs` with the resolved and fetched mods, which must be in
+    /// the same order.
+    pub fn collect(
+        specs: &[ModSpecification],
+        mods: &[(ModInfo, PathBuf)],
+        rules: &BTreeMap<String, Vec<PayloadRule>>,
+    ) -> Vec<ModPayload> {
+        specs
+            .iter()
+            .zip(mods)
+            .filter_map(|(spec, (mod_info, archive))| {
+                let rules = rules.get(&spec.url).filter(|r| !r.is_empty())?;
+                Some(ModPayload {
+                    mod_info: mod_info.clone(),
+                    archive: archive.clone(),
+                    rules: rules.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Directory containing `FSD`, which payload targets are relative to.
+fn game_dir(installation: &DRGInstallation) -> &Path {
+    installation.root.parent().unwrap_or(&installation.root)
+}
+
+/// Whether `target` stays inside the directory it is joined to.
+pub fn is_contained(target: &Path) -> bool {
+    target
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(BACKUP_SUFFIX);
+    path.with_file_name(name)
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, std::io::Error> {
+    match fs::read(path) {
+        Ok(buf) => serde_json::from_slice(&buf).map_err(std::io::Error::other),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Manifest::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remove previously installed payload files, restoring any files they replaced.
+pub fn uninstall(installation: &DRGInstallation) -> Result<(), std::io::Error> {
+    let manifest_path = installation.root.join(PAYLOAD_MANIFEST);
+    let manifest = read_manifest(&manifest_path)?;
+    let game_dir = game_dir(installation);
+
+    let mut dirs = HashSet::new();
+    for file in manifest.files.iter().rev() {
+        let path = game_dir.join(&file.path);
+        match fs::remove_file(long_path(&path)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        if let Some(backup) = &file.backup {
+            fs::rename(long_path(&game_dir.join(backup)), long_path(&path))?;
+        }
+        dirs.extend(path.ancestors().skip(1).map(Path::to_path_buf));
+    }
+
+    // clean up directories created for payloads, deepest first; non-empty ones fail and are kept
+    let mut dirs = dirs
+        .into_iter()
+        .filter(|d| d.starts_with(game_dir) && d != game_dir && d != &installation.root)
+        .collect::<Vec<_>>();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.components().count()));
+    for dir in dirs {
+        std::fs::remove_dir(long_path(&dir)).ok();
+    }
+
+    match fs::remove_file(&manifest_path) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Replace the installed payload files with those of `payloads`.
+pub fn install(
+    installation: &DRGInstallation,
+    payloads: &[ModPayload],
+) -> Result<(), IntegrationError> {
+    uninstall(installation)?;
+
+    let mut manifest = Manifest::default();
+    let res = payloads
+        .iter()
+        .try_for_each(|p| install_mod(game_dir(installation), p, &mut manifest));
+
+    // record what was installed even if a later mod failed so it can still be removed
+    if !manifest.files.is_empty() {
+        let buf = serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::other)?;
+        fs::write(installation.root.join(PAYLOAD_MANIFEST), buf)?;
+        info!("{} payload files installed", manifest.files.len());
+    }
+    res
+}
+
+fn install_mod(
+    game_dir: &Path,
+    payload: &ModPayload,
+    manifest: &mut Manifest,
+) -> Result<(), IntegrationError> {
+    let mod_info = &payload.mod_info;
+    let generic = |msg: String| IntegrationError::CtxtGenericError {
+        source: msg.into(),
+        mod_info: mod_info.clone().into(),
+    };
+
+    if let Some(rule) = payload.rules.iter().find(|r| !is_contained(&r.target)) {
+        return Err(generic(format!(
+            "install destination {} is outside of the game directory",
+            rule.target.display()
+        )));
+    }
+
+    let file = crate::archive::open(&payload.archive, crate::archive::DEFAULT_MEMORY_BUDGET)
+        .with_context(|_| CtxtIoSnafu {
+            mod_info: mod_info.clone(),
+        })?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|_| generic("install destinations require a zip archive".to_string()))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| generic(format!("failed to read zip entry: {e}")))?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        if !entry.is_file() || name.extension().is_some_and(|e| e == "pak") {
+            continue;
+        }
+        let Some(relative) = payload.rules.iter().find_map(|rule| {
+            name.strip_prefix(&rule.source)
+                .ok()
+                .map(|rest| rule.target.join(rest))
+        }) else {
+            continue;
+        };
+
+        let path = game_dir.join(&relative);
+        let ours = manifest.files.iter().any(|f| f.path == relative);
+        let mut backup = None;
+        if !ours && long_path(&path).exists() {
+            let backup_path = backup_path(&path);
+            // a backup left over from an install which was never removed already holds the original
+            if !long_path(&backup_path).exists() {
+                fs::rename(long_path(&path), long_path(&backup_path))?;
+            }
+            backup = backup_path
+                .strip_prefix(game_dir)
+                .ok()
+                .map(Path::to_path_buf);
+        }
+
+        let mut data = vec![];
+        entry.read_to_end(&mut data).with_context(|_| CtxtIoSnafu {
+            mod_info: mod_info.clone(),
+        })?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(long_path(parent))?;
+        }
+        fs::write(long_path(&path), data)?;
+        if !ours {
+            manifest.files.push(InstalledFile {
+                path: relative,
+                backup,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_contained() {
+        assert!(is_contained(Path::new("FSD/Binaries/Win64/Mods")));
+        assert!(is_contained(Path::new("")));
+        assert!(!is_contained(Path::new("../outside")));
+        assert!(!is_contained(Path::new("FSD/../../outside")));
+        assert!(!is_contained(Path::new("/absolute")));
+    }
+}
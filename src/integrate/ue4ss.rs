@@ -0,0 +1,351 @@
+//! UE4SS and its Lua script mods, installed from mod archives alongside the mod bundle.
+//!
+//! An archive ships UE4SS itself if it contains `dwmapi.dll`; the directory containing it is
+//! installed into the game's binaries directory. Any other directory holding a `Scripts` folder
+//! with Lua files is a script mod and is installed into the UE4SS `Mods` directory with an
+//! `enabled.txt`, so disabling the mod in mint removes it from UE4SS on the next install.
+//! Everything installed is recorded in [`UE4SS_MANIFEST`] to be removed again on the next install
+//! or uninstall. Archives with payload rules are left to those instead.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+use tracing::info;
+
+use super::{CtxtIoSnafu, IntegrationError, IntegrationWarning, WarningKind};
+use crate::providers::ModInfo;
+use mint_lib::DRGInstallation;
+use mint_lib::paths::{long_path, long_path_buf};
+
+/// Name of the manifest of installed UE4SS files, stored in the `FSD` directory.
+pub const UE4SS_MANIFEST: &str = "mint_ue4ss.json";
+
+const LOADER_DLL: &str = "dwmapi.dll";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub loader: Option<InstalledPackage>,
+    pub mods: Vec<InstalledPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPackage {
+    /// Name of the script mod directory, or of the mint mod for the loader.
+    pub name: String,
+    /// URL of the mint mod it was installed from.
+    pub url: String,
+    /// Resolved URL of the installed version.
+    pub version: String,
+    /// Relative to the binaries directory.
+    files: Vec<PathBuf>,
+}
+
+/// UE4SS content found in a mod archive.
+#[derive(Debug, Default)]
+pub struct ArchiveContents {
+    pub has_pak: bool,
+    /// Directory inside the archive containing the UE4SS loader.
+    pub loader: Option<PathBuf>,
+    /// Script mod directories inside the archive, keyed by mod name.
+    pub script_mods: BTreeMap<String, PathBuf>,
+}
+
+impl ArchiveContents {
+    pub fn is_empty(&self) -> bool {
+        self.loader.is_none() && self.script_mods.is_empty()
+    }
+}
+
+/// Look for UE4SS content in a mod archive. Returns `None` if it is not a zip archive.
+pub fn inspect(path: &Path) -> Result<Option<ArchiveContents>, std::io::Error> {
+    let file = crate::archive::open(path, crate::archive::DEFAULT_MEMORY_BUDGET)?;
+    let Ok(archive) = zip::ZipArchive::new(file) else {
+        return Ok(None);
+    };
+
+    let mut contents = ArchiveContents::default();
+    for name in archive.file_names() {
+        let name = Path::new(name);
+        let extension = name.extension().map(|e| e.to_ascii_lowercase());
+        let has_extension = |ext: &str| extension.as_deref().is_some_and(|e| e == ext);
+        if has_extension("pak") {
+            contents.has_pak = true;
+        } else if name
+            .file_name()
+            .is_some_and(|f| f.eq_ignore_ascii_case(LOADER_DLL))
+        {
+            contents.loader = Some(name.parent().unwrap_or(Path::new("")).to_path_buf());
+        } else if has_extension("lua")
+            && let Some(dir) = name
+                .ancestors()
+                .skip(1)
+                .find(|a| {
+                    a.file_name()
+                        .is_some_and(|f| f.eq_ignore_ascii_case("Scripts"))
+                })
+                .and_then(Path::parent)
+            && let Some(mod_name) = dir.file_name()
+        {
+            contents
+                .script_mods
+                .insert(mod_name.to_string_lossy().to_string(), dir.to_path_buf());
+        }
+    }
+    // script mods bundled with UE4SS itself are installed as part of it
+    if let Some(loader) = &contents.loader {
+        contents
+            .script_mods
+            .retain(|_, dir| !dir.starts_with(loader));
+    }
+    Ok(Some(contents))
+}
+
+/// Whether the archive only contains UE4SS content, so there is no pak to add to the bundle.
+pub fn is_script_only(path: &Path) -> bool {
+    inspect(path)
+        .ok()
+        .flatten()
+        .is_some_and(|c| !c.has_pak && !c.is_empty())
+}
+
+/// The UE4SS `Mods` directory relative to the binaries directory. UE4SS 3 keeps everything in a
+/// `ue4ss` subdirectory, older versions directly next to the executable.
+fn mods_dir(binaries: &Path) -> PathBuf {
+    if binaries.join("ue4ss").is_dir() {
+        PathBuf::from("ue4ss/Mods")
+    } else {
+        PathBuf::from("Mods")
+    }
+}
+
+pub fn installed(installation: &DRGInstallation) -> Result<Manifest, std::io::Error> {
+    match fs::read(installation.root.join(UE4SS_MANIFEST)) {
+        Ok(buf) => serde_json::from_slice(&buf).map_err(std::io::Error::other),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Manifest::default()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remove UE4SS and the script mods installed by mint.
+pub fn uninstall(installation: &DRGInstallation) -> Result<(), std::io::Error> {
+    let manifest = installed(installation)?;
+    let binaries = installation.binaries_directory();
+
+    let mut dirs = HashSet::new();
+    for file in manifest
+        .mods
+        .iter()
+        .chain(&manifest.loader)
+        .flat_map(|p| &p.files)
+    {
+        let path = binaries.join(file);
+        match fs::remove_file(long_path(&path)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        dirs.extend(path.ancestors().skip(1).map(Path::to_path_buf));
+    }
+
+    // deepest first; directories which still contain other files are kept
+    let mut dirs = dirs
+        .into_iter()
+        .filter(|d| d.starts_with(&binaries) && d != &binaries)
+        .collect::<Vec<_>>();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.components().count()));
+    for dir in dirs {
+        std::fs::remove_dir(long_path(&dir)).ok();
+    }
+
+    match fs::remove_file(installation.root.join(UE4SS_MANIFEST)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Replace the installed UE4SS files with those found in `mods`, except for the mods in
+/// `skip_urls`. Content which is left out is reported in `warnings`.
+pub fn install(
+    installation: &DRGInstallation,
+    mods: &[(ModInfo, PathBuf)],
+    skip_urls: &HashSet<&str>,
+    warnings: &mut Vec<IntegrationWarning>,
+) -> Result<(), IntegrationError> {
+    uninstall(installation)?;
+    let binaries = installation.binaries_directory();
+
+    let mut found = vec![];
+    for (mod_info, path) in mods {
+        if skip_urls.contains(mod_info.spec.url.as_str()) {
+            continue;
+        }
+        let contents = inspect(path).with_context(|_| CtxtIoSnafu {
+            mod_info: mod_info.clone(),
+        })?;
+        if let Some(contents) = contents.filter(|c| !c.is_empty()) {
+            found.push((mod_info, path, contents));
+        }
+    }
+
+    let mut manifest = Manifest::default();
+    let res = (|| -> Result<(), IntegrationError> {
+        // the loader decides where the Mods directory is, so it goes first
+        for (mod_info, path, contents) in &found {
+            let Some(loader) = &contents.loader else {
+                continue;
+            };
+            if manifest.loader.is_some() {
+                warnings.push(IntegrationWarning::new(
+                    WarningKind::SkippedFile,
+                    Some(mod_info),
+                    "UE4SS is already installed by another mod, skipping this copy",
+                ));
+            } else if binaries.join(LOADER_DLL).exists() {
+                warnings.push(IntegrationWarning::new(
+                    WarningKind::Fallback,
+                    Some(mod_info),
+                    "UE4SS is already installed and not managed by mint, keeping it",
+                ));
+            } else {
+                let files = extract(mod_info, path, loader, &binaries, Path::new(""))?;
+                manifest.loader = Some(package(mod_info.name.clone(), mod_info, files));
+            }
+        }
+
+        let mods_dir = mods_dir(&binaries);
+        for (mod_info, path, contents) in &found {
+            for (name, dir) in &contents.script_mods {
+                let target = mods_dir.join(name);
+                if binaries.join(&target).exists() {
+                    warnings.push(IntegrationWarning::new(
+                        WarningKind::SkippedFile,
+                        Some(mod_info),
+                        format!("script mod {name:?} is already installed and not managed by mint"),
+                    ));
+                    continue;
+                }
+                let mut files = extract(mod_info, path, dir, &binaries, &target)?;
+                let enabled = target.join("enabled.txt");
+                fs::write(long_path(&binaries.join(&enabled)), "")?;
+                files.push(enabled);
+                manifest.mods.push(package(name.clone(), mod_info, files));
+            }
+        }
+        Ok(())
+    })();
+
+    if manifest.loader.is_some() || !manifest.mods.is_empty() {
+        if !manifest.mods.is_empty()
+            && manifest.loader.is_none()
+            && !binaries.join(LOADER_DLL).exists()
+        {
+            warnings.push(IntegrationWarning::new(
+                WarningKind::Fallback,
+                None,
+                "script mods were installed but UE4SS was not found, they will not run until it is",
+            ));
+        }
+        let buf = serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::other)?;
+        fs::write(installation.root.join(UE4SS_MANIFEST), buf)?;
+        info!("{} UE4SS script mods installed", manifest.mods.len());
+    }
+    res
+}
+
+fn package(name: String, mod_info: &ModInfo, files: Vec<PathBuf>) -> InstalledPackage {
+    InstalledPackage {
+        name,
+        url: mod_info.spec.url.clone(),
+        version: mod_info.resolution.url.0.clone(),
+        files,
+    }
+}
+
+/// Extract the files under `source` in the archive to `binaries/target`, returning their paths
+/// relative to `binaries`.
+fn extract(
+    mod_info: &ModInfo,
+    path: &Path,
+    source: &Path,
+    binaries: &Path,
+    target: &Path,
+) -> Result<Vec<PathBuf>, IntegrationError> {
+    let ctxt = |_: &mut std::io::Error| CtxtIoSnafu {
+        mod_info: mod_info.clone(),
+    };
+    let file =
+        crate::archive::open(path, crate::archive::DEFAULT_MEMORY_BUDGET).with_context(ctxt)?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| IntegrationError::CtxtGenericError {
+            source: e.into(),
+            mod_info: mod_info.clone().into(),
+        })?;
+
+    let mut files = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| IntegrationError::CtxtGenericError {
+                source: e.into(),
+                mod_info: mod_info.clone().into(),
+            })?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        if !entry.is_file() || name.extension().is_some_and(|e| e == "pak") {
+            continue;
+        }
+        let Ok(rest) = name.strip_prefix(source) else {
+            continue;
+        };
+        let relative = target.join(rest);
+
+        let mut data = vec![];
+        entry.read_to_end(&mut data).with_context(ctxt)?;
+        let dest = long_path_buf(binaries.join(&relative));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(dest, data)?;
+        files.push(relative);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_inspect() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mod.zip");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for name in [
+            "UE4SS/dwmapi.dll",
+            "UE4SS/ue4ss/Mods/Keybinds/Scripts/main.lua",
+            "BetterScanner/Scripts/main.lua",
+            "BetterScanner/Scripts/lib/util.lua",
+        ] {
+            zip.start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(b"").unwrap();
+        }
+        zip.finish().unwrap();
+
+        let contents = inspect(&path).unwrap().unwrap();
+        assert!(!contents.has_pak);
+        assert_eq!(contents.loader, Some(PathBuf::from("UE4SS")));
+        assert_eq!(
+            contents.script_mods.into_iter().collect::<Vec<_>>(),
+            [("BetterScanner".to_string(), PathBuf::from("BetterScanner"))]
+        );
+        assert!(is_script_only(&path));
+    }
+}
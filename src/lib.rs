@@ -1,23 +1,45 @@
 #![feature(if_let_guard)]
 
+pub mod analytics;
+pub mod archive;
+pub mod backup;
+pub mod deep_link;
+pub mod diagnostics;
+pub mod enabled_mods;
+pub mod error_info;
 pub mod gui;
+pub mod health;
 pub mod integrate;
+pub mod lobby;
 pub mod mod_lints;
+pub mod mod_list;
+pub mod preview;
+pub mod profile_health;
 pub mod providers;
+pub mod saves;
+pub mod scan;
+pub mod sessions;
+pub mod signing;
 pub mod state;
 
 use std::ops::Deref;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
+use diagnostics::IntegrationReport;
 use directories::ProjectDirs;
 use fs_err as fs;
-use integrate::IntegrationError;
-use providers::{ModResolution, ModSpecification, ProviderError, ProviderFactory};
+use integrate::payload::ModPayload;
+use integrate::{IntegrationError, IntegrationWarning};
+use mint_lib::DRGInstallation;
+use mint_lib::error::{GenericError, ResultExt as _};
+use mint_lib::paths::long_path;
+use providers::{ModInfo, ModResolution, ModSpecification, ProviderError, ProviderFactory};
+use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
-use state::{State, StateError};
+use state::{LaunchMethod, State, StateError};
 use tracing::*;
 
 #[derive(Debug, Snafu)]
@@ -36,36 +58,34 @@ pub enum MintError {
     },
     #[snafu(transparent)]
     StateError { source: StateError },
+    #[snafu(transparent)]
+    DiagnosticsError {
+        source: diagnostics::DiagnosticsError,
+    },
+    #[snafu(transparent)]
+    ImportError { source: state::import::ImportError },
     #[snafu(display("invalid DRG pak path: {path}"))]
     InvalidDrgPak { path: String },
+    #[snafu(display("cannot move to {}: {reason}", path.display()))]
+    RelocateDir { path: PathBuf, reason: String },
+    #[snafu(display("workspace {name:?}: {reason}"))]
+    Workspace { name: String, reason: String },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Dirs {
     pub config_dir: PathBuf,
     pub cache_dir: PathBuf,
     pub data_dir: PathBuf,
+    /// Name of the [`Workspace`] these belong to, `None` for the default directories or the ones
+    /// passed with `--appdata`.
+    pub workspace: Option<String>,
 }
 
 impl Dirs {
     pub fn default_xdg() -> Result<Self, MintError> {
-        let legacy_dirs = ProjectDirs::from("", "", "drg-mod-integration")
-            .expect("failed to construct project dirs");
-
-        let project_dirs =
-            ProjectDirs::from("", "", "mint").expect("failed to construct project dirs");
-
-        Self::from_paths(
-            Some(legacy_dirs.config_dir())
-                .filter(|p| p.exists())
-                .unwrap_or(project_dirs.config_dir()),
-            Some(legacy_dirs.cache_dir())
-                .filter(|p| p.exists())
-                .unwrap_or(project_dirs.cache_dir()),
-            Some(legacy_dirs.data_dir())
-                .filter(|p| p.exists())
-                .unwrap_or(project_dirs.data_dir()),
-        )
+        let [config_dir, cache_dir, data_dir] = default_paths();
+        Self::from_paths(config_dir, cache_dir, data_dir)
     }
 
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MintError> {
@@ -76,21 +96,491 @@ impl Dirs {
         )
     }
 
+    /// Directories of the workspace `name`, or of the one last switched to if `None`. Unlike the
+    /// default directories, a workspace keeps all of its directories under its root like
+    /// `--appdata`. If the workspaces cannot be read, mint starts in the default workspace rather
+    /// than not at all.
+    pub fn workspace(name: Option<&str>) -> Result<Self, MintError> {
+        let workspaces = match Workspaces::read() {
+            Ok(workspaces) => workspaces,
+            Err(e) => {
+                warn!(
+                    "failed to read {}, starting in the default workspace: {e}",
+                    Workspaces::path().display()
+                );
+                return Self::default_xdg();
+            }
+        };
+        let Some(name) = name.or(workspaces.current.as_deref()) else {
+            return Self::default_xdg();
+        };
+        if name == DEFAULT_WORKSPACE {
+            return Self::default_xdg();
+        }
+        let workspace = workspaces.get(name).context(WorkspaceSnafu {
+            name,
+            reason: "no workspace has this name",
+        })?;
+        Ok(Self {
+            workspace: Some(workspace.name.clone()),
+            ..Self::from_path(&workspace.root)?
+        })
+    }
+
+    /// The cache and data directories are the defaults passed in unless they were moved, see
+    /// [`Dirs::relocate`].
     fn from_paths<P: AsRef<Path>>(
         config_dir: P,
         cache_dir: P,
         data_dir: P,
     ) -> Result<Self, MintError> {
+        let config_dir = config_dir.as_ref().to_path_buf();
         fs::create_dir_all(&config_dir)?;
+
+        let mut overrides = DirOverrides::read(&config_dir)?;
+        let had_pending = !overrides.pending.is_empty();
+        let cache_dir = overrides.resolve(DirKind::Cache, cache_dir.as_ref());
+        let data_dir = overrides.resolve(DirKind::Data, data_dir.as_ref());
+        if had_pending {
+            overrides.write(&config_dir)?;
+        }
+
         fs::create_dir_all(&cache_dir)?;
         fs::create_dir_all(&data_dir)?;
 
         Ok(Self {
-            config_dir: config_dir.as_ref().to_path_buf(),
-            cache_dir: cache_dir.as_ref().to_path_buf(),
-            data_dir: data_dir.as_ref().to_path_buf(),
+            config_dir,
+            cache_dir,
+            data_dir,
+            workspace: None,
         })
     }
+
+    pub fn get(&self, kind: DirKind) -> &Path {
+        match kind {
+            DirKind::Cache => &self.cache_dir,
+            DirKind::Data => &self.data_dir,
+        }
+    }
+
+    /// Move the cache or data directory to `target` on the next start, when nothing in it is
+    /// open yet. Moving to the current directory cancels a scheduled move.
+    pub fn relocate(&self, kind: DirKind, target: PathBuf) -> Result<(), MintError> {
+        let current = self.get(kind);
+        let mut overrides = DirOverrides::read(&self.config_dir)?;
+        overrides.pending.retain(|m| m.from != current);
+
+        if target != current {
+            let invalid = |reason: &str| RelocateDirSnafu {
+                path: target.clone(),
+                reason,
+            };
+            ensure!(target.is_absolute(), invalid("not an absolute path"));
+            ensure!(
+                !target.starts_with(current),
+                invalid("it is inside the current directory")
+            );
+            ensure!(
+                [&self.config_dir, &self.cache_dir, &self.data_dir]
+                    .iter()
+                    .all(|d| !target.starts_with(d) && !d.starts_with(&target)),
+                invalid("it overlaps with another of mint's directories")
+            );
+            if target.exists() {
+                ensure!(
+                    fs::read_dir(&target)?.next().is_none(),
+                    invalid("the directory is not empty")
+                );
+            }
+            overrides.pending.push(PendingMove {
+                from: current.to_path_buf(),
+                to: target.clone(),
+                error: None,
+            });
+        }
+
+        *overrides.get_mut(kind) = Some(target);
+        overrides.write(&self.config_dir)
+    }
+
+    /// Where the directory will be moved to on the next start, and why the last attempt failed
+    /// if it did.
+    pub fn pending_relocation(&self, kind: DirKind) -> Option<(PathBuf, Option<String>)> {
+        let current = self.get(kind);
+        DirOverrides::read(&self.config_dir)
+            .ok()?
+            .pending
+            .into_iter()
+            .find(|m| m.from == current)
+            .map(|m| (m.to, m.error))
+    }
+}
+
+/// Config, cache and data directories used when not started in another workspace or with
+/// `--appdata`, preferring those of the old name if they exist.
+fn default_paths() -> [PathBuf; 3] {
+    let legacy_dirs =
+        ProjectDirs::from("", "", "drg-mod-integration").expect("failed to construct project dirs");
+
+    let project_dirs = ProjectDirs::from("", "", "mint").expect("failed to construct project dirs");
+
+    [
+        (legacy_dirs.config_dir(), project_dirs.config_dir()),
+        (legacy_dirs.cache_dir(), project_dirs.cache_dir()),
+        (legacy_dirs.data_dir(), project_dirs.data_dir()),
+    ]
+    .map(|(legacy, dir)| {
+        Some(legacy)
+            .filter(|p| p.exists())
+            .unwrap_or(dir)
+            .to_path_buf()
+    })
+}
+
+/// Directories of the default workspace: [`default_paths`], and where the cache and data
+/// directories were moved to, see [`Dirs::relocate`].
+fn default_workspace_dirs() -> Vec<PathBuf> {
+    let [config_dir, cache_dir, data_dir] = default_paths();
+    let overrides = DirOverrides::read(&config_dir).unwrap_or_default();
+    [Some(config_dir), Some(cache_dir), Some(data_dir)]
+        .into_iter()
+        .chain([overrides.cache_dir, overrides.data_dir])
+        .flatten()
+        .collect()
+}
+
+/// Name of the workspace using the default directories.
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// File in the default config directory listing the other workspaces.
+const WORKSPACES: &str = "workspaces.json";
+
+/// Independent sets of profiles, settings and downloaded mods, e.g. to maintain a community pack
+/// apart from one's own mods. Besides the default one each has a root directory holding its
+/// config, cache and data directories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workspaces {
+    #[serde(default)]
+    pub workspaces: Vec<Workspace>,
+    /// Workspace mint starts in unless given `--workspace`, the default one if `None`.
+    #[serde(default)]
+    pub current: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub root: PathBuf,
+}
+
+impl Workspaces {
+    fn path() -> PathBuf {
+        let [config_dir, ..] = default_paths();
+        config_dir.join(WORKSPACES)
+    }
+
+    pub fn read() -> Result<Self, MintError> {
+        match fs::read(Self::path()) {
+            Ok(buf) => Ok(serde_json::from_slice(&buf).map_err(std::io::Error::other)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn write(&self) -> Result<(), MintError> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let buf = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(path, buf)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.name == name)
+    }
+
+    /// Names of all workspaces, starting with [`DEFAULT_WORKSPACE`].
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(DEFAULT_WORKSPACE).chain(self.workspaces.iter().map(|w| w.name.as_str()))
+    }
+
+    pub fn add(&mut self, name: &str, root: PathBuf) -> Result<(), MintError> {
+        let invalid = |reason: &'static str| WorkspaceSnafu { name, reason };
+        ensure!(!name.trim().is_empty(), invalid("the name is empty"));
+        ensure!(
+            self.names().all(|n| n != name),
+            invalid("a workspace with this name already exists")
+        );
+        ensure!(
+            root.is_absolute(),
+            invalid("the root is not an absolute path")
+        );
+        ensure!(
+            default_workspace_dirs()
+                .iter()
+                .chain(self.workspaces.iter().map(|w| &w.root))
+                .all(|d| !root.starts_with(d) && !d.starts_with(&root)),
+            invalid("the root overlaps with the directories of another workspace")
+        );
+        self.workspaces.push(Workspace {
+            name: name.to_string(),
+            root,
+        });
+        Ok(())
+    }
+
+    /// Forget the workspace, leaving its directories in place.
+    pub fn remove(&mut self, name: &str) {
+        self.workspaces.retain(|w| w.name != name);
+        if self.current.as_deref() == Some(name) {
+            self.current = None;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DirKind {
+    Cache,
+    Data,
+}
+
+/// File in the config directory recording moved directories. The config directory itself cannot
+/// be moved since it is where this is looked up.
+const DIR_OVERRIDES: &str = "dirs.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DirOverrides {
+    cache_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pending: Vec<PendingMove>,
+}
+
+/// Contents of a directory still to be moved into its override.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingMove {
+    from: PathBuf,
+    to: PathBuf,
+    /// Why the last attempt failed.
+    error: Option<String>,
+}
+
+impl DirOverrides {
+    fn read(config_dir: &Path) -> Result<Self, MintError> {
+        match fs::read(config_dir.join(DIR_OVERRIDES)) {
+            Ok(buf) => Ok(serde_json::from_slice(&buf).map_err(std::io::Error::other)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, config_dir: &Path) -> Result<(), MintError> {
+        let buf = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        fs::write(config_dir.join(DIR_OVERRIDES), buf)?;
+        Ok(())
+    }
+
+    fn get_mut(&mut self, kind: DirKind) -> &mut Option<PathBuf> {
+        match kind {
+            DirKind::Cache => &mut self.cache_dir,
+            DirKind::Data => &mut self.data_dir,
+        }
+    }
+
+    /// The directory to use, moving the contents of the previous one first if still pending. If
+    /// that fails the previous directory is kept for now and the move is retried on the next
+    /// start.
+    fn resolve(&mut self, kind: DirKind, default: &Path) -> PathBuf {
+        let Some(target) = self.get_mut(kind).clone() else {
+            return default.to_path_buf();
+        };
+        let Some(pending) = self.pending.iter_mut().find(|m| m.to == target) else {
+            return target;
+        };
+        match move_dir(&pending.from, &target) {
+            Ok(()) => {
+                self.pending.retain(|m| m.to != target);
+                target
+            }
+            Err(e) => {
+                pending.error = Some(e.to_string());
+                pending.from.clone()
+            }
+        }
+    }
+}
+
+/// Move `from` to `to`, which must not exist or be empty. Across drives the contents are copied
+/// to a sibling of `to` first, which is renamed to `to` and only then removed from `from`, so
+/// `from` stays usable and `to` untouched if copying fails.
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+    if to.exists() {
+        fs::remove_dir(to)?;
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    let mut partial = to.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    if partial.exists() {
+        fs::remove_dir_all(&partial)?;
+    }
+    if let Err(e) = copy_dir(from, &partial).and_then(|()| fs::rename(&partial, to)) {
+        if partial.exists()
+            && let Err(e) = fs::remove_dir_all(&partial)
+        {
+            warn!("failed to remove {}: {e}", partial.display());
+        }
+        return Err(e);
+    }
+    if let Err(e) = fs::remove_dir_all(from) {
+        warn!("failed to remove {} after moving it: {e}", from.display());
+    }
+    Ok(())
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let (src, dst) = (long_path(src), long_path(dst));
+    fs::create_dir_all(&dst)?;
+    for entry in fs::read_dir(&src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest_path = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &dest_path)?;
+        } else {
+            fs::copy(long_path(&path), long_path(&dest_path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Steam app ID of Deep Rock Galactic.
+pub const DRG_STEAM_APP_ID: u32 = 548430;
+
+/// Application user model ID of the Microsoft Store/Game Pass version of Deep Rock Galactic.
+pub const DRG_XBOX_APP_ID: &str =
+    "CoffeeStainStudios.DeepRockGalactic_496a1srhmar9w!AppDeepRockGalacticShipping";
+
+/// Start the game with the [`state::Config::launch_args`] `extra_args` added to its command line.
+/// If mint was started via `mint launch %command%` the command provided by Steam is used and the
+/// game's process returned, for the caller to wait on. Otherwise the game is launched through the
+/// Steam URL handler, or the shell for [`LaunchMethod::Xbox`], and there is no process to watch.
+pub fn launch_game(
+    args: Option<&[String]>,
+    extra_args: &[String],
+    method: Option<LaunchMethod>,
+) -> Result<Option<std::process::Child>, MintError> {
+    if let Some((program, args)) = args.and_then(|a| a.split_first()) {
+        Ok(Some(
+            std::process::Command::new(program)
+                .args(args)
+                .args(extra_args)
+                .spawn()?,
+        ))
+    } else {
+        let target = match method.unwrap_or(LaunchMethod::Steam) {
+            LaunchMethod::Steam => steam_launch_url(extra_args),
+            LaunchMethod::Xbox => {
+                if !extra_args.is_empty() {
+                    warn!("launch options are not passed to the Xbox version: {extra_args:?}");
+                }
+                format!("shell:AppsFolder\\{DRG_XBOX_APP_ID}")
+            }
+        };
+        opener::open(target).map_err(std::io::Error::other)?;
+        Ok(None)
+    }
+}
+
+/// `steam://run` passes arguments as one string, so those containing spaces are quoted.
+fn steam_launch_url(extra_args: &[String]) -> String {
+    if extra_args.is_empty() {
+        return format!("steam://rungameid/{DRG_STEAM_APP_ID}");
+    }
+    let args = extra_args
+        .iter()
+        .map(|a| {
+            if a.contains(' ') {
+                format!("\"{a}\"")
+            } else {
+                a.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "steam://run/{DRG_STEAM_APP_ID}//{}/",
+        args.replace('%', "%25")
+            .replace(' ', "%20")
+            .replace('"', "%22")
+    )
+}
+
+/// Run the [`state::PostGameActions`] of `profile` after the game launched with it exited.
+/// Returns a description of each action taken.
+pub fn run_post_game_actions(state: &mut State, profile: &str) -> Result<Vec<String>, MintError> {
+    let Some(actions) = state.mod_data.post_game.get(profile).cloned() else {
+        return Ok(vec![]);
+    };
+    let mut done = vec![];
+    let installation = state
+        .config
+        .drg_pak_path
+        .as_ref()
+        .and_then(|p| DRGInstallation::from_pak_path(p).ok());
+
+    if actions.snapshot_saves {
+        let installation = installation.as_ref().ok_or_else(|| GenericError {
+            msg: "DRG install not found".to_string(),
+        })?;
+        saves::create(installation, &state.dirs.data_dir, profile, "after playing")
+            .with_generic(|e| format!("failed to snapshot save files: {e}"))?;
+        done.push("save files snapshotted".to_string());
+    }
+
+    if actions.uninstall
+        && state.mod_data.profiles.contains_key(profile)
+        && let Some(pak_path) = &state.config.drg_pak_path
+    {
+        let mut modio_mods = HashSet::new();
+        state.mod_data.for_each_enabled_mod(profile, |mc| {
+            if let Some(id) = state.store.get_mod_info(&mc.spec).and_then(|i| i.modio_id) {
+                modio_mods.insert(id);
+            }
+        });
+        integrate::uninstall(pak_path, modio_mods)
+            .with_generic(|e| format!("failed to uninstall mods: {e}"))?;
+        IntegrationReport::uninstalled().save(&state.dirs.data_dir);
+        done.push("mods uninstalled".to_string());
+    }
+
+    if let Some(switch_to) = &actions.switch_profile {
+        if state.mod_data.profiles.contains_key(switch_to) {
+            state.mod_data.active_profile = switch_to.clone();
+            state.mod_data.save()?;
+            done.push(format!("switched to profile {switch_to:?}"));
+        } else {
+            warn!("not switching to profile {switch_to:?}, it no longer exists");
+        }
+    }
+    Ok(done)
+}
+
+/// Ask Steam to verify the game files, which repairs a corrupted base pak but also undoes the
+/// parts of an install that replace game files.
+pub fn verify_game_files() -> Result<(), MintError> {
+    opener::open(format!("steam://validate/{DRG_STEAM_APP_ID}")).map_err(std::io::Error::other)?;
+    Ok(())
 }
 
 pub fn is_drg_pak<P: AsRef<Path>>(path: P) -> Result<(), MintError> {
@@ -105,7 +595,7 @@ pub async fn resolve_unordered_and_integrate<P: AsRef<Path>>(
     state: &State,
     mod_specs: &[ModSpecification],
     update: bool,
-) -> Result<(), IntegrationError> {
+) -> Result<Vec<IntegrationWarning>, IntegrationError> {
     let mods = state.store.resolve_mods(mod_specs, update).await?;
 
     let mods_set = mod_specs
@@ -129,6 +619,14 @@ pub async fn resolve_unordered_and_integrate<P: AsRef<Path>>(
             warn!("  {d}");
         }
     }
+    warn_violated_constraints(&mods, mod_specs);
+
+    if let Err(e) = state
+        .mod_data
+        .record_history(state::history::Reason::Install)
+    {
+        warn!("failed to snapshot mod data before installing: {e}");
+    }
 
     let to_integrate = mod_specs
         .iter()
@@ -142,11 +640,46 @@ pub async fn resolve_unordered_and_integrate<P: AsRef<Path>>(
     info!("fetching mods...");
     let paths = state.store.fetch_mods(&urls, update, None).await?;
 
-    integrate::integrate(
+    let mut warnings = signing::integration_warnings(&state.store, &to_integrate);
+    let mods = to_integrate.into_iter().zip(paths).collect::<Vec<_>>();
+    let payloads = ModPayload::collect(mod_specs, &mods, &state.mod_data.payload_rules);
+    let asset_winners = integrate::asset_winners(
+        mod_specs,
+        &state.mod_data.get_active_profile().asset_overrides,
+    );
+    warnings.extend(integrate::integrate(
         game_path,
         state.config.deref().into(),
-        to_integrate.into_iter().zip(paths).collect(),
-    )
+        mods,
+        &payloads,
+        &asset_winners,
+        state.config.memory_budget,
+    )?);
+    Ok(warnings)
+}
+
+/// Log the dependencies in `mod_specs` whose version does not satisfy a constraint of a mod
+/// depending on them.
+fn warn_violated_constraints(
+    mods: &HashMap<ModSpecification, ModInfo>,
+    mod_specs: &[ModSpecification],
+) {
+    for dependent in mod_specs.iter().map(|m| &mods[m]) {
+        for (dep, constraint) in &dependent.dependency_constraints {
+            if let Some(dependency) = mod_specs
+                .iter()
+                .map(|m| &mods[m])
+                .find(|m| m.spec.same_mod(dep))
+                && let Some(version) = dependency.version_id()
+                && !constraint.allows(version)
+            {
+                warn!(
+                    "{:?} requires {:?} at version {constraint}, but version {version} is enabled",
+                    dependent.name, dependency.name
+                );
+            }
+        }
+    }
 }
 
 async fn resolve_into_urls(
@@ -176,6 +709,7 @@ async fn resolve_into_urls(
             warn!("  {d}");
         }
     }
+    warn_violated_constraints(&mods, mod_specs);
 
     let urls = mod_specs
         .iter()
@@ -203,21 +737,29 @@ pub async fn resolve_unordered_and_integrate_with_provider_init<P, F>(
     mod_specs: &[ModSpecification],
     update: bool,
     init: F,
-) -> Result<(), MintError>
+) -> Result<Vec<IntegrationWarning>, MintError>
 where
     P: AsRef<Path>,
     F: Fn(&mut State, String, &ProviderFactory) -> Result<(), MintError>,
 {
     loop {
         match resolve_unordered_and_integrate(&game_path, state, mod_specs, update).await {
-            Ok(()) => return Ok(()),
+            Ok(warnings) => {
+                IntegrationReport::new(mod_specs, None::<IntegrationError>)
+                    .with_warnings(&warnings)
+                    .save(&state.dirs.data_dir);
+                return Ok(warnings);
+            }
             Err(ref e)
                 if let IntegrationError::ProviderError { source } = e
                     && let ProviderError::NoProvider { url, factory } = source =>
             {
                 init(state, url.clone(), factory)?
             }
-            Err(e) => Err(e)?,
+            Err(e) => {
+                IntegrationReport::new(mod_specs, Some(&e)).save(&state.dirs.data_dir);
+                Err(e)?
+            }
         }
     }
 }
@@ -0,0 +1,424 @@
+//! Integrity reports for multiplayer lobbies. Every install records the mods it put into the bundle
+//! in the game's `FSD` directory, in load order and with the SHA-256 of each archive. A host shares
+//! a report made from that record, optionally signed with the key used for `mint sign`, and the
+//! players in the lobby check it against their own install. The bundle is built deterministically
+//! from the archives and their order, so matching reports mean matching bundles for the same game
+//! version.
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use snafu::prelude::*;
+
+use crate::providers::{ModInfo, ModSpecification};
+use crate::signing::{self, Signature, SignatureStatus, SigningError, TrustedSigner};
+use mint_lib::DRGInstallation;
+
+/// Name of the record of installed mods, stored in the `FSD` directory.
+pub const INSTALLED_MODS_FILE: &str = "mint_installed.json";
+
+#[derive(Debug, Snafu)]
+pub enum LobbyError {
+    #[snafu(display("no install by mint found, install the mods first"))]
+    NotInstalled,
+    #[snafu(display("invalid lobby report"))]
+    InvalidReport { source: serde_json::Error },
+    #[snafu(transparent)]
+    SigningError { source: SigningError },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+}
+
+/// A mod as it was installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledMod {
+    pub name: String,
+    /// URL of the mod, or only its name for local files.
+    pub url: String,
+    /// The mod.io modfile ID, for mods from mod.io.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    /// Hex encoded SHA-256 of the mod's archive.
+    pub sha256: String,
+}
+
+impl InstalledMod {
+    pub fn new(info: &ModInfo, archive: &Path) -> std::io::Result<Self> {
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut fs::File::open(archive)?, &mut hasher)?;
+        Ok(Self {
+            name: info.name.clone(),
+            url: info.resolution.get_resolvable_url_or_name().to_string(),
+            version: info.version_id(),
+            sha256: hex::encode(hasher.finalize()),
+        })
+    }
+
+    /// Whether both are the same mod, possibly in different versions.
+    fn same_mod(&self, other: &InstalledMod) -> bool {
+        self.name == other.name
+            || ModSpecification::new(self.url.clone())
+                .same_mod(&ModSpecification::new(other.url.clone()))
+    }
+
+    /// Whether this was installed from `spec`. Local files are recorded by their file name only.
+    fn installed_from(&self, spec: &ModSpecification) -> bool {
+        ModSpecification::new(self.url.clone()).same_mod(spec)
+            || Path::new(&spec.url)
+                .file_name()
+                .is_some_and(|name| *name == *self.url)
+    }
+}
+
+/// How a profile differs from the last install.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallChange {
+    /// Enabled in the profile but not installed.
+    Added(ModSpecification),
+    /// Installed but no longer enabled in the profile.
+    Removed(InstalledMod),
+    /// Installed in another version than the profile now has.
+    Updated {
+        installed: InstalledMod,
+        version: u32,
+    },
+}
+
+impl std::fmt::Display for InstallChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallChange::Added(spec) => write!(f, "{} was added", spec.url),
+            InstallChange::Removed(m) => write!(f, "{:?} was removed", m.name),
+            InstallChange::Updated { installed, version } => match installed.version {
+                Some(old) => write!(
+                    f,
+                    "{:?} changed from version {old} to {version}",
+                    installed.name
+                ),
+                None => write!(f, "{:?} changed to version {version}", installed.name),
+            },
+        }
+    }
+}
+
+/// Compare the mods `enabled` in a profile with the `installed` ones. `version` gives the version
+/// a mod would be installed in now, for providers with numbered versions.
+pub fn changes_since_install<V>(
+    installed: &[InstalledMod],
+    enabled: &[ModSpecification],
+    version: V,
+) -> Vec<InstallChange>
+where
+    V: Fn(&ModSpecification) -> Option<u32>,
+{
+    let mut changes = vec![];
+    for spec in enabled {
+        match installed.iter().find(|m| m.installed_from(spec)) {
+            None => changes.push(InstallChange::Added(spec.clone())),
+            Some(m) => {
+                if let Some(version) = version(spec)
+                    && m.version != Some(version)
+                {
+                    changes.push(InstallChange::Updated {
+                        installed: m.clone(),
+                        version,
+                    });
+                }
+            }
+        }
+    }
+    changes.extend(
+        installed
+            .iter()
+            .filter(|m| !enabled.iter().any(|spec| m.installed_from(spec)))
+            .cloned()
+            .map(InstallChange::Removed),
+    );
+    changes
+}
+
+/// Record the mods of an install.
+pub fn write_installed(
+    installation: &DRGInstallation,
+    mods: &[InstalledMod],
+) -> std::io::Result<()> {
+    let buf = serde_json::to_vec_pretty(mods).map_err(std::io::Error::other)?;
+    fs::write(installation.root.join(INSTALLED_MODS_FILE), buf)
+}
+
+/// The mods of the current install, in load order.
+pub fn read_installed(installation: &DRGInstallation) -> Result<Vec<InstalledMod>, LobbyError> {
+    let buf = match fs::read(installation.root.join(INSTALLED_MODS_FILE)) {
+        Ok(buf) => buf,
+        Err(e) if e.kind() == ErrorKind::NotFound => return NotInstalledSnafu.fail(),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_slice(&buf).context(InvalidReportSnafu)
+}
+
+/// Remove the record when the mods are uninstalled.
+pub fn remove_installed(installation: &DRGInstallation) -> std::io::Result<()> {
+    match fs::remove_file(installation.root.join(INSTALLED_MODS_FILE)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// What a lobby host has installed, to share with the players.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LobbyReport {
+    pub created: String,
+    /// Version of mint the host used.
+    pub version: String,
+    /// Installed mods in load order.
+    pub mods: Vec<InstalledMod>,
+    /// Hex encoded SHA-256 of the archives' hashes in load order.
+    pub digest: String,
+    /// Signature of the serialized `mods`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+/// How an install differs from the host's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Difference {
+    /// Installed by the host but not locally.
+    Missing(InstalledMod),
+    /// Installed locally but not by the host.
+    Extra(InstalledMod),
+    /// Installed by both, but from different archives, usually another version.
+    Changed {
+        host: InstalledMod,
+        local: InstalledMod,
+    },
+    /// The same mods in a different order, which changes which mod wins where they conflict.
+    Order,
+}
+
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = |m: &InstalledMod| match m.version {
+            Some(v) => format!("version {v}"),
+            None => format!("archive {}", m.sha256.get(..12).unwrap_or(&m.sha256)),
+        };
+        match self {
+            Difference::Missing(m) => write!(f, "missing {:?} ({})", m.name, m.url),
+            Difference::Extra(m) => write!(f, "{:?} is not installed by the host", m.name),
+            Difference::Changed { host, local } => write!(
+                f,
+                "{:?} differs: the host has {}, you have {}",
+                host.name,
+                version(host),
+                version(local)
+            ),
+            Difference::Order => write!(f, "the mods are installed in a different order"),
+        }
+    }
+}
+
+fn digest(mods: &[InstalledMod]) -> String {
+    let mut hasher = Sha256::new();
+    for m in mods {
+        hasher.update(m.sha256.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+impl LobbyReport {
+    pub fn new(mods: Vec<InstalledMod>) -> Self {
+        Self {
+            created: chrono::Local::now().to_rfc3339(),
+            version: mint_lib::built_info::version().to_string(),
+            digest: digest(&mods),
+            mods,
+            signature: None,
+        }
+    }
+
+    /// Report of the current install.
+    pub fn from_install(installation: &DRGInstallation) -> Result<Self, LobbyError> {
+        Ok(Self::new(read_installed(installation)?))
+    }
+
+    fn signed_data(&self) -> Vec<u8> {
+        serde_json::to_vec(&self.mods).expect("mods serialize")
+    }
+
+    /// Sign the report with the hex encoded `secret_key`.
+    pub fn sign(&mut self, secret_key: &str) -> Result<(), SigningError> {
+        self.signature = Some(signing::sign(&self.signed_data(), secret_key)?);
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LobbyError> {
+        serde_json::from_slice(&fs::read(path)?).context(InvalidReportSnafu)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), LobbyError> {
+        let buf = serde_json::to_vec_pretty(self).map_err(std::io::Error::other)?;
+        Ok(fs::write(path, buf)?)
+    }
+
+    /// Short form of the digest, for comparing installs by reading it out in the lobby chat.
+    pub fn code(&self) -> String {
+        self.digest
+            .get(..12)
+            .unwrap_or(&self.digest)
+            .as_bytes()
+            .chunks(4)
+            .map(|c| String::from_utf8_lossy(c).to_uppercase())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Who signed the report. A report edited after it was made counts as a mismatch, signed or
+    /// not.
+    pub fn signature_status(&self, trusted: &[TrustedSigner]) -> SignatureStatus {
+        if self.digest != digest(&self.mods) {
+            return SignatureStatus::Mismatch;
+        }
+        match &self.signature {
+            Some(signature) => signing::verify_signature(&self.signed_data(), signature, trusted),
+            None => SignatureStatus::Unsigned,
+        }
+    }
+
+    /// How the `installed` mods differ from the host's. Mods are matched by their archive, so the
+    /// same file from another source counts as the same mod.
+    pub fn compare(&self, installed: &[InstalledMod]) -> Vec<Difference> {
+        let mut missing = self
+            .mods
+            .iter()
+            .filter(|m| !installed.iter().any(|l| l.sha256 == m.sha256))
+            .collect::<Vec<_>>();
+        let mut extra = installed
+            .iter()
+            .filter(|l| !self.mods.iter().any(|m| m.sha256 == l.sha256))
+            .collect::<Vec<_>>();
+
+        let mut differences = vec![];
+        missing.retain(|host| match extra.iter().position(|l| l.same_mod(host)) {
+            Some(i) => {
+                differences.push(Difference::Changed {
+                    host: (*host).clone(),
+                    local: extra.remove(i).clone(),
+                });
+                false
+            }
+            None => true,
+        });
+        differences.extend(missing.into_iter().cloned().map(Difference::Missing));
+        differences.extend(extra.into_iter().cloned().map(Difference::Extra));
+
+        if differences.is_empty()
+            && !self
+                .mods
+                .iter()
+                .map(|m| &m.sha256)
+                .eq(installed.iter().map(|l| &l.sha256))
+        {
+            differences.push(Difference::Order);
+        }
+        differences
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(name: &str, url: &str, sha256: &str) -> InstalledMod {
+        InstalledMod {
+            name: name.to_string(),
+            url: url.to_string(),
+            version: None,
+            sha256: sha256.repeat(64 / sha256.len()),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let a = installed("A", "https://mod.io/g/drg/m/a#1/10", "a");
+        let b = installed("B", "https://example.com/b.zip", "b");
+        let c = installed("C", "c.pak", "c");
+        let report = LobbyReport::new(vec![a.clone(), b.clone()]);
+        assert_eq!(report.code().len(), 14);
+
+        assert!(report.compare(&[a.clone(), b.clone()]).is_empty());
+        // the same archive added as a local file
+        let local_b = installed("b", "b.zip", "b");
+        assert!(report.compare(&[a.clone(), local_b]).is_empty());
+        assert_eq!(report.compare(&[b.clone(), a.clone()]), [Difference::Order]);
+
+        let newer_a = installed("A", "https://mod.io/g/drg/m/a#1/11", "d");
+        assert_eq!(
+            report.compare(&[newer_a.clone(), c.clone()]),
+            [
+                Difference::Changed {
+                    host: a.clone(),
+                    local: newer_a
+                },
+                Difference::Missing(b.clone()),
+                Difference::Extra(c)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changes_since_install() {
+        let a = InstalledMod {
+            version: Some(10),
+            ..installed("A", "https://mod.io/g/drg/m/a#1/10", "a")
+        };
+        let b = installed("B", "b.pak", "b");
+        let spec = |url: &str| ModSpecification::new(url.to_string());
+        let version = |s: &ModSpecification| s.version_id();
+
+        let enabled = [spec("https://mod.io/g/drg/m/a#1/10"), spec("/mods/b.pak")];
+        assert!(changes_since_install(&[a.clone(), b.clone()], &enabled, version).is_empty());
+
+        let enabled = [
+            spec("https://mod.io/g/drg/m/a#1/11"),
+            spec("https://example.com/c.zip"),
+        ];
+        assert_eq!(
+            changes_since_install(&[a.clone(), b.clone()], &enabled, version),
+            [
+                InstallChange::Updated {
+                    installed: a,
+                    version: 11
+                },
+                InstallChange::Added(spec("https://example.com/c.zip")),
+                InstallChange::Removed(b),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signature_status() {
+        let (secret, public) = signing::generate_key();
+        let mut report = LobbyReport::new(vec![installed("A", "a.pak", "a")]);
+        assert_eq!(report.signature_status(&[]), SignatureStatus::Unsigned);
+
+        report.sign(&secret).unwrap();
+        let trusted = [TrustedSigner {
+            name: "Host".to_string(),
+            key: public,
+        }];
+        assert_eq!(
+            report.signature_status(&trusted),
+            SignatureStatus::Trusted {
+                signer: "Host".to_string()
+            }
+        );
+
+        report.mods[0].name = "B".to_string();
+        assert_eq!(report.signature_status(&trusted), SignatureStatus::Mismatch);
+        report.mods[0].sha256 = "b".repeat(64);
+        assert_eq!(report.signature_status(&trusted), SignatureStatus::Mismatch);
+    }
+}
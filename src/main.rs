@@ -1,17 +1,38 @@
 use std::collections::BTreeSet;
 use std::path::PathBuf;
+use std::process::ExitCode;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Result, bail};
 use clap::{Parser, Subcommand};
-use tracing::{debug, info};
+use serde_json::json;
+use snafu::prelude::*;
+use tracing::metadata::LevelFilter;
+use tracing::{debug, info, warn};
 
-use mint::mod_lints::{LintId, run_lints};
-use mint::providers::ProviderFactory;
+use mint::deep_link::{self, DeepLink};
+use mint::enabled_mods;
+use mint::error_info::ErrorInfo;
+use mint::health::CheckStatus;
+use mint::integrate::{IntegrationError, IntegrationWarning};
+use mint::lobby::LobbyReport;
+use mint::mod_lints::{LINT_CACHE_FILE, LintCache, LintId, run_lints_cached};
+use mint::providers::{ProviderError, ProviderFactory};
+use mint::sessions::Sessions;
+use mint::signing;
 use mint::{
     Dirs, MintError, resolve_ordered_with_provider_init,
     resolve_unordered_and_integrate_with_provider_init,
 };
-use mint::{gui::gui, providers::ModSpecification, state::State};
+use mint::{
+    gui::{StartupActions, gui},
+    providers::ModSpecification,
+    state::{
+        State,
+        import::{self, Importer},
+        secrets,
+    },
+};
+use mint_lib::DRGInstallation;
 
 /// Command line integration tool.
 #[derive(Parser, Debug)]
@@ -56,6 +77,14 @@ struct ActionIntegrateProfile {
     profile: String,
 }
 
+/// Download the enabled mods of a profile into the cache without installing them, to install
+/// later without a connection.
+#[derive(Parser, Debug)]
+struct ActionDownload {
+    /// Profile to download.
+    profile: String,
+}
+
 /// Launch via steam
 #[derive(Parser, Debug)]
 struct ActionLaunch {
@@ -75,16 +104,158 @@ struct ActionLint {
     profile: String,
 }
 
+/// Write a zip with redacted config, logs and system information for attaching to bug reports.
+#[derive(Parser, Debug)]
+struct ActionSupportBundle {
+    /// Directory to write the bundle to. Defaults to the data directory.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Check the common reasons an install fails and print how to fix them.
+#[derive(Parser, Debug)]
+struct ActionHealthCheck {}
+
+/// Import profiles from another mod manager's data.
+#[derive(Parser, Debug)]
+struct ActionImport {
+    /// File or directory to import, e.g. another install's mod_data.json or a list of mod URLs
+    #[arg(required_unless_present = "list")]
+    path: Option<PathBuf>,
+
+    /// Importer to use instead of detecting the format
+    #[arg(short, long)]
+    importer: Option<String>,
+
+    /// List the available importers and exit
+    #[arg(long, exclusive = true)]
+    list: bool,
+}
+
+/// Sign a mod archive, writing the signature next to it as <archive>.sig to upload along with it.
+#[derive(Parser, Debug)]
+struct ActionSign {
+    /// Archive to sign
+    #[arg(required_unless_present = "generate_key")]
+    archive: Option<PathBuf>,
+
+    /// File holding the secret key. Keep it private.
+    #[arg(short, long)]
+    key: PathBuf,
+
+    /// Write a new secret key to the key file and print its public key for others to trust
+    #[arg(long, conflicts_with = "archive")]
+    generate_key: bool,
+}
+
+/// Manage the signers whose mod archives are trusted.
+#[derive(Parser, Debug)]
+struct ActionTrust {
+    #[command(subcommand)]
+    action: TrustAction,
+}
+
+#[derive(Subcommand, Debug)]
+enum TrustAction {
+    /// List the trusted signers
+    List,
+    /// Trust a signer's public key
+    Add { name: String, key: String },
+    /// Stop trusting a public key
+    Remove { key: String },
+    /// Trust the signers of a trust list, e.g. one shared by the community
+    Import { path: PathBuf },
+    /// Write the trusted signers to a trust list for sharing
+    Export { path: PathBuf },
+}
+
+/// Share the installed mods with a multiplayer lobby, or check them against a host's report.
+#[derive(Parser, Debug)]
+struct ActionLobby {
+    /// Path to FSD-WindowsNoEditor.pak (FSD-WinGDK.pak for Microsoft Store version) located
+    /// inside the "Deep Rock Galactic" installation directory under FSD/Content/Paks. Only
+    /// necessary if it cannot be found automatically.
+    #[arg(short, long, global = true)]
+    fsd_pak: Option<PathBuf>,
+
+    #[command(subcommand)]
+    action: LobbyAction,
+}
+
+/// Pin the mods of a profile to the newest versions released before a date, e.g. to recreate a
+/// pack that worked before a game update.
+#[derive(Parser, Debug)]
+struct ActionPin {
+    /// Profile to pin.
+    profile: String,
+
+    /// Date as YYYY-MM-DD.
+    date: chrono::NaiveDate,
+}
+
+/// Print the mods a profile installs with the version, priority after folder overrides and folder
+/// of each.
+#[derive(Parser, Debug)]
+struct ActionEnabledMods {
+    /// Profile to list. Defaults to the active profile.
+    profile: Option<String>,
+
+    /// File to write the list to instead, as CSV if it ends in .csv and as JSON otherwise
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Print CSV instead of JSON
+    #[arg(long, conflicts_with = "output")]
+    csv: bool,
+}
+
+/// Open a mint:// link, handing it to the running mint if there is one.
+#[derive(Parser, Debug)]
+struct ActionOpenLink {
+    /// Link such as mint://add?url=<mod URL>
+    #[arg(required_unless_present = "register")]
+    link: Option<String>,
+
+    /// Have mint open mint:// links instead, for the current user
+    #[arg(long, conflicts_with = "link")]
+    register: bool,
+}
+
+#[derive(Subcommand, Debug)]
+enum LobbyAction {
+    /// Write a report of the installed mods for the lobby and print its code
+    Report {
+        /// File to write the report to
+        path: PathBuf,
+        /// Sign the report with this secret key, as created by `mint sign --generate-key`
+        #[arg(short, long)]
+        key: Option<PathBuf>,
+    },
+    /// Check the installed mods against a host's report
+    Verify { path: PathBuf },
+}
+
 #[derive(Subcommand, Debug)]
 enum Action {
     Integrate(ActionIntegrate),
     Profile(ActionIntegrateProfile),
+    Download(ActionDownload),
     Launch(ActionLaunch),
     Lint(ActionLint),
+    SupportBundle(ActionSupportBundle),
+    Import(ActionImport),
+    HealthCheck(ActionHealthCheck),
+    Sign(ActionSign),
+    Trust(ActionTrust),
+    Lobby(ActionLobby),
+    Pin(ActionPin),
+    EnabledMods(ActionEnabledMods),
+    OpenLink(ActionOpenLink),
 }
 
 #[derive(Parser, Debug)]
 #[command(author, version=mint_lib::built_info::version())]
+#[command(group(clap::ArgGroup::new("startup").args(["profile", "install", "launch"]).multiple(true)))]
 struct Args {
     #[command(subcommand)]
     action: Option<Action>,
@@ -92,9 +263,183 @@ struct Args {
     /// Location to store configs and data
     #[arg(long)]
     appdata: Option<PathBuf>,
+
+    /// Use the workspace with this name, added in the settings. "default" for the default
+    /// directories. Defaults to the workspace last switched to
+    #[arg(long, conflicts_with = "appdata")]
+    workspace: Option<String>,
+
+    /// Select this profile on startup
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Install the selected profile on startup
+    #[arg(long)]
+    install: bool,
+
+    /// Launch the game on startup, after installing if --install is also given
+    #[arg(long)]
+    launch: bool,
+
+    /// Perform startup actions without showing the GUI and exit once they are complete
+    #[arg(long, requires = "startup")]
+    no_gui: bool,
+
+    /// Start the GUI even if the config or mod data cannot be read, to export or repair them.
+    /// Nothing is saved, mods are not resolved or downloaded and updates are not checked
+    #[arg(long, conflicts_with_all = ["startup", "no_gui"])]
+    safe_mode: bool,
+
+    /// Only log errors to stderr
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Print progress and results as JSON lines on stdout
+    #[arg(long, global = true)]
+    json: bool,
+}
+
+impl Args {
+    fn startup_actions(&self) -> StartupActions {
+        StartupActions {
+            profile: self.profile.clone(),
+            install: self.install,
+            launch: self.launch,
+            link: None,
+            safe_mode: self.safe_mode,
+        }
+    }
+}
+
+/// Output settings shared by all non-GUI actions.
+#[derive(Debug, Clone, Copy)]
+struct Output {
+    quiet: bool,
+    json: bool,
+}
+
+impl Output {
+    /// Emit a single JSON event on stdout if `--json` was passed.
+    fn event(&self, event: &str, mut fields: serde_json::Value) {
+        if !self.json {
+            return;
+        }
+        if let Some(obj) = fields.as_object_mut() {
+            obj.insert("event".into(), event.into());
+        }
+        println!("{fields}");
+    }
+
+    /// Emit the non-fatal problems of an integration, which are already logged as warnings.
+    fn warnings(&self, warnings: &[IntegrationWarning]) {
+        for warning in warnings {
+            self.event("warning", json!(warning));
+        }
+    }
 }
 
-fn main() -> Result<()> {
+/// Errors raised by the CLI itself which need to be told apart by exit code.
+#[derive(Debug, Snafu)]
+enum CliError {
+    #[snafu(display(
+        "could not find DRG pak file, please specify manually with the --fsd_pak flag"
+    ))]
+    MissingPakPath,
+    #[snafu(display("lints reported {count} problem(s)"))]
+    LintProblems { count: usize },
+    #[snafu(display("health check failed {count} check(s)"))]
+    HealthCheckFailed { count: usize },
+    #[snafu(display("installed mods differ from the host's in {count} place(s)"))]
+    LobbyMismatch { count: usize },
+}
+
+/// Failure classes reported through the process exit code. Clap uses 2 for usage errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    Generic,
+    MissingPakPath,
+    ProviderAuth,
+    LintProblems,
+    Integration,
+    HealthCheck,
+    LobbyMismatch,
+}
+
+impl Failure {
+    fn classify(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(e) = cause.downcast_ref::<CliError>() {
+                return match e {
+                    CliError::MissingPakPath => Failure::MissingPakPath,
+                    CliError::LintProblems { .. } => Failure::LintProblems,
+                    CliError::HealthCheckFailed { .. } => Failure::HealthCheck,
+                    CliError::LobbyMismatch { .. } => Failure::LobbyMismatch,
+                };
+            }
+            if let Some(e) = cause.downcast_ref::<MintError>() {
+                return match e {
+                    MintError::InvalidDrgPak { .. } => Failure::MissingPakPath,
+                    MintError::ProviderError { source } => Self::from_provider_error(source),
+                    MintError::IntegrationError {
+                        source: IntegrationError::ProviderError { source },
+                    } => Self::from_provider_error(source),
+                    MintError::IntegrationError { .. } => Failure::Integration,
+                    _ => Failure::Generic,
+                };
+            }
+        }
+        Failure::Generic
+    }
+
+    fn from_provider_error(error: &ProviderError) -> Self {
+        if error.is_auth_failure() {
+            Failure::ProviderAuth
+        } else {
+            Failure::Integration
+        }
+    }
+
+    fn exit_code(self) -> u8 {
+        match self {
+            Failure::Generic => 1,
+            Failure::MissingPakPath => 3,
+            Failure::ProviderAuth => 4,
+            Failure::LintProblems => 5,
+            Failure::Integration => 6,
+            Failure::HealthCheck => 7,
+            Failure::LobbyMismatch => 8,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Failure::Generic => "error",
+            Failure::MissingPakPath => "missing_pak_path",
+            Failure::ProviderAuth => "provider_auth",
+            Failure::LintProblems => "lint_problems",
+            Failure::Integration => "integration",
+            Failure::HealthCheck => "health_check",
+            Failure::LobbyMismatch => "lobby_mismatch",
+        }
+    }
+}
+
+/// The description of the first error in the chain mint knows how to describe.
+fn error_info(error: &anyhow::Error) -> Option<ErrorInfo> {
+    error.chain().find_map(|cause| {
+        if let Some(e) = cause.downcast_ref::<MintError>() {
+            Some(e.info())
+        } else if let Some(e) = cause.downcast_ref::<IntegrationError>() {
+            Some(e.info())
+        } else {
+            cause
+                .downcast_ref::<ProviderError>()
+                .map(ProviderError::info)
+        }
+    })
+}
+
+fn main() -> ExitCode {
     #[cfg(target_os = "windows")]
     {
         // Try to enable ANSI code support on Windows 10 for console. If it fails, then whatever
@@ -103,18 +448,61 @@ fn main() -> Result<()> {
     }
 
     let args = Args::parse();
+    let output = Output {
+        quiet: args.quiet,
+        json: args.json,
+    };
 
-    let dirs = args
-        .appdata
-        .as_ref()
-        .map(Dirs::from_path)
-        .unwrap_or_else(Dirs::default_xdg)?;
+    match run(args, output) {
+        Ok(()) => {
+            output.event("done", json!({}));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            let failure = Failure::classify(&e);
+            let info = error_info(&e);
+            if output.json {
+                output.event(
+                    "error",
+                    json!({
+                        "code": failure.exit_code(),
+                        "kind": failure.name(),
+                        "message": format!("{e:#}"),
+                        "error_code": info.as_ref().map(|i| i.code),
+                        "action": info.as_ref().map(|i| i.action),
+                    }),
+                );
+            } else {
+                eprintln!("Error: {e:?}");
+                if let Some(info) = info {
+                    eprintln!("\n[{}] {}", info.code, info.action);
+                }
+            }
+            ExitCode::from(failure.exit_code())
+        }
+    }
+}
+
+fn run(args: Args, output: Output) -> Result<()> {
+    let dirs = match &args.appdata {
+        Some(appdata) => Dirs::from_path(appdata),
+        None => Dirs::workspace(args.workspace.as_deref()),
+    }?;
 
     unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
 
-    let _guard = mint_lib::setup_logging(dirs.data_dir.join("mint.log"), "mint")?;
+    let stderr_level = if output.quiet {
+        LevelFilter::ERROR
+    } else {
+        LevelFilter::INFO
+    };
+    let _guard =
+        mint_lib::setup_logging_with_level(dirs.data_dir.join("mint.log"), "mint", stderr_level)?;
     debug!("logging setup complete");
 
+    if let Some(workspace) = &dirs.workspace {
+        info!("workspace = {workspace}");
+    }
     info!("config dir = {}", dirs.config_dir.display());
     info!("cache dir = {}", dirs.cache_dir.display());
     info!("data dir = {}", dirs.data_dir.display());
@@ -125,31 +513,75 @@ fn main() -> Result<()> {
 
     debug!(?args);
 
+    let startup = args.startup_actions();
+
     match args.action {
         Some(Action::Integrate(action)) => rt.block_on(async {
-            action_integrate(dirs, action).await?;
+            action_integrate(dirs, output, action).await?;
             Ok(())
         }),
         Some(Action::Profile(action)) => rt.block_on(async {
-            action_integrate_profile(dirs, action).await?;
+            action_integrate_profile(dirs, output, action).await?;
+            Ok(())
+        }),
+        Some(Action::Download(action)) => rt.block_on(async {
+            action_download(dirs, output, action).await?;
+            Ok(())
+        }),
+        Some(Action::Launch(action)) if args.no_gui => rt.block_on(async {
+            action_startup(dirs, output, Some(action.args), startup).await?;
             Ok(())
         }),
         Some(Action::Launch(action)) => {
             std::thread::spawn(move || {
                 rt.block_on(std::future::pending::<()>());
             });
-            gui(dirs, Some(action.args))?;
+            gui(dirs, Some(action.args), startup)?;
             Ok(())
         }
         Some(Action::Lint(action)) => rt.block_on(async {
-            action_lint(dirs, action).await?;
+            action_lint(dirs, output, action).await?;
+            Ok(())
+        }),
+        Some(Action::SupportBundle(action)) => action_support_bundle(dirs, output, action),
+        Some(Action::Import(action)) => action_import(dirs, output, action),
+        Some(Action::HealthCheck(_)) => rt.block_on(action_health_check(dirs, output)),
+        Some(Action::Sign(action)) => action_sign(output, action),
+        Some(Action::Trust(action)) => action_trust(dirs, output, action),
+        Some(Action::Lobby(action)) => action_lobby(dirs, output, action),
+        Some(Action::Pin(action)) => action_pin(dirs, output, action),
+        Some(Action::EnabledMods(action)) => action_enabled_mods(dirs, output, action),
+        Some(Action::OpenLink(ActionOpenLink { link: None, .. })) => {
+            deep_link::register()?;
+            Ok(())
+        }
+        Some(Action::OpenLink(ActionOpenLink {
+            link: Some(link), ..
+        })) => {
+            let parsed = DeepLink::parse(&link)?;
+            if deep_link::forward(&dirs.data_dir, &link) {
+                info!("handed the link to the running mint");
+                return Ok(());
+            }
+            std::thread::spawn(move || {
+                rt.block_on(std::future::pending::<()>());
+            });
+            let startup = StartupActions {
+                link: Some(parsed),
+                ..startup
+            };
+            gui(dirs, None, startup)?;
+            Ok(())
+        }
+        None if args.no_gui => rt.block_on(async {
+            action_startup(dirs, output, None, startup).await?;
             Ok(())
         }),
         None => {
             std::thread::spawn(move || {
                 rt.block_on(std::future::pending::<()>());
             });
-            gui(dirs, None)?;
+            gui(dirs, None, startup)?;
             Ok(())
         }
     }
@@ -163,33 +595,40 @@ fn init_provider(
 ) -> Result<(), MintError> {
     info!("initializing provider for {:?}", url);
 
-    let params = state
-        .config
-        .provider_parameters
-        .entry(factory.id.to_owned())
-        .or_default();
+    let mut params = state.provider_parameters(factory.id);
     for p in factory.parameters {
-        if !params.contains_key(p.name) {
+        if !p.optional && !params.contains_key(p.id) {
+            info!(
+                "{} can also be supplied via the {} environment variable",
+                p.name,
+                secrets::env_var_name(factory.id, p.id)
+            );
             // this blocks but since we're calling it on the main thread it'll be fine
             let value =
                 dialoguer::Password::with_theme(&dialoguer::theme::ColorfulTheme::default())
                     .with_prompt(p.description)
                     .interact()
                     .unwrap();
+            state
+                .config
+                .provider_parameters
+                .entry(factory.id.to_owned())
+                .or_default()
+                .insert(p.id.to_owned(), value.clone());
             params.insert(p.id.to_owned(), value);
         }
     }
-    Ok(state.store.add_provider(factory, params)?)
+    Ok(state.store.add_provider(factory, &params)?)
 }
 
-fn get_pak_path(state: &State, arg: &Option<PathBuf>) -> Result<PathBuf> {
+fn get_pak_path(state: &State, arg: &Option<PathBuf>) -> Result<PathBuf, CliError> {
     arg.as_ref()
         .or_else(|| state.config.drg_pak_path.as_ref())
         .cloned()
-        .context("Could not find DRG pak file, please specify manually with the --fsd_pak flag")
+        .context(MissingPakPathSnafu)
 }
 
-async fn action_integrate(dirs: Dirs, action: ActionIntegrate) -> Result<()> {
+async fn action_integrate(dirs: Dirs, output: Output, action: ActionIntegrate) -> Result<()> {
     let mut state = State::init(dirs)?;
     let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
     debug!(?game_pak_path);
@@ -200,18 +639,27 @@ async fn action_integrate(dirs: Dirs, action: ActionIntegrate) -> Result<()> {
         .map(ModSpecification::new)
         .collect::<Vec<_>>();
 
-    resolve_unordered_and_integrate_with_provider_init(
+    output.event(
+        "progress",
+        json!({ "stage": "integrate", "mods": mod_specs.len() }),
+    );
+    let warnings = resolve_unordered_and_integrate_with_provider_init(
         game_pak_path,
         &mut state,
         &mod_specs,
         action.update,
         init_provider,
     )
-    .await
-    .map_err(|e| anyhow!("{}", e))
+    .await?;
+    output.warnings(&warnings);
+    Ok(())
 }
 
-async fn action_integrate_profile(dirs: Dirs, action: ActionIntegrateProfile) -> Result<()> {
+async fn action_integrate_profile(
+    dirs: Dirs,
+    output: Output,
+    action: ActionIntegrateProfile,
+) -> Result<()> {
     let mut state = State::init(dirs)?;
     let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
     debug!(?game_pak_path);
@@ -221,18 +669,40 @@ async fn action_integrate_profile(dirs: Dirs, action: ActionIntegrateProfile) ->
         mods.push(mc.spec.clone());
     });
 
-    resolve_unordered_and_integrate_with_provider_init(
+    output.event(
+        "progress",
+        json!({ "stage": "integrate", "mods": mods.len() }),
+    );
+    let warnings = resolve_unordered_and_integrate_with_provider_init(
         game_pak_path,
         &mut state,
         &mods,
         action.update,
         init_provider,
     )
-    .await
-    .map_err(|e| anyhow!("{}", e))
+    .await?;
+    output.warnings(&warnings);
+    Ok(())
+}
+
+async fn action_download(dirs: Dirs, output: Output, action: ActionDownload) -> Result<()> {
+    let mut state = State::init(dirs)?;
+
+    let mut mods = Vec::new();
+    state.mod_data.for_each_enabled_mod(&action.profile, |mc| {
+        mods.push(mc.spec.clone());
+    });
+
+    output.event(
+        "progress",
+        json!({ "stage": "download", "mods": mods.len() }),
+    );
+    resolve_ordered_with_provider_init(&mut state, &mods, init_provider).await?;
+    info!("{} mods downloaded", mods.len());
+    Ok(())
 }
 
-async fn action_lint(dirs: Dirs, action: ActionLint) -> Result<()> {
+async fn action_lint(dirs: Dirs, output: Output, action: ActionLint) -> Result<()> {
     let mut state = State::init(dirs)?;
     let game_pak_path = get_pak_path(&state, &action.fsd_pak)?;
     debug!(?game_pak_path);
@@ -242,10 +712,19 @@ async fn action_lint(dirs: Dirs, action: ActionLint) -> Result<()> {
         mods.push(mc.spec.clone());
     });
 
+    output.event(
+        "progress",
+        json!({ "stage": "resolve", "mods": mods.len() }),
+    );
     let mod_paths = resolve_ordered_with_provider_init(&mut state, &mods, init_provider).await?;
 
+    output.event("progress", json!({ "stage": "lint" }));
+
+    let memory_budget = state.config.memory_budget;
+    let cache_path = state.dirs.cache_dir.join(LINT_CACHE_FILE);
     let report = tokio::task::spawn_blocking(move || {
-        run_lints(
+        let mut cache = LintCache::load(&cache_path);
+        let report = run_lints_cached(
             &BTreeSet::from([
                 LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
                 LintId::ASSET_REGISTRY_BIN,
@@ -256,12 +735,389 @@ async fn action_lint(dirs: Dirs, action: ActionLint) -> Result<()> {
                 LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
                 LintId::NON_ASSET_FILES,
                 LintId::SPLIT_ASSET_PAIRS,
+                LintId::CONFIG_OVERRIDES,
+                LintId::UNSUPPORTED_COMPRESSION,
+                LintId::MOUNT_POINT,
+                LintId::LOCALIZATION_CONFLICTS,
             ]),
             mods.into_iter().zip(mod_paths).collect(),
             Some(game_pak_path),
-        )
+            memory_budget,
+            &mut cache,
+            |progress| {
+                debug!(
+                    "linting {} ({} of {})",
+                    progress.mod_spec.url, progress.done, progress.total
+                );
+                output.event(
+                    "progress",
+                    json!({
+                        "stage": "lint",
+                        "done": progress.done,
+                        "total": progress.total,
+                        "mod": progress.mod_spec.url,
+                    }),
+                );
+            },
+        );
+        if let Err(e) = cache.save(&cache_path) {
+            warn!("failed to save lint cache: {e}");
+        }
+        report
     })
     .await??;
-    println!("{report:#?}");
+
+    let counts = report.problem_counts();
+    if output.json {
+        let lints = counts
+            .iter()
+            .map(|(id, count)| (id.to_name_lower(), json!(count)))
+            .collect::<serde_json::Map<_, _>>();
+        output.event("lint_report", json!({ "lints": lints }));
+    } else if !output.quiet {
+        println!("{report:#?}");
+    }
+
+    let count = report.problem_count();
+    ensure!(count == 0, LintProblemsSnafu { count });
+    Ok(())
+}
+
+fn action_support_bundle(dirs: Dirs, output: Output, action: ActionSupportBundle) -> Result<()> {
+    let state = State::init(dirs)?;
+    let dir = action.output.unwrap_or_else(|| state.dirs.data_dir.clone());
+    let path = mint::diagnostics::write_support_bundle(&state, dir)?;
+    if output.json {
+        output.event("support_bundle", json!({ "path": path }));
+    } else {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+async fn action_health_check(dirs: Dirs, output: Output) -> Result<()> {
+    let state = State::init(dirs)?;
+    let results = mint::health::run(
+        state.config.drg_pak_path.clone(),
+        state.dirs.cache_dir.clone(),
+    )
+    .await;
+
+    for result in &results {
+        if output.json {
+            output.event("health_check", json!(result));
+        } else if !output.quiet || result.status == CheckStatus::Fail {
+            println!("{:?}\t{}: {}", result.status, result.name, result.detail);
+            if let Some(fix) = result.fix {
+                println!("\t{fix}");
+            }
+        }
+    }
+
+    let count = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Fail)
+        .count();
+    ensure!(count == 0, HealthCheckFailedSnafu { count });
+    Ok(())
+}
+
+fn action_import(dirs: Dirs, output: Output, action: ActionImport) -> Result<()> {
+    if action.list {
+        for importer in Importer::all() {
+            if output.json {
+                output.event(
+                    "importer",
+                    json!({ "id": importer.id, "name": importer.name }),
+                );
+            } else {
+                println!("{}\t{}", importer.id, importer.name);
+            }
+        }
+        return Ok(());
+    }
+
+    let path = action.path.expect("required unless --list");
+    let mut state = State::init(dirs)?;
+    let importer = match &action.importer {
+        Some(id) => Importer::by_id(id)?,
+        None => Importer::detect(&path)?,
+    };
+    let profiles = importer.import(&path)?;
+    let names = import::add_profiles(&mut state.mod_data, profiles);
+    state.mod_data.save()?;
+
+    for name in &names {
+        let mods = import::mod_count(&state.mod_data.profiles[name]);
+        if output.json {
+            output.event(
+                "imported_profile",
+                json!({ "importer": importer.id, "profile": name, "mods": mods }),
+            );
+        } else if !output.quiet {
+            println!("imported profile {name:?} with {mods} mod(s)");
+        }
+    }
+    Ok(())
+}
+
+fn action_sign(output: Output, action: ActionSign) -> Result<()> {
+    if action.generate_key {
+        if action.key.exists() {
+            bail!(
+                "{} already exists, not overwriting it",
+                action.key.display()
+            );
+        }
+        let (secret, public) = signing::generate_key();
+        fs_err::write(&action.key, secret)?;
+        if output.json {
+            output.event("key", json!({ "path": action.key, "public_key": public }));
+        } else {
+            println!("{public}");
+        }
+        return Ok(());
+    }
+
+    let archive = action.archive.expect("required unless --generate-key");
+    let secret = fs_err::read_to_string(&action.key)?;
+    let path = signing::sign_file(&archive, &secret)?;
+    if output.json {
+        output.event("signature", json!({ "path": path }));
+    } else if !output.quiet {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn action_trust(dirs: Dirs, output: Output, action: ActionTrust) -> Result<()> {
+    let mut state = State::init(dirs)?;
+    match action.action {
+        TrustAction::List => {
+            for signer in &state.config.trusted_signers {
+                if output.json {
+                    output.event("trusted_signer", json!(signer));
+                } else {
+                    println!("{}\t{}", signer.key, signer.name);
+                }
+            }
+        }
+        TrustAction::Add { name, key } => {
+            if !hex::decode(key.trim()).is_ok_and(|k| k.len() == 32) {
+                bail!("{key:?} is not a public key");
+            }
+            let key = key.trim().to_string();
+            state.trust_signers(vec![signing::TrustedSigner { name, key }]);
+        }
+        TrustAction::Remove { key } => {
+            let signers = &mut state.config.trusted_signers;
+            let before = signers.len();
+            signers.retain(|s| !s.key.eq_ignore_ascii_case(key.trim()));
+            if signers.len() == before {
+                bail!("{key:?} is not trusted");
+            }
+            state.config.save()?;
+            state
+                .store
+                .set_trusted_signers(state.config.trusted_signers.clone());
+        }
+        TrustAction::Import { path } => {
+            let added = state.trust_signers(signing::read_trust_list(&path)?);
+            if output.json {
+                output.event("trust_list_imported", json!({ "added": added }));
+            } else if !output.quiet {
+                println!("trusted {added} new signer(s)");
+            }
+        }
+        TrustAction::Export { path } => {
+            fs_err::write(
+                &path,
+                serde_json::to_vec_pretty(&state.config.trusted_signers)?,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn action_lobby(dirs: Dirs, output: Output, action: ActionLobby) -> Result<()> {
+    let state = State::init(dirs)?;
+    let installation = DRGInstallation::from_pak_path(get_pak_path(&state, &action.fsd_pak)?)?;
+    let installed = LobbyReport::from_install(&installation)?;
+    match action.action {
+        LobbyAction::Report { path, key } => {
+            let mut report = installed;
+            if let Some(key) = key {
+                report.sign(&fs_err::read_to_string(key)?)?;
+            }
+            report.write(&path)?;
+            if output.json {
+                output.event(
+                    "lobby_report",
+                    json!({ "path": path, "code": report.code(), "mods": report.mods.len() }),
+                );
+            } else if !output.quiet {
+                println!("{}", report.code());
+            }
+        }
+        LobbyAction::Verify { path } => {
+            let report = LobbyReport::read(&path)?;
+            let status = report.signature_status(&state.config.trusted_signers);
+            if status == signing::SignatureStatus::Mismatch {
+                bail!("the report was changed after it was made");
+            }
+            let differences = report.compare(&installed.mods);
+            if output.json {
+                output.event(
+                    "lobby_report",
+                    json!({ "code": report.code(), "problem": status.problem() }),
+                );
+                for difference in &differences {
+                    output.event(
+                        "lobby_difference",
+                        json!({ "message": difference.to_string() }),
+                    );
+                }
+            } else {
+                if !output.quiet {
+                    match &status {
+                        signing::SignatureStatus::Trusted { signer } => {
+                            println!("{} signed by {signer}", report.code())
+                        }
+                        _ => println!("{} {}", report.code(), status.problem().unwrap_or_default()),
+                    }
+                }
+                for difference in &differences {
+                    println!("{difference}");
+                }
+            }
+            ensure!(
+                differences.is_empty(),
+                LobbyMismatchSnafu {
+                    count: differences.len()
+                }
+            );
+        }
+    }
+    Ok(())
+}
+
+fn action_pin(dirs: Dirs, output: Output, action: ActionPin) -> Result<()> {
+    let mut state = State::init(dirs)?;
+    if !state.mod_data.profiles.contains_key(&action.profile) {
+        bail!("no such profile: {:?}", action.profile);
+    }
+
+    let before = action
+        .date
+        .and_time(chrono::NaiveTime::MIN)
+        .and_utc()
+        .timestamp();
+    let pinned = state.pin_to_date(&action.profile, u64::try_from(before).unwrap_or_default());
+    if output.json {
+        output.event(
+            "pinned",
+            json!({
+                "pinned": pinned.pinned,
+                "too_new": pinned.too_new,
+                "no_history": pinned.no_history,
+            }),
+        );
+    } else if !output.quiet {
+        println!("{pinned}");
+    }
+    Ok(())
+}
+
+fn action_enabled_mods(dirs: Dirs, output: Output, action: ActionEnabledMods) -> Result<()> {
+    let state = State::init(dirs)?;
+    let profile = action
+        .profile
+        .unwrap_or_else(|| state.mod_data.active_profile.clone());
+    if !state.mod_data.profiles.contains_key(&profile) {
+        bail!("no such profile: {profile:?}");
+    }
+
+    let mods = enabled_mods::collect(&state, &profile);
+    if let Some(path) = action.output {
+        enabled_mods::write(&path, &mods)?;
+        if output.json {
+            output.event("enabled_mods", json!({ "path": path, "mods": mods.len() }));
+        } else if !output.quiet {
+            println!("{}", path.display());
+        }
+    } else if action.csv {
+        print!("{}", enabled_mods::to_csv(&mods));
+    } else {
+        println!("{}", enabled_mods::to_json(&mods));
+    }
+    Ok(())
+}
+
+async fn action_startup(
+    dirs: Dirs,
+    output: Output,
+    game_args: Option<Vec<String>>,
+    startup: StartupActions,
+) -> Result<()> {
+    let mut state = State::init(dirs)?;
+
+    if let Some(profile) = startup.profile {
+        if !state.mod_data.profiles.contains_key(&profile) {
+            bail!("no such profile: {profile:?}");
+        }
+        state.mod_data.active_profile = profile;
+        state.mod_data.save()?;
+    }
+
+    if startup.install {
+        let game_pak_path = get_pak_path(&state, &None)?;
+        debug!(?game_pak_path);
+
+        let active_profile = state.mod_data.active_profile.clone();
+        let mods = state.mod_data.enabled_mods_by_priority(&active_profile);
+
+        output.event(
+            "progress",
+            json!({ "stage": "integrate", "mods": mods.len() }),
+        );
+        let warnings = resolve_unordered_and_integrate_with_provider_init(
+            game_pak_path,
+            &mut state,
+            &mods,
+            false,
+            init_provider,
+        )
+        .await?;
+        output.warnings(&warnings);
+        info!("installed profile {active_profile:?}");
+    }
+
+    if startup.launch {
+        output.event("progress", json!({ "stage": "launch" }));
+        let game = mint::launch_game(
+            game_args.as_deref(),
+            &state.config.launch_args,
+            state.config.launch_method(),
+        )?;
+
+        let profile = state.mod_data.active_profile.clone();
+        let session = Sessions::record_launch(&state.dirs.data_dir, &profile);
+        if let Some(mut game) = game {
+            info!("waiting for the game to exit");
+            let launched = std::time::Instant::now();
+            let status = game.wait()?;
+            info!("game exited with {status}");
+            Sessions::record_exit(&state.dirs.data_dir, &session, launched.elapsed());
+            let done = mint::run_post_game_actions(&mut state, &profile)?;
+            if output.json {
+                output.event("post_game", json!({ "actions": done }));
+            } else if !output.quiet {
+                for action in &done {
+                    println!("{action}");
+                }
+            }
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,186 @@
+//! Lint results of each version of each mod, so linting a profile again only reads the paks of
+//! the mods which changed since. A mod's version is told apart by the path, size and modification
+//! time of its file, as every version of a mod is cached at its own path. Lints change between
+//! mint versions, so the cache is dropped when mint is updated.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::SplitAssetPair;
+
+/// File in the cache directory the lint cache is stored in.
+pub const LINT_CACHE_FILE: &str = "lint_cache.json";
+
+/// Result of one lint for one mod.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ModLintResult {
+    /// Whether a lint about the mod as a whole found it.
+    Found(bool),
+    /// Files a lint found, or all of the mod's files for the conflicting lint.
+    Files(BTreeSet<String>),
+    /// Pak version of the mod if it is outdated, see [`repak::Version::from_repr`].
+    PakVersion(Option<usize>),
+    SplitAssetPairs(BTreeMap<String, SplitAssetPair>),
+    /// Compression methods of the mod's pak the game does not support.
+    Compression(BTreeSet<String>),
+    /// Mount point of the mod's pak if the game does not load its files.
+    MountPoint(Option<String>),
+    /// Localization files of the mod with the entries each changes.
+    Localization(BTreeMap<String, BTreeSet<String>>),
+}
+
+/// Size and modification time of a file, which change with its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct FileVersion {
+    size: u64,
+    /// Seconds since the epoch.
+    modified: u64,
+}
+
+impl FileVersion {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_secs(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedMod {
+    version: FileVersion,
+    /// Results by lint, see [`LintCache::lint_key`].
+    results: BTreeMap<String, ModLintResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintCache {
+    /// Version of mint the results were found by.
+    #[serde(default)]
+    mint_version: String,
+    /// Results by the path of the mod's file.
+    mods: BTreeMap<PathBuf, CachedMod>,
+}
+
+impl Default for LintCache {
+    fn default() -> Self {
+        Self {
+            mint_version: env!("CARGO_PKG_VERSION").to_string(),
+            mods: BTreeMap::new(),
+        }
+    }
+}
+
+impl LintCache {
+    /// The cache stored at `path`, empty if there is none, it cannot be read or it was stored by
+    /// another version of mint.
+    pub fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|data| {
+                serde_json::from_slice::<Self>(&data)
+                    .inspect_err(|e| warn!("ignoring unreadable lint cache: {e}"))
+                    .ok()
+            })
+            .filter(|cache| cache.mint_version == env!("CARGO_PKG_VERSION"))
+            .unwrap_or_default()
+    }
+
+    /// Store the cache at `path`, leaving out mods whose files are gone.
+    pub fn save(&mut self, path: &Path) -> std::io::Result<()> {
+        self.mods.retain(|mod_path, _| mod_path.exists());
+        fs::write(path, serde_json::to_vec(self)?)
+    }
+
+    /// Key of `lint` in the results. Results which depend on the game's files are only valid for
+    /// the `game` version they were found with.
+    pub(crate) fn lint_key(lint: &str, game: Option<FileVersion>) -> String {
+        match game {
+            Some(FileVersion { size, modified }) => format!("{lint}@{size}-{modified}"),
+            None => lint.to_string(),
+        }
+    }
+
+    pub(crate) fn get(
+        &self,
+        mod_path: &Path,
+        version: FileVersion,
+        lint_key: &str,
+    ) -> Option<&ModLintResult> {
+        self.mods
+            .get(mod_path)
+            .filter(|m| m.version == version)?
+            .results
+            .get(lint_key)
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        mod_path: &Path,
+        version: FileVersion,
+        lint_key: String,
+        result: ModLintResult,
+    ) {
+        let cached = self
+            .mods
+            .entry(mod_path.to_path_buf())
+            .or_insert_with(|| CachedMod {
+                version,
+                results: BTreeMap::new(),
+            });
+        if cached.version != version {
+            cached.version = version;
+            cached.results.clear();
+        }
+        cached.results.insert(lint_key, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LINT_CACHE_FILE);
+        let version = FileVersion {
+            size: 1,
+            modified: 2,
+        };
+        let mut cache = LintCache::default();
+        cache.insert(
+            Path::new("mod.pak"),
+            version,
+            "empty_archive".to_string(),
+            ModLintResult::Found(true),
+        );
+        fs::write(&path, serde_json::to_vec(&cache).unwrap()).unwrap();
+        let loaded = LintCache::load(&path);
+        assert_eq!(
+            loaded.get(Path::new("mod.pak"), version, "empty_archive"),
+            Some(&ModLintResult::Found(true))
+        );
+
+        // found by another version of mint
+        cache.mint_version = "0.0.0".to_string();
+        fs::write(&path, serde_json::to_vec(&cache).unwrap()).unwrap();
+        let loaded = LintCache::load(&path);
+        assert_eq!(
+            loaded.get(Path::new("mod.pak"), version, "empty_archive"),
+            None
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintError};
+
+/// Files changing the game's global configuration: ini files and anything in a config directory,
+/// plus the assets holding the input, engine and user settings. Players rarely attribute changed
+/// settings or key bindings to a mod.
+#[derive(Default)]
+pub struct ConfigOverridesLint;
+
+const CONFIG_DIRS: [&str; 2] = ["fsd/config/", "engine/config/"];
+
+const CONFIG_EXTENSIONS: [&str; 1] = [".ini"];
+
+/// File names, without extension, of the settings assets.
+const CONFIG_ASSETS: [&str; 6] = [
+    "inputsettings",
+    "gameusersettings",
+    "defaultinput",
+    "defaultengine",
+    "defaultgame",
+    "defaultscalability",
+];
+
+/// Whether `normalized_path` overrides game configuration.
+fn is_config_override(normalized_path: &str) -> bool {
+    let name = normalized_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(normalized_path);
+    let stem = name.split_once('.').map_or(name, |(stem, _)| stem);
+    CONFIG_DIRS
+        .iter()
+        .any(|dir| normalized_path.starts_with(dir))
+        || CONFIG_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+        || CONFIG_ASSETS.contains(&stem)
+}
+
+impl Lint for ConfigOverridesLint {
+    type Output = BTreeMap<ModSpecification, BTreeSet<String>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        let mut config_override_mods = BTreeMap::new();
+
+        lcx.for_each_mod_file(|mod_spec, _, _, _, normalized_path| {
+            if is_config_override(&normalized_path) {
+                config_override_mods
+                    .entry(mod_spec)
+                    .and_modify(|paths: &mut BTreeSet<String>| {
+                        paths.insert(normalized_path.clone());
+                    })
+                    .or_insert_with(|| [normalized_path].into());
+            }
+            Ok(())
+        })?;
+
+        Ok(config_override_mods)
+    }
+}
@@ -1,6 +1,6 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 
 use crate::providers::ModSpecification;
 
@@ -11,23 +11,42 @@ pub struct ConflictingModsLint;
 
 const CONFLICTING_MODS_LINT_WHITELIST: [&str; 1] = ["fsd/content/_interop"];
 
-impl Lint for ConflictingModsLint {
-    type Output = BTreeMap<String, IndexSet<ModSpecification>>;
-
-    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
-        let mut per_path_modifiers = BTreeMap::new();
+impl ConflictingModsLint {
+    /// Normalized paths of the files of each mod, in the order of the mods.
+    pub fn mod_files(
+        lcx: &LintCtxt,
+    ) -> Result<IndexMap<ModSpecification, BTreeSet<String>>, LintError> {
+        let mut mod_files = IndexMap::<_, BTreeSet<_>>::new();
 
         lcx.for_each_mod_file(|mod_spec, _, _, _, normalized_path| {
-            per_path_modifiers
-                .entry(normalized_path)
-                .and_modify(|modifiers: &mut IndexSet<ModSpecification>| {
-                    modifiers.insert(mod_spec.clone());
-                })
-                .or_insert_with(|| [mod_spec.clone()].into());
+            mod_files
+                .entry(mod_spec)
+                .or_default()
+                .insert(normalized_path);
             Ok(())
         })?;
 
-        let conflicting_mods = per_path_modifiers
+        Ok(mod_files)
+    }
+
+    /// Files changed by more than one mod of `mod_files`, see [`Self::mod_files`].
+    pub fn conflicts<'a>(
+        mod_files: impl IntoIterator<Item = (&'a ModSpecification, &'a BTreeSet<String>)>,
+    ) -> BTreeMap<String, IndexSet<ModSpecification>> {
+        let mut per_path_modifiers = BTreeMap::new();
+
+        for (mod_spec, paths) in mod_files {
+            for path in paths {
+                per_path_modifiers
+                    .entry(path.clone())
+                    .and_modify(|modifiers: &mut IndexSet<ModSpecification>| {
+                        modifiers.insert(mod_spec.clone());
+                    })
+                    .or_insert_with(|| [mod_spec.clone()].into());
+            }
+        }
+
+        per_path_modifiers
             .into_iter()
             .filter(|(p, _)| {
                 for whitelisted_path in CONFLICTING_MODS_LINT_WHITELIST {
@@ -38,8 +57,14 @@ impl Lint for ConflictingModsLint {
                 true
             })
             .filter(|(_, modifiers)| modifiers.len() > 1)
-            .collect::<BTreeMap<String, IndexSet<ModSpecification>>>();
+            .collect::<BTreeMap<String, IndexSet<ModSpecification>>>()
+    }
+}
 
-        Ok(conflicting_mods)
+impl Lint for ConflictingModsLint {
+    type Output = BTreeMap<String, IndexSet<ModSpecification>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        Ok(Self::conflicts(&Self::mod_files(lcx)?))
     }
 }
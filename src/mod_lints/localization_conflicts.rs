@@ -0,0 +1,223 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use indexmap::IndexSet;
+
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintError};
+
+/// Localization files changed by more than one mod. The game loads only one mod's version of each
+/// file, so the translations of the others are lost and the UI ends up in mixed languages.
+#[derive(Default)]
+pub struct LocalizationConflictsLint;
+
+/// Where the game's localization files and string tables live.
+const LOCALIZATION_DIRS: [&str; 1] = ["fsd/content/localization/"];
+
+const LOCALIZATION_EXTENSIONS: [&str; 2] = [".locres", ".locmeta"];
+
+/// Magic at the start of locres files newer than the legacy format.
+const LOCRES_MAGIC: [u8; 16] = [
+    0x0e, 0x14, 0x74, 0x75, 0x67, 0x4a, 0x03, 0xfc, 0x4a, 0x15, 0x90, 0x9d, 0xc3, 0x37, 0x7f, 0x1b,
+];
+
+/// A localization file changed by several mods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalizationConflict {
+    pub mods: IndexSet<ModSpecification>,
+    /// Entries, as `namespace/key`, changed by more than one of the mods. Empty if the file is not
+    /// a locres file the entries could be read from.
+    pub entries: BTreeSet<String>,
+}
+
+/// Whether `normalized_path` is a localization file or string table.
+fn is_localization(normalized_path: &str) -> bool {
+    LOCALIZATION_DIRS
+        .iter()
+        .any(|dir| normalized_path.starts_with(dir))
+        || LOCALIZATION_EXTENSIONS
+            .iter()
+            .any(|ext| normalized_path.ends_with(ext))
+}
+
+fn read_u32(r: &mut impl Read) -> std::io::Result<u32> {
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+/// An `FString`: its length including the terminating null, negative for UTF-16. A length past
+/// the end of the data is an error rather than allocated.
+fn read_string(r: &mut Cursor<&[u8]>) -> std::io::Result<String> {
+    let len = read_u32(r)? as i32;
+    let size = len.unsigned_abs() as u64 * if len < 0 { 2 } else { 1 };
+    let remaining = (r.get_ref().len() as u64).saturating_sub(r.position());
+    if size > remaining {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("string of {size} bytes with only {remaining} left"),
+        ));
+    }
+    let string = if len < 0 {
+        let mut buf = vec![0; len.unsigned_abs() as usize * 2];
+        r.read_exact(&mut buf)?;
+        let chars = buf.chunks(2).map(|c| u16::from_le_bytes([c[0], c[1]]));
+        String::from_utf16_lossy(&chars.collect::<Vec<_>>())
+    } else {
+        let mut buf = vec![0; len as usize];
+        r.read_exact(&mut buf)?;
+        String::from_utf8_lossy(&buf).into_owned()
+    };
+    Ok(string.trim_end_matches('\0').to_string())
+}
+
+/// Entries of a locres file as `namespace/key`, in any of the versions the engine writes.
+fn locres_entries(data: &[u8]) -> std::io::Result<BTreeSet<String>> {
+    let mut r = Cursor::new(data);
+    let mut magic = [0; 16];
+    r.read_exact(&mut magic)?;
+    let version = if magic == LOCRES_MAGIC {
+        let mut version = [0];
+        r.read_exact(&mut version)?;
+        version[0]
+    } else {
+        r.rewind()?;
+        0
+    };
+    if version >= 1 {
+        // offset of the localized strings
+        r.seek(SeekFrom::Current(8))?;
+    }
+    if version >= 2 {
+        // number of entries
+        read_u32(&mut r)?;
+    }
+
+    let mut entries = BTreeSet::new();
+    for _ in 0..read_u32(&mut r)? {
+        if version >= 2 {
+            read_u32(&mut r)?;
+        }
+        let namespace = read_string(&mut r)?;
+        for _ in 0..read_u32(&mut r)? {
+            if version >= 2 {
+                read_u32(&mut r)?;
+            }
+            let key = read_string(&mut r)?;
+            // hash of the source string
+            read_u32(&mut r)?;
+            if version >= 1 {
+                read_u32(&mut r)?;
+            } else {
+                read_string(&mut r)?;
+            }
+            entries.insert(format!("{namespace}/{key}"));
+        }
+    }
+    Ok(entries)
+}
+
+impl LocalizationConflictsLint {
+    /// Files changed by more than one mod of `mod_files`, the localization files of each mod with
+    /// the entries they change, see [`LocalizationConflict::entries`].
+    pub fn conflicts<'a>(
+        mod_files: impl IntoIterator<
+            Item = (&'a ModSpecification, &'a BTreeMap<String, BTreeSet<String>>),
+        >,
+    ) -> BTreeMap<String, LocalizationConflict> {
+        let mut per_path = BTreeMap::<_, Vec<_>>::new();
+        for (mod_spec, files) in mod_files {
+            for (path, entries) in files {
+                per_path
+                    .entry(path.clone())
+                    .or_default()
+                    .push((mod_spec, entries));
+            }
+        }
+
+        per_path
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .map(|(path, files)| {
+                let mut seen = BTreeSet::new();
+                let mut entries = BTreeSet::new();
+                for (_, file_entries) in &files {
+                    for entry in *file_entries {
+                        if !seen.insert(entry) {
+                            entries.insert(entry.clone());
+                        }
+                    }
+                }
+                let mods = files.into_iter().map(|(m, _)| m.clone()).collect();
+                (path, LocalizationConflict { mods, entries })
+            })
+            .collect()
+    }
+}
+
+impl Lint for LocalizationConflictsLint {
+    type Output = BTreeMap<ModSpecification, BTreeMap<String, BTreeSet<String>>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        let mut localization_files = BTreeMap::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                let mount = PathBuf::from(pak_reader.mount_point());
+                for p in pak_reader.files() {
+                    let path = mount.join(&p);
+                    let Ok(path) = path.strip_prefix("../../../") else {
+                        continue;
+                    };
+                    let normalized_path = path.to_string_lossy().replace('\\', "/");
+                    let normalized_path = normalized_path.to_ascii_lowercase();
+                    if !is_localization(&normalized_path) {
+                        continue;
+                    }
+                    // string tables and unreadable files conflict as a whole
+                    let entries = if normalized_path.ends_with(".locres") {
+                        pak_reader
+                            .get(&p, pak_read_seek)
+                            .ok()
+                            .and_then(|data| locres_entries(&data).ok())
+                            .unwrap_or_default()
+                    } else {
+                        BTreeSet::new()
+                    };
+                    localization_files
+                        .entry(mod_spec.clone())
+                        .or_insert_with(BTreeMap::new)
+                        .insert(normalized_path, entries);
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(localization_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_string() {
+        let mut data = 4u32.to_le_bytes().to_vec();
+        data.extend(b"key\0");
+        assert_eq!(read_string(&mut Cursor::new(&data[..])).unwrap(), "key");
+
+        // a corrupt length is not allocated
+        for len in [i32::MAX, i32::MIN, 5] {
+            let mut data = len.to_le_bytes().to_vec();
+            data.extend(b"key\0");
+            let err = read_string(&mut Cursor::new(&data[..])).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+}
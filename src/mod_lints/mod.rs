@@ -1,20 +1,26 @@
 mod archive_multiple_paks;
 mod archive_only_non_pak_files;
 mod asset_register_bin;
+mod cache;
+mod config_overrides;
 mod conflicting_mods;
 mod empty_archive;
+mod localization_conflicts;
+mod mount_point;
 mod non_asset_files;
 mod outdated_pak_version;
 mod shader_files;
 mod split_asset_pairs;
 mod unmodified_game_assets;
+mod unsupported_compression;
 
-use std::collections::{BTreeMap, BTreeSet};
-use std::io::{BufReader, Cursor, Read};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use fs_err as fs;
 use indexmap::IndexSet;
+use rayon::prelude::*;
 use repak::PakReader;
 use snafu::prelude::*;
 use tracing::trace;
@@ -22,13 +28,21 @@ use tracing::trace;
 use self::archive_multiple_paks::ArchiveMultiplePaksLint;
 use self::archive_only_non_pak_files::ArchiveOnlyNonPakFilesLint;
 use self::asset_register_bin::AssetRegisterBinLint;
+use self::cache::{FileVersion, ModLintResult};
+pub use self::cache::{LINT_CACHE_FILE, LintCache};
+use self::config_overrides::ConfigOverridesLint;
 use self::empty_archive::EmptyArchiveLint;
+pub use self::localization_conflicts::LocalizationConflict;
+use self::localization_conflicts::LocalizationConflictsLint;
+use self::mount_point::MountPointLint;
 use self::non_asset_files::NonAssetFilesLint;
 use self::outdated_pak_version::OutdatedPakVersionLint;
 use self::shader_files::ShaderFilesLint;
 pub use self::split_asset_pairs::SplitAssetPair;
 use self::split_asset_pairs::SplitAssetPairsLint;
+pub use self::unmodified_game_assets::GameFileHashes;
 use self::unmodified_game_assets::UnmodifiedGameAssetsLint;
+use self::unsupported_compression::UnsupportedCompressionLint;
 use crate::mod_lints::conflicting_mods::ConflictingModsLint;
 use crate::providers::{ModSpecification, ReadSeek};
 
@@ -48,20 +62,29 @@ pub enum LintError {
     OnlyNonPakFiles,
     #[snafu(display("some lints require specifying a valid game pak path"))]
     InvalidGamePath,
+    #[snafu(display("unknown lint {name}"))]
+    UnknownLint { name: &'static str },
 }
 
 pub struct LintCtxt {
     pub(crate) mods: IndexSet<(ModSpecification, PathBuf)>,
     pub(crate) fsd_pak_path: Option<PathBuf>,
+    /// MiB of each pak extracted from an archive to keep in memory.
+    pub(crate) memory_budget: u64,
 }
 
 impl LintCtxt {
     pub fn init(
         mods: IndexSet<(ModSpecification, PathBuf)>,
         fsd_pak_path: Option<PathBuf>,
+        memory_budget: u64,
     ) -> Result<Self, LintError> {
         trace!("LintCtxt::init");
-        Ok(Self { mods, fsd_pak_path })
+        Ok(Self {
+            mods,
+            fsd_pak_path,
+            memory_budget,
+        })
     }
 
     pub fn for_each_mod<F, EmptyArchiveHandler, OnlyNonPakFilesHandler, MultiplePakFilesHandler>(
@@ -78,8 +101,9 @@ impl LintCtxt {
         MultiplePakFilesHandler: FnMut(ModSpecification),
     {
         for (mod_spec, mod_pak_path) in &self.mods {
-            let maybe_archive_reader = Box::new(BufReader::new(fs::File::open(mod_pak_path)?));
-            let bufs = match lint_get_all_files_from_data(maybe_archive_reader) {
+            let maybe_archive_reader = crate::archive::open(mod_pak_path, self.memory_budget)?;
+            let bufs = match lint_get_all_files_from_data(maybe_archive_reader, self.memory_budget)
+            {
                 Ok(bufs) => bufs,
                 Err(e) => match e {
                     LintError::EmptyArchive => {
@@ -135,7 +159,10 @@ impl LintCtxt {
                 let mount = PathBuf::from(pak_reader.mount_point());
                 for p in pak_reader.files() {
                     let path = mount.join(&p);
-                    let path_buf = path.strip_prefix("../../../")?;
+                    // outside the game's directory, see the mount point lint
+                    let Ok(path_buf) = path.strip_prefix("../../../") else {
+                        continue;
+                    };
                     let normalized_path = &path_buf.to_string_lossy().replace('\\', "/");
                     let normalized_path = normalized_path.to_ascii_lowercase();
                     f(
@@ -161,8 +188,11 @@ pub(crate) enum PakOrNotPak {
     NotPak,
 }
 
+/// The files in `data`, or `data` itself if it is not a zip archive. Paks are kept in memory up to
+/// `memory_budget` MiB each, other files are not read.
 pub(crate) fn lint_get_all_files_from_data(
     mut data: Box<dyn ReadSeek>,
+    memory_budget: u64,
 ) -> Result<Vec<(PathBuf, PakOrNotPak)>, LintError> {
     if let Ok(mut archive) = zip::ZipArchive::new(&mut data) {
         ensure!(!archive.is_empty(), EmptyArchiveSnafu);
@@ -177,15 +207,9 @@ pub(crate) fn lint_get_all_files_from_data(
                 && file.is_file()
             {
                 if p.extension().filter(|e| e == &"pak").is_some() {
-                    let mut buf = vec![];
-                    file.read_to_end(&mut buf)?;
-                    files.push((
-                        p.to_path_buf(),
-                        PakOrNotPak::Pak(Box::new(Cursor::new(buf))),
-                    ));
+                    let pak = crate::archive::buffer(&mut file, memory_budget)?;
+                    files.push((p.to_path_buf(), PakOrNotPak::Pak(pak)));
                 } else {
-                    let mut buf = vec![];
-                    file.read_to_end(&mut buf)?;
                     files.push((p.to_path_buf(), PakOrNotPak::NotPak));
                 }
             }
@@ -253,6 +277,36 @@ impl LintId {
     pub const UNMODIFIED_GAME_ASSETS: Self = LintId {
         name: "unmodified_game_assets",
     };
+    pub const CONFIG_OVERRIDES: Self = LintId {
+        name: "config_overrides",
+    };
+    pub const UNSUPPORTED_COMPRESSION: Self = LintId {
+        name: "unsupported_compression",
+    };
+    pub const MOUNT_POINT: Self = LintId {
+        name: "mount_point",
+    };
+    pub const LOCALIZATION_CONFLICTS: Self = LintId {
+        name: "localization_conflicts",
+    };
+
+    /// Every lint, in the order they are listed in.
+    pub const ALL: [Self; 14] = [
+        Self::CONFLICTING,
+        Self::ASSET_REGISTRY_BIN,
+        Self::SHADER_FILES,
+        Self::OUTDATED_PAK_VERSION,
+        Self::EMPTY_ARCHIVE,
+        Self::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
+        Self::ARCHIVE_WITH_MULTIPLE_PAKS,
+        Self::NON_ASSET_FILES,
+        Self::SPLIT_ASSET_PAIRS,
+        Self::UNMODIFIED_GAME_ASSETS,
+        Self::CONFIG_OVERRIDES,
+        Self::UNSUPPORTED_COMPRESSION,
+        Self::MOUNT_POINT,
+        Self::LOCALIZATION_CONFLICTS,
+    ];
 }
 
 #[derive(Default, Debug)]
@@ -268,57 +322,473 @@ pub struct LintReport {
     pub split_asset_pairs_mods:
         Option<BTreeMap<ModSpecification, BTreeMap<String, SplitAssetPair>>>,
     pub unmodified_game_assets_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
+    pub config_override_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
+    /// Compression methods the game does not support by mod.
+    pub unsupported_compression_mods: Option<BTreeMap<ModSpecification, BTreeSet<String>>>,
+    /// Mount point of each mod whose files the game does not load.
+    pub mount_point_mods: Option<BTreeMap<ModSpecification, String>>,
+    /// Localization files changed by more than one mod.
+    pub localization_conflicts: Option<BTreeMap<String, LocalizationConflict>>,
 }
 
+impl LintReport {
+    /// Number of problems found by each lint that was run.
+    pub fn problem_counts(&self) -> BTreeMap<LintId, usize> {
+        [
+            (
+                LintId::CONFLICTING,
+                self.conflicting_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::ASSET_REGISTRY_BIN,
+                self.asset_register_bin_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::SHADER_FILES,
+                self.shader_file_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::OUTDATED_PAK_VERSION,
+                self.outdated_pak_version_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::EMPTY_ARCHIVE,
+                self.empty_archive_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES,
+                self.archive_with_only_non_pak_files_mods
+                    .as_ref()
+                    .map(|m| m.len()),
+            ),
+            (
+                LintId::ARCHIVE_WITH_MULTIPLE_PAKS,
+                self.archive_with_multiple_paks_mods
+                    .as_ref()
+                    .map(|m| m.len()),
+            ),
+            (
+                LintId::NON_ASSET_FILES,
+                self.non_asset_file_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::SPLIT_ASSET_PAIRS,
+                self.split_asset_pairs_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::UNMODIFIED_GAME_ASSETS,
+                self.unmodified_game_assets_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::CONFIG_OVERRIDES,
+                self.config_override_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::UNSUPPORTED_COMPRESSION,
+                self.unsupported_compression_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::MOUNT_POINT,
+                self.mount_point_mods.as_ref().map(|m| m.len()),
+            ),
+            (
+                LintId::LOCALIZATION_CONFLICTS,
+                self.localization_conflicts.as_ref().map(|m| m.len()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(id, count)| Some((id, count?)))
+        .collect()
+    }
+
+    pub fn problem_count(&self) -> usize {
+        self.problem_counts().values().sum()
+    }
+
+    /// Problems found by lint `id`, one per mod and file. Conflicts are listed once for each mod
+    /// changing the file.
+    pub fn findings(&self, id: LintId) -> Vec<LintFinding> {
+        fn per_file<'a>(
+            mods: Option<&'a BTreeMap<ModSpecification, BTreeSet<String>>>,
+        ) -> impl Iterator<Item = LintFinding> + 'a {
+            mods.into_iter().flatten().flat_map(|(mod_spec, paths)| {
+                paths
+                    .iter()
+                    .map(|path| LintFinding::new(mod_spec, Some(path.clone()), None))
+            })
+        }
+        fn per_mod(mods: Option<&BTreeSet<ModSpecification>>) -> Vec<LintFinding> {
+            mods.into_iter()
+                .flatten()
+                .map(|mod_spec| LintFinding::new(mod_spec, None, None))
+                .collect()
+        }
+
+        match id {
+            LintId::CONFLICTING => self
+                .conflicting_mods
+                .iter()
+                .flatten()
+                .flat_map(|(path, mods)| {
+                    mods.iter()
+                        .map(|mod_spec| LintFinding::new(mod_spec, Some(path.clone()), None))
+                })
+                .collect(),
+            LintId::ASSET_REGISTRY_BIN => per_file(self.asset_register_bin_mods.as_ref()).collect(),
+            LintId::SHADER_FILES => per_file(self.shader_file_mods.as_ref()).collect(),
+            LintId::OUTDATED_PAK_VERSION => self
+                .outdated_pak_version_mods
+                .iter()
+                .flatten()
+                .map(|(mod_spec, version)| {
+                    LintFinding::new(mod_spec, None, Some(format!("pak version {version}")))
+                })
+                .collect(),
+            LintId::EMPTY_ARCHIVE => per_mod(self.empty_archive_mods.as_ref()),
+            LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
+                per_mod(self.archive_with_only_non_pak_files_mods.as_ref())
+            }
+            LintId::ARCHIVE_WITH_MULTIPLE_PAKS => {
+                per_mod(self.archive_with_multiple_paks_mods.as_ref())
+            }
+            LintId::NON_ASSET_FILES => per_file(self.non_asset_file_mods.as_ref()).collect(),
+            LintId::SPLIT_ASSET_PAIRS => self
+                .split_asset_pairs_mods
+                .iter()
+                .flatten()
+                .flat_map(|(mod_spec, files)| {
+                    files.iter().map(|(path, kind)| {
+                        let detail = match kind {
+                            SplitAssetPair::MissingUasset => "missing matching .uasset file",
+                            SplitAssetPair::MissingUexp => "missing matching .uexp file",
+                        };
+                        LintFinding::new(mod_spec, Some(path.clone()), Some(detail.to_string()))
+                    })
+                })
+                .collect(),
+            LintId::UNMODIFIED_GAME_ASSETS => {
+                per_file(self.unmodified_game_assets_mods.as_ref()).collect()
+            }
+            LintId::CONFIG_OVERRIDES => per_file(self.config_override_mods.as_ref()).collect(),
+            LintId::UNSUPPORTED_COMPRESSION => self
+                .unsupported_compression_mods
+                .iter()
+                .flatten()
+                .flat_map(|(mod_spec, methods)| {
+                    methods.iter().map(|method| {
+                        LintFinding::new(mod_spec, None, Some(format!("compressed with {method}")))
+                    })
+                })
+                .collect(),
+            LintId::MOUNT_POINT => self
+                .mount_point_mods
+                .iter()
+                .flatten()
+                .map(|(mod_spec, mount_point)| {
+                    let detail = format!("mounted at `{mount_point}`, expected `../../../FSD/`");
+                    LintFinding::new(mod_spec, None, Some(detail))
+                })
+                .collect(),
+            LintId::LOCALIZATION_CONFLICTS => self
+                .localization_conflicts
+                .iter()
+                .flatten()
+                .flat_map(|(path, conflict)| {
+                    let detail = match conflict.entries.len() {
+                        0 => format!("changed by {} mods", conflict.mods.len()),
+                        n => {
+                            let entries = conflict.entries.iter().take(5).cloned();
+                            let entries = entries.collect::<Vec<_>>().join(", ");
+                            let mut detail = format!("overlapping entries: {entries}");
+                            if n > 5 {
+                                detail.push_str(&format!(" and {} more", n - 5));
+                            }
+                            detail
+                        }
+                    };
+                    conflict.mods.iter().map(move |mod_spec| {
+                        LintFinding::new(mod_spec, Some(path.clone()), Some(detail.clone()))
+                    })
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// A problem a lint found in a mod.
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub mod_spec: ModSpecification,
+    /// File inside the mod's pak the problem is about.
+    pub path: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl LintFinding {
+    fn new(mod_spec: &ModSpecification, path: Option<String>, detail: Option<String>) -> Self {
+        Self {
+            mod_spec: mod_spec.clone(),
+            path,
+            detail,
+        }
+    }
+}
+
+/// Progress of [`run_lints_cached`], reported as each mod is started.
+#[derive(Debug, Clone)]
+pub struct LintProgress {
+    /// Mods analyzed so far.
+    pub done: usize,
+    /// Mods to analyze, i.e. those without cached results.
+    pub total: usize,
+    /// The mod being analyzed.
+    pub mod_spec: ModSpecification,
+}
+
+/// Run `enabled_lints` on `mods`. At most `memory_budget` MiB of each pak are kept in memory, see
+/// [`crate::archive::buffer`].
 pub fn run_lints(
     enabled_lints: &BTreeSet<LintId>,
     mods: IndexSet<(ModSpecification, PathBuf)>,
     fsd_pak_path: Option<PathBuf>,
+    memory_budget: u64,
 ) -> Result<LintReport, LintError> {
-    let lint_ctxt = LintCtxt::init(mods, fsd_pak_path)?;
-    let mut lint_report = LintReport::default();
+    run_lints_cached(
+        enabled_lints,
+        mods,
+        fsd_pak_path,
+        memory_budget,
+        &mut LintCache::default(),
+        |_| {},
+    )
+}
+
+/// Least MiB a mod linted in parallel with others gets of the memory budget.
+const MIN_WORKER_BUDGET: u64 = 64;
+
+/// How many of `cores` lint mods at once, and the MiB of memory each may use, so that together
+/// they stay within `memory_budget`.
+fn lint_workers(memory_budget: u64, cores: usize) -> (usize, u64) {
+    let workers = (memory_budget / MIN_WORKER_BUDGET).clamp(1, cores.max(1) as u64);
+    (workers as usize, memory_budget / workers)
+}
+
+/// Like [`run_lints`], but mods with results in `cache` are not read again. The results of the
+/// others are added to it. Mods are analyzed in parallel, as many at once as `memory_budget`
+/// allows, see [`lint_workers`], calling `progress` as each is started.
+pub fn run_lints_cached(
+    enabled_lints: &BTreeSet<LintId>,
+    mods: IndexSet<(ModSpecification, PathBuf)>,
+    fsd_pak_path: Option<PathBuf>,
+    memory_budget: u64,
+    cache: &mut LintCache,
+    progress: impl Fn(LintProgress) + Sync,
+) -> Result<LintReport, LintError> {
+    let game_version = fsd_pak_path.as_deref().and_then(FileVersion::of);
+    let lint_keys = enabled_lints
+        .iter()
+        .map(|lint_id| {
+            // without the game's pak the lint fails, it must not be skipped
+            let key = if *lint_id == LintId::UNMODIFIED_GAME_ASSETS {
+                (
+                    LintCache::lint_key(lint_id.name, game_version),
+                    game_version.is_some(),
+                )
+            } else {
+                (LintCache::lint_key(lint_id.name, None), true)
+            };
+            (*lint_id, key)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut results: BTreeMap<LintId, HashMap<ModSpecification, ModLintResult>> = BTreeMap::new();
+    let mut to_check = vec![];
+    for (mod_spec, path) in &mods {
+        let version = FileVersion::of(path);
+        let mut missing = vec![];
+        for (lint_id, (key, use_cache)) in &lint_keys {
+            match version.and_then(|v| cache.get(path, v, key)) {
+                Some(result) if *use_cache => {
+                    results
+                        .entry(*lint_id)
+                        .or_default()
+                        .insert(mod_spec.clone(), result.clone());
+                }
+                _ => missing.push(*lint_id),
+            }
+        }
+        if !missing.is_empty() {
+            to_check.push((mod_spec, path, version, missing));
+        }
+    }
+    trace!("{} of {} mods to check", to_check.len(), mods.len());
+
+    // hashed once rather than for each mod
+    let game_file_hashes = if to_check
+        .iter()
+        .any(|(_, _, _, missing)| missing.contains(&LintId::UNMODIFIED_GAME_ASSETS))
+    {
+        let Some(game_pak_path) = &fsd_pak_path else {
+            InvalidGamePathSnafu.fail()?
+        };
+        Some(Arc::new(UnmodifiedGameAssetsLint::hash_game_files(
+            game_pak_path,
+        )?))
+    } else {
+        None
+    };
+
+    let total = to_check.len();
+    let done = AtomicUsize::new(0);
+    let (workers, worker_budget) = lint_workers(memory_budget, rayon::current_num_threads());
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers)
+        .build()
+        .map_err(std::io::Error::other)?;
+    let checked = pool.install(|| {
+        to_check
+            .par_iter()
+            .map(|(mod_spec, path, _, missing)| {
+                progress(LintProgress {
+                    done: done.load(Ordering::Relaxed),
+                    total,
+                    mod_spec: (*mod_spec).clone(),
+                });
+                let lint_ctxt = LintCtxt::init(
+                    [((*mod_spec).clone(), (*path).clone())].into(),
+                    fsd_pak_path.clone(),
+                    worker_budget,
+                )?;
+                let found = missing
+                    .iter()
+                    .map(|lint_id| {
+                        let mut found = run_lint(*lint_id, &lint_ctxt, game_file_hashes.as_ref())?;
+                        Ok((*lint_id, found.remove(*mod_spec)))
+                    })
+                    .collect::<Result<Vec<_>, LintError>>()?;
+                done.fetch_add(1, Ordering::Relaxed);
+                Ok(found)
+            })
+            .collect::<Result<Vec<_>, LintError>>()
+    })?;
+
+    for ((mod_spec, path, version, _), found) in to_check.iter().zip(checked) {
+        for (lint_id, result) in found {
+            let Some(result) = result else { continue };
+            if let Some(version) = version {
+                let (key, _) = &lint_keys[&lint_id];
+                cache.insert(path, *version, key.clone(), result.clone());
+            }
+            results
+                .entry(lint_id)
+                .or_default()
+                .insert((*mod_spec).clone(), result);
+        }
+    }
 
+    let mut lint_report = LintReport::default();
     for lint_id in enabled_lints {
+        let results = results.remove(lint_id).unwrap_or_default();
+        let results = mods
+            .iter()
+            .filter_map(|(mod_spec, _)| Some((mod_spec, results.get(mod_spec)?)))
+            .collect::<Vec<_>>();
         match *lint_id {
             LintId::CONFLICTING => {
-                let res = ConflictingModsLint.check_mods(&lint_ctxt)?;
-                lint_report.conflicting_mods = Some(res);
+                lint_report.conflicting_mods =
+                    Some(ConflictingModsLint::conflicts(results.iter().filter_map(
+                        |(mod_spec, result)| match result {
+                            ModLintResult::Files(files) => Some((*mod_spec, files)),
+                            _ => None,
+                        },
+                    )));
             }
             LintId::ASSET_REGISTRY_BIN => {
-                let res = AssetRegisterBinLint.check_mods(&lint_ctxt)?;
-                lint_report.asset_register_bin_mods = Some(res);
+                lint_report.asset_register_bin_mods = Some(found_files(&results));
             }
             LintId::SHADER_FILES => {
-                let res = ShaderFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.shader_file_mods = Some(res);
+                lint_report.shader_file_mods = Some(found_files(&results));
             }
             LintId::OUTDATED_PAK_VERSION => {
-                let res = OutdatedPakVersionLint.check_mods(&lint_ctxt)?;
-                lint_report.outdated_pak_version_mods = Some(res);
+                lint_report.outdated_pak_version_mods = Some(
+                    results
+                        .iter()
+                        .filter_map(|(mod_spec, result)| match result {
+                            ModLintResult::PakVersion(Some(version)) => {
+                                Some(((*mod_spec).clone(), repak::Version::from_repr(*version)?))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                );
             }
             LintId::EMPTY_ARCHIVE => {
-                let res = EmptyArchiveLint.check_mods(&lint_ctxt)?;
-                lint_report.empty_archive_mods = Some(res);
+                lint_report.empty_archive_mods = Some(found_mods(&results));
             }
             LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
-                let res = ArchiveOnlyNonPakFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.archive_with_only_non_pak_files_mods = Some(res);
+                lint_report.archive_with_only_non_pak_files_mods = Some(found_mods(&results));
             }
             LintId::ARCHIVE_WITH_MULTIPLE_PAKS => {
-                let res = ArchiveMultiplePaksLint.check_mods(&lint_ctxt)?;
-                lint_report.archive_with_multiple_paks_mods = Some(res);
+                lint_report.archive_with_multiple_paks_mods = Some(found_mods(&results));
             }
             LintId::NON_ASSET_FILES => {
-                let res = NonAssetFilesLint.check_mods(&lint_ctxt)?;
-                lint_report.non_asset_file_mods = Some(res);
+                lint_report.non_asset_file_mods = Some(found_files(&results));
             }
             LintId::SPLIT_ASSET_PAIRS => {
-                let res = SplitAssetPairsLint.check_mods(&lint_ctxt)?;
-                lint_report.split_asset_pairs_mods = Some(res);
+                lint_report.split_asset_pairs_mods = Some(
+                    results
+                        .iter()
+                        .filter_map(|(mod_spec, result)| match result {
+                            ModLintResult::SplitAssetPairs(pairs) if !pairs.is_empty() => {
+                                Some(((*mod_spec).clone(), pairs.clone()))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                );
             }
             LintId::UNMODIFIED_GAME_ASSETS => {
-                let res = UnmodifiedGameAssetsLint.check_mods(&lint_ctxt)?;
-                lint_report.unmodified_game_assets_mods = Some(res);
+                lint_report.unmodified_game_assets_mods = Some(found_files(&results));
+            }
+            LintId::CONFIG_OVERRIDES => {
+                lint_report.config_override_mods = Some(found_files(&results));
+            }
+            LintId::UNSUPPORTED_COMPRESSION => {
+                lint_report.unsupported_compression_mods = Some(
+                    results
+                        .iter()
+                        .filter_map(|(mod_spec, result)| match result {
+                            ModLintResult::Compression(methods) if !methods.is_empty() => {
+                                Some(((*mod_spec).clone(), methods.clone()))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                );
+            }
+            LintId::MOUNT_POINT => {
+                lint_report.mount_point_mods = Some(
+                    results
+                        .iter()
+                        .filter_map(|(mod_spec, result)| match result {
+                            ModLintResult::MountPoint(Some(mount_point)) => {
+                                Some(((*mod_spec).clone(), mount_point.clone()))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                );
+            }
+            LintId::LOCALIZATION_CONFLICTS => {
+                lint_report.localization_conflicts = Some(LocalizationConflictsLint::conflicts(
+                    results
+                        .iter()
+                        .filter_map(|(mod_spec, result)| match result {
+                            ModLintResult::Localization(files) => Some((*mod_spec, files)),
+                            _ => None,
+                        }),
+                ));
             }
             _ => unimplemented!(),
         }
@@ -326,3 +796,152 @@ pub fn run_lints(
 
     Ok(lint_report)
 }
+
+/// Run lint `id` on the mods of `lcx`, with a result for each of them. `game_file_hashes` are
+/// compared to by [`LintId::UNMODIFIED_GAME_ASSETS`], read from the game's pak if `None`.
+fn run_lint(
+    id: LintId,
+    lcx: &LintCtxt,
+    game_file_hashes: Option<&Arc<GameFileHashes>>,
+) -> Result<HashMap<ModSpecification, ModLintResult>, LintError> {
+    fn files(
+        lcx: &LintCtxt,
+        mut found: BTreeMap<ModSpecification, BTreeSet<String>>,
+    ) -> HashMap<ModSpecification, ModLintResult> {
+        lcx.mods
+            .iter()
+            .map(|(mod_spec, _)| {
+                let files = found.remove(mod_spec).unwrap_or_default();
+                (mod_spec.clone(), ModLintResult::Files(files))
+            })
+            .collect()
+    }
+    fn mods(
+        lcx: &LintCtxt,
+        found: BTreeSet<ModSpecification>,
+    ) -> HashMap<ModSpecification, ModLintResult> {
+        lcx.mods
+            .iter()
+            .map(|(mod_spec, _)| {
+                let result = ModLintResult::Found(found.contains(mod_spec));
+                (mod_spec.clone(), result)
+            })
+            .collect()
+    }
+
+    Ok(match id {
+        LintId::CONFLICTING => {
+            let mut mod_files = ConflictingModsLint::mod_files(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let files = mod_files.swap_remove(mod_spec).unwrap_or_default();
+                    (mod_spec.clone(), ModLintResult::Files(files))
+                })
+                .collect()
+        }
+        LintId::ASSET_REGISTRY_BIN => files(lcx, AssetRegisterBinLint.check_mods(lcx)?),
+        LintId::SHADER_FILES => files(lcx, ShaderFilesLint.check_mods(lcx)?),
+        LintId::OUTDATED_PAK_VERSION => {
+            let mut found = OutdatedPakVersionLint.check_mods(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let version = found.remove(mod_spec).map(|v| v as usize);
+                    (mod_spec.clone(), ModLintResult::PakVersion(version))
+                })
+                .collect()
+        }
+        LintId::EMPTY_ARCHIVE => mods(lcx, EmptyArchiveLint.check_mods(lcx)?),
+        LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES => {
+            mods(lcx, ArchiveOnlyNonPakFilesLint.check_mods(lcx)?)
+        }
+        LintId::ARCHIVE_WITH_MULTIPLE_PAKS => mods(lcx, ArchiveMultiplePaksLint.check_mods(lcx)?),
+        LintId::NON_ASSET_FILES => files(lcx, NonAssetFilesLint.check_mods(lcx)?),
+        LintId::SPLIT_ASSET_PAIRS => {
+            let mut found = SplitAssetPairsLint.check_mods(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let pairs = found.remove(mod_spec).unwrap_or_default();
+                    (mod_spec.clone(), ModLintResult::SplitAssetPairs(pairs))
+                })
+                .collect()
+        }
+        LintId::UNMODIFIED_GAME_ASSETS => {
+            let mut lint = UnmodifiedGameAssetsLint {
+                game_file_hashes: game_file_hashes.cloned(),
+            };
+            files(lcx, lint.check_mods(lcx)?)
+        }
+        LintId::CONFIG_OVERRIDES => files(lcx, ConfigOverridesLint.check_mods(lcx)?),
+        LintId::UNSUPPORTED_COMPRESSION => {
+            let mut found = UnsupportedCompressionLint.check_mods(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let methods = found.remove(mod_spec).unwrap_or_default();
+                    (mod_spec.clone(), ModLintResult::Compression(methods))
+                })
+                .collect()
+        }
+        LintId::MOUNT_POINT => {
+            let mut found = MountPointLint.check_mods(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let mount_point = found.remove(mod_spec);
+                    (mod_spec.clone(), ModLintResult::MountPoint(mount_point))
+                })
+                .collect()
+        }
+        LintId::LOCALIZATION_CONFLICTS => {
+            let mut found = LocalizationConflictsLint.check_mods(lcx)?;
+            lcx.mods
+                .iter()
+                .map(|(mod_spec, _)| {
+                    let files = found.remove(mod_spec).unwrap_or_default();
+                    (mod_spec.clone(), ModLintResult::Localization(files))
+                })
+                .collect()
+        }
+        _ => return UnknownLintSnafu { name: id.name }.fail(),
+    })
+}
+
+/// Mods for which a lint found files, with the files.
+fn found_files(
+    results: &[(&ModSpecification, &ModLintResult)],
+) -> BTreeMap<ModSpecification, BTreeSet<String>> {
+    results
+        .iter()
+        .filter_map(|(mod_spec, result)| match result {
+            ModLintResult::Files(files) if !files.is_empty() => {
+                Some(((*mod_spec).clone(), files.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Mods a lint about mods as a whole found.
+fn found_mods(results: &[(&ModSpecification, &ModLintResult)]) -> BTreeSet<ModSpecification> {
+    results
+        .iter()
+        .filter(|(_, result)| **result == ModLintResult::Found(true))
+        .map(|(mod_spec, _)| (*mod_spec).clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_workers() {
+        assert_eq!(lint_workers(512, 16), (8, 64));
+        assert_eq!(lint_workers(512, 2), (2, 256));
+        assert_eq!(lint_workers(100, 16), (1, 100));
+        assert_eq!(lint_workers(0, 16), (1, 0));
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintError};
+
+/// Where the game's files are, relative to the mount point of paks.
+const GAME_ROOT: &str = "../../../";
+
+/// Directories under [`GAME_ROOT`] the game loads mod files from.
+const GAME_DIRS: [&str; 2] = ["fsd/", "engine/"];
+
+/// Paks whose files are not mounted inside the game's directories, e.g. because the mount point is
+/// missing `../../../` or the files were packed without the `FSD/` directory. The game does not
+/// load them, so the mod does nothing.
+#[derive(Default)]
+pub struct MountPointLint;
+
+impl Lint for MountPointLint {
+    /// Mount point of each mod with misplaced files.
+    type Output = BTreeMap<ModSpecification, String>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        let mut mount_point_mods = BTreeMap::new();
+
+        lcx.for_each_mod(
+            |mod_spec, _, pak_reader| {
+                let mut mount_point = pak_reader.mount_point().replace('\\', "/");
+                if !mount_point.ends_with('/') {
+                    mount_point.push('/');
+                }
+                let misplaced = pak_reader.files().iter().any(|file| {
+                    let path = format!("{mount_point}{file}").to_ascii_lowercase();
+                    !path
+                        .strip_prefix(GAME_ROOT)
+                        .is_some_and(|path| GAME_DIRS.iter().any(|dir| path.starts_with(dir)))
+                });
+                if misplaced {
+                    mount_point_mods.insert(mod_spec, pak_reader.mount_point().to_string());
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(mount_point_mods)
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use serde::{Deserialize, Serialize};
 use tracing::trace;
 
 use crate::providers::ModSpecification;
@@ -9,7 +10,8 @@ use super::{Lint, LintCtxt, LintError};
 #[derive(Default)]
 pub struct SplitAssetPairsLint;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SplitAssetPair {
     MissingUexp,
     MissingUasset,
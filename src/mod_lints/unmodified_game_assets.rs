@@ -1,7 +1,7 @@
-use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::io::BufReader;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use fs_err as fs;
 use path_slash::PathExt;
@@ -13,17 +13,17 @@ use crate::providers::ModSpecification;
 
 use super::{InvalidGamePathSnafu, Lint, LintCtxt, LintError};
 
-#[derive(Default)]
-pub struct UnmodifiedGameAssetsLint;
+/// SHA-256 of each of the game's files by normalized path.
+pub type GameFileHashes = BTreeMap<String, Vec<u8>>;
 
-impl Lint for UnmodifiedGameAssetsLint {
-    type Output = BTreeMap<ModSpecification, BTreeSet<String>>;
-
-    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
-        let Some(game_pak_path) = &lcx.fsd_pak_path else {
-            InvalidGamePathSnafu.fail()?
-        };
+#[derive(Default)]
+pub struct UnmodifiedGameAssetsLint {
+    /// Hashes to compare the mods' files to, read from the game's pak if `None`.
+    pub game_file_hashes: Option<Arc<GameFileHashes>>,
+}
 
+impl UnmodifiedGameAssetsLint {
+    pub fn hash_game_files(game_pak_path: &Path) -> Result<GameFileHashes, LintError> {
         // Adapted from
         // <https://github.com/trumank/repak/blob/a006d9ed6f021687a87b8b2ff9d66083d019824c/repak_cli/src/main.rs#L217>.
         let mut reader = BufReader::new(fs::File::open(game_pak_path)?);
@@ -41,9 +41,7 @@ impl Lint for UnmodifiedGameAssetsLint {
             .map(|(full_path, _path)| full_path.strip_prefix("../../../"))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let game_file_hashes: std::sync::Arc<
-            std::sync::Mutex<BTreeMap<std::borrow::Cow<'_, str>, Vec<u8>>>,
-        > = Default::default();
+        let game_file_hashes: Arc<Mutex<GameFileHashes>> = Default::default();
 
         full_paths.par_iter().zip(stripped).try_for_each_init(
             || (game_file_hashes.clone(), fs::File::open(game_pak_path)),
@@ -58,20 +56,37 @@ impl Lint for UnmodifiedGameAssetsLint {
                 hashes
                     .lock()
                     .unwrap()
-                    .insert(stripped.to_slash_lossy(), hash.to_vec());
+                    .insert(stripped.to_slash_lossy().into_owned(), hash.to_vec());
                 Ok(())
             },
         )?;
 
+        Ok(Arc::into_inner(game_file_hashes)
+            .unwrap()
+            .into_inner()
+            .unwrap())
+    }
+}
+
+impl Lint for UnmodifiedGameAssetsLint {
+    type Output = BTreeMap<ModSpecification, BTreeSet<String>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        let game_file_hashes = match &self.game_file_hashes {
+            Some(hashes) => hashes.clone(),
+            None => {
+                let Some(game_pak_path) = &lcx.fsd_pak_path else {
+                    InvalidGamePathSnafu.fail()?
+                };
+                Arc::new(Self::hash_game_files(game_pak_path)?)
+            }
+        };
+
         let mut unmodified_game_assets = BTreeMap::new();
 
         lcx.for_each_mod_file(
             |mod_spec, mut pak_read_seek, pak_reader, _, normalized_path| {
-                if let Some(reference_hash) = game_file_hashes
-                    .lock()
-                    .unwrap()
-                    .get(&Cow::Owned(normalized_path.clone()))
-                {
+                if let Some(reference_hash) = game_file_hashes.get(&normalized_path) {
                     let mut hasher = sha2::Sha256::new();
                     pak_reader.read_file(&normalized_path, &mut pak_read_seek, &mut hasher)?;
                     let mod_file_hash = hasher.finalize().to_vec();
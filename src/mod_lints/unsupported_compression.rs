@@ -0,0 +1,70 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::providers::ModSpecification;
+
+use super::{Lint, LintCtxt, LintError};
+
+/// Compression methods the game can decompress. Paks using others crash the game when their files
+/// are loaded, with nothing in the log pointing at the mod.
+const SUPPORTED_COMPRESSION: [&str; 4] = ["Zlib", "Gzip", "LZ4", "Oodle"];
+
+/// Length of each compression method name at the end of the pak.
+const COMPRESSION_NAME_LEN: i64 = 32;
+
+#[derive(Default)]
+pub struct UnsupportedCompressionLint;
+
+/// Names of the compression methods a pak of `version` declares, listed at the very end of its
+/// footer. Paks older than v8 only support zlib and have no list.
+fn compression_methods<R: Read + Seek>(
+    version: repak::Version,
+    pak: &mut R,
+) -> std::io::Result<Vec<String>> {
+    let count = match version {
+        v if v < repak::Version::V8A => return Ok(vec![]),
+        repak::Version::V8A => 4,
+        _ => 5,
+    };
+    pak.seek(SeekFrom::End(-count * COMPRESSION_NAME_LEN))?;
+    let mut names = vec![0; (count * COMPRESSION_NAME_LEN) as usize];
+    pak.read_exact(&mut names)?;
+    Ok(names
+        .chunks(COMPRESSION_NAME_LEN as usize)
+        .map(|name| {
+            let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+            String::from_utf8_lossy(&name[..end]).into_owned()
+        })
+        .filter(|name| !name.is_empty())
+        .collect())
+}
+
+impl Lint for UnsupportedCompressionLint {
+    type Output = BTreeMap<ModSpecification, BTreeSet<String>>;
+
+    fn check_mods(&mut self, lcx: &LintCtxt) -> Result<Self::Output, LintError> {
+        let mut unsupported_compression_mods = BTreeMap::new();
+
+        lcx.for_each_mod(
+            |mod_spec, pak_read_seek, pak_reader| {
+                let unsupported = compression_methods(pak_reader.version(), pak_read_seek)?
+                    .into_iter()
+                    .filter(|name| {
+                        !SUPPORTED_COMPRESSION
+                            .iter()
+                            .any(|supported| supported.eq_ignore_ascii_case(name))
+                    })
+                    .collect::<BTreeSet<_>>();
+                if !unsupported.is_empty() {
+                    unsupported_compression_mods.insert(mod_spec, unsupported);
+                }
+                Ok(())
+            },
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+            None::<fn(ModSpecification)>,
+        )?;
+
+        Ok(unsupported_compression_mods)
+    }
+}
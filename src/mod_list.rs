@@ -0,0 +1,141 @@
+//! Mod lists to share in chat, which unlike plain URL lists keep the version, enabled state and
+//! priority of each mod, so pasting one reproduces the same setup rather than the latest versions:
+//!
+//! ```text
+//! # mint mod list
+//! [x] 10 https://mod.io/g/drg/m/some-mod#1234/5678
+//! [ ] 0 https://mod.io/g/drg/m/other-mod#2345/6789
+//! ```
+
+use std::collections::HashMap;
+
+use snafu::prelude::*;
+
+use crate::providers::ModSpecification;
+use crate::state::State;
+
+/// First line of a mod list, telling it apart from a list of URLs.
+pub const HEADER: &str = "# mint mod list";
+
+#[derive(Debug, Snafu)]
+pub enum ModListError {
+    #[snafu(display("line {line} of the mod list is not \"[x] <priority> <URL>\": {text:?}"))]
+    InvalidLine { line: usize, text: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedMod {
+    /// Pinned to the version the mod resolved to, for providers with versions.
+    pub spec: ModSpecification,
+    pub enabled: bool,
+    pub priority: i32,
+}
+
+/// The mods of `profile` in profile order. Enabled mods are listed with the priority they are
+/// installed with after folder overrides and offsets, as the list has no folders.
+pub fn from_profile(state: &State, profile: &str) -> Vec<ListedMod> {
+    let installed = state
+        .mod_data
+        .effective_mods(profile)
+        .into_iter()
+        .map(|m| (m.config.spec.url, m.priority))
+        .collect::<HashMap<_, _>>();
+    let mut mods = vec![];
+    state.mod_data.for_each_mod(profile, |mc| {
+        let resolved = || {
+            let info = state.store.get_mod_info(&mc.spec)?;
+            info.resolved_version().cloned()
+        };
+        let spec = match mc.spec.version_id() {
+            Some(_) => mc.spec.clone(),
+            None => resolved().unwrap_or_else(|| mc.spec.clone()),
+        };
+        let priority = installed.get(&mc.spec.url).copied();
+        mods.push(ListedMod {
+            spec,
+            enabled: priority.is_some(),
+            priority: priority.unwrap_or(mc.priority),
+        });
+    });
+    mods
+}
+
+pub fn to_string(mods: &[ListedMod]) -> String {
+    let mut list = format!("{HEADER}\n");
+    for m in mods {
+        let enabled = if m.enabled { 'x' } else { ' ' };
+        list.push_str(&format!("[{enabled}] {} {}\n", m.priority, m.spec.url));
+    }
+    list
+}
+
+/// Whether `text` is a mod list rather than a list of URLs.
+pub fn is_mod_list(text: &str) -> bool {
+    text.trim_start().starts_with(HEADER)
+}
+
+/// Parse a mod list written by [`to_string`]. Empty lines and lines starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<Vec<ListedMod>, ModListError> {
+    let mut mods = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parse_line = || {
+            let (enabled, rest) = line.split_at_checked(3)?;
+            let enabled = match enabled {
+                "[x]" | "[X]" => true,
+                "[ ]" => false,
+                _ => return None,
+            };
+            let (priority, url) = rest.trim_start().split_once(' ')?;
+            let url = url.trim();
+            if url.is_empty() {
+                return None;
+            }
+            Some(ListedMod {
+                spec: ModSpecification::new(url.to_string()),
+                enabled,
+                priority: priority.parse().ok()?,
+            })
+        };
+        let listed = parse_line().context(InvalidLineSnafu {
+            line: i + 1,
+            text: line,
+        })?;
+        mods.push(listed);
+    }
+    Ok(mods)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mods = vec![
+            ListedMod {
+                spec: ModSpecification::new("https://mod.io/g/drg/m/some-mod#1234/5678".into()),
+                enabled: true,
+                priority: 10,
+            },
+            ListedMod {
+                spec: ModSpecification::new("C:\\My Mods\\local.pak".into()),
+                enabled: false,
+                priority: -3,
+            },
+        ];
+        let list = to_string(&mods);
+        assert!(is_mod_list(&list));
+        assert_eq!(parse(&list).unwrap(), mods);
+
+        assert!(!is_mod_list("https://mod.io/g/drg/m/some-mod"));
+        let invalid = format!("{HEADER}\n\n[x] high https://mod.io/g/drg/m/some-mod");
+        assert!(matches!(
+            parse(&invalid),
+            Err(ModListError::InvalidLine { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,196 @@
+pub mod audio;
+pub mod texture;
+
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use crate::integrate::IntegrationError;
+
+/// Extensions of sound files which can be previewed without installing the mod. The sounds
+/// embedded in `.bnk` soundbanks are listed as `.wem` files inside them.
+pub const AUDIO_EXTENSIONS: &[&str] = &["ogg", "wav", "wem", "bnk"];
+
+/// Extensions of bundled images (usually preview screenshots) which can be displayed directly.
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// A file extracted from a mod archive for previewing.
+#[derive(Debug, Clone)]
+pub struct PreviewFile {
+    /// Path of the file inside the pak or archive.
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+impl PreviewFile {
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+}
+
+/// Previewable contents of a single mod.
+#[derive(Debug, Default, Clone)]
+pub struct ModPreview {
+    pub audio: Vec<PreviewFile>,
+    pub images: Vec<PreviewFile>,
+    /// Texture assets (`T_*.uasset`) contained in the mod, to compare what skin packs change.
+    pub textures: Vec<TexturePreview>,
+}
+
+/// A cooked texture asset of a mod.
+#[derive(Debug, Clone)]
+pub struct TexturePreview {
+    pub path: String,
+    /// Why the texture could not be decoded, if it could not.
+    pub image: Result<texture::Image, String>,
+}
+
+impl ModPreview {
+    pub fn is_empty(&self) -> bool {
+        self.audio.is_empty() && self.images.is_empty() && self.textures.is_empty()
+    }
+
+    fn push(&mut self, path: String, data: Vec<u8>) {
+        if has_extension(&path, &["bnk"]) {
+            for (id, sound) in audio::bnk_sounds(&data) {
+                self.audio.push(PreviewFile {
+                    path: format!("{path}/{id}.wem"),
+                    data: sound.to_vec(),
+                });
+            }
+        } else if has_extension(&path, AUDIO_EXTENSIONS) {
+            self.audio.push(PreviewFile { path, data });
+        } else if has_extension(&path, IMAGE_EXTENSIONS) {
+            self.images.push(PreviewFile { path, data });
+        }
+    }
+
+    fn wants(path: &str) -> bool {
+        has_extension(path, AUDIO_EXTENSIONS) || has_extension(path, IMAGE_EXTENSIONS)
+    }
+}
+
+fn is_texture_asset(path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    has_extension(name, &["uasset"]) && name.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("t_"))
+}
+
+/// Collect previewable files from a mod, which may either be a bare pak or a zip archive
+/// containing paks and loose files.
+pub fn extract_preview<P: AsRef<Path>>(path: P) -> Result<ModPreview, IntegrationError> {
+    let mut preview = ModPreview::default();
+    let mut data = crate::archive::open(path.as_ref(), crate::archive::DEFAULT_MEMORY_BUDGET)?;
+
+    if let Ok(mut archive) = zip::ZipArchive::new(&mut data) {
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .map_err(|_| IntegrationError::GenericError {
+                    msg: "failed to extract file in zip archive".to_string(),
+                })?;
+            let Some(p) = file.enclosed_name() else {
+                continue;
+            };
+            if !file.is_file() {
+                continue;
+            }
+            let name = p.to_string_lossy().replace('\\', "/");
+            if has_extension(&name, &["pak"]) {
+                let mut buf = vec![];
+                file.read_to_end(&mut buf)?;
+                extract_from_pak(&mut Cursor::new(buf), &mut preview)?;
+            } else if ModPreview::wants(&name) {
+                let mut buf = vec![];
+                file.read_to_end(&mut buf)?;
+                preview.push(name, buf);
+            }
+        }
+    } else {
+        data.rewind()?;
+        extract_from_pak(&mut data, &mut preview)?;
+    }
+
+    Ok(preview)
+}
+
+fn extract_from_pak<R: Read + Seek>(
+    reader: &mut R,
+    preview: &mut ModPreview,
+) -> Result<(), IntegrationError> {
+    let pak = repak::PakBuilder::new().reader(reader)?;
+    for path in pak.files() {
+        if ModPreview::wants(&path) {
+            let data = pak.get(&path, reader)?;
+            preview.push(path, data);
+        } else if is_texture_asset(&path) {
+            // the texture data is in the .uexp next to the asset, large mips in the .ubulk
+            let stem = &path[..path.len() - "uasset".len()];
+            let image = match pak.get(&format!("{stem}uexp"), reader) {
+                Ok(uexp) => {
+                    let ubulk = pak.get(&format!("{stem}ubulk"), reader).ok();
+                    texture::decode(&uexp, ubulk.as_deref()).map_err(|e| e.to_string())
+                }
+                Err(_) => Err("the .uexp of the texture is missing".to_string()),
+            };
+            preview.textures.push(TexturePreview { path, image });
+        }
+    }
+    Ok(())
+}
+
+fn has_extension(path: &str, extensions: &[&str]) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|x| e.eq_ignore_ascii_case(x)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        let mut bnk = vec![];
+        for (id, body) in [
+            (
+                b"DIDX",
+                [1u32, 0, 2, 2, 2, 1].map(u32::to_le_bytes).concat(),
+            ),
+            (b"DATA", b"abc".to_vec()),
+        ] {
+            bnk.extend(id);
+            bnk.extend((body.len() as u32).to_le_bytes());
+            bnk.extend(body);
+        }
+
+        let mut preview = ModPreview::default();
+        for path in [
+            "Content/Music.bnk",
+            "Content/Shot.WEM",
+            "Preview.png",
+            "Content/M.uasset",
+        ] {
+            if ModPreview::wants(path) {
+                preview.push(path.to_string(), bnk.clone());
+            }
+        }
+        let audio = preview
+            .audio
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            audio,
+            [
+                "Content/Music.bnk/1.wem",
+                "Content/Music.bnk/2.wem",
+                "Content/Shot.WEM"
+            ]
+        );
+        assert_eq!(preview.audio[1].data, b"c");
+        assert_eq!(preview.audio[1].file_name(), "2.wem");
+        assert_eq!(preview.images.len(), 1);
+        assert!(is_texture_asset("Content/T_Skin_D.uasset"));
+        assert!(!is_texture_asset("Content/M.uasset"));
+    }
+}
@@ -0,0 +1,297 @@
+//! Decoding of the sound files mods ship, in particular Wwise's `.wem` files and the `.bnk`
+//! soundbanks embedding them, so they can be played inside mint.
+
+use snafu::prelude::*;
+
+#[derive(Debug, Snafu)]
+pub enum AudioError {
+    #[snafu(display("the sound file is truncated or corrupt"))]
+    Truncated,
+    #[snafu(display("sounds in Wwise's Vorbis encoding cannot be played yet"))]
+    WwiseVorbis,
+    #[snafu(display("unsupported sound encoding {codec:#06x}"))]
+    UnsupportedCodec { codec: u16 },
+    #[snafu(display("unsupported sample size of {bits} bits"))]
+    UnsupportedBits { bits: u16 },
+    #[snafu(display("not a sound file mint can play"))]
+    UnknownFormat,
+}
+
+/// Encoding of a sound file, read from its header rather than its extension as `.wem` files come
+/// in several encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFormat {
+    Ogg,
+    /// RIFF with uncompressed samples, as `.wav` and some `.wem` files are.
+    Pcm,
+    /// Wwise's variant of IMA ADPCM.
+    WwiseAdpcm,
+    /// Wwise's Vorbis, which leaves out the codebooks needed to decode it.
+    WwiseVorbis,
+    /// RIFF with any other encoding.
+    Unsupported {
+        codec: u16,
+    },
+    Unknown,
+}
+
+/// Decoded sound, with the samples of the channels interleaved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pcm {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub samples: Vec<i16>,
+}
+
+pub fn detect(data: &[u8]) -> AudioFormat {
+    if data.starts_with(b"OggS") {
+        return AudioFormat::Ogg;
+    }
+    let Some(fmt) = riff_chunk(data, b"fmt ") else {
+        return AudioFormat::Unknown;
+    };
+    match read_u16(fmt, 0) {
+        Some(0x0001 | 0xFFFE) => AudioFormat::Pcm,
+        // Microsoft's ADPCM shares the code but has a much longer format chunk with its
+        // coefficients
+        Some(0x0002) if fmt.len() < 50 => AudioFormat::WwiseAdpcm,
+        Some(0xFFFF) => AudioFormat::WwiseVorbis,
+        Some(codec) => AudioFormat::Unsupported { codec },
+        None => AudioFormat::Unknown,
+    }
+}
+
+/// Decode a `.wav` or `.wem` file. Ogg files are left to the player.
+pub fn decode(data: &[u8]) -> Result<Pcm, AudioError> {
+    let format = detect(data);
+    let fmt = riff_chunk(data, b"fmt ").context(UnknownFormatSnafu)?;
+    let body = riff_chunk(data, b"data").context(TruncatedSnafu)?;
+    let channels = read_u16(fmt, 2)
+        .filter(|c| *c > 0)
+        .context(TruncatedSnafu)?;
+    let sample_rate = read_u32(fmt, 4)
+        .filter(|r| *r > 0)
+        .context(TruncatedSnafu)?;
+    let samples = match format {
+        AudioFormat::Pcm => match read_u16(fmt, 14).context(TruncatedSnafu)? {
+            8 => body.iter().map(|s| (*s as i16 - 128) << 8).collect(),
+            16 => body
+                .chunks_exact(2)
+                .map(|s| i16::from_le_bytes([s[0], s[1]]))
+                .collect(),
+            bits => return UnsupportedBitsSnafu { bits }.fail(),
+        },
+        AudioFormat::WwiseAdpcm => {
+            let block_align = read_u16(fmt, 12).context(TruncatedSnafu)?;
+            decode_wwise_adpcm(body, channels.into(), block_align.into())?
+        }
+        AudioFormat::WwiseVorbis => return WwiseVorbisSnafu.fail(),
+        AudioFormat::Unsupported { codec } => return UnsupportedCodecSnafu { codec }.fail(),
+        AudioFormat::Ogg | AudioFormat::Unknown => return UnknownFormatSnafu.fail(),
+    };
+    Ok(Pcm {
+        channels,
+        sample_rate,
+        samples,
+    })
+}
+
+/// `.wem` files embedded in a `.bnk` soundbank, with their Wwise IDs.
+pub fn bnk_sounds(data: &[u8]) -> Vec<(u32, &[u8])> {
+    let chunks = read_chunks(data);
+    let find = |id: &[u8; 4]| chunks.iter().find(|(i, _)| i == id).map(|(_, body)| *body);
+    let (Some(index), Some(body)) = (find(b"DIDX"), find(b"DATA")) else {
+        return vec![];
+    };
+    index
+        .chunks_exact(12)
+        .filter_map(|entry| {
+            let id = read_u32(entry, 0)?;
+            let offset = read_u32(entry, 4)? as usize;
+            let size = read_u32(entry, 8)? as usize;
+            Some((id, body.get(offset..offset.checked_add(size)?)?))
+        })
+        .collect()
+}
+
+const IMA_STEPS: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+const IMA_INDEX_CHANGES: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+
+/// Unlike standard IMA ADPCM, Wwise stores each channel's part of a block in one piece: a
+/// 4-byte header with the first sample and step index, then two samples per byte, low nibble
+/// first.
+fn decode_wwise_adpcm(
+    data: &[u8],
+    channels: usize,
+    block_align: usize,
+) -> Result<Vec<i16>, AudioError> {
+    ensure!(
+        block_align > 4 * channels && block_align % channels == 0,
+        TruncatedSnafu
+    );
+    let channel_block = block_align / channels;
+    let frames_per_block = (channel_block - 4) * 2 + 1;
+    let mut samples = Vec::with_capacity(data.len() / block_align * frames_per_block * channels);
+    for block in data.chunks_exact(block_align) {
+        let start = samples.len();
+        samples.resize(start + frames_per_block * channels, 0);
+        for (channel, data) in block.chunks_exact(channel_block).enumerate() {
+            let mut predictor = i16::from_le_bytes([data[0], data[1]]) as i32;
+            let mut index = (data[2] as i32).clamp(0, 88);
+            samples[start + channel] = predictor as i16;
+            let nibbles = data[4..].iter().flat_map(|b| [b & 0xF, b >> 4]);
+            for (frame, nibble) in nibbles.enumerate() {
+                let step = IMA_STEPS[index as usize];
+                let mut diff = step >> 3;
+                if nibble & 4 != 0 {
+                    diff += step;
+                }
+                if nibble & 2 != 0 {
+                    diff += step >> 1;
+                }
+                if nibble & 1 != 0 {
+                    diff += step >> 2;
+                }
+                predictor += if nibble & 8 != 0 { -diff } else { diff };
+                predictor = predictor.clamp(i16::MIN.into(), i16::MAX.into());
+                index = (index + IMA_INDEX_CHANGES[nibble as usize]).clamp(0, 88);
+                samples[start + (frame + 1) * channels + channel] = predictor as i16;
+            }
+        }
+    }
+    Ok(samples)
+}
+
+/// Body of the chunk `id` of a RIFF/WAVE file.
+fn riff_chunk<'a>(data: &'a [u8], id: &[u8; 4]) -> Option<&'a [u8]> {
+    if data.get(..4)? != b"RIFF" || data.get(8..12)? != b"WAVE" {
+        return None;
+    }
+    read_chunks(&data[12..])
+        .into_iter()
+        .find(|(i, _)| i == id)
+        .map(|(_, body)| body)
+}
+
+/// Chunks of 4-byte ID, little endian size and body, as RIFF and Wwise files consist of. The body
+/// of a truncated last chunk is cut short rather than dropped.
+fn read_chunks(mut data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut chunks = vec![];
+    while data.len() >= 8 {
+        let id = [data[0], data[1], data[2], data[3]];
+        let size = read_u32(data, 4).unwrap() as usize;
+        let end = data.len().min(8 + size);
+        chunks.push((id, &data[8..end]));
+        data = data.get(8 + size + size % 2..).unwrap_or_default();
+    }
+    chunks
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn riff(codec: u16, channels: u16, block_align: u16, bits: u16, body: &[u8]) -> Vec<u8> {
+        let mut fmt = vec![];
+        fmt.extend(codec.to_le_bytes());
+        fmt.extend(channels.to_le_bytes());
+        fmt.extend(48000u32.to_le_bytes());
+        fmt.extend(0u32.to_le_bytes());
+        fmt.extend(block_align.to_le_bytes());
+        fmt.extend(bits.to_le_bytes());
+        let mut chunks = vec![];
+        for (id, chunk) in [(b"fmt ", &fmt[..]), (b"data", body)] {
+            chunks.extend(id);
+            chunks.extend((chunk.len() as u32).to_le_bytes());
+            chunks.extend(chunk);
+        }
+        let mut file = b"RIFF".to_vec();
+        file.extend((chunks.len() as u32 + 4).to_le_bytes());
+        file.extend(b"WAVE");
+        file.extend(chunks);
+        file
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect(b"OggS\0\x02"), AudioFormat::Ogg);
+        assert_eq!(detect(&riff(0x0001, 1, 2, 16, &[])), AudioFormat::Pcm);
+        assert_eq!(detect(&riff(0xFFFE, 2, 4, 16, &[])), AudioFormat::Pcm);
+        assert_eq!(
+            detect(&riff(0x0002, 1, 36, 4, &[])),
+            AudioFormat::WwiseAdpcm
+        );
+        assert_eq!(
+            detect(&riff(0xFFFF, 2, 0, 0, &[])),
+            AudioFormat::WwiseVorbis
+        );
+        assert_eq!(
+            detect(&riff(0x0055, 2, 0, 0, &[])),
+            AudioFormat::Unsupported { codec: 0x0055 }
+        );
+        assert_eq!(detect(b"RIFF\0\0\0\0AVI "), AudioFormat::Unknown);
+        assert_eq!(detect(b""), AudioFormat::Unknown);
+    }
+
+    #[test]
+    fn test_decode() {
+        let pcm = decode(&riff(0x0001, 2, 4, 16, &[1, 0, 0xFF, 0xFF, 2, 0, 0, 0x80])).unwrap();
+        assert_eq!(pcm.channels, 2);
+        assert_eq!(pcm.sample_rate, 48000);
+        assert_eq!(pcm.samples, [1, -1, 2, i16::MIN]);
+
+        // first sample 100 from the header, then +7 at step index 0 and -10 at step index 2
+        let adpcm = decode(&riff(0x0002, 1, 6, 4, &[100, 0, 0, 0, 0xC4, 0x00])).unwrap();
+        assert_eq!(adpcm.samples, [100, 107, 97, 98, 99]);
+
+        assert!(matches!(
+            decode(&riff(0xFFFF, 2, 0, 0, &[])),
+            Err(AudioError::WwiseVorbis)
+        ));
+        assert!(matches!(
+            decode(&riff(0x0001, 1, 3, 24, &[])),
+            Err(AudioError::UnsupportedBits { bits: 24 })
+        ));
+    }
+
+    #[test]
+    fn test_bnk_sounds() {
+        let mut bnk = vec![];
+        for (id, body) in [
+            (b"BKHD", vec![0; 8]),
+            (
+                b"DIDX",
+                [7u32, 0, 3, 9, 3, 2, 11, 4, 9]
+                    .map(u32::to_le_bytes)
+                    .concat(),
+            ),
+            (b"DATA", b"abcde".to_vec()),
+        ] {
+            bnk.extend(id);
+            bnk.extend((body.len() as u32).to_le_bytes());
+            bnk.extend(body);
+        }
+        // the last entry points past the end of the data
+        assert_eq!(bnk_sounds(&bnk), [(7, &b"abc"[..]), (9, &b"de"[..])]);
+        assert!(bnk_sounds(b"BKHD\0\0\0\0").is_empty());
+    }
+}
@@ -0,0 +1,357 @@
+//! Decoding of cooked `Texture2D` assets as UE 4.27 writes them, so skin packs can be compared
+//! without installing them. Only the platform data at the end of the `.uexp` is read: the size
+//! and pixel format of the texture and its mips, which are either inline or in the `.ubulk`.
+
+use snafu::prelude::*;
+
+/// Largest width or height of the mip decoded for a preview.
+pub const MAX_PREVIEW_SIZE: u32 = 512;
+
+#[derive(Debug, Snafu)]
+pub enum TextureError {
+    #[snafu(display("no texture data found in the asset"))]
+    NoPlatformData,
+    #[snafu(display("textures in {format} cannot be shown yet"))]
+    UnsupportedFormat { format: String },
+    #[snafu(display("no mip of at most {MAX_PREVIEW_SIZE} pixels is included in the mod"))]
+    NoMip,
+    #[snafu(display("the texture data is truncated or corrupt"))]
+    Truncated,
+}
+
+/// Decoded mip of a texture, in RGBA with 8 bits per channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Flags of a mip's bulk data.
+const BULKDATA_PAYLOAD_AT_END_OF_FILE: u32 = 1 << 0;
+const BULKDATA_UNUSED: u32 = 1 << 5;
+const BULKDATA_PAYLOAD_IN_SEPARATE_FILE: u32 = 1 << 8;
+const BULKDATA_SIZE_64BIT: u32 = 1 << 13;
+/// Bit of the packed data saying the optional platform data follows the pixel format.
+const HAS_OPT_DATA: u32 = 1 << 30;
+
+/// Decode the largest mip of at most [`MAX_PREVIEW_SIZE`] pixels from the `.uexp` of a texture
+/// and its `.ubulk`, if it has one.
+pub fn decode(uexp: &[u8], ubulk: Option<&[u8]>) -> Result<Image, TextureError> {
+    let (pos, format) = find_pixel_format(uexp).context(NoPlatformDataSnafu)?;
+    let block = BlockFormat::from_name(&format).context(UnsupportedFormatSnafu { format })?;
+    let packed = read_u32(uexp, pos - 4).context(TruncatedSnafu)?;
+
+    let mut r = Reader {
+        data: uexp,
+        pos: pos + 4 + read_u32(uexp, pos).context(TruncatedSnafu)? as usize,
+    };
+    if packed & HAS_OPT_DATA != 0 {
+        r.skip(8)?;
+    }
+    let _first_mip = r.u32()?;
+    let mips = r.u32()?;
+    for _ in 0..mips {
+        let _cooked = r.u32()?;
+        let flags = r.u32()?;
+        let (_count, size) = if flags & BULKDATA_SIZE_64BIT != 0 {
+            (r.u64()?, r.u64()?)
+        } else {
+            (r.u32()?.into(), r.u32()?.into())
+        };
+        let offset = r.u64()?;
+        let size = usize::try_from(size).ok().context(TruncatedSnafu)?;
+        let data = if flags & BULKDATA_UNUSED != 0 {
+            None
+        } else if flags & BULKDATA_PAYLOAD_AT_END_OF_FILE == 0 {
+            Some(r.bytes(size)?)
+        } else if flags & BULKDATA_PAYLOAD_IN_SEPARATE_FILE != 0 {
+            let offset = usize::try_from(offset).ok();
+            ubulk
+                .zip(offset)
+                .and_then(|(ubulk, offset)| ubulk.get(offset..offset.checked_add(size)?))
+        } else {
+            None
+        };
+        let width = r.u32()?;
+        let height = r.u32()?;
+        let _depth = r.u32()?;
+        if let Some(data) = data
+            && width.max(height) <= MAX_PREVIEW_SIZE
+        {
+            return block.decode(data, width, height);
+        }
+    }
+    NoMipSnafu.fail()
+}
+
+/// Position and name of the pixel format string, e.g. `PF_DXT5`, which the platform data starts
+/// with after the size of the texture.
+fn find_pixel_format(data: &[u8]) -> Option<(usize, String)> {
+    (12..data.len().saturating_sub(8)).find_map(|pos| {
+        let len = read_u32(data, pos)? as usize;
+        if !(4..=32).contains(&len) || data.get(pos + 4..pos + 7)? != b"PF_" {
+            return None;
+        }
+        let name = data.get(pos + 4..pos + 4 + len)?.strip_suffix(&[0u8])?;
+        let name = std::str::from_utf8(name).ok()?;
+        name.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+            .then(|| (pos, name.to_string()))
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockFormat {
+    Bgra8,
+    G8,
+    Bc1,
+    Bc3,
+    Bc4,
+    Bc5,
+}
+
+impl BlockFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "PF_B8G8R8A8" => Self::Bgra8,
+            "PF_G8" => Self::G8,
+            "PF_DXT1" => Self::Bc1,
+            "PF_DXT5" => Self::Bc3,
+            "PF_BC4" => Self::Bc4,
+            "PF_BC5" => Self::Bc5,
+            _ => return None,
+        })
+    }
+
+    fn decode(self, data: &[u8], width: u32, height: u32) -> Result<Image, TextureError> {
+        let (w, h) = (width as usize, height as usize);
+        let mut rgba = vec![0; w * h * 4];
+        match self {
+            Self::Bgra8 => {
+                let pixels = data.get(..w * h * 4).context(TruncatedSnafu)?;
+                for (out, bgra) in rgba.chunks_exact_mut(4).zip(pixels.chunks_exact(4)) {
+                    out.copy_from_slice(&[bgra[2], bgra[1], bgra[0], bgra[3]]);
+                }
+            }
+            Self::G8 => {
+                let pixels = data.get(..w * h).context(TruncatedSnafu)?;
+                for (out, g) in rgba.chunks_exact_mut(4).zip(pixels) {
+                    out.copy_from_slice(&[*g, *g, *g, 255]);
+                }
+            }
+            _ => {
+                let block_size = if matches!(self, Self::Bc1 | Self::Bc4) {
+                    8
+                } else {
+                    16
+                };
+                let (blocks_x, blocks_y) = (w.div_ceil(4), h.div_ceil(4));
+                let blocks = data
+                    .get(..blocks_x * blocks_y * block_size)
+                    .context(TruncatedSnafu)?;
+                for (i, block) in blocks.chunks_exact(block_size).enumerate() {
+                    let texels = self.decode_block(block);
+                    let (bx, by) = (i % blocks_x * 4, i / blocks_x * 4);
+                    for (j, texel) in texels.iter().enumerate() {
+                        let (x, y) = (bx + j % 4, by + j / 4);
+                        if x < w && y < h {
+                            let at = (y * w + x) * 4;
+                            rgba[at..at + 4].copy_from_slice(texel);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Image {
+            width,
+            height,
+            rgba,
+        })
+    }
+
+    fn decode_block(self, block: &[u8]) -> [[u8; 4]; 16] {
+        match self {
+            Self::Bc1 => color_block(block, true),
+            Self::Bc3 => {
+                let mut texels = color_block(&block[8..], false);
+                for (texel, alpha) in texels.iter_mut().zip(alpha_block(&block[..8])) {
+                    texel[3] = alpha;
+                }
+                texels
+            }
+            Self::Bc4 => alpha_block(block).map(|r| [r, r, r, 255]),
+            Self::Bc5 => {
+                let (red, green) = (alpha_block(&block[..8]), alpha_block(&block[8..]));
+                std::array::from_fn(|i| {
+                    // normal maps store X and Y only, Z follows from the normal's length of 1
+                    let x = red[i] as f32 / 127.5 - 1.0;
+                    let y = green[i] as f32 / 127.5 - 1.0;
+                    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+                    [red[i], green[i], ((z + 1.0) * 127.5) as u8, 255]
+                })
+            }
+            Self::Bgra8 | Self::G8 => unreachable!("not block compressed"),
+        }
+    }
+}
+
+/// BC1 colors, with the 3-color mode and its transparent texels only in BC1 itself.
+fn color_block(block: &[u8], bc1: bool) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let rgb = |c: u16| {
+        let (r, g, b) = ((c >> 11) & 0x1F, (c >> 5) & 0x3F, c & 0x1F);
+        [
+            (r << 3 | r >> 2) as u32,
+            (g << 2 | g >> 4) as u32,
+            (b << 3 | b >> 2) as u32,
+        ]
+    };
+    let (a, b) = (rgb(c0), rgb(c1));
+    let mix = |wa: u32, wb: u32| {
+        let [r, g, bl]: [u8; 3] =
+            std::array::from_fn(|i| ((a[i] * wa + b[i] * wb) / (wa + wb)) as u8);
+        [r, g, bl, 255]
+    };
+    let palette = if c0 > c1 || !bc1 {
+        [mix(1, 0), mix(0, 1), mix(2, 1), mix(1, 2)]
+    } else {
+        [mix(1, 0), mix(0, 1), mix(1, 1), [0, 0, 0, 0]]
+    };
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    std::array::from_fn(|i| palette[((indices >> (2 * i)) & 3) as usize])
+}
+
+/// BC4 values, also used for the alpha of BC3 and both channels of BC5.
+fn alpha_block(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u32, block[1] as u32);
+    let palette: [u8; 8] = std::array::from_fn(|i| match i as u32 {
+        0 => a0 as u8,
+        1 => a1 as u8,
+        i if a0 > a1 => (((8 - i) * a0 + (i - 1) * a1) / 7) as u8,
+        6 => 0,
+        7 => 255,
+        i => (((6 - i) * a0 + (i - 1) * a1) / 5) as u8,
+    });
+    let mut bits = [0; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    std::array::from_fn(|i| palette[((indices >> (3 * i)) & 7) as usize])
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], TextureError> {
+        let end = self.pos.checked_add(len).context(TruncatedSnafu)?;
+        let bytes = self.data.get(self.pos..end).context(TruncatedSnafu)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), TextureError> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u32(&mut self) -> Result<u32, TextureError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, TextureError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Platform data with a 1024x1024 mip in the `.ubulk` followed by inline mips.
+    fn uexp(format: &str, inline_mips: &[(u32, &[u8])]) -> Vec<u8> {
+        let mut data = vec![0xAA; 16];
+        for v in [1024u32, 1024, 1] {
+            data.extend(v.to_le_bytes());
+        }
+        data.extend((format.len() as u32 + 1).to_le_bytes());
+        data.extend(format.as_bytes());
+        data.push(0);
+        data.extend(0u32.to_le_bytes());
+        data.extend((inline_mips.len() as u32 + 1).to_le_bytes());
+
+        data.extend(1u32.to_le_bytes());
+        data.extend(
+            (BULKDATA_PAYLOAD_AT_END_OF_FILE | BULKDATA_PAYLOAD_IN_SEPARATE_FILE).to_le_bytes(),
+        );
+        data.extend([16u32, 16].map(u32::to_le_bytes).concat());
+        data.extend(0u64.to_le_bytes());
+        data.extend([1024u32, 1024, 1].map(u32::to_le_bytes).concat());
+        for (size, mip) in inline_mips {
+            data.extend(1u32.to_le_bytes());
+            data.extend(0x48u32.to_le_bytes());
+            data.extend([mip.len() as u32; 2].map(u32::to_le_bytes).concat());
+            data.extend(0u64.to_le_bytes());
+            data.extend(*mip);
+            data.extend([*size, *size, 1].map(u32::to_le_bytes).concat());
+        }
+        data
+    }
+
+    #[test]
+    fn test_decode_bgra() {
+        let mip = [
+            [1, 2, 3, 4],
+            [5, 6, 7, 8],
+            [9, 10, 11, 12],
+            [13, 14, 15, 16],
+        ]
+        .concat();
+        let image = decode(&uexp("PF_B8G8R8A8", &[(2, &mip)]), None).unwrap();
+        assert_eq!((image.width, image.height), (2, 2));
+        assert_eq!(&image.rgba[..8], [3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+
+    #[test]
+    fn test_decode_bc1() {
+        // red and blue end points, the first row alternating between them and the rest blue
+        let block = [0x00, 0xF8, 0x1F, 0x00, 0b01_00_01_00, 0x55, 0x55, 0x55];
+        let image = decode(&uexp("PF_DXT1", &[(4, &block)]), None).unwrap();
+        let texels = image.rgba.chunks(4).collect::<Vec<_>>();
+        assert_eq!(texels[0], [255, 0, 0, 255]);
+        assert_eq!(texels[1], [0, 0, 255, 255]);
+        assert_eq!(texels[2], [255, 0, 0, 255]);
+        assert_eq!(texels[4], [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_decode_from_ubulk_and_errors() {
+        let ubulk = [0x80; 16];
+        let mut data = uexp("PF_G8", &[]);
+        // shrink the 1024x1024 mip in the .ubulk to 4x4
+        let len = data.len();
+        data[len - 12..len - 4].copy_from_slice(&[4, 0, 0, 0, 4, 0, 0, 0]);
+        assert_eq!(
+            decode(&data, Some(&ubulk)).unwrap().rgba[..4],
+            [0x80, 0x80, 0x80, 255]
+        );
+        assert!(matches!(decode(&data, None), Err(TextureError::NoMip)));
+
+        assert!(matches!(
+            decode(&uexp("PF_BC7", &[]), None),
+            Err(TextureError::UnsupportedFormat { format }) if format == "PF_BC7"
+        ));
+        assert!(matches!(
+            decode(b"no texture here", None),
+            Err(TextureError::NoPlatformData)
+        ));
+    }
+}
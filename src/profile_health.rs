@@ -0,0 +1,150 @@
+//! Health score of a profile, shown next to the profile selector so it is clear at a glance whether
+//! the pack needs maintenance before playing. Mods which can no longer be resolved, problems found
+//! by the last lint of the profile, asset conflicts without a chosen winner and mods not checked for
+//! updates in a while each lower it.
+
+use std::time::Duration;
+
+/// Score of a profile without any problems.
+pub const MAX_SCORE: u32 = 100;
+
+/// How long after the last cache update the mods count as not checked for updates recently.
+pub const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Like [`STALE_AFTER`], for losing the full points of [`STALE_PENALTY`].
+pub const VERY_STALE_AFTER: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Points lost per problem of each kind, and at most for all problems of the kind.
+const DEAD_MOD_PENALTY: (u32, u32) = (15, 45);
+const LINT_PENALTY: (u32, u32) = (5, 25);
+const CONFLICT_PENALTY: (u32, u32) = (2, 20);
+/// Points lost if the mods were not checked for updates recently, and if not in a long time.
+const STALE_PENALTY: (u32, u32) = (10, 20);
+
+/// What the score of a profile is computed from.
+#[derive(Debug, Clone, Default)]
+pub struct HealthInputs {
+    /// Enabled mods whose info cannot be found, e.g. because they were deleted from mod.io.
+    pub dead_mods: usize,
+    /// Problems found by the last lint of the profile other than conflicts, `None` if it was not
+    /// linted.
+    pub lint_problems: Option<usize>,
+    /// Conflicting assets without a chosen winner, `None` if conflicts were not linted.
+    pub conflicts: Option<usize>,
+    /// Time since the mods were last checked for updates, `None` if never.
+    pub since_update: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    Good,
+    Fair,
+    Poor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileHealth {
+    /// From 0 to [`MAX_SCORE`].
+    pub score: u32,
+    /// What lowered the score, with the points lost for each. Hints which cost no points, e.g.
+    /// that the profile was not linted, are listed with 0.
+    pub issues: Vec<(String, u32)>,
+}
+
+impl ProfileHealth {
+    pub fn new(inputs: &HealthInputs) -> Self {
+        let mut issues = vec![];
+        let mut penalize = |count: usize, (per, max): (u32, u32), what: String| {
+            if count > 0 {
+                issues.push((what, (count as u32).saturating_mul(per).min(max)));
+            }
+        };
+        penalize(
+            inputs.dead_mods,
+            DEAD_MOD_PENALTY,
+            format!("{} mods can no longer be found", inputs.dead_mods),
+        );
+        match inputs.lint_problems {
+            Some(count) => penalize(count, LINT_PENALTY, format!("{count} lint problems")),
+            None => issues.push(("not linted since mint started".to_string(), 0)),
+        }
+        if let Some(count) = inputs.conflicts {
+            penalize(
+                count,
+                CONFLICT_PENALTY,
+                format!("{count} conflicting assets without a chosen winner"),
+            );
+        }
+        match inputs.since_update {
+            None => issues.push((
+                "mods never checked for updates".to_string(),
+                STALE_PENALTY.1,
+            )),
+            Some(age) if age >= STALE_AFTER => {
+                let penalty = if age >= VERY_STALE_AFTER {
+                    STALE_PENALTY.1
+                } else {
+                    STALE_PENALTY.0
+                };
+                let days = age.as_secs() / (24 * 60 * 60);
+                issues.push((
+                    format!("mods last checked for updates {days} days ago"),
+                    penalty,
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let lost = issues.iter().map(|(_, points)| points).sum::<u32>();
+        Self {
+            score: MAX_SCORE.saturating_sub(lost),
+            issues,
+        }
+    }
+
+    pub fn grade(&self) -> Grade {
+        match self.score {
+            80.. => Grade::Good,
+            50.. => Grade::Fair,
+            _ => Grade::Poor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_health() {
+        let healthy = ProfileHealth::new(&HealthInputs {
+            dead_mods: 0,
+            lint_problems: Some(0),
+            conflicts: Some(0),
+            since_update: Some(Duration::from_secs(60)),
+        });
+        assert_eq!(healthy.score, MAX_SCORE);
+        assert!(healthy.issues.is_empty());
+        assert_eq!(healthy.grade(), Grade::Good);
+
+        let neglected = ProfileHealth::new(&HealthInputs {
+            dead_mods: 1,
+            lint_problems: None,
+            conflicts: Some(20),
+            since_update: Some(STALE_AFTER),
+        });
+        // 15 for the dead mod, at most 20 for the conflicts, 10 for the update check
+        assert_eq!(neglected.score, 55);
+        assert_eq!(neglected.grade(), Grade::Fair);
+        assert_eq!(neglected.issues.len(), 4);
+
+        let abandoned = ProfileHealth::new(&HealthInputs {
+            dead_mods: 10,
+            lint_problems: Some(10),
+            conflicts: None,
+            since_update: None,
+        });
+        assert_eq!(abandoned.score, 10);
+        assert_eq!(abandoned.grade(), Grade::Poor);
+    }
+}
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 
 use fs_err as fs;
+use mint_lib::paths::long_path_buf;
 use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 
@@ -213,15 +214,22 @@ impl BlobCache {
         hasher.update(blob);
         let hash = hex::encode(hasher.finalize());
 
-        let tmp = self.path.join(format!(".{hash}"));
+        let tmp = long_path_buf(self.path.join(format!(".{hash}")));
         fs::write(&tmp, blob).context(BlobCacheSnafu { kind: "write" })?;
-        fs::rename(tmp, self.path.join(&hash)).context(BlobCacheSnafu { kind: "rename" })?;
+        fs::rename(tmp, long_path_buf(self.path.join(&hash)))
+            .context(BlobCacheSnafu { kind: "rename" })?;
 
         Ok(BlobRef(hash))
     }
 
+    /// Whether `path` is a blob in the cache, i.e. was downloaded rather than stored locally.
+    pub(super) fn contains(&self, path: &Path) -> bool {
+        path.parent()
+            .is_some_and(|dir| dir == long_path_buf(&self.path) || dir == self.path)
+    }
+
     pub(super) fn get_path(&self, blob: &BlobRef) -> Option<PathBuf> {
-        let path = self.path.join(&blob.0);
+        let path = long_path_buf(self.path.join(&blob.0));
         path.exists().then_some(path)
     }
 }
@@ -0,0 +1,195 @@
+//! The HTTP client shared by the providers. Sharing it pools their connections, which are reused
+//! across requests and multiplexed over HTTP/2 where the server supports it, rather than each
+//! provider connecting on its own. Its timeouts are set in the settings.
+//!
+//! Downloads read through [`read_resumable`] pick up where they stopped when the connection drops.
+//! Like browsers, the client goes through the system proxy, set in the Windows settings or the
+//! `HTTPS_PROXY` environment variable.
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use reqwest::header::{
+    CONTENT_RANGE, ETAG, HeaderMap, HeaderValue, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+use tracing::warn;
+
+use super::{FetchProgress, ModResolution, priority};
+
+/// How often a download resumes after the connection drops before giving up.
+pub const MAX_RESUMES: usize = 5;
+
+/// Timeouts of the shared client, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timeouts {
+    /// Longest to wait for a connection to be established.
+    pub connect: u64,
+    /// Longest to wait for more of a response, reset whenever some arrives.
+    pub read: u64,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: 30,
+            read: 60,
+        }
+    }
+}
+
+static CLIENT: RwLock<Option<(Timeouts, reqwest::Client)>> = RwLock::new(None);
+
+/// The shared client, with the timeouts last passed to [`configure`].
+pub fn client() -> reqwest::Client {
+    if let Some((_, client)) = CLIENT.read().unwrap().as_ref() {
+        return client.clone();
+    }
+    CLIENT
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| (Timeouts::default(), build(Timeouts::default())))
+        .1
+        .clone()
+}
+
+/// Set the timeouts of the shared client. Providers only pick up the new client once they are
+/// created again, see [`super::ModStore::reload_providers`].
+pub fn configure(timeouts: Timeouts) {
+    let mut client = CLIENT.write().unwrap();
+    if client.as_ref().is_none_or(|(t, _)| *t != timeouts) {
+        *client = Some((timeouts, build(timeouts)));
+    }
+}
+
+/// Read the body of `response` to `request` into memory, reporting progress as that of `res` if
+/// its `size` is known. If the connection drops, the rest is requested with a Range request made
+/// conditional on the ETag or Last-Modified of `response` through If-Range, so a file changed in
+/// the meantime is never spliced together. If the server ignores the range, sends another one or
+/// gave no validator, all of it is downloaded again.
+pub async fn read_resumable(
+    request: reqwest::RequestBuilder,
+    mut response: reqwest::Response,
+    size: Option<u64>,
+    res: &ModResolution,
+    tx: &Option<Sender<FetchProgress>>,
+) -> reqwest::Result<Vec<u8>> {
+    use futures::stream::TryStreamExt;
+
+    let validator = validator(response.headers());
+    let mut data = vec![];
+    let mut resumes = 0;
+    let mut download = priority::Download::start();
+    loop {
+        let mut stream = response.bytes_stream();
+        let err = loop {
+            download.yield_to_metadata().await;
+            match stream.try_next().await {
+                Ok(Some(bytes)) => {
+                    data.extend_from_slice(&bytes);
+                    if let Some(size) = size
+                        && let Some(tx) = tx
+                    {
+                        tx.send(FetchProgress::Progress {
+                            resolution: res.clone(),
+                            progress: data.len() as u64,
+                            size,
+                        })
+                        .await
+                        .unwrap();
+                    }
+                }
+                Ok(None) => return Ok(data),
+                Err(e) => break e,
+            }
+        };
+        let Some(retry) = request.try_clone().filter(|_| resumes < MAX_RESUMES) else {
+            return Err(err);
+        };
+        resumes += 1;
+        warn!(
+            "download of <{}> interrupted after {} bytes, resuming: {err}",
+            res.url.0,
+            data.len()
+        );
+        let offset = data.len() as u64;
+        let retry = match &validator {
+            Some(validator) => retry
+                .header(RANGE, format!("bytes={offset}-"))
+                .header(IF_RANGE, validator),
+            None => retry,
+        };
+        response = retry.send().await?.error_for_status()?;
+        if response.status() == StatusCode::PARTIAL_CONTENT
+            && content_range_start(response.headers()) != Some(offset)
+        {
+            // not the part that is missing, start over without a range
+            let Some(retry) = request.try_clone() else {
+                return Err(err);
+            };
+            response = retry.send().await?.error_for_status()?;
+        }
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            data.clear();
+        }
+    }
+}
+
+/// The validator to resume a download of a response with through If-Range: its ETag if that is
+/// strong, as If-Range doesn't allow weak ones, or else its Last-Modified date.
+fn validator(headers: &HeaderMap) -> Option<HeaderValue> {
+    headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))
+        .cloned()
+}
+
+/// Where the part in a 206 response starts, from its `Content-Range: bytes <start>-<end>/<size>`.
+fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    let range = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+    let (start, _) = range.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+fn build(timeouts: Timeouts) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(timeouts.connect))
+        .read_timeout(Duration::from_secs(timeouts.read))
+        .build()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(reqwest::header::HeaderName, &'static str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.clone(), HeaderValue::from_static(value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_validator() {
+        let strong = headers(&[(ETAG, "\"abc\""), (LAST_MODIFIED, "Tue, 15 Nov 1994")]);
+        assert_eq!(validator(&strong).unwrap(), "\"abc\"");
+        let weak = headers(&[(ETAG, "W/\"abc\""), (LAST_MODIFIED, "Tue, 15 Nov 1994")]);
+        assert_eq!(validator(&weak).unwrap(), "Tue, 15 Nov 1994");
+        assert_eq!(validator(&headers(&[(ETAG, "W/\"abc\"")])), None);
+        assert_eq!(validator(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        let start = |range| content_range_start(&headers(&[(CONTENT_RANGE, range)]));
+        assert_eq!(start("bytes 100-199/200"), Some(100));
+        assert_eq!(start("bytes 0-199/*"), Some(0));
+        assert_eq!(start("bytes */200"), None);
+        assert_eq!(start("items 100-199/200"), None);
+        assert_eq!(content_range_start(&HeaderMap::new()), None);
+    }
+}
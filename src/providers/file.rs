@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use fs_err as fs;
+use snafu::prelude::*;
 use tokio::sync::mpsc::Sender;
 
 use super::{
-    BlobCache, FetchProgress, ModInfo, ModProvider, ModResolution, ModResponse, ModSpecification,
-    ProviderCache, ProviderError,
+    BlobCache, BufferIoSnafu, DownloadSize, FetchProgress, ModInfo, ModProvider, ModResolution,
+    ModResponse, ModSpecification, Pause, ProviderCache, ProviderError,
 };
 
 inventory::submit! {
@@ -41,6 +43,74 @@ impl FileProvider {
 
 const FILE_PROVIDER_ID: &str = "file";
 
+/// File names which say nothing about the mod, as left by pak tools and some download sites.
+const GENERIC_NAMES: &[&str] = &["mod", "mods", "download", "archive", "new", "output"];
+
+/// A readable name for the local mod at `path`, taken from its file name: without extension and
+/// the `_P` suffix of patch paks, with `_` and `-` as spaces. If the file name is generic, such as
+/// `pakchunk99-WindowsNoEditor_P.pak`, the directory the pak's files are mounted under is used
+/// instead. Reads the pak, so call it off the UI thread.
+pub fn derive_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_string_lossy();
+    // parts of multi-part archives are named after the whole
+    let file_name =
+        crate::archive::MultiPart::first_part(&file_name).map_or(&*file_name, |(_, base)| base);
+    let stem = Path::new(file_name).file_stem()?.to_string_lossy();
+    match readable_name(&stem) {
+        Some(name) if !is_generic(&name) => Some(name),
+        name => mount_name(path).and_then(|n| readable_name(&n)).or(name),
+    }
+}
+
+fn readable_name(stem: &str) -> Option<String> {
+    let stem = stem
+        .strip_suffix("_P")
+        .or_else(|| stem.strip_suffix("_p"))
+        .unwrap_or(stem);
+    let name = stem
+        .split(['_', '-', ' '])
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!name.is_empty()).then_some(name)
+}
+
+fn is_generic(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.starts_with("pakchunk")
+        || name.chars().all(|c| c.is_ascii_digit() || c == ' ')
+        || GENERIC_NAMES.contains(&name.as_str())
+}
+
+/// The single directory below `FSD/Content` all files of the pak are in, e.g. `BetterScanner` for
+/// a pak containing `FSD/Content/BetterScanner/...`.
+fn mount_name(path: &Path) -> Option<String> {
+    let file = crate::archive::open(path, crate::archive::DEFAULT_MEMORY_BUDGET).ok()?;
+    let mut reader =
+        crate::integrate::get_pak_from_data(file, crate::archive::DEFAULT_MEMORY_BUDGET).ok()?;
+    let pak = repak::PakBuilder::new().reader(&mut reader).ok()?;
+    let mount = pak.mount_point().trim_start_matches("../").to_string();
+
+    let dirs = pak
+        .files()
+        .into_iter()
+        .filter_map(|file| {
+            let full = format!("{}/{file}", mount.trim_end_matches('/'));
+            let mut components = full.trim_start_matches('/').split('/');
+            let (_, content, dir) = (components.next()?, components.next()?, components.next()?);
+            // the directory must not be the file itself
+            components.next()?;
+            content
+                .eq_ignore_ascii_case("Content")
+                .then(|| dir.to_string())
+        })
+        .collect::<BTreeSet<_>>();
+    match dirs.len() {
+        1 => dirs.into_iter().next(),
+        _ => None,
+    }
+}
+
 #[async_trait::async_trait]
 impl ModProvider for FileProvider {
     async fn resolve_mod(
@@ -67,8 +137,10 @@ impl ModProvider for FileProvider {
             ),
             suggested_require: false,
             suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
             modio_tags: None,
             modio_id: None,
+            local_tags: Default::default(),
         }))
     }
 
@@ -77,9 +149,23 @@ impl ModProvider for FileProvider {
         res: &ModResolution,
         _update: bool,
         _cache: ProviderCache,
-        _blob_cache: &BlobCache,
+        blob_cache: &BlobCache,
         tx: Option<Sender<FetchProgress>>,
     ) -> Result<PathBuf, ProviderError> {
+        let mut path = PathBuf::from(&res.url.0);
+        // multi-part archives are joined into the blob cache, anything else is used in place
+        if let Some((kind, parts)) = crate::archive::local_parts(&path) {
+            let joined = parts
+                .iter()
+                .map(fs::read)
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|parts| kind.join(parts))
+                .with_context(|_| BufferIoSnafu {
+                    url: res.url.0.clone(),
+                })?;
+            let blob = blob_cache.write(&joined)?;
+            path = blob_cache.get_path(&blob).unwrap();
+        }
         if let Some(tx) = tx {
             tx.send(FetchProgress::Complete {
                 resolution: res.clone(),
@@ -87,10 +173,23 @@ impl ModProvider for FileProvider {
             .await
             .unwrap();
         }
-        Ok(PathBuf::from(&res.url.0))
+        Ok(path)
     }
 
-    async fn update_cache(&self, _cache: ProviderCache) -> Result<(), ProviderError> {
+    async fn download_size(
+        &self,
+        _res: &ModResolution,
+        _cache: ProviderCache,
+        _blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError> {
+        Ok(DownloadSize::Cached)
+    }
+
+    async fn update_cache(
+        &self,
+        _cache: ProviderCache,
+        _pause: &Pause,
+    ) -> Result<(), ProviderError> {
         Ok(())
     }
 
@@ -117,8 +216,10 @@ impl ModProvider for FileProvider {
             ),
             suggested_require: false,
             suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
             modio_tags: None,
             modio_id: None,
+            local_tags: Default::default(),
         })
     }
 
@@ -129,4 +230,30 @@ impl ModProvider for FileProvider {
     fn get_version_name(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
         Some("latest".to_string())
     }
+
+    async fn fetch_signature(&self, res: &ModResolution) -> Option<Vec<u8>> {
+        fs::read(format!("{}{}", res.url.0, crate::signing::SIGNATURE_SUFFIX)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readable_name() {
+        assert_eq!(
+            readable_name("Better_Scanner_P").as_deref(),
+            Some("Better Scanner")
+        );
+        assert_eq!(
+            readable_name("no-more-fog--v2").as_deref(),
+            Some("no more fog v2")
+        );
+        assert_eq!(readable_name("_P"), None);
+        assert!(is_generic("pakchunk99 WindowsNoEditor"));
+        assert!(is_generic("Mod"));
+        assert!(is_generic("12345 67"));
+        assert!(!is_generic("Better Scanner"));
+    }
 }
@@ -1,8 +1,11 @@
 use std::sync::OnceLock;
 
+use reqwest::StatusCode;
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::providers::priority::MetadataCall;
 use crate::providers::*;
 
 inventory::submit! {
@@ -14,8 +17,32 @@ inventory::submit! {
                 .captures(url)
                 .and_then(|c| c.name("hostname"))
                 .is_some_and(|h| !["mod.io", "drg.mod.io", "drg.old.mod.io"].contains(&h.as_str()))
+                && !is_torrent_url(url)
         },
-        parameters: &[],
+        parameters: &[
+            super::ProviderParameter {
+                id: REFRESH_RULES,
+                name: "Link refresh rules",
+                description: "One rule per line as `<regex> => <replacement>`. When a download \
+                              link matching the regex has expired, the replacement is \
+                              downloaded instead, e.g. a page redirecting to a freshly signed \
+                              link. `$1` etc. refer to the regex's capture groups.",
+                link: None,
+                email_login: None,
+                optional: true,
+            },
+            super::ProviderParameter {
+                id: HEADERS,
+                name: "Request headers",
+                description: "One header per line as `<host> <name>: <value>`, sent with every \
+                              request to that host, or to all hosts for `*`. For servers which \
+                              require a token header or a specific user agent. Tokens can also \
+                              be kept out of the config through the secrets file.",
+                link: None,
+                email_login: None,
+                optional: true,
+            },
+        ],
     }
 }
 
@@ -42,6 +69,10 @@ impl ModProviderCache for HttpProviderCache {
 #[derive(Debug)]
 pub struct HttpProvider {
     client: reqwest::Client,
+    /// Turn an expired link into one which yields a fresh download.
+    refresh_rules: Vec<(regex::Regex, String)>,
+    /// Extra headers by host, `*` for all hosts.
+    headers: Vec<(String, HeaderName, HeaderValue)>,
 }
 
 impl Default for HttpProvider {
@@ -52,15 +83,160 @@ impl Default for HttpProvider {
 
 impl HttpProvider {
     pub fn new_provider(
-        _parameters: &HashMap<String, String>,
+        parameters: &HashMap<String, String>,
     ) -> Result<Arc<dyn ModProvider>, ProviderError> {
-        Ok(Arc::new(Self::new()))
+        let refresh_rules = match parameters.get(REFRESH_RULES) {
+            Some(rules) => parse_refresh_rules(rules).context(InitProviderFailedSnafu {
+                id: HTTP_PROVIDER_ID,
+                parameters: parameters.clone(),
+            })?,
+            None => vec![],
+        };
+        let headers = match parameters.get(HEADERS) {
+            Some(headers) => parse_headers(headers).context(InitProviderFailedSnafu {
+                id: HTTP_PROVIDER_ID,
+                parameters: parameters.clone(),
+            })?,
+            None => vec![],
+        };
+        Ok(Arc::new(Self {
+            refresh_rules,
+            headers,
+            ..Self::new()
+        }))
     }
 
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: client::client(),
+            refresh_rules: vec![],
+            headers: vec![],
+        }
+    }
+
+    /// A request to `url` carrying the configured headers for its host.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase));
+        self.headers
+            .iter()
+            .filter(|(h, _, _)| h == "*" || Some(h) == host.as_ref())
+            .fold(self.client.request(method, url), |req, (_, name, value)| {
+                req.header(name, value)
+            })
+    }
+
+    /// The link to download instead of the expired `url`, from the first matching refresh rule.
+    fn refresh_url(&self, url: &str) -> Option<String> {
+        self.refresh_rules
+            .iter()
+            .find(|(re, _)| re.is_match(url))
+            .map(|(re, replacement)| re.replace(url, replacement).into_owned())
+    }
+
+    /// Like [`Self::download`], but through a refresh rule if `url` has expired.
+    async fn download_refreshing(
+        &self,
+        url: &str,
+        res: &ModResolution,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        match self.download(url, res, tx).await {
+            Err(ProviderError::LinkExpired { .. })
+                if let Some(refreshed) = self.refresh_url(url) =>
+            {
+                info!("{url} has expired, downloading {refreshed} instead");
+                self.download(&refreshed, res, tx).await
+            }
+            result => result,
+        }
+    }
+
+    /// Download the mod at `url`, joining the parts if it is a multi-part archive.
+    async fn fetch_data(
+        &self,
+        url: &str,
+        res: &ModResolution,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        let Some((kind, base)) = crate::archive::MultiPart::first_part(url) else {
+            return self.download_refreshing(url, res, tx).await;
+        };
+        let mut parts = vec![self.download_refreshing(url, res, tx).await?];
+        // the numbered parts continue until one is missing
+        for n in 2.. {
+            match self.download_refreshing(&kind.part(base, n), res, tx).await {
+                Err(ProviderError::ResponseError { source, .. })
+                    if source.status() == Some(reqwest::StatusCode::NOT_FOUND) =>
+                {
+                    break;
+                }
+                part => parts.push(part?),
+            }
+        }
+        if let Some(last) = kind.last_part(base) {
+            parts.push(self.download_refreshing(&last, res, tx).await?);
+        }
+        kind.join(parts).context(BufferIoSnafu {
+            url: url.to_string(),
+        })
+    }
+
+    /// Download `url` into memory, reporting progress as that of `res`.
+    pub(super) async fn download(
+        &self,
+        url: &str,
+        res: &ModResolution,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        let request = self.request(reqwest::Method::GET, url);
+        let response = request
+            .try_clone()
+            .unwrap()
+            .send()
+            .await
+            .context(RequestFailedSnafu {
+                url: url.to_string(),
+            })?;
+        let status = response.status();
+        if matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::GONE
+        ) {
+            // the error pages are small, and only read to tell expired links apart
+            let body = response.text().await.unwrap_or_default();
+            return if is_expired(url, status, &body, chrono::Utc::now()) {
+                LinkExpiredSnafu { url }.fail()
+            } else {
+                UnauthorizedSnafu { url, status }.fail()
+            };
         }
+        let response = response.error_for_status().context(ResponseSnafu {
+            url: url.to_string(),
+        })?;
+        let size = response.content_length(); // TODO will be incorrect if compressed
+        if let Some(mime) = response
+            .headers()
+            .get(reqwest::header::HeaderName::from_static("content-type"))
+        {
+            let content_type = mime.to_str().context(InvalidMimeSnafu {
+                url: url.to_string(),
+            })?;
+            ensure!(
+                ARCHIVE_CONTENT_TYPES.contains(&content_type),
+                UnexpectedContentTypeSnafu {
+                    found_content_type: content_type.to_string(),
+                    url: url.to_string(),
+                }
+            );
+        }
+
+        client::read_resumable(request, response, size, res, tx)
+            .await
+            .with_context(|_| FetchSnafu {
+                url: url.to_string(),
+            })
     }
 }
 
@@ -69,7 +245,66 @@ fn re_mod() -> &'static regex::Regex {
     RE_MOD.get_or_init(|| regex::Regex::new(r"^https?://(?P<hostname>[^/]+)(/|$)").unwrap())
 }
 
+/// Links to `.torrent` files are left to the torrent provider if it is built.
+fn is_torrent_url(url: &str) -> bool {
+    #[cfg(feature = "torrent")]
+    {
+        super::torrent::is_torrent_url(url)
+    }
+    #[cfg(not(feature = "torrent"))]
+    {
+        let _ = url;
+        false
+    }
+}
+
 const HTTP_PROVIDER_ID: &str = "http";
+const REFRESH_RULES: &str = "refresh_rules";
+const HEADERS: &str = "headers";
+
+/// Parse one `<regex> => <replacement>` rule per line, skipping blank lines and `#` comments.
+fn parse_refresh_rules(rules: &str) -> Option<Vec<(regex::Regex, String)>> {
+    rules
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let (pattern, replacement) = l.split_once("=>")?;
+            let re = regex::Regex::new(pattern.trim()).ok()?;
+            Some((re, replacement.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Parse one `<host> <name>: <value>` header per line, skipping blank lines and `#` comments.
+fn parse_headers(headers: &str) -> Option<Vec<(String, HeaderName, HeaderValue)>> {
+    headers
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let (host, header) = l.split_once(char::is_whitespace)?;
+            let (name, value) = header.split_once(':')?;
+            Some((
+                host.to_lowercase(),
+                HeaderName::from_bytes(name.trim().as_bytes()).ok()?,
+                HeaderValue::from_str(value.trim()).ok()?,
+            ))
+        })
+        .collect()
+}
+
+/// Content types of downloads which can be mods. Hosts often serve paks and archives as plain
+/// binary data.
+const ARCHIVE_CONTENT_TYPES: &[&str] = &[
+    "application/zip",
+    "application/x-7z-compressed",
+    "application/vnd.rar",
+    "application/x-rar-compressed",
+    "application/octet-stream",
+    // IPFS gateways, whose CAR files are checked against the CID, see [`super::ipfs::car`]
+    "application/vnd.ipld.car",
+];
 
 #[async_trait::async_trait]
 impl ModProvider for HttpProvider {
@@ -99,8 +334,10 @@ impl ModProvider for HttpProvider {
             resolution: ModResolution::resolvable(spec.url.as_str().into()),
             suggested_require: false,
             suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
             modio_tags: None,
             modio_id: None,
+            local_tags: Default::default(),
         }))
     }
 
@@ -113,105 +350,90 @@ impl ModProvider for HttpProvider {
         tx: Option<Sender<FetchProgress>>,
     ) -> Result<PathBuf, ProviderError> {
         let url = &res.url;
-        Ok(
-            if let Some(path) = if update {
-                None
-            } else {
-                cache
-                    .read()
-                    .unwrap()
-                    .get::<HttpProviderCache>(HTTP_PROVIDER_ID)
-                    .and_then(|c| c.url_blobs.get(&url.0))
-                    .and_then(|r| blob_cache.get_path(r))
-            } {
-                if let Some(tx) = tx {
-                    tx.send(FetchProgress::Complete {
-                        resolution: res.clone(),
-                    })
-                    .await
-                    .unwrap();
-                }
-                path
-            } else {
-                info!("downloading mod {url:?}...");
-                let response = self
-                    .client
-                    .get(&url.0)
-                    .send()
-                    .await
-                    .context(RequestFailedSnafu {
-                        url: url.0.to_string(),
-                    })?
-                    .error_for_status()
-                    .context(ResponseSnafu {
-                        url: url.0.to_string(),
-                    })?;
-                let size = response.content_length(); // TODO will be incorrect if compressed
-                if let Some(mime) = response
-                    .headers()
-                    .get(reqwest::header::HeaderName::from_static("content-type"))
-                {
-                    let content_type = mime.to_str().context(InvalidMimeSnafu {
-                        url: url.0.to_string(),
-                    })?;
-                    ensure!(
-                        ["application/zip", "application/octet-stream"].contains(&content_type),
-                        UnexpectedContentTypeSnafu {
-                            found_content_type: content_type.to_string(),
-                            url: url.0.to_string(),
-                        }
-                    );
-                }
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<HttpProviderCache>(HTTP_PROVIDER_ID)
+            .and_then(|c| c.url_blobs.get(&url.0))
+            .and_then(|r| blob_cache.get_path(r));
 
-                use futures::stream::TryStreamExt;
-                use tokio::io::AsyncWriteExt;
-
-                let mut cursor = std::io::Cursor::new(vec![]);
-                let mut stream = response.bytes_stream();
-                while let Some(bytes) = stream.try_next().await.with_context(|_| FetchSnafu {
-                    url: url.0.to_string(),
-                })? {
-                    cursor
-                        .write_all(&bytes)
-                        .await
-                        .with_context(|_| BufferIoSnafu {
-                            url: url.0.to_string(),
-                        })?;
-                    if let Some(size) = size
-                        && let Some(tx) = &tx
-                    {
-                        tx.send(FetchProgress::Progress {
-                            resolution: res.clone(),
-                            progress: cursor.get_ref().len() as u64,
-                            size,
-                        })
-                        .await
-                        .unwrap();
+        let path = match cached {
+            Some(path) if !update => path,
+            cached => {
+                info!("downloading mod {url:?}...");
+                match self.fetch_data(&url.0, res, &tx).await {
+                    Ok(data) => {
+                        // a tampered archive must not replace the last good download
+                        let signature = self.fetch_signature(res).await;
+                        check_signature(&url.0, &data, signature.as_deref())?;
+                        let blob = blob_cache.write(&data)?;
+                        let path = blob_cache.get_path(&blob).unwrap();
+                        cache
+                            .write()
+                            .unwrap()
+                            .get_mut::<HttpProviderCache>(HTTP_PROVIDER_ID)
+                            .url_blobs
+                            .insert(url.0.to_owned(), blob);
+                        path
                     }
+                    // an expired link doesn't make the last download any less usable
+                    Err(e @ ProviderError::LinkExpired { .. }) if cached.is_some() => {
+                        warn!("{e}, keeping the cached download");
+                        cached.unwrap()
+                    }
+                    // any part of a multi-part mod may have expired, but it is replaced as a whole
+                    Err(ProviderError::LinkExpired { .. }) => {
+                        return LinkExpiredSnafu { url: &url.0 }.fail();
+                    }
+                    Err(e) => return Err(e),
                 }
+            }
+        };
 
-                let blob = blob_cache.write(&cursor.into_inner())?;
-                let path = blob_cache.get_path(&blob).unwrap();
-                cache
-                    .write()
-                    .unwrap()
-                    .get_mut::<HttpProviderCache>(HTTP_PROVIDER_ID)
-                    .url_blobs
-                    .insert(url.0.to_owned(), blob);
-
-                if let Some(tx) = tx {
-                    tx.send(FetchProgress::Complete {
-                        resolution: res.clone(),
-                    })
-                    .await
-                    .unwrap();
-                }
-                path
-            },
-        )
+        if let Some(tx) = tx {
+            tx.send(FetchProgress::Complete {
+                resolution: res.clone(),
+            })
+            .await
+            .unwrap();
+        }
+        Ok(path)
+    }
+
+    async fn download_size(
+        &self,
+        res: &ModResolution,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError> {
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<HttpProviderCache>(HTTP_PROVIDER_ID)
+            .and_then(|c| c.url_blobs.get(&res.url.0))
+            .and_then(|r| blob_cache.get_path(r))
+            .is_some();
+        if cached {
+            return Ok(DownloadSize::Cached);
+        }
+        let _call = MetadataCall::start();
+        // not every server answers HEAD requests, so failing to get a size is not an error
+        let size = match self.request(reqwest::Method::HEAD, &res.url.0).send().await {
+            Ok(response) if response.status().is_success() => response
+                .headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok()),
+            _ => None,
+        };
+        Ok(DownloadSize::Download(size))
     }
 
-    async fn update_cache(&self, _cache: ProviderCache) -> Result<(), ProviderError> {
+    async fn update_cache(
+        &self,
+        _cache: ProviderCache,
+        _pause: &Pause,
+    ) -> Result<(), ProviderError> {
         Ok(())
     }
 
@@ -234,8 +456,10 @@ impl ModProvider for HttpProvider {
             resolution: ModResolution::resolvable(spec.url.as_str().into()),
             suggested_require: false,
             suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
             modio_tags: None,
             modio_id: None,
+            local_tags: Default::default(),
         })
     }
 
@@ -246,4 +470,175 @@ impl ModProvider for HttpProvider {
     fn get_version_name(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
         Some("latest".to_string())
     }
+
+    async fn fetch_signature(&self, res: &ModResolution) -> Option<Vec<u8>> {
+        // the signature is uploaded next to the archive, keeping the query for hosts needing it
+        let mut url = url::Url::parse(&res.url.0).ok()?;
+        url.set_path(&format!(
+            "{}{}",
+            url.path(),
+            crate::signing::SIGNATURE_SUFFIX
+        ));
+        let response = self
+            .request(reqwest::Method::GET, url.as_str())
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?;
+        Some(response.bytes().await.ok()?.to_vec())
+    }
+}
+
+/// Fail if `data` does not match its signature. Whether the signer is trusted is up to the
+/// [`ModStore`], which checks the cached archive against its trusted signers.
+fn check_signature(url: &str, data: &[u8], signature: Option<&[u8]>) -> Result<(), ProviderError> {
+    ensure!(
+        crate::signing::verify(data, signature, &[]) != crate::signing::SignatureStatus::Mismatch,
+        SignatureMismatchSnafu { url }
+    );
+    Ok(())
+}
+
+/// Whether a download answered with `status` failed because `url` is a signed link which has
+/// expired, rather than because access to it is not allowed at all, e.g. for a missing token
+/// header. Hosts say so in the error page or put the expiry time into the link, as S3, Azure and
+/// CloudFront do.
+fn is_expired(
+    url: &str,
+    status: StatusCode,
+    body: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if status == StatusCode::GONE {
+        return true;
+    }
+    if body.to_lowercase().contains("expired") {
+        return true;
+    }
+    let Ok(url) = url::Url::parse(url) else {
+        return false;
+    };
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.into_owned())
+    };
+    let expiry = if let Some(date) = param("X-Amz-Date")
+        && let Some(seconds) = param("X-Amz-Expires")
+    {
+        chrono::NaiveDateTime::parse_from_str(&date, "%Y%m%dT%H%M%SZ")
+            .ok()
+            .zip(seconds.parse().ok())
+            .map(|(date, seconds)| date.and_utc() + chrono::Duration::seconds(seconds))
+    } else if let Some(se) = param("se") {
+        chrono::DateTime::parse_from_rfc3339(&se)
+            .ok()
+            .map(|d| d.with_timezone(&chrono::Utc))
+    } else {
+        param("Expires")
+            .or_else(|| param("exp"))
+            .and_then(|e| e.parse().ok())
+            .and_then(|e| chrono::DateTime::from_timestamp(e, 0))
+    };
+    expiry.is_some_and(|expiry| expiry <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_rules() {
+        let rules = parse_refresh_rules(
+            "# signed links expire after a day\n\n\
+             ^https://cdn\\.example\\.com/(\\w+)\\.zip\\?.* => https://example.com/download/$1\n",
+        )
+        .unwrap();
+        let provider = HttpProvider {
+            refresh_rules: rules,
+            ..HttpProvider::new()
+        };
+        assert_eq!(
+            provider
+                .refresh_url("https://cdn.example.com/mod.zip?token=abc")
+                .as_deref(),
+            Some("https://example.com/download/mod")
+        );
+        assert_eq!(provider.refresh_url("https://other.com/mod.zip"), None);
+        assert!(parse_refresh_rules("no arrow").is_none());
+        assert!(parse_refresh_rules("( => x").is_none());
+    }
+
+    #[test]
+    fn test_headers() {
+        let headers = parse_headers(
+            "Files.Example.com X-Auth-Token: secret\n* User-Agent: mint\n# comment\n",
+        )
+        .unwrap();
+        let provider = HttpProvider {
+            headers,
+            ..HttpProvider::new()
+        };
+        let request = |url: &str| provider.request(reqwest::Method::GET, url).build().unwrap();
+        let own = request("https://files.example.com/mod.zip");
+        assert_eq!(own.headers()["x-auth-token"], "secret");
+        assert_eq!(own.headers()["user-agent"], "mint");
+        let other = request("https://other.com/mod.zip");
+        assert!(!other.headers().contains_key("x-auth-token"));
+        assert_eq!(other.headers()["user-agent"], "mint");
+
+        assert!(parse_headers("example.com no-colon").is_none());
+        assert!(parse_headers("X-Auth-Token: secret").is_none());
+    }
+
+    #[test]
+    fn test_check_signature() {
+        let url = "https://example.com/mod.zip";
+        let (secret, _) = crate::signing::generate_key();
+        let signature = crate::signing::sign(b"archive", &secret).unwrap();
+        let signature = serde_json::to_vec(&signature).unwrap();
+
+        assert!(check_signature(url, b"archive", None).is_ok());
+        assert!(check_signature(url, b"archive", Some(&signature)).is_ok());
+        assert!(matches!(
+            check_signature(url, b"tampered", Some(&signature)),
+            Err(ProviderError::SignatureMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let now = chrono::DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let expired = |url: &str, status, body: &str| is_expired(url, status, body, now);
+        let plain = "https://example.com/mod.zip";
+
+        assert!(expired(plain, StatusCode::GONE, ""));
+        assert!(expired(
+            plain,
+            StatusCode::FORBIDDEN,
+            "<Code>AccessDenied</Code><Message>Request has expired</Message>"
+        ));
+        // a missing token header or a private file is not fixed by a new link
+        assert!(!expired(plain, StatusCode::FORBIDDEN, "Access denied"));
+        assert!(!expired(plain, StatusCode::UNAUTHORIZED, ""));
+
+        let s3 = |date: &str| {
+            format!("https://b.s3.amazonaws.com/m.zip?X-Amz-Date={date}&X-Amz-Expires=3600")
+        };
+        assert!(expired(&s3("20240601T105959Z"), StatusCode::FORBIDDEN, ""));
+        assert!(!expired(&s3("20240601T113000Z"), StatusCode::FORBIDDEN, ""));
+        let azure = "https://a.blob.core.windows.net/c/m.zip?sv=1&se=2024-06-01T11%3A00%3A00Z";
+        assert!(expired(azure, StatusCode::FORBIDDEN, ""));
+        // 2024-06-01T13:00:00Z
+        let cloudfront = "https://d.cloudfront.net/m.zip?Expires=1717246800&Signature=x";
+        assert!(!expired(cloudfront, StatusCode::FORBIDDEN, ""));
+        assert!(expired(
+            "https://d.cloudfront.net/m.zip?Expires=1717236000",
+            StatusCode::FORBIDDEN,
+            ""
+        ));
+    }
 }
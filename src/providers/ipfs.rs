@@ -0,0 +1,281 @@
+//! Mods pinned on IPFS, added as `ipfs://<CID>` or `ipfs://<CID>/<path>`. They are downloaded
+//! through HTTP gateways, trying the next one if a gateway fails, and checked against the CID, see
+//! [`car`]. As the CID names the content itself, a download never needs updating.
+
+pub mod car;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::http::HttpProvider;
+use crate::providers::*;
+
+inventory::submit! {
+    super::ProviderFactory {
+        id: IPFS_PROVIDER_ID,
+        new: IpfsProvider::new_provider,
+        can_provide: |url| -> bool { parse_ipfs_url(url).is_some() },
+        parameters: &[
+            super::ProviderParameter {
+                id: GATEWAYS,
+                name: "Gateways",
+                description: "One gateway URL per line, tried in order, e.g. \
+                              `https://ipfs.io`. Leave empty for the default public gateways.",
+                link: None,
+                email_login: None,
+                optional: true,
+            },
+        ],
+    }
+}
+
+const IPFS_PROVIDER_ID: &str = "ipfs";
+const GATEWAYS: &str = "gateways";
+
+const DEFAULT_GATEWAYS: &[&str] = &["https://ipfs.io", "https://dweb.link"];
+
+/// The CID and the path within it of an `ipfs://` URL.
+fn parse_ipfs_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("ipfs://")?;
+    let (cid, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+    (!cid.is_empty() && cid.chars().all(|c| c.is_ascii_alphanumeric())).then_some((cid, path))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IpfsProviderCache {
+    /// Downloads checked against their CID. Those cached in `url_blobs` before downloads were
+    /// checked are left behind and downloaded again.
+    #[serde(default)]
+    verified_blobs: HashMap<String, BlobRef>,
+}
+
+#[typetag::serde]
+impl ModProviderCache for IpfsProviderCache {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct IpfsProvider {
+    http: HttpProvider,
+    gateways: Vec<String>,
+}
+
+impl IpfsProvider {
+    pub fn new_provider(
+        parameters: &HashMap<String, String>,
+    ) -> Result<Arc<dyn ModProvider>, ProviderError> {
+        let mut gateways = parameters
+            .get(GATEWAYS)
+            .map(|g| parse_gateways(g))
+            .unwrap_or_default();
+        ensure!(
+            gateways.iter().all(|g| url::Url::parse(g).is_ok()),
+            InitProviderFailedSnafu {
+                id: IPFS_PROVIDER_ID,
+                parameters: parameters.clone(),
+            }
+        );
+        if gateways.is_empty() {
+            gateways = DEFAULT_GATEWAYS.iter().map(|g| g.to_string()).collect();
+        }
+        Ok(Arc::new(Self {
+            http: HttpProvider::new(),
+            gateways,
+        }))
+    }
+
+    /// Download `url` from the first gateway which serves content matching its CID.
+    async fn download(
+        &self,
+        url: &str,
+        res: &ModResolution,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        let (cid, path) = parse_ipfs_url(url).context(InvalidUrlSnafu { url })?;
+        let mut last_error = None;
+        for gateway in &self.gateways {
+            let gateway_url = format!("{gateway}/ipfs/{cid}{path}?format=car");
+            let data = self
+                .http
+                .download(&gateway_url, res, tx)
+                .await
+                .and_then(|blocks| {
+                    car::content(&blocks, cid, path).context(ContentMismatchSnafu { url })
+                });
+            match data {
+                Ok(data) => return Ok(data),
+                Err(e) => {
+                    warn!("IPFS gateway {gateway} failed, trying the next one: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("there is always at least one gateway"))
+    }
+}
+
+/// Parse one gateway per line, skipping blank lines and `#` comments.
+fn parse_gateways(gateways: &str) -> Vec<String> {
+    gateways
+        .lines()
+        .map(|l| l.trim().trim_end_matches('/'))
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl ModProvider for IpfsProvider {
+    async fn resolve_mod(
+        &self,
+        spec: &ModSpecification,
+        _update: bool,
+        cache: ProviderCache,
+    ) -> Result<ModResponse, ProviderError> {
+        Ok(ModResponse::Resolve(
+            self.get_mod_info(spec, cache)
+                .context(InvalidUrlSnafu { url: &spec.url })?,
+        ))
+    }
+
+    async fn fetch_mod(
+        &self,
+        res: &ModResolution,
+        _update: bool,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+        tx: Option<Sender<FetchProgress>>,
+    ) -> Result<PathBuf, ProviderError> {
+        let url = &res.url;
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<IpfsProviderCache>(IPFS_PROVIDER_ID)
+            .and_then(|c| c.verified_blobs.get(&url.0))
+            .and_then(|r| blob_cache.get_path(r));
+
+        let path = match cached {
+            Some(path) => path,
+            None => {
+                info!("downloading mod {url:?} from IPFS...");
+                let data = self.download(&url.0, res, &tx).await?;
+                let blob = blob_cache.write(&data)?;
+                let path = blob_cache.get_path(&blob).unwrap();
+                cache
+                    .write()
+                    .unwrap()
+                    .get_mut::<IpfsProviderCache>(IPFS_PROVIDER_ID)
+                    .verified_blobs
+                    .insert(url.0.to_owned(), blob);
+                path
+            }
+        };
+
+        if let Some(tx) = tx {
+            tx.send(FetchProgress::Complete {
+                resolution: res.clone(),
+            })
+            .await
+            .unwrap();
+        }
+        Ok(path)
+    }
+
+    async fn download_size(
+        &self,
+        res: &ModResolution,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError> {
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<IpfsProviderCache>(IPFS_PROVIDER_ID)
+            .and_then(|c| c.verified_blobs.get(&res.url.0))
+            .and_then(|r| blob_cache.get_path(r))
+            .is_some();
+        Ok(if cached {
+            DownloadSize::Cached
+        } else {
+            DownloadSize::Download(None)
+        })
+    }
+
+    async fn update_cache(
+        &self,
+        _cache: ProviderCache,
+        _pause: &Pause,
+    ) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    async fn check(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    fn get_mod_info(&self, spec: &ModSpecification, _cache: ProviderCache) -> Option<ModInfo> {
+        let (cid, path) = parse_ipfs_url(&spec.url)?;
+        let name = path
+            .rsplit('/')
+            .find(|s| !s.is_empty())
+            .unwrap_or(cid)
+            .to_string();
+        Some(ModInfo {
+            provider: IPFS_PROVIDER_ID,
+            name,
+            spec: spec.clone(),
+            versions: vec![],
+            resolution: ModResolution::resolvable(spec.url.as_str().into()),
+            suggested_require: false,
+            suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
+            modio_tags: None,
+            modio_id: None,
+            local_tags: Default::default(),
+        })
+    }
+
+    fn is_pinned(&self, _spec: &ModSpecification, _cache: ProviderCache) -> bool {
+        true
+    }
+
+    fn get_version_name(&self, spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
+        parse_ipfs_url(&spec.url).map(|(cid, _)| cid.chars().take(12).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipfs_url() {
+        let cid = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        assert_eq!(parse_ipfs_url(&format!("ipfs://{cid}")), Some((cid, "")));
+        assert_eq!(
+            parse_ipfs_url(&format!("ipfs://{cid}/packs/big_pack.zip")),
+            Some((cid, "/packs/big_pack.zip"))
+        );
+        assert_eq!(parse_ipfs_url("ipfs://"), None);
+        assert_eq!(parse_ipfs_url("ipfs://not a cid"), None);
+        assert_eq!(parse_ipfs_url("https://ipfs.io/ipfs/abc"), None);
+    }
+
+    #[test]
+    fn test_parse_gateways() {
+        assert_eq!(
+            parse_gateways("# own node first\nhttp://127.0.0.1:8080/\n\n https://ipfs.io \n"),
+            vec!["http://127.0.0.1:8080", "https://ipfs.io"]
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Checking content downloaded from an IPFS gateway against its CID. Asked for `?format=car`, a
+//! gateway answers with a CAR file holding the blocks the content is made of. Each block is checked
+//! against the hash in its CID, and the content is put together by following the links from the
+//! requested CID, so a gateway cannot serve anything but the content the CID names.
+
+use std::collections::HashMap;
+
+use sha2::{Digest, Sha256};
+use snafu::prelude::*;
+
+/// Multicodec codes of the CIDs and hashes which can be checked.
+const CODEC_RAW: u64 = 0x55;
+const CODEC_DAG_PB: u64 = 0x70;
+const HASH_IDENTITY: u64 = 0x00;
+const HASH_SHA2_256: u64 = 0x12;
+
+/// UnixFS node types.
+const UNIXFS_RAW: u64 = 0;
+const UNIXFS_DIRECTORY: u64 = 1;
+const UNIXFS_FILE: u64 = 2;
+
+#[derive(Debug, Snafu)]
+pub enum CarError {
+    #[snafu(display("{cid} is not a valid CID"))]
+    InvalidCid { cid: String },
+    #[snafu(display("CIDs of {what} cannot be checked yet"))]
+    Unsupported { what: String },
+    #[snafu(display("the CAR file is truncated or corrupt"))]
+    Malformed,
+    #[snafu(display("the gateway sent a block which does not match its CID"))]
+    HashMismatch,
+    #[snafu(display("the gateway left out a block of the content"))]
+    MissingBlock,
+    #[snafu(display("{name} is not in the directory"))]
+    NotFound { name: String },
+    #[snafu(display("the content is not a file"))]
+    NotAFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cid {
+    codec: u64,
+    hash: u64,
+    digest: Vec<u8>,
+}
+
+impl Cid {
+    /// Parse a CIDv0 (`Qm...`), or a CIDv1 in base32 or base58btc.
+    fn parse(cid: &str) -> Result<Self, CarError> {
+        let invalid = || InvalidCidSnafu { cid }.build();
+        let bytes = if cid.len() == 46 && cid.starts_with("Qm") {
+            decode_base58(cid).ok_or_else(invalid)?
+        } else {
+            let mut chars = cid.chars();
+            match chars.next() {
+                Some('b') => decode_base32(chars.as_str()),
+                Some('B') => decode_base32(&chars.as_str().to_ascii_lowercase()),
+                Some('z') => decode_base58(chars.as_str()),
+                _ => {
+                    return UnsupportedSnafu {
+                        what: format!("the multibase of {cid}"),
+                    }
+                    .fail();
+                }
+            }
+            .ok_or_else(invalid)?
+        };
+        let mut input = bytes.as_slice();
+        let parsed = Self::read(&mut input).ok_or_else(invalid)?;
+        ensure!(input.is_empty(), InvalidCidSnafu { cid });
+        Ok(parsed)
+    }
+
+    /// Read a CID in its binary form, as CAR files and links have them.
+    fn read(input: &mut &[u8]) -> Option<Self> {
+        if input.starts_with(&[HASH_SHA2_256 as u8, 32]) {
+            let (hash, digest) = read_multihash(input)?;
+            return Some(Self {
+                codec: CODEC_DAG_PB,
+                hash,
+                digest,
+            });
+        }
+        if read_varint(input)? != 1 {
+            return None;
+        }
+        let codec = read_varint(input)?;
+        let (hash, digest) = read_multihash(input)?;
+        Some(Self {
+            codec,
+            hash,
+            digest,
+        })
+    }
+
+    /// Whether `data` is the block this CID names.
+    fn matches(&self, data: &[u8]) -> Result<bool, CarError> {
+        match self.hash {
+            HASH_IDENTITY => Ok(self.digest == data),
+            HASH_SHA2_256 => Ok(Sha256::digest(data).as_slice() == self.digest),
+            hash => UnsupportedSnafu {
+                what: format!("multihash {hash:#x}"),
+            }
+            .fail(),
+        }
+    }
+}
+
+fn read_multihash(input: &mut &[u8]) -> Option<(u64, Vec<u8>)> {
+    let hash = read_varint(input)?;
+    let len = read_varint(input)? as usize;
+    let digest = take(input, len)?;
+    Some((hash, digest.to_vec()))
+}
+
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = input.split_first()?;
+        *input = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (taken, rest) = input.split_at(len);
+    *input = rest;
+    Some(taken)
+}
+
+fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in s.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u32::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
+
+fn decode_base58(s: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    // little endian digits in base 256
+    let mut digits: Vec<u8> = vec![];
+    for c in s.bytes() {
+        let mut carry = ALPHABET.iter().position(|&a| a == c)? as u32;
+        for digit in &mut digits {
+            carry += u32::from(*digit) * 58;
+            *digit = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            digits.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = s.bytes().take_while(|&c| c == b'1').count();
+    Some(
+        std::iter::repeat_n(0, zeros)
+            .chain(digits.into_iter().rev())
+            .collect(),
+    )
+}
+
+/// Fields of a protobuf message, as field number and either a varint or length delimited bytes.
+fn protobuf_fields(mut input: &[u8]) -> Result<Vec<(u64, Field<'_>)>, CarError> {
+    let mut fields = vec![];
+    while !input.is_empty() {
+        let key = read_varint(&mut input).context(MalformedSnafu)?;
+        let field = match key & 7 {
+            0 => Field::Varint(read_varint(&mut input).context(MalformedSnafu)?),
+            2 => {
+                let len = read_varint(&mut input).context(MalformedSnafu)? as usize;
+                Field::Bytes(take(&mut input, len).context(MalformedSnafu)?)
+            }
+            _ => return MalformedSnafu.fail(),
+        };
+        fields.push((key >> 3, field));
+    }
+    Ok(fields)
+}
+
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// A dag-pb node with its UnixFS data.
+struct Node<'a> {
+    links: Vec<(Cid, &'a str)>,
+    kind: u64,
+    data: &'a [u8],
+}
+
+impl<'a> Node<'a> {
+    fn parse(block: &'a [u8]) -> Result<Self, CarError> {
+        let mut links = vec![];
+        let mut unixfs: &[u8] = &[];
+        for (number, field) in protobuf_fields(block)? {
+            match (number, field) {
+                (1, Field::Bytes(data)) => unixfs = data,
+                (2, Field::Bytes(link)) => {
+                    let (mut cid, mut name) = (None, "");
+                    for (number, field) in protobuf_fields(link)? {
+                        match (number, field) {
+                            (1, Field::Bytes(mut hash)) => cid = Cid::read(&mut hash),
+                            (2, Field::Bytes(n)) => {
+                                name = std::str::from_utf8(n).ok().context(MalformedSnafu)?
+                            }
+                            _ => {}
+                        }
+                    }
+                    links.push((cid.context(MalformedSnafu)?, name));
+                }
+                _ => {}
+            }
+        }
+        let (mut kind, mut data) = (None, &[][..]);
+        for (number, field) in protobuf_fields(unixfs)? {
+            match (number, field) {
+                (1, Field::Varint(k)) => kind = Some(k),
+                (2, Field::Bytes(d)) => data = d,
+                _ => {}
+            }
+        }
+        Ok(Self {
+            links,
+            kind: kind.context(MalformedSnafu)?,
+            data,
+        })
+    }
+}
+
+/// Blocks by the hash function and digest of their CID.
+type Blocks<'a> = HashMap<(u64, Vec<u8>), &'a [u8]>;
+
+/// The blocks of a CARv1 file, each checked against its CID.
+fn read_blocks(mut car: &[u8]) -> Result<Blocks<'_>, CarError> {
+    // the roots in the header are not needed, the content is found from the CID that was asked for
+    let header_len = read_varint(&mut car).context(MalformedSnafu)? as usize;
+    take(&mut car, header_len).context(MalformedSnafu)?;
+    let mut blocks = HashMap::new();
+    while !car.is_empty() {
+        let len = read_varint(&mut car).context(MalformedSnafu)? as usize;
+        let mut section = take(&mut car, len).context(MalformedSnafu)?;
+        let cid = Cid::read(&mut section).context(MalformedSnafu)?;
+        ensure!(cid.matches(section)?, HashMismatchSnafu);
+        blocks.insert((cid.hash, cid.digest), section);
+    }
+    Ok(blocks)
+}
+
+/// The file at `path` within the content named by `cid`, put together from the blocks in `car`.
+pub fn content(car: &[u8], cid: &str, path: &str) -> Result<Vec<u8>, CarError> {
+    let blocks = read_blocks(car)?;
+    let block = |cid: &Cid| -> Result<Vec<u8>, CarError> {
+        if cid.hash == HASH_IDENTITY {
+            return Ok(cid.digest.clone());
+        }
+        let block = blocks
+            .get(&(cid.hash, cid.digest.clone()))
+            .context(MissingBlockSnafu)?;
+        Ok(block.to_vec())
+    };
+
+    let mut cid = Cid::parse(cid)?;
+    for name in path.split('/').filter(|s| !s.is_empty()) {
+        ensure!(cid.codec == CODEC_DAG_PB, NotFoundSnafu { name });
+        let data = block(&cid)?;
+        let node = Node::parse(&data)?;
+        ensure!(
+            node.kind == UNIXFS_DIRECTORY,
+            UnsupportedSnafu {
+                what: "sharded or non-directory nodes in a path"
+            }
+        );
+        cid = node
+            .links
+            .into_iter()
+            .find(|(_, n)| *n == name)
+            .map(|(cid, _)| cid)
+            .context(NotFoundSnafu { name })?;
+    }
+
+    let mut out = vec![];
+    append_file(&block, &cid, &mut out)?;
+    Ok(out)
+}
+
+fn append_file(
+    block: &impl Fn(&Cid) -> Result<Vec<u8>, CarError>,
+    cid: &Cid,
+    out: &mut Vec<u8>,
+) -> Result<(), CarError> {
+    let data = block(cid)?;
+    match cid.codec {
+        CODEC_RAW => out.extend_from_slice(&data),
+        CODEC_DAG_PB => {
+            let node = Node::parse(&data)?;
+            ensure!(matches!(node.kind, UNIXFS_RAW | UNIXFS_FILE), NotAFileSnafu);
+            out.extend_from_slice(node.data);
+            for (link, _) in &node.links {
+                append_file(block, link, out)?;
+            }
+        }
+        codec => {
+            return UnsupportedSnafu {
+                what: format!("codec {codec:#x}"),
+            }
+            .fail();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = vec![];
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn field(number: u64, bytes: &[u8]) -> Vec<u8> {
+        [
+            varint((number << 3) | 2),
+            varint(bytes.len() as u64),
+            bytes.to_vec(),
+        ]
+        .concat()
+    }
+
+    fn cid(codec: u64, block: &[u8]) -> Vec<u8> {
+        [
+            vec![1],
+            varint(codec),
+            vec![0x12, 32],
+            Sha256::digest(block).to_vec(),
+        ]
+        .concat()
+    }
+
+    fn base32(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+        let mut out = "b".to_string();
+        let (mut buffer, mut bits) = (0u32, 0);
+        for &byte in bytes {
+            buffer = (buffer << 8) | u32::from(byte);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(ALPHABET[(buffer >> bits) as usize & 31] as char);
+            }
+        }
+        if bits > 0 {
+            out.push(ALPHABET[(buffer << (5 - bits)) as usize & 31] as char);
+        }
+        out
+    }
+
+    fn car(blocks: &[(&[u8], &[u8])]) -> Vec<u8> {
+        let mut car = [varint(1), vec![0xa0]].concat();
+        for (cid, block) in blocks {
+            car.extend(varint((cid.len() + block.len()) as u64));
+            car.extend_from_slice(cid);
+            car.extend_from_slice(block);
+        }
+        car
+    }
+
+    /// A dag-pb node of UnixFS type `kind` linking to `links`.
+    fn node(kind: u64, links: &[(&[u8], &str)]) -> Vec<u8> {
+        let mut node = vec![];
+        for (cid, name) in links {
+            node.extend(field(
+                2,
+                &[field(1, cid), field(2, name.as_bytes())].concat(),
+            ));
+        }
+        node.extend(field(1, &[vec![1 << 3], varint(kind)].concat()));
+        node
+    }
+
+    #[test]
+    fn test_decode_cid() {
+        // both name "hello world"
+        let cid = Cid::parse("QmaozNR7DZHQK1ZcU9p7QdrshMvXqWK6gpu5rmrkPdT3L4").unwrap();
+        assert_eq!(cid.codec, CODEC_DAG_PB);
+        assert!(cid.matches(b"hello world").unwrap());
+        let cid =
+            Cid::parse("bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e").unwrap();
+        assert_eq!(cid.codec, CODEC_RAW);
+        assert!(cid.matches(b"hello world").unwrap());
+        assert!(!cid.matches(b"hello").unwrap());
+        assert!(Cid::parse("bnot-base32").is_err());
+        assert!(Cid::parse("fabc").is_err());
+    }
+
+    #[test]
+    fn test_content() {
+        let (hel, lo) = (b"hel".as_slice(), b"lo".as_slice());
+        let (hel_cid, lo_cid) = (cid(CODEC_RAW, hel), cid(CODEC_RAW, lo));
+        let file = node(UNIXFS_FILE, &[(&hel_cid[..], ""), (&lo_cid[..], "")]);
+        let file_cid = cid(CODEC_DAG_PB, &file);
+        let dir = node(UNIXFS_DIRECTORY, &[(&file_cid[..], "mod.pak")]);
+        let dir_cid = cid(CODEC_DAG_PB, &dir);
+        let blocks = [
+            (&dir_cid[..], &dir[..]),
+            (&file_cid[..], &file[..]),
+            (&hel_cid[..], hel),
+            (&lo_cid[..], lo),
+        ];
+
+        let root = base32(&dir_cid);
+        assert_eq!(content(&car(&blocks), &root, "/mod.pak").unwrap(), b"hello");
+        assert_eq!(
+            content(&car(&blocks), &base32(&file_cid), "").unwrap(),
+            b"hello"
+        );
+        assert!(matches!(
+            content(&car(&blocks), &root, "/other.pak"),
+            Err(CarError::NotFound { .. })
+        ));
+        assert!(matches!(
+            content(&car(&blocks), &root, ""),
+            Err(CarError::NotAFile)
+        ));
+        assert!(matches!(
+            content(&car(&blocks[..3]), &root, "/mod.pak"),
+            Err(CarError::MissingBlock)
+        ));
+
+        let tampered = [blocks[0], blocks[1], blocks[2], (&lo_cid[..], &b"LO"[..])];
+        assert!(matches!(
+            content(&car(&tampered), &root, "/mod.pak"),
+            Err(CarError::HashMismatch)
+        ));
+    }
+}
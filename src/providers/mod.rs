@@ -1,12 +1,17 @@
 pub mod file;
 pub mod http;
+pub mod ipfs;
 pub mod modio;
+#[cfg(feature = "torrent")]
+pub mod torrent;
 #[macro_use]
 pub mod cache;
+pub mod client;
 pub mod mod_store;
+pub mod priority;
 
 use snafu::prelude::*;
-use tokio::sync::mpsc::Sender;
+use tokio::sync::{mpsc::Sender, watch};
 
 use std::collections::HashMap;
 use std::io::{Read, Seek};
@@ -45,6 +50,42 @@ impl FetchProgress {
     }
 }
 
+/// Holds a long-running operation between items, such as between mod downloads, until resumed.
+/// Items already started finish first.
+#[derive(Debug, Clone)]
+pub struct Pause(Arc<watch::Sender<bool>>);
+
+impl Default for Pause {
+    fn default() -> Self {
+        Self(Arc::new(watch::channel(false).0))
+    }
+}
+
+impl Pause {
+    pub fn set(&self, paused: bool) {
+        self.0.send_replace(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Wait until not paused.
+    pub async fn wait(&self) {
+        let mut rx = self.0.subscribe();
+        // cannot fail, the sender is kept by `self`
+        let _ = rx.wait_for(|paused| !paused).await;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadSize {
+    /// Already cached or stored locally.
+    Cached,
+    /// Needs downloading, with the size in bytes if the provider reports it up front.
+    Download(Option<u64>),
+}
+
 #[async_trait::async_trait]
 pub trait ModProvider: Send + Sync {
     async fn resolve_mod(
@@ -61,12 +102,38 @@ pub trait ModProvider: Send + Sync {
         blob_cache: &BlobCache,
         tx: Option<Sender<FetchProgress>>,
     ) -> Result<PathBuf, ProviderError>;
-    async fn update_cache(&self, cache: ProviderCache) -> Result<(), ProviderError>;
+    /// How much fetching `res` would download, without downloading it.
+    async fn download_size(
+        &self,
+        res: &ModResolution,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError>;
+    /// Update the cached mods, holding between mods while `pause` is set.
+    async fn update_cache(&self, cache: ProviderCache, pause: &Pause) -> Result<(), ProviderError>;
     /// Check if provider is configured correctly
     async fn check(&self) -> Result<(), ProviderError>;
     fn get_mod_info(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<ModInfo>;
     fn is_pinned(&self, spec: &ModSpecification, cache: ProviderCache) -> bool;
     fn get_version_name(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<String>;
+    /// Each version of the mod as a spec pinning it and when it was released, as a unix
+    /// timestamp. `None` for providers which keep no version history.
+    fn version_dates(
+        &self,
+        _spec: &ModSpecification,
+        _cache: ProviderCache,
+    ) -> Option<Vec<(ModSpecification, u64)>> {
+        None
+    }
+    /// URL of a small image identifying the mod, for providers which have one.
+    fn thumbnail_url(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
+        None
+    }
+    /// Contents of the detached signature of the fetched `res`, see [`crate::signing`]. Missing
+    /// signatures are not an error.
+    async fn fetch_signature(&self, _res: &ModResolution) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 #[derive(Debug, Snafu)]
@@ -110,6 +177,32 @@ pub enum ProviderError {
     },
     #[snafu(display("error while fetching mod <{url}>"))]
     FetchError { source: reqwest::Error, url: String },
+    #[snafu(display("link <{url}> has expired"))]
+    LinkExpired { url: String },
+    #[snafu(display("<{url}> refused access ({status})"))]
+    Unauthorized {
+        url: String,
+        status: reqwest::StatusCode,
+    },
+    #[snafu(display(
+        "a login portal answered in place of {url}, you appear to be on a network which requires logging in"
+    ))]
+    CaptivePortal { url: String },
+    #[cfg(feature = "torrent")]
+    #[snafu(display("torrent <{url}> could not be downloaded: {source}"))]
+    TorrentError { source: anyhow::Error, url: String },
+    #[snafu(display("downloaded archive of <{url}> did not pass the scan: {source}"))]
+    ScanFailed {
+        source: crate::scan::ScanError,
+        url: String,
+    },
+    #[snafu(display("the signature of <{url}> does not match the downloaded archive"))]
+    SignatureMismatch { url: String },
+    #[snafu(display("content of <{url}> from the IPFS gateway does not match its CID: {source}"))]
+    ContentMismatch {
+        source: ipfs::car::CarError,
+        url: String,
+    },
     #[snafu(display("error processing <{url}> while writing to local buffer"))]
     BufferIoError { source: std::io::Error, url: String },
     #[snafu(display(
@@ -133,6 +226,62 @@ impl ProviderError {
             _ => None,
         }
     }
+
+    /// Whether the error shows that a login portal answered in place of the provider, as on hotel
+    /// or airport Wi-Fi before logging in: an HTML page came back where JSON or an archive was
+    /// expected.
+    pub fn is_captive_portal(&self) -> bool {
+        if let ProviderError::UnexpectedContentType {
+            found_content_type, ..
+        } = self
+        {
+            return found_content_type.starts_with("text/html");
+        }
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(error) = source {
+            if let Some(error) = error.downcast_ref::<serde_json::Error>()
+                && is_not_json(error)
+            {
+                return true;
+            }
+            source = error.source();
+        }
+        false
+    }
+
+    /// Whether the error may stem from a login portal answering in place of the provider without
+    /// showing it, see [`Self::is_captive_portal`].
+    pub fn may_be_captive_portal(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::RequestFailed { .. }
+                | ProviderError::ResponseError { .. }
+                | ProviderError::FetchError { .. }
+                | ProviderError::UnexpectedContentType { .. }
+                | ProviderError::ModCtxtModioError { .. }
+                | ProviderError::DrgModioError {
+                    source: DrgModioError::GenericModioError { .. }
+                        | DrgModioError::CheckFailed { .. }
+                        | DrgModioError::FetchModFilesFailed { .. }
+                        | DrgModioError::FetchModFileFailed { .. }
+                        | DrgModioError::FetchModFailed { .. }
+                        | DrgModioError::FetchDependenciesFailed { .. }
+                }
+        )
+    }
+
+    /// Whether the error stems from a provider not being configured or rejecting its
+    /// credentials, as opposed to a failure fetching or processing a particular mod.
+    pub fn is_auth_failure(&self) -> bool {
+        matches!(
+            self,
+            ProviderError::InitProviderFailed { .. }
+                | ProviderError::NoProvider { .. }
+                | ProviderError::DrgModioError {
+                    source: DrgModioError::MissingOauthToken | DrgModioError::CheckFailed { .. }
+                }
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -159,6 +308,66 @@ pub struct ProviderParameter<'a> {
     pub name: &'a str,
     pub description: &'a str,
     pub link: Option<&'a str>,
+    /// Gets the value by logging in with a code sent by email, instead of it being pasted.
+    pub email_login: Option<EmailLogin>,
+    /// The provider works without it. Optional parameters are settings rather than credentials
+    /// and are edited as plain text which may span several lines.
+    pub optional: bool,
+}
+
+/// A login which emails a security code to the user, which is then exchanged for the value of a
+/// [`ProviderParameter`], such as an OAuth token.
+#[derive(Debug, Clone, Copy)]
+pub struct EmailLogin {
+    /// Send a code to the email address.
+    pub request_code: fn(String) -> futures::future::BoxFuture<'static, Result<(), ProviderError>>,
+    /// Exchange the code for the value.
+    pub exchange_code:
+        fn(String) -> futures::future::BoxFuture<'static, Result<String, ProviderError>>,
 }
 
 inventory::collect!(ProviderFactory);
+
+/// Whether `error` is from parsing a body which does not start with a JSON value, like a page
+/// starting with `<`, rather than from JSON of the wrong shape.
+fn is_not_json(error: &serde_json::Error) -> bool {
+    error.is_syntax() && error.column() == 1 && error.to_string().starts_with("expected value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_captive_portal() {
+        let parse = |body: &str| serde_json::from_str::<serde_json::Value>(body).unwrap_err();
+        assert!(is_not_json(&parse("<!DOCTYPE html><html></html>")));
+        assert!(is_not_json(&parse("\n\n<html></html>")));
+        assert!(!is_not_json(&parse(r#"{"mods": }"#)));
+        assert!(!is_not_json(&parse(r#"{"mods": [1, 2"#)));
+
+        let content_type = |found: &str| ProviderError::UnexpectedContentType {
+            found_content_type: found.to_string(),
+            url: "https://example.com/mod.zip".to_string(),
+        };
+        assert!(content_type("text/html; charset=utf-8").is_captive_portal());
+        assert!(!content_type("application/json").is_captive_portal());
+    }
+
+    #[tokio::test]
+    async fn test_pause() {
+        let pause = Pause::default();
+        pause.wait().await;
+
+        pause.set(true);
+        let waiting = tokio::spawn({
+            let pause = pause.clone();
+            async move { pause.wait().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        pause.set(false);
+        waiting.await.unwrap();
+    }
+}
@@ -1,27 +1,167 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 use tracing::*;
 
 use crate::providers::*;
+use crate::scan::Scanner;
+use crate::signing::{SignatureStatus, TrustedSigner};
 use crate::state::config::ConfigWrapper;
 
+/// What fetching a set of mods would download.
+#[derive(Debug, Default, Clone)]
+pub struct DownloadEstimate {
+    /// Mods which are already cached or stored locally.
+    pub cached: usize,
+    /// Mods to download, by provider ID.
+    pub providers: BTreeMap<&'static str, ProviderDownload>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProviderDownload {
+    pub mods: usize,
+    /// Total size of the mods with a known size.
+    pub bytes: u64,
+    /// Mods whose size is not known until downloaded.
+    pub unknown_size: usize,
+}
+
+impl DownloadEstimate {
+    pub fn to_download(&self) -> usize {
+        self.providers.values().map(|p| p.mods).sum()
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.providers.values().map(|p| p.bytes).sum()
+    }
+
+    pub fn unknown_size(&self) -> usize {
+        self.providers.values().map(|p| p.unknown_size).sum()
+    }
+}
+
+/// Metadata set by the user for a mod whose provider has none, such as a local file or a plain
+/// URL, so it can be named, tagged and depend on other mods like a mod.io mod.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<LocalDependency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalDependency {
+    pub spec: ModSpecification,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<VersionConstraint>,
+}
+
+impl LocalMetadata {
+    /// Providers whose mods can be given local metadata.
+    pub const PROVIDERS: &[&str] = &["file", "http"];
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    fn apply(&self, info: &mut ModInfo) {
+        if let Some(name) = &self.name {
+            info.name = name.clone();
+        }
+        info.local_tags = self.tags.clone();
+        info.suggested_dependencies = self.dependencies.iter().map(|d| d.spec.clone()).collect();
+        info.dependency_constraints = self
+            .dependencies
+            .iter()
+            .filter_map(|d| Some((d.spec.clone(), d.constraint?)))
+            .collect();
+    }
+}
+
 pub struct ModStore {
     providers: Providers,
     cache: ProviderCache,
     blob_cache: BlobCache,
+    /// Keyed by mod URL, see [`LocalMetadata`].
+    local_metadata: RwLock<BTreeMap<String, LocalMetadata>>,
+    trusted_signers: RwLock<Vec<TrustedSigner>>,
+    /// Signature checks of the mods fetched so far, keyed by resolution URL.
+    signatures: RwLock<HashMap<String, SignatureStatus>>,
+    cache_dir: PathBuf,
+    scanner: RwLock<Option<Arc<Scanner>>>,
+    /// How many mods are downloaded at once.
+    download_concurrency: AtomicUsize,
 }
 
+/// Default of how many mods are downloaded at once.
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 5;
+
+/// Directory inside the cache directory holding downloaded thumbnails.
+const THUMBNAIL_DIR: &str = "thumbnails";
+
 impl ModStore {
     pub fn new<P: AsRef<Path>>(
         cache_path: P,
         parameters: &HashMap<String, HashMap<String, String>>,
     ) -> Result<Self, ProviderError> {
+        let providers = Self::create_providers(parameters)?;
+
+        let cache_metadata_path = cache_path.as_ref().join("cache.json");
+
+        let cache = read_cache_metadata_or_default(&cache_metadata_path)?;
+        let cache = ConfigWrapper::new(&cache_metadata_path, cache);
+        cache.save().unwrap();
+
+        Ok(Self::with_cache(cache_path.as_ref(), providers, cache))
+    }
+
+    /// Store without any providers whose cache is only kept in memory, for `--safe-mode`. Mods
+    /// already in `cache` can be looked up, but none are resolved or downloaded.
+    pub fn offline<P: AsRef<Path>>(cache_path: P, cache: VersionAnnotatedCache) -> Self {
+        Self::with_cache(
+            cache_path.as_ref(),
+            HashMap::new(),
+            ConfigWrapper::memory(cache),
+        )
+    }
+
+    fn with_cache(
+        cache_path: &Path,
+        providers: HashMap<&'static str, Arc<dyn ModProvider>>,
+        cache: ConfigWrapper<VersionAnnotatedCache>,
+    ) -> Self {
+        Self {
+            providers: RwLock::new(providers),
+            cache: Arc::new(RwLock::new(cache)),
+            blob_cache: BlobCache::new(cache_path.join("blobs")),
+            local_metadata: Default::default(),
+            trusted_signers: Default::default(),
+            signatures: Default::default(),
+            cache_dir: cache_path.to_path_buf(),
+            scanner: Default::default(),
+            download_concurrency: AtomicUsize::new(DEFAULT_DOWNLOAD_CONCURRENCY),
+        }
+    }
+
+    /// The providers whose required parameters are all in `parameters`, by ID.
+    fn create_providers(
+        parameters: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<HashMap<&'static str, Arc<dyn ModProvider>>, ProviderError> {
         let mut providers = HashMap::new();
         for prov in Self::get_provider_factories() {
             let params = parameters.get(prov.id).cloned().unwrap_or_default();
-            if prov.parameters.iter().all(|p| params.contains_key(p.id)) {
+            if prov
+                .parameters
+                .iter()
+                .all(|p| p.optional || params.contains_key(p.id))
+            {
                 let Ok(provider) = (prov.new)(&params) else {
                     return Err(ProviderError::InitProviderFailed {
                         id: prov.id,
@@ -31,18 +171,61 @@ impl ModStore {
                 providers.insert(prov.id, provider);
             }
         }
+        Ok(providers)
+    }
 
-        let cache_metadata_path = cache_path.as_ref().join("cache.json");
+    /// Create the providers again, e.g. to use a newly configured [`client`]. Operations already
+    /// running keep the providers they started with.
+    pub fn reload_providers(
+        &self,
+        parameters: &HashMap<String, HashMap<String, String>>,
+    ) -> Result<(), ProviderError> {
+        *self.providers.write().unwrap() = Self::create_providers(parameters)?;
+        Ok(())
+    }
 
-        let cache = read_cache_metadata_or_default(&cache_metadata_path)?;
-        let cache = ConfigWrapper::new(&cache_metadata_path, cache);
-        cache.save().unwrap();
+    /// Replace the metadata set by the user, applied to the mods of [`LocalMetadata::PROVIDERS`]
+    /// when they are resolved or looked up.
+    pub fn set_local_metadata(&self, metadata: BTreeMap<String, LocalMetadata>) {
+        *self.local_metadata.write().unwrap() = metadata;
+    }
 
-        Ok(Self {
-            providers: RwLock::new(providers),
-            cache: Arc::new(RwLock::new(cache)),
-            blob_cache: BlobCache::new(cache_path.as_ref().join("blobs")),
-        })
+    /// Replace the signers whose signatures are trusted. Mods fetched before are checked again
+    /// when next fetched.
+    pub fn set_trusted_signers(&self, signers: Vec<TrustedSigner>) {
+        *self.trusted_signers.write().unwrap() = signers;
+        self.signatures.write().unwrap().clear();
+    }
+
+    /// Set the command downloaded archives are scanned with before they can be installed, see
+    /// [`crate::scan`]. Empty or `None` turns scanning off.
+    pub fn set_scan_command(&self, command: Option<&str>) {
+        *self.scanner.write().unwrap() = command
+            .and_then(|c| Scanner::new(c, &self.cache_dir))
+            .map(Arc::new);
+    }
+
+    pub fn set_download_concurrency(&self, concurrency: usize) {
+        self.download_concurrency
+            .store(concurrency.max(1), Ordering::Relaxed);
+    }
+
+    fn download_concurrency(&self) -> usize {
+        self.download_concurrency.load(Ordering::Relaxed)
+    }
+
+    /// Result of checking the signature of the mod fetched from `url`, if fetched yet.
+    pub fn signature_status(&self, url: &str) -> Option<SignatureStatus> {
+        self.signatures.read().unwrap().get(url).cloned()
+    }
+
+    fn apply_local_metadata(&self, mut info: ModInfo) -> ModInfo {
+        if LocalMetadata::PROVIDERS.contains(&info.provider)
+            && let Some(metadata) = self.local_metadata.read().unwrap().get(&info.spec.url)
+        {
+            metadata.apply(&mut info);
+        }
+        info
     }
 
     pub fn get_provider_factories() -> impl Iterator<Item = &'static ProviderFactory> {
@@ -68,7 +251,9 @@ impl ModStore {
         parameters: &HashMap<String, String>,
     ) -> Result<(), ProviderError> {
         let provider = (provider_factory.new)(parameters)?;
-        provider.check().await?;
+        if let Err(e) = provider.check().await {
+            return Err(PortalVerdict::default().check(e, provider_factory.id));
+        }
         self.providers
             .write()
             .unwrap()
@@ -102,6 +287,7 @@ impl ModStore {
 
         let mut to_resolve = mods.iter().cloned().collect::<HashSet<ModSpecification>>();
         let mut mods_map = HashMap::new();
+        let portal = PortalVerdict::default();
 
         // used to deduplicate dependencies from mods already present in the mod list
         let mut precise_mod_specs = HashSet::new();
@@ -110,7 +296,7 @@ impl ModStore {
             for (u, m) in stream::iter(
                 to_resolve
                     .iter()
-                    .map(|u| self.resolve_mod(u.to_owned(), update)),
+                    .map(|u| self.resolve_mod_in(u.to_owned(), update, &portal)),
             )
             .boxed()
             .buffer_unordered(5)
@@ -137,18 +323,30 @@ impl ModStore {
         &self,
         original_spec: ModSpecification,
         update: bool,
+    ) -> Result<(ModSpecification, ModInfo), ProviderError> {
+        self.resolve_mod_in(original_spec, update, &PortalVerdict::default())
+            .await
+    }
+
+    /// [`Self::resolve_mod`] as part of the batch `portal` is the verdict of.
+    async fn resolve_mod_in(
+        &self,
+        original_spec: ModSpecification,
+        update: bool,
+        portal: &PortalVerdict,
     ) -> Result<(ModSpecification, ModInfo), ProviderError> {
         let mut spec = original_spec.clone();
         loop {
-            match self
+            let response = self
                 .get_provider(&spec.url)?
                 .resolve_mod(&spec, update, self.cache.clone())
-                .await?
-            {
-                ModResponse::Resolve(m) => {
-                    return Ok((original_spec, m));
+                .await;
+            match response {
+                Err(e) => return Err(portal.check(e, &spec.url)),
+                Ok(ModResponse::Resolve(m)) => {
+                    return Ok((original_spec, self.apply_local_metadata(m)));
                 }
-                ModResponse::Redirect(redirected_spec) => spec = redirected_spec,
+                Ok(ModResponse::Redirect(redirected_spec)) => spec = redirected_spec,
             };
         }
     }
@@ -161,30 +359,40 @@ impl ModStore {
     ) -> Result<Vec<PathBuf>, ProviderError> {
         use futures::stream::{self, StreamExt, TryStreamExt};
 
+        let portal = PortalVerdict::default();
         stream::iter(
             mods.iter()
-                .map(|res| self.fetch_mod(res, update, tx.clone())),
+                .map(|res| self.fetch_mod_in(res, update, tx.clone(), &portal)),
         )
         .boxed() // without this the future becomes !Send https://github.com/rust-lang/rust/issues/104382
-        .buffer_unordered(5)
+        .buffer_unordered(self.download_concurrency())
         .try_collect::<Vec<_>>()
         .await
     }
 
+    /// Fetch `mods` in order, holding the mods not started yet while `pause` is set.
     pub async fn fetch_mods_ordered(
         &self,
         mods: &[&ModResolution],
         update: bool,
         tx: Option<Sender<FetchProgress>>,
+        pause: Option<&Pause>,
     ) -> Result<Vec<PathBuf>, ProviderError> {
         use futures::stream::{self, StreamExt, TryStreamExt};
 
-        stream::iter(
-            mods.iter()
-                .map(|res| self.fetch_mod(res, update, tx.clone())),
-        )
+        let portal = PortalVerdict::default();
+        let portal = &portal;
+        stream::iter(mods.iter().map(|res| {
+            let tx = tx.clone();
+            async move {
+                if let Some(pause) = pause {
+                    pause.wait().await;
+                }
+                self.fetch_mod_in(res, update, tx, portal).await
+            }
+        }))
         .boxed() // without this the future becomes !Send https://github.com/rust-lang/rust/issues/104382
-        .buffered(5)
+        .buffered(self.download_concurrency())
         .try_collect::<Vec<_>>()
         .await
     }
@@ -195,7 +403,20 @@ impl ModStore {
         update: bool,
         tx: Option<Sender<FetchProgress>>,
     ) -> Result<PathBuf, ProviderError> {
-        self.get_provider(&res.url.0)?
+        self.fetch_mod_in(res, update, tx, &PortalVerdict::default())
+            .await
+    }
+
+    /// [`Self::fetch_mod`] as part of the batch `portal` is the verdict of.
+    async fn fetch_mod_in(
+        &self,
+        res: &ModResolution,
+        update: bool,
+        tx: Option<Sender<FetchProgress>>,
+        portal: &PortalVerdict,
+    ) -> Result<PathBuf, ProviderError> {
+        let provider = self.get_provider(&res.url.0)?;
+        let path = match provider
             .fetch_mod(
                 res,
                 update,
@@ -204,13 +425,84 @@ impl ModStore {
                 tx,
             )
             .await
+        {
+            Ok(path) => path,
+            Err(e) => return Err(portal.check(e, &res.url.0)),
+        };
+        let scanner = self.scanner.read().unwrap().clone();
+        if let Some(scanner) = scanner
+            && self.blob_cache.contains(&path)
+        {
+            scanner
+                .scan(&path)
+                .await
+                .context(ScanFailedSnafu { url: &res.url.0 })?;
+        }
+        let checked = self.signatures.read().unwrap().contains_key(&res.url.0);
+        if update || !checked {
+            let status = match provider.fetch_signature(res).await {
+                Some(signature) => {
+                    let data = fs::read(&path).context(BufferIoSnafu { url: &res.url.0 })?;
+                    crate::signing::verify(
+                        &data,
+                        Some(&signature),
+                        &self.trusted_signers.read().unwrap(),
+                    )
+                }
+                None => SignatureStatus::Unsigned,
+            };
+            self.signatures
+                .write()
+                .unwrap()
+                .insert(res.url.0.clone(), status);
+        }
+        Ok(path)
     }
 
-    pub async fn update_cache(&self) -> Result<(), ProviderError> {
+    pub async fn download_size(&self, res: &ModResolution) -> Result<DownloadSize, ProviderError> {
+        self.get_provider(&res.url.0)?
+            .download_size(res, self.cache.clone(), &self.blob_cache)
+            .await
+    }
+
+    /// How much fetching `mods` would download, without downloading anything.
+    pub async fn estimate_download(
+        &self,
+        mods: &[&ModInfo],
+    ) -> Result<DownloadEstimate, ProviderError> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        let sizes = stream::iter(mods.iter().map(|m| self.download_size(&m.resolution)))
+            .boxed()
+            .buffered(5)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut estimate = DownloadEstimate::default();
+        for (info, size) in mods.iter().zip(sizes) {
+            match size {
+                DownloadSize::Cached => estimate.cached += 1,
+                DownloadSize::Download(size) => {
+                    let provider = estimate.providers.entry(info.provider).or_default();
+                    provider.mods += 1;
+                    match size {
+                        Some(size) => provider.bytes += size,
+                        None => provider.unknown_size += 1,
+                    }
+                }
+            }
+        }
+        Ok(estimate)
+    }
+
+    pub async fn update_cache(&self, pause: &Pause) -> Result<(), ProviderError> {
         let providers = self.providers.read().unwrap().clone();
         for (name, provider) in providers.iter() {
+            pause.wait().await;
             info!("updating cache for {name} provider");
-            provider.update_cache(self.cache.clone()).await?;
+            if let Err(e) = provider.update_cache(self.cache.clone(), pause).await {
+                return Err(PortalVerdict::default().check(e, name));
+            }
         }
         Ok(())
     }
@@ -219,6 +511,7 @@ impl ModStore {
         self.get_provider(&spec.url)
             .ok()?
             .get_mod_info(spec, self.cache.clone())
+            .map(|info| self.apply_local_metadata(info))
     }
 
     pub fn is_pinned(&self, spec: &ModSpecification) -> bool {
@@ -232,4 +525,89 @@ impl ModStore {
             .unwrap()
             .get_version_name(spec, self.cache.clone())
     }
+
+    pub fn version_dates(&self, spec: &ModSpecification) -> Option<Vec<(ModSpecification, u64)>> {
+        self.get_provider(&spec.url)
+            .ok()?
+            .version_dates(spec, self.cache.clone())
+    }
+
+    /// URL of the thumbnail of `spec`, see [`ModProvider::thumbnail_url`].
+    pub fn thumbnail_url(&self, spec: &ModSpecification) -> Option<String> {
+        self.get_provider(&spec.url)
+            .ok()?
+            .thumbnail_url(spec, self.cache.clone())
+    }
+
+    /// The image at thumbnail `url`, downloaded once and read from the cache directory after.
+    pub async fn fetch_thumbnail(&self, url: &str) -> Result<Vec<u8>, ProviderError> {
+        use sha2::{Digest, Sha256};
+
+        let path = self
+            .cache_dir
+            .join(THUMBNAIL_DIR)
+            .join(hex::encode(Sha256::digest(url)));
+        if let Ok(data) = fs::read(&path) {
+            return Ok(data);
+        }
+
+        let data = client::client()
+            .get(url)
+            .send()
+            .await
+            .context(RequestFailedSnafu { url })?
+            .error_for_status()
+            .context(ResponseSnafu { url })?
+            .bytes()
+            .await
+            .context(FetchSnafu { url })?
+            .to_vec();
+        let dir = path.parent().unwrap();
+        if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&path, &data)) {
+            warn!("failed to cache thumbnail {url}: {e}");
+        }
+        Ok(data)
+    }
+
+    /// Size in bytes of the cached metadata of each provider, for diagnostics.
+    pub fn cache_summary(&self) -> BTreeMap<String, usize> {
+        let cache = self.cache.read().unwrap();
+        cache
+            .cache
+            .iter()
+            .map(|(id, c)| {
+                let size = serde_json::to_vec(c).map(|v| v.len()).unwrap_or_default();
+                (id.clone(), size)
+            })
+            .collect()
+    }
+}
+
+/// Whether a login portal answered any request of a batch, such as resolving or fetching a list
+/// of mods. Once one failed request shows it, the other network failures of the batch are put down
+/// to it as well, as they need not show it themselves, e.g. when the portal broke TLS.
+#[derive(Default)]
+struct PortalVerdict(AtomicBool);
+
+impl PortalVerdict {
+    /// `error`, or [`ProviderError::CaptivePortal`] if a login portal answered the request for
+    /// `url`, or to the provider it names, which caused it. The user is then told to log in
+    /// rather than shown e.g. a JSON parse error.
+    fn check(&self, error: ProviderError, url: &str) -> ProviderError {
+        let portal = if error.is_captive_portal() {
+            if !self.0.swap(true, Ordering::Relaxed) {
+                warn!("login portal detected, failed with: {error}");
+            }
+            true
+        } else {
+            error.may_be_captive_portal() && self.0.load(Ordering::Relaxed)
+        };
+        if portal {
+            ProviderError::CaptivePortal {
+                url: url.to_string(),
+            }
+        } else {
+            error
+        }
+    }
 }
@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use task_local_extensions::Extensions;
 use tracing::*;
 
+use crate::providers::priority::{self, MetadataCall};
 use crate::providers::*;
 
 static RE_MOD: OnceLock<regex::Regex> = OnceLock::new();
@@ -57,11 +58,84 @@ inventory::submit! {
                 name: "OAuth Token",
                 description: "mod.io OAuth token",
                 link: Some("https://mod.io/me/access"),
+                email_login: EMAIL_LOGIN,
+                optional: false,
             },
         ]
     }
 }
 
+/// API key of mint's mod.io app, set when building mint. Logging in by email needs one, without it
+/// the OAuth token has to be created on mod.io and pasted.
+const MODIO_API_KEY: Option<&str> = option_env!("MINT_MODIO_API_KEY");
+const MODIO_API_URL: &str = "https://api.mod.io/v1";
+
+const EMAIL_LOGIN: Option<super::EmailLogin> = match MODIO_API_KEY {
+    Some(_) => Some(super::EmailLogin {
+        request_code: |email| Box::pin(request_email_code(email)),
+        exchange_code: |code| Box::pin(exchange_email_code(code)),
+    }),
+    None => None,
+};
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    error: ApiErrorMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorMessage {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+/// The response of a login endpoint, or the message mod.io refused the login with.
+fn login_response<T: serde::de::DeserializeOwned>(
+    status: reqwest::StatusCode,
+    body: &[u8],
+) -> Result<T, DrgModioError> {
+    if !status.is_success() {
+        let message = serde_json::from_slice::<ApiError>(body)
+            .map(|e| e.error.message)
+            .unwrap_or_else(|_| status.to_string());
+        return LoginRefusedSnafu { message }.fail();
+    }
+    serde_json::from_slice(body).context(InvalidLoginResponseSnafu)
+}
+
+/// Post `form` to the OAuth `endpoint` with mint's API key.
+async fn login_request<T: serde::de::DeserializeOwned>(
+    endpoint: &str,
+    form: (&str, &str),
+) -> Result<T, DrgModioError> {
+    let api_key = MODIO_API_KEY.context(LoginUnavailableSnafu)?;
+    let response = client::client()
+        .post(format!("{MODIO_API_URL}/oauth/{endpoint}"))
+        .form(&[("api_key", api_key), form])
+        .send()
+        .await
+        .context(LoginRequestFailedSnafu)?;
+    let status = response.status();
+    let body = response.bytes().await.context(LoginRequestFailedSnafu)?;
+    login_response(status, &body)
+}
+
+/// Have mod.io email a security code to `email`, see [`exchange_email_code`].
+async fn request_email_code(email: String) -> Result<(), ProviderError> {
+    login_request::<serde::de::IgnoredAny>("emailrequest", ("email", email.as_str())).await?;
+    Ok(())
+}
+
+/// Exchange the security code from the email for an OAuth token.
+async fn exchange_email_code(code: String) -> Result<String, ProviderError> {
+    let token: AccessToken = login_request("emailexchange", ("security_code", code.trim())).await?;
+    Ok(token.access_token)
+}
+
 fn format_spec(name_id: &str, mod_id: u32, file_id: Option<u32>) -> ModSpecification {
     ModSpecification::new(if let Some(file_id) = file_id {
         format!("https://mod.io/g/drg/m/{name_id}#{mod_id}/{file_id}")
@@ -85,6 +159,60 @@ impl<M: DrgModio + 'static> ModioProvider<M> {
     }
 }
 
+impl<M: DrgModio> ModioProvider<M> {
+    /// Download `file` through the modio client, so the OAuth token and its rate limit handling
+    /// apply. The client can't make Range requests, so if the connection drops the download starts
+    /// over and skips what was already read.
+    async fn download_file(
+        &self,
+        res: &ModResolution,
+        mod_id: u32,
+        file: modio::files::File,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        use futures::stream::TryStreamExt;
+
+        let size = file.filesize;
+        let mut data = vec![];
+        let mut resumes = 0;
+        let mut download = priority::Download::start();
+        loop {
+            let mut skip = data.len();
+            let mut stream = Box::pin(self.modio.download(file.clone()).stream());
+            let err = loop {
+                download.yield_to_metadata().await;
+                match stream.try_next().await {
+                    Ok(Some(bytes)) => {
+                        let skipped = skip.min(bytes.len());
+                        skip -= skipped;
+                        data.extend_from_slice(&bytes[skipped..]);
+                        if let Some(tx) = tx {
+                            tx.send(FetchProgress::Progress {
+                                resolution: res.clone(),
+                                progress: data.len() as u64,
+                                size,
+                            })
+                            .await
+                            .unwrap();
+                        }
+                    }
+                    Ok(None) => return Ok(data),
+                    Err(e) => break e,
+                }
+            };
+            if resumes >= client::MAX_RESUMES {
+                return Err(err).context(ModCtxtModioSnafu { mod_id });
+            }
+            resumes += 1;
+            warn!(
+                "download of <{}> interrupted after {} bytes, restarting: {err}",
+                res.url.0,
+                data.len()
+            );
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModioCache {
     mod_id_map: HashMap<String, u32>,
@@ -126,6 +254,8 @@ pub struct ModioMod {
     latest_modfile: Option<u32>,
     modfiles: Vec<ModioFile>,
     tags: HashSet<String>,
+    /// URL of the 320x180 thumbnail of the mod's logo.
+    logo: Option<String>,
 }
 
 impl ModioMod {
@@ -136,6 +266,7 @@ impl ModioMod {
             latest_modfile: mod_.modfile.map(|f| f.id),
             modfiles: files.into_iter().map(ModioFile::new).collect(),
             tags: mod_.tags.into_iter().map(|t| t.name).collect(),
+            logo: Some(mod_.logo.thumb_320x180.to_string()),
         }
     }
 }
@@ -242,6 +373,14 @@ pub enum DrgModioError {
     },
     #[snafu(display("encountered mod.io-related error: {msg}"))]
     GenericError { msg: &'static str },
+    #[snafu(display("this build of mint cannot log in to mod.io by email"))]
+    LoginUnavailable,
+    #[snafu(display("mod.io login request failed: {source}"))]
+    LoginRequestFailed { source: reqwest::Error },
+    #[snafu(display("mod.io refused the login: {message}"))]
+    LoginRefused { message: String },
+    #[snafu(display("unexpected mod.io login response: {source}"))]
+    InvalidLoginResponse { source: serde_json::Error },
 }
 
 impl DrgModioError {
@@ -294,7 +433,7 @@ pub trait DrgModio: Sync + Send {
 #[async_trait::async_trait]
 impl DrgModio for modio::Modio {
     fn with_parameters(parameters: &HashMap<String, String>) -> Result<Self, DrgModioError> {
-        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        let client = reqwest_middleware::ClientBuilder::new(client::client())
             .with::<LoggingMiddleware>(Default::default())
             .build();
         let modio = modio::Modio::new(
@@ -310,6 +449,7 @@ impl DrgModio for modio::Modio {
     }
 
     async fn check(&self) -> Result<(), DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::Eq;
         use modio::mods::filters::Id;
 
@@ -323,6 +463,7 @@ impl DrgModio for modio::Modio {
     }
 
     async fn fetch_mod(&self, url: String, id: u32) -> Result<ModioMod, DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::NotEq;
         use modio::mods::filters::Id;
 
@@ -348,6 +489,7 @@ impl DrgModio for modio::Modio {
     }
 
     async fn fetch_files(&self, url: String, mod_id: u32) -> Result<ModioMod, DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::NotEq;
         use modio::mods::filters::Id;
 
@@ -378,6 +520,7 @@ impl DrgModio for modio::Modio {
         mod_id: u32,
         modfile_id: u32,
     ) -> Result<modio::files::File, DrgModioError> {
+        let _call = MetadataCall::start();
         let file = self
             .game(MODIO_DRG_ID)
             .mod_(mod_id)
@@ -397,6 +540,7 @@ impl DrgModio for modio::Modio {
         url: String,
         mod_id: u32,
     ) -> Result<Vec<u32>, DrgModioError> {
+        let _call = MetadataCall::start();
         Ok(self
             .game(MODIO_DRG_ID)
             .mod_(mod_id)
@@ -413,6 +557,7 @@ impl DrgModio for modio::Modio {
         &self,
         name_id: &str,
     ) -> Result<Vec<ModioModResponse>, DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::{Eq, In};
         use modio::mods::filters::{NameId, Visible};
 
@@ -433,6 +578,7 @@ impl DrgModio for modio::Modio {
         &self,
         filter_ids: Vec<u32>,
     ) -> Result<Vec<modio::mods::Mod>, DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::In;
         use modio::mods::filters::Id;
 
@@ -452,6 +598,7 @@ impl DrgModio for modio::Modio {
         mod_ids: Vec<u32>,
         last_update: u64,
     ) -> Result<HashSet<u32>, DrgModioError> {
+        let _call = MetadataCall::start();
         use modio::filter::Cmp;
         use modio::filter::In;
         use modio::filter::NotIn;
@@ -619,8 +766,10 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
                 resolution: ModResolution::resolvable(url.as_str().into()),
                 suggested_require: mod_.tags.contains("RequiredByAll"),
                 suggested_dependencies: deps,
+                dependency_constraints: Default::default(),
                 modio_tags: Some(process_modio_tags(&mod_.tags)),
                 modio_id: Some(mod_id),
+                local_tags: Default::default(),
             }))
         } else if let Some(mod_id) = parsed.mod_id {
             // only mod ID specified, use latest version (either cached local or remote depending)
@@ -750,37 +899,11 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
                         .fetch_file(res.url.0.clone(), mod_id, modfile_id)
                         .await?;
 
-                    let size = file.filesize;
-                    let download: modio::download::DownloadAction = file.into();
-
                     info!("downloading mod {url:?}...");
 
-                    use futures::stream::TryStreamExt;
-                    use tokio::io::AsyncWriteExt;
-
-                    let mut cursor = std::io::Cursor::new(vec![]);
-                    let mut stream = Box::pin(self.modio.download(download).stream());
-                    while let Some(bytes) = stream
-                        .try_next()
-                        .await
-                        .with_context(|_| ModCtxtModioSnafu { mod_id })?
-                    {
-                        cursor
-                            .write_all(&bytes)
-                            .await
-                            .with_context(|_| ModCtxtIoSnafu { mod_id })?;
-                        if let Some(tx) = &tx {
-                            tx.send(FetchProgress::Progress {
-                                resolution: res.clone(),
-                                progress: cursor.get_ref().len() as u64,
-                                size,
-                            })
-                            .await
-                            .unwrap();
-                        }
-                    }
+                    let data = self.download_file(res, mod_id, file, &tx).await?;
 
-                    let blob = blob_cache.write(&cursor.into_inner())?;
+                    let blob = blob_cache.write(&data)?;
                     let path = blob_cache.get_path(&blob).unwrap();
 
                     cache
@@ -809,7 +932,41 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
         }
     }
 
-    async fn update_cache(&self, cache: ProviderCache) -> Result<(), ProviderError> {
+    async fn download_size(
+        &self,
+        res: &ModResolution,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError> {
+        let ModIoModUrl {
+            mod_id: Some(mod_id),
+            modfile_id: Some(modfile_id),
+            ..
+        } = parse_url(&res.url.0)?
+        else {
+            return InvalidUrlSnafu {
+                url: res.url.0.to_string(),
+            }
+            .fail();
+        };
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<ModioCache>(MODIO_PROVIDER_ID)
+            .and_then(|c| c.modfile_blobs.get(&modfile_id))
+            .and_then(|r| blob_cache.get_path(r))
+            .is_some();
+        if cached {
+            return Ok(DownloadSize::Cached);
+        }
+        let file = self
+            .modio
+            .fetch_file(res.url.0.clone(), mod_id, modfile_id)
+            .await?;
+        Ok(DownloadSize::Download(Some(file.filesize)))
+    }
+
+    async fn update_cache(&self, cache: ProviderCache, pause: &Pause) -> Result<(), ProviderError> {
         use futures::stream::{self, StreamExt, TryStreamExt};
 
         let now = SystemTime::now();
@@ -870,11 +1027,13 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
         }
 
         while !to_resolve.is_empty() {
-            for (u, m) in stream::iter(
-                to_resolve
-                    .iter()
-                    .map(|u| resolve_mod(self, cache.clone(), u.to_owned())),
-            )
+            for (u, m) in stream::iter(to_resolve.iter().map(|u| {
+                let cache = cache.clone();
+                async move {
+                    pause.wait().await;
+                    resolve_mod(self, cache, u.to_owned()).await
+                }
+            }))
             .boxed()
             .buffer_unordered(5)
             .try_collect::<Vec<_>>()
@@ -950,8 +1109,10 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
             ),
             suggested_require: mod_.tags.contains("RequiredByAll"),
             suggested_dependencies: deps,
+            dependency_constraints: Default::default(),
             modio_tags: Some(process_modio_tags(&mod_.tags)),
             modio_id: Some(mod_id),
+            local_tags: Default::default(),
         })
     }
 
@@ -961,6 +1122,40 @@ impl<M: DrgModio + Send + Sync> ModProvider for ModioProvider<M> {
             .is_some_and(|p| p.modfile_id.is_some())
     }
 
+    fn version_dates(
+        &self,
+        spec: &ModSpecification,
+        cache: ProviderCache,
+    ) -> Option<Vec<(ModSpecification, u64)>> {
+        let parsed = parse_url(&spec.url).ok()?;
+
+        let cache = cache.read().unwrap();
+        let prov = cache.get::<ModioCache>(MODIO_PROVIDER_ID)?;
+        let mod_id = match parsed.mod_id {
+            Some(mod_id) => mod_id,
+            None => prov.mod_id_map.get(parsed.name_id).cloned()?,
+        };
+        let mod_ = prov.mods.get(&mod_id)?;
+        Some(
+            mod_.modfiles
+                .iter()
+                .map(|f| (format_spec(&mod_.name_id, mod_id, Some(f.id)), f.date_added))
+                .collect(),
+        )
+    }
+
+    fn thumbnail_url(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<String> {
+        let parsed = parse_url(&spec.url).ok()?;
+
+        let cache = cache.read().unwrap();
+        let prov = cache.get::<ModioCache>(MODIO_PROVIDER_ID)?;
+        let mod_id = match parsed.mod_id {
+            Some(mod_id) => mod_id,
+            None => prov.mod_id_map.get(parsed.name_id).cloned()?,
+        };
+        prov.mods.get(&mod_id)?.logo.clone()
+    }
+
     fn get_version_name(&self, spec: &ModSpecification, cache: ProviderCache) -> Option<String> {
         let parsed = parse_url(&spec.url).ok()?;
 
@@ -1131,6 +1326,7 @@ mod test {
                             changelog: None,
                         }],
                         tags: HashSet::new(),
+                        logo: None,
                     },
                     dependencies: vec![],
                 },
@@ -1205,4 +1401,35 @@ mod test {
                 .collect()
         );
     }
+
+    #[test]
+    fn test_login_response() {
+        let token: AccessToken = login_response(
+            reqwest::StatusCode::OK,
+            br#"{"code":200,"access_token":"abc","date_expires":1}"#,
+        )
+        .unwrap();
+        assert_eq!(token.access_token, "abc");
+
+        let refused = login_response::<AccessToken>(
+            reqwest::StatusCode::UNAUTHORIZED,
+            br#"{"error":{"code":401,"error_ref":11012,"message":"The security code has expired."}}"#,
+        );
+        assert!(matches!(
+            refused,
+            Err(DrgModioError::LoginRefused { message })
+                if message == "The security code has expired."
+        ));
+
+        let refused =
+            login_response::<AccessToken>(reqwest::StatusCode::BAD_GATEWAY, b"<html></html>");
+        assert!(matches!(
+            refused,
+            Err(DrgModioError::LoginRefused { message }) if message == "502 Bad Gateway"
+        ));
+        assert!(matches!(
+            login_response::<AccessToken>(reqwest::StatusCode::OK, b"{}"),
+            Err(DrgModioError::InvalidLoginResponse { .. })
+        ));
+    }
 }
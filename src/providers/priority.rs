@@ -0,0 +1,122 @@
+//! Priority lanes for the store's HTTP requests. Metadata calls, such as resolving mods and
+//! checking them for updates, are small but time out when file downloads saturate the connection.
+//! While any is in flight, downloads hold off between chunks to leave them the bandwidth.
+
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+/// Longest a download holds off for the metadata calls in flight, so a hanging call can't stall
+/// it. Once it has waited this long it keeps going until no calls are in flight again.
+const MAX_HOLD: Duration = Duration::from_millis(500);
+
+static LANES: LazyLock<Lanes> = LazyLock::new(Lanes::new);
+
+/// Counts the metadata calls in flight and wakes waiting downloads once there are none.
+#[derive(Clone)]
+struct Lanes(Arc<watch::Sender<usize>>);
+
+impl Lanes {
+    fn new() -> Self {
+        Self(Arc::new(watch::Sender::new(0)))
+    }
+
+    fn metadata_call(&self) -> MetadataCall {
+        self.0.send_modify(|n| *n += 1);
+        MetadataCall(self.clone())
+    }
+
+    fn download(&self) -> Download {
+        Download {
+            in_flight: self.0.subscribe(),
+            gave_up: false,
+        }
+    }
+}
+
+/// Marks a metadata call as in flight until dropped.
+#[must_use]
+pub struct MetadataCall(Lanes);
+
+impl MetadataCall {
+    pub fn start() -> Self {
+        LANES.metadata_call()
+    }
+}
+
+impl Drop for MetadataCall {
+    fn drop(&mut self) {
+        self.0.0.send_modify(|n| *n -= 1);
+    }
+}
+
+/// A download's view of the metadata calls in flight.
+pub struct Download {
+    in_flight: watch::Receiver<usize>,
+    /// Set once a hold ran out, until no calls are in flight again.
+    gave_up: bool,
+}
+
+impl Download {
+    pub fn start() -> Self {
+        LANES.download()
+    }
+
+    /// Called before reading the next chunk: waits until no metadata calls are in flight, for up
+    /// to [`MAX_HOLD`].
+    pub async fn yield_to_metadata(&mut self) {
+        if *self.in_flight.borrow() == 0 {
+            self.gave_up = false;
+            return;
+        }
+        if self.gave_up {
+            return;
+        }
+        let idle = self.in_flight.wait_for(|n| *n == 0);
+        if tokio::time::timeout(MAX_HOLD, idle).await.is_err() {
+            self.gave_up = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_yield_to_metadata() {
+        use std::time::Instant;
+
+        let lanes = Lanes::new();
+        let mut download = lanes.download();
+
+        // woken as soon as the call finishes
+        let call = lanes.metadata_call();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(call);
+        });
+        let start = Instant::now();
+        download.yield_to_metadata().await;
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert!(start.elapsed() < MAX_HOLD);
+
+        // a hanging call holds the download once, not on every chunk
+        let call = lanes.metadata_call();
+        let start = Instant::now();
+        download.yield_to_metadata().await;
+        assert!(start.elapsed() >= MAX_HOLD);
+        let start = Instant::now();
+        download.yield_to_metadata().await;
+        assert!(start.elapsed() < MAX_HOLD);
+
+        // and again once no calls were in flight
+        drop(call);
+        download.yield_to_metadata().await;
+        let _call = lanes.metadata_call();
+        let start = Instant::now();
+        download.yield_to_metadata().await;
+        assert!(start.elapsed() >= MAX_HOLD);
+    }
+}
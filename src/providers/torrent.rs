@@ -0,0 +1,363 @@
+//! Mods shared over BitTorrent, for community packs too large for other hosts. Accepts magnet
+//! links and links to `.torrent` files. Finished downloads stop sharing right away unless seeding
+//! is enabled in the provider settings.
+
+use std::path::Path;
+use std::time::Duration;
+
+use fs_err as fs;
+use librqbit::{AddTorrent, AddTorrentOptions, AddTorrentResponse, Session};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::providers::*;
+
+inventory::submit! {
+    super::ProviderFactory {
+        id: TORRENT_PROVIDER_ID,
+        new: TorrentProvider::new_provider,
+        can_provide: is_torrent_url,
+        parameters: &[
+            super::ProviderParameter {
+                id: SEED,
+                name: "Seed",
+                description: "Set to `true` to keep sharing downloaded packs with others until \
+                              mint is closed.",
+                link: None,
+                email_login: None,
+                optional: true,
+            },
+        ],
+    }
+}
+
+const TORRENT_PROVIDER_ID: &str = "torrent";
+const SEED: &str = "seed";
+
+/// Extensions of files in a torrent which can be the mod, if it has more than one file.
+const MOD_EXTENSIONS: &[&str] = &["pak", "zip", "7z", "rar"];
+
+/// Whether `url` is a magnet link or a link to a `.torrent` file.
+pub fn is_torrent_url(url: &str) -> bool {
+    url.starts_with("magnet:?")
+        || url::Url::parse(url).is_ok_and(|u| {
+            ["http", "https"].contains(&u.scheme()) && u.path().ends_with(".torrent")
+        })
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TorrentProviderCache {
+    url_blobs: HashMap<String, BlobRef>,
+}
+
+#[typetag::serde]
+impl ModProviderCache for TorrentProviderCache {
+    fn new() -> Self {
+        Default::default()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+pub struct TorrentProvider {
+    seed: bool,
+    /// Sessions still seeding, with the directories they seed from.
+    seeding: std::sync::Mutex<Vec<(Arc<Session>, tempfile::TempDir)>>,
+}
+
+impl std::fmt::Debug for TorrentProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TorrentProvider")
+            .field("seed", &self.seed)
+            .finish()
+    }
+}
+
+impl TorrentProvider {
+    pub fn new_provider(
+        parameters: &HashMap<String, String>,
+    ) -> Result<Arc<dyn ModProvider>, ProviderError> {
+        let seed = match parameters.get(SEED).map(|s| s.trim()) {
+            None | Some("") => false,
+            Some(seed) => seed.parse().ok().context(InitProviderFailedSnafu {
+                id: TORRENT_PROVIDER_ID,
+                parameters: parameters.clone(),
+            })?,
+        };
+        Ok(Arc::new(Self {
+            seed,
+            seeding: Default::default(),
+        }))
+    }
+
+    /// Download the torrent at `url` and read the mod out of it.
+    async fn download(
+        &self,
+        url: &str,
+        res: &ModResolution,
+        tx: &Option<Sender<FetchProgress>>,
+    ) -> Result<Vec<u8>, ProviderError> {
+        let dir = tempfile::tempdir().context(BufferIoSnafu { url })?;
+        let session = Session::new(dir.path().to_path_buf())
+            .await
+            .context(TorrentSnafu { url })?;
+        let options = AddTorrentOptions {
+            overwrite: true,
+            ..Default::default()
+        };
+        let handle = match session
+            .add_torrent(AddTorrent::from_url(url), Some(options))
+            .await
+            .context(TorrentSnafu { url })?
+        {
+            AddTorrentResponse::Added(_, handle)
+            | AddTorrentResponse::AlreadyManaged(_, handle) => handle,
+            AddTorrentResponse::ListOnly(_) => unreachable!("torrent was not added as list only"),
+        };
+
+        let completed = handle.wait_until_completed();
+        tokio::pin!(completed);
+        let mut interval = tokio::time::interval(Duration::from_millis(500));
+        loop {
+            tokio::select! {
+                result = &mut completed => {
+                    result.context(TorrentSnafu { url })?;
+                    break;
+                }
+                _ = interval.tick() => {
+                    if let Some(tx) = tx {
+                        let stats = handle.stats();
+                        tx.send(FetchProgress::Progress {
+                            resolution: res.clone(),
+                            progress: stats.progress_bytes,
+                            size: stats.total_bytes,
+                        })
+                        .await
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        let data = mod_file(dir.path())
+            .and_then(|file| {
+                file.ok_or_else(|| std::io::Error::other("torrent does not contain a mod file"))
+            })
+            .and_then(fs::read)
+            .context(BufferIoSnafu { url })?;
+        if self.seed {
+            info!("seeding {url}");
+            self.seeding.lock().unwrap().push((session, dir));
+        } else {
+            session.stop().await;
+        }
+        Ok(data)
+    }
+}
+
+/// The mod among the downloaded files in `dir`: the only file, or else the largest pak or
+/// archive.
+fn mod_file(dir: &Path) -> std::io::Result<Option<PathBuf>> {
+    fn walk(dir: &Path, files: &mut Vec<(PathBuf, u64)>) -> std::io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let meta = entry.metadata()?;
+            if meta.is_dir() {
+                walk(&entry.path(), files)?;
+            } else {
+                files.push((entry.path(), meta.len()));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    walk(dir, &mut files)?;
+    if files.len() == 1 {
+        return Ok(files.pop().map(|(path, _)| path));
+    }
+    Ok(files
+        .into_iter()
+        .filter(|(path, _)| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| MOD_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        })
+        .max_by_key(|(_, len)| *len)
+        .map(|(path, _)| path))
+}
+
+/// Name shown for a torrent: the magnet link's display name or the `.torrent` file's name.
+fn torrent_name(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            if u.scheme() == "magnet" {
+                u.query_pairs()
+                    .find(|(k, _)| k == "dn")
+                    .map(|(_, v)| v.into_owned())
+            } else {
+                u.path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .map(|s| s.trim_end_matches(".torrent").to_string())
+            }
+        })
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[async_trait::async_trait]
+impl ModProvider for TorrentProvider {
+    async fn resolve_mod(
+        &self,
+        spec: &ModSpecification,
+        _update: bool,
+        cache: ProviderCache,
+    ) -> Result<ModResponse, ProviderError> {
+        Ok(ModResponse::Resolve(
+            self.get_mod_info(spec, cache)
+                .context(InvalidUrlSnafu { url: &spec.url })?,
+        ))
+    }
+
+    async fn fetch_mod(
+        &self,
+        res: &ModResolution,
+        _update: bool,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+        tx: Option<Sender<FetchProgress>>,
+    ) -> Result<PathBuf, ProviderError> {
+        let url = &res.url;
+        // a torrent's contents never change, so there is nothing to update
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<TorrentProviderCache>(TORRENT_PROVIDER_ID)
+            .and_then(|c| c.url_blobs.get(&url.0))
+            .and_then(|r| blob_cache.get_path(r));
+
+        let path = match cached {
+            Some(path) => path,
+            None => {
+                info!("downloading torrent {url:?}...");
+                let data = self.download(&url.0, res, &tx).await?;
+                let blob = blob_cache.write(&data)?;
+                let path = blob_cache.get_path(&blob).unwrap();
+                cache
+                    .write()
+                    .unwrap()
+                    .get_mut::<TorrentProviderCache>(TORRENT_PROVIDER_ID)
+                    .url_blobs
+                    .insert(url.0.to_owned(), blob);
+                path
+            }
+        };
+
+        if let Some(tx) = tx {
+            tx.send(FetchProgress::Complete {
+                resolution: res.clone(),
+            })
+            .await
+            .unwrap();
+        }
+        Ok(path)
+    }
+
+    async fn download_size(
+        &self,
+        res: &ModResolution,
+        cache: ProviderCache,
+        blob_cache: &BlobCache,
+    ) -> Result<DownloadSize, ProviderError> {
+        let cached = cache
+            .read()
+            .unwrap()
+            .get::<TorrentProviderCache>(TORRENT_PROVIDER_ID)
+            .and_then(|c| c.url_blobs.get(&res.url.0))
+            .and_then(|r| blob_cache.get_path(r))
+            .is_some();
+        // the size is only known once peers have sent the torrent's metadata
+        Ok(if cached {
+            DownloadSize::Cached
+        } else {
+            DownloadSize::Download(None)
+        })
+    }
+
+    async fn update_cache(
+        &self,
+        _cache: ProviderCache,
+        _pause: &Pause,
+    ) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    async fn check(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    fn get_mod_info(&self, spec: &ModSpecification, _cache: ProviderCache) -> Option<ModInfo> {
+        Some(ModInfo {
+            provider: TORRENT_PROVIDER_ID,
+            name: torrent_name(&spec.url),
+            spec: spec.clone(),
+            versions: vec![],
+            resolution: ModResolution::resolvable(spec.url.as_str().into()),
+            suggested_require: false,
+            suggested_dependencies: vec![],
+            dependency_constraints: Default::default(),
+            modio_tags: None,
+            modio_id: None,
+            local_tags: Default::default(),
+        })
+    }
+
+    fn is_pinned(&self, _spec: &ModSpecification, _cache: ProviderCache) -> bool {
+        true
+    }
+
+    fn get_version_name(&self, _spec: &ModSpecification, _cache: ProviderCache) -> Option<String> {
+        Some("torrent".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_torrent_urls() {
+        let magnet = "magnet:?xt=urn:btih:0123456789abcdef0123456789abcdef01234567&dn=Big+Pack";
+        assert!(is_torrent_url(magnet));
+        assert_eq!(torrent_name(magnet), "Big Pack");
+        assert!(is_torrent_url("https://example.com/packs/big_pack.torrent"));
+        assert_eq!(
+            torrent_name("https://example.com/packs/big_pack.torrent"),
+            "big_pack"
+        );
+        assert!(!is_torrent_url("https://example.com/big_pack.zip"));
+        assert!(!is_torrent_url("C:\\mods\\big_pack.torrent"));
+    }
+
+    #[test]
+    fn test_mod_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack = dir.path().join("Big Pack");
+        fs::create_dir(&pack).unwrap();
+        fs::write(pack.join("readme.txt"), b"a long readme about the pack").unwrap();
+        fs::write(pack.join("small_P.pak"), b"pak").unwrap();
+        fs::write(pack.join("pack.ZIP"), b"larger archive").unwrap();
+        assert_eq!(mod_file(dir.path()).unwrap(), Some(pack.join("pack.ZIP")));
+
+        fs::remove_file(pack.join("small_P.pak")).unwrap();
+        fs::remove_file(pack.join("pack.ZIP")).unwrap();
+        assert_eq!(mod_file(dir.path()).unwrap(), Some(pack.join("readme.txt")));
+    }
+}
@@ -0,0 +1,337 @@
+//! Snapshots of the game's save files, so progression can be restored if a gameplay-changing mod
+//! misbehaves. A snapshot is taken automatically whenever a profile is installed with a different
+//! set of Approved or Sandbox mods than at its last automatic snapshot, and can be taken and
+//! restored by hand from the settings.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use mint_lib::mod_info::ApprovalStatus;
+use mint_lib::paths::long_path;
+use mint_lib::{DRGInstallation, DRGInstallationType};
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+use tracing::{info, warn};
+
+use crate::providers::ModInfo;
+
+/// Directory inside the data directory holding one subdirectory per snapshot.
+pub const SNAPSHOT_DIR: &str = "save_snapshots";
+
+const SNAPSHOT_INFO: &str = "snapshot.json";
+
+/// Automatic snapshots kept per profile, older ones are deleted when a new one is taken. Snapshots
+/// taken by hand are kept until deleted.
+pub const MAX_AUTOMATIC_SNAPSHOTS: usize = 5;
+
+/// Package directory of the Microsoft Store version of the game.
+const XBOX_PACKAGE: &str = "CoffeeStainStudios.DeepRockGalactic_496a1srhmar9w";
+
+#[derive(Debug, Snafu)]
+pub enum SaveBackupError {
+    #[snafu(display("save files not found at {}", path.display()))]
+    SavesNotFound { path: PathBuf },
+    #[snafu(display("unable to determine where the save files are stored"))]
+    UnknownSaveLocation,
+    #[snafu(display("failed to serialize snapshot info"))]
+    SerializeFailed { source: serde_json::Error },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Directory the snapshot is stored in.
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Profile which was about to be installed, or which was active when taken by hand.
+    pub profile: String,
+    pub time: String,
+    pub reason: String,
+    /// Taken before an install rather than by hand.
+    #[serde(default)]
+    pub automatic: bool,
+    /// Gameplay mods of the install an automatic snapshot was taken for.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub gameplay_mods: BTreeSet<String>,
+}
+
+/// Directory the game keeps its save files in.
+pub fn save_dir(installation: &DRGInstallation) -> Result<PathBuf, SaveBackupError> {
+    match installation.installation_type {
+        DRGInstallationType::Steam => Ok(installation.root.join("Saved").join("SaveGames")),
+        DRGInstallationType::Xbox => directories::BaseDirs::new()
+            .map(|dirs| {
+                dirs.data_local_dir()
+                    .join("Packages")
+                    .join(XBOX_PACKAGE)
+                    .join("SystemAppData")
+                    .join("wgs")
+            })
+            .context(UnknownSaveLocationSnafu),
+    }
+}
+
+fn snapshot_root(data_dir: &Path) -> PathBuf {
+    data_dir.join(SNAPSHOT_DIR)
+}
+
+/// All snapshots, newest first. Directories without readable snapshot info are skipped.
+pub fn list(data_dir: &Path) -> Result<Vec<Snapshot>, SaveBackupError> {
+    let root = snapshot_root(data_dir);
+    if !root.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut snapshots = vec![];
+    for entry in fs::read_dir(&root)? {
+        let path = entry?.path();
+        let info = fs::read(path.join(SNAPSHOT_INFO)).and_then(|buf| {
+            serde_json::from_slice::<Snapshot>(&buf).map_err(std::io::Error::other)
+        });
+        match info {
+            Ok(snapshot) => snapshots.push(Snapshot { path, ..snapshot }),
+            Err(e) => warn!("skipping save snapshot {}: {e}", path.display()),
+        }
+    }
+    snapshots.sort_by(|a, b| b.time.cmp(&a.time));
+    Ok(snapshots)
+}
+
+/// Copy the current save files into a new snapshot.
+pub fn create(
+    installation: &DRGInstallation,
+    data_dir: &Path,
+    profile: &str,
+    reason: &str,
+) -> Result<Snapshot, SaveBackupError> {
+    let snapshot = Snapshot {
+        path: PathBuf::new(),
+        profile: profile.to_string(),
+        time: String::new(),
+        reason: reason.to_string(),
+        automatic: false,
+        gameplay_mods: BTreeSet::new(),
+    };
+    write_snapshot(&save_dir(installation)?, data_dir, snapshot)
+}
+
+/// Copy the save files in `saves` into a new snapshot described by `snapshot`, setting its path
+/// and time.
+fn write_snapshot(
+    saves: &Path,
+    data_dir: &Path,
+    snapshot: Snapshot,
+) -> Result<Snapshot, SaveBackupError> {
+    ensure!(saves.is_dir(), SavesNotFoundSnafu { path: saves });
+
+    let now = chrono::Local::now();
+    let root = snapshot_root(data_dir);
+    let base = now.format("%Y%m%d_%H%M%S").to_string();
+    let mut path = root.join(&base);
+    let mut n = 1;
+    while path.exists() {
+        n += 1;
+        path = root.join(format!("{base}_{n}"));
+    }
+
+    copy_dir(saves, &path.join("saves"))?;
+    let snapshot = Snapshot {
+        path,
+        time: now.to_rfc3339(),
+        ..snapshot
+    };
+    let buf = serde_json::to_vec_pretty(&snapshot).context(SerializeFailedSnafu)?;
+    fs::write(snapshot.path.join(SNAPSHOT_INFO), buf)?;
+    info!("save files snapshotted to {}", snapshot.path.display());
+    Ok(snapshot)
+}
+
+/// Copy the save files of `snapshot` back, after snapshotting the current ones so the restore can
+/// be undone.
+pub fn restore(
+    installation: &DRGInstallation,
+    data_dir: &Path,
+    snapshot: &Snapshot,
+) -> Result<Snapshot, SaveBackupError> {
+    let saves = save_dir(installation)?;
+    let before = create(installation, data_dir, &snapshot.profile, "before restore")?;
+    copy_dir(&snapshot.path.join("saves"), &saves)?;
+    info!("save files restored from {}", snapshot.path.display());
+    Ok(before)
+}
+
+pub fn delete(snapshot: &Snapshot) -> Result<(), SaveBackupError> {
+    fs::remove_dir_all(&snapshot.path)?;
+    Ok(())
+}
+
+/// Mods which can affect progression, i.e. all but Verified ones.
+pub fn gameplay_mods(mods: &[(ModInfo, PathBuf)]) -> BTreeSet<String> {
+    mods.iter()
+        .filter(|(info, _)| {
+            info.modio_tags
+                .as_ref()
+                .map(|t| t.approval_status)
+                .unwrap_or(ApprovalStatus::Sandbox)
+                != ApprovalStatus::Verified
+        })
+        .map(|(info, _)| info.spec.url.clone())
+        .collect()
+}
+
+/// Whether installing `gameplay` mods for `profile` should snapshot the saves first: there are
+/// gameplay mods, and they differ from those of the profile's last automatic snapshot.
+pub fn needs_snapshot(snapshots: &[Snapshot], profile: &str, gameplay: &BTreeSet<String>) -> bool {
+    !gameplay.is_empty()
+        && snapshots
+            .iter()
+            .find(|s| s.automatic && s.profile == profile)
+            .is_none_or(|s| s.gameplay_mods != *gameplay)
+}
+
+/// Delete automatic snapshots of `profile` beyond the newest [`MAX_AUTOMATIC_SNAPSHOTS`].
+pub fn prune(data_dir: &Path, profile: &str) -> Result<(), SaveBackupError> {
+    let snapshots = list(data_dir)?;
+    let old = snapshots
+        .iter()
+        .filter(|s| s.automatic && s.profile == profile)
+        .skip(MAX_AUTOMATIC_SNAPSHOTS);
+    for snapshot in old {
+        delete(snapshot)?;
+    }
+    Ok(())
+}
+
+/// Snapshot the saves before installing a profile, if enabled in the config.
+#[derive(Debug, Clone)]
+pub struct SnapshotBeforeInstall {
+    pub data_dir: PathBuf,
+    pub profile: String,
+}
+
+impl SnapshotBeforeInstall {
+    /// Take the snapshot if [`needs_snapshot`], then [`prune`] old ones. Missing save files, as on
+    /// a fresh install, are not an error since there is nothing to lose.
+    pub fn take(
+        &self,
+        installation: &DRGInstallation,
+        mods: &[(ModInfo, PathBuf)],
+    ) -> Result<Option<Snapshot>, SaveBackupError> {
+        self.take_from(&save_dir(installation)?, gameplay_mods(mods))
+    }
+
+    fn take_from(
+        &self,
+        saves: &Path,
+        gameplay_mods: BTreeSet<String>,
+    ) -> Result<Option<Snapshot>, SaveBackupError> {
+        if !needs_snapshot(&list(&self.data_dir)?, &self.profile, &gameplay_mods) {
+            return Ok(None);
+        }
+        let snapshot = Snapshot {
+            path: PathBuf::new(),
+            profile: self.profile.clone(),
+            time: String::new(),
+            reason: "gameplay mods changed".to_string(),
+            automatic: true,
+            gameplay_mods,
+        };
+        match write_snapshot(saves, &self.data_dir, snapshot) {
+            Ok(snapshot) => {
+                prune(&self.data_dir, &self.profile)?;
+                Ok(Some(snapshot))
+            }
+            Err(SaveBackupError::SavesNotFound { path }) => {
+                warn!(
+                    "no save files found at {}, not snapshotting",
+                    path.display()
+                );
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let (src, dst) = (long_path(src), long_path(dst));
+    fs::create_dir_all(&dst)?;
+    for entry in fs::read_dir(&src)? {
+        let entry = entry?;
+        let path = entry.path();
+        let dest_path = dst.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &dest_path)?;
+        } else {
+            fs::copy(long_path(&path), long_path(&dest_path))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mods(urls: &[&str]) -> BTreeSet<String> {
+        urls.iter().map(|u| u.to_string()).collect()
+    }
+
+    #[test]
+    fn test_needs_snapshot() {
+        let snapshot = |profile: &str, automatic, gameplay: &[&str]| Snapshot {
+            path: PathBuf::new(),
+            profile: profile.to_string(),
+            time: String::new(),
+            reason: String::new(),
+            automatic,
+            gameplay_mods: mods(gameplay),
+        };
+        let a = mods(&["a"]);
+        assert!(!needs_snapshot(&[], "p", &BTreeSet::new()));
+        assert!(needs_snapshot(&[], "p", &a));
+
+        // newest first: the last automatic snapshot of "p" was taken with "a"
+        let snapshots = [
+            snapshot("p", false, &[]),
+            snapshot("other", true, &["b"]),
+            snapshot("p", true, &["a"]),
+            snapshot("p", true, &["a", "b"]),
+        ];
+        assert!(!needs_snapshot(&snapshots, "p", &a));
+        assert!(needs_snapshot(&snapshots, "p", &mods(&["a", "b"])));
+        assert!(needs_snapshot(&snapshots, "p", &mods(&["b"])));
+        assert!(needs_snapshot(&snapshots, "other", &a));
+    }
+
+    #[test]
+    fn test_take_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let saves = dir.path().join("SaveGames");
+        fs::create_dir_all(&saves).unwrap();
+        fs::write(saves.join("player.sav"), "progress").unwrap();
+        let data_dir = dir.path().join("data");
+        let before_install = SnapshotBeforeInstall {
+            data_dir: data_dir.clone(),
+            profile: "p".to_string(),
+        };
+        let take = |gameplay: &[&str]| before_install.take_from(&saves, mods(gameplay)).unwrap();
+
+        assert!(take(&[]).is_none());
+        let snapshot = take(&["a"]).unwrap();
+        assert_eq!(
+            fs::read_to_string(snapshot.path.join("saves/player.sav")).unwrap(),
+            "progress"
+        );
+        assert!(take(&["a"]).is_none());
+
+        for i in 0..MAX_AUTOMATIC_SNAPSHOTS + 2 {
+            assert!(take(&[format!("mod {i}").as_str()]).is_some());
+        }
+        let snapshots = list(&data_dir).unwrap();
+        assert_eq!(snapshots.len(), MAX_AUTOMATIC_SNAPSHOTS);
+        let newest = format!("mod {}", MAX_AUTOMATIC_SNAPSHOTS + 1);
+        assert_eq!(snapshots[0].gameplay_mods, mods(&[newest.as_str()]));
+    }
+}
@@ -0,0 +1,229 @@
+//! Scanning of downloaded mod archives with a command the user configures, such as an antivirus'
+//! command line scanner. Each archive is scanned once after it was downloaded and before it can be
+//! installed. A nonzero exit status flags it: the archive is moved out of the cache into the
+//! quarantine directory and fetching the mod fails.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::sync::Mutex;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+use tracing::{info, warn};
+
+/// Replaced by the path of the archive in the scan command. Without it, the path is appended.
+pub const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Directory inside the cache directory flagged archives are moved to.
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+const SCANNED_FILE: &str = "scanned.json";
+
+#[derive(Debug, Snafu)]
+pub enum ScanError {
+    #[snafu(display("failed to run scan command {program:?}"))]
+    RunFailed {
+        source: std::io::Error,
+        program: String,
+    },
+    #[snafu(display(
+        "scan command flagged the archive ({status}), moved it to {}",
+        quarantined.display()
+    ))]
+    Flagged {
+        status: ExitStatus,
+        quarantined: PathBuf,
+    },
+    #[snafu(display("failed to quarantine {}", path.display()))]
+    QuarantineFailed {
+        source: std::io::Error,
+        path: PathBuf,
+    },
+}
+
+/// Archives which passed the scan, so they are not scanned again. Kept per command since a
+/// different scanner may flag what another didn't.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Scanned {
+    command: String,
+    passed: BTreeSet<String>,
+}
+
+#[derive(Debug)]
+pub struct Scanner {
+    command: String,
+    args: Vec<String>,
+    cache_dir: PathBuf,
+    scanned: Mutex<Scanned>,
+}
+
+impl Scanner {
+    /// A scanner running `command`, or `None` if it is empty.
+    pub fn new(command: &str, cache_dir: &Path) -> Option<Self> {
+        let args = split_command(command);
+        if args.is_empty() {
+            return None;
+        }
+        let scanned = fs::read(cache_dir.join(SCANNED_FILE))
+            .ok()
+            .and_then(|buf| serde_json::from_slice::<Scanned>(&buf).ok())
+            .filter(|s| s.command == command)
+            .unwrap_or_else(|| Scanned {
+                command: command.to_string(),
+                passed: Default::default(),
+            });
+        Some(Self {
+            command: command.to_string(),
+            args,
+            cache_dir: cache_dir.to_path_buf(),
+            scanned: Mutex::new(scanned),
+        })
+    }
+
+    /// Scan the archive at `path` unless it passed before.
+    pub async fn scan(&self, path: &Path) -> Result<(), ScanError> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if self.scanned.lock().unwrap().passed.contains(&name) {
+            return Ok(());
+        }
+
+        let args = command_args(&self.args, path);
+        info!("scanning {} with {:?}", path.display(), self.command);
+        let output = tokio::process::Command::new(&args[0])
+            .args(&args[1..])
+            .output()
+            .await
+            .context(RunFailedSnafu { program: &args[0] })?;
+
+        if output.status.success() {
+            let mut scanned = self.scanned.lock().unwrap();
+            scanned.passed.insert(name);
+            if let Err(e) = serde_json::to_vec(&*scanned)
+                .map_err(std::io::Error::other)
+                .and_then(|buf| fs::write(self.cache_dir.join(SCANNED_FILE), buf))
+            {
+                warn!("failed to record scan result: {e}");
+            }
+            return Ok(());
+        }
+
+        warn!(
+            "scan of {} failed with {}:\n{}{}",
+            path.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let quarantine = self.cache_dir.join(QUARANTINE_DIR);
+        let quarantined = quarantine.join(&name);
+        fs::create_dir_all(&quarantine)
+            .and_then(|_| fs::rename(path, &quarantined))
+            .context(QuarantineFailedSnafu { path })?;
+        FlaggedSnafu {
+            status: output.status,
+            quarantined,
+        }
+        .fail()
+    }
+}
+
+/// Split a command line into its arguments. Arguments containing spaces can be quoted with `"`.
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut args = vec![];
+    let mut arg = String::new();
+    let mut quoted = false;
+    let mut in_arg = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    args
+}
+
+/// `args` with the archive's path in place of [`FILE_PLACEHOLDER`], or appended.
+fn command_args(args: &[String], path: &Path) -> Vec<String> {
+    let path = path.to_string_lossy();
+    if args.iter().any(|a| a.contains(FILE_PLACEHOLDER)) {
+        args.iter()
+            .map(|a| a.replace(FILE_PLACEHOLDER, &path))
+            .collect()
+    } else {
+        args.iter()
+            .cloned()
+            .chain(std::iter::once(path.into_owned()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args() {
+        let args = split_command(
+            r#""C:\Program Files\Windows Defender\MpCmdRun.exe" -Scan -ScanType 3 -File {file}"#,
+        );
+        assert_eq!(
+            command_args(&args, Path::new("blobs/abc")),
+            [
+                r"C:\Program Files\Windows Defender\MpCmdRun.exe",
+                "-Scan",
+                "-ScanType",
+                "3",
+                "-File",
+                "blobs/abc"
+            ]
+        );
+        assert_eq!(
+            command_args(
+                &split_command("clamscan  --no-summary "),
+                Path::new("blobs/abc")
+            ),
+            ["clamscan", "--no-summary", "blobs/abc"]
+        );
+        assert_eq!(split_command(r#"scan """#), ["scan", ""]);
+        assert!(split_command("   ").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_flagged_archives_are_quarantined() {
+        let dir = tempfile::tempdir().unwrap();
+        let blob = dir.path().join("blobs").join("abc");
+        fs::create_dir(dir.path().join("blobs")).unwrap();
+        fs::write(&blob, b"archive").unwrap();
+
+        let passing = Scanner::new("true", dir.path()).unwrap();
+        passing.scan(&blob).await.unwrap();
+        assert!(blob.exists());
+
+        let flagging = Scanner::new("false", dir.path()).unwrap();
+        let err = flagging.scan(&blob).await.unwrap_err();
+        assert!(matches!(err, ScanError::Flagged { .. }));
+        assert!(!blob.exists());
+        assert!(dir.path().join(QUARANTINE_DIR).join("abc").exists());
+    }
+}
@@ -0,0 +1,204 @@
+//! Statistics of the game sessions mint launched: when, with which profile and for how long. How
+//! long a session lasted is only known when mint started the game itself from Steam's launch
+//! options; the Steam URL handler gives mint no process to watch.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// File in the data directory the sessions are recorded in.
+pub const SESSIONS_FILE: &str = "sessions.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub profile: String,
+    /// When the game was launched, RFC 3339.
+    pub started: String,
+    /// How long the game ran in seconds, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u64>,
+}
+
+/// Totals of the sessions played with one profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileStats {
+    pub profile: String,
+    pub launches: usize,
+    /// Total seconds of the sessions with a known duration.
+    pub played: u64,
+    pub last_started: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Sessions {
+    pub sessions: Vec<Session>,
+}
+
+impl Sessions {
+    /// The recorded sessions, none if nothing was recorded yet. A file which cannot be parsed is
+    /// an error, so it is not overwritten by a new recording.
+    pub fn load<P: AsRef<Path>>(data_dir: P) -> std::io::Result<Self> {
+        match fs::read(data_dir.as_ref().join(SESSIONS_FILE)) {
+            Ok(buf) => serde_json::from_slice(&buf)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Load, change and save the sessions, leaving the file alone if it cannot be read.
+    fn update<P: AsRef<Path>>(data_dir: P, f: impl FnOnce(&mut Self)) {
+        match Self::load(&data_dir) {
+            Ok(mut sessions) => {
+                f(&mut sessions);
+                sessions.save(&data_dir);
+            }
+            Err(e) => warn!("not recording play sessions, failed to read {SESSIONS_FILE}: {e}"),
+        }
+    }
+
+    /// Best-effort save; failing to record statistics must not keep the game from launching.
+    pub fn save<P: AsRef<Path>>(&self, data_dir: P) {
+        let res = serde_json::to_vec_pretty(self)
+            .map_err(std::io::Error::other)
+            .and_then(|buf| fs::write(data_dir.as_ref().join(SESSIONS_FILE), buf));
+        if let Err(e) = res {
+            warn!("failed to write play sessions: {e}");
+        }
+    }
+
+    /// Record that the game was launched with `profile`. Returns the session's start time, to
+    /// pass to [`Sessions::record_exit`] once the game exits.
+    pub fn record_launch<P: AsRef<Path>>(data_dir: P, profile: &str) -> String {
+        let started = chrono::Local::now().to_rfc3339();
+        Self::update(data_dir, |sessions| {
+            sessions.sessions.push(Session {
+                profile: profile.to_string(),
+                started: started.clone(),
+                duration: None,
+            });
+        });
+        started
+    }
+
+    /// Record how long the session started at `started` lasted.
+    pub fn record_exit<P: AsRef<Path>>(data_dir: P, started: &str, duration: std::time::Duration) {
+        Self::update(data_dir, |sessions| {
+            if let Some(session) = sessions.sessions.iter_mut().find(|s| s.started == started) {
+                session.duration = Some(duration.as_secs());
+            }
+        });
+    }
+
+    /// Move the sessions of profile `old` over to its new name `new`, as sessions are kept by
+    /// profile name.
+    pub fn rename_profile<P: AsRef<Path>>(data_dir: P, old: &str, new: &str) {
+        Self::update(data_dir, |sessions| {
+            for session in &mut sessions.sessions {
+                if session.profile == old {
+                    session.profile = new.to_string();
+                }
+            }
+        });
+    }
+
+    /// Totals per profile, the most played first.
+    pub fn by_profile(&self) -> Vec<ProfileStats> {
+        let mut stats = BTreeMap::<&str, ProfileStats>::new();
+        for session in &self.sessions {
+            let entry = stats
+                .entry(&session.profile)
+                .or_insert_with(|| ProfileStats {
+                    profile: session.profile.clone(),
+                    launches: 0,
+                    played: 0,
+                    last_started: String::new(),
+                });
+            entry.launches += 1;
+            entry.played += session.duration.unwrap_or_default();
+            if session.started > entry.last_started {
+                entry.last_started = session.started.clone();
+            }
+        }
+        let mut stats = stats.into_values().collect::<Vec<_>>();
+        stats.sort_by(|a, b| b.played.cmp(&a.played).then(b.launches.cmp(&a.launches)));
+        stats
+    }
+}
+
+/// Seconds as hours and minutes, e.g. `12h 5m`.
+pub fn format_duration(secs: u64) -> String {
+    let (hours, minutes) = (secs / 3600, secs / 60 % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_by_profile() {
+        let session = |profile: &str, started: &str, duration| Session {
+            profile: profile.to_string(),
+            started: started.to_string(),
+            duration,
+        };
+        let sessions = Sessions {
+            sessions: vec![
+                session("vanilla", "2024-01-01T20:00:00+00:00", Some(600)),
+                session("modded", "2024-01-02T20:00:00+00:00", Some(3600)),
+                session("modded", "2024-01-03T20:00:00+00:00", None),
+            ],
+        };
+        let stats = sessions.by_profile();
+        assert_eq!(
+            stats,
+            [
+                ProfileStats {
+                    profile: "modded".to_string(),
+                    launches: 2,
+                    played: 3600,
+                    last_started: "2024-01-03T20:00:00+00:00".to_string(),
+                },
+                ProfileStats {
+                    profile: "vanilla".to_string(),
+                    launches: 1,
+                    played: 600,
+                    last_started: "2024-01-01T20:00:00+00:00".to_string(),
+                },
+            ]
+        );
+        assert_eq!(format_duration(3600 * 12 + 5 * 60 + 59), "12h 5m");
+        assert_eq!(format_duration(59), "0m");
+    }
+
+    #[test]
+    fn test_record_and_rename() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Sessions::load(dir.path()).unwrap().sessions.is_empty());
+
+        let started = Sessions::record_launch(dir.path(), "modded");
+        Sessions::record_exit(dir.path(), &started, std::time::Duration::from_secs(90));
+        Sessions::record_launch(dir.path(), "vanilla");
+        Sessions::rename_profile(dir.path(), "modded", "hazard 5");
+        let sessions = Sessions::load(dir.path()).unwrap().sessions;
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].profile, "hazard 5");
+        assert_eq!(sessions[0].duration, Some(90));
+        assert_eq!(sessions[1].profile, "vanilla");
+
+        // a file which cannot be read is left as it is
+        let path = dir.path().join(SESSIONS_FILE);
+        fs::write(&path, "{ \"sessions\": [").unwrap();
+        assert!(Sessions::load(dir.path()).is_err());
+        Sessions::record_launch(dir.path(), "modded");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ \"sessions\": [");
+    }
+}
@@ -0,0 +1,248 @@
+//! Signatures of mod archives, for mods from sources other than mod.io where nothing vouches for
+//! who made a file. Authors sign an archive with `mint sign`, which writes a detached signature
+//! next to it (`<archive>.sig`, uploaded alongside the archive for http mods). mint checks fetched
+//! archives against the signers the user trusts, which can be added one by one or imported from a
+//! community list.
+
+use std::path::Path;
+
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use snafu::prelude::*;
+
+use crate::integrate::{IntegrationWarning, WarningKind};
+use crate::providers::{ModInfo, ModStore};
+
+/// Appended to the archive's file name or URL to get its signature's.
+pub const SIGNATURE_SUFFIX: &str = ".sig";
+
+#[derive(Debug, Snafu)]
+pub enum SigningError {
+    #[snafu(display("invalid key, expected {len} hex encoded bytes"))]
+    InvalidKey { len: usize },
+    #[snafu(display("failed to serialize signature"))]
+    SerializeSignature { source: serde_json::Error },
+    #[snafu(display("invalid trust list"))]
+    InvalidTrustList { source: serde_json::Error },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+}
+
+/// Contents of a `.sig` file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signature {
+    /// Hex encoded public key of the signer.
+    pub key: String,
+    /// Hex encoded ed25519 signature of the whole archive.
+    pub signature: String,
+}
+
+/// A public key the user trusts, with the name shown for mods it signed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedSigner {
+    pub name: String,
+    pub key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// Signed by a trusted signer.
+    Trusted {
+        signer: String,
+    },
+    /// Validly signed, but by a key which is not trusted.
+    UnknownSigner {
+        key: String,
+    },
+    /// The signature does not match the archive, which was changed after signing.
+    Mismatch,
+    Unsigned,
+}
+
+impl SignatureStatus {
+    /// Why the mod should not be trusted, if it shouldn't.
+    pub fn problem(&self) -> Option<String> {
+        match self {
+            SignatureStatus::Trusted { .. } => None,
+            SignatureStatus::UnknownSigner { key } => {
+                Some(format!("signed by the unknown key {key}"))
+            }
+            SignatureStatus::Mismatch => {
+                Some("does not match its signature, it was changed after signing".to_string())
+            }
+            SignatureStatus::Unsigned => Some("is not signed".to_string()),
+        }
+    }
+}
+
+fn decode_key<const N: usize>(key: &str) -> Result<[u8; N], SigningError> {
+    hex::decode(key.trim())
+        .ok()
+        .and_then(|k| k.try_into().ok())
+        .context(InvalidKeySnafu { len: N })
+}
+
+/// A new secret key and its public key, both hex encoded.
+pub fn generate_key() -> (String, String) {
+    let key = SigningKey::generate(&mut rand::rngs::OsRng);
+    (
+        hex::encode(key.to_bytes()),
+        hex::encode(key.verifying_key().to_bytes()),
+    )
+}
+
+/// Sign `data` with the hex encoded `secret_key`.
+pub fn sign(data: &[u8], secret_key: &str) -> Result<Signature, SigningError> {
+    let key = SigningKey::from_bytes(&decode_key(secret_key)?);
+    Ok(Signature {
+        key: hex::encode(key.verifying_key().to_bytes()),
+        signature: hex::encode(key.sign(data).to_bytes()),
+    })
+}
+
+/// Sign the archive at `path` and write the signature next to it. Returns the signature's path.
+pub fn sign_file(path: &Path, secret_key: &str) -> Result<std::path::PathBuf, SigningError> {
+    let signature = sign(&fs::read(path)?, secret_key)?;
+    let mut sig_path = path.as_os_str().to_owned();
+    sig_path.push(SIGNATURE_SUFFIX);
+    fs::write(
+        &sig_path,
+        serde_json::to_vec_pretty(&signature).context(SerializeSignatureSnafu)?,
+    )?;
+    Ok(sig_path.into())
+}
+
+/// Check `data` against the contents of its `.sig` file, if it has one.
+pub fn verify(data: &[u8], signature: Option<&[u8]>, trusted: &[TrustedSigner]) -> SignatureStatus {
+    let Some(signature) = signature else {
+        return SignatureStatus::Unsigned;
+    };
+    match serde_json::from_slice::<Signature>(signature) {
+        Ok(signature) => verify_signature(data, &signature, trusted),
+        Err(_) => SignatureStatus::Mismatch,
+    }
+}
+
+/// Check `data` against a signature which is already parsed.
+pub fn verify_signature(
+    data: &[u8],
+    signature: &Signature,
+    trusted: &[TrustedSigner],
+) -> SignatureStatus {
+    let (Ok(key), Ok(sig)) = (decode_key(&signature.key), decode_key(&signature.signature)) else {
+        return SignatureStatus::Mismatch;
+    };
+    let valid = VerifyingKey::from_bytes(&key).is_ok_and(|key| {
+        key.verify(data, &ed25519_dalek::Signature::from_bytes(&sig))
+            .is_ok()
+    });
+    if !valid {
+        return SignatureStatus::Mismatch;
+    }
+    match trusted
+        .iter()
+        .find(|t| t.key.eq_ignore_ascii_case(&signature.key))
+    {
+        Some(signer) => SignatureStatus::Trusted {
+            signer: signer.name.clone(),
+        },
+        None => SignatureStatus::UnknownSigner {
+            key: signature.key.clone(),
+        },
+    }
+}
+
+/// Read a trust list, a JSON array of [`TrustedSigner`]s.
+pub fn read_trust_list(path: &Path) -> Result<Vec<TrustedSigner>, SigningError> {
+    serde_json::from_slice(&fs::read(path)?).context(InvalidTrustListSnafu)
+}
+
+/// Add `signers` to `trusted`, replacing the name of keys already trusted. Returns how many keys
+/// are new.
+pub fn add_trusted(trusted: &mut Vec<TrustedSigner>, signers: Vec<TrustedSigner>) -> usize {
+    let mut added = 0;
+    for signer in signers {
+        match trusted
+            .iter_mut()
+            .find(|t| t.key.eq_ignore_ascii_case(&signer.key))
+        {
+            Some(existing) => existing.name = signer.name,
+            None => {
+                trusted.push(signer);
+                added += 1;
+            }
+        }
+    }
+    added
+}
+
+/// Warnings for fetched `mods` from sources other than mod.io which are not signed by a trusted
+/// signer.
+pub fn integration_warnings(store: &ModStore, mods: &[ModInfo]) -> Vec<IntegrationWarning> {
+    mods.iter()
+        .filter(|m| m.provider != "modio")
+        .filter_map(|m| {
+            let problem = store.signature_status(&m.resolution.url.0)?.problem()?;
+            Some(IntegrationWarning::new(
+                WarningKind::Untrusted,
+                Some(m),
+                format!("archive {problem}"),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let (secret, public) = generate_key();
+        let signature = serde_json::to_vec(&sign(b"mod archive", &secret).unwrap()).unwrap();
+        let trusted = vec![TrustedSigner {
+            name: "Author".to_string(),
+            key: public.to_uppercase(),
+        }];
+
+        assert_eq!(
+            verify(b"mod archive", Some(&signature[..]), &trusted),
+            SignatureStatus::Trusted {
+                signer: "Author".to_string()
+            }
+        );
+        assert_eq!(
+            verify(b"mod archive", Some(&signature[..]), &[]),
+            SignatureStatus::UnknownSigner { key: public }
+        );
+        assert_eq!(
+            verify(b"changed archive", Some(&signature[..]), &trusted),
+            SignatureStatus::Mismatch
+        );
+        assert_eq!(
+            verify(b"mod archive", Some(b"garbage".as_slice()), &trusted),
+            SignatureStatus::Mismatch
+        );
+        assert_eq!(
+            verify(b"mod archive", None, &trusted),
+            SignatureStatus::Unsigned
+        );
+        assert!(sign(b"mod archive", "abc").is_err());
+    }
+
+    #[test]
+    fn test_add_trusted() {
+        let signer = |name: &str, key: &str| TrustedSigner {
+            name: name.to_string(),
+            key: key.to_string(),
+        };
+        let mut trusted = vec![signer("old name", "aa")];
+        let added = add_trusted(
+            &mut trusted,
+            vec![signer("new name", "AA"), signer("b", "bb")],
+        );
+        assert_eq!(added, 1);
+        assert_eq!(trusted, vec![signer("new name", "aa"), signer("b", "bb")]);
+    }
+}
@@ -13,6 +13,10 @@ impl<T> ConfigTrait for T where T: std::fmt::Debug + Default + Serialize + Deser
 pub struct ConfigWrapper<C: ConfigTrait> {
     path: Option<PathBuf>,
     config: C,
+    /// Directory the file is copied into before it is overwritten, see [`super::history`].
+    history: Option<PathBuf>,
+    /// Whether saving is put off, see [`Self::hold`].
+    held: bool,
 }
 
 impl<C: ConfigTrait> ConfigWrapper<C> {
@@ -21,12 +25,48 @@ impl<C: ConfigTrait> ConfigWrapper<C> {
         Self {
             config,
             path: Some(path.as_ref().to_path_buf()),
+            history: None,
+            held: false,
         }
     }
 
     /// Create wrapper that lives only in memory and has no file backing
     pub fn memory(config: C) -> Self {
-        Self { config, path: None }
+        Self {
+            config,
+            path: None,
+            history: None,
+            held: false,
+        }
+    }
+
+    /// Don't write the file until [`Self::release`], e.g. while the user has not agreed to
+    /// upgrading its format yet. Changes are kept in memory until then.
+    pub fn hold(mut self) -> Self {
+        self.held = true;
+        self
+    }
+
+    /// Write the file again on each save, see [`Self::hold`].
+    pub fn release(&mut self) {
+        self.held = false;
+    }
+
+    /// Keep the previous contents of the file in the history in `dir` whenever it changes.
+    pub fn with_history<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.history = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Copy the file as it is now into the history, see [`Self::with_history`].
+    pub fn record_history(
+        &self,
+        reason: history::Reason,
+    ) -> std::io::Result<Option<history::Snapshot>> {
+        match (&self.path, &self.history) {
+            (Some(path), Some(history)) => history::record(history, path, None, reason),
+            _ => Ok(None),
+        }
     }
 
     /// Try our best to ensure that the config written is complete to protect against partial
@@ -37,14 +77,21 @@ impl<C: ConfigTrait> ConfigWrapper<C> {
     ///
     /// See <https://stackoverflow.com/questions/70362352/atomic-file-create-write>.
     pub fn save(&self) -> Result<(), StateError> {
-        if let Some(final_path) = &self.path {
-            let mut temp_file = tempfile::NamedTempFile::new_in(final_path.parent().unwrap())?;
-            temp_file
-                .write_all(
-                    &serde_json::to_vec_pretty(&self.config)
-                        .context(CfgSerializationFailedSnafu)?,
+        if let Some(final_path) = self.path.as_ref().filter(|_| !self.held) {
+            let contents =
+                serde_json::to_vec_pretty(&self.config).context(CfgSerializationFailedSnafu)?;
+            if let Some(history) = &self.history
+                && let Err(e) = history::record(
+                    history,
+                    final_path,
+                    Some(&contents),
+                    history::Reason::Change,
                 )
-                .context(CfgSaveFailedSnafu)?;
+            {
+                tracing::warn!("failed to keep history of {}: {e}", final_path.display());
+            }
+            let mut temp_file = tempfile::NamedTempFile::new_in(final_path.parent().unwrap())?;
+            temp_file.write_all(&contents).context(CfgSaveFailedSnafu)?;
             temp_file.persist(final_path)?;
         }
         Ok(())
@@ -69,3 +116,24 @@ impl<C: ConfigTrait> Drop for ConfigWrapper<C> {
         self.save().unwrap();
     }
 }
+
+/// A game installation mods can be installed to. The active one is the one at
+/// [`Config::drg_pak_path`], so switching between several does not require editing it each time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameInstall {
+    pub name: String,
+    pub pak_path: PathBuf,
+    pub kind: DRGInstallationType,
+}
+
+impl GameInstall {
+    /// The installation of `pak_path`, named after its store. `None` if it is not a DRG pak.
+    pub fn from_pak_path(pak_path: PathBuf) -> Option<Self> {
+        let kind = DRGInstallationType::from_pak_path(&pak_path).ok()?;
+        Some(Self {
+            name: kind.name().to_string(),
+            pak_path,
+            kind,
+        })
+    }
+}
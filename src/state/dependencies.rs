@@ -0,0 +1,200 @@
+//! Graph of the dependencies of a profile's enabled mods, following them through disabled and
+//! missing mods so whole dependency chains can be shown and enabled at once.
+
+use crate::providers::ModSpecification;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStatus {
+    Enabled,
+    /// In the profile, but disabled itself or by one of its folders.
+    Disabled,
+    /// Not in the profile at all.
+    Missing,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// Spec of the mod in the profile, or the dependency itself if it is missing.
+    pub spec: ModSpecification,
+    pub status: NodeStatus,
+    /// Indices of the nodes this mod depends on.
+    pub dependencies: Vec<usize>,
+    /// Whether the mod depends on itself through its dependencies.
+    pub in_cycle: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    /// The enabled mods in profile order, followed by the disabled and missing mods they depend
+    /// on.
+    pub nodes: Vec<Node>,
+}
+
+impl DependencyGraph {
+    /// Graph of the dependencies of the enabled ones of `mods`, given as specs with whether they
+    /// are enabled. A dependency is satisfied by an enabled mod if there is one, else by a
+    /// disabled one, else it is missing.
+    pub fn build<D>(
+        mods: impl IntoIterator<Item = (ModSpecification, bool)>,
+        dependencies: D,
+    ) -> Self
+    where
+        D: Fn(&ModSpecification) -> Vec<ModSpecification>,
+    {
+        let (enabled, disabled): (Vec<_>, Vec<_>) = mods.into_iter().partition(|(_, e)| *e);
+        let mut graph = Self {
+            nodes: enabled
+                .into_iter()
+                .map(|(spec, _)| Node {
+                    spec,
+                    status: NodeStatus::Enabled,
+                    dependencies: vec![],
+                    in_cycle: false,
+                })
+                .collect(),
+        };
+
+        let mut next = 0;
+        while next < graph.nodes.len() {
+            for dependency in dependencies(&graph.nodes[next].spec) {
+                let satisfying = |status| {
+                    graph.nodes.iter().position(|n| {
+                        n.status == status && n.spec.satisfies_dependency(&dependency)
+                    })
+                };
+                let existing = satisfying(NodeStatus::Enabled)
+                    .or_else(|| satisfying(NodeStatus::Disabled))
+                    .or_else(|| satisfying(NodeStatus::Missing));
+                let index = match existing {
+                    Some(index) => index,
+                    None => {
+                        let (spec, status) = match disabled
+                            .iter()
+                            .find(|(s, _)| s.satisfies_dependency(&dependency))
+                        {
+                            Some((s, _)) => (s.clone(), NodeStatus::Disabled),
+                            None => (dependency, NodeStatus::Missing),
+                        };
+                        graph.nodes.push(Node {
+                            spec,
+                            status,
+                            dependencies: vec![],
+                            in_cycle: false,
+                        });
+                        graph.nodes.len() - 1
+                    }
+                };
+                let node = &mut graph.nodes[next];
+                if !node.dependencies.contains(&index) {
+                    node.dependencies.push(index);
+                }
+            }
+            next += 1;
+        }
+
+        for i in 0..graph.nodes.len() {
+            let in_cycle = graph.nodes[i]
+                .dependencies
+                .iter()
+                .any(|&d| graph.chain(d).contains(&i));
+            graph.nodes[i].in_cycle = in_cycle;
+        }
+        graph
+    }
+
+    /// `root` followed by all of its direct and indirect dependencies.
+    pub fn chain(&self, root: usize) -> Vec<usize> {
+        let mut chain = vec![root];
+        let mut seen = vec![false; self.nodes.len()];
+        seen[root] = true;
+        let mut next = 0;
+        while next < chain.len() {
+            for &d in &self.nodes[chain[next]].dependencies {
+                if !seen[d] {
+                    seen[d] = true;
+                    chain.push(d);
+                }
+            }
+            next += 1;
+        }
+        chain
+    }
+
+    /// Indices of the nodes depending on `index` directly.
+    pub fn dependents(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |&i| self.nodes[i].dependencies.contains(&index))
+    }
+
+    /// Enabled mods with dependencies which no enabled mod depends on, followed by one mod of each
+    /// cycle of enabled mods not reachable from those, so every dependency is reachable from them.
+    pub fn roots(&self) -> Vec<usize> {
+        let enabled = |i: &usize| self.nodes[*i].status == NodeStatus::Enabled;
+        let mut roots = (0..self.nodes.len())
+            .filter(enabled)
+            .filter(|&i| !self.nodes[i].dependencies.is_empty())
+            .filter(|&i| !self.dependents(i).any(|d| enabled(&d)))
+            .collect::<Vec<_>>();
+        let mut reachable = roots
+            .iter()
+            .flat_map(|&r| self.chain(r))
+            .collect::<Vec<_>>();
+        for i in (0..self.nodes.len()).filter(enabled) {
+            if !self.nodes[i].dependencies.is_empty() && !reachable.contains(&i) {
+                roots.push(i);
+                reachable.extend(self.chain(i));
+            }
+        }
+        roots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(url: &str) -> ModSpecification {
+        ModSpecification::new(url.to_string())
+    }
+
+    #[test]
+    fn test_build() {
+        // a -> b -> c (disabled) -> d (missing), e <-> f
+        let mods = [
+            ("a", true),
+            ("b", true),
+            ("c", false),
+            ("e", true),
+            ("f", true),
+        ];
+        let graph = DependencyGraph::build(
+            mods.map(|(url, enabled)| (spec(url), enabled)),
+            |s: &ModSpecification| match s.url.as_str() {
+                "a" => vec![spec("b")],
+                "b" => vec![spec("c")],
+                "c" => vec![spec("d")],
+                "e" => vec![spec("f")],
+                "f" => vec![spec("e")],
+                _ => vec![],
+            },
+        );
+
+        let urls = graph
+            .nodes
+            .iter()
+            .map(|n| n.spec.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(urls, ["a", "b", "e", "f", "c", "d"]);
+        let statuses = graph.nodes.iter().map(|n| n.status).collect::<Vec<_>>();
+        use NodeStatus::*;
+        assert_eq!(
+            statuses,
+            [Enabled, Enabled, Enabled, Enabled, Disabled, Missing]
+        );
+        let cycles = graph.nodes.iter().map(|n| n.in_cycle).collect::<Vec<_>>();
+        assert_eq!(cycles, [false, false, true, true, false, false]);
+
+        assert_eq!(graph.chain(0), [0, 1, 4, 5]);
+        assert_eq!(graph.dependents(4).collect::<Vec<_>>(), [1]);
+        assert_eq!(graph.roots(), [0, 2]);
+    }
+}
@@ -0,0 +1,238 @@
+//! History of `mod_data.json`, so profiles can be rolled back after a mistake. The file is copied
+//! into the history before changes and before every install. The newest [`KEEP_SNAPSHOTS`] copies
+//! taken before installs are kept, and as many of the others, so a burst of changes can't push
+//! out the installed profiles.
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use fs_err as fs;
+use tracing::info;
+
+/// Directory inside the data directory holding the snapshots.
+pub const HISTORY_DIR: &str = "snapshots";
+
+/// How many snapshots of installs, and how many of changes and restores, are kept. Older ones are
+/// deleted.
+pub const KEEP_SNAPSHOTS: usize = 100;
+
+/// Changes made within this long of the last snapshot before a change are not snapshotted again,
+/// so clicking through the UI keeps the mod data as it was before the first click.
+const CHANGE_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+const TIME_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// Taken before the mod data was changed.
+    Change,
+    /// The mod data that was installed.
+    Install,
+    /// Taken before a snapshot was restored.
+    Restore,
+}
+
+impl Reason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Reason::Change => "change",
+            Reason::Install => "install",
+            Reason::Restore => "restore",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        [Reason::Change, Reason::Install, Reason::Restore]
+            .into_iter()
+            .find(|r| r.as_str() == s)
+    }
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Reason::Change => "before change",
+            Reason::Install => "installed",
+            Reason::Restore => "before restore",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    /// Local time the snapshot was taken.
+    pub time: NaiveDateTime,
+    pub reason: Reason,
+}
+
+impl Snapshot {
+    fn from_path(path: PathBuf) -> Option<Self> {
+        let stem = path.file_stem()?.to_str()?;
+        let (time, reason) = stem.rsplit_once('_')?;
+        Some(Self {
+            time: NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()?,
+            reason: Reason::parse(reason)?,
+            path,
+        })
+    }
+}
+
+/// All snapshots in `dir`, newest first. Files not named like a snapshot are skipped.
+pub fn list(dir: &Path) -> std::io::Result<Vec<Snapshot>> {
+    if !dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut snapshots = vec![];
+    for entry in fs::read_dir(dir)? {
+        if let Some(snapshot) = Snapshot::from_path(entry?.path()) {
+            snapshots.push(snapshot);
+        }
+    }
+    snapshots.sort_by(|a, b| b.time.cmp(&a.time));
+    Ok(snapshots)
+}
+
+/// Whether `reason` is kept in the pool of install snapshots rather than that of the others.
+fn is_install(reason: Reason) -> bool {
+    reason == Reason::Install
+}
+
+/// The snapshots of the same pool as `reason` beyond the newest `keep` of `snapshots`.
+fn outdated(
+    snapshots: &[Snapshot],
+    reason: Reason,
+    keep: usize,
+) -> impl Iterator<Item = &Snapshot> {
+    snapshots
+        .iter()
+        .filter(move |s| is_install(s.reason) == is_install(reason))
+        .skip(keep)
+}
+
+/// Copy `file` into the history in `dir`, unless it is missing, its contents equal `unchanged`,
+/// e.g. because it is about to be overwritten with the same contents, or the newest snapshot
+/// already has them. Changes are only snapshotted once per [`CHANGE_INTERVAL`].
+pub fn record(
+    dir: &Path,
+    file: &Path,
+    unchanged: Option<&[u8]>,
+    reason: Reason,
+) -> std::io::Result<Option<Snapshot>> {
+    let contents = match fs::read(file) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if unchanged == Some(contents.as_slice()) {
+        return Ok(None);
+    }
+    let snapshots = list(dir)?;
+    if let Some(newest) = snapshots.first()
+        && fs::read(&newest.path).is_ok_and(|c| c == contents)
+    {
+        return Ok(None);
+    }
+    let time = chrono::Local::now().naive_local();
+    if reason == Reason::Change
+        && snapshots
+            .iter()
+            .find(|s| s.reason == Reason::Change)
+            .is_some_and(|s| time - s.time < CHANGE_INTERVAL)
+    {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{}_{}.json",
+        time.format(TIME_FORMAT),
+        reason.as_str()
+    ));
+    fs::write(&path, contents)?;
+    info!("mod data snapshotted to {}", path.display());
+
+    for old in outdated(&snapshots, reason, KEEP_SNAPSHOTS - 1) {
+        fs::remove_file(&old.path)?;
+    }
+    Ok(Some(Snapshot { path, time, reason }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = dir.path().join(HISTORY_DIR);
+        let file = dir.path().join("mod_data.json");
+
+        // nothing to keep yet
+        assert!(
+            record(&history, &file, None, Reason::Change)
+                .unwrap()
+                .is_none()
+        );
+
+        fs::write(&file, b"{\"a\":1}").unwrap();
+        assert!(
+            record(&history, &file, Some(b"{\"a\":1}"), Reason::Change)
+                .unwrap()
+                .is_none()
+        );
+        let snapshot = record(&history, &file, Some(b"{\"a\":2}"), Reason::Change)
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.reason, Reason::Change);
+        assert_eq!(fs::read(&snapshot.path).unwrap(), b"{\"a\":1}");
+
+        // same contents as the newest snapshot
+        assert!(
+            record(&history, &file, None, Reason::Install)
+                .unwrap()
+                .is_none()
+        );
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        fs::write(&file, b"{\"a\":2}").unwrap();
+        record(&history, &file, None, Reason::Install).unwrap();
+        let snapshots = list(&history).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].reason, Reason::Install);
+        assert_eq!(snapshots[1].path, snapshot.path);
+
+        // the next change is too soon after the last
+        fs::write(&file, b"{\"a\":3}").unwrap();
+        assert!(
+            record(&history, &file, Some(b"{\"a\":4}"), Reason::Change)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_outdated() {
+        let time = chrono::Local::now().naive_local();
+        let snapshot = |n: i64, reason| Snapshot {
+            path: PathBuf::from(n.to_string()),
+            time: time - chrono::TimeDelta::minutes(n),
+            reason,
+        };
+        // newest first
+        let snapshots = [
+            snapshot(0, Reason::Change),
+            snapshot(1, Reason::Restore),
+            snapshot(2, Reason::Install),
+            snapshot(3, Reason::Change),
+            snapshot(4, Reason::Install),
+        ];
+        let paths = |reason| {
+            outdated(&snapshots, reason, 1)
+                .map(|s| s.path.to_str().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(Reason::Change), ["1", "3"]);
+        assert_eq!(paths(Reason::Install), ["4"]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Profiles of DRG Mod Manager, kept in its `profiles.json`:
+//!
+//! ```json
+//! {
+//!   "ActiveProfile": "Default",
+//!   "Profiles": [{
+//!     "Name": "Default",
+//!     "Mods": [{ "Name": "...", "ModioNameId": "...", "ModioId": 1234, "Path": null, "Enabled": true, "Priority": 10 }]
+//!   }]
+//! }
+//! ```
+//!
+//! Mods from mod.io have a `ModioNameId`, local mods a `Path` to the .pak instead. Mods are
+//! listed in load order, which is kept along with their priorities.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use serde::Deserialize;
+use snafu::prelude::*;
+
+use super::{ImportError, ImportedProfile, ParseFailedSnafu, UnrecognizedFormatSnafu};
+use crate::providers::ModSpecification;
+use crate::state::{ModConfig, ModOrGroup, ModProfile_v0_3_0 as ModProfile, UpdatePolicy};
+
+inventory::submit! {
+    super::Importer {
+        id: "drg_mod_manager",
+        name: "DRG Mod Manager",
+        detect,
+        import,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Profiles {
+    profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Profile {
+    name: String,
+    #[serde(default)]
+    mods: Vec<Mod>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Mod {
+    modio_name_id: Option<String>,
+    modio_id: Option<u32>,
+    path: Option<PathBuf>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    priority: i32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Mod {
+    fn url(&self) -> Option<String> {
+        match (&self.modio_name_id, self.modio_id, &self.path) {
+            (Some(name_id), Some(id), _) => Some(format!("https://mod.io/g/drg/m/{name_id}#{id}")),
+            (Some(name_id), None, _) => Some(format!("https://mod.io/g/drg/m/{name_id}")),
+            (None, _, Some(path)) => Some(path.to_string_lossy().to_string()),
+            (None, _, None) => None,
+        }
+    }
+}
+
+/// `profiles.json` inside the manager's data directory, or `path` itself if it is a JSON file.
+fn profiles_file(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        Some(path.join("profiles.json")).filter(|p| p.is_file())
+    } else {
+        (path.extension().is_some_and(|e| e == "json")).then(|| path.to_path_buf())
+    }
+}
+
+fn detect(path: &Path) -> bool {
+    profiles_file(path)
+        .and_then(|f| fs::read(f).ok())
+        .is_some_and(|buf| serde_json::from_slice::<Profiles>(&buf).is_ok())
+}
+
+fn import(path: &Path) -> Result<Vec<ImportedProfile>, ImportError> {
+    let file = profiles_file(path).context(UnrecognizedFormatSnafu { path })?;
+    let buf = fs::read(&file)?;
+    let profiles =
+        serde_json::from_slice::<Profiles>(&buf).context(ParseFailedSnafu { path: &file })?;
+
+    Ok(profiles
+        .profiles
+        .into_iter()
+        .map(|p| ImportedProfile {
+            name: p.name,
+            profile: ModProfile {
+                mods: p
+                    .mods
+                    .iter()
+                    .filter_map(|m| {
+                        Some(ModOrGroup::Individual(ModConfig {
+                            spec: ModSpecification::new(m.url()?),
+                            required: false,
+                            enabled: m.enabled,
+                            priority: m.priority,
+                            update_policy: UpdatePolicy::Latest,
+                            tags: BTreeSet::new(),
+                        }))
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        })
+        .collect())
+}
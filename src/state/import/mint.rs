@@ -0,0 +1,54 @@
+//! mod_data.json (or the older profiles.json) of another mint or drg-mod-integration install, in
+//! any version mint has written so far.
+
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use snafu::prelude::*;
+
+use super::{ImportError, ImportedProfile, ParseFailedSnafu, UnrecognizedFormatSnafu};
+use crate::state::{MaybeVersionedModData, migrate_mod_data};
+
+inventory::submit! {
+    super::Importer {
+        id: "mint",
+        name: "mint / drg-mod-integration",
+        detect,
+        import,
+    }
+}
+
+/// The data file inside a config directory, or `path` itself if it is a JSON file.
+fn data_file(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        ["mod_data.json", "profiles.json"]
+            .into_iter()
+            .map(|f| path.join(f))
+            .find(|p| p.is_file())
+    } else {
+        (path.extension().is_some_and(|e| e == "json")).then(|| path.to_path_buf())
+    }
+}
+
+fn detect(path: &Path) -> bool {
+    data_file(path)
+        .and_then(|f| fs::read(f).ok())
+        .is_some_and(|buf| serde_json::from_slice::<MaybeVersionedModData>(&buf).is_ok())
+}
+
+fn import(path: &Path) -> Result<Vec<ImportedProfile>, ImportError> {
+    let file = data_file(path).context(UnrecognizedFormatSnafu { path })?;
+    let buf = fs::read(&file)?;
+    let mod_data = serde_json::from_slice::<MaybeVersionedModData>(&buf)
+        .context(ParseFailedSnafu { path: &file })?;
+    let mod_data = migrate_mod_data(mod_data);
+
+    Ok(mod_data
+        .profiles
+        .iter()
+        .map(|(name, profile)| ImportedProfile {
+            name: name.clone(),
+            profile: profile.clone(),
+        })
+        .collect())
+}
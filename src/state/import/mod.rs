@@ -0,0 +1,239 @@
+//! Importers for mod lists kept by other mod managers, so switching to mint keeps the mods, their
+//! order and which of them are enabled.
+//!
+//! Importers register themselves through `inventory` in the same way mod providers do. Each one
+//! recognizes its format from a path and turns it into one or more profiles, which are then added
+//! next to the existing ones with [`add_profiles`].
+
+mod drg_mod_manager;
+mod mint;
+mod mod_list;
+mod modio_app;
+
+use std::path::{Path, PathBuf};
+
+use snafu::prelude::*;
+
+use super::{ModData_v0_3_0 as ModData, ModOrGroup, ModProfile_v0_3_0 as ModProfile};
+
+#[derive(Debug, Snafu)]
+pub enum ImportError {
+    #[snafu(display("no importer recognizes {}", path.display()))]
+    UnrecognizedFormat { path: PathBuf },
+    #[snafu(display("no importer with id {id:?}"))]
+    UnknownImporter { id: String },
+    #[snafu(display("failed to parse {}", path.display()))]
+    ParseFailed {
+        source: serde_json::Error,
+        path: PathBuf,
+    },
+    #[snafu(display("{} contains no mods", path.display()))]
+    NothingToImport { path: PathBuf },
+    #[snafu(transparent)]
+    IoError { source: std::io::Error },
+}
+
+/// A profile read from another manager's data, named as it was there.
+#[derive(Debug, Clone)]
+pub struct ImportedProfile {
+    pub name: String,
+    pub profile: ModProfile,
+}
+
+pub struct Importer {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Whether `path` looks like data this importer understands.
+    detect: fn(&Path) -> bool,
+    import: fn(&Path) -> Result<Vec<ImportedProfile>, ImportError>,
+}
+
+impl std::fmt::Debug for Importer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Importer")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+inventory::collect!(Importer);
+
+impl Importer {
+    pub fn all() -> impl Iterator<Item = &'static Importer> {
+        inventory::iter::<Importer>()
+    }
+
+    pub fn by_id(id: &str) -> Result<&'static Importer, ImportError> {
+        Self::all()
+            .find(|i| i.id == id)
+            .context(UnknownImporterSnafu { id })
+    }
+
+    /// Find the importer which understands `path`.
+    pub fn detect(path: &Path) -> Result<&'static Importer, ImportError> {
+        Self::all()
+            .find(|i| (i.detect)(path))
+            .context(UnrecognizedFormatSnafu { path })
+    }
+
+    pub fn import(&self, path: &Path) -> Result<Vec<ImportedProfile>, ImportError> {
+        let profiles = (self.import)(path)?;
+        ensure!(
+            profiles.iter().any(|p| !p.profile.mods.is_empty()),
+            NothingToImportSnafu { path }
+        );
+        Ok(profiles)
+    }
+}
+
+/// Add imported profiles to `mod_data`, appending " (imported)" and a counter to names already
+/// taken. Returns the names the profiles were added under.
+pub fn add_profiles(mod_data: &mut ModData, profiles: Vec<ImportedProfile>) -> Vec<String> {
+    let mut names = vec![];
+    for ImportedProfile { name, profile } in profiles {
+        if profile.mods.is_empty() {
+            continue;
+        }
+        let mut unique_name = name.clone();
+        let mut n = 1;
+        while mod_data.profiles.contains_key(&unique_name) {
+            unique_name = if n == 1 {
+                format!("{name} (imported)")
+            } else {
+                format!("{name} (imported {n})")
+            };
+            n += 1;
+        }
+        mod_data.profiles.insert(unique_name.clone(), profile);
+        names.push(unique_name);
+    }
+    names
+}
+
+/// Number of mods in a profile, including those inside folders.
+pub fn mod_count(profile: &ModProfile) -> usize {
+    let mut count = 0;
+    profile.visit(|m, _| {
+        if let ModOrGroup::Individual(_) = m {
+            count += 1;
+        }
+    });
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ModData_v0_3_0 as ModData;
+
+    #[test]
+    fn test_detect_and_import() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let list = dir.path().join("friends.txt");
+        std::fs::write(
+            &list,
+            "# shared pack\nhttps://mod.io/g/drg/m/a#1/2\n\n# https://example.com/b.pak\n",
+        )
+        .unwrap();
+        let importer = Importer::detect(&list).unwrap();
+        assert_eq!(importer.id, "mod_list");
+        let profiles = importer.import(&list).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "friends");
+        assert_eq!(profiles[0].profile.readme, None);
+        let mods = profiles[0]
+            .profile
+            .mods
+            .iter()
+            .map(|m| match m {
+                ModOrGroup::Individual(mc) => (mc.spec.url.as_str(), mc.enabled),
+                ModOrGroup::Group { .. } => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mods,
+            [
+                ("https://mod.io/g/drg/m/a#1/2", true),
+                ("https://example.com/b.pak", false)
+            ]
+        );
+
+        std::fs::write(dir.path().join("friends.md"), "Install notes").unwrap();
+        let profiles = importer.import(&list).unwrap();
+        assert_eq!(profiles[0].profile.readme.as_deref(), Some("Install notes"));
+
+        let mint_data = dir.path().join("mod_data.json");
+        std::fs::write(
+            &mint_data,
+            r#"{"active_profile":"default","profiles":{"default":{"mods":[{"spec":{"url":"https://example.com/c.pak"},"required":false,"enabled":true}]}}}"#,
+        )
+        .unwrap();
+        let importer = Importer::detect(dir.path()).unwrap();
+        assert_eq!(importer.id, "mint");
+
+        let mut mod_data = ModData::default();
+        let names = add_profiles(&mut mod_data, importer.import(dir.path()).unwrap());
+        assert_eq!(names, ["default (imported)"]);
+        assert_eq!(mod_count(&mod_data.profiles["default (imported)"]), 1);
+    }
+
+    /// Imported mods of the profile as `(url, enabled, priority)`.
+    fn mods(profile: &ImportedProfile) -> Vec<(&str, bool, i32)> {
+        profile
+            .profile
+            .mods
+            .iter()
+            .filter_map(ModOrGroup::as_mod)
+            .map(|mc| (mc.spec.url.as_str(), mc.enabled, mc.priority))
+            .collect()
+    }
+
+    #[test]
+    fn test_import_drg_mod_manager() {
+        let dir = Path::new("test_assets/import/drg_mod_manager");
+        for path in [dir.to_path_buf(), dir.join("profiles.json")] {
+            assert_eq!(Importer::detect(&path).unwrap().id, "drg_mod_manager");
+        }
+        let profiles = Importer::detect(dir).unwrap().import(dir).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].name, "Hazard 5");
+        assert_eq!(
+            mods(&profiles[0]),
+            [
+                (
+                    "https://mod.io/g/drg/m/better-post-processing#1861561",
+                    true,
+                    10
+                ),
+                ("C:\\Mods\\LocalTweaks.pak", false, 0),
+            ]
+        );
+
+        let mut mod_data = ModData::default();
+        assert_eq!(add_profiles(&mut mod_data, profiles), ["Hazard 5"]);
+    }
+
+    #[test]
+    fn test_import_modio() {
+        let dir = Path::new("test_assets/import/modio");
+        for path in [dir.to_path_buf(), dir.join("metadata/state.json")] {
+            assert_eq!(Importer::detect(&path).unwrap().id, "modio");
+        }
+        let profiles = Importer::detect(dir).unwrap().import(dir).unwrap();
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].name, "mod.io");
+        assert_eq!(
+            mods(&profiles[0]),
+            [
+                (
+                    "https://mod.io/g/drg/m/better-post-processing#1861561/4269876",
+                    true,
+                    0
+                ),
+                ("https://mod.io/g/drg/m/sandbox-utilities#2087032", true, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Plain text mod lists with one mod URL or path per line, as produced by "Copy profile mods" or
+//! kept by hand when sharing packs. Lines starting with `#` are comments, except that a commented
+//! out URL is imported as a disabled mod. A Markdown file with the same name next to the list,
+//! e.g. `pack.md` for `pack.txt`, becomes the profile's readme.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use fs_err as fs;
+
+use super::{ImportError, ImportedProfile};
+use crate::providers::ModSpecification;
+use crate::state::{ModConfig, ModOrGroup, ModProfile_v0_3_0 as ModProfile, UpdatePolicy};
+
+inventory::submit! {
+    super::Importer {
+        id: "mod_list",
+        name: "Mod list (one URL per line)",
+        detect,
+        import,
+    }
+}
+
+fn is_url(s: &str) -> bool {
+    s.starts_with("https://") || s.starts_with("http://")
+}
+
+/// Parse a line into the mod it names and whether it is enabled.
+fn parse_line(line: &str) -> Option<(&str, bool)> {
+    let line = line.trim();
+    if let Some(comment) = line.strip_prefix('#') {
+        let comment = comment.trim();
+        is_url(comment).then_some((comment, false))
+    } else if line.is_empty() {
+        None
+    } else {
+        Some((line, true))
+    }
+}
+
+fn detect(path: &Path) -> bool {
+    if path.is_dir() || path.extension().is_some_and(|e| e == "json") {
+        return false;
+    }
+    let Ok(contents) = fs::read_to_string(path) else {
+        return false;
+    };
+    let mut mods = contents.lines().filter_map(parse_line).peekable();
+    mods.peek().is_some() && mods.all(|(m, _)| is_url(m) || Path::new(m).exists())
+}
+
+fn import(path: &Path) -> Result<Vec<ImportedProfile>, ImportError> {
+    let contents = fs::read_to_string(path)?;
+    let mods = contents
+        .lines()
+        .filter_map(parse_line)
+        .map(|(url, enabled)| {
+            ModOrGroup::Individual(ModConfig {
+                spec: ModSpecification::new(url.to_string()),
+                required: false,
+                enabled,
+                priority: 0,
+                update_policy: UpdatePolicy::Latest,
+                tags: BTreeSet::new(),
+            })
+        })
+        .collect();
+
+    let name = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "imported".to_string());
+    Ok(vec![ImportedProfile {
+        name,
+        profile: ModProfile {
+            mods,
+            readme: fs::read_to_string(path.with_extension("md")).ok(),
+            ..Default::default()
+        },
+    }])
+}
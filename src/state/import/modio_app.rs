@@ -0,0 +1,111 @@
+//! Mods subscribed to through mod.io in game or in the mod.io app. mod.io keeps the installed mods
+//! of a game in `<mod.io dir>/<game id>/metadata/state.json`, e.g.
+//! `C:\Users\Public\mod.io\2475\metadata\state.json` for Deep Rock Galactic:
+//!
+//! ```json
+//! { "Mods": [{ "ID": 1234, "Profile": { "name": "...", "name_id": "...", "modfile": { "id": 5678 } } }] }
+//! ```
+//!
+//! The game decides which of them are enabled, so all are imported enabled, pinned to the
+//! installed file.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use serde::Deserialize;
+use snafu::prelude::*;
+
+use super::{ImportError, ImportedProfile, ParseFailedSnafu, UnrecognizedFormatSnafu};
+use crate::providers::ModSpecification;
+use crate::state::{ModConfig, ModOrGroup, ModProfile_v0_3_0 as ModProfile, UpdatePolicy};
+
+inventory::submit! {
+    super::Importer {
+        id: "modio",
+        name: "mod.io app / in-game mod.io",
+        detect,
+        import,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct State {
+    mods: Vec<InstalledMod>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InstalledMod {
+    #[serde(rename = "ID")]
+    id: u32,
+    profile: ModProfileInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModProfileInfo {
+    name_id: String,
+    modfile: Option<Modfile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Modfile {
+    id: u32,
+}
+
+/// `state.json` inside a game's mod.io directory or its `metadata` directory, or `path` itself.
+fn state_file(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        [
+            path.join("metadata").join("state.json"),
+            path.join("state.json"),
+        ]
+        .into_iter()
+        .find(|p| p.is_file())
+    } else {
+        (path.file_name().is_some_and(|n| n == "state.json")).then(|| path.to_path_buf())
+    }
+}
+
+fn detect(path: &Path) -> bool {
+    state_file(path)
+        .and_then(|f| fs::read(f).ok())
+        .is_some_and(|buf| serde_json::from_slice::<State>(&buf).is_ok())
+}
+
+fn import(path: &Path) -> Result<Vec<ImportedProfile>, ImportError> {
+    let file = state_file(path).context(UnrecognizedFormatSnafu { path })?;
+    let buf = fs::read(&file)?;
+    let state = serde_json::from_slice::<State>(&buf).context(ParseFailedSnafu { path: &file })?;
+
+    let mods = state
+        .mods
+        .into_iter()
+        .map(|m| {
+            let url = match m.profile.modfile {
+                Some(file) => format!(
+                    "https://mod.io/g/drg/m/{}#{}/{}",
+                    m.profile.name_id, m.id, file.id
+                ),
+                None => format!("https://mod.io/g/drg/m/{}#{}", m.profile.name_id, m.id),
+            };
+            ModOrGroup::Individual(ModConfig {
+                spec: ModSpecification::new(url),
+                required: false,
+                enabled: true,
+                priority: 0,
+                update_policy: UpdatePolicy::Latest,
+                tags: BTreeSet::new(),
+            })
+        })
+        .collect();
+
+    Ok(vec![ImportedProfile {
+        name: "mod.io".to_string(),
+        profile: ModProfile {
+            mods,
+            ..Default::default()
+        },
+    }])
+}
@@ -0,0 +1,192 @@
+//! Suggested priorities for mods changing the same assets, so which mod wins each asset follows
+//! from the priorities alone instead of from the order of the mods in the profile.
+
+use std::collections::HashMap;
+
+use indexmap::IndexSet;
+
+use crate::providers::ModSpecification;
+
+/// A suggested change of a mod's priority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityChange {
+    pub spec: ModSpecification,
+    pub from: i32,
+    pub to: i32,
+}
+
+/// An enabled mod to suggest a priority for.
+#[derive(Debug, Clone)]
+pub struct PrioritizedMod {
+    pub spec: ModSpecification,
+    /// Effective priority, i.e. that of its folder if one overrides it.
+    pub priority: i32,
+    /// Whether the priority comes from a folder, which is left as it is.
+    pub fixed: bool,
+}
+
+/// Priorities for `mods`, given in profile order, such that no two mods changing the same asset,
+/// as listed by `conflicts`, share a priority. Mods which already win by priority keep winning;
+/// of mods sharing a priority the one changing fewer conflicting assets wins, as smaller mods are
+/// usually patches meant to go on top of larger ones, then the one listed first as before. A mod
+/// is never raised to or above a fixed mod winning over it by priority; the mods it has to win
+/// over are lowered below it instead. Only priorities which change are returned, in the order the
+/// mods win.
+pub fn suggest_priorities<'a>(
+    mods: &[PrioritizedMod],
+    conflicts: impl IntoIterator<Item = &'a IndexSet<ModSpecification>>,
+) -> Vec<PriorityChange> {
+    let index = mods
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (&m.spec, i))
+        .collect::<HashMap<_, _>>();
+    let mut conflicting = vec![IndexSet::new(); mods.len()];
+    let mut assets = vec![0; mods.len()];
+    for asset_mods in conflicts {
+        let asset_mods = asset_mods
+            .iter()
+            .filter_map(|spec| index.get(spec).copied())
+            .collect::<Vec<_>>();
+        if asset_mods.len() < 2 {
+            continue;
+        }
+        for &a in &asset_mods {
+            assets[a] += 1;
+            conflicting[a].extend(asset_mods.iter().filter(|b| **b != a));
+        }
+    }
+
+    // winners first
+    let mut ranking = (0..mods.len()).collect::<Vec<_>>();
+    ranking.sort_by_key(|&i| (-mods[i].priority, assets[i], i));
+
+    let mut rank = vec![0; mods.len()];
+    for (r, &i) in ranking.iter().enumerate() {
+        rank[i] = r;
+    }
+
+    let mut priorities = mods.iter().map(|m| m.priority).collect::<Vec<_>>();
+    let mut placed = vec![false; mods.len()];
+    for &i in ranking.iter().rev() {
+        if !mods[i].fixed
+            && let Some(lowest) = conflicting[i]
+                .iter()
+                .filter(|&&other| placed[other])
+                .map(|&other| priorities[other] + 1)
+                .max()
+        {
+            let highest = conflicting[i]
+                .iter()
+                .filter(|&&other| mods[other].fixed && mods[other].priority > mods[i].priority)
+                .map(|&other| priorities[other] - 1)
+                .min()
+                .unwrap_or(i32::MAX);
+            priorities[i] = priorities[i].max(lowest).min(highest);
+            if priorities[i] < lowest {
+                lower_below(i, mods, &conflicting, &rank, &mut priorities);
+            }
+        }
+        placed[i] = true;
+    }
+
+    ranking
+        .into_iter()
+        .filter(|&i| priorities[i] != mods[i].priority)
+        .map(|i| PriorityChange {
+            spec: mods[i].spec.clone(),
+            from: mods[i].priority,
+            to: priorities[i],
+        })
+        .collect()
+}
+
+/// Lower the mods which `i` wins over below its priority, and so on for the mods they win over.
+/// Fixed mods are left as they are.
+fn lower_below(
+    i: usize,
+    mods: &[PrioritizedMod],
+    conflicting: &[IndexSet<usize>],
+    rank: &[usize],
+    priorities: &mut [i32],
+) {
+    for &other in &conflicting[i] {
+        if rank[other] > rank[i] && !mods[other].fixed && priorities[other] >= priorities[i] {
+            priorities[other] = priorities[i] - 1;
+            lower_below(other, mods, conflicting, rank, priorities);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prioritized(url: &str, priority: i32, fixed: bool) -> PrioritizedMod {
+        PrioritizedMod {
+            spec: ModSpecification::new(url.to_string()),
+            priority,
+            fixed,
+        }
+    }
+
+    fn set(urls: &[&str]) -> IndexSet<ModSpecification> {
+        urls.iter()
+            .map(|url| ModSpecification::new(url.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_suggest_priorities() {
+        let mods = [
+            prioritized("overhaul", 0, false),
+            prioritized("patch", 0, false),
+            prioritized("other", 0, false),
+            prioritized("high", 5, false),
+            prioritized("folder", 0, true),
+        ];
+        let conflicts = [
+            set(&["overhaul", "patch"]),
+            set(&["overhaul", "other"]),
+            set(&["overhaul", "high"]),
+            set(&["other", "folder"]),
+            set(&["overhaul", "unknown"]),
+        ];
+
+        let changes = suggest_priorities(&mods, &conflicts);
+        let changes = changes
+            .iter()
+            .map(|c| (c.spec.url.as_str(), c.from, c.to))
+            .collect::<Vec<_>>();
+        // patch and other change one asset each, so they win over overhaul; other also beats
+        // folder, which stays as it is
+        assert_eq!(changes, [("patch", 0, 1), ("other", 0, 1)]);
+
+        assert!(suggest_priorities(&mods, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_priorities_below_fixed() {
+        let mods = [
+            prioritized("i", 0, false),
+            prioritized("j", 1, true),
+            prioritized("k", 0, false),
+            prioritized("m", 0, false),
+        ];
+        let conflicts = [
+            set(&["i", "j"]),
+            set(&["i", "k"]),
+            set(&["k", "m"]),
+            set(&["k", "m"]),
+            set(&["i", "m"]),
+        ];
+
+        let changes = suggest_priorities(&mods, &conflicts);
+        let changes = changes
+            .iter()
+            .map(|c| (c.spec.url.as_str(), c.from, c.to))
+            .collect::<Vec<_>>();
+        // i can't be raised over k and m without beating j, so they are lowered below it
+        assert_eq!(changes, [("k", 0, -1), ("m", 0, -2)]);
+    }
+}
@@ -0,0 +1,159 @@
+//! What upgrading `mod_data.json` saved by an older version of mint changes. The GUI shows the
+//! changes before the file is saved in the current format for the first time, and a copy of it is
+//! kept next to it then, so they are not made silently.
+
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+
+use super::{MaybeVersionedModData, ModData_v0_3_0, ModOrGroup, VersionAnnotatedModData};
+
+/// Version mod data is saved in.
+pub const CURRENT_VERSION: &str = "0.3.0";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationPreview {
+    /// File the mod data was read from, `mod_data.json` or the legacy `profiles.json`.
+    pub file: PathBuf,
+    /// Version of the file before the migration, "unversioned" for files from before versions
+    /// were recorded.
+    pub from: String,
+    /// What the migration changes in each profile.
+    pub profiles: Vec<ProfileMigration>,
+    /// Folders shared by all profiles before 0.2.0 which no profile used, which are dropped.
+    pub dropped_folders: Vec<String>,
+    /// Where the file is copied to before it is first saved in the current format, e.g.
+    /// `mod_data.json.0.1.0.bak`.
+    pub backup: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileMigration {
+    pub name: String,
+    /// Mods in the profile after the migration.
+    pub mods: usize,
+    /// Folders shared by all profiles before 0.2.0 which the profile gets its own copy of.
+    pub copied_folders: Vec<String>,
+}
+
+impl MigrationPreview {
+    /// What migrating `mod_data`, read from `file`, changes, `None` if it already has the current
+    /// version. [`Self::count_mods`] fills in the mods of each profile once it is migrated.
+    pub fn new(file: &Path, mod_data: &MaybeVersionedModData) -> Option<Self> {
+        let mut profiles = vec![];
+        let mut dropped_folders = vec![];
+        let from = match mod_data {
+            MaybeVersionedModData::Legacy(_) => "unversioned",
+            MaybeVersionedModData::Versioned(VersionAnnotatedModData::V0_0_0(_)) => "0.0.0",
+            MaybeVersionedModData::Versioned(VersionAnnotatedModData::V0_1_0(md)) => {
+                for (name, profile) in &md.profiles {
+                    let copied_folders = profile
+                        .mods
+                        .iter()
+                        .filter_map(|entry| match entry {
+                            ModOrGroup::Group { group_name, .. } => Some(group_name),
+                            ModOrGroup::Individual(_) => None,
+                        })
+                        .filter(|folder| md.groups.contains_key(*folder))
+                        .cloned()
+                        .collect();
+                    profiles.push(ProfileMigration {
+                        name: name.clone(),
+                        mods: 0,
+                        copied_folders,
+                    });
+                }
+                dropped_folders = md
+                    .groups
+                    .keys()
+                    .filter(|folder| !profiles.iter().any(|p| p.copied_folders.contains(*folder)))
+                    .cloned()
+                    .collect();
+                "0.1.0"
+            }
+            MaybeVersionedModData::Versioned(VersionAnnotatedModData::V0_2_0(_)) => "0.2.0",
+            MaybeVersionedModData::Versioned(VersionAnnotatedModData::V0_3_0(_)) => return None,
+        };
+        let mut backup = file.file_name().unwrap_or_default().to_os_string();
+        backup.push(format!(".{from}.bak"));
+        Some(Self {
+            file: file.to_path_buf(),
+            from: from.to_string(),
+            profiles,
+            dropped_folders,
+            backup: file.with_file_name(backup),
+        })
+    }
+
+    /// Fill in the mods of each of [`Self::profiles`] from the migrated mod data.
+    pub fn count_mods(&mut self, mod_data: &ModData_v0_3_0) {
+        for (name, profile) in &mod_data.profiles {
+            let mods = profile.all_entries().filter_map(ModOrGroup::as_mod).count();
+            match self.profiles.iter_mut().find(|p| p.name == *name) {
+                Some(migration) => migration.mods = mods,
+                None => self.profiles.push(ProfileMigration {
+                    name: name.clone(),
+                    mods,
+                    copied_folders: vec![],
+                }),
+            }
+        }
+    }
+
+    /// Copy [`Self::file`] to [`Self::backup`] before it is overwritten in the current format.
+    pub fn backup(&self) -> std::io::Result<()> {
+        fs::copy(&self.file, &self.backup)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_shared_folders() {
+        let json = r#"{
+            "version": "0.1.0",
+            "active_profile": "a",
+            "profiles": {
+                "a": { "mods": [{ "group_name": "shared", "enabled": true }] },
+                "b": {
+                    "mods": [
+                        { "spec": { "url": "x" }, "required": false },
+                        { "group_name": "shared", "enabled": false }
+                    ]
+                }
+            },
+            "groups": {
+                "shared": { "mods": [{ "spec": { "url": "y" }, "required": false }] },
+                "unused": { "mods": [] }
+            }
+        }"#;
+        let file = Path::new("config/mod_data.json");
+        let mod_data = serde_json::from_str::<MaybeVersionedModData>(json).unwrap();
+        let mut preview = MigrationPreview::new(file, &mod_data).unwrap();
+        assert_eq!(preview.from, "0.1.0");
+        assert_eq!(preview.backup, Path::new("config/mod_data.json.0.1.0.bak"));
+        assert_eq!(preview.dropped_folders, ["unused"]);
+
+        let VersionAnnotatedModData::V0_3_0(migrated) = crate::state::migrate_mod_data(mod_data)
+        else {
+            unreachable!();
+        };
+        preview.count_mods(&migrated);
+        let profile = |name: &str, mods, copied_folders: &[&str]| ProfileMigration {
+            name: name.to_string(),
+            mods,
+            copied_folders: copied_folders.iter().map(|f| f.to_string()).collect(),
+        };
+        assert_eq!(
+            preview.profiles,
+            [profile("a", 1, &["shared"]), profile("b", 2, &["shared"])]
+        );
+
+        let current = r#"{ "version": "0.3.0", "active_profile": "a", "profiles": {} }"#;
+        let current = serde_json::from_str::<MaybeVersionedModData>(current).unwrap();
+        assert_eq!(MigrationPreview::new(file, &current), None);
+    }
+}
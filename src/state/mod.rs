@@ -1,24 +1,37 @@
 pub mod config;
+pub mod dependencies;
+pub mod history;
+pub mod import;
+pub mod load_order;
+pub mod migration;
+pub mod secrets;
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
 use fs_err as fs;
+use indexmap::IndexSet;
 use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
 
-use self::config::ConfigWrapper;
+use self::config::{ConfigWrapper, GameInstall};
+use self::load_order::{PrioritizedMod, PriorityChange};
+use self::migration::MigrationPreview;
+use self::secrets::ProviderParameters;
 use crate::{
     Dirs,
+    backup::AutoBackup,
     gui::GuiTheme,
-    providers::{ModSpecification, ModStore},
+    providers::{LocalMetadata, ModSpecification, ModStore, client},
+    signing::TrustedSigner,
 };
 use crate::{gui::SortBy, providers::ProviderError};
-use mint_lib::{DRGInstallation, mod_info::MetaConfig};
+use mint_lib::mod_info::{ApprovalStatus, MetaConfig, canonical_url};
+use mint_lib::{DRGInstallation, DRGInstallationType};
 
 /// Mod configuration, holds ModSpecification as well as other metadata
 #[derive(Debug, Clone, Hash, Serialize, Deserialize)]
@@ -30,28 +43,193 @@ pub struct ModConfig {
     pub enabled: bool,
     #[serde(default, skip_serializing_if = "is_zero")]
     pub priority: i32,
+    #[serde(default, skip_serializing_if = "UpdatePolicy::is_latest")]
+    pub update_policy: UpdatePolicy,
+    /// Tags given by the user, e.g. "deep dives", to turn mods for an activity on or off at once.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+}
+
+/// Which versions "Update cache" may move a mod to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdatePolicy {
+    /// Always the newest version.
+    #[default]
+    Latest,
+    /// The newest version while the mod is approved or verified on mod.io. Updates which put it
+    /// in the sandbox are held back at the version it had.
+    LatestApproved,
+    /// The version it is set to, never updated.
+    Pinned,
+}
+
+impl UpdatePolicy {
+    fn is_latest(&self) -> bool {
+        *self == UpdatePolicy::Latest
+    }
+}
+
+/// How "Launch game" starts an installation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchMethod {
+    /// Through the Steam URL handler.
+    Steam,
+    /// Through the shell by the app ID of the Microsoft Store/Game Pass version.
+    Xbox,
+}
+
+impl LaunchMethod {
+    /// The launcher of the store `installation_type` was bought from.
+    pub fn for_installation_type(installation_type: DRGInstallationType) -> Self {
+        match installation_type {
+            DRGInstallationType::Steam => LaunchMethod::Steam,
+            DRGInstallationType::Xbox => LaunchMethod::Xbox,
+        }
+    }
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_memory_budget() -> u64 {
+    crate::archive::DEFAULT_MEMORY_BUDGET
+}
+
+fn default_download_concurrency() -> usize {
+    crate::providers::mod_store::DEFAULT_DOWNLOAD_CONCURRENCY
+}
+
 fn is_zero(value: &i32) -> bool {
     *value == 0
 }
 
+#[obake::versioned]
+#[obake(version("0.1.0"))]
+#[obake(version("0.3.0"))]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModGroup {
+    #[obake(cfg("0.1.0"))]
     pub mods: Vec<ModConfig>,
+    /// Mods and nested folders, in order. A nested folder is another folder of the same profile
+    /// (added in 0.3.0).
+    #[obake(cfg("0.3.0"))]
+    pub mods: Vec<ModOrGroup>,
     /// When Some, all mods in this group use this priority instead of their individual priority
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub priority_override: Option<i32>,
+    /// When Some, added to the priority of all mods in this group, keeping their order. Offsets of
+    /// nested folders add up, and apply on top of an override.
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_offset: Option<i32>,
+}
+
+impl From<ModGroup!["0.1.0"]> for ModGroup!["0.3.0"] {
+    fn from(legacy: ModGroup!["0.1.0"]) -> Self {
+        Self {
+            mods: legacy
+                .mods
+                .into_iter()
+                .map(ModOrGroup::Individual)
+                .collect(),
+            priority_override: legacy.priority_override,
+            priority_offset: None,
+        }
+    }
+}
+
+/// An enabled mod as it is installed, see [`ModData_v0_3_0::effective_mods`].
+#[derive(Debug, Clone)]
+pub struct EffectiveMod {
+    pub config: ModConfig,
+    /// Priority of the mod, or the override of the innermost folder containing it which has one,
+    /// plus [`Self::offset`].
+    pub priority: i32,
+    /// Sum of the priority offsets of the folders containing the mod.
+    pub offset: i32,
+    /// Innermost folder containing the mod, `None` at the top level.
+    pub folder: Option<String>,
+    /// Folder whose priority override applies.
+    pub priority_folder: Option<String>,
+}
+
+/// An enabled mod whose dependency is not enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenDependency {
+    pub dependent: ModSpecification,
+    pub dependency: ModSpecification,
+}
+
+/// Contents of a profile which exist in no other profile.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct UniqueContents {
+    pub folders: Vec<String>,
+    pub mods: Vec<ModSpecification>,
+}
+
+impl UniqueContents {
+    pub fn is_empty(&self) -> bool {
+        self.folders.is_empty() && self.mods.is_empty()
+    }
+}
+
+/// A mod present in both profiles of a merge, but with a different version or priority.
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    /// Identity of the mod as returned by the `key` function passed to the merge.
+    pub key: String,
+    pub target: ModConfig,
+    pub source: ModConfig,
+}
+
+/// Installs files from a mod's archive outside of the mod bundle, e.g. config files or scripts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadRule {
+    /// Directory inside the archive whose files are installed. Empty for the whole archive.
+    pub source: String,
+    /// Directory relative to the game directory (the one containing `FSD`) to install them to.
+    pub target: PathBuf,
+}
+
+/// A named snapshot of which mods and folders are enabled within a profile, so a profile can be
+/// switched between e.g. "screenshots" and "greenbeard-friendly" without being duplicated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Loadout {
+    /// Enabled state of each mod, keyed by mod URL.
+    pub mods: BTreeMap<String, bool>,
+    /// Enabled state of each folder, keyed by folder name.
+    #[serde(default)]
+    pub groups: BTreeMap<String, bool>,
+}
+
+/// What to do once the game exits, when mint launched it with the profile they belong to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostGameActions {
+    /// Snapshot the save files.
+    #[serde(default)]
+    pub snapshot_saves: bool,
+    /// Uninstall the mods, e.g. to play unmodded from Steam next time.
+    #[serde(default)]
+    pub uninstall: bool,
+    /// Profile to select afterwards.
+    #[serde(default)]
+    pub switch_profile: Option<String>,
+}
+
+impl PostGameActions {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 #[obake::versioned]
 #[obake(version("0.0.0"))]
 #[obake(version("0.1.0"))]
 #[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModProfile {
     #[obake(cfg("0.0.0"))]
@@ -60,12 +238,59 @@ pub struct ModProfile {
     /// A profile can contain ordered individual mods mixed with mod groups.
     #[obake(cfg("0.1.0"))]
     #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
     pub mods: Vec<ModOrGroup>,
     
     /// Per-profile folder storage (added in 0.2.0)
     #[obake(cfg("0.2.0"))]
     #[serde(default)]
-    pub groups: BTreeMap<String, ModGroup>,
+    pub groups: BTreeMap<String, ModGroup!["0.1.0"]>,
+    /// Folders may contain other folders (added in 0.3.0)
+    #[obake(cfg("0.3.0"))]
+    #[serde(default)]
+    pub groups: BTreeMap<String, ModGroup!["0.3.0"]>,
+
+    /// Game season or version the profile was made for, e.g. "Season 5".
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub season: Option<String>,
+
+    /// Archived profiles are hidden from the profile list until they are restored.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+
+    /// Favorites are listed first in the profile list.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+
+    /// Emoji shown before the profile's name.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    /// What the profile is for, shown when hovering it in the profile list.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Notes for the profile in CommonMark, e.g. install notes of a modpack.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme: Option<String>,
+
+    /// URL of the mod that wins each asset changed by more than one mod, regardless of priority,
+    /// by [`crate::integrate::asset_key`].
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub asset_overrides: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Hash, Serialize, Deserialize)]
@@ -75,6 +300,26 @@ pub enum ModOrGroup {
     Individual(ModConfig),
 }
 
+impl ModOrGroup {
+    pub fn as_mod(&self) -> Option<&ModConfig> {
+        match self {
+            ModOrGroup::Individual(mc) => Some(mc),
+            ModOrGroup::Group { .. } => None,
+        }
+    }
+
+    pub fn as_mod_mut(&mut self) -> Option<&mut ModConfig> {
+        match self {
+            ModOrGroup::Individual(mc) => Some(mc),
+            ModOrGroup::Group { .. } => None,
+        }
+    }
+
+    fn is_folder(&self, folder: &str) -> bool {
+        matches!(self, ModOrGroup::Group { group_name, .. } if group_name == folder)
+    }
+}
+
 impl From<ModProfile!["0.0.0"]> for ModProfile!["0.1.0"] {
     fn from(_legacy: ModProfile!["0.0.0"]) -> Self {
         // The migration requires `ModData` to handle instead.
@@ -87,14 +332,329 @@ impl From<ModProfile!["0.1.0"]> for ModProfile!["0.2.0"] {
         Self {
             mods: legacy.mods,
             groups: BTreeMap::new(), // Will be populated during ModData migration
+            season: None,
+            archived: false,
+            favorite: false,
+            icon: None,
+            description: None,
+            readme: None,
+        }
+    }
+}
+
+impl From<ModProfile!["0.2.0"]> for ModProfile!["0.3.0"] {
+    fn from(legacy: ModProfile!["0.2.0"]) -> Self {
+        Self {
+            mods: legacy.mods,
+            groups: legacy
+                .groups
+                .into_iter()
+                .map(|(name, group)| (name, group.into()))
+                .collect(),
+            season: legacy.season,
+            archived: legacy.archived,
+            favorite: legacy.favorite,
+            icon: legacy.icon,
+            description: legacy.description,
+            readme: legacy.readme,
+            asset_overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl ModProfile!["0.3.0"] {
+    /// Call `f` for each entry of the profile in order, descending into folders depth first, with
+    /// the folders containing the entry and whether each of them is enabled, outermost first. A
+    /// folder referenced more than once, e.g. nested in itself, is only descended into once.
+    pub fn visit<'a, F: FnMut(&'a ModOrGroup, &[(&'a str, bool)])>(&'a self, mut f: F) {
+        self.visit_entries(&self.mods, &mut vec![], &mut HashSet::new(), &mut f);
+    }
+
+    fn visit_entries<'a, F: FnMut(&'a ModOrGroup, &[(&'a str, bool)])>(
+        &'a self,
+        entries: &'a [ModOrGroup],
+        path: &mut Vec<(&'a str, bool)>,
+        visited: &mut HashSet<&'a str>,
+        f: &mut F,
+    ) {
+        for entry in entries {
+            f(entry, path);
+            if let ModOrGroup::Group {
+                group_name,
+                enabled,
+            } = entry
+                && visited.insert(group_name.as_str())
+                && let Some(group) = self.groups.get(group_name)
+            {
+                path.push((group_name.as_str(), *enabled));
+                self.visit_entries(&group.mods, path, visited, f);
+                path.pop();
+            }
+        }
+    }
+
+    /// Whether each folder reachable from the top level is enabled, taking the folders containing
+    /// it into account.
+    pub fn folder_states(&self) -> HashMap<String, bool> {
+        let mut states = HashMap::new();
+        self.visit(|entry, folders| {
+            if let ModOrGroup::Group {
+                group_name,
+                enabled,
+            } = entry
+            {
+                states
+                    .entry(group_name.clone())
+                    .or_insert_with(|| *enabled && folders.iter().all(|(_, e)| *e));
+            }
+        });
+        states
+    }
+
+    /// Every entry of the profile, at the top level and inside folders, in no particular order.
+    pub fn all_entries(&self) -> impl Iterator<Item = &ModOrGroup> {
+        self.mods
+            .iter()
+            .chain(self.groups.values().flat_map(|g| &g.mods))
+    }
+
+    pub fn all_entries_mut(&mut self) -> impl Iterator<Item = &mut ModOrGroup> {
+        self.entry_lists_mut().flatten()
+    }
+
+    /// The top level and the contents of every folder.
+    fn entry_lists_mut(&mut self) -> impl Iterator<Item = &mut Vec<ModOrGroup>> {
+        std::iter::once(&mut self.mods).chain(self.groups.values_mut().map(|g| &mut g.mods))
+    }
+
+    /// Entries of `folder`, or of the top level for `None`.
+    pub fn entries_mut(&mut self, folder: Option<&str>) -> Option<&mut Vec<ModOrGroup>> {
+        match folder {
+            Some(folder) => self.groups.get_mut(folder).map(|g| &mut g.mods),
+            None => Some(&mut self.mods),
+        }
+    }
+
+    /// The folder `folder` is shown in, `None` if it is at the top level or not shown at all.
+    pub fn parent_folder(&self, folder: &str) -> Option<&str> {
+        let mut parent = None;
+        let mut found = false;
+        self.visit(|entry, folders| {
+            if !found && entry.is_folder(folder) {
+                found = true;
+                parent = folders.last().map(|(f, _)| *f);
+            }
+        });
+        parent
+    }
+
+    /// Whether `folder` is `ancestor` or nested in it at any depth.
+    pub fn is_within(&self, folder: &str, ancestor: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(folder);
+        while let Some(f) = current.filter(|f| visited.insert(*f)) {
+            if f == ancestor {
+                return true;
+            }
+            current = self.parent_folder(f);
         }
+        false
     }
+
+    /// Set whether `folder` is enabled, wherever it is.
+    pub fn set_folder_enabled(&mut self, folder: &str, enabled: bool) {
+        for entry in self.all_entries_mut() {
+            if let ModOrGroup::Group {
+                group_name,
+                enabled: e,
+            } = entry
+                && group_name == folder
+            {
+                *e = enabled;
+            }
+        }
+    }
+
+    /// Move `folder` to the end of `into`, or of the top level for `None`. Returns false if `into`
+    /// does not exist or is `folder` itself or nested in it.
+    pub fn move_folder(&mut self, folder: &str, into: Option<&str>) -> bool {
+        if !self.groups.contains_key(folder)
+            || into
+                .is_some_and(|into| !self.groups.contains_key(into) || self.is_within(into, folder))
+        {
+            return false;
+        }
+        let mut enabled = true;
+        for entries in self.entry_lists_mut() {
+            entries.retain(|m| match m {
+                ModOrGroup::Group {
+                    group_name,
+                    enabled: e,
+                } if group_name == folder => {
+                    enabled = *e;
+                    false
+                }
+                _ => true,
+            });
+        }
+        self.entries_mut(into).unwrap().push(ModOrGroup::Group {
+            group_name: folder.to_string(),
+            enabled,
+        });
+        true
+    }
+
+    /// Delete `folder`. Its mods and nested folders take its place in the folder containing it.
+    pub fn remove_folder(&mut self, folder: &str) {
+        let parent = self.parent_folder(folder).map(str::to_string);
+        let Some(group) = self.groups.remove(folder) else {
+            return;
+        };
+        let Some(entries) = self.entries_mut(parent.as_deref()) else {
+            return;
+        };
+        match entries.iter().position(|m| m.is_folder(folder)) {
+            Some(index) => {
+                entries.splice(index..=index, group.mods);
+            }
+            None => entries.extend(group.mods),
+        }
+        for entries in self.entry_lists_mut() {
+            entries.retain(|m| !m.is_folder(folder));
+        }
+    }
+
+    /// The mods with their URL in `urls`, wherever they are.
+    pub fn mods_with_urls_mut(
+        &mut self,
+        urls: &HashSet<String>,
+    ) -> impl Iterator<Item = &mut ModConfig> {
+        self.all_entries_mut()
+            .filter_map(ModOrGroup::as_mod_mut)
+            .filter(|mc| urls.contains(&mc.spec.url))
+    }
+
+    /// Remove the mods with their URL in `urls`, wherever they are. Returns the removed mods.
+    pub fn remove_mods(&mut self, urls: &HashSet<String>) -> Vec<ModConfig> {
+        let mut removed = vec![];
+        for entries in self.entry_lists_mut() {
+            entries.retain(|m| match m {
+                ModOrGroup::Individual(mc) if urls.contains(&mc.spec.url) => {
+                    removed.push(mc.clone());
+                    false
+                }
+                _ => true,
+            });
+        }
+        removed
+    }
+
+    /// Move the mods with their URL in `urls` to the end of `into`, or of the top level for
+    /// `None`, keeping their order. Returns false if `into` does not exist.
+    pub fn move_mods(&mut self, urls: &HashSet<String>, into: Option<&str>) -> bool {
+        if into.is_some_and(|into| !self.groups.contains_key(into)) {
+            return false;
+        }
+        let moved = self.remove_mods(urls);
+        self.entries_mut(into)
+            .unwrap()
+            .extend(moved.into_iter().map(ModOrGroup::Individual));
+        true
+    }
+
+    /// Create the folders of `path` which don't exist yet, each inside the one before it or at the
+    /// top level, with the priority settings of the folder of the same name in `from`. Returns the
+    /// innermost folder.
+    fn add_folder_path<'a>(&mut self, path: &[(&'a str, bool)], from: &Self) -> Option<&'a str> {
+        let mut parent = None;
+        for (folder, enabled) in path {
+            if !self.groups.contains_key(*folder) {
+                let source = from.groups.get(*folder);
+                self.groups.insert(
+                    folder.to_string(),
+                    ModGroup_v0_3_0 {
+                        mods: vec![],
+                        priority_override: source.and_then(|g| g.priority_override),
+                        priority_offset: source.and_then(|g| g.priority_offset),
+                    },
+                );
+                self.entries_mut(parent).unwrap().push(ModOrGroup::Group {
+                    group_name: folder.to_string(),
+                    enabled: *enabled,
+                });
+            }
+            parent = Some(*folder);
+        }
+        parent
+    }
+
+    /// Sum of the priority offsets of `folders`, e.g. those containing a mod as passed to
+    /// [`Self::visit`].
+    pub fn priority_offset(&self, folders: &[(&str, bool)]) -> i32 {
+        folders
+            .iter()
+            .filter_map(|(folder, _)| self.groups.get(*folder)?.priority_offset)
+            .sum()
+    }
+
+    /// Rename `old` to `new`, wherever it is.
+    pub fn rename_folder(&mut self, old: &str, new: &str) {
+        if let Some(group) = self.groups.remove(old) {
+            self.groups.insert(new.to_string(), group);
+        }
+        for entry in self.all_entries_mut() {
+            if let ModOrGroup::Group { group_name, .. } = entry
+                && group_name == old
+            {
+                *group_name = new.to_string();
+            }
+        }
+    }
+}
+
+/// How long deleted profiles are kept in the trash.
+pub const TRASH_DAYS: i64 = 30;
+
+/// A deleted profile with everything kept for it by name, restorable for [`TRASH_DAYS`] days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedProfile {
+    pub name: String,
+    /// When it was deleted, RFC 3339.
+    pub deleted: String,
+    pub profile: ModProfile!["0.3.0"],
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loadouts: BTreeMap<String, Loadout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_game: Option<PostGameActions>,
+}
+
+impl TrashedProfile {
+    fn expired(&self, now: chrono::DateTime<chrono::Local>) -> bool {
+        chrono::DateTime::parse_from_rfc3339(&self.deleted).is_ok_and(|deleted| {
+            now.signed_duration_since(deleted) > chrono::Duration::days(TRASH_DAYS)
+        })
+    }
+}
+
+/// Extension of files written by [`ModData::export_profile`].
+pub const PROFILE_EXTENSION: &str = "mintprofile";
+
+/// A profile with everything kept for it by name, as saved to a `.mintprofile` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedProfile {
+    pub name: String,
+    pub profile: ModProfile!["0.3.0"],
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loadouts: BTreeMap<String, Loadout>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_game: Option<PostGameActions>,
 }
 
 #[obake::versioned]
 #[obake(version("0.0.0"))]
 #[obake(version("0.1.0"))]
 #[obake(version("0.2.0"))]
+#[obake(version("0.3.0"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModData {
     pub active_profile: String,
@@ -104,12 +664,45 @@ pub struct ModData {
     pub profiles: BTreeMap<String, ModProfile!["0.1.0"]>,
     #[obake(cfg("0.2.0"))]
     pub profiles: BTreeMap<String, ModProfile!["0.2.0"]>,
+    #[obake(cfg("0.3.0"))]
+    pub profiles: BTreeMap<String, ModProfile!["0.3.0"]>,
     /// Global groups storage (legacy, removed in 0.2.0)
     #[obake(cfg("0.1.0"))]
-    pub groups: BTreeMap<String, ModGroup>,
+    pub groups: BTreeMap<String, ModGroup!["0.1.0"]>,
+    /// Named loadouts, keyed by profile name and then by loadout name.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub loadouts: BTreeMap<String, BTreeMap<String, Loadout>>,
+    /// Where to install files of a mod which do not belong in the pak, keyed by mod URL.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub payload_rules: BTreeMap<String, Vec<PayloadRule>>,
+    /// Names, tags and dependencies set by the user for mods without provider metadata, keyed by
+    /// mod URL.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub local_metadata: BTreeMap<String, LocalMetadata>,
+    /// What to do once the game exits, keyed by profile name.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub post_game: BTreeMap<String, PostGameActions>,
+    /// Manual order of the profile list, by name. Profiles missing from it follow alphabetically.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profile_order: Vec<String>,
+    /// Deleted profiles, oldest first.
+    #[obake(cfg("0.2.0"))]
+    #[obake(cfg("0.3.0"))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<TrashedProfile>,
 }
 
-impl ModData!["0.2.0"] {
+impl ModData!["0.3.0"] {
     pub fn for_each_mod_predicate<
         F: FnMut(&ModConfig),
         G: FnMut(bool /* mod group enabled? */) -> bool,
@@ -122,29 +715,14 @@ impl ModData!["0.2.0"] {
         mut p: P,
     ) {
         let prof = self.profiles.get(profile).unwrap();
-        for ref mod_or_group in &prof.mods {
-            match mod_or_group {
-                ModOrGroup::Group {
-                    group_name,
-                    enabled,
-                } => {
-                    if g(*enabled) {
-                        if let Some(group) = prof.groups.get(group_name) {
-                            for mc in &group.mods {
-                                if p(mc) {
-                                    f(mc);
-                                }
-                            }
-                        }
-                    }
-                }
-                ModOrGroup::Individual(mc) => {
-                    if p(mc) {
-                        f(mc);
-                    }
-                }
+        prof.visit(|entry, folders| {
+            if let ModOrGroup::Individual(mc) = entry
+                && (folders.is_empty() || g(folders.iter().all(|(_, enabled)| *enabled)))
+                && p(mc)
+            {
+                f(mc);
             }
-        }
+        });
     }
 
     pub fn for_each_mod_predicate_mut<
@@ -159,34 +737,26 @@ impl ModData!["0.2.0"] {
         mut p: P,
     ) {
         let prof = self.profiles.get_mut(profile).unwrap();
-        // Need to iterate mods and groups separately due to borrow checker
-        let group_refs: Vec<_> = prof.mods.iter().filter_map(|m| {
-            if let ModOrGroup::Group { group_name, enabled } = m {
-                Some((group_name.clone(), *enabled))
-            } else {
-                None
-            }
-        }).collect();
-        
+        // Need to know which folders are enabled before borrowing their mods mutably
+        let folders_enabled = prof.folder_states();
+
         // Process groups
-        for (group_name, enabled) in group_refs {
-            if g(enabled) {
-                if let Some(group) = prof.groups.get_mut(&group_name) {
-                    for mc in &mut group.mods {
-                        if p(mc) {
-                            f(mc);
-                        }
+        for (group_name, group) in &mut prof.groups {
+            if let Some(enabled) = folders_enabled.get(group_name)
+                && g(*enabled)
+            {
+                for mc in group.mods.iter_mut().filter_map(ModOrGroup::as_mod_mut) {
+                    if p(mc) {
+                        f(mc);
                     }
                 }
             }
         }
-        
+
         // Process individual mods
-        for mod_or_group in &mut prof.mods {
-            if let ModOrGroup::Individual(mc) = mod_or_group {
-                if p(mc) {
-                    f(mc);
-                }
+        for mc in prof.mods.iter_mut().filter_map(ModOrGroup::as_mod_mut) {
+            if p(mc) {
+                f(mc);
             }
         }
     }
@@ -202,33 +772,84 @@ impl ModData!["0.2.0"] {
     /// Returns enabled mods with their effective priority (considering folder overrides)
     /// Returns Vec of (ModConfig clone, effective_priority)
     pub fn get_enabled_mods_with_priority(&self, profile: &str) -> Vec<(ModConfig, i32)> {
+        self.effective_mods(profile)
+            .into_iter()
+            .map(|m| (m.config, m.priority))
+            .collect()
+    }
+
+    /// Enabled mods of `profile` in profile order, with their effective priority and the folders
+    /// it comes from.
+    pub fn effective_mods(&self, profile: &str) -> Vec<EffectiveMod> {
         let mut result = Vec::new();
         let prof = self.profiles.get(profile).unwrap();
-        for mod_or_group in &prof.mods {
-            match mod_or_group {
-                ModOrGroup::Group { group_name, enabled } => {
-                    if *enabled {
-                        if let Some(group) = prof.groups.get(group_name) {
-                            let override_priority = group.priority_override;
-                            for mc in &group.mods {
-                                if mc.enabled {
-                                    let effective_priority = override_priority.unwrap_or(mc.priority);
-                                    result.push((mc.clone(), effective_priority));
-                                }
-                            }
-                        }
-                    }
-                }
-                ModOrGroup::Individual(mc) => {
-                    if mc.enabled {
-                        result.push((mc.clone(), mc.priority));
-                    }
-                }
+        prof.visit(|entry, folders| {
+            if let ModOrGroup::Individual(mc) = entry
+                && mc.enabled
+                && folders.iter().all(|(_, enabled)| *enabled)
+            {
+                // the override of the innermost folder which has one applies
+                let override_priority = folders.iter().rev().find_map(|(folder, _)| {
+                    Some((*folder, prof.groups.get(*folder)?.priority_override?))
+                });
+                let offset = prof.priority_offset(folders);
+                let priority = override_priority.map_or(mc.priority, |(_, priority)| priority);
+                result.push(EffectiveMod {
+                    config: mc.clone(),
+                    priority: priority + offset,
+                    offset,
+                    folder: folders.last().map(|(folder, _)| folder.to_string()),
+                    priority_folder: override_priority.map(|(folder, _)| folder.to_string()),
+                });
             }
-        }
+        });
         result
     }
 
+    /// Priorities for the enabled mods of `profile` resolving the `conflicts` between them, see
+    /// [`load_order::suggest_priorities`].
+    pub fn suggest_priorities<'a>(
+        &self,
+        profile: &str,
+        conflicts: impl IntoIterator<Item = &'a IndexSet<ModSpecification>>,
+    ) -> Vec<PriorityChange> {
+        if !self.profiles.contains_key(profile) {
+            return vec![];
+        }
+        let effective = self.effective_mods(profile);
+        let mods = effective
+            .iter()
+            .map(|m| PrioritizedMod {
+                spec: m.config.spec.clone(),
+                priority: m.priority,
+                fixed: m.priority_folder.is_some(),
+            })
+            .collect::<Vec<_>>();
+        let offsets = effective
+            .iter()
+            .map(|m| (&m.config.spec, m.offset))
+            .collect::<HashMap<_, _>>();
+        // suggested for the effective priorities, but applied to the mods' own
+        let mut changes = load_order::suggest_priorities(&mods, conflicts);
+        for change in &mut changes {
+            let offset = offsets.get(&change.spec).copied().unwrap_or_default();
+            change.from -= offset;
+            change.to -= offset;
+        }
+        changes
+    }
+
+    /// Returns specs of enabled mods ordered by descending effective priority, i.e. the order in
+    /// which they should be integrated.
+    pub fn enabled_mods_by_priority(&self, profile: &str) -> Vec<ModSpecification> {
+        let mut mods_with_priority = self.get_enabled_mods_with_priority(profile);
+        mods_with_priority.sort_by_key(|(_, priority)| -priority);
+        mods_with_priority
+            .into_iter()
+            .map(|(config, _)| config.spec)
+            .collect()
+    }
+
     pub fn for_each_mod_mut<F: FnMut(&mut ModConfig)>(&mut self, profile: &str, f: F) {
         self.for_each_mod_predicate_mut(profile, f, |_| true, |_| true)
     }
@@ -239,22 +860,18 @@ impl ModData!["0.2.0"] {
         mut f: F,
     ) -> bool {
         let prof = self.profiles.get(profile).unwrap();
-        prof.mods.iter().any(|m| {
-            let f = &mut f;
-            match m {
-                ModOrGroup::Individual(mc) => f(mc, None),
-                ModOrGroup::Group {
-                    group_name,
-                    enabled,
-                } => prof
-                    .groups
-                    .get(group_name)
-                    .map(|g| g.mods.iter().any(|mc| f(mc, Some(*enabled))))
-                    .unwrap_or(false),
+        let mut found = false;
+        prof.visit(|entry, folders| {
+            if !found && let ModOrGroup::Individual(mc) = entry {
+                let enabled = (!folders.is_empty()).then(|| folders.iter().all(|(_, e)| *e));
+                found = f(mc, enabled);
             }
-        })
+        });
+        found
     }
 
+    /// Like [`Self::any_mod`], but for a mod inside folders the flag passed to `f` is whether all
+    /// of them are enabled; setting it enables every one of them.
     pub fn any_mod_mut<
         F: FnMut(&mut ModConfig, Option<&mut bool> /* mod group enabled? */) -> bool,
     >(
@@ -263,42 +880,44 @@ impl ModData!["0.2.0"] {
         mut f: F,
     ) -> bool {
         let prof = self.profiles.get_mut(profile).unwrap();
-        // Collect group names first to avoid borrow issues
-        let group_names: Vec<_> = prof.mods.iter().filter_map(|m| {
-            if let ModOrGroup::Group { group_name, .. } = m {
-                Some(group_name.clone())
-            } else {
-                None
-            }
-        }).collect();
-        
+        // Collect each folder with the folders containing it first to avoid borrow issues
+        let mut folder_paths: Vec<(String, Vec<String>)> = vec![];
+        prof.visit(|entry, folders| {
+            if let ModOrGroup::Group { group_name, .. } = entry
+                && !folder_paths.iter().any(|(name, _)| name == group_name)
+            {
+                let path = folders.iter().map(|(f, _)| f.to_string());
+                folder_paths.push((group_name.clone(), path.collect()));
+            }
+        });
+        let folders_enabled = prof.folder_states();
+
         // Check individual mods
-        for m in &mut prof.mods {
-            if let ModOrGroup::Individual(mc) = m {
-                if f(mc, None) {
-                    return true;
-                }
+        for mc in prof.mods.iter_mut().filter_map(ModOrGroup::as_mod_mut) {
+            if f(mc, None) {
+                return true;
             }
         }
-        
-        // Check group mods (need to get enabled state from mods vec)
-        for group_name in group_names {
-            let enabled = prof.mods.iter_mut().find_map(|m| {
-                if let ModOrGroup::Group { group_name: gn, enabled } = m {
-                    if gn == &group_name { Some(enabled) } else { None }
-                } else {
-                    None
-                }
-            });
-            
-            if let Some(enabled) = enabled {
-                if let Some(group) = prof.groups.get_mut(&group_name) {
-                    for mc in &mut group.mods {
-                        if f(mc, Some(enabled)) {
-                            return true;
-                        }
+
+        // Check group mods
+        for (group_name, path) in folder_paths {
+            let was_enabled = folders_enabled.get(&group_name).copied().unwrap_or(false);
+            let mut enabled = was_enabled;
+            let Some(group) = prof.groups.get_mut(&group_name) else {
+                continue;
+            };
+            if group
+                .mods
+                .iter_mut()
+                .filter_map(ModOrGroup::as_mod_mut)
+                .any(|mc| f(mc, Some(&mut enabled)))
+            {
+                if enabled && !was_enabled {
+                    for folder in path.iter().chain([&group_name]) {
+                        prof.set_folder_enabled(folder, true);
                     }
                 }
+                return true;
             }
         }
         false
@@ -359,6 +978,12 @@ impl From<ModData!["0.1.0"]> for ModData!["0.2.0"] {
             let new_profile = ModProfile_v0_2_0 {
                 mods: profile.mods,
                 groups: profile_groups,
+                season: None,
+                archived: false,
+                favorite: false,
+                icon: None,
+                description: None,
+                readme: None,
             };
             new_profiles.insert(name, new_profile);
         }
@@ -366,17 +991,48 @@ impl From<ModData!["0.1.0"]> for ModData!["0.2.0"] {
         Self {
             active_profile: legacy.active_profile,
             profiles: new_profiles,
+            loadouts: BTreeMap::new(),
+            payload_rules: BTreeMap::new(),
+            local_metadata: BTreeMap::new(),
+            post_game: BTreeMap::new(),
+            profile_order: vec![],
+            trash: vec![],
         }
     }
 }
 
-impl Default for ModData!["0.2.0"] {
+impl From<ModData!["0.2.0"]> for ModData!["0.3.0"] {
+    fn from(legacy: ModData!["0.2.0"]) -> Self {
+        Self {
+            active_profile: legacy.active_profile,
+            profiles: legacy
+                .profiles
+                .into_iter()
+                .map(|(name, profile)| (name, profile.into()))
+                .collect(),
+            loadouts: legacy.loadouts,
+            payload_rules: legacy.payload_rules,
+            local_metadata: legacy.local_metadata,
+            post_game: legacy.post_game,
+            profile_order: legacy.profile_order,
+            trash: legacy.trash,
+        }
+    }
+}
+
+impl Default for ModData!["0.3.0"] {
     fn default() -> Self {
         Self {
             active_profile: "default".to_string(),
             profiles: [("default".to_string(), Default::default())]
                 .into_iter()
                 .collect(),
+            loadouts: BTreeMap::new(),
+            payload_rules: BTreeMap::new(),
+            local_metadata: BTreeMap::new(),
+            post_game: BTreeMap::new(),
+            profile_order: vec![],
+            trash: vec![],
         }
     }
 }
@@ -390,6 +1046,8 @@ pub enum VersionAnnotatedModData {
     V0_1_0(ModData!["0.1.0"]),
     #[serde(rename = "0.2.0")]
     V0_2_0(ModData!["0.2.0"]),
+    #[serde(rename = "0.3.0")]
+    V0_3_0(ModData!["0.3.0"]),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -418,18 +1076,19 @@ impl Default for MaybeVersionedModData {
 
 impl Default for VersionAnnotatedModData {
     fn default() -> Self {
-        VersionAnnotatedModData::V0_2_0(Default::default())
+        VersionAnnotatedModData::V0_3_0(Default::default())
     }
 }
 
 impl Deref for VersionAnnotatedModData {
-    type Target = ModData!["0.2.0"];
+    type Target = ModData!["0.3.0"];
 
     fn deref(&self) -> &Self::Target {
         match self {
             VersionAnnotatedModData::V0_0_0(_) => unreachable!(),
             VersionAnnotatedModData::V0_1_0(_) => unreachable!(),
-            VersionAnnotatedModData::V0_2_0(md) => md,
+            VersionAnnotatedModData::V0_2_0(_) => unreachable!(),
+            VersionAnnotatedModData::V0_3_0(md) => md,
         }
     }
 }
@@ -439,23 +1098,683 @@ impl DerefMut for VersionAnnotatedModData {
         match self {
             VersionAnnotatedModData::V0_0_0(_) => unreachable!(),
             VersionAnnotatedModData::V0_1_0(_) => unreachable!(),
-            VersionAnnotatedModData::V0_2_0(md) => md,
+            VersionAnnotatedModData::V0_2_0(_) => unreachable!(),
+            VersionAnnotatedModData::V0_3_0(md) => md,
         }
     }
 }
 
-impl ModData!["0.2.0"] {
-    pub fn get_active_profile(&self) -> &ModProfile!["0.2.0"] {
+impl ModData!["0.3.0"] {
+    pub fn get_active_profile(&self) -> &ModProfile!["0.3.0"] {
         &self.profiles[&self.active_profile]
     }
 
-    pub fn get_active_profile_mut(&mut self) -> &mut ModProfile!["0.2.0"] {
+    pub fn get_active_profile_mut(&mut self) -> &mut ModProfile!["0.3.0"] {
         self.profiles.get_mut(&self.active_profile).unwrap()
     }
 
     pub fn remove_active_profile(&mut self) {
-        self.profiles.remove(&self.active_profile);
-        self.active_profile = self.profiles.keys().next().unwrap().to_string();
+        self.trash_profile(&self.active_profile.clone());
+    }
+
+    /// Copy `profile` with its folders, loadouts and post-game actions to `new_name` and select
+    /// the copy. If the profile list is ordered by hand, the copy follows the original.
+    pub fn duplicate_profile(&mut self, profile: &str, new_name: String) {
+        let Some(mut copy) = self.profiles.get(profile).cloned() else {
+            return;
+        };
+        // it is selected, so it would be shown anyway
+        copy.archived = false;
+        self.profiles.insert(new_name.clone(), copy);
+        if let Some(loadouts) = self.loadouts.get(profile).cloned() {
+            self.loadouts.insert(new_name.clone(), loadouts);
+        }
+        if let Some(actions) = self.post_game.get(profile).cloned() {
+            self.post_game.insert(new_name.clone(), actions);
+        }
+        if let Some(i) = self.profile_order.iter().position(|p| p == profile) {
+            self.profile_order.insert(i + 1, new_name.clone());
+        }
+        self.active_profile = new_name;
+    }
+
+    /// Move `profile` into the trash, selecting another profile if it is the active one.
+    pub fn trash_profile(&mut self, profile: &str) {
+        let Some(removed) = self.profiles.remove(profile) else {
+            return;
+        };
+        self.trash.push(TrashedProfile {
+            name: profile.to_string(),
+            deleted: chrono::Local::now().to_rfc3339(),
+            profile: removed,
+            loadouts: self.loadouts.remove(profile).unwrap_or_default(),
+            post_game: self.post_game.remove(profile),
+        });
+        if self.active_profile == profile {
+            self.active_profile = self.fallback_profile(None);
+        }
+    }
+
+    /// Restore the profile at `index` in the trash, under a new name if its name was taken since.
+    /// Returns its name.
+    pub fn restore_trashed(&mut self, index: usize) -> Option<String> {
+        if index >= self.trash.len() {
+            return None;
+        }
+        let trashed = self.trash.remove(index);
+        let name = self.unused_profile_name(&trashed.name);
+        self.profiles.insert(name.clone(), trashed.profile);
+        if !trashed.loadouts.is_empty() {
+            self.loadouts.insert(name.clone(), trashed.loadouts);
+        }
+        if let Some(actions) = trashed.post_game {
+            self.post_game.insert(name.clone(), actions);
+        }
+        Some(name)
+    }
+
+    /// `name`, or `name (2)`, `name (3)`, ... if it is taken.
+    fn unused_profile_name(&self, name: &str) -> String {
+        let mut unused = name.to_string();
+        let mut n = 1;
+        while self.profiles.contains_key(&unused) {
+            n += 1;
+            unused = format!("{name} ({n})");
+        }
+        unused
+    }
+
+    /// Write `profile` with its folders, loadouts and after-game actions to a `.mintprofile` file.
+    pub fn export_profile(&self, profile: &str, path: &Path) -> Result<(), StateError> {
+        let exported = ExportedProfile {
+            name: profile.to_string(),
+            profile: self
+                .profiles
+                .get(profile)
+                .context(NoSuchProfileSnafu { name: profile })?
+                .clone(),
+            loadouts: self.loadouts.get(profile).cloned().unwrap_or_default(),
+            post_game: self.post_game.get(profile).cloned(),
+        };
+        let buf = serde_json::to_vec_pretty(&exported).context(ProfileSerializationFailedSnafu)?;
+        fs::write(path, buf)?;
+        Ok(())
+    }
+
+    /// Add the profile of a `.mintprofile` file, numbering its name if it is taken. Returns the
+    /// name it was added under.
+    pub fn import_profile(&mut self, path: &Path) -> Result<String, StateError> {
+        let exported: ExportedProfile =
+            serde_json::from_slice(&fs::read(path)?).context(ProfileDeserializationFailedSnafu)?;
+        let name = self.unused_profile_name(&exported.name);
+        self.profiles.insert(name.clone(), exported.profile);
+        if !exported.loadouts.is_empty() {
+            self.loadouts.insert(name.clone(), exported.loadouts);
+        }
+        if let Some(mut actions) = exported.post_game {
+            // the profile to switch to may not exist on this machine
+            actions
+                .switch_profile
+                .take_if(|p| !self.profiles.contains_key(p.as_str()));
+            self.post_game.insert(name.clone(), actions);
+        }
+        Ok(name)
+    }
+
+    /// Drop profiles deleted more than [`TRASH_DAYS`] days ago. Returns whether any were.
+    pub fn purge_trash(&mut self) -> bool {
+        let now = chrono::Local::now();
+        let len = self.trash.len();
+        self.trash.retain(|t| !t.expired(now));
+        self.trash.len() != len
+    }
+
+    /// Profile names in the order of the profile list: favorites first, then by the manual order,
+    /// then the rest alphabetically.
+    pub fn ordered_profiles(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut names = self
+            .profile_order
+            .iter()
+            .chain(self.profiles.keys())
+            .filter(|name| self.profiles.contains_key(*name) && seen.insert(*name))
+            .cloned()
+            .collect::<Vec<_>>();
+        names.sort_by_key(|name| !self.profiles[name].favorite);
+        names
+    }
+
+    /// A profile to select instead of `except`, preferring one which is not archived.
+    pub fn fallback_profile(&self, except: Option<&str>) -> String {
+        let others = || {
+            self.profiles
+                .iter()
+                .filter(move |(name, _)| Some(name.as_str()) != except)
+        };
+        others()
+            .find(|(_, p)| !p.archived)
+            .or_else(|| others().next())
+            .map(|(name, _)| name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Archive or restore `profile`. Archiving the active profile selects another one. The last
+    /// profile which is not archived can't be archived; returns whether the state changed.
+    pub fn set_archived(&mut self, profile: &str, archived: bool) -> bool {
+        let others_active = self
+            .profiles
+            .iter()
+            .any(|(name, p)| name != profile && !p.archived);
+        let Some(prof) = self.profiles.get_mut(profile) else {
+            return false;
+        };
+        if prof.archived == archived || (archived && !others_active) {
+            return false;
+        }
+        prof.archived = archived;
+        if archived && self.active_profile == profile {
+            self.active_profile = self.fallback_profile(Some(profile));
+        }
+        true
+    }
+
+    /// Archive every profile tagged with `season`. Returns how many were archived.
+    pub fn archive_season(&mut self, season: &str) -> usize {
+        let names = self
+            .profiles
+            .iter()
+            .filter(|(_, p)| p.season.as_deref() == Some(season))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        names
+            .iter()
+            .filter(|name| self.set_archived(name, true))
+            .count()
+    }
+
+    /// Seasons profiles are tagged with.
+    pub fn seasons(&self) -> BTreeSet<String> {
+        self.profiles
+            .values()
+            .filter_map(|p| p.season.clone())
+            .collect()
+    }
+
+    /// Tags given to mods of `profile`.
+    pub fn mod_tags(&self, profile: &str) -> BTreeSet<String> {
+        let mut tags = BTreeSet::new();
+        self.for_each_mod(profile, |mc| tags.extend(mc.tags.iter().cloned()));
+        tags
+    }
+
+    /// Enable or disable every mod of `profile` tagged with `tag`. Mods in disabled folders stay
+    /// off until their folder is enabled. Returns how many mods were changed.
+    pub fn set_tag_enabled(&mut self, profile: &str, tag: &str, enabled: bool) -> usize {
+        let mut changed = 0;
+        self.for_each_mod_mut(profile, |mc| {
+            if mc.enabled != enabled && mc.tags.contains(tag) {
+                mc.enabled = enabled;
+                changed += 1;
+            }
+        });
+        changed
+    }
+
+    /// Snapshot the current enabled state of every mod and folder in `profile` as a loadout,
+    /// replacing any existing loadout of the same name.
+    pub fn save_loadout(&mut self, profile: &str, name: String) {
+        let Some(prof) = self.profiles.get(profile) else {
+            return;
+        };
+        let mut loadout = Loadout::default();
+        for mod_or_group in prof.all_entries() {
+            match mod_or_group {
+                ModOrGroup::Individual(mc) => {
+                    loadout.mods.insert(mc.spec.url.clone(), mc.enabled);
+                }
+                ModOrGroup::Group {
+                    group_name,
+                    enabled,
+                } => {
+                    loadout.groups.insert(group_name.clone(), *enabled);
+                }
+            }
+        }
+        self.loadouts
+            .entry(profile.to_string())
+            .or_default()
+            .insert(name, loadout);
+    }
+
+    /// Apply a previously saved loadout to `profile`. Mods and folders which were added after the
+    /// loadout was saved keep their current state. Returns false if no such loadout exists.
+    pub fn apply_loadout(&mut self, profile: &str, name: &str) -> bool {
+        let Some(loadout) = self.loadouts.get(profile).and_then(|l| l.get(name)) else {
+            return false;
+        };
+        let Some(prof) = self.profiles.get_mut(profile) else {
+            return false;
+        };
+        for mod_or_group in prof.all_entries_mut() {
+            match mod_or_group {
+                ModOrGroup::Individual(mc) => {
+                    if let Some(enabled) = loadout.mods.get(&mc.spec.url) {
+                        mc.enabled = *enabled;
+                    }
+                }
+                ModOrGroup::Group {
+                    group_name,
+                    enabled,
+                } => {
+                    if let Some(e) = loadout.groups.get(group_name) {
+                        *enabled = *e;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    pub fn remove_loadout(&mut self, profile: &str, name: &str) {
+        if let Some(loadouts) = self.loadouts.get_mut(profile) {
+            loadouts.remove(name);
+            if loadouts.is_empty() {
+                self.loadouts.remove(profile);
+            }
+        }
+    }
+
+    /// Dependencies broken by turning `folder` of `profile` on or off: when disabling it, those of
+    /// mods outside the folder on mods inside it; when enabling it, those of mods inside it on mods
+    /// which are not enabled. Mods in folders nested in `folder` count as inside it. `dependencies`
+    /// looks up what a mod depends on.
+    pub fn folder_broken_dependencies<D>(
+        &self,
+        profile: &str,
+        folder: &str,
+        enabled: bool,
+        dependencies: D,
+    ) -> Vec<BrokenDependency>
+    where
+        D: Fn(&ModSpecification) -> Vec<ModSpecification>,
+    {
+        let Some(prof) = self.profiles.get(profile) else {
+            return vec![];
+        };
+        let mut inside = vec![];
+        let mut outside = vec![];
+        prof.visit(|entry, folders| {
+            if let ModOrGroup::Individual(mc) = entry
+                && mc.enabled
+                && folders.iter().all(|(f, enabled)| *enabled || *f == folder)
+            {
+                if folders.iter().any(|(f, _)| *f == folder) {
+                    inside.push(&mc.spec);
+                } else {
+                    outside.push(&mc.spec);
+                }
+            }
+        });
+        let satisfied = |specs: &[&ModSpecification], dep: &ModSpecification| {
+            specs.iter().any(|s| s.satisfies_dependency(dep))
+        };
+
+        let dependents = if enabled { &inside } else { &outside };
+        let mut broken = vec![];
+        for spec in dependents {
+            for dependency in dependencies(spec) {
+                let is_broken = if enabled {
+                    !satisfied(&inside, &dependency) && !satisfied(&outside, &dependency)
+                } else {
+                    satisfied(&inside, &dependency) && !satisfied(&outside, &dependency)
+                };
+                if is_broken {
+                    broken.push(BrokenDependency {
+                        dependent: (*spec).clone(),
+                        dependency,
+                    });
+                }
+            }
+        }
+        broken
+    }
+
+    /// Every mod of `profile` in order, with whether it is enabled taking its folders into
+    /// account, as [`dependencies::DependencyGraph::build`] takes them.
+    pub fn mod_states(&self, profile: &str) -> Vec<(ModSpecification, bool)> {
+        let mut mods = vec![];
+        if let Some(prof) = self.profiles.get(profile) {
+            prof.visit(|entry, folders| {
+                if let ModOrGroup::Individual(mc) = entry {
+                    let enabled = mc.enabled && folders.iter().all(|(_, enabled)| *enabled);
+                    mods.push((mc.spec.clone(), enabled));
+                }
+            });
+        }
+        mods
+    }
+
+    /// Enable the mods of `profile` with their URL in `urls` along with the folders containing
+    /// them. Other mods which enabling those folders would turn on are disabled, so only the
+    /// given mods end up enabled.
+    pub fn enable_only(&mut self, profile: &str, urls: &HashSet<String>) {
+        let enabled_urls = |mod_data: &Self| {
+            mod_data
+                .mod_states(profile)
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(spec, _)| spec.url)
+                .collect::<HashSet<_>>()
+        };
+        let before = enabled_urls(self);
+        for url in urls {
+            self.any_mod_mut(profile, |mc, folder_enabled| {
+                if &mc.spec.url != url {
+                    return false;
+                }
+                mc.enabled = true;
+                if let Some(folder_enabled) = folder_enabled {
+                    *folder_enabled = true;
+                }
+                true
+            });
+        }
+        let turned_on = enabled_urls(self)
+            .into_iter()
+            .filter(|url| !before.contains(url) && !urls.contains(url))
+            .collect::<HashSet<_>>();
+        if let Some(prof) = self.profiles.get_mut(profile) {
+            for mc in prof.mods_with_urls_mut(&turned_on) {
+                mc.enabled = false;
+            }
+        }
+    }
+
+    /// Folders and mods of `profile` that no other profile has, i.e. what would be lost for good
+    /// if it were deleted.
+    pub fn unique_contents(&self, profile: &str) -> UniqueContents {
+        let mut other_folders = BTreeSet::new();
+        let mut other_mods = HashSet::new();
+        for (name, prof) in &self.profiles {
+            if name != profile {
+                other_folders.extend(prof.groups.keys().map(String::as_str));
+                self.for_each_mod(name, |mc| {
+                    other_mods.insert(mc.spec.url.clone());
+                });
+            }
+        }
+
+        let mut unique = UniqueContents::default();
+        if let Some(prof) = self.profiles.get(profile) {
+            unique.folders = prof
+                .groups
+                .keys()
+                .filter(|g| !other_folders.contains(g.as_str()))
+                .cloned()
+                .collect();
+        }
+        self.for_each_mod(profile, |mc| {
+            if !other_mods.contains(&mc.spec.url) {
+                unique.mods.push(mc.spec.clone());
+            }
+        });
+        unique
+    }
+
+    /// Copy the folders and mods of `from` which only exist there into `to`. Unique folders are
+    /// copied with the unique mods in them; other unique mods go into the folder of the same name
+    /// in `to` if there is one, otherwise to its top level.
+    pub fn move_unique_contents(&mut self, from: &str, to: &str) {
+        let unique = self.unique_contents(from);
+        let unique_mods = unique
+            .mods
+            .iter()
+            .map(|s| s.url.as_str())
+            .collect::<HashSet<_>>();
+        let is_unique = |folder: &str| unique.folders.iter().any(|f| f == folder);
+        let Some(src) = self.profiles.get(from).cloned() else {
+            return;
+        };
+        let Some(dst) = self.profiles.get_mut(to) else {
+            return;
+        };
+
+        let mut present = dst
+            .all_entries()
+            .filter_map(ModOrGroup::as_mod)
+            .map(|mc| mc.spec.url.clone())
+            .collect::<HashSet<_>>();
+        src.visit(|item, folders| {
+            let parent = folders.last().map(|(f, _)| *f);
+            // everything inside a unique folder is copied along with it
+            let copied = parent.is_some_and(is_unique);
+            let target = parent.filter(|p| dst.groups.contains_key(*p));
+            match item {
+                ModOrGroup::Group {
+                    group_name,
+                    enabled,
+                } => {
+                    if !is_unique(group_name) || dst.groups.contains_key(group_name) {
+                        return;
+                    }
+                    let Some(group) = src.groups.get(group_name) else {
+                        return;
+                    };
+                    let mut group = group.clone();
+                    // nested folders which are neither copied nor in `to` would be dangling, and
+                    // mods other profiles have as well would end up in `to` twice
+                    group.mods.retain(|m| match m {
+                        ModOrGroup::Group { group_name, .. } => {
+                            is_unique(group_name) || dst.groups.contains_key(group_name)
+                        }
+                        ModOrGroup::Individual(mc) => {
+                            unique_mods.contains(mc.spec.url.as_str())
+                                && present.insert(mc.spec.url.clone())
+                        }
+                    });
+                    dst.groups.insert(group_name.clone(), group);
+                    if !copied {
+                        dst.entries_mut(target).unwrap().push(ModOrGroup::Group {
+                            group_name: group_name.clone(),
+                            enabled: *enabled,
+                        });
+                    }
+                }
+                ModOrGroup::Individual(mc) => {
+                    if !copied
+                        && unique_mods.contains(mc.spec.url.as_str())
+                        && present.insert(mc.spec.url.clone())
+                    {
+                        dst.entries_mut(target)
+                            .unwrap()
+                            .push(ModOrGroup::Individual(mc.clone()));
+                    }
+                }
+            }
+        });
+    }
+
+    /// Mods which both `into` and `from` contain with a differing version or priority. `key`
+    /// identifies a mod independently of its version, e.g. the unpinned mod.io URL.
+    pub fn merge_conflicts(
+        &self,
+        into: &str,
+        from: &str,
+        key: impl Fn(&ModSpecification) -> String,
+    ) -> Vec<MergeConflict> {
+        let mut target = HashMap::new();
+        self.for_each_mod(into, |mc| {
+            target.entry(key(&mc.spec)).or_insert_with(|| mc.clone());
+        });
+
+        let mut seen = HashSet::new();
+        let mut conflicts = vec![];
+        self.for_each_mod(from, |mc| {
+            let k = key(&mc.spec);
+            if let Some(t) = target.get(&k)
+                && (t.spec.url != mc.spec.url || t.priority != mc.priority)
+                && seen.insert(k.clone())
+            {
+                conflicts.push(MergeConflict {
+                    key: k,
+                    target: t.clone(),
+                    source: mc.clone(),
+                });
+            }
+        });
+        conflicts
+    }
+
+    /// Add all mods of `from` which `into` does not have yet to `into`. Folders of `from` are
+    /// merged into the folder of the same name in `into`, or created if it doesn't exist. For the
+    /// conflicting mods whose key is in `take_source`, the version and priority of `from` replace
+    /// those in `into`; all other conflicts keep `into` as is.
+    pub fn merge_profiles(
+        &mut self,
+        into: &str,
+        from: &str,
+        key: impl Fn(&ModSpecification) -> String,
+        take_source: &HashSet<String>,
+    ) {
+        if into == from {
+            return;
+        }
+        let Some(src) = self.profiles.get(from).cloned() else {
+            return;
+        };
+        let Some(dst) = self.profiles.get_mut(into) else {
+            return;
+        };
+
+        let mut source_mods = HashMap::new();
+        for mc in src.all_entries().filter_map(ModOrGroup::as_mod) {
+            source_mods.entry(key(&mc.spec)).or_insert(mc);
+        }
+
+        let mut present = HashSet::new();
+        for mc in dst.all_entries_mut().filter_map(ModOrGroup::as_mod_mut) {
+            let k = key(&mc.spec);
+            if take_source.contains(&k)
+                && let Some(source) = source_mods.get(&k)
+            {
+                mc.spec = source.spec.clone();
+                mc.priority = source.priority;
+            }
+            present.insert(k);
+        }
+
+        src.visit(|item, folders| {
+            let ModOrGroup::Individual(mc) = item else {
+                return;
+            };
+            if !present.insert(key(&mc.spec)) {
+                return;
+            }
+            let parent = dst.add_folder_path(folders, &src);
+            dst.entries_mut(parent)
+                .unwrap()
+                .push(ModOrGroup::Individual(mc.clone()));
+        });
+    }
+
+    /// Copy the mods of `from` with their URL in `urls`, and the folders in `folders` with
+    /// everything nested in them, to `to`, skipping mods `to` already has. Copied folders are
+    /// merged into the folder of the same name in `to`, or created at its top level; other mods go
+    /// into the folder of the same name in `to` if there is one, otherwise to its top level.
+    /// Returns how many mods were copied and how many were skipped.
+    pub fn copy_to_profile(
+        &mut self,
+        from: &str,
+        to: &str,
+        urls: &HashSet<String>,
+        folders: &HashSet<String>,
+    ) -> (usize, usize) {
+        if from == to {
+            return (0, 0);
+        }
+        let Some(src) = self.profiles.get(from).cloned() else {
+            return (0, 0);
+        };
+        let Some(dst) = self.profiles.get_mut(to) else {
+            return (0, 0);
+        };
+
+        let mut present = dst
+            .all_entries()
+            .filter_map(ModOrGroup::as_mod)
+            .map(|mc| mc.spec.url.clone())
+            .collect::<HashSet<_>>();
+        let (mut copied, mut skipped) = (0, 0);
+        src.visit(|item, path| {
+            // the folders containing the item, from the outermost copied one on
+            let copied_path = path
+                .iter()
+                .position(|(folder, _)| folders.contains(*folder))
+                .map(|i| &path[i..]);
+            match item {
+                ModOrGroup::Group {
+                    group_name,
+                    enabled,
+                } => {
+                    if copied_path.is_some() || folders.contains(group_name) {
+                        let mut path = copied_path.unwrap_or_default().to_vec();
+                        path.push((group_name.as_str(), *enabled));
+                        dst.add_folder_path(&path, &src);
+                    }
+                }
+                ModOrGroup::Individual(mc) => {
+                    let parent = match copied_path {
+                        Some(path) => dst.add_folder_path(path, &src),
+                        None if urls.contains(&mc.spec.url) => path
+                            .last()
+                            .map(|(folder, _)| *folder)
+                            .filter(|folder| dst.groups.contains_key(*folder)),
+                        None => return,
+                    };
+                    if present.insert(mc.spec.url.clone()) {
+                        dst.entries_mut(parent)
+                            .unwrap()
+                            .push(ModOrGroup::Individual(mc.clone()));
+                        copied += 1;
+                    } else {
+                        skipped += 1;
+                    }
+                }
+            }
+        });
+        (copied, skipped)
+    }
+
+    /// Point every mod at `old` to `new` in all profiles, keeping its position, folder, priority
+    /// and enabled state, and carry its loadout state, install destinations, asset overrides and
+    /// local metadata over. Returns whether any mod was changed.
+    pub fn replace_mod_url(&mut self, old: &str, new: &ModSpecification) -> bool {
+        let mut replaced = false;
+        for prof in self.profiles.values_mut() {
+            for mc in prof
+                .all_entries_mut()
+                .filter_map(ModOrGroup::as_mod_mut)
+                .filter(|mc| mc.spec.url == old)
+            {
+                mc.spec = new.clone();
+                replaced = true;
+            }
+            for winner in prof.asset_overrides.values_mut().filter(|w| *w == old) {
+                *winner = new.url.clone();
+            }
+        }
+        for loadout in self.loadouts.values_mut().flat_map(|l| l.values_mut()) {
+            if let Some(enabled) = loadout.mods.remove(old) {
+                loadout.mods.insert(new.url.clone(), enabled);
+            }
+        }
+        if let Some(rules) = self.payload_rules.remove(old) {
+            self.payload_rules.insert(new.url.clone(), rules);
+        }
+        if let Some(metadata) = self.local_metadata.remove(old) {
+            self.local_metadata.insert(new.url.clone(), metadata);
+        }
+        replaced
     }
 }
 
@@ -472,7 +1791,146 @@ pub struct Config {
     #[serde(default = "default_true")]
     pub confirm_profile_deletion: bool,
     #[serde(default)]
-    pub backup_path: Option<PathBuf>,
+    pub backup_path: Option<PathBuf>,
+    /// Whether backups are packed into a single `.zip` instead of copied into a directory.
+    #[serde(default)]
+    pub zip_backups: bool,
+    #[serde(default)]
+    pub auto_backup: AutoBackup,
+    #[serde(default = "default_true")]
+    pub check_for_updates: bool,
+    /// GitHub `owner/repo` or URL of a release document to check for updates instead of upstream.
+    #[serde(default)]
+    pub update_endpoint: Option<String>,
+    /// Whether the guided tour was completed or skipped. Configs written before the tour existed
+    /// belong to existing users, so a missing field counts as completed; fresh configs start from
+    /// `Default` and show it.
+    #[serde(default = "default_true")]
+    pub tutorial_completed: bool,
+    /// Snapshot the save files before the first install of a profile with gameplay mods.
+    #[serde(default = "default_true")]
+    pub snapshot_saves: bool,
+    /// Show how much needs downloading before installing, for metered connections.
+    #[serde(default)]
+    pub confirm_downloads: bool,
+    /// Signers whose signatures mark mods from outside mod.io as trusted.
+    #[serde(default)]
+    pub trusted_signers: Vec<TrustedSigner>,
+    /// Command downloaded archives are scanned with before they can be installed.
+    #[serde(default)]
+    pub scan_command: Option<String>,
+    /// Count locally which features are used and which errors occur, see [`crate::analytics`].
+    #[serde(default)]
+    pub usage_analytics: bool,
+    /// How many MiB of a pak extracted from an archive are kept in memory while installing or
+    /// linting before it moves to a temporary file.
+    #[serde(default = "default_memory_budget")]
+    pub memory_budget: u64,
+    /// Timeouts of the HTTP client the providers share.
+    #[serde(default)]
+    pub http_timeouts: client::Timeouts,
+    /// How many mods are downloaded at once.
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+    /// Added to the game's command line when mint launches it, one argument each.
+    #[serde(default)]
+    pub launch_args: Vec<String>,
+    /// Launcher "Launch game" uses for an installation, keyed by its main pak. Installations
+    /// without an entry use the launcher of their store.
+    #[serde(default)]
+    pub launch_methods: HashMap<PathBuf, LaunchMethod>,
+    /// Show the thumbnails of mods which have one in the mod list.
+    #[serde(default = "default_true")]
+    pub show_thumbnails: bool,
+    /// When the mod cache was last updated, as RFC 3339, for the profile health score.
+    #[serde(default)]
+    pub last_cache_update: Option<String>,
+    /// Game installations to switch between, see [`GameInstall`].
+    #[serde(default)]
+    pub game_installs: Vec<GameInstall>,
+}
+
+/// Settings which describe this machine rather than preferences, left out of exported settings.
+const MACHINE_SETTINGS: [&str; 4] = [
+    "drg_pak_path",
+    "game_installs",
+    "launch_methods",
+    "last_cache_update",
+];
+
+/// Settings which decide what mint runs, trusts and downloads from, left out of exported settings
+/// and kept on import, as a settings file someone shared could otherwise run any command on each
+/// download, redirect self-updates or rewrite download links. Provider parameters hold tokens as
+/// well as settings such as link refresh rules and IPFS gateways, so none of them are shared.
+const TRUSTED_SETTINGS: [&str; 4] = [
+    "provider_parameters",
+    "scan_command",
+    "update_endpoint",
+    "trusted_signers",
+];
+
+impl Config!["0.0.0"] {
+    /// Write the settings to `path` for setting up mint on another machine, without
+    /// [`MACHINE_SETTINGS`] and [`TRUSTED_SETTINGS`].
+    pub fn export_settings(&self, path: &Path) -> Result<(), StateError> {
+        let mut settings = serde_json::to_value(self).context(CfgSerializationFailedSnafu)?;
+        if let Some(settings) = settings.as_object_mut() {
+            for name in MACHINE_SETTINGS.into_iter().chain(TRUSTED_SETTINGS) {
+                settings.remove(name);
+            }
+        }
+        let buf = serde_json::to_vec_pretty(&settings).context(CfgSerializationFailedSnafu)?;
+        fs::write(path, buf)?;
+        Ok(())
+    }
+
+    /// Replace the settings by those exported to `path` by [`Self::export_settings`], keeping the
+    /// [`MACHINE_SETTINGS`] and [`TRUSTED_SETTINGS`] of this machine, even if the file has them.
+    pub fn import_settings(&mut self, path: &Path) -> Result<(), StateError> {
+        let mut imported: Self =
+            serde_json::from_slice(&fs::read(path)?).context(SettingsDeserializationFailedSnafu)?;
+        imported.drg_pak_path = self.drg_pak_path.take();
+        imported.game_installs = std::mem::take(&mut self.game_installs);
+        imported.launch_methods = std::mem::take(&mut self.launch_methods);
+        imported.last_cache_update = self.last_cache_update.take();
+        imported.scan_command = self.scan_command.take();
+        imported.update_endpoint = self.update_endpoint.take();
+        imported.trusted_signers = std::mem::take(&mut self.trusted_signers);
+        imported.provider_parameters = std::mem::take(&mut self.provider_parameters);
+        *self = imported;
+        Ok(())
+    }
+
+    /// Launcher of the installation at [`Self::drg_pak_path`], `None` without one.
+    pub fn launch_method(&self) -> Option<LaunchMethod> {
+        let pak_path = self.drg_pak_path.as_ref()?;
+        self.launch_methods.get(pak_path).copied().or_else(|| {
+            DRGInstallationType::from_pak_path(pak_path)
+                .ok()
+                .map(LaunchMethod::for_installation_type)
+        })
+    }
+
+    /// Index in [`Self::game_installs`] of the installation at [`Self::drg_pak_path`].
+    pub fn active_game_install(&self) -> Option<usize> {
+        let pak_path = self.drg_pak_path.as_ref()?;
+        self.game_installs
+            .iter()
+            .position(|install| &install.pak_path == pak_path)
+    }
+
+    /// Add the installation at [`Self::drg_pak_path`] to [`Self::game_installs`] unless it is
+    /// listed already, e.g. after the pak was changed in the settings.
+    pub fn remember_game_install(&mut self) {
+        if self.active_game_install().is_none()
+            && let Some(install) = self
+                .drg_pak_path
+                .clone()
+                .and_then(GameInstall::from_pak_path)
+        {
+            self.game_installs.push(install);
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -550,6 +2008,24 @@ impl Default for Config!["0.0.0"] {
             confirm_mod_deletion: true,
             confirm_profile_deletion: true,
             backup_path: None,
+            zip_backups: false,
+            auto_backup: Default::default(),
+            check_for_updates: true,
+            update_endpoint: None,
+            tutorial_completed: false,
+            snapshot_saves: true,
+            confirm_downloads: false,
+            trusted_signers: vec![],
+            scan_command: None,
+            usage_analytics: false,
+            memory_budget: default_memory_budget(),
+            http_timeouts: Default::default(),
+            download_concurrency: default_download_concurrency(),
+            launch_args: vec![],
+            launch_methods: Default::default(),
+            show_thumbnails: true,
+            last_cache_update: None,
+            game_installs: vec![],
         }
     }
 }
@@ -582,6 +2058,39 @@ pub enum StateError {
     ModDataDeserializationFailed { source: serde_json::Error },
     #[snafu(display("failed to deserialize legacy profiles"))]
     LegacyProfilesDeserializationFailed { source: serde_json::Error },
+    #[snafu(display("failed to deserialize secrets file"))]
+    SecretsDeserializationFailed { source: serde_json::Error },
+    #[snafu(display("no profile named {name:?}"))]
+    NoSuchProfile { name: String },
+    #[snafu(display("failed to serialize profile"))]
+    ProfileSerializationFailed { source: serde_json::Error },
+    #[snafu(display("not a valid mint profile"))]
+    ProfileDeserializationFailed { source: serde_json::Error },
+    #[snafu(display("not valid mint settings"))]
+    SettingsDeserializationFailed { source: serde_json::Error },
+}
+
+/// Outcome of [`State::pin_to_date`], by mod name.
+#[derive(Debug, Default)]
+pub struct PinnedToDate {
+    pub pinned: Vec<String>,
+    /// Mods without a version released before the date.
+    pub too_new: Vec<String>,
+    /// Mods whose provider keeps no version history, such as local files.
+    pub no_history: Vec<String>,
+}
+
+impl std::fmt::Display for PinnedToDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pinned {} mods", self.pinned.len())?;
+        if !self.too_new.is_empty() {
+            write!(f, ", not released yet: {}", self.too_new.join(", "))?;
+        }
+        if !self.no_history.is_empty() {
+            write!(f, ", no version history: {}", self.no_history.join(", "))?;
+        }
+        Ok(())
+    }
 }
 
 pub struct State {
@@ -589,31 +2098,383 @@ pub struct State {
     pub config: ConfigWrapper<VersionAnnotatedConfig>,
     pub mod_data: ConfigWrapper<VersionAnnotatedModData>,
     pub store: Arc<ModStore>,
+    /// Provider parameters from environment variables and the secrets file, kept apart from
+    /// config so they are never persisted.
+    pub external_provider_parameters: ProviderParameters,
+    /// Set if started with `--safe-mode`, see [`State::init_safe_mode`].
+    pub safe_mode: Option<SafeModeReport>,
+    /// Set while the mod data read has an older format and is not saved yet, see
+    /// [`Self::apply_migration`].
+    pub migration: Option<MigrationPreview>,
+}
+
+/// What `--safe-mode` found while starting.
+#[derive(Debug, Default)]
+pub struct SafeModeReport {
+    /// Files which could not be read, with why. Defaults are used in their place.
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// Migrations a normal start would apply and save.
+    pub migrations: Vec<String>,
+}
+
+impl SafeModeReport {
+    /// The contents of `path`, or the default if they could not be read. If `current` is given,
+    /// notes the migration if the file has an older version.
+    fn check<T: Default, E: std::error::Error>(
+        &mut self,
+        path: &Path,
+        current: Option<&str>,
+        read: Result<T, E>,
+    ) -> T {
+        match read {
+            Ok(contents) => {
+                if let Some(current) = current
+                    && let Ok(buf) = fs::read(path)
+                {
+                    let version = serde_json::from_slice::<serde_json::Value>(&buf)
+                        .ok()
+                        .and_then(|v| Some(v.get("version")?.as_str()?.to_string()));
+                    if version.as_deref() != Some(current) {
+                        self.migrations.push(format!(
+                            "{} from {} to {current}",
+                            path.display(),
+                            version.as_deref().unwrap_or("before versioning")
+                        ));
+                    }
+                }
+                contents
+            }
+            Err(e) => {
+                let mut reason = e.to_string();
+                let mut source = e.source();
+                while let Some(e) = source {
+                    reason.push_str(&format!(": {e}"));
+                    source = e.source();
+                }
+                tracing::warn!("safe mode: could not read {}: {reason}", path.display());
+                self.unreadable.push((path.to_path_buf(), reason));
+                T::default()
+            }
+        }
+    }
 }
 
 impl State {
+    /// Import settings exported on another machine, see [`Config::import_settings`], and apply
+    /// those the store and client were set up with.
+    pub fn import_settings(&mut self, path: &Path) -> Result<(), StateError> {
+        self.config.import_settings(path)?;
+        self.config.save()?;
+        client::configure(self.config.http_timeouts);
+        self.store.reload_providers(&secrets::merge_parameters(
+            &self.config.provider_parameters,
+            &self.external_provider_parameters,
+        ))?;
+        self.store
+            .set_trusted_signers(self.config.trusted_signers.clone());
+        self.store
+            .set_scan_command(self.config.scan_command.as_deref());
+        self.store
+            .set_download_concurrency(self.config.download_concurrency);
+        Ok(())
+    }
+
+    /// Load the state, saving the mod data in the current format right away if it has an older
+    /// one, see [`Self::init_previewing_migration`].
     pub fn init(dirs: Dirs) -> Result<Self, StateError> {
+        let mut state = Self::init_previewing_migration(dirs)?;
+        state.apply_migration()?;
+        Ok(state)
+    }
+
+    /// Load the state, but if the mod data has an older format, hold off saving it until
+    /// [`Self::apply_migration`], so the changes can be shown first.
+    pub fn init_previewing_migration(dirs: Dirs) -> Result<Self, StateError> {
         let config_path = dirs.config_dir.join("config.json");
 
         let config = read_config_or_default(&config_path)?;
-        let config = ConfigWrapper::<VersionAnnotatedConfig>::new(&config_path, config);
+        let mut config = ConfigWrapper::<VersionAnnotatedConfig>::new(&config_path, config);
+        config.remember_game_install();
         config.save().unwrap();
 
         let legacy_mod_profiles_path = dirs.config_dir.join("profiles.json");
         let mod_data_path = dirs.config_dir.join("mod_data.json");
-        let mod_data = read_mod_data_or_default(&mod_data_path, legacy_mod_profiles_path)?;
-        let mod_data = ConfigWrapper::<VersionAnnotatedModData>::new(mod_data_path, mod_data);
+        let (mod_data, migration) = read_mod_data(&mod_data_path, &legacy_mod_profiles_path)?;
+        let mut mod_data = ConfigWrapper::<VersionAnnotatedModData>::new(mod_data_path, mod_data)
+            .with_history(dirs.data_dir.join(history::HISTORY_DIR));
+        if migration.is_some() {
+            mod_data = mod_data.hold();
+        }
+        mod_data.purge_trash();
         mod_data.save().unwrap();
 
-        let store = ModStore::new(&dirs.cache_dir, &config.provider_parameters)?.into();
+        let external_provider_parameters = secrets::read_external_parameters(&dirs.config_dir)?;
+        client::configure(config.http_timeouts);
+        let store = ModStore::new(
+            &dirs.cache_dir,
+            &secrets::merge_parameters(&config.provider_parameters, &external_provider_parameters),
+        )?;
+        store.set_local_metadata(mod_data.local_metadata.clone());
+        store.set_trusted_signers(config.trusted_signers.clone());
+        store.set_scan_command(config.scan_command.as_deref());
+        store.set_download_concurrency(config.download_concurrency);
+        let store = store.into();
 
         Ok(Self {
             dirs,
             config,
             mod_data,
             store,
+            external_provider_parameters,
+            safe_mode: None,
+            migration,
         })
     }
+
+    /// Start without failing on broken files, so the data can still be exported or repaired:
+    /// files which cannot be read are replaced by defaults, migrations are only reported, and
+    /// nothing is written back as everything is kept in memory. The store has no providers, so
+    /// no mods are resolved or downloaded.
+    pub fn init_safe_mode(dirs: Dirs) -> Self {
+        let mut report = SafeModeReport::default();
+
+        let config_path = dirs.config_dir.join("config.json");
+        let config = report.check(
+            &config_path,
+            Some("0.0.0"),
+            read_config_or_default(&config_path),
+        );
+        let mut config = ConfigWrapper::<VersionAnnotatedConfig>::memory(config);
+        config.remember_game_install();
+
+        let mod_data_path = dirs.config_dir.join("mod_data.json");
+        let legacy_mod_profiles_path = dirs.config_dir.join("profiles.json");
+        let read_path = if !mod_data_path.exists() && legacy_mod_profiles_path.exists() {
+            &legacy_mod_profiles_path
+        } else {
+            &mod_data_path
+        };
+        let (mod_data, migration) = report.check(
+            read_path,
+            None,
+            read_mod_data(&mod_data_path, &legacy_mod_profiles_path),
+        );
+        if let Some(migration) = migration {
+            report.migrations.push(format!(
+                "{} from {} to {}",
+                migration.file.display(),
+                migration.from,
+                migration::CURRENT_VERSION
+            ));
+        }
+        if read_path == &legacy_mod_profiles_path {
+            report
+                .migrations
+                .push(format!("{} into mod_data.json", read_path.display()));
+        }
+        let mut mod_data = ConfigWrapper::<VersionAnnotatedModData>::memory(mod_data);
+        mod_data.purge_trash();
+
+        let external_provider_parameters = report.check(
+            &secrets::secrets_file_path(&dirs.config_dir),
+            None,
+            secrets::read_external_parameters(&dirs.config_dir),
+        );
+        let cache_path = dirs.cache_dir.join("cache.json");
+        let cache = report.check(
+            &cache_path,
+            None,
+            crate::providers::cache::read_cache_metadata_or_default(&cache_path),
+        );
+        let store = ModStore::offline(&dirs.cache_dir, cache);
+        store.set_local_metadata(mod_data.local_metadata.clone());
+        store.set_trusted_signers(config.trusted_signers.clone());
+
+        Self {
+            dirs,
+            config,
+            mod_data,
+            store: store.into(),
+            external_provider_parameters,
+            safe_mode: Some(report),
+            migration: None,
+        }
+    }
+
+    /// Save the mod data in the current format if it was read in an older one, after copying the
+    /// file read to [`MigrationPreview::backup`].
+    pub fn apply_migration(&mut self) -> Result<(), StateError> {
+        let Some(migration) = self.migration.clone() else {
+            return Ok(());
+        };
+        migration.backup()?;
+        tracing::info!(
+            "migrating {} from {} to {}, backed up to {}",
+            migration.file.display(),
+            migration.from,
+            migration::CURRENT_VERSION,
+            migration.backup.display()
+        );
+        self.mod_data.release();
+        self.mod_data.save()?;
+        if migration.file != self.dirs.config_dir.join("mod_data.json") {
+            fs::remove_file(&migration.file)?;
+        }
+        self.migration = None;
+        Ok(())
+    }
+
+    /// Effective parameters for a provider, with externally supplied values taking precedence.
+    pub fn provider_parameters(&self, provider_id: &str) -> HashMap<String, String> {
+        let mut params = self
+            .config
+            .provider_parameters
+            .get(provider_id)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(external) = self.external_provider_parameters.get(provider_id) {
+            params.extend(external.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        params
+    }
+
+    /// Replace the mod data with `snapshot`, after keeping the current one in the history so the
+    /// restore can be undone.
+    pub fn restore_snapshot(&mut self, snapshot: &history::Snapshot) -> Result<(), StateError> {
+        let buf = fs::read(&snapshot.path)?;
+        let mod_data = serde_json::from_slice::<MaybeVersionedModData>(&buf)
+            .context(ModDataDeserializationFailedSnafu)?;
+        self.mod_data.record_history(history::Reason::Restore)?;
+        *self.mod_data = migrate_mod_data(mod_data);
+        self.mod_data.save()?;
+        self.store
+            .set_local_metadata(self.mod_data.local_metadata.clone());
+        Ok(())
+    }
+
+    /// Pin each mod of `profile` to its newest version released before `before`, a unix
+    /// timestamp, to recreate the profile as it was at that time. Mods without a version history
+    /// or without a version that old are left as they are.
+    pub fn pin_to_date(&mut self, profile: &str, before: u64) -> PinnedToDate {
+        let store = self.store.clone();
+        let mut pinned = PinnedToDate::default();
+        self.mod_data.for_each_mod_mut(profile, |mc| {
+            let name = store
+                .get_mod_info(&mc.spec)
+                .map(|i| i.name)
+                .unwrap_or_else(|| mc.spec.url.clone());
+            let Some(versions) = store.version_dates(&mc.spec) else {
+                pinned.no_history.push(name);
+                return;
+            };
+            match versions
+                .into_iter()
+                .filter(|(_, date)| *date < before)
+                .max_by_key(|(_, date)| *date)
+            {
+                Some((spec, _)) => {
+                    mc.spec = spec;
+                    pinned.pinned.push(name);
+                }
+                None => pinned.too_new.push(name),
+            }
+        });
+        self.mod_data.save().unwrap();
+        pinned
+    }
+
+    /// The versions mods whose update policy may hold them back currently resolve to, to pass to
+    /// [`State::apply_update_policies`] once the cache is updated.
+    pub fn held_versions(&self) -> HashMap<ModSpecification, ModSpecification> {
+        let mut held = HashMap::new();
+        for profile in self.mod_data.profiles.keys() {
+            self.mod_data.for_each_mod(profile, |mc| {
+                if !mc.update_policy.is_latest()
+                    && mc.spec.version_id().is_none()
+                    && let Some(info) = self.store.get_mod_info(&mc.spec)
+                    && let Some(version) = info.resolved_version()
+                {
+                    held.insert(mc.spec.clone(), version.clone());
+                }
+            });
+        }
+        held
+    }
+
+    /// Keep mods on the versions in `held`, from [`State::held_versions`] before "Update cache",
+    /// where their update policy asks for it: pinned mods, and mods following approved versions
+    /// which are in the sandbox now. Those follow the newest version again once it is approved.
+    /// Returns the names of the mods held back from an update.
+    pub fn apply_update_policies(
+        &mut self,
+        held: &HashMap<ModSpecification, ModSpecification>,
+    ) -> Vec<String> {
+        let store = self.store.clone();
+        let profiles = self.mod_data.profiles.keys().cloned().collect::<Vec<_>>();
+        let mut held_back = vec![];
+        for profile in profiles {
+            self.mod_data.for_each_mod_mut(&profile, |mc| {
+                let Some(info) = store.get_mod_info(&mc.spec) else {
+                    return;
+                };
+                let approved = info
+                    .modio_tags
+                    .as_ref()
+                    .is_none_or(|tags| tags.approval_status != ApprovalStatus::Sandbox);
+                match mc.update_policy {
+                    UpdatePolicy::Latest => {}
+                    UpdatePolicy::Pinned => {
+                        if let Some(version) = held.get(&mc.spec) {
+                            mc.spec = version.clone();
+                        }
+                    }
+                    // held back by an earlier update
+                    UpdatePolicy::LatestApproved if mc.spec.version_id().is_some() => {
+                        if approved {
+                            mc.spec = info.spec;
+                        }
+                    }
+                    UpdatePolicy::LatestApproved => {
+                        if !approved
+                            && let Some(version) = held.get(&mc.spec)
+                            && version.version_id() != info.version_id()
+                        {
+                            mc.spec = version.clone();
+                            if !held_back.contains(&info.name) {
+                                held_back.push(info.name);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+        self.mod_data.save().unwrap();
+        held_back
+    }
+
+    /// Use the configured HTTP timeouts for requests from now on.
+    pub fn apply_http_timeouts(&self) -> Result<(), ProviderError> {
+        client::configure(self.config.http_timeouts);
+        self.store.reload_providers(&secrets::merge_parameters(
+            &self.config.provider_parameters,
+            &self.external_provider_parameters,
+        ))
+    }
+
+    /// Trust `signers` in addition to those trusted already. Returns how many are new.
+    pub fn trust_signers(&mut self, signers: Vec<TrustedSigner>) -> usize {
+        let added = crate::signing::add_trusted(&mut self.config.trusted_signers, signers);
+        self.config.save().unwrap();
+        self.store
+            .set_trusted_signers(self.config.trusted_signers.clone());
+        added
+    }
+
+    pub fn is_external_provider_parameter(&self, provider_id: &str, parameter_id: &str) -> bool {
+        self.external_provider_parameters
+            .get(provider_id)
+            .is_some_and(|p| p.contains_key(parameter_id))
+    }
 }
 
 fn read_config_or_default(config_path: &PathBuf) -> Result<VersionAnnotatedConfig, StateError> {
@@ -630,6 +2491,7 @@ fn read_config_or_default(config_path: &PathBuf) -> Result<VersionAnnotatedConfi
                     VersionAnnotatedConfig::V0_0_0(Config_v0_0_0 {
                         provider_parameters: legacy.provider_parameters,
                         drg_pak_path: legacy.drg_pak_path,
+                        tutorial_completed: true,
                         ..Default::default()
                     })
                 }
@@ -640,23 +2502,28 @@ fn read_config_or_default(config_path: &PathBuf) -> Result<VersionAnnotatedConfi
     })
 }
 
-fn read_mod_data_or_default(
-    mod_data_path: &PathBuf,
-    legacy_mod_profiles_path: PathBuf,
-) -> Result<VersionAnnotatedModData, StateError> {
-    let mod_data = match fs::read(mod_data_path) {
-        Ok(buf) => serde_json::from_slice::<MaybeVersionedModData>(&buf)
-            .context(ModDataDeserializationFailedSnafu)?,
+/// The mod data at `mod_data_path`, or at the legacy `legacy_mod_profiles_path` if there is none
+/// yet, brought up to the current version, and what that changed if the file read had an older
+/// one. The default if neither exists. Nothing is written, see [`State::apply_migration`].
+fn read_mod_data(
+    mod_data_path: &Path,
+    legacy_mod_profiles_path: &Path,
+) -> Result<(VersionAnnotatedModData, Option<MigrationPreview>), StateError> {
+    let (mod_data, read_from) = match fs::read(mod_data_path) {
+        Ok(buf) => (
+            serde_json::from_slice::<MaybeVersionedModData>(&buf)
+                .context(ModDataDeserializationFailedSnafu)?,
+            Some(mod_data_path),
+        ),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            match fs::read(&legacy_mod_profiles_path) {
-                Ok(buf) => {
-                    let mod_data = serde_json::from_slice::<MaybeVersionedModData>(&buf)
-                        .context(LegacyProfilesDeserializationFailedSnafu)?;
-                    fs::remove_file(&legacy_mod_profiles_path)?;
-                    mod_data
-                }
+            match fs::read(legacy_mod_profiles_path) {
+                Ok(buf) => (
+                    serde_json::from_slice::<MaybeVersionedModData>(&buf)
+                        .context(LegacyProfilesDeserializationFailedSnafu)?,
+                    Some(legacy_mod_profiles_path),
+                ),
                 Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                    MaybeVersionedModData::default()
+                    (MaybeVersionedModData::default(), None)
                 }
                 Err(e) => Err(e)?,
             }
@@ -664,33 +2531,68 @@ fn read_mod_data_or_default(
         Err(e) => Err(e)?,
     };
 
-    let mod_data = match mod_data {
+    let mut preview = read_from.and_then(|path| MigrationPreview::new(path, &mod_data));
+    let mod_data = canonicalize_mod_data(migrate_mod_data(mod_data));
+    if let Some(preview) = &mut preview
+        && let VersionAnnotatedModData::V0_3_0(md) = &mod_data
+    {
+        preview.count_mods(md);
+    }
+    Ok((mod_data, preview))
+}
+
+/// Canonicalize the mod URLs keying mod data, as they are when the mods themselves are loaded.
+fn canonicalize_mod_data(mut mod_data: VersionAnnotatedModData) -> VersionAnnotatedModData {
+    if let VersionAnnotatedModData::V0_3_0(md) = &mut mod_data {
+        md.payload_rules = std::mem::take(&mut md.payload_rules)
+            .into_iter()
+            .map(|(url, rules)| (canonical_url(&url), rules))
+            .collect();
+        md.local_metadata = std::mem::take(&mut md.local_metadata)
+            .into_iter()
+            .map(|(url, metadata)| (canonical_url(&url), metadata))
+            .collect();
+    }
+    mod_data
+}
+
+/// Bring mod data of any supported version up to the current one.
+pub(crate) fn migrate_mod_data(mod_data: MaybeVersionedModData) -> VersionAnnotatedModData {
+    match mod_data {
         MaybeVersionedModData::Legacy(legacy) => {
-            // 0.0.0 -> 0.1.0 -> 0.2.0
+            // 0.0.0 -> 0.1.0 -> 0.2.0 -> 0.3.0
             let v0_1_0: ModData_v0_1_0 = legacy.into();
-            VersionAnnotatedModData::V0_2_0(v0_1_0.into())
+            let v0_2_0: ModData_v0_2_0 = v0_1_0.into();
+            VersionAnnotatedModData::V0_3_0(v0_2_0.into())
         }
         MaybeVersionedModData::Versioned(v) => match v {
             VersionAnnotatedModData::V0_0_0(md) => {
-                // 0.0.0 -> 0.1.0 -> 0.2.0
+                // 0.0.0 -> 0.1.0 -> 0.2.0 -> 0.3.0
                 let v0_1_0: ModData_v0_1_0 = md.into();
-                VersionAnnotatedModData::V0_2_0(v0_1_0.into())
+                let v0_2_0: ModData_v0_2_0 = v0_1_0.into();
+                VersionAnnotatedModData::V0_3_0(v0_2_0.into())
             }
             VersionAnnotatedModData::V0_1_0(md) => {
-                // 0.1.0 -> 0.2.0
-                VersionAnnotatedModData::V0_2_0(md.into())
+                // 0.1.0 -> 0.2.0 -> 0.3.0
+                let v0_2_0: ModData_v0_2_0 = md.into();
+                VersionAnnotatedModData::V0_3_0(v0_2_0.into())
+            }
+            VersionAnnotatedModData::V0_2_0(md) => {
+                // 0.2.0 -> 0.3.0
+                VersionAnnotatedModData::V0_3_0(md.into())
             }
-            VersionAnnotatedModData::V0_2_0(md) => VersionAnnotatedModData::V0_2_0(md),
+            VersionAnnotatedModData::V0_3_0(md) => VersionAnnotatedModData::V0_3_0(md),
         },
-    };
-
-    Ok(mod_data)
+    }
 }
 
 #[cfg(test)]
 mod mod_data_tests {
+    use std::collections::BTreeSet;
+
     use super::{
-        ModConfig, ModData_v0_1_0 as ModData, ModGroup, ModOrGroup, ModProfile_v0_1_0 as ModProfile,
+        ModConfig, ModData_v0_1_0 as ModData, ModGroup_v0_1_0 as ModGroup, ModOrGroup,
+        ModProfile_v0_1_0 as ModProfile, UpdatePolicy,
     };
     use crate::providers::ModSpecification;
 
@@ -701,6 +2603,8 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_2 = ModConfig {
@@ -708,6 +2612,8 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_3 = ModConfig {
@@ -715,6 +2621,8 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_data = ModData {
@@ -755,6 +2663,8 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_2 = ModConfig {
@@ -762,6 +2672,8 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_3 = ModConfig {
@@ -769,6 +2681,8 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_data = ModData {
@@ -809,6 +2723,8 @@ mod mod_data_tests {
             required: false,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_2 = ModConfig {
@@ -816,6 +2732,8 @@ mod mod_data_tests {
             required: true,
             enabled: false,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_3 = ModConfig {
@@ -823,6 +2741,8 @@ mod mod_data_tests {
             required: false,
             enabled: true,
             priority: 50,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
         };
 
         let mod_data = ModData {
@@ -853,3 +2773,882 @@ mod mod_data_tests {
         assert!(any_required);
     }
 }
+
+#[cfg(test)]
+mod test_util {
+    use std::collections::BTreeSet;
+
+    use super::{
+        ModConfig, ModData_v0_3_0 as ModData, ModGroup_v0_3_0 as ModGroup, ModOrGroup,
+        ModProfile_v0_3_0 as ModProfile, UpdatePolicy,
+    };
+    use crate::providers::ModSpecification;
+
+    pub fn mod_config(url: &str, enabled: bool) -> ModConfig {
+        ModConfig {
+            spec: ModSpecification::new(url.to_string()),
+            required: false,
+            enabled,
+            priority: 0,
+            update_policy: UpdatePolicy::Latest,
+            tags: BTreeSet::new(),
+        }
+    }
+
+    pub fn folder(name: &str, enabled: bool) -> ModOrGroup {
+        ModOrGroup::Group {
+            group_name: name.to_string(),
+            enabled,
+        }
+    }
+
+    /// Builds a profile from its top level entries and the entries of each of its folders.
+    pub fn profile<const N: usize>(
+        mods: Vec<ModOrGroup>,
+        folders: [(&str, Vec<ModOrGroup>); N],
+    ) -> ModProfile {
+        ModProfile {
+            mods,
+            groups: folders
+                .into_iter()
+                .map(|(name, mods)| {
+                    let group = ModGroup {
+                        mods,
+                        priority_override: None,
+                        priority_offset: None,
+                    };
+                    (name.to_string(), group)
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Builds [`ModData`] with `profile` as its active "default" profile.
+    pub fn mod_data(profile: ModProfile) -> ModData {
+        let mut mod_data = ModData::default();
+        *mod_data.get_active_profile_mut() = profile;
+        mod_data
+    }
+
+    pub fn enabled_urls(mod_data: &ModData) -> Vec<String> {
+        let mut urls = vec![];
+        mod_data.for_each_enabled_mod("default", |mc| urls.push(mc.spec.url.clone()));
+        urls.sort();
+        urls
+    }
+}
+
+#[cfg(test)]
+mod loadout_tests {
+    use super::ModOrGroup;
+    use super::test_util::{enabled_urls, folder, mod_config, mod_data, profile};
+
+    #[test]
+    fn test_save_and_apply_loadout() {
+        let mut mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                folder("g", true),
+            ],
+            [("g", vec![ModOrGroup::Individual(mod_config("b", true))])],
+        ));
+
+        mod_data.save_loadout("default", "all".to_string());
+        assert_eq!(enabled_urls(&mod_data), ["a", "b"]);
+
+        mod_data.for_each_mod_mut("default", |mc| mc.enabled = false);
+        assert!(enabled_urls(&mod_data).is_empty());
+
+        assert!(mod_data.apply_loadout("default", "all"));
+        assert_eq!(enabled_urls(&mod_data), ["a", "b"]);
+
+        assert!(!mod_data.apply_loadout("default", "missing"));
+
+        mod_data.remove_loadout("default", "all");
+        assert!(mod_data.loadouts.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tag_tests {
+    use std::collections::BTreeSet;
+
+    use super::test_util::{enabled_urls, folder, mod_config, mod_data, profile};
+    use super::{ModConfig, ModOrGroup};
+
+    #[test]
+    fn test_set_tag_enabled() {
+        let tagged = |url, tags: &[&str]| {
+            ModOrGroup::Individual(ModConfig {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..mod_config(url, false)
+            })
+        };
+        let mut mod_data = mod_data(profile(
+            vec![
+                tagged("a", &["deep dives"]),
+                tagged("b", &["deep dives", "hazard 5+"]),
+                ModOrGroup::Individual(mod_config("c", false)),
+                folder("g", true),
+            ],
+            [("g", vec![tagged("d", &["hazard 5+"])])],
+        ));
+        assert_eq!(
+            mod_data.mod_tags("default"),
+            BTreeSet::from(["deep dives".to_string(), "hazard 5+".to_string()])
+        );
+
+        assert_eq!(mod_data.set_tag_enabled("default", "hazard 5+", true), 2);
+        assert_eq!(enabled_urls(&mod_data), ["b", "d"]);
+        assert_eq!(mod_data.set_tag_enabled("default", "deep dives", true), 1);
+        assert_eq!(mod_data.set_tag_enabled("default", "deep dives", false), 2);
+        assert_eq!(enabled_urls(&mod_data), ["d"]);
+        assert_eq!(mod_data.set_tag_enabled("default", "missing", true), 0);
+    }
+}
+
+#[cfg(test)]
+mod mod_config_tests {
+    use super::test_util::{folder, mod_config, mod_data, profile};
+    use super::{ModConfig, ModOrGroup, UpdatePolicy};
+    use crate::providers::ModSpecification;
+
+    #[test]
+    fn test_replace_mod_url() {
+        let old = ModConfig {
+            priority: 7,
+            ..mod_config("old.pak", false)
+        };
+        let mut mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                folder("g", true),
+            ],
+            [(
+                "g",
+                vec![
+                    ModOrGroup::Individual(mod_config("b", true)),
+                    ModOrGroup::Individual(old),
+                ],
+            )],
+        ));
+        mod_data.save_loadout("default", "l".to_string());
+        mod_data
+            .local_metadata
+            .insert("old.pak".to_string(), Default::default());
+
+        let new = ModSpecification::new("new.pak".to_string());
+        assert!(mod_data.replace_mod_url("old.pak", &new));
+        assert!(!mod_data.replace_mod_url("old.pak", &new));
+
+        let replaced = mod_data.get_active_profile().groups["g"].mods[1]
+            .as_mod()
+            .unwrap();
+        assert_eq!(replaced.spec.url, "new.pak");
+        assert_eq!(replaced.priority, 7);
+        assert!(!replaced.enabled);
+        assert_eq!(
+            mod_data.loadouts["default"]["l"].mods.get("new.pak"),
+            Some(&false)
+        );
+        assert!(mod_data.local_metadata.contains_key("new.pak"));
+    }
+
+    #[test]
+    fn test_migrate_folders_to_0_3_0() {
+        let json = r#"{
+            "version": "0.2.0",
+            "active_profile": "default",
+            "profiles": {
+                "default": {
+                    "mods": [{ "group_name": "g", "enabled": true }],
+                    "groups": {
+                        "g": { "mods": [{ "spec": { "url": "a" }, "required": false }] }
+                    }
+                }
+            }
+        }"#;
+        let mod_data = super::migrate_mod_data(serde_json::from_str(json).unwrap());
+        assert!(matches!(
+            mod_data,
+            super::VersionAnnotatedModData::V0_3_0(_)
+        ));
+        assert!(matches!(
+            &mod_data.profiles["default"].groups["g"].mods[..],
+            [ModOrGroup::Individual(mc)] if mc.spec.url == "a"
+        ));
+    }
+
+    #[test]
+    fn test_update_policy_serde() {
+        let mut mc = mod_config("a", true);
+        let json = serde_json::to_value(&mc).unwrap();
+        assert!(json.get("update_policy").is_none());
+
+        mc.update_policy = UpdatePolicy::LatestApproved;
+        let json = serde_json::to_string(&mc).unwrap();
+        assert!(json.contains(r#""update_policy":"latest_approved""#));
+        let parsed = serde_json::from_str::<ModConfig>(&json).unwrap();
+        assert_eq!(parsed.update_policy, UpdatePolicy::LatestApproved);
+    }
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use super::test_util::{folder, mod_config, mod_data, profile};
+    use super::{
+        ModData_v0_3_0 as ModData, ModOrGroup, ModProfile_v0_3_0 as ModProfile, PostGameActions,
+    };
+
+    #[test]
+    fn test_archive_season() {
+        let mut mod_data = ModData::default();
+        for (name, season) in [
+            ("s4", "Season 4"),
+            ("s4 hard", "Season 4"),
+            ("s5", "Season 5"),
+        ] {
+            mod_data.profiles.insert(
+                name.to_string(),
+                ModProfile {
+                    season: Some(season.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+        mod_data.active_profile = "s4".to_string();
+        assert_eq!(mod_data.seasons().len(), 2);
+
+        assert_eq!(mod_data.archive_season("Season 4"), 2);
+        assert!(mod_data.profiles["s4 hard"].archived);
+        assert_eq!(mod_data.active_profile, "default");
+
+        assert!(mod_data.set_archived("default", true));
+        assert_eq!(mod_data.active_profile, "s5");
+        // the last profile which is not archived stays
+        assert!(!mod_data.set_archived("s5", true));
+        assert!(mod_data.set_archived("s4", false));
+        assert!(!mod_data.profiles["s4"].archived);
+    }
+
+    #[test]
+    fn test_trash_and_restore_profile() {
+        let mut mod_data = mod_data(profile(
+            vec![ModOrGroup::Individual(mod_config("a", true))],
+            [],
+        ));
+        mod_data.save_loadout("default", "all".to_string());
+        mod_data
+            .profiles
+            .insert("other".to_string(), Default::default());
+
+        mod_data.trash_profile("default");
+        assert!(!mod_data.profiles.contains_key("default"));
+        assert!(mod_data.loadouts.is_empty());
+
+        // the name was taken again in the meantime
+        mod_data
+            .profiles
+            .insert("default".to_string(), Default::default());
+        assert_eq!(mod_data.restore_trashed(0).as_deref(), Some("default (2)"));
+        assert!(mod_data.trash.is_empty());
+        assert_eq!(mod_data.profiles["default (2)"].mods.len(), 1);
+        assert!(mod_data.loadouts["default (2)"]["all"].mods["a"]);
+
+        mod_data.trash_profile("other");
+        assert!(!mod_data.purge_trash());
+        mod_data.trash[0].deleted =
+            (chrono::Local::now() - chrono::Duration::days(31)).to_rfc3339();
+        assert!(mod_data.purge_trash());
+        assert!(mod_data.trash.is_empty());
+    }
+
+    #[test]
+    fn test_export_and_import_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pack.mintprofile");
+        let mut mod_data = mod_data(profile(
+            vec![ModOrGroup::Individual(mod_config("a", true))],
+            [],
+        ));
+        mod_data.save_loadout("default", "all".to_string());
+        mod_data.post_game.insert(
+            "default".to_string(),
+            PostGameActions {
+                uninstall: true,
+                switch_profile: Some("vanilla".to_string()),
+                ..Default::default()
+            },
+        );
+        mod_data.export_profile("default", &path).unwrap();
+        assert!(mod_data.export_profile("missing", &path).is_err());
+
+        let name = mod_data.import_profile(&path).unwrap();
+        assert_eq!(name, "default (2)");
+        assert_eq!(mod_data.profiles[&name].mods.len(), 1);
+        assert!(mod_data.loadouts[&name]["all"].mods["a"]);
+        let actions = &mod_data.post_game[&name];
+        assert!(actions.uninstall);
+        assert_eq!(actions.switch_profile, None);
+    }
+
+    #[test]
+    fn test_ordered_profiles() {
+        let mut mod_data = ModData::default();
+        for name in ["a", "b", "c", "d"] {
+            mod_data
+                .profiles
+                .insert(name.to_string(), Default::default());
+        }
+        mod_data.profile_order = ["c", "gone", "a", "c"].map(String::from).to_vec();
+        mod_data.profiles.get_mut("d").unwrap().favorite = true;
+        assert_eq!(mod_data.ordered_profiles(), ["d", "c", "a", "b", "default"]);
+    }
+
+    #[test]
+    fn test_duplicate_profile() {
+        let mut source = profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                folder("Visual", true),
+            ],
+            [(
+                "Visual",
+                vec![ModOrGroup::Individual(mod_config("b", false))],
+            )],
+        );
+        source.groups.get_mut("Visual").unwrap().priority_override = Some(3);
+        let mut mod_data = mod_data(source);
+        mod_data.save_loadout("default", "all".to_string());
+        mod_data
+            .profiles
+            .insert("other".to_string(), Default::default());
+        mod_data.profile_order = ["default", "other"].map(String::from).to_vec();
+
+        mod_data.duplicate_profile("default", "default - Copy".to_string());
+        assert_eq!(mod_data.active_profile, "default - Copy");
+        assert_eq!(
+            mod_data.ordered_profiles(),
+            ["default", "default - Copy", "other"]
+        );
+        let copy = &mod_data.profiles["default - Copy"];
+        assert_eq!(copy.mods.len(), 2);
+        assert_eq!(copy.groups["Visual"].priority_override, Some(3));
+        assert!(mod_data.loadouts["default - Copy"]["all"].mods["a"]);
+
+        // changing the copy leaves the original as it was
+        mod_data.get_active_profile_mut().groups.clear();
+        assert_eq!(mod_data.profiles["default"].groups.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use super::test_util::{folder, mod_config, mod_data, profile};
+    use super::{ModConfig, ModOrGroup};
+    use crate::providers::ModSpecification;
+
+    #[test]
+    fn test_move_unique_contents() {
+        let mut source = profile(
+            vec![
+                ModOrGroup::Individual(mod_config("shared", true)),
+                ModOrGroup::Individual(mod_config("solo", true)),
+                folder("g", false),
+            ],
+            [(
+                "g",
+                vec![ModOrGroup::Individual(mod_config("in-group", true))],
+            )],
+        );
+        source.groups.get_mut("g").unwrap().priority_override = Some(3);
+        let mut mod_data = mod_data(source);
+        let other = profile(vec![ModOrGroup::Individual(mod_config("shared", true))], []);
+        mod_data.profiles.insert("other".to_string(), other);
+
+        let unique = mod_data.unique_contents("default");
+        assert_eq!(unique.folders, ["g"]);
+        let mut urls = unique
+            .mods
+            .iter()
+            .map(|s| s.url.as_str())
+            .collect::<Vec<_>>();
+        urls.sort();
+        assert_eq!(urls, ["in-group", "solo"]);
+
+        mod_data.move_unique_contents("default", "other");
+        assert!(mod_data.unique_contents("default").is_empty());
+        let other = &mod_data.profiles["other"];
+        assert_eq!(other.mods.len(), 3);
+        assert_eq!(other.groups["g"].priority_override, Some(3));
+    }
+
+    #[test]
+    fn test_move_unique_folder_with_shared_mod() {
+        let mut mod_data = mod_data(profile(
+            vec![folder("g", true)],
+            [(
+                "g",
+                vec![
+                    ModOrGroup::Individual(mod_config("shared", true)),
+                    ModOrGroup::Individual(mod_config("solo", true)),
+                ],
+            )],
+        ));
+        let other = profile(vec![ModOrGroup::Individual(mod_config("shared", true))], []);
+        mod_data.profiles.insert("other".to_string(), other);
+
+        mod_data.move_unique_contents("default", "other");
+        let mut urls = vec![];
+        mod_data.for_each_mod("other", |mc| urls.push(mc.spec.url.clone()));
+        urls.sort();
+        assert_eq!(urls, ["shared", "solo"]);
+        assert!(matches!(
+            mod_data.profiles["other"].groups["g"].mods.as_slice(),
+            [ModOrGroup::Individual(mc)] if mc.spec.url == "solo"
+        ));
+    }
+
+    #[test]
+    fn test_merge_profiles() {
+        // ignore the "#version" suffix so differing versions count as the same mod
+        let key = |spec: &ModSpecification| spec.url.split('#').next().unwrap().to_string();
+
+        let mut mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a#1", true)),
+                folder("g", true),
+            ],
+            [("g", vec![ModOrGroup::Individual(mod_config("b#1", true))])],
+        ));
+        let b2 = ModConfig {
+            priority: 5,
+            ..mod_config("b#2", true)
+        };
+        let other = profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a#2", true)),
+                folder("g", true),
+                folder("h", false),
+            ],
+            [
+                (
+                    "g",
+                    vec![
+                        ModOrGroup::Individual(b2),
+                        ModOrGroup::Individual(mod_config("c", true)),
+                    ],
+                ),
+                ("h", vec![ModOrGroup::Individual(mod_config("d", true))]),
+            ],
+        );
+        mod_data.profiles.insert("other".to_string(), other);
+
+        let conflicts = mod_data.merge_conflicts("default", "other", key);
+        let mut keys = conflicts.iter().map(|c| c.key.as_str()).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(keys, ["a", "b"]);
+
+        mod_data.merge_profiles("default", "other", key, &["b".to_string()].into());
+        let merged = mod_data.get_active_profile();
+        let urls = merged.groups["g"]
+            .mods
+            .iter()
+            .filter_map(ModOrGroup::as_mod)
+            .map(|mc| (mc.spec.url.as_str(), mc.priority))
+            .collect::<Vec<_>>();
+        assert_eq!(urls, [("b#2", 5), ("c", 0)]);
+        assert!(matches!(&merged.mods[0], ModOrGroup::Individual(mc) if mc.spec.url == "a#1"));
+        assert_eq!(merged.groups["h"].mods.len(), 1);
+        assert!(matches!(
+            merged.mods.last(),
+            Some(ModOrGroup::Group { group_name, enabled: false }) if group_name == "h"
+        ));
+    }
+
+    #[test]
+    fn test_copy_to_profile() {
+        let mut source = profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                ModOrGroup::Individual(mod_config("b", false)),
+                folder("Visual", false),
+            ],
+            [
+                (
+                    "Visual",
+                    vec![
+                        ModOrGroup::Individual(mod_config("c", true)),
+                        folder("Visual/Weapons", true),
+                    ],
+                ),
+                (
+                    "Visual/Weapons",
+                    vec![ModOrGroup::Individual(mod_config("d", true))],
+                ),
+            ],
+        );
+        source.groups.get_mut("Visual").unwrap().priority_override = Some(4);
+        let mut mod_data = mod_data(source);
+        let other = profile(vec![ModOrGroup::Individual(mod_config("c", true))], []);
+        mod_data.profiles.insert("other".to_string(), other);
+
+        let urls = ["b".to_string()].into();
+        let folders = ["Visual".to_string()].into();
+        assert_eq!(
+            mod_data.copy_to_profile("default", "other", &urls, &folders),
+            (2, 1)
+        );
+        let other = &mod_data.profiles["other"];
+        assert_eq!(other.mods.len(), 3);
+        assert!(matches!(&other.mods[1], ModOrGroup::Individual(mc) if mc.spec.url == "b"));
+        assert!(matches!(
+            &other.mods[2],
+            ModOrGroup::Group { group_name, enabled: false } if group_name == "Visual"
+        ));
+        // "c" is already in "other" and stays where it is
+        assert!(matches!(
+            other.groups["Visual"].mods.as_slice(),
+            [ModOrGroup::Group { group_name, .. }] if group_name == "Visual/Weapons"
+        ));
+        assert_eq!(other.groups["Visual"].priority_override, Some(4));
+        assert_eq!(other.groups["Visual/Weapons"].mods.len(), 1);
+        // the source is left as it was
+        assert_eq!(mod_data.profiles["default"].mods.len(), 3);
+    }
+}
+
+#[cfg(test)]
+mod folder_tests {
+    use super::test_util::{enabled_urls, folder, mod_config, mod_data, profile};
+    use super::{BrokenDependency, ModOrGroup};
+    use crate::providers::ModSpecification;
+
+    #[test]
+    fn test_folder_broken_dependencies() {
+        let mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("app", true)),
+                folder("libs", true),
+            ],
+            [(
+                "libs",
+                vec![
+                    ModOrGroup::Individual(mod_config("lib", true)),
+                    ModOrGroup::Individual(mod_config("addon", true)),
+                ],
+            )],
+        ));
+        let dependencies = |spec: &ModSpecification| match spec.url.as_str() {
+            "app" => vec![ModSpecification::new("lib".to_string())],
+            "addon" => vec![ModSpecification::new("base".to_string())],
+            _ => vec![],
+        };
+        let broken = |dependent: &str, dependency: &str| BrokenDependency {
+            dependent: ModSpecification::new(dependent.to_string()),
+            dependency: ModSpecification::new(dependency.to_string()),
+        };
+
+        assert_eq!(
+            mod_data.folder_broken_dependencies("default", "libs", false, dependencies),
+            [broken("app", "lib")]
+        );
+        assert_eq!(
+            mod_data.folder_broken_dependencies("default", "libs", true, dependencies),
+            [broken("addon", "base")]
+        );
+    }
+
+    #[test]
+    fn test_enable_only() {
+        let mut mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("app", true)),
+                folder("libs", false),
+            ],
+            [
+                (
+                    "libs",
+                    vec![
+                        ModOrGroup::Individual(mod_config("lib", false)),
+                        ModOrGroup::Individual(mod_config("other", true)),
+                        folder("nested", true),
+                    ],
+                ),
+                (
+                    "nested",
+                    vec![ModOrGroup::Individual(mod_config("deep", true))],
+                ),
+            ],
+        ));
+
+        let urls = ["lib".to_string()].into();
+        mod_data.enable_only("default", &urls);
+        assert_eq!(enabled_urls(&mod_data), ["app", "lib"]);
+        // the folder is enabled, the mods it would have turned on are disabled instead
+        let libs = &mod_data.get_active_profile().mods[1];
+        assert!(matches!(libs, ModOrGroup::Group { enabled: true, .. }));
+    }
+
+    #[test]
+    fn test_nested_folders() {
+        let mut source = profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                folder("Visual", true),
+            ],
+            [
+                (
+                    "Visual",
+                    vec![
+                        ModOrGroup::Individual(mod_config("b", true)),
+                        folder("Visual/Weapons", false),
+                        folder("Visual/Terrain", true),
+                    ],
+                ),
+                (
+                    "Visual/Weapons",
+                    vec![ModOrGroup::Individual(mod_config("c", true))],
+                ),
+                (
+                    "Visual/Terrain",
+                    vec![
+                        ModOrGroup::Individual(mod_config("d", true)),
+                        // nested in itself, which must not recurse forever
+                        folder("Visual", true),
+                    ],
+                ),
+            ],
+        );
+        source.groups.get_mut("Visual").unwrap().priority_override = Some(2);
+        let mut mod_data = mod_data(source);
+
+        assert_eq!(enabled_urls(&mod_data), ["a", "b", "d"]);
+        let priorities = mod_data.get_enabled_mods_with_priority("default");
+        let priorities = priorities
+            .iter()
+            .map(|(mc, p)| (mc.spec.url.as_str(), *p))
+            .collect::<Vec<_>>();
+        assert_eq!(priorities, [("a", 0), ("b", 2), ("d", 2)]);
+        let folders = mod_data
+            .effective_mods("default")
+            .into_iter()
+            .map(|m| (m.folder, m.priority_folder))
+            .collect::<Vec<_>>();
+        let visual = Some("Visual".to_string());
+        assert_eq!(
+            folders,
+            [
+                (None, None),
+                (visual.clone(), visual.clone()),
+                (Some("Visual/Terrain".to_string()), visual),
+            ]
+        );
+
+        // offsets apply on top of the override of a folder containing them
+        mod_data
+            .get_active_profile_mut()
+            .groups
+            .get_mut("Visual/Terrain")
+            .unwrap()
+            .priority_offset = Some(3);
+        let priorities = mod_data
+            .effective_mods("default")
+            .into_iter()
+            .map(|m| m.priority)
+            .collect::<Vec<_>>();
+        assert_eq!(priorities, [0, 2, 5]);
+
+        // enabling a dependency inside a disabled folder enables the folders containing it
+        assert!(mod_data.any_mod_mut("default", |mc, folder_enabled| {
+            if mc.spec.url != "c" {
+                return false;
+            }
+            if let Some(folder_enabled) = folder_enabled {
+                *folder_enabled = true;
+            }
+            true
+        }));
+        assert_eq!(enabled_urls(&mod_data), ["a", "b", "c", "d"]);
+
+        let profile = mod_data.get_active_profile_mut();
+        assert_eq!(profile.parent_folder("Visual/Weapons"), Some("Visual"));
+        assert!(profile.is_within("Visual/Weapons", "Visual"));
+        assert!(!profile.move_folder("Visual", Some("Visual/Weapons")));
+        assert!(profile.move_folder("Visual/Weapons", None));
+        assert_eq!(profile.parent_folder("Visual/Weapons"), None);
+        assert_eq!(profile.mods.len(), 3);
+
+        profile.remove_folder("Visual");
+        assert!(!profile.groups.contains_key("Visual"));
+        let urls = profile
+            .mods
+            .iter()
+            .map(|m| match m {
+                ModOrGroup::Individual(mc) => mc.spec.url.as_str(),
+                ModOrGroup::Group { group_name, .. } => group_name.as_str(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(urls, ["a", "b", "Visual/Terrain", "Visual/Weapons"]);
+        assert!(profile.all_entries().all(|m| !matches!(
+            m,
+            ModOrGroup::Group { group_name, .. } if group_name == "Visual"
+        )));
+    }
+
+    #[test]
+    fn test_bulk_edit_mods() {
+        let mut mod_data = mod_data(profile(
+            vec![
+                ModOrGroup::Individual(mod_config("a", true)),
+                ModOrGroup::Individual(mod_config("b", true)),
+                folder("g", true),
+            ],
+            [("g", vec![ModOrGroup::Individual(mod_config("c", true))])],
+        ));
+        let selected = ["a", "c"].map(String::from).into_iter().collect();
+
+        let profile = mod_data.get_active_profile_mut();
+        for mc in profile.mods_with_urls_mut(&selected) {
+            mc.enabled = false;
+            mc.priority = 3;
+        }
+        assert_eq!(enabled_urls(&mod_data), ["b"]);
+
+        let profile = mod_data.get_active_profile_mut();
+        assert!(!profile.move_mods(&selected, Some("missing")));
+        assert!(profile.move_mods(&selected, Some("g")));
+        let urls = profile.groups["g"]
+            .mods
+            .iter()
+            .filter_map(|m| Some((m.as_mod()?.spec.url.as_str(), m.as_mod()?.priority)))
+            .collect::<Vec<_>>();
+        assert_eq!(urls, [("a", 3), ("c", 3)]);
+        assert_eq!(profile.mods.len(), 2);
+
+        let removed = profile.remove_mods(&selected);
+        assert_eq!(removed.len(), 2);
+        assert!(profile.groups["g"].mods.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use mint_lib::DRGInstallationType;
+
+    use super::Config_v0_0_0 as Config;
+    use super::config::GameInstall;
+    use crate::signing::TrustedSigner;
+
+    #[test]
+    fn test_export_and_import_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let parameters = |rules: &str, token: &str| {
+            HashMap::from([
+                (
+                    "http".to_string(),
+                    HashMap::from([("refresh_rules".to_string(), rules.to_string())]),
+                ),
+                (
+                    "modio".to_string(),
+                    HashMap::from([("oauth".to_string(), token.to_string())]),
+                ),
+            ])
+        };
+        let signer = |name: &str| TrustedSigner {
+            name: name.to_string(),
+            key: format!("{name} key"),
+        };
+
+        let mut shared = Config {
+            provider_parameters: parameters("evil => b", "their token"),
+            drg_pak_path: Some(PathBuf::from("their/FSD-WindowsNoEditor.pak")),
+            zip_backups: true,
+            scan_command: Some("evil {file}".to_string()),
+            update_endpoint: Some("evil/mint".to_string()),
+            trusted_signers: vec![signer("evil")],
+            ..Default::default()
+        };
+        shared.remember_game_install();
+        shared.export_settings(&path).unwrap();
+        let exported = std::fs::read_to_string(&path).unwrap();
+        for left_out in ["their", "evil"] {
+            assert!(!exported.contains(left_out), "{left_out} in {exported}");
+        }
+
+        // a hand-edited file with everything in it
+        std::fs::write(&path, serde_json::to_vec(&shared).unwrap()).unwrap();
+        let mut config = Config {
+            provider_parameters: parameters("", "my token"),
+            drg_pak_path: Some(PathBuf::from("mine/FSD-WinGDK.pak")),
+            scan_command: Some("scan {file}".to_string()),
+            trusted_signers: vec![signer("friend")],
+            ..Default::default()
+        };
+        config.remember_game_install();
+        config.import_settings(&path).unwrap();
+
+        assert!(config.zip_backups);
+        assert_eq!(config.provider_parameters, parameters("", "my token"));
+        assert_eq!(
+            config.drg_pak_path,
+            Some(PathBuf::from("mine/FSD-WinGDK.pak"))
+        );
+        assert_eq!(config.game_installs.len(), 1);
+        assert_eq!(config.game_installs[0].kind, DRGInstallationType::Xbox);
+        assert_eq!(config.scan_command.as_deref(), Some("scan {file}"));
+        assert_eq!(config.update_endpoint, None);
+        assert_eq!(config.trusted_signers, [signer("friend")]);
+    }
+
+    #[test]
+    fn test_game_installs() {
+        let steam = PathBuf::from("steam/FSD/Content/Paks/FSD-WindowsNoEditor.pak");
+        let xbox = PathBuf::from("xbox/FSD/Content/Paks/FSD-WinGDK.pak");
+        let mut config = Config::default();
+        assert_eq!(config.active_game_install(), None);
+        config.remember_game_install();
+        assert!(config.game_installs.is_empty());
+
+        config.drg_pak_path = Some(steam.clone());
+        config.remember_game_install();
+        config.drg_pak_path = Some(xbox.clone());
+        config.remember_game_install();
+        assert_eq!(config.active_game_install(), Some(1));
+        assert_eq!(
+            config.game_installs,
+            [
+                GameInstall {
+                    name: "Steam".to_string(),
+                    pak_path: steam.clone(),
+                    kind: DRGInstallationType::Steam,
+                },
+                GameInstall {
+                    name: "Microsoft Store".to_string(),
+                    pak_path: xbox,
+                    kind: DRGInstallationType::Xbox,
+                },
+            ]
+        );
+
+        // listed once, however often it is switched back to
+        config.drg_pak_path = Some(steam);
+        config.remember_game_install();
+        assert_eq!(config.active_game_install(), Some(0));
+        assert_eq!(config.game_installs.len(), 2);
+
+        // not a game pak
+        config.drg_pak_path = Some(PathBuf::from("other.pak"));
+        config.remember_game_install();
+        assert_eq!(config.active_game_install(), None);
+        assert_eq!(config.game_installs.len(), 2);
+
+        let json = serde_json::to_string(&config.game_installs[1]).unwrap();
+        assert!(json.contains(r#""kind":"xbox""#));
+    }
+}
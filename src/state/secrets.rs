@@ -0,0 +1,156 @@
+//! Provider parameters supplied from outside of config.json.
+//!
+//! Secrets such as the mod.io OAuth token can be provided through environment variables named
+//! `MINT_<PROVIDER>_<PARAMETER>` (e.g. `MINT_MODIO_OAUTH`) or a `secrets.json` file, which has the
+//! same layout as `provider_parameters` in config.json:
+//!
+//! ```json
+//! { "modio": { "oauth": "..." } }
+//! ```
+//!
+//! The secrets file is read from the config directory unless `MINT_SECRETS_FILE` points
+//! elsewhere. Environment variables take precedence over the secrets file, which takes precedence
+//! over config.json. Values from either source are never written back to config.json.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use fs_err as fs;
+use snafu::prelude::*;
+
+use super::{SecretsDeserializationFailedSnafu, StateError};
+use crate::providers::ModStore;
+
+pub const SECRETS_FILE_ENV: &str = "MINT_SECRETS_FILE";
+
+pub type ProviderParameters = HashMap<String, HashMap<String, String>>;
+
+/// Name of the environment variable holding a provider parameter.
+pub fn env_var_name(provider_id: &str, parameter_id: &str) -> String {
+    format!("MINT_{provider_id}_{parameter_id}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub fn secrets_file_path(config_dir: &Path) -> PathBuf {
+    std::env::var_os(SECRETS_FILE_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| config_dir.join("secrets.json"))
+}
+
+/// Load provider parameters from the secrets file and environment variables.
+pub fn read_external_parameters(config_dir: &Path) -> Result<ProviderParameters, StateError> {
+    let mut params = match fs::read(secrets_file_path(config_dir)) {
+        Ok(buf) => serde_json::from_slice::<ProviderParameters>(&buf)
+            .context(SecretsDeserializationFailedSnafu)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => ProviderParameters::new(),
+        Err(e) => Err(e)?,
+    };
+
+    for factory in ModStore::get_provider_factories() {
+        for p in factory.parameters {
+            if let Ok(value) = std::env::var(env_var_name(factory.id, p.id))
+                && !value.is_empty()
+            {
+                params
+                    .entry(factory.id.to_owned())
+                    .or_default()
+                    .insert(p.id.to_owned(), value);
+            }
+        }
+    }
+
+    Ok(params)
+}
+
+/// Overlay externally supplied parameters on top of those stored in config.json.
+pub fn merge_parameters(
+    config: &ProviderParameters,
+    external: &ProviderParameters,
+) -> ProviderParameters {
+    let mut merged = config.clone();
+    for (provider, params) in external {
+        merged
+            .entry(provider.clone())
+            .or_default()
+            .extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(entries: &[(&str, &str, &str)]) -> ProviderParameters {
+        let mut params = ProviderParameters::new();
+        for (provider, param, value) in entries {
+            params
+                .entry(provider.to_string())
+                .or_default()
+                .insert(param.to_string(), value.to_string());
+        }
+        params
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("modio", "oauth"), "MINT_MODIO_OAUTH");
+        assert_eq!(
+            env_var_name("http", "refresh-rules"),
+            "MINT_HTTP_REFRESH_RULES"
+        );
+    }
+
+    #[test]
+    fn test_read_external_parameters() {
+        let dir = tempfile::tempdir().unwrap();
+        let read = |provider: &str, param: &str| {
+            read_external_parameters(dir.path())
+                .unwrap()
+                .get(provider)
+                .and_then(|p| p.get(param).cloned())
+        };
+        assert_eq!(read("http", "headers"), None);
+
+        let secrets = params(&[("http", "headers", "* Authorization: Bearer abc")]);
+        fs::write(
+            dir.path().join("secrets.json"),
+            serde_json::to_vec(&secrets).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            read("http", "headers").as_deref(),
+            Some("* Authorization: Bearer abc")
+        );
+
+        fs::write(dir.path().join("secrets.json"), "{ \"http\": ").unwrap();
+        assert!(matches!(
+            read_external_parameters(dir.path()),
+            Err(StateError::SecretsDeserializationFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn test_merge_parameters() {
+        let config = params(&[
+            ("modio", "oauth", "from config"),
+            ("http", "refresh_rules", "a => b"),
+        ]);
+        let external = params(&[("modio", "oauth", "from env"), ("http", "headers", "h")]);
+        let merged = merge_parameters(&config, &external);
+        assert_eq!(merged["modio"]["oauth"], "from env");
+        assert_eq!(merged["http"]["refresh_rules"], "a => b");
+        assert_eq!(merged["http"]["headers"], "h");
+        // the stored parameters are left as they were
+        assert_eq!(config["modio"]["oauth"], "from config");
+        assert!(!config["http"].contains_key("headers"));
+    }
+}
@@ -1,7 +1,8 @@
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use mint::mod_lints::{LintId, LintReport, SplitAssetPair};
+use mint::archive::DEFAULT_MEMORY_BUDGET;
+use mint::mod_lints::{LINT_CACHE_FILE, LintCache, LintId, LintReport, SplitAssetPair};
 use mint::providers::ModSpecification;
 
 #[test]
@@ -23,7 +24,13 @@ pub fn test_lint_conflicting_files() {
 
     let LintReport {
         conflicting_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::CONFLICTING].into(), mods.into(), None).unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::CONFLICTING].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{conflicting_mods:#?}");
 
@@ -51,7 +58,13 @@ pub fn test_lint_shader() {
 
     let LintReport {
         shader_file_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::SHADER_FILES].into(), mods.into(), None).unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::SHADER_FILES].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{shader_file_mods:#?}");
 
@@ -80,8 +93,13 @@ pub fn test_lint_asset_registry_bin() {
     let LintReport {
         asset_register_bin_mods,
         ..
-    } = mint::mod_lints::run_lints(&[LintId::ASSET_REGISTRY_BIN].into(), mods.into(), None)
-        .unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::ASSET_REGISTRY_BIN].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{asset_register_bin_mods:#?}");
 
@@ -105,8 +123,13 @@ pub fn test_lint_outdated_pak_version() {
     let LintReport {
         outdated_pak_version_mods,
         ..
-    } = mint::mod_lints::run_lints(&[LintId::OUTDATED_PAK_VERSION].into(), mods.into(), None)
-        .unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::OUTDATED_PAK_VERSION].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{outdated_pak_version_mods:#?}");
 
@@ -129,7 +152,13 @@ pub fn test_lint_empty_archive() {
 
     let LintReport {
         empty_archive_mods, ..
-    } = mint::mod_lints::run_lints(&[LintId::EMPTY_ARCHIVE].into(), mods.into(), None).unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::EMPTY_ARCHIVE].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{empty_archive_mods:#?}");
 
@@ -162,6 +191,7 @@ pub fn test_lint_only_non_pak_files() {
         &[LintId::ARCHIVE_WITH_ONLY_NON_PAK_FILES].into(),
         mods.into(),
         None,
+        DEFAULT_MEMORY_BUDGET,
     )
     .unwrap();
 
@@ -192,6 +222,7 @@ pub fn test_lint_multi_pak_archive() {
         &[LintId::ARCHIVE_WITH_MULTIPLE_PAKS].into(),
         mods.into(),
         None,
+        DEFAULT_MEMORY_BUDGET,
     )
     .unwrap();
 
@@ -218,7 +249,13 @@ pub fn test_lint_non_asset_files() {
     let LintReport {
         non_asset_file_mods,
         ..
-    } = mint::mod_lints::run_lints(&[LintId::NON_ASSET_FILES].into(), mods.into(), None).unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::NON_ASSET_FILES].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{non_asset_file_mods:#?}");
 
@@ -242,7 +279,13 @@ pub fn test_lint_split_asset_pairs() {
     let LintReport {
         split_asset_pairs_mods,
         ..
-    } = mint::mod_lints::run_lints(&[LintId::SPLIT_ASSET_PAIRS].into(), mods.into(), None).unwrap();
+    } = mint::mod_lints::run_lints(
+        &[LintId::SPLIT_ASSET_PAIRS].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
 
     println!("{split_asset_pairs_mods:#?}");
 
@@ -287,6 +330,7 @@ pub fn test_lint_unmodified_game_assets() {
         &[LintId::UNMODIFIED_GAME_ASSETS].into(),
         mods.into(),
         Some(reference_pak_path),
+        DEFAULT_MEMORY_BUDGET,
     )
     .unwrap();
 
@@ -299,3 +343,287 @@ pub fn test_lint_unmodified_game_assets() {
         Some(&["a.uexp".to_string(), "a.uasset".to_string()].into())
     );
 }
+
+#[test]
+pub fn test_lint_cached() {
+    let base_path = PathBuf::from_str("test_assets/lints/").unwrap();
+    let a_spec = ModSpecification {
+        url: "A".to_string(),
+    };
+    let b_spec = ModSpecification {
+        url: "B".to_string(),
+    };
+    let mods = [
+        (a_spec.clone(), base_path.join("A.pak")),
+        (b_spec.clone(), base_path.join("B.pak")),
+    ];
+    let lints = [LintId::CONFLICTING, LintId::SHADER_FILES].into();
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache_path = dir.path().join(LINT_CACHE_FILE);
+    let mut cache = LintCache::load(&cache_path);
+    let progress = std::sync::Mutex::new(vec![]);
+    let first = mint::mod_lints::run_lints_cached(
+        &lints,
+        mods.clone().into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+        &mut cache,
+        |p| progress.lock().unwrap().push(p.total),
+    )
+    .unwrap();
+    assert_eq!(progress.lock().unwrap().as_slice(), [2, 2]);
+    cache.save(&cache_path).unwrap();
+
+    // the second run only uses the cached results
+    let mut cache = LintCache::load(&cache_path);
+    let second = mint::mod_lints::run_lints_cached(
+        &lints,
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+        &mut cache,
+        |_| panic!("no mod should be analyzed again"),
+    )
+    .unwrap();
+
+    assert_eq!(first.conflicting_mods, second.conflicting_mods);
+    assert_eq!(
+        second.conflicting_mods.unwrap().get("fsd/content/a.uexp"),
+        Some(&[a_spec.clone(), b_spec].into())
+    );
+    assert_eq!(first.shader_file_mods, second.shader_file_mods);
+    assert_eq!(
+        second.shader_file_mods.unwrap().get(&a_spec),
+        Some(&["fsd/content/c.ushaderbytecode".to_string()].into())
+    );
+}
+
+#[test]
+pub fn test_lint_config_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let config_overrides_path = dir.path().join("config_overrides.pak");
+    let mut pak = repak::PakBuilder::new().writer(
+        std::fs::File::create(&config_overrides_path).unwrap(),
+        repak::Version::V11,
+        "../../../".to_string(),
+        None,
+    );
+    for path in [
+        "FSD/Config/DefaultInput.ini",
+        "FSD/Content/Game/InputSettings.uasset",
+        "FSD/Content/A.uexp",
+    ] {
+        pak.write_file(path, b"").unwrap();
+    }
+    pak.write_index().unwrap();
+
+    let config_overrides_spec = ModSpecification {
+        url: "config_overrides".to_string(),
+    };
+    let mods = [(config_overrides_spec.clone(), config_overrides_path)];
+
+    let LintReport {
+        config_override_mods,
+        ..
+    } = mint::mod_lints::run_lints(
+        &[LintId::CONFIG_OVERRIDES].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config_override_mods.unwrap().get(&config_overrides_spec),
+        Some(
+            &[
+                "fsd/config/defaultinput.ini".to_string(),
+                "fsd/content/game/inputsettings.uasset".to_string(),
+            ]
+            .into()
+        )
+    );
+}
+
+#[test]
+pub fn test_lint_unsupported_compression() {
+    let dir = tempfile::tempdir().unwrap();
+    let pak = |name: &str| {
+        let path = dir.path().join(name);
+        let mut pak = repak::PakBuilder::new()
+            .compression([repak::Compression::Zlib])
+            .writer(
+                std::fs::File::create(&path).unwrap(),
+                repak::Version::V11,
+                "../../../".to_string(),
+                None,
+            );
+        pak.write_file("FSD/Content/A.uexp", b"").unwrap();
+        pak.write_index().unwrap();
+        path
+    };
+    let zlib_path = pak("zlib.pak");
+    let zstd_path = pak("zstd.pak");
+    // the compression method names are the last bytes of the pak
+    let mut data = std::fs::read(&zstd_path).unwrap();
+    let names = data.len() - 5 * 32;
+    let zlib = names + data[names..].windows(4).position(|w| w == b"Zlib").unwrap();
+    data[zlib..zlib + 4].copy_from_slice(b"Zstd");
+    std::fs::write(&zstd_path, data).unwrap();
+
+    let zlib_spec = ModSpecification {
+        url: "zlib".to_string(),
+    };
+    let zstd_spec = ModSpecification {
+        url: "zstd".to_string(),
+    };
+    let mods = [
+        (zlib_spec.clone(), zlib_path),
+        (zstd_spec.clone(), zstd_path),
+    ];
+
+    let LintReport {
+        unsupported_compression_mods,
+        ..
+    } = mint::mod_lints::run_lints(
+        &[LintId::UNSUPPORTED_COMPRESSION].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
+
+    let unsupported_compression_mods = unsupported_compression_mods.unwrap();
+    assert_eq!(unsupported_compression_mods.get(&zlib_spec), None);
+    assert_eq!(
+        unsupported_compression_mods.get(&zstd_spec),
+        Some(&["Zstd".to_string()].into())
+    );
+}
+
+#[test]
+pub fn test_lint_mount_point() {
+    let dir = tempfile::tempdir().unwrap();
+    let pak = |name: &str, mount_point: &str, file: &str| {
+        let path = dir.path().join(name);
+        let mut pak = repak::PakBuilder::new().writer(
+            std::fs::File::create(&path).unwrap(),
+            repak::Version::V11,
+            mount_point.to_string(),
+            None,
+        );
+        pak.write_file(file, b"").unwrap();
+        pak.write_index().unwrap();
+        path
+    };
+    let root_spec = ModSpecification {
+        url: "root".to_string(),
+    };
+    let fsd_spec = ModSpecification {
+        url: "fsd".to_string(),
+    };
+    let no_fsd_spec = ModSpecification {
+        url: "no_fsd".to_string(),
+    };
+    let mods = [
+        (
+            root_spec,
+            pak("root.pak", "../../../", "FSD/Content/A.uexp"),
+        ),
+        (fsd_spec, pak("fsd.pak", "../../../FSD/", "Content/A.uexp")),
+        (
+            no_fsd_spec.clone(),
+            pak("no_fsd.pak", "../../../", "Content/A.uexp"),
+        ),
+    ];
+
+    let LintReport {
+        mount_point_mods, ..
+    } = mint::mod_lints::run_lints(
+        &[LintId::MOUNT_POINT].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
+
+    assert_eq!(
+        mount_point_mods.unwrap(),
+        [(no_fsd_spec, "../../../".to_string())].into()
+    );
+}
+
+/// A locres file in the legacy format with `keys` of `namespace`.
+fn legacy_locres(namespace: &str, keys: &[&str]) -> Vec<u8> {
+    fn string(data: &mut Vec<u8>, s: &str) {
+        data.extend((s.len() as u32 + 1).to_le_bytes());
+        data.extend(s.as_bytes());
+        data.push(0);
+    }
+    let mut data = 1u32.to_le_bytes().to_vec();
+    string(&mut data, namespace);
+    data.extend((keys.len() as u32).to_le_bytes());
+    for key in keys {
+        string(&mut data, key);
+        data.extend(0u32.to_le_bytes());
+        string(&mut data, "text");
+    }
+    data
+}
+
+#[test]
+pub fn test_lint_localization_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    let pak = |name: &str, path: &str, data: &[u8]| {
+        let pak_path = dir.path().join(name);
+        let mut pak = repak::PakBuilder::new().writer(
+            std::fs::File::create(&pak_path).unwrap(),
+            repak::Version::V11,
+            "../../../".to_string(),
+            None,
+        );
+        pak.write_file(path, data).unwrap();
+        pak.write_index().unwrap();
+        pak_path
+    };
+    let spec = |url: &str| ModSpecification {
+        url: url.to_string(),
+    };
+    let en = "FSD/Content/Localization/Game/en/Game.locres";
+    let mods = [
+        (
+            spec("a"),
+            pak("a.pak", en, &legacy_locres("UI", &["Play", "Quit"])),
+        ),
+        (
+            spec("b"),
+            pak("b.pak", en, &legacy_locres("UI", &["Quit", "Options"])),
+        ),
+        (
+            spec("c"),
+            pak(
+                "c.pak",
+                "FSD/Content/Localization/Game/de/Game.locres",
+                &legacy_locres("UI", &["Quit"]),
+            ),
+        ),
+    ];
+
+    let LintReport {
+        localization_conflicts,
+        ..
+    } = mint::mod_lints::run_lints(
+        &[LintId::LOCALIZATION_CONFLICTS].into(),
+        mods.into(),
+        None,
+        DEFAULT_MEMORY_BUDGET,
+    )
+    .unwrap();
+
+    let conflicts = localization_conflicts.unwrap();
+    assert_eq!(conflicts.len(), 1);
+    let conflict = &conflicts["fsd/content/localization/game/en/game.locres"];
+    assert_eq!(conflict.mods, [spec("a"), spec("b")].into());
+    assert_eq!(conflict.entries, ["UI/Quit".to_string()].into());
+}